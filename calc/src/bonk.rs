@@ -1,4 +1,9 @@
-use crate::instruction::utils::bonk::accounts;
+use super::common::{calculate_with_slippage_buy, ceil_div};
+use super::fees::bonk as accounts;
+
+/// Sum of the fee rates deducted from the quote side of every trade, in basis points.
+const TOTAL_FEE_RATE: u128 =
+    accounts::PROTOCOL_FEE_RATE + accounts::PLATFORM_FEE_RATE + accounts::SHARE_FEE_RATE;
 
 /// Calculates the amount of tokens to receive when buying with SOL
 ///
@@ -110,3 +115,82 @@ pub fn get_sell_sol_amount_from_token_amount(
 
     final_amount as u64
 }
+
+/// Calculates the SOL to spend when buying exactly `amount_out` tokens (`buy_exact_out`)
+///
+/// Inverse of [`get_buy_token_amount_from_sol_amount`]: the constant-product input is rounded up,
+/// grossed up by the quote-side fees, then raised by the slippage tolerance.
+///
+/// # Arguments
+///
+/// * `amount_out` - The exact amount of tokens to receive
+/// * `virtual_base` - Virtual base token reserves
+/// * `virtual_quote` - Virtual quote token (SOL) reserves
+/// * `real_base` - Real base token reserves
+/// * `real_quote` - Real quote token (SOL) reserves
+/// * `slippage_basis_points` - Maximum slippage tolerance in basis points (e.g., 100 = 1%)
+///
+/// # Returns
+///
+/// The maximum amount of SOL to sign for, or `None` when the curve cannot provide `amount_out`
+pub fn get_buy_sol_amount_from_token_amount(
+    amount_out: u64,
+    virtual_base: u128,
+    virtual_quote: u128,
+    real_base: u128,
+    real_quote: u128,
+    slippage_basis_points: u128,
+) -> Option<u64> {
+    let amount_out_u128 = amount_out as u128;
+    let input_reserve = virtual_quote.checked_add(real_quote)?;
+    let output_reserve = virtual_base.checked_sub(real_base)?;
+    if amount_out_u128 >= output_reserve {
+        return None;
+    }
+
+    let amount_in_net =
+        ceil_div(input_reserve.checked_mul(amount_out_u128)?, output_reserve - amount_out_u128);
+    let amount_in = ceil_div(amount_in_net.checked_mul(10000)?, 10000 - TOTAL_FEE_RATE);
+
+    Some(calculate_with_slippage_buy(u64::try_from(amount_in).ok()?, slippage_basis_points as u64))
+}
+
+/// Calculates the tokens to sell when receiving exactly `amount_out` SOL (`sell_exact_out`)
+///
+/// Inverse of [`get_sell_sol_amount_from_token_amount`]: the SOL output is grossed up by the
+/// quote-side fees, the constant-product input is rounded up, then raised by the slippage
+/// tolerance.
+///
+/// # Arguments
+///
+/// * `amount_out` - The exact amount of SOL to receive
+/// * `virtual_base` - Virtual base token reserves
+/// * `virtual_quote` - Virtual quote token (SOL) reserves
+/// * `real_base` - Real base token reserves
+/// * `real_quote` - Real quote token (SOL) reserves
+/// * `slippage_basis_points` - Maximum slippage tolerance in basis points (e.g., 100 = 1%)
+///
+/// # Returns
+///
+/// The maximum amount of tokens to sign for, or `None` when the curve cannot provide `amount_out`
+pub fn get_sell_token_amount_from_sol_amount(
+    amount_out: u64,
+    virtual_base: u128,
+    virtual_quote: u128,
+    real_base: u128,
+    real_quote: u128,
+    slippage_basis_points: u128,
+) -> Option<u64> {
+    let input_reserve = virtual_base.checked_sub(real_base)?;
+    let output_reserve = virtual_quote.checked_add(real_quote)?;
+
+    let sol_amount_out = ceil_div((amount_out as u128).checked_mul(10000)?, 10000 - TOTAL_FEE_RATE);
+    if sol_amount_out >= output_reserve {
+        return None;
+    }
+
+    let amount_in =
+        ceil_div(input_reserve.checked_mul(sol_amount_out)?, output_reserve - sol_amount_out);
+
+    Some(calculate_with_slippage_buy(u64::try_from(amount_in).ok()?, slippage_basis_points as u64))
+}
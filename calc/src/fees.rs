@@ -0,0 +1,36 @@
+//! Default protocol fee rates used by the calc functions. The `instruction::utils::*` modules
+//! re-export these, so each rate is defined once. 各协议默认费率（唯一定义处）。
+
+pub mod pumpfun {
+    /// Bonding-curve protocol fee, basis points.
+    pub const FEE_BASIS_POINTS: u64 = 95;
+    /// Creator fee, basis points (only charged when the curve has a creator).
+    pub const CREATOR_FEE: u64 = 30;
+}
+
+pub mod pumpswap {
+    pub const LP_FEE_BASIS_POINTS: u64 = 25;
+    pub const PROTOCOL_FEE_BASIS_POINTS: u64 = 5;
+    pub const COIN_CREATOR_FEE_BASIS_POINTS: u64 = 5;
+}
+
+pub mod bonk {
+    pub const PLATFORM_FEE_RATE: u128 = 100; // 1%
+    pub const PROTOCOL_FEE_RATE: u128 = 25; // 0.25%
+    pub const SHARE_FEE_RATE: u128 = 0; // 0%
+}
+
+pub mod raydium_amm_v4 {
+    pub const TRADE_FEE_NUMERATOR: u64 = 25;
+    pub const TRADE_FEE_DENOMINATOR: u64 = 10000;
+    pub const SWAP_FEE_NUMERATOR: u64 = 25;
+    pub const SWAP_FEE_DENOMINATOR: u64 = 10000;
+}
+
+pub mod raydium_cpmm {
+    pub const FEE_RATE_DENOMINATOR_VALUE: u128 = 1_000_000;
+    pub const TRADE_FEE_RATE: u64 = 2500;
+    pub const CREATOR_FEE_RATE: u64 = 0;
+    pub const PROTOCOL_FEE_RATE: u64 = 120000;
+    pub const FUND_FEE_RATE: u64 = 40000;
+}
@@ -0,0 +1,74 @@
+//! Pure amount math (constant product, fees, slippage) for every supported protocol.
+//! 纯计算模块：不依赖 async / RPC，可用于测试、模拟器或链上程序。
+//!
+//! Depends only on `core`, `alloc` (`String` errors) and `solana-pubkey`, so it builds without the
+//! SDK's RPC/async stack. Default fee rates live in [`fees`]. `sol-trade-sdk` re-exports this
+//! crate as `sol_trade_sdk::utils::calc` and its `instruction::utils::*` modules re-export the fee
+//! rates, so existing paths keep working.
+
+pub mod bonk;
+pub mod common;
+pub mod fees;
+pub mod pumpfun;
+pub mod pumpswap;
+pub mod raydium_amm_v4;
+pub mod raydium_cpmm;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_pubkey::Pubkey;
+
+    const VIRTUAL_TOKEN: u128 = 1_073_000_000_000_000;
+    const VIRTUAL_SOL: u128 = 30_000_000_000;
+    const REAL_TOKEN: u128 = 793_100_000_000_000;
+
+    #[test]
+    fn pumpfun_round_trip_loses_only_the_fees() {
+        let creator = Pubkey::new_unique();
+        let lamports = 1_000_000_000u64;
+        let tokens = pumpfun::get_buy_token_amount_from_sol_amount(
+            VIRTUAL_TOKEN,
+            VIRTUAL_SOL,
+            REAL_TOKEN,
+            creator,
+            lamports,
+        );
+        assert!(tokens > 0);
+        // same inputs, same quote: no hidden state
+        assert_eq!(
+            tokens,
+            pumpfun::get_buy_token_amount_from_sol_amount(
+                VIRTUAL_TOKEN,
+                VIRTUAL_SOL,
+                REAL_TOKEN,
+                creator,
+                lamports,
+            )
+        );
+
+        let back = pumpfun::get_sell_sol_amount_from_token_amount(
+            VIRTUAL_TOKEN - tokens as u128,
+            VIRTUAL_SOL + lamports as u128,
+            creator,
+            tokens,
+        );
+        // protocol + creator fee on each leg, nothing more
+        let fee_bps = fees::pumpfun::FEE_BASIS_POINTS + fees::pumpfun::CREATOR_FEE;
+        assert!(back < lamports);
+        assert!(back as u128 * 10_000 >= lamports as u128 * (10_000 - 2 * fee_bps as u128 - 10));
+    }
+
+    #[test]
+    fn raydium_cpmm_quote_respects_slippage() {
+        let exact =
+            raydium_cpmm::compute_swap_amount(1_000_000_000, 2_000_000_000, true, 1_000_000, 0);
+        assert_eq!(exact.min_amount_out, exact.amount_out);
+        assert!(exact.fee > 0);
+
+        let loose =
+            raydium_cpmm::compute_swap_amount(1_000_000_000, 2_000_000_000, true, 1_000_000, 100);
+        assert_eq!(loose.amount_out, exact.amount_out);
+        assert_eq!(loose.min_amount_out, exact.amount_out - exact.amount_out / 100);
+    }
+}
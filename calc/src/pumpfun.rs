@@ -1,9 +1,7 @@
-use solana_sdk::pubkey::Pubkey;
+use solana_pubkey::Pubkey;
 
-use crate::{
-    instruction::utils::pumpfun::global_constants::{CREATOR_FEE, FEE_BASIS_POINTS},
-    utils::calc::common::compute_fee,
-};
+use super::common::{ceil_div, compute_fee};
+use super::fees::pumpfun::{CREATOR_FEE, FEE_BASIS_POINTS};
 
 /// Calculates the amount of tokens that can be purchased with a given SOL amount
 /// using the bonding curve formula.
@@ -59,6 +57,37 @@ pub fn get_buy_token_amount_from_sol_amount(
     tokens_received.min(u64::MAX as u128) as u64
 }
 
+/// Calculates the SOL (fees included) needed to buy exactly `amount` tokens on the bonding curve.
+///
+/// Inverse of [`get_buy_token_amount_from_sol_amount`], rounded up. Returns `None` when `amount`
+/// exceeds the real token reserves (the curve cannot fill it).
+#[inline]
+pub fn get_buy_sol_amount_from_token_amount(
+    virtual_token_reserves: u128,
+    virtual_sol_reserves: u128,
+    real_token_reserves: u128,
+    creator: Pubkey,
+    amount: u64,
+) -> Option<u64> {
+    let amount_128 = amount as u128;
+    if amount_128 > real_token_reserves || amount_128 >= virtual_token_reserves {
+        return None;
+    }
+    if amount == 0 {
+        return Some(0);
+    }
+
+    let sol_cost = ceil_div(
+        amount_128.checked_mul(virtual_sol_reserves)?,
+        virtual_token_reserves - amount_128,
+    );
+    let total_fee_basis_points =
+        FEE_BASIS_POINTS + if creator != Pubkey::default() { CREATOR_FEE } else { 0 };
+    let fee = compute_fee(sol_cost, total_fee_basis_points as u128);
+
+    u64::try_from(sol_cost.checked_add(fee)?).ok()
+}
+
 /// Calculates the amount of SOL that will be received when selling a given token amount
 /// using the bonding curve formula with transaction fees deducted.
 ///
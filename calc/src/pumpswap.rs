@@ -1,21 +1,62 @@
 use super::common::{
     calculate_with_slippage_buy, calculate_with_slippage_sell, ceil_div, compute_fee,
 };
-use crate::instruction::utils::pumpswap::accounts::{
+use super::fees::pumpswap::{
     COIN_CREATOR_FEE_BASIS_POINTS, LP_FEE_BASIS_POINTS, PROTOCOL_FEE_BASIS_POINTS,
 };
-use crate::instruction::utils::pumpswap::PumpSwapFeeBasisPoints;
-use solana_sdk::pubkey::Pubkey;
+use solana_pubkey::Pubkey;
+
+/// PumpSwap LP / protocol / coin-creator fee rates, basis points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PumpSwapFeeBasisPoints {
+    pub lp_fee_basis_points: u64,
+    pub protocol_fee_basis_points: u64,
+    pub coin_creator_fee_basis_points: u64,
+}
+
+impl PumpSwapFeeBasisPoints {
+    #[inline]
+    pub const fn new(
+        lp_fee_basis_points: u64,
+        protocol_fee_basis_points: u64,
+        coin_creator_fee_basis_points: u64,
+    ) -> Self {
+        Self { lp_fee_basis_points, protocol_fee_basis_points, coin_creator_fee_basis_points }
+    }
+
+    #[inline]
+    pub const fn legacy_default() -> Self {
+        Self::new(LP_FEE_BASIS_POINTS, PROTOCOL_FEE_BASIS_POINTS, COIN_CREATOR_FEE_BASIS_POINTS)
+    }
+}
+
+impl Default for PumpSwapFeeBasisPoints {
+    #[inline]
+    fn default() -> Self {
+        Self::legacy_default()
+    }
+}
+
+/// Compute the quote reserves used by PumpSwap pricing.
+///
+/// Returns `None` when the signed sum is non-positive or cannot fit in a `u64`.
+#[inline]
+pub fn effective_quote_reserves(
+    quote_vault_balance: u64,
+    virtual_quote_reserves: i128,
+) -> Option<u64> {
+    i128::from(quote_vault_balance)
+        .checked_add(virtual_quote_reserves)
+        .and_then(|reserves| u64::try_from(reserves).ok())
+        .filter(|reserves| *reserves != 0)
+}
 
 #[inline]
 fn effective_quote_reserve(
     quote_reserve: u64,
     virtual_quote_reserves: i128,
 ) -> Result<u64, String> {
-    crate::instruction::utils::pumpswap_types::effective_quote_reserves(
-        quote_reserve,
-        virtual_quote_reserves,
-    )
+    effective_quote_reserves(quote_reserve, virtual_quote_reserves)
     .filter(|reserve| *reserve != 0)
     .ok_or_else(|| {
         format!(
@@ -645,6 +686,21 @@ mod tests {
         assert_eq!(sell_error, "Calculated base amount exceeds u64.");
     }
 
+    #[test]
+    fn u64_max_scale_reserves_stay_exact_in_u128() {
+        let max = u64::MAX;
+        let buy = buy_base_input_internal_with_fees(1_000_000, 100, max, max, 0, &fees()).unwrap();
+        assert_eq!(buy.internal_quote_amount, 1_000_001);
+
+        let sell =
+            sell_base_input_internal_with_fees(max / 2, 0, max / 2, max, 0, &fees()).unwrap();
+        assert_eq!(sell.internal_quote_amount_out, max / 2);
+        assert!(sell.ui_quote < sell.internal_quote_amount_out);
+
+        let bought = buy_quote_input_internal_with_fees(max / 2, 0, max, max, 0, &fees()).unwrap();
+        assert!(bought.base > 0 && bought.base < max / 2);
+    }
+
     #[test]
     fn invalid_fee_boundaries_return_errors() {
         let overflowing_fees = PumpSwapFeeBasisPoints::new(u64::MAX, 1, 0);
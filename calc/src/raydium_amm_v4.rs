@@ -1,4 +1,5 @@
-use crate::instruction::utils::raydium_amm_v4::accounts::{
+use super::common::{calculate_with_slippage_buy, ceil_div};
+use super::fees::raydium_amm_v4::{
     SWAP_FEE_DENOMINATOR, SWAP_FEE_NUMERATOR, TRADE_FEE_DENOMINATOR, TRADE_FEE_NUMERATOR,
 };
 
@@ -43,6 +44,17 @@ pub struct ComputeSwapParams {
     pub fee: u64,
 }
 
+/// Parameters computed for an exact-output (`swap_base_out`) swap.
+#[derive(Debug, Clone)]
+pub struct ComputeSwapBaseOutParams {
+    /// The exact output amount requested
+    pub amount_out: u64,
+    /// The input amount the pool needs for `amount_out` at current reserves (fees included)
+    pub amount_in: u64,
+    /// The maximum input amount to sign for (considering slippage_basis_points)
+    pub max_amount_in: u64,
+}
+
 /// Result of a swap calculation containing all relevant amounts and fees.
 #[derive(Debug, Clone)]
 pub struct SwapResult {
@@ -148,3 +160,46 @@ pub fn compute_swap_amount(
         fee: swap_result.trade_fee,
     }
 }
+
+/// Computes the input needed to receive exactly `amount_out` (Raydium AMM V4 `swap_base_out`).
+///
+/// Mirrors the program: the constant-product input is rounded up, then grossed up by the swap fee.
+///
+/// # Arguments
+/// * `base_reserve` - The current reserve amount of the base token in the pool
+/// * `quote_reserve` - The current reserve amount of the quote token in the pool
+/// * `is_base_in` - Whether the input token is the base token (true) or quote token (false)
+/// * `amount_out` - The exact amount of output tokens wanted
+/// * `slippage_basis_points` - Extra input allowed on top of the quoted input (e.g., 100 for 1%)
+///
+/// # Returns
+/// `None` when the pool does not hold enough output tokens for `amount_out`.
+pub fn compute_swap_amount_base_out(
+    base_reserve: u64,
+    quote_reserve: u64,
+    is_base_in: bool,
+    amount_out: u64,
+    slippage_basis_points: u64,
+) -> Option<ComputeSwapBaseOutParams> {
+    let (input_reserve, output_reserve) =
+        if is_base_in { (base_reserve, quote_reserve) } else { (quote_reserve, base_reserve) };
+    if amount_out == 0 || amount_out >= output_reserve {
+        return None;
+    }
+
+    let amount_in_before_fee = ceil_div(
+        (input_reserve as u128).checked_mul(amount_out as u128)?,
+        (output_reserve - amount_out) as u128,
+    );
+    let amount_in = ceil_div(
+        amount_in_before_fee.checked_mul(TRADE_FEE_DENOMINATOR as u128)?,
+        (TRADE_FEE_DENOMINATOR - TRADE_FEE_NUMERATOR) as u128,
+    );
+    let amount_in = u64::try_from(amount_in).ok()?;
+
+    Some(ComputeSwapBaseOutParams {
+        amount_out,
+        amount_in,
+        max_amount_in: calculate_with_slippage_buy(amount_in, slippage_basis_points),
+    })
+}
@@ -0,0 +1,347 @@
+use super::common::{calculate_with_slippage_buy, calculate_with_slippage_sell, ceil_div};
+use super::fees::raydium_cpmm::{
+    CREATOR_FEE_RATE, FEE_RATE_DENOMINATOR_VALUE, FUND_FEE_RATE, PROTOCOL_FEE_RATE, TRADE_FEE_RATE,
+};
+
+/// Computes trading fee using ceiling division.
+///
+/// # Arguments
+/// * `amount` - The amount to calculate fee for
+/// * `fee_rate` - The fee rate to apply
+///
+/// # Returns
+/// The calculated trading fee
+#[inline(always)]
+fn compute_trading_fee(amount: u64, fee_rate: u64) -> u64 {
+    let numerator = (amount as u128) * (fee_rate as u128);
+    saturating_u64(ceil_div(numerator, FEE_RATE_DENOMINATOR_VALUE))
+}
+
+/// Computes protocol or fund fee using floor division.
+///
+/// # Arguments
+/// * `amount` - The amount to calculate fee for
+/// * `fee_rate` - The fee rate to apply
+///
+/// # Returns
+/// The calculated protocol or fund fee
+#[inline(always)]
+fn compute_protocol_fund_fee(amount: u64, fee_rate: u64) -> u64 {
+    let numerator = (amount as u128) * (fee_rate as u128);
+    saturating_u64(numerator / FEE_RATE_DENOMINATOR_VALUE)
+}
+
+/// Narrows a u128 intermediate to u64, saturating instead of truncating.
+#[inline(always)]
+fn saturating_u64(value: u128) -> u64 {
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+/// Computes creator fee using ceiling division.
+///
+/// # Arguments
+/// * `amount` - The amount to calculate fee for
+/// * `fee_rate` - The fee rate to apply
+///
+/// # Returns
+/// The calculated creator fee
+#[inline(always)]
+fn compute_creator_fee_new(amount: u64, fee_rate: u64) -> u64 {
+    let numerator = (amount as u128) * (fee_rate as u128);
+    saturating_u64(ceil_div(numerator, FEE_RATE_DENOMINATOR_VALUE))
+}
+
+/// Parameters for computing swap amounts and fees.
+#[derive(Debug, Clone)]
+pub struct ComputeSwapParams {
+    /// Whether the entire input amount is traded
+    pub all_trade: bool,
+    /// The input amount for the swap
+    pub amount_in: u64,
+    /// The expected output amount from the swap
+    pub amount_out: u64,
+    /// The minimum acceptable output amount (considering slippage_basis_points)
+    pub min_amount_out: u64,
+    /// The trading fee amount
+    pub fee: u64,
+}
+
+/// Parameters computed for an exact-output (`swap_base_output`) swap.
+#[derive(Debug, Clone)]
+pub struct ComputeSwapBaseOutParams {
+    /// The exact output amount requested
+    pub amount_out: u64,
+    /// The input amount the pool needs for `amount_out` at current reserves (fees included)
+    pub amount_in: u64,
+    /// The maximum input amount to sign for (considering slippage_basis_points)
+    pub max_amount_in: u64,
+    /// The trading fee amount
+    pub fee: u64,
+}
+
+/// Result of a swap calculation containing all relevant amounts and fees.
+#[derive(Debug, Clone)]
+pub struct SwapResult {
+    /// The new amount in the input vault after the swap
+    pub new_input_vault_amount: u64,
+    /// The new amount in the output vault after the swap
+    pub new_output_vault_amount: u64,
+    /// The actual input amount used in the swap
+    pub input_amount: u64,
+    /// The actual output amount received from the swap
+    pub output_amount: u64,
+    /// The trading fee charged
+    pub trade_fee: u64,
+    /// The protocol fee charged
+    pub protocol_fee: u64,
+    /// The fund fee charged
+    pub fund_fee: u64,
+    /// The creator fee charged
+    pub creator_fee: u64,
+}
+
+/// Performs a swap calculation based on input amount.
+///
+/// Calculates the output amount and all associated fees when swapping a specific input amount.
+///
+/// # Arguments
+/// * `input_amount` - The amount of input tokens to swap
+/// * `input_vault_amount` - Current amount in the input token vault
+/// * `output_vault_amount` - Current amount in the output token vault
+/// * `trade_fee_rate` - The trading fee rate
+/// * `creator_fee_rate` - The creator fee rate
+/// * `protocol_fee_rate` - The protocol fee rate
+/// * `fund_fee_rate` - The fund fee rate
+/// * `is_creator_fee_on_input` - Whether creator fee is charged on input tokens
+///
+/// # Returns
+/// A `SwapResult` containing all swap calculations and fees
+#[inline]
+fn swap_base_input(
+    input_amount: u64,
+    input_vault_amount: u64,
+    output_vault_amount: u64,
+    trade_fee_rate: u64,
+    creator_fee_rate: u64,
+    protocol_fee_rate: u64,
+    fund_fee_rate: u64,
+    is_creator_fee_on_input: bool,
+) -> SwapResult {
+    let mut creator_fee = 0u64;
+
+    let trade_fee = compute_trading_fee(input_amount, trade_fee_rate);
+
+    let input_amount_less_fees = if is_creator_fee_on_input {
+        creator_fee = compute_creator_fee_new(input_amount, creator_fee_rate);
+        input_amount.saturating_sub(trade_fee).saturating_sub(creator_fee)
+    } else {
+        input_amount.saturating_sub(trade_fee)
+    };
+
+    let protocol_fee = compute_protocol_fund_fee(trade_fee, protocol_fee_rate);
+    let fund_fee = compute_protocol_fund_fee(trade_fee, fund_fee_rate);
+
+    // u64 * u64 fits in u128; the quotient never exceeds `output_vault_amount`.
+    let denominator = (input_vault_amount as u128) + (input_amount_less_fees as u128);
+    let output_amount_swapped = if denominator == 0 {
+        0
+    } else {
+        saturating_u64(
+            (output_vault_amount as u128) * (input_amount_less_fees as u128) / denominator,
+        )
+    };
+
+    let output_amount = if is_creator_fee_on_input {
+        output_amount_swapped
+    } else {
+        creator_fee = compute_creator_fee_new(output_amount_swapped, creator_fee_rate);
+        output_amount_swapped.saturating_sub(creator_fee)
+    };
+
+    SwapResult {
+        new_input_vault_amount: input_vault_amount.saturating_add(input_amount_less_fees),
+        new_output_vault_amount: output_vault_amount.saturating_sub(output_amount_swapped),
+        input_amount,
+        output_amount,
+        trade_fee,
+        protocol_fee,
+        fund_fee,
+        creator_fee,
+    }
+}
+
+/// Inverts a fee: the smallest pre-fee amount whose fee-deducted value is `post_fee_amount`.
+#[inline(always)]
+fn calculate_pre_fee_amount(post_fee_amount: u128, fee_rate: u64) -> Option<u128> {
+    if fee_rate == 0 {
+        return Some(post_fee_amount);
+    }
+    let denominator = FEE_RATE_DENOMINATOR_VALUE.checked_sub(fee_rate as u128)?;
+    if denominator == 0 {
+        return None;
+    }
+    Some(ceil_div(post_fee_amount.checked_mul(FEE_RATE_DENOMINATOR_VALUE)?, denominator))
+}
+
+/// Performs a swap calculation based on output amount (mirror of the program's
+/// `swap_base_output`, creator fee charged on input).
+///
+/// Returns `None` when the output vault cannot cover `output_amount`.
+#[inline]
+fn swap_base_output(
+    output_amount: u64,
+    input_vault_amount: u64,
+    output_vault_amount: u64,
+    trade_fee_rate: u64,
+    creator_fee_rate: u64,
+) -> Option<(u64, u64)> {
+    if output_amount == 0 || output_amount >= output_vault_amount {
+        return None;
+    }
+    // Constant product without fees, rounded up in the pool's favour.
+    let input_amount_swapped = ceil_div(
+        (input_vault_amount as u128).checked_mul(output_amount as u128)?,
+        (output_vault_amount - output_amount) as u128,
+    );
+    let total_fee_rate = trade_fee_rate.checked_add(creator_fee_rate)?;
+    let input_amount = calculate_pre_fee_amount(input_amount_swapped, total_fee_rate)?;
+    let total_fee = input_amount - input_amount_swapped;
+    let creator_fee = if total_fee_rate == 0 {
+        0
+    } else {
+        total_fee * creator_fee_rate as u128 / total_fee_rate as u128
+    };
+    let input_amount = u64::try_from(input_amount).ok()?;
+    Some((input_amount, (total_fee - creator_fee) as u64))
+}
+
+/// Computes swap parameters including amounts, fees, and slippage protection.
+///
+/// This function calculates the expected output amount, minimum output amount (with slippage),
+/// and trading fees for a given input amount in a CPMM (Constant Product Market Maker) pool.
+///
+/// # Arguments
+/// * `base_reserve` - The current reserve amount of the base token in the pool
+/// * `quote_reserve` - The current reserve amount of the quote token in the pool  
+/// * `is_base_in` - Whether the input token is the base token (true) or quote token (false)
+/// * `amount_in` - The amount of input tokens to swap
+/// * `slippage_basis_points` - The acceptable slippage in basis points (e.g., 100 for 1%)
+///
+/// # Returns
+/// A `ComputeSwapParams` struct containing all computed swap parameters
+#[inline]
+pub fn compute_swap_amount(
+    base_reserve: u64,
+    quote_reserve: u64,
+    is_base_in: bool,
+    amount_in: u64,
+    slippage_basis_points: u64,
+) -> ComputeSwapParams {
+    let (input_reserve, output_reserve) =
+        if is_base_in { (base_reserve, quote_reserve) } else { (quote_reserve, base_reserve) };
+
+    let swap_result = swap_base_input(
+        amount_in,
+        input_reserve,
+        output_reserve,
+        TRADE_FEE_RATE,
+        CREATOR_FEE_RATE,
+        PROTOCOL_FEE_RATE,
+        FUND_FEE_RATE,
+        true,
+    );
+
+    let min_amount_out =
+        calculate_with_slippage_sell(swap_result.output_amount, slippage_basis_points);
+
+    let all_trade = swap_result.input_amount == amount_in;
+
+    ComputeSwapParams {
+        all_trade,
+        amount_in,
+        amount_out: swap_result.output_amount,
+        min_amount_out,
+        fee: swap_result.trade_fee,
+    }
+}
+
+/// Computes the input needed to receive exactly `amount_out` (Raydium CPMM `swap_base_output`).
+///
+/// # Arguments
+/// * `base_reserve` - The current reserve amount of the base token in the pool
+/// * `quote_reserve` - The current reserve amount of the quote token in the pool
+/// * `is_base_in` - Whether the input token is the base token (true) or quote token (false)
+/// * `amount_out` - The exact amount of output tokens wanted
+/// * `slippage_basis_points` - Extra input allowed on top of the quoted input (e.g., 100 for 1%)
+///
+/// # Returns
+/// `None` when the pool does not hold enough output tokens for `amount_out`.
+#[inline]
+pub fn compute_swap_amount_base_out(
+    base_reserve: u64,
+    quote_reserve: u64,
+    is_base_in: bool,
+    amount_out: u64,
+    slippage_basis_points: u64,
+) -> Option<ComputeSwapBaseOutParams> {
+    let (input_reserve, output_reserve) =
+        if is_base_in { (base_reserve, quote_reserve) } else { (quote_reserve, base_reserve) };
+
+    let (amount_in, fee) = swap_base_output(
+        amount_out,
+        input_reserve,
+        output_reserve,
+        TRADE_FEE_RATE,
+        CREATOR_FEE_RATE,
+    )?;
+
+    Some(ComputeSwapBaseOutParams {
+        amount_out,
+        amount_in,
+        max_amount_in: calculate_with_slippage_buy(amount_in, slippage_basis_points),
+        fee,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_out_input_buys_at_least_the_requested_output() {
+        let (base, quote) = (5_000_000_000u64, 80_000_000_000_000u64);
+        let wanted = 1_234_567_890u64;
+        let quote_out = compute_swap_amount_base_out(base, quote, true, wanted, 0).unwrap();
+
+        let forward = compute_swap_amount(base, quote, true, quote_out.amount_in, 0);
+        assert!(forward.amount_out >= wanted);
+        let one_less = compute_swap_amount(base, quote, true, quote_out.amount_in - 1, 0);
+        assert!(one_less.amount_out <= wanted);
+        assert_eq!(quote_out.max_amount_in, quote_out.amount_in);
+    }
+
+    #[test]
+    fn base_out_applies_slippage_and_rejects_draining_the_vault() {
+        let quote_out =
+            compute_swap_amount_base_out(1_000_000, 1_000_000, false, 1_000, 100).unwrap();
+        assert_eq!(quote_out.max_amount_in, quote_out.amount_in + quote_out.amount_in / 100);
+        assert!(compute_swap_amount_base_out(1_000_000, 1_000_000, true, 1_000_000, 0).is_none());
+        assert!(compute_swap_amount_base_out(1_000_000, 1_000_000, true, 0, 0).is_none());
+    }
+
+    #[test]
+    fn u64_max_scale_reserves_do_not_overflow_or_truncate() {
+        let max = u64::MAX;
+        let swap = compute_swap_amount(max, max, true, max, 100);
+        assert!(swap.amount_out < max && swap.amount_out > max / 2 - max / 100);
+        assert_eq!(swap.fee, ceil_div(max as u128 * TRADE_FEE_RATE as u128, 1_000_000) as u64);
+        assert_eq!(swap.min_amount_out, calculate_with_slippage_sell(swap.amount_out, 100));
+
+        assert_eq!(compute_trading_fee(max, FEE_RATE_DENOMINATOR_VALUE as u64 * 2), max);
+        assert_eq!(compute_protocol_fund_fee(max, 1_000_000), max);
+
+        let base_out = compute_swap_amount_base_out(max, max, true, max / 3, 9_999).unwrap();
+        assert!(base_out.amount_in > max / 2 && base_out.max_amount_in == max);
+        assert!(compute_swap_amount_base_out(max, max, true, max / 2, 0).is_none());
+    }
+}
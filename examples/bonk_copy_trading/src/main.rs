@@ -7,13 +7,14 @@ use sol_trade_sdk::common::GasFeeStrategy;
 use sol_trade_sdk::common::TradeConfig;
 use sol_trade_sdk::{
     common::AnyResult,
-    swqos::SwqosConfig,
+    swqos::{SwqosConfig, TradeType},
     trading::{
         core::params::{BonkParams, DexParamEnum},
-        factory::DexType,
+        CopyTradeEvent, DecodedTrade,
     },
-    SolanaTrade,
+    SolanaTrade, TradeTokenType,
 };
+use sol_trade_sdk_streamer::EventTrade;
 use solana_commitment_config::CommitmentConfig;
 use solana_streamer_sdk::streaming::event_parser::common::filter::EventTypeFilter;
 use solana_streamer_sdk::streaming::event_parser::common::EventType;
@@ -114,118 +115,83 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
     Ok(solana_trade)
 }
 
-/// Bonk sniper trade
-/// This function demonstrates how to snipe a new token from a Bonk trade event
+/// Bonk copy trade
+/// Buys from the streamer event (`sol-trade-sdk-streamer` turns it into a `DecodedTrade`),
+/// then sells against freshly loaded pool state
 async fn bonk_copy_trade_with_grpc(trade_info: BonkTradeEvent) -> AnyResult<()> {
     println!("Testing Bonk trading...");
 
     let client = create_solana_trade_client().await?;
-    let mint_pubkey = trade_info.base_token_mint;
+    let buy_trade = trade_info.decoded_trade()?;
+    let mint_pubkey = buy_trade.mint();
+    let mint_token_program = trade_info.base_token_program;
     let slippage_basis_points = Some(100);
-    let recent_blockhash = client.infrastructure.rpc.get_latest_blockhash().await?;
 
     let gas_fee_strategy = GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
     // Buy tokens
     println!("Buying tokens from Bonk...");
-    let input_token_type =
-        if trade_info.quote_token_mint == sol_trade_sdk::constants::USD1_TOKEN_ACCOUNT {
-            sol_trade_sdk::TradeTokenType::USD1
-        } else {
-            sol_trade_sdk::TradeTokenType::SOL
-        };
-    let balance_before = client
-        .get_payer_token_balance_with_program(&mint_pubkey, &trade_info.base_token_program)
-        .await?;
+    let balance_before =
+        client.get_payer_token_balance_with_program(&mint_pubkey, &mint_token_program).await?;
     let buy_sol_amount = 100_000;
-    let buy_params = sol_trade_sdk::TradeBuyParams {
-        dex_type: DexType::Bonk,
-        input_token_type: input_token_type.clone(),
-        mint: mint_pubkey,
-        input_token_amount: buy_sol_amount,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::Bonk(BonkParams::from_trade(
-            trade_info.virtual_base,
-            trade_info.virtual_quote,
-            trade_info.real_base_after,
-            trade_info.real_quote_after,
-            trade_info.pool_state,
-            trade_info.base_vault,
-            trade_info.quote_vault,
-            trade_info.base_token_program,
-            trade_info.platform_config,
-            trade_info.platform_associated_account,
-            trade_info.creator_associated_account,
-            trade_info.global_config,
-        )),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: true,
-        close_input_token_ata: false,
-        create_mint_ata: true,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy.clone(),
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
-    let (ok, sigs, err, _) = client.buy(buy_params).await?;
-    if !ok {
-        return Err(
-            std::io::Error::other(format!("buy failed: {:?}; sigs: {:?}", err, sigs)).into()
-        );
+    let outcome = client
+        .copy_trade(
+            &buy_trade,
+            TradeType::Buy,
+            buy_sol_amount,
+            slippage_basis_points,
+            gas_fee_strategy.clone(),
+        )
+        .await?;
+    if !outcome.success {
+        return Err(std::io::Error::other(format!(
+            "buy failed: {:?}; sigs: {:?}",
+            outcome.error, outcome.signatures
+        ))
+        .into());
     }
 
     // Sell tokens
     println!("Selling tokens from Bonk...");
 
-    let balance_after = client
-        .get_payer_token_balance_with_program(&mint_pubkey, &trade_info.base_token_program)
-        .await?;
+    let balance_after =
+        client.get_payer_token_balance_with_program(&mint_pubkey, &mint_token_program).await?;
     let amount_token = balance_after
         .checked_sub(balance_before)
         .ok_or_else(|| std::io::Error::other("token balance decreased after buy"))?;
     if amount_token == 0 {
         return Err(std::io::Error::other("confirmed buy did not increase token balance").into());
     }
+    // the event's reserves are stale by now: sell against the current pool
     let sell_extension = BonkParams::from_mint_by_rpc(
         &client.infrastructure.rpc,
         &mint_pubkey,
-        trade_info.quote_token_mint == sol_trade_sdk::constants::USD1_TOKEN_ACCOUNT,
+        buy_trade.quote_token_type() == TradeTokenType::USD1,
     )
     .await?;
+    let sell_trade = DecodedTrade {
+        mint_token_program: buy_trade.mint_token_program(),
+        ..DecodedTrade::new(mint_pubkey, DexParamEnum::Bonk(sell_extension))
+            .with_quote(buy_trade.quote_token_type())
+    };
 
     println!("Selling {} tokens", amount_token);
-    let sell_params = sol_trade_sdk::TradeSellParams {
-        dex_type: DexType::Bonk,
-        output_token_type: input_token_type,
-        mint: mint_pubkey,
-        input_token_amount: amount_token,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
-        extension_params: DexParamEnum::Bonk(sell_extension),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        with_tip: false,
-        durable_nonce: None,
-        create_output_token_ata: false,
-        close_output_token_ata: false,
-        close_mint_token_ata: false,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
-    let (ok, sigs, err, _) = client.sell(sell_params).await?;
-    if !ok {
-        return Err(
-            std::io::Error::other(format!("sell failed: {:?}; sigs: {:?}", err, sigs)).into()
-        );
+    let outcome = client
+        .copy_trade(
+            &sell_trade,
+            TradeType::Sell,
+            amount_token,
+            slippage_basis_points,
+            gas_fee_strategy,
+        )
+        .await?;
+    if !outcome.success {
+        return Err(std::io::Error::other(format!(
+            "sell failed: {:?}; sigs: {:?}",
+            outcome.error, outcome.signatures
+        ))
+        .into());
     }
 
     // Exit program
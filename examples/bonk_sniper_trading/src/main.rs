@@ -91,7 +91,7 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
 
     let client = create_solana_trade_client().await?;
     let mint_pubkey = trade_info.base_token_mint;
-    let slippage_basis_points = Some(300);
+    let slippage_basis_points = 300;
     let recent_blockhash = client.infrastructure.rpc.get_latest_blockhash().await?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
@@ -110,14 +110,11 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
     // Buy tokens
     println!("Buying tokens from Bonk...");
     let buy_sol_amount = 100_000;
-    let buy_params = sol_trade_sdk::TradeBuyParams {
-        dex_type: DexType::Bonk,
-        input_token_type: token_type.clone(),
-        mint: mint_pubkey,
-        input_token_amount: buy_sol_amount,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::Bonk(BonkParams::from_dev_trade(
+    let buy_params = sol_trade_sdk::TradeBuyParams::builder(
+        DexType::Bonk,
+        mint_pubkey,
+        buy_sol_amount,
+        DexParamEnum::Bonk(BonkParams::from_dev_trade(
             trade_info.exact_in,
             trade_info.amount_in,
             trade_info.amount_out,
@@ -130,19 +127,14 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
             trade_info.creator_associated_account,
             trade_info.global_config,
         )),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: true,
-        close_input_token_ata: true,
-        create_mint_ata: true,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy.clone(),
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .recent_blockhash(recent_blockhash)
+    .input_token_type(token_type.clone())
+    .create_input_token_ata(true)
+    .close_input_token_ata(true)
+    .gas_fee_strategy(gas_fee_strategy.clone())
+    .build();
     let (ok, sigs, err, _) = client.buy(buy_params).await?;
     if !ok {
         return Err(
@@ -164,33 +156,26 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
     }
 
     println!("Selling {} tokens", amount_token);
-    let sell_params = sol_trade_sdk::TradeSellParams {
-        dex_type: DexType::Bonk,
-        output_token_type: token_type,
-        mint: mint_pubkey,
-        input_token_amount: amount_token,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
-        extension_params: DexParamEnum::Bonk(BonkParams::immediate_sell(
+    let sell_params = sol_trade_sdk::TradeSellParams::builder(
+        DexType::Bonk,
+        mint_pubkey,
+        amount_token,
+        DexParamEnum::Bonk(BonkParams::immediate_sell(
             trade_info.base_token_program,
             trade_info.platform_config,
             trade_info.platform_associated_account,
             trade_info.creator_associated_account,
             trade_info.global_config,
         )),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: true,
-        close_output_token_ata: true,
-        close_mint_token_ata: false,
-        with_tip: false,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .recent_blockhash(client.infrastructure.rpc.get_latest_blockhash().await?)
+    .output_token_type(token_type)
+    .create_output_token_ata(true)
+    .close_output_token_ata(true)
+    .with_tip(false)
+    .gas_fee_strategy(gas_fee_strategy)
+    .build();
     let (ok, sigs, err, _) = client.sell(sell_params).await?;
     if !ok {
         return Err(
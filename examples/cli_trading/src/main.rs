@@ -606,27 +606,19 @@ async fn handle_buy_pumpfun(
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let buy_params = TradeBuyParams {
-        dex_type: DexType::PumpFun,
-        input_token_type: TradeTokenType::SOL,
-        mint: mint_pubkey,
-        input_token_amount: sol_lamports,
-        slippage_basis_points: slippage,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::PumpFun(param),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: false,
-        close_input_token_ata: false,
-        create_mint_ata,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    let mut builder = TradeBuyParams::builder(
+        DexType::PumpFun,
+        mint_pubkey,
+        sol_lamports,
+        DexParamEnum::PumpFun(param),
+    )
+    .recent_blockhash(recent_blockhash)
+    .create_mint_ata(create_mint_ata)
+    .gas_fee_strategy(gas_fee_strategy);
+    if let Some(slippage) = slippage {
+        builder = builder.slippage_basis_points(slippage);
+    }
+    let buy_params = builder.build();
     match client.buy(buy_params).await {
         Ok((_, signature, _, _)) => {
             println!("   ✅ Successfully bought tokens from PumpFun!");
@@ -663,27 +655,21 @@ async fn handle_buy_pumpswap(
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let buy_params = TradeBuyParams {
-        dex_type: DexType::PumpSwap,
-        input_token_type: TradeTokenType::WSOL,
-        mint: mint_pubkey,
-        input_token_amount: sol_lamports,
-        slippage_basis_points: slippage,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::PumpSwap(param),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: true,
-        close_input_token_ata: false,
-        create_mint_ata,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    let mut builder = TradeBuyParams::builder(
+        DexType::PumpSwap,
+        mint_pubkey,
+        sol_lamports,
+        DexParamEnum::PumpSwap(param),
+    )
+    .recent_blockhash(recent_blockhash)
+    .input_token_type(TradeTokenType::WSOL)
+    .create_input_token_ata(true)
+    .create_mint_ata(create_mint_ata)
+    .gas_fee_strategy(gas_fee_strategy);
+    if let Some(slippage) = slippage {
+        builder = builder.slippage_basis_points(slippage);
+    }
+    let buy_params = builder.build();
     match client.buy(buy_params).await {
         Ok((_, signature, _, _)) => {
             println!("   ✅ Successfully bought tokens from PumpSwap!");
@@ -720,27 +706,21 @@ async fn handle_buy_bonk(
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let buy_params = TradeBuyParams {
-        dex_type: DexType::Bonk,
-        input_token_type: TradeTokenType::WSOL,
-        mint: mint_pubkey,
-        input_token_amount: sol_lamports,
-        slippage_basis_points: slippage,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::Bonk(param),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: true,
-        close_input_token_ata: false,
-        create_mint_ata,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    let mut builder = TradeBuyParams::builder(
+        DexType::Bonk,
+        mint_pubkey,
+        sol_lamports,
+        DexParamEnum::Bonk(param),
+    )
+    .recent_blockhash(recent_blockhash)
+    .input_token_type(TradeTokenType::WSOL)
+    .create_input_token_ata(true)
+    .create_mint_ata(create_mint_ata)
+    .gas_fee_strategy(gas_fee_strategy);
+    if let Some(slippage) = slippage {
+        builder = builder.slippage_basis_points(slippage);
+    }
+    let buy_params = builder.build();
     match client.buy(buy_params).await {
         Ok((_, signature, _, _)) => {
             println!("   ✅ Successfully bought tokens from Bonk!");
@@ -781,27 +761,21 @@ async fn handle_buy_raydium_v4(
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let buy_params = TradeBuyParams {
-        dex_type: DexType::RaydiumAmmV4,
-        input_token_type: TradeTokenType::WSOL,
-        mint: mint_pubkey,
-        input_token_amount: sol_lamports,
-        slippage_basis_points: slippage,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::RaydiumAmmV4(param),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: true,
-        close_input_token_ata: false,
-        create_mint_ata,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    let mut builder = TradeBuyParams::builder(
+        DexType::RaydiumAmmV4,
+        mint_pubkey,
+        sol_lamports,
+        DexParamEnum::RaydiumAmmV4(param),
+    )
+    .recent_blockhash(recent_blockhash)
+    .input_token_type(TradeTokenType::WSOL)
+    .create_input_token_ata(true)
+    .create_mint_ata(create_mint_ata)
+    .gas_fee_strategy(gas_fee_strategy);
+    if let Some(slippage) = slippage {
+        builder = builder.slippage_basis_points(slippage);
+    }
+    let buy_params = builder.build();
     match client.buy(buy_params).await {
         Ok((_, signature, _, _)) => {
             println!("   ✅ Successfully bought tokens from Raydium V4!");
@@ -843,27 +817,21 @@ async fn handle_buy_raydium_cpmm(
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let buy_params = TradeBuyParams {
-        dex_type: DexType::RaydiumCpmm,
-        input_token_type: TradeTokenType::WSOL,
-        mint: mint_pubkey,
-        input_token_amount: sol_lamports,
-        slippage_basis_points: slippage,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::RaydiumCpmm(param),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: true,
-        close_input_token_ata: false,
-        create_mint_ata,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    let mut builder = TradeBuyParams::builder(
+        DexType::RaydiumCpmm,
+        mint_pubkey,
+        sol_lamports,
+        DexParamEnum::RaydiumCpmm(param),
+    )
+    .recent_blockhash(recent_blockhash)
+    .input_token_type(TradeTokenType::WSOL)
+    .create_input_token_ata(true)
+    .create_mint_ata(create_mint_ata)
+    .gas_fee_strategy(gas_fee_strategy);
+    if let Some(slippage) = slippage {
+        builder = builder.slippage_basis_points(slippage);
+    }
+    let buy_params = builder.build();
     match client.buy(buy_params).await {
         Ok((_, signature, _, _)) => {
             println!("   ✅ Successfully bought tokens from Raydium CPMM!");
@@ -1013,27 +981,20 @@ async fn handle_sell_pumpfun(
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let sell_params = TradeSellParams {
-        dex_type: DexType::PumpFun,
-        output_token_type: TradeTokenType::SOL,
-        mint: mint_pubkey,
-        input_token_amount: amount as u64,
-        slippage_basis_points: slippage,
-        recent_blockhash: Some(recent_blockhash),
-        with_tip: false,
-        extension_params: DexParamEnum::PumpFun(param),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: true,
-        close_output_token_ata: false,
-        close_mint_token_ata: false,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
+    let mut builder = TradeSellParams::builder(
+        DexType::PumpFun,
+        mint_pubkey,
+        amount as u64,
+        DexParamEnum::PumpFun(param),
+    )
+    .recent_blockhash(recent_blockhash)
+    .with_tip(false)
+    .create_output_token_ata(true)
+    .gas_fee_strategy(gas_fee_strategy);
+    if let Some(slippage) = slippage {
+        builder = builder.slippage_basis_points(slippage);
+    }
+    let sell_params = builder.build();
 
     match client.sell(sell_params).await {
         Ok((_, signature, _, _)) => {
@@ -1073,27 +1034,21 @@ async fn handle_sell_pumpswap(
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let sell_params = TradeSellParams {
-        dex_type: DexType::PumpSwap,
-        output_token_type: TradeTokenType::WSOL,
-        mint: mint_pubkey,
-        input_token_amount: amount as u64,
-        slippage_basis_points: slippage,
-        recent_blockhash: Some(recent_blockhash),
-        with_tip: false,
-        extension_params: DexParamEnum::PumpSwap(param),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: true,
-        close_output_token_ata: false,
-        close_mint_token_ata: false,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
+    let mut builder = TradeSellParams::builder(
+        DexType::PumpSwap,
+        mint_pubkey,
+        amount as u64,
+        DexParamEnum::PumpSwap(param),
+    )
+    .recent_blockhash(recent_blockhash)
+    .output_token_type(TradeTokenType::WSOL)
+    .with_tip(false)
+    .create_output_token_ata(true)
+    .gas_fee_strategy(gas_fee_strategy);
+    if let Some(slippage) = slippage {
+        builder = builder.slippage_basis_points(slippage);
+    }
+    let sell_params = builder.build();
     match client.sell(sell_params).await {
         Ok((_, signature, _, _)) => {
             println!("   ✅ Successfully sold tokens from PumpSwap!");
@@ -1133,27 +1088,21 @@ async fn handle_sell_bonk(
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let sell_params = TradeSellParams {
-        dex_type: DexType::Bonk,
-        output_token_type: TradeTokenType::WSOL,
-        mint: mint_pubkey,
-        input_token_amount: amount as u64,
-        slippage_basis_points: slippage,
-        recent_blockhash: Some(recent_blockhash),
-        with_tip: false,
-        extension_params: DexParamEnum::Bonk(param),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: true,
-        close_output_token_ata: false,
-        close_mint_token_ata: false,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
+    let mut builder = TradeSellParams::builder(
+        DexType::Bonk,
+        mint_pubkey,
+        amount as u64,
+        DexParamEnum::Bonk(param),
+    )
+    .recent_blockhash(recent_blockhash)
+    .output_token_type(TradeTokenType::WSOL)
+    .with_tip(false)
+    .create_output_token_ata(true)
+    .gas_fee_strategy(gas_fee_strategy);
+    if let Some(slippage) = slippage {
+        builder = builder.slippage_basis_points(slippage);
+    }
+    let sell_params = builder.build();
     match client.sell(sell_params).await {
         Ok((_, signature, _, _)) => {
             println!("   ✅ Successfully sold tokens from Bonk!");
@@ -1196,27 +1145,21 @@ async fn handle_sell_raydium_v4(
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let sell_params = TradeSellParams {
-        dex_type: DexType::RaydiumAmmV4,
-        output_token_type: TradeTokenType::WSOL,
-        mint: mint_pubkey,
-        input_token_amount: amount as u64,
-        slippage_basis_points: slippage,
-        recent_blockhash: Some(recent_blockhash),
-        with_tip: false,
-        extension_params: DexParamEnum::RaydiumAmmV4(param),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: true,
-        close_output_token_ata: false,
-        close_mint_token_ata: false,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
+    let mut builder = TradeSellParams::builder(
+        DexType::RaydiumAmmV4,
+        mint_pubkey,
+        amount as u64,
+        DexParamEnum::RaydiumAmmV4(param),
+    )
+    .recent_blockhash(recent_blockhash)
+    .output_token_type(TradeTokenType::WSOL)
+    .with_tip(false)
+    .create_output_token_ata(true)
+    .gas_fee_strategy(gas_fee_strategy);
+    if let Some(slippage) = slippage {
+        builder = builder.slippage_basis_points(slippage);
+    }
+    let sell_params = builder.build();
     match client.sell(sell_params).await {
         Ok((_, signature, _, _)) => {
             println!("   ✅ Successfully sold tokens from Raydium V4!");
@@ -1260,27 +1203,21 @@ async fn handle_sell_raydium_cpmm(
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let sell_params = TradeSellParams {
-        dex_type: DexType::RaydiumCpmm,
-        output_token_type: TradeTokenType::WSOL,
-        mint: mint_pubkey,
-        input_token_amount: amount as u64,
-        slippage_basis_points: slippage,
-        recent_blockhash: Some(recent_blockhash),
-        with_tip: false,
-        extension_params: DexParamEnum::RaydiumCpmm(param),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: true,
-        close_output_token_ata: false,
-        close_mint_token_ata: false,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
+    let mut builder = TradeSellParams::builder(
+        DexType::RaydiumCpmm,
+        mint_pubkey,
+        amount as u64,
+        DexParamEnum::RaydiumCpmm(param),
+    )
+    .recent_blockhash(recent_blockhash)
+    .output_token_type(TradeTokenType::WSOL)
+    .with_tip(false)
+    .create_output_token_ata(true)
+    .gas_fee_strategy(gas_fee_strategy);
+    if let Some(slippage) = slippage {
+        builder = builder.slippage_basis_points(slippage);
+    }
+    let sell_params = builder.build();
     match client.sell(sell_params).await {
         Ok((_, signature, _, _)) => {
             println!("   ✅ Successfully sold tokens from Raydium CPMM!");
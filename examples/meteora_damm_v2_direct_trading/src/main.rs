@@ -16,7 +16,7 @@ async fn main() -> AnyResult<()> {
     println!("Testing Metaora Damm V2 trading...");
 
     let client = create_solana_trade_client().await?;
-    let slippage_basis_points = Some(100);
+    let slippage_basis_points = 100;
     let recent_blockhash = client.infrastructure.rpc.get_latest_blockhash().await?;
     let pool = Pubkey::from_str("7dVri3qjYD3uobSZL3Zth8vSCgU6r6R2nvFsh7uVfDte").unwrap();
     let mint_pubkey = Pubkey::from_str("PRVT6TB7uss3FrUd2D9xs2zqDBsa3GbMJMwCQsgmeta").unwrap();
@@ -40,27 +40,19 @@ async fn main() -> AnyResult<()> {
     // Buy tokens
     println!("Buying tokens from Metaora Damm V2...");
     let input_token_amount = 100_000;
-    let buy_params = sol_trade_sdk::TradeBuyParams {
-        dex_type: DexType::MeteoraDammV2,
-        input_token_type: TradeTokenType::USDC, // or USDC
-        mint: mint_pubkey,
-        input_token_amount: input_token_amount,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::MeteoraDammV2(pool_params),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: false, //if input token is SOL/WSOL,set to true,if input token is USDC,set to false.
-        close_input_token_ata: false, //if input token is SOL/WSOL,set to true,if input token is USDC,set to false.
-        create_mint_ata: true,
-        durable_nonce: None,
-        fixed_output_token_amount: Some(min_buy_output),
-        gas_fee_strategy: gas_fee_strategy.clone(),
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    // Meteora params carry no pool price, so the swap2 minimum output is set explicitly.
+    let buy_params = sol_trade_sdk::TradeBuyParams::builder(
+        DexType::MeteoraDammV2,
+        mint_pubkey,
+        input_token_amount,
+        DexParamEnum::MeteoraDammV2(pool_params.with_minimum_amount_out(min_buy_output)),
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .recent_blockhash(recent_blockhash)
+    .input_token_type(TradeTokenType::USDC)
+    // For a SOL/WSOL input, also set create_input_token_ata / close_input_token_ata to true.
+    .gas_fee_strategy(gas_fee_strategy.clone())
+    .build();
     let (ok, sigs, err, _) = client.buy(buy_params).await?;
     if !ok {
         anyhow::bail!("buy failed: {:?}; signatures: {:?}", err, sigs);
@@ -78,30 +70,23 @@ async fn main() -> AnyResult<()> {
         anyhow::bail!("confirmed buy did not increase token balance");
     }
     println!("Position acquired by this run: {}", amount_token);
-    let sell_params = sol_trade_sdk::TradeSellParams {
-        dex_type: DexType::MeteoraDammV2,
-        output_token_type: TradeTokenType::USDC,
-        mint: mint_pubkey,
-        input_token_amount: amount_token,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
-        with_tip: false,
-        extension_params: DexParamEnum::MeteoraDammV2(
+    let sell_params = sol_trade_sdk::TradeSellParams::builder(
+        DexType::MeteoraDammV2,
+        mint_pubkey,
+        amount_token,
+        DexParamEnum::MeteoraDammV2(
             MeteoraDammV2Params::from_pool_address_by_rpc(&client.infrastructure.rpc, &pool)
-                .await?,
+                .await?
+                .with_minimum_amount_out(min_sell_output),
         ),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: false, //if output token is SOL/WSOL,set to true,if output token is USDC,set to false.
-        close_output_token_ata: false, //if output token is SOL/WSOL,set to true,if output token is USDC,set to false.
-        close_mint_token_ata: false,
-        durable_nonce: None,
-        fixed_output_token_amount: Some(min_sell_output),
-        gas_fee_strategy: gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .recent_blockhash(client.infrastructure.rpc.get_latest_blockhash().await?)
+    .with_tip(false)
+    .output_token_type(TradeTokenType::USDC)
+    // For a SOL/WSOL output, also set create_output_token_ata / close_output_token_ata to true.
+    .gas_fee_strategy(gas_fee_strategy)
+    .build();
     let (ok, sigs, err, _) = client.sell(sell_params).await?;
     if !ok {
         anyhow::bail!("sell failed: {:?}; signatures: {:?}", err, sigs);
@@ -5,7 +5,7 @@ use sol_trade_sdk::{
     trading::{
         core::params::{DexParamEnum, PumpSwapParams},
         factory::DexType,
-        InstructionMiddleware, MiddlewareManager,
+        InstructionMiddleware, MiddlewareManager, TradeContext,
     },
     SolanaTrade, TradeTokenType,
 };
@@ -50,6 +50,22 @@ impl InstructionMiddleware for CustomMiddleware {
         Ok(full_instructions)
     }
 
+    // Optional: override the `_with_context` variants to see mint, amounts and pool.
+    fn process_protocol_instructions_with_context(
+        &self,
+        protocol_instructions: Vec<Instruction>,
+        context: &TradeContext,
+    ) -> Result<Vec<Instruction>> {
+        println!(
+            "{} {} {} input_amount={:?}",
+            context.dex_type,
+            if context.is_buy { "buy" } else { "sell" },
+            context.mint,
+            context.input_amount
+        );
+        Ok(protocol_instructions)
+    }
+
     fn clone_box(&self) -> Box<dyn InstructionMiddleware> {
         Box::new(self.clone())
     }
@@ -84,37 +100,29 @@ async fn test_middleware() -> AnyResult<()> {
     client = client.with_middleware_manager(middleware_manager);
     let mint_pubkey = Pubkey::from_str("pumpCmXqMfrsAkQ5r49WcJnRayYRqmXz6ae8H7H9Dfn")?;
     let buy_sol_cost = 100_000;
-    let slippage_basis_points = Some(100);
+    let slippage_basis_points = 100;
     let recent_blockhash = client.infrastructure.rpc.get_latest_blockhash().await?;
     let pool_address = Pubkey::from_str("539m4mVWt6iduB6W8rDGPMarzNCMesuqY5eUTiiYHAgR")?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let buy_params = sol_trade_sdk::TradeBuyParams {
-        dex_type: DexType::PumpSwap,
-        input_token_type: TradeTokenType::WSOL,
-        mint: mint_pubkey,
-        input_token_amount: buy_sol_cost,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::PumpSwap(
+    let buy_params = sol_trade_sdk::TradeBuyParams::builder(
+        DexType::PumpSwap,
+        mint_pubkey,
+        buy_sol_cost,
+        DexParamEnum::PumpSwap(
             PumpSwapParams::from_pool_address_by_rpc(&client.infrastructure.rpc, &pool_address)
                 .await?,
         ),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: true,
-        close_input_token_ata: true,
-        create_mint_ata: true,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy,
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .recent_blockhash(recent_blockhash)
+    .input_token_type(TradeTokenType::WSOL)
+    .create_input_token_ata(true)
+    .close_input_token_ata(true)
+    .gas_fee_strategy(gas_fee_strategy)
+    .build();
     client.buy(buy_params).await?;
     println!("tip: This transaction will not succeed because we're using a test account. You can modify the code to initialize the payer with your own private key");
     Ok(())
@@ -12,12 +12,11 @@ use sol_parser_sdk::grpc::{
 };
 use sol_parser_sdk::DexEvent;
 use sol_trade_sdk::common::{nonce_cache::fetch_nonce_info, TradeConfig};
-use sol_trade_sdk::TradeTokenType;
 use sol_trade_sdk::{
     common::AnyResult,
     swqos::SwqosConfig,
     trading::{
-        core::params::{DexParamEnum, PumpFunParams},
+        core::params::{DexParamEnum, PumpFunParams, PumpFunTradeEvent},
         factory::DexType,
     },
     SolanaTrade,
@@ -129,64 +128,50 @@ async fn pumpfun_copy_trade_with_grpc(
 
     let client = create_solana_trade_client().await?;
     let mint_pubkey = trade_info.mint;
-    let virtual_quote_reserves = if trade_info.virtual_quote_reserves != 0 {
-        trade_info.virtual_quote_reserves
-    } else {
-        trade_info.virtual_sol_reserves
-    };
-    let real_quote_reserves = if trade_info.virtual_quote_reserves != 0 {
-        trade_info.real_quote_reserves
-    } else {
-        trade_info.real_sol_reserves
-    };
-    let slippage_basis_points = Some(100);
-    let recent_blockhash = client.infrastructure.rpc.get_latest_blockhash().await?;
+    let slippage_basis_points = 100;
 
     let nonce_account_str = Pubkey::from_str("use_your_nonce_account_here")?;
-    let durable_nonce = fetch_nonce_info(&client.infrastructure.rpc, nonce_account_str).await;
+    let durable_nonce = fetch_nonce_info(&client.infrastructure.rpc, nonce_account_str)
+        .await
+        .ok_or_else(|| anyhow::anyhow!("failed to read nonce account {}", nonce_account_str))?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
     // is_cashback_coin from gRPC event (sol-parser-sdk parses it from trade event)
-    let buy_params = sol_trade_sdk::TradeBuyParams {
-        dex_type: DexType::PumpFun,
-        input_token_type: TradeTokenType::SOL,
-        mint: mint_pubkey,
-        input_token_amount: 100_000,
-        slippage_basis_points,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::PumpFun(PumpFunParams::from_trade(
-            trade_info.bonding_curve,
-            trade_info.associated_bonding_curve,
-            trade_info.mint,
-            trade_info.quote_mint,
-            trade_info.creator,
-            trade_info.creator_vault,
-            trade_info.virtual_token_reserves,
-            virtual_quote_reserves,
-            trade_info.real_token_reserves,
-            real_quote_reserves,
-            None,
-            trade_info.fee_recipient,
-            trade_info.token_program,
-            trade_info.is_cashback_coin,
-            Some(trade_info.mayhem_mode),
-        )),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: false,
-        close_input_token_ata: false,
-        create_mint_ata: true,
-        durable_nonce,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy.clone(),
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    let buy_params = sol_trade_sdk::TradeBuyParams::builder(
+        DexType::PumpFun,
+        mint_pubkey,
+        100_000,
+        DexParamEnum::PumpFun(PumpFunParams::from_event(&pumpfun_trade_event(&trade_info), None)),
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .durable_nonce(durable_nonce)
+    .gas_fee_strategy(gas_fee_strategy.clone())
+    .build();
     client.buy(buy_params).await?;
 
     std::process::exit(0);
 }
+
+/// Map the parser's event onto the SDK's by field name.
+fn pumpfun_trade_event(e: &sol_parser_sdk::core::events::PumpFunTradeEvent) -> PumpFunTradeEvent {
+    PumpFunTradeEvent {
+        mint: e.mint,
+        quote_mint: e.quote_mint,
+        bonding_curve: e.bonding_curve,
+        associated_bonding_curve: e.associated_bonding_curve,
+        creator: e.creator,
+        creator_vault: e.creator_vault,
+        fee_recipient: e.fee_recipient,
+        token_program: e.token_program,
+        virtual_token_reserves: e.virtual_token_reserves,
+        real_token_reserves: e.real_token_reserves,
+        virtual_sol_reserves: e.virtual_sol_reserves,
+        real_sol_reserves: e.real_sol_reserves,
+        virtual_quote_reserves: e.virtual_quote_reserves,
+        real_quote_reserves: e.real_quote_reserves,
+        is_cashback_coin: e.is_cashback_coin,
+        mayhem_mode: e.mayhem_mode,
+    }
+}
@@ -13,12 +13,11 @@ use sol_parser_sdk::grpc::{
 };
 use sol_parser_sdk::DexEvent;
 use sol_trade_sdk::common::TradeConfig;
-use sol_trade_sdk::TradeTokenType;
 use sol_trade_sdk::{
     common::AnyResult,
     swqos::SwqosConfig,
     trading::{
-        core::params::{DexParamEnum, PumpFunParams},
+        core::params::{DexParamEnum, PumpFunParams, PumpFunTradeEvent},
         factory::DexType,
     },
     SolanaTrade,
@@ -30,6 +29,9 @@ static ALREADY_EXECUTED: AtomicBool = AtomicBool::new(false);
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("PumpFun 跟单示例（sol-parser-sdk gRPC）...");
+    if !SolanaTrade::catches_panics() {
+        println!("注意：panic = \"abort\" 构建下交易任务 panic 会直接中止进程");
+    }
 
     let config = ClientConfig {
         enable_metrics: false,
@@ -86,12 +88,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 _ => None,
             };
             if let Some(e) = run {
+                // spawn_trade 捕获 panic（仅 panic = "unwind" 构建，见 catches_panics），结果通过通道返回
+                let result = SolanaTrade::spawn_trade(pumpfun_copy_trade(e));
                 tokio::spawn(async move {
-                    if let Err(err) = pumpfun_copy_trade(e).await {
-                        eprintln!("跟单执行错误: {:?}", err);
-                        std::process::exit(1);
+                    match result.await {
+                        Ok(Ok(())) => std::process::exit(0),
+                        Ok(Err(err)) => {
+                            eprintln!("跟单执行错误: {}", err);
+                            std::process::exit(1);
+                        }
+                        Err(_) => std::process::exit(1),
                     }
-                    std::process::exit(0);
                 });
                 break;
             }
@@ -124,14 +131,7 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
 async fn pumpfun_copy_trade(e: sol_parser_sdk::core::events::PumpFunTradeEvent) -> AnyResult<()> {
     let client = create_solana_trade_client().await?;
     let mint_pubkey = e.mint;
-    let virtual_quote_reserves = if e.virtual_quote_reserves != 0 {
-        e.virtual_quote_reserves
-    } else {
-        e.virtual_sol_reserves
-    };
-    let real_quote_reserves =
-        if e.virtual_quote_reserves != 0 { e.real_quote_reserves } else { e.real_sol_reserves };
-    let slippage_basis_points = Some(100u64);
+    let slippage_basis_points = 100u64;
     let recent_blockhash = client.infrastructure.rpc.get_latest_blockhash().await?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
@@ -141,43 +141,16 @@ async fn pumpfun_copy_trade(e: sol_parser_sdk::core::events::PumpFunTradeEvent)
 
     // 买入：使用事件参数，含 is_cashback_coin（来自 sol-parser-sdk 解析）
     let buy_sol_amount = 100_000u64;
-    let buy_params = sol_trade_sdk::TradeBuyParams {
-        dex_type: DexType::PumpFun,
-        input_token_type: TradeTokenType::SOL,
-        mint: mint_pubkey,
-        input_token_amount: buy_sol_amount,
-        slippage_basis_points,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::PumpFun(PumpFunParams::from_trade(
-            e.bonding_curve,
-            e.associated_bonding_curve,
-            e.mint,
-            e.quote_mint,
-            e.creator,
-            e.creator_vault,
-            e.virtual_token_reserves,
-            virtual_quote_reserves,
-            e.real_token_reserves,
-            real_quote_reserves,
-            None,
-            e.fee_recipient,
-            e.token_program,
-            e.is_cashback_coin,
-            Some(e.mayhem_mode),
-        )),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: false,
-        close_input_token_ata: false,
-        create_mint_ata: true,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy.clone(),
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    let buy_params = sol_trade_sdk::TradeBuyParams::builder(
+        DexType::PumpFun,
+        mint_pubkey,
+        buy_sol_amount,
+        DexParamEnum::PumpFun(PumpFunParams::from_event(&pumpfun_trade_event(&e), None)),
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .recent_blockhash(recent_blockhash)
+    .gas_fee_strategy(gas_fee_strategy.clone())
+    .build();
     let (ok, sigs, err, _) = client.buy(buy_params).await?;
     if !ok {
         return Err(
@@ -198,27 +171,17 @@ async fn pumpfun_copy_trade(e: sol_parser_sdk::core::events::PumpFunTradeEvent)
         .await?
         .with_creator_vault(e.creator_vault);
 
-    let sell_params = sol_trade_sdk::TradeSellParams {
-        dex_type: DexType::PumpFun,
-        output_token_type: TradeTokenType::SOL,
-        mint: mint_pubkey,
-        input_token_amount: amount_token,
-        slippage_basis_points,
-        recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
-        with_tip: false,
-        extension_params: DexParamEnum::PumpFun(sell_extension),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: false,
-        close_output_token_ata: false,
-        close_mint_token_ata: false,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
+    let sell_params = sol_trade_sdk::TradeSellParams::builder(
+        DexType::PumpFun,
+        mint_pubkey,
+        amount_token,
+        DexParamEnum::PumpFun(sell_extension),
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .recent_blockhash(client.infrastructure.rpc.get_latest_blockhash().await?)
+    .with_tip(false)
+    .gas_fee_strategy(gas_fee_strategy)
+    .build();
     let (ok, sigs, err, _) = client.sell(sell_params).await?;
     if !ok {
         return Err(
@@ -229,3 +192,25 @@ async fn pumpfun_copy_trade(e: sol_parser_sdk::core::events::PumpFunTradeEvent)
     println!("跟单一次买+卖完成");
     Ok(())
 }
+
+/// Map the parser's event onto the SDK's by field name.
+fn pumpfun_trade_event(e: &sol_parser_sdk::core::events::PumpFunTradeEvent) -> PumpFunTradeEvent {
+    PumpFunTradeEvent {
+        mint: e.mint,
+        quote_mint: e.quote_mint,
+        bonding_curve: e.bonding_curve,
+        associated_bonding_curve: e.associated_bonding_curve,
+        creator: e.creator,
+        creator_vault: e.creator_vault,
+        fee_recipient: e.fee_recipient,
+        token_program: e.token_program,
+        virtual_token_reserves: e.virtual_token_reserves,
+        real_token_reserves: e.real_token_reserves,
+        virtual_sol_reserves: e.virtual_sol_reserves,
+        real_sol_reserves: e.real_sol_reserves,
+        virtual_quote_reserves: e.virtual_quote_reserves,
+        real_quote_reserves: e.real_quote_reserves,
+        is_cashback_coin: e.is_cashback_coin,
+        mayhem_mode: e.mayhem_mode,
+    }
+}
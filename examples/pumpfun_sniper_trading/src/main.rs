@@ -14,7 +14,6 @@ use sol_parser_sdk::grpc::{
 };
 use sol_parser_sdk::DexEvent;
 use sol_trade_sdk::common::TradeConfig;
-use sol_trade_sdk::TradeTokenType;
 use sol_trade_sdk::{
     common::AnyResult,
     swqos::SwqosConfig,
@@ -115,7 +114,7 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
 async fn pumpfun_sniper_trade(e: sol_parser_sdk::core::events::PumpFunTradeEvent) -> AnyResult<()> {
     let client = create_solana_trade_client().await?;
     let mint_pubkey = e.mint;
-    let slippage_basis_points = Some(300u64);
+    let slippage_basis_points = 300u64;
     let recent_blockhash = client.infrastructure.rpc.get_latest_blockhash().await?;
     let balance_before =
         client.get_payer_token_balance_with_program(&mint_pubkey, &e.token_program).await?;
@@ -127,14 +126,11 @@ async fn pumpfun_sniper_trade(e: sol_parser_sdk::core::events::PumpFunTradeEvent
     let buy_sol_amount = 100_000u64;
     let max_sol_cost = e.sol_amount.saturating_add(e.sol_amount / 10); // 约 +10% 作为上限
 
-    let buy_params = sol_trade_sdk::TradeBuyParams {
-        dex_type: DexType::PumpFun,
-        input_token_type: TradeTokenType::SOL,
-        mint: mint_pubkey,
-        input_token_amount: buy_sol_amount,
-        slippage_basis_points,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::PumpFun(PumpFunParams::from_dev_trade(
+    let buy_params = sol_trade_sdk::TradeBuyParams::builder(
+        DexType::PumpFun,
+        mint_pubkey,
+        buy_sol_amount,
+        DexParamEnum::PumpFun(PumpFunParams::from_dev_trade(
             e.mint,
             e.token_amount,
             max_sol_cost,
@@ -148,19 +144,13 @@ async fn pumpfun_sniper_trade(e: sol_parser_sdk::core::events::PumpFunTradeEvent
             e.is_cashback_coin,
             Some(e.mayhem_mode),
         )),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: true,
-        close_input_token_ata: true,
-        create_mint_ata: true,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy.clone(),
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .recent_blockhash(recent_blockhash)
+    .create_input_token_ata(true)
+    .close_input_token_ata(true)
+    .gas_fee_strategy(gas_fee_strategy.clone())
+    .build();
     let (ok, sigs, err, _) = client.buy(buy_params).await?;
     if !ok {
         anyhow::bail!("buy failed: {:?}; signatures: {:?}", err, sigs);
@@ -178,27 +168,19 @@ async fn pumpfun_sniper_trade(e: sol_parser_sdk::core::events::PumpFunTradeEvent
         .await?
         .with_creator_vault(e.creator_vault);
 
-    let sell_params = sol_trade_sdk::TradeSellParams {
-        dex_type: DexType::PumpFun,
-        output_token_type: TradeTokenType::SOL,
-        mint: mint_pubkey,
-        input_token_amount: amount_token,
-        slippage_basis_points,
-        recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
-        with_tip: false,
-        extension_params: DexParamEnum::PumpFun(sell_extension),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: true,
-        close_output_token_ata: true,
-        close_mint_token_ata: false,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
+    let sell_params = sol_trade_sdk::TradeSellParams::builder(
+        DexType::PumpFun,
+        mint_pubkey,
+        amount_token,
+        DexParamEnum::PumpFun(sell_extension),
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .recent_blockhash(client.infrastructure.rpc.get_latest_blockhash().await?)
+    .with_tip(false)
+    .create_output_token_ata(true)
+    .close_output_token_ata(true)
+    .gas_fee_strategy(gas_fee_strategy)
+    .build();
     let (ok, sigs, err, _) = client.sell(sell_params).await?;
     if !ok {
         anyhow::bail!("sell failed: {:?}; signatures: {:?}", err, sigs);
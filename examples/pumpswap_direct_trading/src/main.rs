@@ -5,7 +5,7 @@ use sol_trade_sdk::{
         core::params::{DexParamEnum, PumpSwapParams},
         factory::DexType,
     },
-    SolanaTrade, TradeTokenType,
+    SolanaTrade,
 };
 use solana_commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
@@ -16,7 +16,7 @@ async fn main() -> AnyResult<()> {
     println!("Testing PumpSwap trading...");
 
     let client = create_solana_trade_client().await?;
-    let slippage_basis_points = Some(100);
+    let slippage_basis_points = 100;
     let recent_blockhash = client.infrastructure.rpc.get_latest_blockhash().await?;
     let pool = Pubkey::from_str("539m4mVWt6iduB6W8rDGPMarzNCMesuqY5eUTiiYHAgR").unwrap();
     let mint_pubkey = Pubkey::from_str("pumpCmXqMfrsAkQ5r49WcJnRayYRqmXz6ae8H7H9Dfn").unwrap();
@@ -25,40 +25,27 @@ async fn main() -> AnyResult<()> {
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
     let pool_params =
         PumpSwapParams::from_pool_address_by_rpc(&client.infrastructure.rpc, &pool).await?;
-    let token_program = if pool_params.base_mint == mint_pubkey {
-        pool_params.base_token_program
-    } else if pool_params.quote_mint == mint_pubkey {
-        pool_params.quote_token_program
-    } else {
+    if pool_params.base_mint != mint_pubkey && pool_params.quote_mint != mint_pubkey {
         anyhow::bail!("target mint does not belong to the configured pool");
-    };
-    let balance_before =
-        client.get_payer_token_balance_with_program(&mint_pubkey, &token_program).await?;
+    }
+    // Token program is inferred from the mint owner (SPL Token vs Token-2022).
+    let balance_before = client.get_payer_trade_token_balance(&mint_pubkey).await?;
 
     // Buy tokens
     println!("Buying tokens from PumpSwap...");
     let buy_sol_amount = 100_000;
-    let buy_params = sol_trade_sdk::TradeBuyParams {
-        dex_type: DexType::PumpSwap,
-        input_token_type: TradeTokenType::SOL,
-        mint: mint_pubkey,
-        input_token_amount: buy_sol_amount,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::PumpSwap(pool_params),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: true,
-        close_input_token_ata: true,
-        create_mint_ata: true,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy.clone(),
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
+    let buy_params = sol_trade_sdk::TradeBuyParams::builder(
+        DexType::PumpSwap,
+        mint_pubkey,
+        buy_sol_amount,
+        DexParamEnum::PumpSwap(pool_params),
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .recent_blockhash(recent_blockhash)
+    .create_input_token_ata(true)
+    .close_input_token_ata(true)
+    .gas_fee_strategy(gas_fee_strategy.clone())
+    .build();
     let (ok, sigs, err, _) = client.buy(buy_params).await?;
     if !ok {
         anyhow::bail!("buy failed: {:?}; signatures: {:?}", err, sigs);
@@ -67,37 +54,28 @@ async fn main() -> AnyResult<()> {
     // Sell tokens
     println!("Selling tokens from PumpSwap...");
 
-    let balance_after =
-        client.get_payer_token_balance_with_program(&mint_pubkey, &token_program).await?;
+    let balance_after = client.get_payer_trade_token_balance(&mint_pubkey).await?;
     let amount_token = balance_after.checked_sub(balance_before).ok_or_else(|| {
         anyhow::anyhow!("token balance decreased after buy; refusing to sell existing holdings")
     })?;
     if amount_token == 0 {
         anyhow::bail!("confirmed buy did not increase token balance");
     }
-    let sell_params = sol_trade_sdk::TradeSellParams {
-        dex_type: DexType::PumpSwap,
-        output_token_type: TradeTokenType::SOL,
-        mint: mint_pubkey,
-        input_token_amount: amount_token,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
-        with_tip: false,
-        extension_params: DexParamEnum::PumpSwap(
+    let sell_params = sol_trade_sdk::TradeSellParams::builder(
+        DexType::PumpSwap,
+        mint_pubkey,
+        amount_token,
+        DexParamEnum::PumpSwap(
             PumpSwapParams::from_pool_address_by_rpc(&client.infrastructure.rpc, &pool).await?,
         ),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: true,
-        close_output_token_ata: true,
-        close_mint_token_ata: false,
-        grpc_recv_us: None,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy,
-        simulate: false,
-    };
+    )
+    .slippage_basis_points(slippage_basis_points)
+    .recent_blockhash(client.infrastructure.rpc.get_latest_blockhash().await?)
+    .with_tip(false)
+    .create_output_token_ata(true)
+    .close_output_token_ata(true)
+    .gas_fee_strategy(gas_fee_strategy)
+    .build();
     let (ok, sigs, err, _) = client.sell(sell_params).await?;
     if !ok {
         anyhow::bail!("sell failed: {:?}; signatures: {:?}", err, sigs);
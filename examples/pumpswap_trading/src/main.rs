@@ -1,14 +1,15 @@
 use sol_trade_sdk::common::{clock::now_micros, SolanaRpcClient, TradeConfig};
-use sol_trade_sdk::TradeTokenType;
 use sol_trade_sdk::{
     common::AnyResult,
     swqos::SwqosConfig,
     trading::{
         core::params::{DexParamEnum, PumpSwapParams},
         factory::DexType,
+        CopyTradeEvent,
     },
     AccountPolicy, BuyAmount, SellAmount, SimpleBuyParams, SimpleSellParams, SolanaTrade,
 };
+use sol_trade_sdk_streamer::{EventTrade, FromEvent};
 use solana_commitment_config::CommitmentConfig;
 use solana_sdk::{hash::Hash, pubkey::Pubkey};
 use solana_streamer_sdk::streaming::event_parser::protocols::pumpswap::parser::PUMPSWAP_PROGRAM_ID;
@@ -339,43 +340,14 @@ async fn pumpswap_trade_with_grpc_buy_event(
     selection: EventSelection,
     trade_info: PumpSwapBuyEvent,
 ) -> AnyResult<EventAction> {
-    let params = PumpSwapParams::from_trade_with_fee_basis_points(
-        trade_info.pool,
-        trade_info.base_mint,
-        trade_info.quote_mint,
-        trade_info.pool_base_token_account,
-        trade_info.pool_quote_token_account,
-        trade_info.pool_base_token_reserves,
-        trade_info.pool_quote_token_reserves,
-        trade_info.virtual_quote_reserves,
-        trade_info.coin_creator_vault_ata,
-        trade_info.coin_creator_vault_authority,
-        trade_info.base_token_program,
-        trade_info.quote_token_program,
-        trade_info.protocol_fee_recipient,
-        Pubkey::default(),
-        trade_info.coin_creator,
-        trade_info.cashback_fee_basis_points != 0 || trade_info.cashback != 0,
-        trade_info.cashback_fee_basis_points,
-        trade_info.lp_fee_basis_points,
-        trade_info.protocol_fee_basis_points,
-        trade_info.coin_creator_fee_basis_points,
-    );
-    let mint = if trade_info.base_mint == sol_trade_sdk::constants::USDC_TOKEN_ACCOUNT
-        || trade_info.base_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT
-    {
-        trade_info.quote_mint
-    } else {
-        trade_info.base_mint
-    };
     pumpswap_trade_with_grpc(
         &client,
         &blockhash_cache,
         &position_baseline,
         trade_info.metadata.recv_us,
         selection.max_event_age_ms,
-        mint,
-        params,
+        &trade_info.decoded_trade()?,
+        PumpSwapParams::try_from_event(&trade_info)?,
     )
     .await
 }
@@ -387,43 +359,14 @@ async fn pumpswap_trade_with_grpc_sell_event(
     selection: EventSelection,
     trade_info: PumpSwapSellEvent,
 ) -> AnyResult<EventAction> {
-    let params = PumpSwapParams::from_trade_with_fee_basis_points(
-        trade_info.pool,
-        trade_info.base_mint,
-        trade_info.quote_mint,
-        trade_info.pool_base_token_account,
-        trade_info.pool_quote_token_account,
-        trade_info.pool_base_token_reserves,
-        trade_info.pool_quote_token_reserves,
-        trade_info.virtual_quote_reserves,
-        trade_info.coin_creator_vault_ata,
-        trade_info.coin_creator_vault_authority,
-        trade_info.base_token_program,
-        trade_info.quote_token_program,
-        trade_info.protocol_fee_recipient,
-        Pubkey::default(),
-        trade_info.coin_creator,
-        trade_info.cashback_fee_basis_points != 0 || trade_info.cashback != 0,
-        trade_info.cashback_fee_basis_points,
-        trade_info.lp_fee_basis_points,
-        trade_info.protocol_fee_basis_points,
-        trade_info.coin_creator_fee_basis_points,
-    );
-    let mint = if trade_info.base_mint == sol_trade_sdk::constants::USDC_TOKEN_ACCOUNT
-        || trade_info.base_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT
-    {
-        trade_info.quote_mint
-    } else {
-        trade_info.base_mint
-    };
     pumpswap_trade_with_grpc(
         &client,
         &blockhash_cache,
         &position_baseline,
         trade_info.metadata.recv_us,
         selection.max_event_age_ms,
-        mint,
-        params,
+        &trade_info.decoded_trade()?,
+        PumpSwapParams::try_from_event(&trade_info)?,
     )
     .await
 }
@@ -434,7 +377,7 @@ async fn pumpswap_trade_with_grpc(
     position_baseline: &Arc<RwLock<Option<PositionBaseline>>>,
     grpc_recv_us: i64,
     max_event_age_ms: u64,
-    mint_pubkey: Pubkey,
+    event: &dyn CopyTradeEvent,
     params: PumpSwapParams,
 ) -> AnyResult<EventAction> {
     println!("Testing PumpSwap trading...");
@@ -444,15 +387,12 @@ async fn pumpswap_trade_with_grpc(
     }
     let slippage_basis_points = Some(500);
 
-    let is_sol = params.base_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT
-        || params.quote_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT;
-    let program_id = if params.base_mint == mint_pubkey {
-        params.base_token_program
-    } else if params.quote_mint == mint_pubkey {
-        params.quote_token_program
-    } else {
-        anyhow::bail!("target mint {} does not belong to pool {}", mint_pubkey, params.pool);
-    };
+    // the traded side is the pool's non-quote mint, whichever side the pool stores it on
+    let mint_pubkey = event.mint();
+    let quote_token_type = event.quote_token_type();
+    let program_id = event
+        .mint_token_program()
+        .ok_or_else(|| anyhow::anyhow!("event does not name the mint's token program"))?;
     let baseline = position_baseline
         .read()
         .map_err(|_| anyhow::anyhow!("position baseline lock is poisoned"))?
@@ -488,7 +428,7 @@ async fn pumpswap_trade_with_grpc(
     let buy_token_amount = 300_000;
     let buy_params = SimpleBuyParams::new(
         DexType::PumpSwap,
-        if is_sol { TradeTokenType::SOL } else { TradeTokenType::USDC },
+        quote_token_type.clone(),
         mint_pubkey,
         BuyAmount::WithMaxInput { quote_amount: buy_token_amount },
         DexParamEnum::PumpSwap(params.clone()),
@@ -523,7 +463,7 @@ async fn pumpswap_trade_with_grpc(
         PumpSwapParams::from_pool_address_by_rpc(&client.infrastructure.rpc, &params.pool).await?;
     let sell_params = SimpleSellParams::new(
         DexType::PumpSwap,
-        if is_sol { TradeTokenType::SOL } else { TradeTokenType::USDC },
+        quote_token_type,
         mint_pubkey,
         SellAmount::ExactInput(position_amount),
         DexParamEnum::PumpSwap(sell_params_from_rpc),
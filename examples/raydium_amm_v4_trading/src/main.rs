@@ -1,13 +1,14 @@
+use sol_trade_sdk::common::TradeConfig;
 use sol_trade_sdk::{
     common::AnyResult,
-    swqos::SwqosConfig,
+    swqos::{SwqosConfig, TradeType},
     trading::{
         core::params::{DexParamEnum, RaydiumAmmV4Params},
-        factory::DexType,
+        CopyTradeEvent, DecodedTrade,
     },
     SolanaTrade,
 };
-use sol_trade_sdk::{common::TradeConfig, TradeTokenType};
+use sol_trade_sdk_streamer::FromEventByRpc;
 use solana_commitment_config::CommitmentConfig;
 use solana_streamer_sdk::streaming::event_parser::common::filter::EventTypeFilter;
 use solana_streamer_sdk::streaming::event_parser::common::EventType;
@@ -110,25 +111,19 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
     Ok(solana_trade)
 }
 
-/// Raydium_amm_v4 sniper trade
-/// This function demonstrates how to snipe a new token from a Raydium_amm_v4 trade event
+/// Raydium_amm_v4 copy trade
+/// The swap event names the AMM but carries no mints or reserves: load the AMM it names and trade
+/// its non-quote side through `copy_trade`
 async fn raydium_amm_v4_copy_trade_with_grpc(trade_info: RaydiumAmmV4SwapEvent) -> AnyResult<()> {
     println!("Testing Raydium_amm_v4 trading...");
 
     let client = create_solana_trade_client().await?;
     let slippage_basis_points = Some(100);
-    let recent_blockhash = client.infrastructure.rpc.get_latest_blockhash().await?;
-
-    let params =
-        RaydiumAmmV4Params::from_amm_address_by_rpc(&client.infrastructure.rpc, trade_info.amm)
-            .await?;
-    let mint_pubkey = if params.pc_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT
-        || params.pc_mint == sol_trade_sdk::constants::USDC_TOKEN_ACCOUNT
-    {
-        params.coin_mint
-    } else {
-        params.pc_mint
-    };
+
+    let buy_trade = DecodedTrade::from_pool_params(DexParamEnum::RaydiumAmmV4(
+        RaydiumAmmV4Params::from_event_by_rpc(&client.infrastructure.rpc, &trade_info).await?,
+    ))?;
+    let mint_pubkey = buy_trade.mint();
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
@@ -136,37 +131,25 @@ async fn raydium_amm_v4_copy_trade_with_grpc(trade_info: RaydiumAmmV4SwapEvent)
     // Buy tokens
     println!("Buying tokens from Raydium_amm_v4...");
     let input_token_amount = 100_000;
-    let is_wsol = params.pc_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT
-        || params.coin_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT;
+    // AMM v4 pool state does not record token programs
     let mint_token_program = client.infrastructure.rpc.get_account(&mint_pubkey).await?.owner;
     let balance_before =
         client.get_payer_token_balance_with_program(&mint_pubkey, &mint_token_program).await?;
-    let buy_params = sol_trade_sdk::TradeBuyParams {
-        dex_type: DexType::RaydiumAmmV4,
-        input_token_type: if is_wsol { TradeTokenType::WSOL } else { TradeTokenType::USDC },
-        mint: mint_pubkey,
-        input_token_amount: input_token_amount,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::RaydiumAmmV4(params),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: is_wsol,
-        close_input_token_ata: is_wsol,
-        create_mint_ata: true,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy.clone(),
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
-    let (ok, sigs, err, _) = client.buy(buy_params).await?;
-    if !ok {
-        return Err(
-            std::io::Error::other(format!("buy failed: {:?}; sigs: {:?}", err, sigs)).into()
-        );
+    let outcome = client
+        .copy_trade(
+            &buy_trade,
+            TradeType::Buy,
+            input_token_amount,
+            slippage_basis_points,
+            gas_fee_strategy.clone(),
+        )
+        .await?;
+    if !outcome.success {
+        return Err(std::io::Error::other(format!(
+            "buy failed: {:?}; sigs: {:?}",
+            outcome.error, outcome.signatures
+        ))
+        .into());
     }
 
     // Sell tokens
@@ -182,35 +165,24 @@ async fn raydium_amm_v4_copy_trade_with_grpc(trade_info: RaydiumAmmV4SwapEvent)
     }
 
     println!("Selling {} tokens", amount_token);
-    let params =
-        RaydiumAmmV4Params::from_amm_address_by_rpc(&client.infrastructure.rpc, trade_info.amm)
-            .await?;
-    let sell_params = sol_trade_sdk::TradeSellParams {
-        dex_type: DexType::RaydiumAmmV4,
-        output_token_type: if is_wsol { TradeTokenType::WSOL } else { TradeTokenType::USDC },
-        mint: mint_pubkey,
-        input_token_amount: amount_token,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
-        with_tip: false,
-        extension_params: DexParamEnum::RaydiumAmmV4(params),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: is_wsol,
-        close_output_token_ata: is_wsol,
-        close_mint_token_ata: false,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
-    let (ok, sigs, err, _) = client.sell(sell_params).await?;
-    if !ok {
-        return Err(
-            std::io::Error::other(format!("sell failed: {:?}; sigs: {:?}", err, sigs)).into()
-        );
+    let sell_trade = DecodedTrade::from_pool_params(DexParamEnum::RaydiumAmmV4(
+        RaydiumAmmV4Params::from_event_by_rpc(&client.infrastructure.rpc, &trade_info).await?,
+    ))?;
+    let outcome = client
+        .copy_trade(
+            &sell_trade,
+            TradeType::Sell,
+            amount_token,
+            slippage_basis_points,
+            gas_fee_strategy,
+        )
+        .await?;
+    if !outcome.success {
+        return Err(std::io::Error::other(format!(
+            "sell failed: {:?}; sigs: {:?}",
+            outcome.error, outcome.signatures
+        ))
+        .into());
     }
 
     // Exit program
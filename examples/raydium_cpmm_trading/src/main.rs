@@ -1,9 +1,13 @@
 use sol_trade_sdk::common::TradeConfig;
 use sol_trade_sdk::constants::{USDC_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
 use sol_trade_sdk::trading::core::params::{DexParamEnum, RaydiumCpmmParams};
-use sol_trade_sdk::trading::factory::DexType;
-use sol_trade_sdk::TradeTokenType;
-use sol_trade_sdk::{common::AnyResult, swqos::SwqosConfig, SolanaTrade};
+use sol_trade_sdk::trading::{CopyTradeEvent, DecodedTrade};
+use sol_trade_sdk::{
+    common::AnyResult,
+    swqos::{SwqosConfig, TradeType},
+    SolanaTrade,
+};
+use sol_trade_sdk_streamer::FromEventByRpc;
 use solana_commitment_config::CommitmentConfig;
 use solana_streamer_sdk::streaming::event_parser::common::filter::EventTypeFilter;
 use solana_streamer_sdk::streaming::event_parser::common::EventType;
@@ -113,72 +117,46 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
     Ok(solana_trade)
 }
 
-/// Raydium_cpmm sniper trade
-/// This function demonstrates how to snipe a new token from a Raydium_cpmm trade event
+/// Raydium_cpmm copy trade
+/// The swap event names the pool but carries no reserves: load the pool it names and trade its
+/// non-quote side through `copy_trade`
 async fn raydium_cpmm_copy_trade_with_grpc(trade_info: RaydiumCpmmSwapEvent) -> AnyResult<()> {
     println!("Testing Raydium_cpmm trading...");
 
     let client = create_solana_trade_client().await?;
-    let mint_pubkey = if trade_info.input_token_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT
-        || trade_info.input_token_mint == sol_trade_sdk::constants::USDC_TOKEN_ACCOUNT
-    {
-        trade_info.output_token_mint
-    } else {
-        trade_info.input_token_mint
-    };
     let slippage_basis_points = Some(100);
-    let recent_blockhash = client.infrastructure.rpc.get_latest_blockhash().await?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
     gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
 
-    let buy_params = RaydiumCpmmParams::from_pool_address_by_rpc(
-        &client.infrastructure.rpc,
-        &trade_info.pool_state,
-    )
-    .await?;
-
-    let is_wsol = trade_info.input_token_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT
-        || trade_info.output_token_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT;
-    let mint_token_program = if buy_params.base_mint == mint_pubkey {
-        buy_params.base_token_program
-    } else if buy_params.quote_mint == mint_pubkey {
-        buy_params.quote_token_program
-    } else {
-        return Err(std::io::Error::other("target mint does not belong to pool").into());
-    };
+    let buy_trade = DecodedTrade::from_pool_params(DexParamEnum::RaydiumCpmm(
+        RaydiumCpmmParams::from_event_by_rpc(&client.infrastructure.rpc, &trade_info).await?,
+    ))?;
+    let mint_pubkey = buy_trade.mint();
+    let mint_token_program = buy_trade
+        .mint_token_program()
+        .ok_or_else(|| std::io::Error::other("pool does not name the mint's token program"))?;
     let balance_before =
         client.get_payer_token_balance_with_program(&mint_pubkey, &mint_token_program).await?;
 
     // Buy tokens
     println!("Buying tokens from Raydium_cpmm...");
     let input_token_amount = 100_000;
-    let buy_params = sol_trade_sdk::TradeBuyParams {
-        dex_type: DexType::RaydiumCpmm,
-        input_token_type: if is_wsol { TradeTokenType::SOL } else { TradeTokenType::USDC },
-        mint: mint_pubkey,
-        input_token_amount: input_token_amount,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::RaydiumCpmm(buy_params),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_input_token_ata: is_wsol,
-        close_input_token_ata: is_wsol,
-        create_mint_ata: true,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy.clone(),
-        simulate: false,
-        use_exact_sol_amount: None,
-        grpc_recv_us: None,
-    };
-    let (ok, sigs, err, _) = client.buy(buy_params).await?;
-    if !ok {
-        return Err(
-            std::io::Error::other(format!("buy failed: {:?}; sigs: {:?}", err, sigs)).into()
-        );
+    let outcome = client
+        .copy_trade(
+            &buy_trade,
+            TradeType::Buy,
+            input_token_amount,
+            slippage_basis_points,
+            gas_fee_strategy.clone(),
+        )
+        .await?;
+    if !outcome.success {
+        return Err(std::io::Error::other(format!(
+            "buy failed: {:?}; sigs: {:?}",
+            outcome.error, outcome.signatures
+        ))
+        .into());
     }
 
     // Sell tokens
@@ -193,39 +171,26 @@ async fn raydium_cpmm_copy_trade_with_grpc(trade_info: RaydiumCpmmSwapEvent) ->
         return Err(std::io::Error::other("confirmed buy did not increase token balance").into());
     }
 
-    let sell_params = RaydiumCpmmParams::from_pool_address_by_rpc(
-        &client.infrastructure.rpc,
-        &trade_info.pool_state,
-    )
-    .await?;
+    let sell_trade = DecodedTrade::from_pool_params(DexParamEnum::RaydiumCpmm(
+        RaydiumCpmmParams::from_event_by_rpc(&client.infrastructure.rpc, &trade_info).await?,
+    ))?;
 
     println!("Selling {} tokens", amount_token);
-    let sell_params = sol_trade_sdk::TradeSellParams {
-        dex_type: DexType::RaydiumCpmm,
-        output_token_type: if is_wsol { TradeTokenType::SOL } else { TradeTokenType::USDC },
-        mint: mint_pubkey,
-        input_token_amount: amount_token,
-        slippage_basis_points: slippage_basis_points,
-        recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
-        with_tip: false,
-        extension_params: DexParamEnum::RaydiumCpmm(sell_params),
-        address_lookup_table_accounts: Vec::new(),
-        wait_tx_confirmed: true,
-        wait_for_all_submits: false,
-        create_output_token_ata: is_wsol,
-        close_output_token_ata: is_wsol,
-        close_mint_token_ata: false,
-        durable_nonce: None,
-        fixed_output_token_amount: None,
-        gas_fee_strategy: gas_fee_strategy,
-        simulate: false,
-        grpc_recv_us: None,
-    };
-    let (ok, sigs, err, _) = client.sell(sell_params).await?;
-    if !ok {
-        return Err(
-            std::io::Error::other(format!("sell failed: {:?}; sigs: {:?}", err, sigs)).into()
-        );
+    let outcome = client
+        .copy_trade(
+            &sell_trade,
+            TradeType::Sell,
+            amount_token,
+            slippage_basis_points,
+            gas_fee_strategy,
+        )
+        .await?;
+    if !outcome.success {
+        return Err(std::io::Error::other(format!(
+            "sell failed: {:?}; sigs: {:?}",
+            outcome.error, outcome.signatures
+        ))
+        .into());
     }
 
     // Exit program
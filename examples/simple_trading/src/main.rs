@@ -11,7 +11,7 @@ use sol_trade_sdk::{
     instruction::utils::pumpfun::global_constants,
     swqos::SwqosConfig,
     trading::{
-        core::params::{DexParamEnum, PumpFunParams},
+        core::params::{DexParamEnum, PumpFunParams, PumpFunTradeEvent},
         factory::DexType,
     },
     AccountPolicy, BuyAmount, DurableNonceInfo, SellAmount, SimpleBuyParams, SimpleSellParams,
@@ -40,27 +40,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // In production, fill these fields from your parser/RPC cache. They are
     // protocol state, not user preferences.
-    let pumpfun_params = DexParamEnum::PumpFun(PumpFunParams::from_trade(
-        Pubkey::new_unique(), // bonding_curve
-        Pubkey::new_unique(), // associated_bonding_curve
+    let event = PumpFunTradeEvent {
         mint,
         // WSOL quote_mint selects PumpFun V2 SOL layout. Users still pay with
         // native SOL below by setting `TradeTokenType::SOL`.
-        WSOL_TOKEN_ACCOUNT,
-        Pubkey::new_unique(),  // creator from event/cache
-        Pubkey::default(),     // creator_vault; SDK derives it if unavailable
-        1_073_000_000_000_000, // virtual_token_reserves
-        30_000_000_000,        // virtual_quote_reserves
-        793_100_000_000_000,   // real_token_reserves
-        0,                     // real_quote_reserves
-        None,                  // close_token_account_when_sell
-        global_constants::FEE_RECIPIENT,
+        quote_mint: WSOL_TOKEN_ACCOUNT,
+        bonding_curve: Pubkey::new_unique(),
+        associated_bonding_curve: Pubkey::new_unique(),
+        creator: Pubkey::new_unique(),
+        // creator_vault left default: the SDK derives it.
+        fee_recipient: global_constants::FEE_RECIPIENT,
         // If parser/cache does not know the mint owner, PumpFun now defaults to
         // Token-2022. Passing it explicitly makes the example easier to read.
-        TOKEN_PROGRAM_2022,
-        false,       // is_cashback_coin
-        Some(false), // mayhem_mode
-    ));
+        token_program: TOKEN_PROGRAM_2022,
+        virtual_token_reserves: 1_073_000_000_000_000,
+        real_token_reserves: 793_100_000_000_000,
+        virtual_sol_reserves: 30_000_000_000,
+        ..Default::default()
+    };
+    let pumpfun_params = DexParamEnum::PumpFun(PumpFunParams::from_event(&event, None));
 
     let buy_params = SimpleBuyParams::new(
         DexType::PumpFun,
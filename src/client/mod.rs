@@ -1,10 +1,14 @@
 //! High-level [`TradingClient`], [`TradingInfrastructure`], and trade parameter types.
 
-use crate::common::nonce_cache::DurableNonceInfo;
+mod params_builder;
+pub use params_builder::{TradeBuyParamsBuilder, TradeSellParamsBuilder};
+
+use crate::common::nonce_cache::{DurableNonceInfo, MultiNonceConfig};
+use crate::common::positions::{fetch_positions, Position};
 use crate::common::sdk_log;
 use crate::common::GasFeeStrategy;
 use crate::common::SolanaRpcClient;
-use crate::common::{InfrastructureConfig, TradeConfig};
+use crate::common::{InfrastructureConfig, ParallelStrategy, TradeConfig};
 #[cfg(feature = "perf-trace")]
 use crate::constants::trade::trade::DEFAULT_SLIPPAGE;
 use crate::constants::SOL_TOKEN_ACCOUNT;
@@ -12,10 +16,17 @@ use crate::constants::USD1_TOKEN_ACCOUNT;
 use crate::constants::USDC_TOKEN_ACCOUNT;
 use crate::constants::WSOL_TOKEN_ACCOUNT;
 use crate::swqos::common::TradeError;
+use crate::swqos::rate_limit::RateLimitedSwqosClient;
+use crate::swqos::submit_timeout::TimeoutSwqosClient;
+use crate::swqos::watchdog::{SwqosEndpointStats, SwqosWatchdog, SwqosWatchdogConfig};
 use crate::swqos::SwqosClient;
 use crate::swqos::SwqosConfig;
 use crate::swqos::SwqosType;
 use crate::swqos::TradeType;
+use crate::trading::common::blockhash_refresh::{BlockhashGuard, BlockhashRefreshConfig};
+use crate::trading::copy_trade::{CopyTradeEvent, DecodedTrade};
+use crate::trading::core::async_executor::BeforeSendHook;
+use crate::trading::core::params::AtaCreationMode;
 use crate::trading::core::params::BonkParams;
 use crate::trading::core::params::DexParamEnum;
 use crate::trading::core::params::MeteoraDammV2Params;
@@ -23,7 +34,11 @@ use crate::trading::core::params::PumpFunParams;
 use crate::trading::core::params::PumpSwapParams;
 use crate::trading::core::params::RaydiumAmmV4Params;
 use crate::trading::core::params::RaydiumCpmmParams;
+use crate::trading::cu_learner::{CuLearner, CuLearnerConfig};
 use crate::trading::factory::DexType;
+use crate::trading::outcome::{TradeOutcomeBroadcast, TradeOutcomeContext, TradeOutcomeSink};
+use crate::trading::simulation::{SimulationCapture, SimulationReport};
+use crate::trading::slippage::{SlippageContext, SlippageModel};
 use crate::trading::MiddlewareManager;
 use crate::trading::SwapParams;
 use crate::trading::TradeFactory;
@@ -33,9 +48,11 @@ use solana_sdk::hash::Hash;
 use solana_sdk::message::AddressLookupTableAccount;
 use solana_sdk::signer::Signer;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair, signature::Signature};
+use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 #[allow(unused_imports)]
-use tracing::{debug, error, info, warn};
+use tracing::{debug, Level};
 
 /// Single place to validate that protocol params match the given DEX type (avoids duplicate match in buy/sell).
 #[inline(always)]
@@ -59,6 +76,21 @@ fn normalize_swqos_configs(rpc_url: &str, configs: &[SwqosConfig]) -> Vec<SwqosC
     out
 }
 
+/// Configured, non-blacklisted routes that have no client in `clients` (their init failed or
+/// timed out). 未成功初始化的 SWQOS 通道。
+fn missing_swqos_routes(
+    rpc_url: &str,
+    configs: &[SwqosConfig],
+    clients: &[Arc<SwqosClient>],
+) -> Vec<SwqosType> {
+    normalize_swqos_configs(rpc_url, configs)
+        .iter()
+        .filter(|c| !c.is_blacklisted())
+        .map(|c| c.swqos_type())
+        .filter(|t| !clients.iter().any(|client| client.get_swqos_type() == *t))
+        .collect()
+}
+
 /// 按 mint 查找池地址（通用入口，根据 DEX 类型分发，仅 PumpSwap 等已实现的类型会走优化路径）。
 ///
 /// * `dex_type`：PumpSwap 时先走 PDA 再回退 getProgramAccounts，其他类型返回未实现错误。
@@ -73,6 +105,23 @@ pub async fn find_pool_by_mint(
     }
 }
 
+/// 按 mint + quote mint 查找池地址，用于卖出到与买入不同的 quote 池（如 WSOL 买入、USDC 卖出）。
+///
+/// Only PumpSwap is supported; the result is meant for [`TradeSellParams::pool_override`].
+pub async fn find_pool_by_mint_and_quote(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    quote_mint: &Pubkey,
+    dex_type: DexType,
+) -> Result<Pubkey, anyhow::Error> {
+    match dex_type {
+        DexType::PumpSwap => {
+            crate::instruction::utils::pumpswap::find_pool_with_quote(rpc, mint, quote_mint).await
+        }
+        _ => Err(anyhow::anyhow!("find_pool_by_mint_and_quote not implemented for {:?}", dex_type)),
+    }
+}
+
 /// Type of the token to buy
 #[derive(Clone, PartialEq)]
 pub enum TradeTokenType {
@@ -529,6 +578,47 @@ impl TradingInfrastructure {
     /// - Creates SWQOS clients (each with their own HTTP client)
     /// - Initializes rent cache and starts background updater
     pub async fn new(config: InfrastructureConfig) -> Self {
+        // Create RPC client (custom headers / timeout when configured)
+        let rpc = match config.rpc_client_config.build_client(&config.rpc_url, config.commitment) {
+            Ok(rpc) => rpc,
+            Err(e) => {
+                sdk_log::startup_log(
+                    Level::ERROR,
+                    format_args!("RPC client config rejected: {}; using defaults", e),
+                );
+                SolanaRpcClient::new_with_commitment(config.rpc_url.clone(), config.commitment)
+            }
+        };
+        Self::with_rpc_client(config, Arc::new(rpc)).await
+    }
+
+    /// Client for `swqos`; the default RPC route at `config.rpc_url` reuses `rpc`.
+    async fn swqos_client(
+        config: &InfrastructureConfig,
+        rpc: &Arc<SolanaRpcClient>,
+        swqos: SwqosConfig,
+    ) -> anyhow::Result<Arc<SwqosClient>> {
+        match swqos {
+            SwqosConfig::Default(endpoint) if endpoint == config.rpc_url => {
+                Ok(Arc::new(crate::swqos::solana_rpc::SolRpcClient::new(rpc.clone())))
+            }
+            swqos => {
+                SwqosConfig::get_swqos_client(
+                    config.rpc_url.clone(),
+                    config.commitment.clone(),
+                    swqos,
+                    config.mev_protection,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Same as [`Self::new`] but uses `rpc` instead of building one from `config.rpc_url`
+    /// (`rpc_client_config` is ignored). The default RPC route (`SwqosConfig::Default` at
+    /// `config.rpc_url`, added when none is configured) submits through `rpc` as well; other
+    /// SWQOS routes are created from `config`. 使用外部注入的 RPC 客户端。
+    pub async fn with_rpc_client(config: InfrastructureConfig, rpc: Arc<SolanaRpcClient>) -> Self {
         // Install crypto provider (idempotent)
         if CryptoProvider::get_default().is_none() {
             let _ = default_provider()
@@ -536,12 +626,6 @@ impl TradingInfrastructure {
                 .map_err(|e| anyhow::anyhow!("Failed to install crypto provider: {:?}", e));
         }
 
-        // Create RPC client
-        let rpc = Arc::new(SolanaRpcClient::new_with_commitment(
-            config.rpc_url.clone(),
-            config.commitment.clone(),
-        ));
-
         // Initialize rent cache (with timeout so slow RPC doesn't block forever)
         const RENT_UPDATE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
         match tokio::time::timeout(RENT_UPDATE_TIMEOUT, crate::common::seed::update_rents(&rpc))
@@ -549,15 +633,20 @@ impl TradingInfrastructure {
         {
             Ok(Ok(())) => {}
             Ok(Err(e)) => {
-                if sdk_log::sdk_log_enabled() {
-                    warn!(target: "sol_trade_sdk", "rent update failed: {}, using defaults", e);
-                }
+                sdk_log::startup_log(
+                    Level::WARN,
+                    format_args!("rent update failed: {}; using default rents", e),
+                );
                 crate::common::seed::set_default_rents();
             }
             Err(_) => {
-                if sdk_log::sdk_log_enabled() {
-                    warn!(target: "sol_trade_sdk", "rent update timed out ({}s), using defaults; check RPC", RENT_UPDATE_TIMEOUT.as_secs());
-                }
+                sdk_log::startup_log(
+                    Level::WARN,
+                    format_args!(
+                        "rent update timed out after {}s; using default rents, check RPC",
+                        RENT_UPDATE_TIMEOUT.as_secs()
+                    ),
+                );
                 crate::common::seed::set_default_rents();
             }
         }
@@ -569,93 +658,74 @@ impl TradingInfrastructure {
         let mut swqos_clients: Vec<Arc<SwqosClient>> = vec![];
         for swqos in &swqos_configs {
             if swqos.is_blacklisted() {
-                if sdk_log::sdk_log_enabled() {
-                    warn!(target: "sol_trade_sdk", "⚠️ SWQOS {:?} is blacklisted, skipping", swqos.swqos_type());
-                }
+                sdk_log::startup_log(
+                    Level::WARN,
+                    format_args!("SWQOS {:?} is blacklisted, skipping", swqos.swqos_type()),
+                );
                 continue;
             }
             match tokio::time::timeout(
                 SWQOS_CLIENT_TIMEOUT,
-                SwqosConfig::get_swqos_client(
-                    config.rpc_url.clone(),
-                    config.commitment.clone(),
-                    swqos.clone(),
-                    config.mev_protection,
-                ),
+                Self::swqos_client(&config, &rpc, swqos.clone()),
             )
             .await
             {
-                Ok(Ok(swqos_client)) => swqos_clients.push(swqos_client),
-                Ok(Err(err)) => {
-                    eprintln!(
-                        "⚠️  SWQOS {:?} 初始化失败: {}（已从列表中排除）",
+                Ok(Ok(swqos_client)) => swqos_clients.push(RateLimitedSwqosClient::wrap(
+                    TimeoutSwqosClient::wrap(
+                        swqos_client,
+                        config.submit_timeout_for(swqos.swqos_type()),
+                    ),
+                    config.rate_limit_for(swqos.swqos_type()),
+                )),
+                Ok(Err(err)) => sdk_log::startup_log(
+                    Level::WARN,
+                    format_args!(
+                        "SWQOS {:?} client init failed: {}; excluded from routes",
                         swqos.swqos_type(),
                         err
-                    );
-                    if sdk_log::sdk_log_enabled() {
-                        warn!(
-                            target: "sol_trade_sdk",
-                            "failed to create {:?} swqos client: {err}. Excluding from swqos list",
-                            swqos.swqos_type()
-                        );
-                    }
-                }
-                Err(_) => {
-                    eprintln!(
-                        "⚠️  SWQOS {:?} 初始化超时（{}s），已跳过",
+                    ),
+                ),
+                Err(_) => sdk_log::startup_log(
+                    Level::WARN,
+                    format_args!(
+                        "SWQOS {:?} client init timed out after {}s; excluded from routes",
                         swqos.swqos_type(),
                         SWQOS_CLIENT_TIMEOUT.as_secs()
-                    );
-                    if sdk_log::sdk_log_enabled() {
-                        warn!(
-                            target: "sol_trade_sdk",
-                            "swqos {:?} init timed out ({}s), skipping",
-                            swqos.swqos_type(),
-                            SWQOS_CLIENT_TIMEOUT.as_secs()
-                        );
-                    }
-                }
+                    ),
+                ),
             }
         }
 
         // 若全部失败、被黑名单跳过或仅配置了不可用通道，至少保留一条 Rpc Default，否则 execute_parallel 会因 swqos_clients 为空直接报错。
         if swqos_clients.is_empty() {
-            eprintln!(
-                "⚠️  无任何 SWQOS 客户端初始化成功，将回退为普通 RPC 发送: {}",
-                config.rpc_url
-            );
-            if sdk_log::sdk_log_enabled() {
-                warn!(
-                    target: "sol_trade_sdk",
-                    "no SWQOS clients initialized; falling back to Rpc Default ({})",
+            sdk_log::startup_log(
+                Level::WARN,
+                format_args!(
+                    "no SWQOS client initialized; falling back to plain RPC submission ({})",
                     config.rpc_url
-                );
-            }
-            match SwqosConfig::get_swqos_client(
-                config.rpc_url.clone(),
-                config.commitment.clone(),
-                SwqosConfig::Default(config.rpc_url.clone()),
-                config.mev_protection,
-            )
-            .await
+                ),
+            );
+            match Self::swqos_client(&config, &rpc, SwqosConfig::Default(config.rpc_url.clone()))
+                .await
             {
-                Ok(c) => swqos_clients.push(c),
-                Err(e) => {
-                    if sdk_log::sdk_log_enabled() {
-                        warn!(
-                            target: "sol_trade_sdk",
-                            "fallback Rpc Default client failed: {}",
-                            e
-                        );
-                    }
-                }
+                Ok(c) => swqos_clients.push(RateLimitedSwqosClient::wrap(
+                    TimeoutSwqosClient::wrap(c, config.submit_timeout_for(SwqosType::Default)),
+                    config.rate_limit_for(SwqosType::Default),
+                )),
+                Err(e) => sdk_log::startup_log(
+                    Level::ERROR,
+                    format_args!("fallback RPC submission client failed: {}", e),
+                ),
             }
         }
 
         if !swqos_clients.is_empty() {
             let labels: Vec<&str> =
                 swqos_clients.iter().map(|c| c.get_swqos_type().as_str()).collect();
-            eprintln!("ℹ️  SWQOS 通道已就绪: {} 条 → [{}]", swqos_clients.len(), labels.join(", "));
+            sdk_log::startup_log(
+                Level::INFO,
+                format_args!("SWQOS routes ready: {} [{}]", swqos_clients.len(), labels.join(", ")),
+            );
         }
 
         let max_submit_lanes = swqos_clients
@@ -744,10 +814,36 @@ pub struct TradingClient {
     pub log_enabled: bool,
     /// Whether to check minimum tip per SWQOS (from TradeConfig.check_min_tip). Default false for lower latency.
     pub check_min_tip: bool,
+    /// Optional SWQOS landing watchdog (set via with_swqos_watchdog). 落地率看门狗。
+    pub swqos_watchdog: Option<Arc<SwqosWatchdog>>,
+    /// Optional compute-unit learner (set via with_cu_learner). CU 消耗学习。
+    pub cu_learner: Option<Arc<CuLearner>>,
+    /// Receivers of every buy/sell outcome (set via with_outcome_sink / with_webhook).
+    pub outcome_sinks: Vec<Arc<dyn TradeOutcomeSink>>,
+    /// Every buy/sell outcome, for `subscribe_trades`. Shared by clones of this client.
+    pub trade_outcomes: TradeOutcomeBroadcast,
+    /// When set, event reserves are checked against chain before each trade (set via with_reserve_check).
+    pub reserve_check_tolerance_bps: Option<u64>,
+    /// When true, seed-optimized sells first check the seed token account exists (set via with_seed_ata_check).
+    pub seed_ata_check: bool,
+    /// When set, PumpFun trades first check the curve has not graduated; the value is
+    /// `auto_switch_protocol` (set via with_migration_guard).
+    pub migration_guard: Option<bool>,
+    /// How submits fan out across SWQOS routes (set via with_parallel_strategy). Default `RaceAll`.
+    pub parallel_strategy: ParallelStrategy,
+    /// Last-resort plain-RPC submit when every SWQOS route fails (set via with_rpc_fallback).
+    pub rpc_fallback: bool,
+    /// Refreshes stale caller-supplied blockhashes (set via with_blockhash_refresh).
+    pub blockhash_guard: Option<Arc<BlockhashGuard>>,
+    /// Veto on each final signed transaction before submit (set via with_on_before_send).
+    pub on_before_send: Option<BeforeSendHook>,
 }
 
 static INSTANCE: Mutex<Option<Arc<TradingClient>>> = Mutex::new(None);
 
+/// How long [`TradingClient::request_airdrop`] waits for the airdrop to confirm.
+const AIRDROP_CONFIRM_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
 /// 🔄 向后兼容：SolanaTrade 别名
 pub type SolanaTrade = TradingClient;
 
@@ -764,6 +860,17 @@ impl Clone for TradingClient {
             effective_core_ids: self.effective_core_ids.clone(),
             log_enabled: self.log_enabled,
             check_min_tip: self.check_min_tip,
+            swqos_watchdog: self.swqos_watchdog.clone(),
+            cu_learner: self.cu_learner.clone(),
+            outcome_sinks: self.outcome_sinks.clone(),
+            trade_outcomes: self.trade_outcomes.clone(),
+            reserve_check_tolerance_bps: self.reserve_check_tolerance_bps,
+            seed_ata_check: self.seed_ata_check,
+            migration_guard: self.migration_guard,
+            parallel_strategy: self.parallel_strategy,
+            rpc_fallback: self.rpc_fallback,
+            blockhash_guard: self.blockhash_guard.clone(),
+            on_before_send: self.on_before_send.clone(),
         }
     }
 }
@@ -801,16 +908,23 @@ pub struct TradeBuyParams {
     /// variants are not mutually exclusive; durable nonce variants are.
     /// Defaults to false. See `SwapParams.wait_for_all_submits`.
     pub wait_for_all_submits: bool,
-    /// Whether to create input token associated token account
+    /// Create the payer's quote ATA (`input_token_type`): the WSOL ATA for SOL/WSOL, wrapping
+    /// the buy amount into it; the USDC/USD1 ATA otherwise. Never the bought token's ATA.
+    /// Maps to `SwapParams.create_input_mint_ata`.
     pub create_input_token_ata: bool,
-    /// Whether to close input token associated token account after trade
+    /// Close the payer's WSOL ATA after the buy (returns leftover wrapped SOL and rent).
+    /// Only WSOL is ever closed; USDC/USD1 quote ATAs and the bought token's ATA stay open.
+    /// Maps to `SwapParams.close_input_mint_ata`.
     pub close_input_token_ata: bool,
-    /// Whether to create token mint associated token account
+    /// Create the bought token's (`mint`) ATA. Maps to `SwapParams.create_output_mint_ata`.
     pub create_mint_ata: bool,
     /// Durable nonce information
     pub durable_nonce: Option<DurableNonceInfo>,
-    /// Optional fixed output token amount. On exact-out capable DEXes this uses
-    /// the exact-out instruction and treats `input_token_amount` as max input.
+    /// One durable nonce per SWQOS route, so several independent transactions can land;
+    /// replaces `durable_nonce`. Every landed signature is returned.
+    pub multi_nonce: Option<Arc<MultiNonceConfig>>,
+    /// Exact tokens out. The max quote input is quoted from reserves plus slippage and must fit
+    /// in `input_token_amount`. `Some(0)` is rejected, as is any value on Meteora DAMM v2.
     pub fixed_output_token_amount: Option<u64>,
     /// Gas fee strategy
     pub gas_fee_strategy: GasFeeStrategy,
@@ -823,6 +937,57 @@ pub struct TradeBuyParams {
     pub use_exact_sol_amount: Option<bool>,
     /// Optional upstream receive timestamp (e.g. gRPC recv) in microseconds for latency tracing.
     pub grpc_recv_us: Option<i64>,
+    /// Per-trade override of the client's `use_seed_optimize`. `None` uses the client default.
+    /// Must match how the token account is later looked up (balance reads, sells).
+    pub open_seed_optimize_override: Option<bool>,
+    /// Inline (default) or separate pre-transaction for token-account creation.
+    pub ata_creation: AtaCreationMode,
+    /// Fail with `TradeError::TOO_MANY_ATA_CREATIONS` when the trade would create more token
+    /// accounts than this (see `TradeExecutor::planned_ata_creations`). `None` = no cap.
+    pub max_ata_creations: Option<usize>,
+    /// Optional strategy tag appended as a Memo program instruction (UTF-8, max 256 bytes).
+    pub memo: Option<String>,
+    /// Skip the buy with `TradeError::INSUFFICIENT_LIQUIDITY` when the pool's quote-side reserve
+    /// (quote base units, e.g. lamports) in `extension_params` is below this floor. Meteora DAMM v2
+    /// params carry no reserves, so its quote vault balance is read from chain (one RPC call).
+    pub min_quote_liquidity: Option<u64>,
+    /// Absolute floor on the tokens the buy receives. The quote from the reserves in
+    /// `extension_params` is checked first (below the floor the buy fails with
+    /// `TradeError::BELOW_MIN_TOKENS_OUT` before anything is sent), and the instruction's on-chain
+    /// minimum out is raised to at least this value, so a fill worsened after the quote (e.g. by a
+    /// sandwich front-run) fails on-chain instead of landing under the floor. PumpSwap and Raydium
+    /// AMM v4/CPMM. 最少买入数量（同时写入链上最小输出）。
+    pub min_tokens_out: Option<u64>,
+    /// After a failed buy, close token accounts the SDK created for it that are still empty, so
+    /// their rent is not stranded. Only accounts from a separate creation transaction
+    /// (`ata_creation` = `SeparateTx`/`Auto`) can survive a failed swap; inline creations revert
+    /// with it. On-chain failures are only seen with `wait_tx_confirmed`.
+    pub cleanup_on_failure: bool,
+    /// Compute slippage per trade instead of using `slippage_basis_points`; when set, its result
+    /// replaces `slippage_basis_points`. See [`crate::trading::slippage`].
+    pub slippage_model: Option<Arc<dyn SlippageModel>>,
+    /// Leave out the SDK's `SetComputeUnitLimit`/`SetComputeUnitPrice` instructions. The caller
+    /// then owns the compute budget (e.g. via middleware or its own instructions); without one the
+    /// runtime default limit applies and the gas strategy's `cu_limit`/`cu_price` are ignored.
+    pub skip_compute_budget: bool,
+    /// Price this trade's compute units at this percentile (0-100) of the recent prioritization
+    /// fees paid for writes to the pool, e.g. `90` for a snipe or `50` for a patient trade. The
+    /// estimate replaces the `cu_price` of every `gas_fee_strategy` lane for this call only
+    /// (still capped by `max_cu_price`). `None` keeps the configured `cu_price`. 优先费分位数。
+    pub priority_fee_percentile: Option<u8>,
+    /// Signer paying the rent of token accounts this trade creates (ATA or seed account, WSOL
+    /// included), e.g. a relayer so the user does not fund rent. It co-signs the transaction; the
+    /// payer still owns the accounts and gets the rent back on close. `None` = the payer. 代付租金。
+    pub ata_rent_funder: Option<Arc<Keypair>>,
+    /// Token program of the traded mint, e.g. `TOKEN_PROGRAM_2022`; overrides the program in
+    /// `extension_params`. `None` keeps the params' program (taken from the mint's on-chain owner
+    /// by the RPC loaders).
+    pub mint_token_program: Option<Pubkey>,
+    /// Also compile with the lookup table registered for this pool or protocol in
+    /// [`ALT_REGISTRY`](crate::common::address_lookup::ALT_REGISTRY), fetched once and cached
+    /// with no deactivation or extension check (see `AltRegistry::invalidate`). The SDK registers
+    /// no tables, so this is a no-op until the caller registers one.
+    pub auto_alt: bool,
 }
 
 /// Parameters for executing sell orders across different DEX protocols
@@ -860,16 +1025,26 @@ pub struct TradeSellParams {
     /// variants are not mutually exclusive; durable nonce variants are.
     /// Defaults to false. See `SwapParams.wait_for_all_submits`.
     pub wait_for_all_submits: bool,
-    /// Whether to create output token associated token account
+    /// Create the payer's quote ATA (`output_token_type`): the WSOL ATA for SOL/WSOL, the
+    /// USDC/USD1 ATA otherwise. Never the sold token's ATA.
+    /// Maps to `SwapParams.create_output_mint_ata`.
     pub create_output_token_ata: bool,
-    /// Whether to close output token associated token account after trade
+    /// Close the payer's WSOL ATA after the sell, unwrapping the proceeds to SOL.
+    /// Only WSOL is ever closed (USDC/USD1 ATAs stay open); never touches the sold token's ATA.
+    /// Maps to `SwapParams.close_output_mint_ata`.
     pub close_output_token_ata: bool,
-    /// Whether to close mint token associated token account after trade
+    /// Close the sold token's ATA (`mint`) after the sell, reclaiming its rent. Only succeeds
+    /// when the full balance is sold; never touches the WSOL/quote ATA.
+    /// Maps to `SwapParams.close_input_mint_ata`.
     pub close_mint_token_ata: bool,
     /// Durable nonce information
     pub durable_nonce: Option<DurableNonceInfo>,
-    /// Optional fixed output token amount. On exact-out capable DEXes this uses
-    /// the exact-out instruction and treats `input_token_amount` as max input.
+    /// One durable nonce per SWQOS route, so several independent transactions can land;
+    /// replaces `durable_nonce`. Every landed signature is returned.
+    pub multi_nonce: Option<Arc<MultiNonceConfig>>,
+    /// Exact quote out (minimum quote out on PumpFun/PumpSwap exact-in); `input_token_amount`
+    /// stays the tokens sold / max tokens in. `Some(0)` is rejected, as is any value on Meteora
+    /// DAMM v2 (set `MeteoraDammV2Params::minimum_amount_out` instead).
     pub fixed_output_token_amount: Option<u64>,
     /// Gas fee strategy
     pub gas_fee_strategy: GasFeeStrategy,
@@ -877,6 +1052,104 @@ pub struct TradeSellParams {
     pub simulate: bool,
     /// Optional upstream receive timestamp (e.g. gRPC recv) in microseconds for latency tracing.
     pub grpc_recv_us: Option<i64>,
+    /// Per-trade override of the client's `use_seed_optimize`. `None` uses the client default.
+    /// Must match the derivation used when the tokens were bought.
+    pub open_seed_optimize_override: Option<bool>,
+    /// Inline (default) or separate pre-transaction for token-account creation.
+    pub ata_creation: AtaCreationMode,
+    /// Fail with `TradeError::TOO_MANY_ATA_CREATIONS` when the trade would create more token
+    /// accounts than this (see `TradeExecutor::planned_ata_creations`). `None` = no cap.
+    pub max_ata_creations: Option<usize>,
+    /// Sell through this pool instead of the one in `extension_params` (PumpSwap only).
+    ///
+    /// Lets a position bought in one pool (e.g. WSOL) be sold into another pool for the same
+    /// mint with a different quote (e.g. USDC). This assumes both pools exist on-chain; look the
+    /// second one up with [`find_pool_by_mint_and_quote`]. The pool's quote mint must match
+    /// `output_token_type`, and its params are reloaded via RPC before building.
+    pub pool_override: Option<Pubkey>,
+    /// Optional strategy tag appended as a Memo program instruction (UTF-8, max 256 bytes).
+    pub memo: Option<String>,
+    /// Compute slippage per trade instead of using `slippage_basis_points`; when set, its result
+    /// replaces `slippage_basis_points`. See [`crate::trading::slippage`].
+    pub slippage_model: Option<Arc<dyn SlippageModel>>,
+    /// Leave out the SDK's `SetComputeUnitLimit`/`SetComputeUnitPrice` instructions. The caller
+    /// then owns the compute budget (e.g. via middleware or its own instructions); without one the
+    /// runtime default limit applies and the gas strategy's `cu_limit`/`cu_price` are ignored.
+    pub skip_compute_budget: bool,
+    /// Price this trade's compute units at this percentile (0-100) of the recent prioritization
+    /// fees paid for writes to the pool, e.g. `90` for a snipe or `50` for a patient trade. The
+    /// estimate replaces the `cu_price` of every `gas_fee_strategy` lane for this call only
+    /// (still capped by `max_cu_price`). `None` keeps the configured `cu_price`. 优先费分位数。
+    pub priority_fee_percentile: Option<u8>,
+    /// Signer paying the rent of token accounts this trade creates (ATA or seed account, WSOL
+    /// included), e.g. a relayer so the user does not fund rent. It co-signs the transaction; the
+    /// payer still owns the accounts and gets the rent back on close. `None` = the payer. 代付租金。
+    pub ata_rent_funder: Option<Arc<Keypair>>,
+    /// Token program of the traded mint, e.g. `TOKEN_PROGRAM_2022`; overrides the program in
+    /// `extension_params`. `None` keeps the params' program (taken from the mint's on-chain owner
+    /// by the RPC loaders).
+    pub mint_token_program: Option<Pubkey>,
+    /// Also compile with the lookup table registered for this pool or protocol in
+    /// [`ALT_REGISTRY`](crate::common::address_lookup::ALT_REGISTRY), fetched once and cached
+    /// with no deactivation or extension check (see `AltRegistry::invalidate`). The SDK registers
+    /// no tables, so this is a no-op until the caller registers one.
+    pub auto_alt: bool,
+    /// End the transaction with a close of the payer's WSOL account so the proceeds arrive as
+    /// native SOL in the same transaction, whatever the close flags or middleware did. Requires
+    /// `output_token_type` `SOL` or `WSOL`; implies `create_output_token_ata`.
+    pub receive_native_sol: bool,
+}
+
+/// A buy or a sell, for calls that take either (e.g. [`TradingClient::simulate_with_accounts`]).
+pub enum TradeRequest {
+    Buy(TradeBuyParams),
+    Sell(TradeSellParams),
+}
+
+impl From<TradeBuyParams> for TradeRequest {
+    fn from(params: TradeBuyParams) -> Self {
+        Self::Buy(params)
+    }
+}
+
+impl From<TradeSellParams> for TradeRequest {
+    fn from(params: TradeSellParams) -> Self {
+        Self::Sell(params)
+    }
+}
+
+/// `SwapParams` ATA flags, named by swap direction (input/output mint) rather than by the
+/// buy/sell-facing names on [`TradeBuyParams`] / [`TradeSellParams`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SwapAtaFlags {
+    create_input_mint_ata: bool,
+    close_input_mint_ata: bool,
+    create_output_mint_ata: bool,
+    close_output_mint_ata: bool,
+}
+
+impl TradeBuyParams {
+    /// Buy: input = quote (WSOL/USDC/USD1), output = `mint`.
+    fn swap_ata_flags(&self) -> SwapAtaFlags {
+        SwapAtaFlags {
+            create_input_mint_ata: self.create_input_token_ata,
+            close_input_mint_ata: self.close_input_token_ata,
+            create_output_mint_ata: self.create_mint_ata,
+            close_output_mint_ata: false,
+        }
+    }
+}
+
+impl TradeSellParams {
+    /// Sell: input = `mint`, output = quote (WSOL/USDC/USD1).
+    fn swap_ata_flags(&self) -> SwapAtaFlags {
+        SwapAtaFlags {
+            create_input_mint_ata: false,
+            close_input_mint_ata: self.close_mint_token_ata,
+            create_output_mint_ata: self.create_output_token_ata || self.receive_native_sol,
+            close_output_mint_ata: self.close_output_token_ata,
+        }
+    }
 }
 
 #[inline]
@@ -930,6 +1203,20 @@ impl From<SimpleBuyParams> for TradeBuyParams {
             simulate: params.simulate,
             use_exact_sol_amount,
             grpc_recv_us: params.grpc_recv_us,
+            open_seed_optimize_override: None,
+            ata_creation: AtaCreationMode::Inline,
+            memo: None,
+            min_quote_liquidity: None,
+            max_ata_creations: None,
+            slippage_model: None,
+            cleanup_on_failure: false,
+            skip_compute_budget: false,
+            priority_fee_percentile: None,
+            ata_rent_funder: None,
+            mint_token_program: None,
+            auto_alt: false,
+            multi_nonce: None,
+            min_tokens_out: None,
         }
     }
 }
@@ -965,6 +1252,19 @@ impl From<SimpleSellParams> for TradeSellParams {
             gas_fee_strategy: params.gas_fee_strategy,
             simulate: params.simulate,
             grpc_recv_us: params.grpc_recv_us,
+            pool_override: None,
+            open_seed_optimize_override: None,
+            ata_creation: AtaCreationMode::Inline,
+            memo: None,
+            max_ata_creations: None,
+            slippage_model: None,
+            skip_compute_budget: false,
+            priority_fee_percentile: None,
+            ata_rent_funder: None,
+            mint_token_program: None,
+            auto_alt: false,
+            receive_native_sol: false,
+            multi_nonce: None,
         }
     }
 }
@@ -1004,6 +1304,17 @@ impl TradingClient {
             effective_core_ids,
             log_enabled: true,
             check_min_tip: false,
+            swqos_watchdog: None,
+            cu_learner: None,
+            outcome_sinks: Vec::new(),
+            trade_outcomes: TradeOutcomeBroadcast::default(),
+            reserve_check_tolerance_bps: None,
+            seed_ata_check: false,
+            migration_guard: None,
+            parallel_strategy: ParallelStrategy::RaceAll,
+            rpc_fallback: false,
+            blockhash_guard: None,
+            on_before_send: None,
         }
     }
 
@@ -1031,9 +1342,10 @@ impl TradingClient {
             tokio::spawn(async move {
                 Self::ensure_wsol_ata(&payer_clone, &rpc_clone).await;
             });
-            if sdk_log::sdk_log_enabled() {
-                info!(target: "sol_trade_sdk", "ℹ️ WSOL ATA creation started in background, does not block bot startup");
-            }
+            sdk_log::startup_log(
+                Level::INFO,
+                format_args!("WSOL ATA check started in the background (startup not blocked)"),
+            );
         }
 
         let max_sender_concurrency = infrastructure.max_sender_concurrency;
@@ -1050,6 +1362,17 @@ impl TradingClient {
             effective_core_ids,
             log_enabled: true,
             check_min_tip: false,
+            swqos_watchdog: None,
+            cu_learner: None,
+            outcome_sinks: Vec::new(),
+            trade_outcomes: TradeOutcomeBroadcast::default(),
+            reserve_check_tolerance_bps: None,
+            seed_ata_check: false,
+            migration_guard: None,
+            parallel_strategy: ParallelStrategy::RaceAll,
+            rpc_fallback: false,
+            blockhash_guard: None,
+            on_before_send: None,
         }
     }
 
@@ -1101,29 +1424,27 @@ impl TradingClient {
         );
 
         if rpc.get_account(&wsol_ata).await.is_ok() {
-            if sdk_log::sdk_log_enabled() {
-                info!(target: "sol_trade_sdk", "✅ WSOL ATA already exists: {}", wsol_ata);
-            }
+            sdk_log::startup_log(Level::INFO, format_args!("WSOL ATA exists: {}", wsol_ata));
             return;
         }
 
         let create_ata_ixs = crate::trading::common::wsol_manager::create_wsol_ata(&payer.pubkey());
         if create_ata_ixs.is_empty() {
-            if sdk_log::sdk_log_enabled() {
-                info!(target: "sol_trade_sdk", "ℹ️ WSOL ATA already exists (no need to create)");
-            }
+            sdk_log::startup_log(Level::INFO, format_args!("WSOL ATA exists: {}", wsol_ata));
             return;
         }
 
-        if sdk_log::sdk_log_enabled() {
-            info!(target: "sol_trade_sdk", "🔨 Creating WSOL ATA: {}", wsol_ata);
-        }
+        sdk_log::startup_log(Level::INFO, format_args!("creating WSOL ATA {}", wsol_ata));
         let mut last_error = None;
         for attempt in 1..=MAX_RETRIES {
             if attempt > 1 {
-                if sdk_log::sdk_log_enabled() {
-                    info!(target: "sol_trade_sdk", "🔄 Retrying WSOL ATA creation (attempt {}/{})...", attempt, MAX_RETRIES);
-                }
+                sdk_log::startup_log(
+                    Level::INFO,
+                    format_args!(
+                        "retrying WSOL ATA creation (attempt {}/{})",
+                        attempt, MAX_RETRIES
+                    ),
+                );
                 tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
             }
             match Self::try_create_wsol_ata_once(
@@ -1136,30 +1457,33 @@ impl TradingClient {
             .await
             {
                 Ok(()) => {
-                    if sdk_log::sdk_log_enabled() {
-                        info!(target: "sol_trade_sdk", "✅ WSOL ATA created or already exists");
-                    }
+                    sdk_log::startup_log(Level::INFO, format_args!("WSOL ATA ready: {}", wsol_ata));
                     return;
                 }
                 Err(e) => {
-                    last_error = Some(e.clone());
-                    if attempt < MAX_RETRIES && sdk_log::sdk_log_enabled() {
-                        warn!(target: "sol_trade_sdk", "⚠️ Attempt {} failed: {}", attempt, e);
+                    if attempt < MAX_RETRIES {
+                        sdk_log::startup_log(
+                            Level::WARN,
+                            format_args!("WSOL ATA creation attempt {} failed: {}", attempt, e),
+                        );
                     }
+                    last_error = Some(e);
                 }
             }
         }
 
         if let Some(err) = last_error {
-            if sdk_log::sdk_log_enabled() {
-                error!(target: "sol_trade_sdk", "❌ WSOL ATA creation failed after {} retries: {}", MAX_RETRIES, wsol_ata);
-                error!(target: "sol_trade_sdk", "   Error: {}", err);
-                error!(target: "sol_trade_sdk", "   💡 Possible causes: insufficient SOL, RPC timeout, or fee");
-                error!(target: "sol_trade_sdk", "   🔧 Solutions: fund wallet (e.g. 0.1 SOL), retry, check RPC");
-            }
+            sdk_log::startup_log(
+                Level::ERROR,
+                format_args!(
+                    "WSOL ATA {} creation failed after {} attempts: {} (insufficient SOL, RPC \
+                     timeout or fee; fund the wallet, e.g. 0.1 SOL, check RPC and retry)",
+                    wsol_ata, MAX_RETRIES, err
+                ),
+            );
             std::thread::sleep(std::time::Duration::from_secs(5));
             panic!(
-                "❌ WSOL ATA creation failed and account does not exist: {}. Error: {}",
+                "WSOL ATA creation failed and account does not exist: {}. Error: {}",
                 wsol_ata, err
             );
         }
@@ -1178,14 +1502,47 @@ impl TradingClient {
     /// Returns a configured `SolTradingSDK` instance ready for trading operations
     #[inline]
     pub async fn new(payer: Arc<Keypair>, trade_config: TradeConfig) -> Self {
+        Self::apply_global_config(&trade_config);
+        let infra_config = InfrastructureConfig::from_trade_config(&trade_config);
+        let infrastructure = Arc::new(TradingInfrastructure::new(infra_config).await);
+        Self::from_trade_config(payer, trade_config, infrastructure).await
+    }
+
+    /// Same as [`Self::new`] but trades through `rpc` (custom middleware, retry wrapper,
+    /// [`MockSolanaRpcClient`](crate::common::MockSolanaRpcClient), or one client shared by
+    /// several SDK instances) instead of building one from `trade_config.rpc_url`.
+    /// `trade_config.rpc_client_config` is ignored; SWQOS routes are still created from
+    /// `trade_config`. 注入预先配置好的 RPC 客户端。
+    pub async fn with_rpc_client(
+        payer: Arc<Keypair>,
+        rpc: Arc<SolanaRpcClient>,
+        trade_config: TradeConfig,
+    ) -> Self {
+        Self::apply_global_config(&trade_config);
+        let infra_config = InfrastructureConfig::from_trade_config(&trade_config);
+        let infrastructure =
+            Arc::new(TradingInfrastructure::with_rpc_client(infra_config, rpc).await);
+        Self::from_trade_config(payer, trade_config, infrastructure).await
+    }
+
+    /// Process-wide settings from `trade_config` (logging, caches). Protocol overrides are
+    /// installed separately with `set_protocol_overrides`.
+    fn apply_global_config(trade_config: &TradeConfig) {
         // 设置 SDK 全局日志开关，后续所有 SDK 内日志（SWQOS/WSOL/耗时等）均受此控制
         sdk_log::set_sdk_log_enabled(trade_config.log_enabled);
+        sdk_log::set_verbose_startup(trade_config.verbose_startup);
+        crate::trading::core::params::pool_cache::POOL_CACHE.set_ttl(trade_config.pool_cache_ttl);
+        crate::swqos::jito::JITO_TIP_ACCOUNT_CACHE.set_ttl(trade_config.jito_tip_account_refresh);
         // 预热高性能时钟，避免首笔交易时触发 3 次 Utc::now() 校准
         let _ = crate::common::clock::now_micros();
-        // Create infrastructure from trade config
-        let infra_config = InfrastructureConfig::from_trade_config(&trade_config);
-        let infrastructure = Arc::new(TradingInfrastructure::new(infra_config).await);
+    }
 
+    /// Wallet setup and client assembly shared by [`Self::new`] and [`Self::with_rpc_client`].
+    async fn from_trade_config(
+        payer: Arc<Keypair>,
+        trade_config: TradeConfig,
+        infrastructure: Arc<TradingInfrastructure>,
+    ) -> Self {
         // Initialize wallet-specific caches
         crate::common::fast_fn::fast_init(&payer.pubkey());
 
@@ -1207,12 +1564,13 @@ impl TradingClient {
             .unwrap_or(0);
             if balance >= MIN_SOL_FOR_WSOL_ATA_LAMPORTS {
                 Self::ensure_wsol_ata(&payer, &infrastructure.rpc).await;
-            } else if sdk_log::sdk_log_enabled() {
-                info!(
-                    target: "sol_trade_sdk",
-                    "⏭️ 跳过创建 WSOL ATA：钱包 SOL 不足（当前 {} lamports，需要至少 {}）",
-                    balance,
-                    MIN_SOL_FOR_WSOL_ATA_LAMPORTS
+            } else {
+                sdk_log::startup_log(
+                    Level::INFO,
+                    format_args!(
+                        "skipping WSOL ATA creation: wallet has {} lamports, needs at least {}",
+                        balance, MIN_SOL_FOR_WSOL_ATA_LAMPORTS
+                    ),
                 );
             }
         }
@@ -1229,6 +1587,17 @@ impl TradingClient {
             effective_core_ids: infrastructure.effective_core_ids.clone(),
             log_enabled: trade_config.log_enabled,
             check_min_tip: trade_config.check_min_tip,
+            swqos_watchdog: None,
+            cu_learner: None,
+            outcome_sinks: Vec::new(),
+            trade_outcomes: TradeOutcomeBroadcast::default(),
+            reserve_check_tolerance_bps: None,
+            seed_ata_check: false,
+            migration_guard: None,
+            parallel_strategy: ParallelStrategy::RaceAll,
+            rpc_fallback: false,
+            blockhash_guard: None,
+            on_before_send: None,
         };
 
         let mut current = INSTANCE.lock();
@@ -1237,6 +1606,57 @@ impl TradingClient {
         instance
     }
 
+    /// [`Self::new`] followed by [`Self::await_warm`]: returns only once the rent cache, the RPC
+    /// connection and every SWQOS route are ready, so the first trade pays no warm-up latency.
+    /// 预热完成后才返回，失败或超时返回错误。
+    pub async fn new_warmed(
+        payer: Arc<Keypair>,
+        trade_config: TradeConfig,
+        timeout: std::time::Duration,
+    ) -> Result<Self, anyhow::Error> {
+        let client = Self::new(payer, trade_config).await;
+        client.await_warm(timeout).await?;
+        Ok(client)
+    }
+
+    /// Wait until this client is warm, failing after `timeout`:
+    /// - the rent cache holds the on-chain rent (not the fallback `new` uses when RPC is slow),
+    /// - a latest blockhash was fetched (returned, and recorded by the blockhash guard if set),
+    /// - every configured, non-blacklisted SWQOS route has a connected client.
+    pub async fn await_warm(
+        &self,
+        timeout: std::time::Duration,
+    ) -> Result<solana_hash::Hash, anyhow::Error> {
+        tokio::time::timeout(timeout, self.warm()).await.map_err(|_| {
+            anyhow::anyhow!("client warm-up did not finish within {} ms", timeout.as_millis())
+        })?
+    }
+
+    async fn warm(&self) -> Result<solana_hash::Hash, anyhow::Error> {
+        let rpc = &self.infrastructure.rpc;
+        crate::common::seed::update_rents(rpc)
+            .await
+            .map_err(|e| anyhow::anyhow!("rent cache warm-up failed: {}", e))?;
+        let blockhash = rpc
+            .get_latest_blockhash()
+            .await
+            .map_err(|e| anyhow::anyhow!("blockhash warm-up failed: {}", e))?;
+        if let Some(guard) = &self.blockhash_guard {
+            guard.observe(blockhash);
+        }
+        let config = &self.infrastructure.config;
+        let missing = missing_swqos_routes(
+            &config.rpc_url,
+            &config.swqos_configs,
+            &self.infrastructure.swqos_clients,
+        );
+        if !missing.is_empty() {
+            let labels: Vec<&str> = missing.iter().map(|t| t.as_str()).collect();
+            return Err(anyhow::anyhow!("SWQOS routes not connected: {}", labels.join(", ")));
+        }
+        Ok(blockhash)
+    }
+
     /// Adds a middleware manager to the SolanaTrade instance
     ///
     /// Middleware managers can be used to implement custom logic that runs before or after trading operations,
@@ -1252,78 +1672,623 @@ impl TradingClient {
         self
     }
 
-    /// **Advanced.** Use dedicated OS threads for sender pool (and optionally pin to cores).  
-    /// By default the SDK uses a shared tokio pool; this can reduce scheduling contention when sending many txs.  
-    /// Concurrency and core count are capped internally (≤ max submit lanes, ≤ 2/3 of CPU cores).
-    /// - `None`: keep default (shared tokio pool).  
-    /// - `Some(vec![])`: dedicated threads with default count, no core pinning.  
-    /// - `Some(indices)`: dedicated threads pinned to those core indices (trimmed to cap).  
-    ///  
-    /// **Latency note:** If a core is busy with other work (node, bot), SWQOS submit on that core can be delayed.  
-    /// For lowest latency, pass core indices that are *reserved* for SWQOS (do not run other CPU-heavy work on those cores).
-    pub fn with_dedicated_sender_threads(mut self, core_indices: Option<Vec<usize>>) -> Self {
-        match core_indices {
-            None => {
-                self.use_dedicated_sender_threads = false;
-                self.sender_thread_cores = None;
-            }
-            Some(v) if v.is_empty() => {
-                self.use_dedicated_sender_threads = true;
-                self.sender_thread_cores = None;
+    /// Track which SWQOS route actually lands each confirmed trade and temporarily skip routes
+    /// whose landing rate falls below `config.min_landing_rate`. Only trades with
+    /// `wait_tx_confirmed = true` feed the watchdog; the RPC `Default` route is never skipped.
+    /// 记录各通道落地率，长期不落地的通道冷却期内跳过。
+    pub fn with_swqos_watchdog(mut self, config: SwqosWatchdogConfig) -> Self {
+        self.swqos_watchdog = Some(Arc::new(SwqosWatchdog::new(config)));
+        self
+    }
+
+    /// Per-endpoint landing stats; empty when no watchdog is attached.
+    pub fn swqos_endpoint_stats(&self) -> Vec<SwqosEndpointStats> {
+        self.swqos_watchdog.as_ref().map(|w| w.stats()).unwrap_or_default()
+    }
+
+    /// Learn compute units per protocol and direction from simulations and confirmed trades
+    /// (`wait_tx_confirmed = true`; one background `getTransaction` per landing). With
+    /// `config.auto_apply`, routes whose `cu_limit` is 0 use the learned limit.
+    /// 按协议/方向学习 CU 消耗，可自动填充未设置的 cu_limit。
+    pub fn with_cu_learner(mut self, config: CuLearnerConfig) -> Self {
+        self.cu_learner = Some(Arc::new(CuLearner::new(config)));
+        self
+    }
+
+    /// Learned `cu_limit` for `dex_type` buys/sells; `None` without a learner or enough samples.
+    pub fn suggested_cu_limit(&self, dex_type: DexType, is_buy: bool) -> Option<u32> {
+        self.cu_learner.as_ref().and_then(|l| l.suggested_limit(dex_type, is_buy))
+    }
+
+    /// Pre-fetch a pool and its vaults/mints/config a few hundred ms before an expected trade so
+    /// the RPC node has them hot, and fill SDK caches read on the build path (PumpSwap fee
+    /// config). With `TradeConfig::pool_cache_ttl` set, PumpSwap, Raydium CPMM and Meteora DAMM
+    /// v2 pools are also decoded into the pool cache, so loading their params with
+    /// `from_pool_address_by_rpc` within the TTL makes no RPC call. Pools are recognized by owner,
+    /// including programs replaced with `set_protocol_overrides`. Returns the number of accounts
+    /// fetched. There is no built-in pool watcher; call this from your own watcher when a target
+    /// pool is seen. 触发交易前预热池子相关账户，并写入池子参数缓存。
+    pub async fn warm_pool(&self, pool: Pubkey) -> Result<usize, anyhow::Error> {
+        crate::trading::common::pool_warm::warm_pool_accounts(&self.infrastructure.rpc, &pool).await
+    }
+
+    /// Before each buy/sell, fetch the pool/curve from RPC and abort with
+    /// `TradeError::RESERVE_MISMATCH` when the reserves in `extension_params` differ from chain
+    /// by more than `tolerance_bps` (either side). Adds RPC round trips to the hot path; meant for
+    /// event-driven flows where reserves can arrive stale or zeroed. Meteora DAMM v2 is not checked.
+    /// 交易前校验事件储备量与链上是否一致。
+    pub fn with_reserve_check(mut self, tolerance_bps: u64) -> Self {
+        self.reserve_check_tolerance_bps = Some(tolerance_bps);
+        self
+    }
+
+    /// Before each seed-optimized sell, check the seed-derived token account exists; when it does
+    /// not but the standard ATA does (tokens bought without seed optimization), fail with
+    /// `TradeError::SEED_ATA_MISMATCH` instead of a generic "account not found". Adds one
+    /// `getMultipleAccounts` call per such sell. 卖出前检查 seed 账户，避免派生方式不一致。
+    pub fn with_seed_ata_check(mut self, enabled: bool) -> Self {
+        self.seed_ata_check = enabled;
+        self
+    }
+
+    /// Fan submits out with `strategy` instead of racing every SWQOS route at once, e.g.
+    /// `ParallelStrategy::Staggered { delay }` to try the first (cheapest) route and only tip the
+    /// others when it has not landed within `delay`. `SwqosSubmitTiming::wave` shows which wave won.
+    pub fn with_parallel_strategy(mut self, strategy: ParallelStrategy) -> Self {
+        self.parallel_strategy = strategy;
+        self
+    }
+
+    /// When every SWQOS submission fails (rejected or timed out, none landed), resend the
+    /// already-built transaction once through the plain RPC client. Reusing the same signed
+    /// transaction means a relay that did forward it cannot cause a double fill.
+    /// `TradeOutcome::rpc_fallback` reports when this path was taken. SWQOS 全部失败时回退 RPC。
+    pub fn with_rpc_fallback(mut self, enabled: bool) -> Self {
+        self.rpc_fallback = enabled;
+        self
+    }
+
+    /// Before each buy/sell, replace a `recent_blockhash` that is older than `max_age` or no
+    /// longer valid (`isBlockhashValid`) with the latest one. Trades using a durable nonce are
+    /// left alone. `TradeOutcome::blockhash` reports the hash actually used. 过期 blockhash 自动刷新。
+    pub fn with_blockhash_refresh(mut self, config: BlockhashRefreshConfig) -> Self {
+        self.blockhash_guard = Some(Arc::new(BlockhashGuard::new(config)));
+        self
+    }
+
+    /// Inspect every fully assembled, signed transaction (all accounts, compute budget, tip)
+    /// after it is built and before it goes to a SWQOS route; return `Err` to abort that submit
+    /// (reported as `TradeError::SEND_VETOED`, never as landed). Runs once per route, since each
+    /// route signs its own tip. Not run for simulations or the separate ATA pre-transaction.
+    /// 发送前最终交易检查，可否决发送。
+    pub fn with_on_before_send<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&solana_sdk::transaction::VersionedTransaction) -> Result<(), TradeError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.on_before_send = Some(Arc::new(hook));
+        self
+    }
+
+    async fn refresh_blockhash_if_enabled(
+        &self,
+        recent_blockhash: Option<Hash>,
+        durable_nonce: Option<&DurableNonceInfo>,
+    ) -> Result<Option<Hash>, anyhow::Error> {
+        match (&self.blockhash_guard, recent_blockhash) {
+            (Some(guard), Some(hash)) if durable_nonce.is_none() => {
+                Ok(Some(guard.ensure_fresh(&self.infrastructure.rpc, hash).await?))
             }
-            Some(v) => {
-                self.use_dedicated_sender_threads = true;
-                let cap = v.len().min(self.max_sender_concurrency);
-                self.sender_thread_cores =
-                    Some(Arc::new(if cap < v.len() { v[..cap].to_vec() } else { v }));
+            _ => Ok(recent_blockhash),
+        }
+    }
+
+    /// Append the [`ALT_REGISTRY`](crate::common::address_lookup::ALT_REGISTRY) table for this
+    /// pool/protocol (keyed by the overridden program id, if any) unless the caller already passed
+    /// it. A failed fetch is logged and the trade goes out without it.
+    async fn attach_auto_alt(
+        &self,
+        dex_type: DexType,
+        protocol_params: &DexParamEnum,
+        tables: &mut Vec<AddressLookupTableAccount>,
+    ) {
+        let registry = &crate::common::address_lookup::ALT_REGISTRY;
+        let pool = protocol_params.pool_address();
+        let program = crate::trading::common::protocol_overrides::program_id(dex_type);
+        match registry.resolve(&self.infrastructure.rpc, pool.as_ref(), &program).await {
+            Ok(Some(table)) if !tables.iter().any(|t| t.key == table.key) => tables.push(table),
+            Ok(_) => {}
+            Err(e) => {
+                if sdk_log::sdk_log_enabled() {
+                    eprintln!(" [SDK] auto_alt: lookup table fetch failed, sending without: {}", e);
+                }
             }
         }
-        if self.use_dedicated_sender_threads {
-            crate::trading::core::async_executor::warm_dedicated_sender_pool(
-                self.sender_thread_cores.as_ref().map(|v| v.as_slice()),
-                self.max_sender_concurrency,
-            );
+    }
+
+    /// Before each PumpFun buy/sell, read the bonding curve and, when it is complete (the token
+    /// graduated to PumpSwap), either trade the token's PumpSwap pool instead
+    /// (`auto_switch_protocol`, one extra pool lookup) or fail with
+    /// `TradeError::PROTOCOL_MIGRATED`. Adds one RPC read to every PumpFun trade.
+    /// 防毕业：交易前确认仍在内盘，否则切换到 PumpSwap 或报错。
+    pub fn with_migration_guard(mut self, auto_switch_protocol: bool) -> Self {
+        self.migration_guard = Some(auto_switch_protocol);
+        self
+    }
+
+    async fn guard_migration_if_enabled(
+        &self,
+        dex_type: &mut DexType,
+        protocol_params: DexParamEnum,
+        mint: &Pubkey,
+    ) -> Result<DexParamEnum, anyhow::Error> {
+        let Some(auto_switch_protocol) = self.migration_guard else {
+            return Ok(protocol_params);
+        };
+        let rpc = &self.infrastructure.rpc;
+        if *dex_type != DexType::PumpFun
+            || !crate::instruction::utils::pumpfun::is_graduated(rpc, mint).await?
+        {
+            return Ok(protocol_params);
+        }
+        if !auto_switch_protocol {
+            return Err(TradeError::protocol_migrated(
+                mint,
+                DexType::PumpFun.as_str(),
+                DexType::PumpSwap.as_str(),
+            )
+            .into());
+        }
+        let pool = PumpSwapParams::from_mint_by_rpc(rpc, mint).await?;
+        *dex_type = DexType::PumpSwap;
+        Ok(DexParamEnum::PumpSwap(pool))
+    }
+
+    /// Copy of `strategy` with `trade_type`'s `cu_price` set to the `percentile` of recent
+    /// prioritization fees for writes to the params' pool.
+    async fn priced_at_percentile(
+        &self,
+        strategy: &GasFeeStrategy,
+        trade_type: TradeType,
+        protocol_params: &DexParamEnum,
+        percentile: u8,
+    ) -> Result<GasFeeStrategy, anyhow::Error> {
+        let accounts: Vec<Pubkey> = protocol_params.pool_address().into_iter().collect();
+        let cu_price = crate::common::priority_fee::estimate_priority_fee(
+            &self.infrastructure.rpc,
+            &accounts,
+            percentile,
+        )
+        .await?;
+        Ok(strategy.with_cu_price(trade_type, cu_price))
+    }
+
+    async fn check_reserves_if_enabled(
+        &self,
+        protocol_params: &DexParamEnum,
+        mint: &Pubkey,
+    ) -> Result<(), anyhow::Error> {
+        match self.reserve_check_tolerance_bps {
+            Some(tolerance_bps) => {
+                crate::trading::core::reserve_check::verify_reserves(
+                    &self.infrastructure.rpc,
+                    protocol_params,
+                    mint,
+                    tolerance_bps,
+                )
+                .await
+            }
+            None => Ok(()),
         }
+    }
+
+    /// Deliver every buy/sell outcome to `sink` (the sink must not block).
+    /// For confirmed trades the SDK first reads the landed transaction (one `getTransaction`)
+    /// to fill `fee_lamports` / `units_consumed` on a background task, so those outcomes reach
+    /// the sink shortly after the trade call returns; this only happens while a sink is attached.
+    pub fn with_outcome_sink(mut self, sink: Arc<dyn TradeOutcomeSink>) -> Self {
+        self.outcome_sinks.push(sink);
         self
     }
 
-    /// Gets the RPC client instance for direct Solana blockchain interactions
-    ///
-    /// This provides access to the underlying Solana RPC client that can be used
-    /// for custom blockchain operations outside of the trading framework.
-    ///
-    /// # Returns
-    /// Returns a reference to the Arc-wrapped SolanaRpcClient instance
-    pub fn get_rpc(&self) -> &Arc<SolanaRpcClient> {
-        &self.infrastructure.rpc
+    /// Stream of every buy/sell outcome from this client and its clones, landed or failed, with
+    /// the same fields the outcome sinks get. Includes [`Self::pumpfun_create_and_buy`] and
+    /// [`Self::fire_prepared`]; a [`PreparedTrade`](crate::trading::PreparedTrade) fired directly
+    /// with `PreparedTrade::fire` is not seen by the client and reports nothing. Bounded: see [`TradeOutcomeBroadcast`] for how a
+    /// lagging receiver is handled. 订阅本客户端的全部交易结果。
+    pub fn subscribe_trades(
+        &self,
+    ) -> tokio::sync::broadcast::Receiver<crate::trading::TradeOutcome> {
+        self.trade_outcomes.subscribe()
     }
 
-    /// Gets the current globally shared SolanaTrade instance
-    ///
-    /// This provides access to the singleton instance that was created with `new()`.
-    /// Useful for accessing the trading instance from different parts of the application.
-    ///
-    /// # Returns
-    /// Returns the Arc-wrapped SolanaTrade instance
-    ///
-    /// # Panics
-    /// Panics if no instance has been initialized yet. Make sure to call `new()` first.
-    pub fn get_instance() -> Arc<Self> {
-        let instance = INSTANCE.lock();
-        instance
-            .as_ref()
-            .expect("SolanaTrade instance not initialized. Please call new() first.")
-            .clone()
+    /// Wait for many in-flight signatures at once (e.g. trades sent with
+    /// `wait_tx_confirmed: false`): polls `getSignatureStatuses` in batches of up to 256 until
+    /// each is confirmed, failed or `timeout` elapses. Every signature gets an entry; see
+    /// [`ConfirmStatus`](crate::swqos::common::ConfirmStatus). 批量轮询交易确认。
+    pub async fn confirm_batch(
+        &self,
+        signatures: Vec<Signature>,
+        timeout: std::time::Duration,
+    ) -> HashMap<Signature, crate::swqos::common::ConfirmStatus> {
+        crate::swqos::common::confirm_signatures(&self.infrastructure.rpc, &signatures, timeout)
+            .await
     }
 
-    /// Execute a buy order for a specified token
-    ///
-    /// 🔧 修复：返回Vec<Signature>支持多SWQOS并发交易
-    /// - bool: 是否至少有一个交易成功
-    /// - Vec<Signature>: 所有提交的交易签名（按SWQOS顺序）
-    /// - Option<TradeError>: 最后一个错误（如果全部失败）
-    ///
-    /// # Arguments
+    /// Airdrop `lamports` to the payer and wait for confirmation, for running the examples and
+    /// integration tests on devnet without manual funding. Errors on mainnet-beta; see
+    /// [`request_airdrop`](crate::trading::common::utils::request_airdrop). 测试网空投到 payer。
+    pub async fn request_airdrop(&self, lamports: u64) -> Result<Signature, anyhow::Error> {
+        crate::trading::common::utils::request_airdrop(
+            &self.infrastructure.rpc,
+            &self.payer.pubkey(),
+            lamports,
+            AIRDROP_CONFIRM_TIMEOUT,
+        )
+        .await
+    }
+
+    /// POST every buy/sell outcome as JSON to `url` from a background task (bounded queue,
+    /// fire-and-forget). Must be called inside a tokio runtime. 交易结果推送到 webhook。
+    pub fn with_webhook(self, url: impl Into<String>) -> Self {
+        self.with_outcome_sink(Arc::new(crate::trading::WebhookSink::new(url)))
+    }
+
+    /// Dry-run a buy: expected token output, fees, tip and ATA rent, and the resulting
+    /// balances (current − spend + output). Nothing is built or sent; reads balances over RPC.
+    /// 买入预演：估算花费与余额变化，不发送交易。
+    pub async fn preview(
+        &self,
+        params: &TradeBuyParams,
+    ) -> Result<crate::trading::BalanceImpact, anyhow::Error> {
+        let rpc = &self.infrastructure.rpc;
+        let payer = self.payer.pubkey();
+        let use_seed = params.open_seed_optimize_override.unwrap_or(self.use_seed_optimize);
+        let input_mint = quote_token_mint(&params.input_token_type);
+        let quote_mint =
+            if input_mint == SOL_TOKEN_ACCOUNT { WSOL_TOKEN_ACCOUNT } else { input_mint };
+
+        let expected_token_out = params.fixed_output_token_amount.or_else(|| {
+            params.extension_params.expected_buy_output(&quote_mint, params.input_token_amount)
+        });
+        // an `ata_rent_funder` other than the payer co-signs whenever the buy creates an account
+        let signatures =
+            1 + u8::from(params.ata_rent_funder.as_ref().is_some_and(|f| f.pubkey() != payer));
+        let base_fee_lamports =
+            crate::trading::preview::BASE_FEE_CACHE.base_fee(rpc, &payer, signatures).await;
+        let (network_fee_lamports, tip_lamports) = crate::trading::preview::route_costs(
+            &params.gas_fee_strategy.get_strategies(TradeType::Buy),
+            base_fee_lamports,
+        );
+
+        let sol_balance = crate::trading::common::utils::get_sol_balance(rpc, &payer).await?;
+        let quote_balance = if input_mint == SOL_TOKEN_ACCOUNT {
+            None
+        } else {
+            // A missing quote ATA reads as an error; count it as empty.
+            Some(
+                crate::trading::common::utils::get_token_balance_with_options(
+                    rpc,
+                    &payer,
+                    &quote_mint,
+                    &crate::constants::TOKEN_PROGRAM,
+                    use_seed,
+                )
+                .await
+                .unwrap_or(0),
+            )
+        };
+        let token_program =
+            crate::trading::common::utils::resolve_token_program(rpc, &params.mint).await?;
+        let token_balance = crate::trading::common::utils::get_token_balance_with_options(
+            rpc,
+            &payer,
+            &params.mint,
+            &token_program,
+            use_seed,
+        )
+        .await
+        .ok();
+        let ata_rent_lamports = if params.create_mint_ata && token_balance.is_none() {
+            crate::common::seed::token_account_rent(&token_program)
+        } else {
+            0
+        };
+
+        Ok(crate::trading::BalanceImpact {
+            quote_spend: params.input_token_amount,
+            expected_token_out,
+            network_fee_lamports,
+            tip_lamports,
+            ata_rent_lamports,
+            sol_balance,
+            quote_balance,
+            token_balance: token_balance.unwrap_or(0),
+        })
+    }
+
+    /// Probe executable depth before a large buy: simulate `probe_fraction` (0, 1] of
+    /// `params.input_token_amount`, read the tokens the payer's token account would receive and
+    /// extrapolate the full size along the pool curve. Nothing is sent; costs the
+    /// [`Self::simulate_with_accounts`] round trips plus one mint lookup. 小额模拟探测深度。
+    pub async fn probe_depth(
+        &self,
+        params: &TradeBuyParams,
+        probe_fraction: f64,
+    ) -> Result<crate::trading::DepthEstimate, anyhow::Error> {
+        if !(probe_fraction > 0.0 && probe_fraction <= 1.0) {
+            return Err(anyhow::anyhow!(
+                "probe_fraction must be in (0, 1], got {}",
+                probe_fraction
+            ));
+        }
+        let full_input = params.input_token_amount;
+        let probe_input = ((full_input as f64 * probe_fraction) as u64).max(1);
+        let mut probe = params.clone();
+        probe.input_token_amount = probe_input;
+        probe.fixed_output_token_amount = None;
+
+        let use_seed = params.open_seed_optimize_override.unwrap_or(self.use_seed_optimize);
+        let token_program = crate::trading::common::utils::resolve_token_program(
+            &self.infrastructure.rpc,
+            &params.mint,
+        )
+        .await?;
+        let token_account =
+            crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed(
+                &self.payer.pubkey(),
+                &params.mint,
+                &token_program,
+                use_seed,
+            );
+        let report = self.simulate_with_accounts(probe, vec![token_account]).await?;
+        if !report.success {
+            return Err(anyhow::anyhow!(
+                "probe simulation failed: {}",
+                report.error.unwrap_or_default()
+            ));
+        }
+        let probe_output = report
+            .accounts
+            .first()
+            .and_then(|delta| delta.token_delta())
+            .and_then(|delta| u64::try_from(delta).ok())
+            .unwrap_or(0);
+
+        let input_mint = quote_token_mint(&params.input_token_type);
+        let quote_mint =
+            if input_mint == SOL_TOKEN_ACCOUNT { WSOL_TOKEN_ACCOUNT } else { input_mint };
+        let quote = |amount| params.extension_params.expected_buy_output(&quote_mint, amount);
+        crate::trading::DepthEstimate::from_probe(
+            probe_input,
+            probe_output,
+            full_input,
+            quote(probe_input),
+            quote(full_input),
+        )
+        .ok_or_else(|| anyhow::anyhow!("probe of {} received no tokens", probe_input))
+    }
+
+    /// Simulate a buy or sell and report how each of `accounts` would change: lamports and, for
+    /// token accounts, the token amount. Forces `simulate`, so nothing is sent. Costs one
+    /// `getMultipleAccounts` for the pre-states plus the simulation. 模拟并返回账户余额变化。
+    pub async fn simulate_with_accounts(
+        &self,
+        params: impl Into<TradeRequest>,
+        accounts: Vec<Pubkey>,
+    ) -> Result<SimulationReport, anyhow::Error> {
+        let pre = self.infrastructure.rpc.get_multiple_accounts(&accounts).await?;
+        let capture = Arc::new(SimulationCapture::new(accounts));
+        let (success, _, error, _) = match params.into() {
+            TradeRequest::Buy(mut params) => {
+                params.simulate = true;
+                self.buy_inner(params, Some(capture.clone())).await?
+            }
+            TradeRequest::Sell(mut params) => {
+                params.simulate = true;
+                self.sell_inner(params, Some(capture.clone())).await?
+            }
+        };
+        Ok(capture.report(&pre, success, error.map(|e| e.to_string())))
+    }
+
+    /// Fill `outcome.fill` with the amounts the landed transaction actually moved for this
+    /// payer (token and quote balance changes, decimals included) so
+    /// [`TradeOutcome::summary`](crate::trading::TradeOutcome::summary) can print the effective
+    /// price. Costs one `getTransaction` per signature until a successful one is found; returns
+    /// `false` when none of the signatures has landed successfully (yet).
+    pub async fn enrich_outcome(
+        &self,
+        outcome: &mut crate::trading::TradeOutcome,
+    ) -> Result<bool, anyhow::Error> {
+        let mint = Pubkey::from_str(&outcome.mint)?;
+        for sig in &outcome.signatures {
+            let sig = Signature::from_str(sig)?;
+            let config = crate::trading::outcome::landed_transaction_config(
+                solana_transaction_status::UiTransactionEncoding::JsonParsed,
+            );
+            let Ok(tx) = self.infrastructure.rpc.get_transaction_with_config(&sig, config).await
+            else {
+                continue;
+            };
+            if let Some(meta) = tx.transaction.meta.filter(|m| m.err.is_none()) {
+                let (fee, units) = crate::trading::outcome::cost_from_meta(&meta);
+                outcome.fee_lamports = Some(fee);
+                outcome.units_consumed = units;
+                outcome.fill =
+                    crate::trading::outcome::fill_from_meta(&meta, &self.payer.pubkey(), &mint);
+                return Ok(outcome.fill.is_some());
+            }
+        }
+        Ok(false)
+    }
+
+    async fn emit_outcome(
+        &self,
+        ctx: &TradeOutcomeContext,
+        result: &Result<
+            (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
+            anyhow::Error,
+        >,
+        via_rpc_fallback: bool,
+    ) {
+        if self.outcome_sinks.is_empty() && !self.trade_outcomes.has_subscribers() {
+            return;
+        }
+        let mut outcome = outcome_from_result(ctx, result);
+        outcome.rpc_fallback = via_rpc_fallback;
+        // landed, or failed on-chain (program error codes are below the SDK range)
+        let landed_on_chain = outcome.landed == Some(true)
+            || outcome
+                .error_code
+                .is_some_and(|c| c > 0 && c != 500 && c < TradeError::SDK_ERROR_CODE_BASE);
+        if ctx.wait_tx_confirmed && !ctx.simulate && landed_on_chain {
+            // the trade result must not wait on a `getTransaction` round trip
+            let rpc = self.infrastructure.rpc.clone();
+            let sinks = self.outcome_sinks.clone();
+            let broadcast = self.trade_outcomes.clone();
+            tokio::spawn(async move {
+                fill_landed_cost(&rpc, &mut outcome).await;
+                deliver_outcome(&sinks, &broadcast, &outcome);
+            });
+            return;
+        }
+        deliver_outcome(&self.outcome_sinks, &self.trade_outcomes, &outcome);
+    }
+
+    /// Spawn a trade future on the tokio runtime with a panic boundary. A panic inside `future`
+    /// (e.g. an `unwrap` in a calc path) is delivered as `TradeError::PANICKED` on the returned
+    /// channel instead of silently killing the task; other errors are converted with
+    /// `TradeError::from`. The task still runs if the receiver is dropped.
+    ///
+    /// Only under `panic = "unwind"` (see [`Self::catches_panics`]): this crate's release
+    /// profile sets `panic = "abort"`, where a panic aborts the process before any boundary runs.
+    /// 仅在 panic = "unwind" 时捕获 panic；release 配置为 abort 时进程直接中止。
+    pub fn spawn_trade<F, T>(future: F) -> tokio::sync::oneshot::Receiver<Result<T, TradeError>>
+    where
+        F: std::future::Future<Output = anyhow::Result<T>> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let task = tokio::spawn(future);
+        tokio::spawn(async move {
+            let result = match task.await {
+                Ok(result) => result.map_err(TradeError::from),
+                Err(err) if !err.is_panic() => {
+                    Err(TradeError::panicked(format!("trade task did not complete: {}", err)))
+                }
+                Err(err) => {
+                    let payload = err.into_panic();
+                    let message = payload
+                        .downcast_ref::<&str>()
+                        .map(|s| s.to_string())
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "non-string panic payload".to_string());
+                    if crate::common::sdk_log::sdk_log_enabled() {
+                        eprintln!(" [SDK] trade task panicked: {}", message);
+                    }
+                    Err(TradeError::panicked(format!("trade task panicked: {}", message)))
+                }
+            };
+            let _ = tx.send(result);
+        });
+        rx
+    }
+
+    /// Whether [`Self::spawn_trade`] can turn a panic into `TradeError::PANICKED`: `false` when
+    /// built with `panic = "abort"`. 当前构建是否能捕获 panic。
+    pub const fn catches_panics() -> bool {
+        cfg!(panic = "unwind")
+    }
+
+    /// **Advanced.** Use dedicated OS threads for sender pool (and optionally pin to cores).  
+    /// By default the SDK uses a shared tokio pool; this can reduce scheduling contention when sending many txs.  
+    /// Concurrency and core count are capped internally (≤ max submit lanes, ≤ 2/3 of CPU cores).
+    /// - `None`: keep default (shared tokio pool).  
+    /// - `Some(vec![])`: dedicated threads with default count, no core pinning.  
+    /// - `Some(indices)`: dedicated threads pinned to those core indices (trimmed to cap).  
+    ///  
+    /// **Latency note:** If a core is busy with other work (node, bot), SWQOS submit on that core can be delayed.  
+    /// For lowest latency, pass core indices that are *reserved* for SWQOS (do not run other CPU-heavy work on those cores).
+    pub fn with_dedicated_sender_threads(mut self, core_indices: Option<Vec<usize>>) -> Self {
+        match core_indices {
+            None => {
+                self.use_dedicated_sender_threads = false;
+                self.sender_thread_cores = None;
+            }
+            Some(v) if v.is_empty() => {
+                self.use_dedicated_sender_threads = true;
+                self.sender_thread_cores = None;
+            }
+            Some(v) => {
+                self.use_dedicated_sender_threads = true;
+                let cap = v.len().min(self.max_sender_concurrency);
+                self.sender_thread_cores =
+                    Some(Arc::new(if cap < v.len() { v[..cap].to_vec() } else { v }));
+            }
+        }
+        if self.use_dedicated_sender_threads {
+            crate::trading::core::async_executor::warm_dedicated_sender_pool(
+                self.sender_thread_cores.as_ref().map(|v| v.as_slice()),
+                self.max_sender_concurrency,
+            );
+        }
+        self
+    }
+
+    /// Gets the RPC client instance for direct Solana blockchain interactions
+    ///
+    /// This provides access to the underlying Solana RPC client that can be used
+    /// for custom blockchain operations outside of the trading framework.
+    ///
+    /// # Returns
+    /// Returns a reference to the Arc-wrapped SolanaRpcClient instance
+    pub fn get_rpc(&self) -> &Arc<SolanaRpcClient> {
+        &self.infrastructure.rpc
+    }
+
+    /// Crate version the running binary was built with. 编译时的 SDK 版本。
+    pub const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    /// Program id the SDK targets for each supported protocol, e.g. to confirm a deployed bot
+    /// is built against the current programs after a protocol migration. Reflects program ids
+    /// replaced with [`set_protocol_overrides`](crate::trading::set_protocol_overrides), which
+    /// are process-wide rather than a `TradeConfig` field. 各协议实际使用的程序 ID（已应用覆盖）。
+    pub fn program_ids() -> HashMap<DexType, Pubkey> {
+        TradeFactory::supported_dex_types()
+            .iter()
+            .map(|dex| (*dex, crate::trading::common::protocol_overrides::program_id(*dex)))
+            .collect()
+    }
+
+    /// Gets the current globally shared SolanaTrade instance
+    ///
+    /// This provides access to the singleton instance that was created with `new()`.
+    /// Useful for accessing the trading instance from different parts of the application.
+    ///
+    /// # Returns
+    /// Returns the Arc-wrapped SolanaTrade instance
+    ///
+    /// # Panics
+    /// Panics if no instance has been initialized yet. Make sure to call `new()` first.
+    pub fn get_instance() -> Arc<Self> {
+        let instance = INSTANCE.lock();
+        instance
+            .as_ref()
+            .expect("SolanaTrade instance not initialized. Please call new() first.")
+            .clone()
+    }
+
+    /// Execute a buy order for a specified token
+    ///
+    /// 🔧 修复：返回Vec<Signature>支持多SWQOS并发交易
+    /// - bool: 是否至少有一个交易成功
+    /// - Vec<Signature>: 所有提交的交易签名（按SWQOS顺序）
+    /// - Option<TradeError>: 最后一个错误（如果全部失败）
+    ///
+    /// # Arguments
     ///
     /// * `params` - Buy trade parameters containing all necessary trading configuration
     ///
@@ -1348,15 +2313,69 @@ impl TradingClient {
         (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
         anyhow::Error,
     > {
+        self.buy_inner(params, None).await
+    }
+
+    async fn buy_inner(
+        &self,
+        params: TradeBuyParams,
+        simulation_capture: Option<Arc<SimulationCapture>>,
+    ) -> Result<
+        (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
+        anyhow::Error,
+    > {
+        let (buy_params, outcome_ctx) = self.buy_swap_params(params, simulation_capture).await?;
+        let executor = TradeFactory::create_executor(outcome_ctx.dex_type);
+        let swap_result = executor.swap(buy_params).await;
+        let via_rpc_fallback = matches!(&swap_result, Ok((true, _, _, timings)) if timings.iter().any(|t| t.rpc_fallback));
+        let result = swap_result.map(|(success, sigs, err, timings)| {
+            let legacy_timings = timings
+                .into_iter()
+                .map(|timing| (timing.swqos_type, timing.submit_done_us))
+                .collect();
+            (success, sigs, err.map(TradeError::from), legacy_timings)
+        });
+        self.emit_outcome(&outcome_ctx, &result, via_rpc_fallback).await;
+        result
+    }
+
+    /// Checks and enrichment of a buy request up to the [`SwapParams`] the executor runs, shared
+    /// by [`Self::buy`] and [`Self::prepare_buy`]; also returns the request's outcome context.
+    async fn buy_swap_params(
+        &self,
+        mut params: TradeBuyParams,
+        simulation_capture: Option<Arc<SimulationCapture>>,
+    ) -> Result<(SwapParams, TradeOutcomeContext), anyhow::Error> {
+        if let Some(model) = &params.slippage_model {
+            params.slippage_basis_points = Some(model.slippage_bps(&SlippageContext::new(
+                params.dex_type,
+                true,
+                params.mint,
+                params.input_token_amount,
+                &params.extension_params,
+                params.slippage_basis_points,
+            )));
+        }
         validate_trade_safety(
             "buy",
             params.input_token_amount,
             params.fixed_output_token_amount,
             params.slippage_basis_points,
         )?;
-        if params.recent_blockhash.is_none() && params.durable_nonce.is_none() {
+        if let Some(percentile) = params.priority_fee_percentile {
+            crate::common::priority_fee::validate_percentile(percentile)?;
+        }
+        if params.recent_blockhash.is_none()
+            && params.durable_nonce.is_none()
+            && params.multi_nonce.is_none()
+        {
             return Err(anyhow::anyhow!(
-                "Must provide either recent_blockhash or durable_nonce for buy (required for transaction validity)"
+                "Must provide either recent_blockhash, durable_nonce or multi_nonce for buy (required for transaction validity)"
+            ));
+        }
+        if params.durable_nonce.is_some() && params.multi_nonce.is_some() {
+            return Err(anyhow::anyhow!(
+                "durable_nonce and multi_nonce are mutually exclusive for buy"
             ));
         }
         #[cfg(feature = "perf-trace")]
@@ -1367,7 +2386,9 @@ impl TradingClient {
                 DEFAULT_SLIPPAGE
             );
         }
-        if params.input_token_type == TradeTokenType::USD1 && params.dex_type != DexType::Bonk {
+        if params.input_token_type == TradeTokenType::USD1
+            && !TradeFactory::capabilities(params.dex_type).supports_usd1
+        {
             return Err(anyhow::anyhow!(
                 " Current version only supports USD1 trading on Bonk protocols"
             ));
@@ -1379,16 +2400,53 @@ impl TradingClient {
                 params.dex_type
             ));
         }
-        let input_token_mint = if params.input_token_type == TradeTokenType::SOL {
-            SOL_TOKEN_ACCOUNT
-        } else if params.input_token_type == TradeTokenType::WSOL {
-            WSOL_TOKEN_ACCOUNT
-        } else if params.input_token_type == TradeTokenType::USDC {
-            USDC_TOKEN_ACCOUNT
-        } else {
-            USD1_TOKEN_ACCOUNT
+        let protocol_params = self
+            .guard_migration_if_enabled(&mut params.dex_type, protocol_params, &params.mint)
+            .await?;
+        let input_token_mint = quote_token_mint(&params.input_token_type);
+        if let Some(min) = params.min_quote_liquidity {
+            verify_min_quote_liquidity(
+                &self.infrastructure.rpc,
+                &protocol_params,
+                &input_token_mint,
+                min,
+            )
+            .await?;
+        }
+        self.check_reserves_if_enabled(&protocol_params, &params.mint).await?;
+        if params.auto_alt {
+            self.attach_auto_alt(
+                params.dex_type,
+                &protocol_params,
+                &mut params.address_lookup_table_accounts,
+            )
+            .await;
+        }
+        params.recent_blockhash = self
+            .refresh_blockhash_if_enabled(params.recent_blockhash, params.durable_nonce.as_ref())
+            .await?;
+        let outcome_ctx = TradeOutcomeContext {
+            dex_type: params.dex_type,
+            is_buy: true,
+            mint: params.mint,
+            input_amount: params.input_token_amount,
+            fixed_output_amount: params.fixed_output_token_amount,
+            slippage_basis_points: params.slippage_basis_points,
+            wait_tx_confirmed: params.wait_tx_confirmed,
+            simulate: params.simulate,
+            blockhash: used_blockhash(params.recent_blockhash, params.durable_nonce.as_ref()),
         };
-        let executor = TradeFactory::create_executor(params.dex_type);
+        if let Some(percentile) = params.priority_fee_percentile {
+            params.gas_fee_strategy = self
+                .priced_at_percentile(
+                    &params.gas_fee_strategy,
+                    TradeType::Buy,
+                    &protocol_params,
+                    percentile,
+                )
+                .await?;
+        }
+        let ata_flags = params.swap_ata_flags();
         let buy_params = SwapParams {
             rpc: Some(self.infrastructure.rpc.clone()),
             payer: self.payer.clone(),
@@ -1396,22 +2454,25 @@ impl TradingClient {
             input_mint: input_token_mint,
             output_mint: params.mint,
             input_token_program: None,
-            output_token_program: None,
+            output_token_program: params.mint_token_program,
             input_amount: Some(params.input_token_amount),
             slippage_basis_points: params.slippage_basis_points,
             address_lookup_table_accounts: params.address_lookup_table_accounts,
             recent_blockhash: params.recent_blockhash,
             wait_tx_confirmed: params.wait_tx_confirmed,
             protocol_params,
-            open_seed_optimize: self.use_seed_optimize, // 使用全局seed优化配置
+            // 单笔覆盖优先，否则使用全局seed优化配置
+            open_seed_optimize: params
+                .open_seed_optimize_override
+                .unwrap_or(self.use_seed_optimize),
             swqos_clients: self.infrastructure.swqos_clients.clone(),
             middleware_manager: self.middleware_manager.clone(),
             durable_nonce: params.durable_nonce,
             with_tip: true,
-            create_input_mint_ata: params.create_input_token_ata,
-            close_input_mint_ata: params.close_input_token_ata,
-            create_output_mint_ata: params.create_mint_ata,
-            close_output_mint_ata: false,
+            create_input_mint_ata: ata_flags.create_input_mint_ata,
+            close_input_mint_ata: ata_flags.close_input_mint_ata,
+            create_output_mint_ata: ata_flags.create_output_mint_ata,
+            close_output_mint_ata: ata_flags.close_output_mint_ata,
             fixed_output_amount: params.fixed_output_token_amount,
             gas_fee_strategy: params.gas_fee_strategy,
             simulate: params.simulate,
@@ -1424,19 +2485,256 @@ impl TradingClient {
             check_min_tip: self.check_min_tip,
             grpc_recv_us: params.grpc_recv_us,
             use_exact_sol_amount: params.use_exact_sol_amount,
+            swqos_watchdog: self.swqos_watchdog.clone(),
+            ata_creation: params.ata_creation,
+            memo: params.memo,
+            parallel_strategy: self.parallel_strategy,
+            max_ata_creations: params.max_ata_creations,
+            cu_learner: self.cu_learner.clone(),
+            rpc_fallback: self.rpc_fallback,
+            cleanup_on_failure: params.cleanup_on_failure,
+            skip_compute_budget: params.skip_compute_budget,
+            simulation_capture,
+            ata_rent_funder: params.ata_rent_funder.clone(),
+            receive_native_sol: false,
+            on_before_send: self.on_before_send.clone(),
+            multi_nonce: params.multi_nonce.clone(),
+            min_tokens_out: params.min_tokens_out,
         };
+        Ok((buy_params, outcome_ctx))
+    }
 
-        let swap_result = executor.swap(buy_params).await;
-        let result = swap_result.map(|(success, sigs, err, timings)| {
-            let legacy_timings = timings
-                .into_iter()
-                .map(|timing| (timing.swqos_type, timing.submit_done_us))
-                .collect();
-            (success, sigs, err.map(TradeError::from), legacy_timings)
-        });
+    /// Run a buy request's checks and enrichment like [`Self::buy`], then build and sign it for
+    /// later broadcast with [`Self::fire_prepared`] instead of sending it now
+    /// ([`PreparedTrade::prepare`]). `params.durable_nonce` is required. 预构建并签名买入交易。
+    ///
+    /// [`PreparedTrade::prepare`]: crate::trading::PreparedTrade::prepare
+    pub async fn prepare_buy(
+        &self,
+        params: TradeBuyParams,
+    ) -> Result<crate::trading::PreparedTrade, anyhow::Error> {
+        let (buy_params, _) = self.buy_swap_params(params, None).await?;
+        crate::trading::PreparedTrade::prepare(buy_params).await
+    }
+
+    /// Run a sell request's checks and enrichment like [`Self::sell`], then build and sign it for
+    /// later broadcast with [`Self::fire_prepared`] instead of sending it now
+    /// ([`PreparedTrade::prepare`]). `params.durable_nonce` is required. 预构建并签名卖出交易。
+    ///
+    /// [`PreparedTrade::prepare`]: crate::trading::PreparedTrade::prepare
+    pub async fn prepare_sell(
+        &self,
+        params: TradeSellParams,
+    ) -> Result<crate::trading::PreparedTrade, anyhow::Error> {
+        let (sell_params, _) = self.sell_swap_params(params, None).await?;
+        crate::trading::PreparedTrade::prepare(sell_params).await
+    }
+
+    /// Broadcast `prepared` through this client's SWQOS routes ([`PreparedTrade::fire`]) and
+    /// report it to the outcome sinks and [`Self::subscribe_trades`] like a live buy/sell. The
+    /// send is not confirmed, so the outcome's `landed` is `None`. 触发预签名交易并上报结果。
+    ///
+    /// [`PreparedTrade::fire`]: crate::trading::PreparedTrade::fire
+    pub async fn fire_prepared(
+        &self,
+        prepared: &crate::trading::PreparedTrade,
+    ) -> Result<
+        (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
+        anyhow::Error,
+    > {
+        let result = prepared
+            .fire(&self.infrastructure.swqos_clients)
+            .await
+            .map(|(ok, signatures, err)| (ok, signatures, err.map(TradeError::from), Vec::new()));
+        self.emit_outcome(prepared.outcome_context(), &result, false).await;
         result
     }
 
+    /// Create a Pump.fun coin and buy `buy_sol_amount` lamports of it in the same transaction.
+    ///
+    /// Builds the legacy `create` (SPL Token mint), extends the new bonding curve to the size the
+    /// buy expects, then creates the payer's token account and buys priced off the initial
+    /// reserves. Signs with the payer and the mint, and sends it over RPC (not SWQOS, since
+    /// relays only carry payer-signed transactions). Returns the signature and the new mint.
+    /// The buy is reported to the outcome sinks and [`Self::subscribe_trades`] without waiting
+    /// for confirmation. 发币并在同一笔交易内买入，返回签名与新 mint。
+    pub async fn pumpfun_create_and_buy(
+        &self,
+        mut create_args: crate::trading::core::params::PumpFunCreateArgs,
+        buy_sol_amount: u64,
+        slippage_basis_points: Option<u64>,
+    ) -> Result<(Signature, Pubkey), anyhow::Error> {
+        // Fix the mint first so a failure before the send is still reported against it.
+        let mint_keypair = create_args.mint.take().unwrap_or_else(Keypair::new);
+        let mint = mint_keypair.pubkey();
+        create_args.mint = Some(mint_keypair);
+        let overrides = crate::trading::common::protocol_overrides::protocol_overrides();
+        let built = self
+            .pumpfun_create_and_buy_transaction(
+                create_args,
+                buy_sol_amount,
+                slippage_basis_points,
+                &overrides,
+            )
+            .await;
+        let blockhash = built.as_ref().ok().map(|(tx, _)| *tx.message.recent_blockhash());
+        let result = match built {
+            Ok((transaction, _)) => self
+                .infrastructure
+                .rpc
+                .send_transaction(&transaction)
+                .await
+                .map(|signature| (true, vec![signature], None, Vec::new()))
+                .map_err(anyhow::Error::from),
+            Err(e) => Err(e),
+        };
+        let outcome_ctx = TradeOutcomeContext {
+            dex_type: DexType::PumpFun,
+            is_buy: true,
+            mint,
+            input_amount: buy_sol_amount,
+            fixed_output_amount: None,
+            slippage_basis_points,
+            wait_tx_confirmed: false,
+            simulate: false,
+            blockhash,
+        };
+        self.emit_outcome(&outcome_ctx, &result, false).await;
+        result.map(|(_, signatures, _, _)| (signatures[0], mint))
+    }
+
+    /// The signed transaction [`Self::pumpfun_create_and_buy`] sends, and the new mint. The
+    /// PumpFun entry of `overrides` (the installed set) is applied to every instruction, so the
+    /// create, extend and buy address the same program.
+    async fn pumpfun_create_and_buy_transaction(
+        &self,
+        create_args: crate::trading::core::params::PumpFunCreateArgs,
+        buy_sol_amount: u64,
+        slippage_basis_points: Option<u64>,
+        overrides: &crate::trading::ProtocolOverrides,
+    ) -> Result<(solana_sdk::transaction::VersionedTransaction, Pubkey), anyhow::Error> {
+        validate_trade_safety("create_and_buy", buy_sol_amount, None, slippage_basis_points)?;
+        let payer = self.payer.pubkey();
+        let mint_keypair = create_args.mint.unwrap_or_else(Keypair::new);
+        let mint = mint_keypair.pubkey();
+        let creator = create_args.creator.unwrap_or(payer);
+        if mint.to_string().ends_with("pump") {
+            // The buy path routes `...pump` mints as Token-2022; legacy `create` mints SPL Token.
+            return Err(anyhow::anyhow!(
+                "pumpfun_create_and_buy: legacy create mints SPL Token; use a mint not ending in `pump`"
+            ));
+        }
+
+        let create_ix = crate::instruction::pumpfun::create_pumpfun_instruction(
+            &payer,
+            &mint,
+            &create_args.name,
+            &create_args.symbol,
+            &create_args.uri,
+            &creator,
+        )?;
+        let bonding_curve = crate::instruction::utils::pumpfun::get_bonding_curve_pda(&mint)
+            .ok_or_else(|| anyhow::anyhow!("bonding curve PDA derivation failed for {}", mint))?;
+        let extend_ix =
+            crate::instruction::utils::pumpfun::extend_bonding_curve_account_instruction(
+                &bonding_curve,
+                &payer,
+            );
+        let protocol_params = PumpFunParams::from_dev_trade(
+            mint,
+            0,
+            0,
+            creator,
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            None,
+            crate::instruction::utils::pumpfun::global_constants::FEE_RECIPIENT,
+            crate::constants::TOKEN_PROGRAM,
+            false,
+            Some(false),
+        );
+        // Only the buy builder reads these params; the transaction goes out over RPC, so the
+        // SWQOS routing, fallback and send-hook fields stay neutral.
+        let buy_params = SwapParams {
+            rpc: Some(self.infrastructure.rpc.clone()),
+            payer: self.payer.clone(),
+            trade_type: TradeType::CreateAndBuy,
+            input_mint: SOL_TOKEN_ACCOUNT,
+            output_mint: mint,
+            input_token_program: None,
+            output_token_program: Some(crate::constants::TOKEN_PROGRAM),
+            input_amount: Some(buy_sol_amount),
+            slippage_basis_points,
+            address_lookup_table_accounts: Vec::new(),
+            recent_blockhash: None,
+            wait_tx_confirmed: false,
+            protocol_params: DexParamEnum::PumpFun(protocol_params),
+            open_seed_optimize: self.use_seed_optimize,
+            swqos_clients: Arc::new(Vec::new()),
+            middleware_manager: None,
+            durable_nonce: None,
+            with_tip: false,
+            create_input_mint_ata: false,
+            close_input_mint_ata: false,
+            create_output_mint_ata: true,
+            close_output_mint_ata: false,
+            fixed_output_amount: None,
+            gas_fee_strategy: GasFeeStrategy::new(),
+            simulate: false,
+            log_enabled: self.log_enabled,
+            wait_for_all_submits: false,
+            use_dedicated_sender_threads: false,
+            sender_thread_cores: None,
+            max_sender_concurrency: self.max_sender_concurrency,
+            effective_core_ids: self.effective_core_ids.clone(),
+            check_min_tip: false,
+            grpc_recv_us: None,
+            use_exact_sol_amount: None,
+            swqos_watchdog: None,
+            ata_creation: AtaCreationMode::Inline,
+            memo: None,
+            parallel_strategy: ParallelStrategy::default(),
+            max_ata_creations: None,
+            cu_learner: None,
+            rpc_fallback: false,
+            cleanup_on_failure: false,
+            skip_compute_budget: false,
+            simulation_capture: None,
+            ata_rent_funder: None,
+            receive_native_sol: false,
+            on_before_send: None,
+            multi_nonce: None,
+            min_tokens_out: None,
+        };
+        let buy_ixs = crate::trading::InstructionBuilder::build_buy_instructions(
+            &crate::instruction::pumpfun::PumpFunInstructionBuilder,
+            &buy_params,
+        )
+        .await?;
+
+        let mut instructions: Vec<_> =
+            crate::trading::common::compute_budget_manager::compute_budget_instructions(
+                create_args.cu_price,
+                create_args.cu_limit,
+            )
+            .into_iter()
+            .collect();
+        instructions.push(create_ix);
+        instructions.push(extend_ix);
+        instructions.extend(buy_ixs);
+        overrides.apply(DexType::PumpFun, &mut instructions);
+
+        let blockhash = self.infrastructure.rpc.get_latest_blockhash().await?;
+        let message =
+            solana_message::v0::Message::try_compile(&payer, &instructions, &[], blockhash)?;
+        let transaction = solana_sdk::transaction::VersionedTransaction::try_new(
+            solana_message::VersionedMessage::V0(message),
+            &[self.payer.as_ref(), &mint_keypair],
+        )?;
+        Ok((transaction, mint))
+    }
+
     /// Execute a high-level buy request.
     #[inline]
     pub async fn buy_simple(
@@ -1482,12 +2780,58 @@ impl TradingClient {
         (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
         anyhow::Error,
     > {
+        self.sell_inner(params, None).await
+    }
+
+    async fn sell_inner(
+        &self,
+        params: TradeSellParams,
+        simulation_capture: Option<Arc<SimulationCapture>>,
+    ) -> Result<
+        (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
+        anyhow::Error,
+    > {
+        let (sell_params, outcome_ctx) = self.sell_swap_params(params, simulation_capture).await?;
+        let executor = TradeFactory::create_executor(outcome_ctx.dex_type);
+        let swap_result = executor.swap(sell_params).await;
+        let via_rpc_fallback = matches!(&swap_result, Ok((true, _, _, timings)) if timings.iter().any(|t| t.rpc_fallback));
+        let result = swap_result.map(|(success, sigs, err, timings)| {
+            let legacy_timings = timings
+                .into_iter()
+                .map(|timing| (timing.swqos_type, timing.submit_done_us))
+                .collect();
+            (success, sigs, err.map(TradeError::from), legacy_timings)
+        });
+        self.emit_outcome(&outcome_ctx, &result, via_rpc_fallback).await;
+        result
+    }
+
+    /// Checks and enrichment of a sell request up to the [`SwapParams`] the executor runs, shared
+    /// by [`Self::sell`] and [`Self::prepare_sell`]; also returns the request's outcome context.
+    async fn sell_swap_params(
+        &self,
+        mut params: TradeSellParams,
+        simulation_capture: Option<Arc<SimulationCapture>>,
+    ) -> Result<(SwapParams, TradeOutcomeContext), anyhow::Error> {
+        if let Some(model) = &params.slippage_model {
+            params.slippage_basis_points = Some(model.slippage_bps(&SlippageContext::new(
+                params.dex_type,
+                false,
+                params.mint,
+                params.input_token_amount,
+                &params.extension_params,
+                params.slippage_basis_points,
+            )));
+        }
         validate_trade_safety(
             "sell",
             params.input_token_amount,
             params.fixed_output_token_amount,
             params.slippage_basis_points,
         )?;
+        if let Some(percentile) = params.priority_fee_percentile {
+            crate::common::priority_fee::validate_percentile(percentile)?;
+        }
         #[cfg(feature = "perf-trace")]
         if sdk_log::sdk_log_enabled() && params.slippage_basis_points.is_none() {
             debug!(
@@ -1496,40 +2840,102 @@ impl TradingClient {
                 DEFAULT_SLIPPAGE
             );
         }
-        if params.recent_blockhash.is_none() && params.durable_nonce.is_none() {
+        if params.recent_blockhash.is_none()
+            && params.durable_nonce.is_none()
+            && params.multi_nonce.is_none()
+        {
+            return Err(anyhow::anyhow!(
+                "Must provide either recent_blockhash, durable_nonce or multi_nonce for sell (required for transaction validity)"
+            ));
+        }
+        if params.durable_nonce.is_some() && params.multi_nonce.is_some() {
             return Err(anyhow::anyhow!(
-                "Must provide either recent_blockhash or durable_nonce for sell (required for transaction validity)"
+                "durable_nonce and multi_nonce are mutually exclusive for sell"
             ));
         }
-        if params.output_token_type == TradeTokenType::USD1 && params.dex_type != DexType::Bonk {
+        if params.output_token_type == TradeTokenType::USD1
+            && !TradeFactory::capabilities(params.dex_type).supports_usd1
+        {
             return Err(anyhow::anyhow!(
                 " Current version only supports USD1 trading on Bonk protocols"
             ));
         }
-        let protocol_params = params.extension_params;
+        let mut protocol_params = params.extension_params;
         if !validate_protocol_params(params.dex_type, &protocol_params) {
             return Err(anyhow::anyhow!(
                 "Invalid protocol params for Trade (dex={:?})",
                 params.dex_type
             ));
         }
-        let executor = TradeFactory::create_executor(params.dex_type);
-        let output_token_mint = if params.output_token_type == TradeTokenType::SOL {
-            SOL_TOKEN_ACCOUNT
-        } else if params.output_token_type == TradeTokenType::WSOL {
-            WSOL_TOKEN_ACCOUNT
-        } else if params.output_token_type == TradeTokenType::USDC {
-            USDC_TOKEN_ACCOUNT
-        } else {
-            USD1_TOKEN_ACCOUNT
+        protocol_params = self
+            .guard_migration_if_enabled(&mut params.dex_type, protocol_params, &params.mint)
+            .await?;
+        if params.receive_native_sol
+            && !matches!(params.output_token_type, TradeTokenType::SOL | TradeTokenType::WSOL)
+        {
+            return Err(anyhow::anyhow!(
+                "receive_native_sol requires a SOL or WSOL output, got {:?}",
+                params.output_token_type
+            ));
+        }
+        let output_token_mint = quote_token_mint(&params.output_token_type);
+        params.recent_blockhash = self
+            .refresh_blockhash_if_enabled(params.recent_blockhash, params.durable_nonce.as_ref())
+            .await?;
+        let outcome_ctx = TradeOutcomeContext {
+            dex_type: params.dex_type,
+            is_buy: false,
+            mint: params.mint,
+            input_amount: params.input_token_amount,
+            fixed_output_amount: params.fixed_output_token_amount,
+            slippage_basis_points: params.slippage_basis_points,
+            wait_tx_confirmed: params.wait_tx_confirmed,
+            simulate: params.simulate,
+            blockhash: used_blockhash(params.recent_blockhash, params.durable_nonce.as_ref()),
         };
+        if let Some(pool) = params.pool_override {
+            protocol_params = self
+                .load_sell_pool_override(params.dex_type, &pool, &params.mint, output_token_mint)
+                .await?;
+        }
+        self.check_reserves_if_enabled(&protocol_params, &params.mint).await?;
+        let open_seed_optimize =
+            params.open_seed_optimize_override.unwrap_or(self.use_seed_optimize);
+        if self.seed_ata_check && open_seed_optimize {
+            crate::trading::core::seed_ata_check::check_seed_ata(
+                &self.infrastructure.rpc,
+                &self.payer.pubkey(),
+                &params.mint,
+                params.mint_token_program,
+            )
+            .await?;
+        }
+        if params.auto_alt {
+            self.attach_auto_alt(
+                params.dex_type,
+                &protocol_params,
+                &mut params.address_lookup_table_accounts,
+            )
+            .await;
+        }
+        if let Some(percentile) = params.priority_fee_percentile {
+            params.gas_fee_strategy = self
+                .priced_at_percentile(
+                    &params.gas_fee_strategy,
+                    TradeType::Sell,
+                    &protocol_params,
+                    percentile,
+                )
+                .await?;
+        }
+        let ata_flags = params.swap_ata_flags();
         let sell_params = SwapParams {
             rpc: Some(self.infrastructure.rpc.clone()),
             payer: self.payer.clone(),
             trade_type: TradeType::Sell,
             input_mint: params.mint,
             output_mint: output_token_mint,
-            input_token_program: None,
+            input_token_program: params.mint_token_program,
             output_token_program: None,
             input_amount: Some(params.input_token_amount),
             slippage_basis_points: params.slippage_basis_points,
@@ -1538,14 +2944,15 @@ impl TradingClient {
             wait_tx_confirmed: params.wait_tx_confirmed,
             protocol_params,
             with_tip: params.with_tip,
-            open_seed_optimize: self.use_seed_optimize, // 使用全局seed优化配置
+            // 单笔覆盖优先，否则使用全局seed优化配置
+            open_seed_optimize,
             swqos_clients: self.infrastructure.swqos_clients.clone(),
             middleware_manager: self.middleware_manager.clone(),
             durable_nonce: params.durable_nonce,
-            create_input_mint_ata: false,
-            close_input_mint_ata: params.close_mint_token_ata,
-            create_output_mint_ata: params.create_output_token_ata,
-            close_output_mint_ata: params.close_output_token_ata,
+            create_input_mint_ata: ata_flags.create_input_mint_ata,
+            close_input_mint_ata: ata_flags.close_input_mint_ata,
+            create_output_mint_ata: ata_flags.create_output_mint_ata,
+            close_output_mint_ata: ata_flags.close_output_mint_ata,
             fixed_output_amount: params.fixed_output_token_amount,
             gas_fee_strategy: params.gas_fee_strategy,
             simulate: params.simulate,
@@ -1558,72 +2965,424 @@ impl TradingClient {
             check_min_tip: self.check_min_tip,
             grpc_recv_us: params.grpc_recv_us,
             use_exact_sol_amount: None,
+            swqos_watchdog: self.swqos_watchdog.clone(),
+            ata_creation: params.ata_creation,
+            memo: params.memo,
+            parallel_strategy: self.parallel_strategy,
+            max_ata_creations: params.max_ata_creations,
+            cu_learner: self.cu_learner.clone(),
+            rpc_fallback: self.rpc_fallback,
+            cleanup_on_failure: false,
+            skip_compute_budget: params.skip_compute_budget,
+            simulation_capture,
+            ata_rent_funder: params.ata_rent_funder.clone(),
+            receive_native_sol: params.receive_native_sol,
+            on_before_send: self.on_before_send.clone(),
+            multi_nonce: params.multi_nonce.clone(),
+            min_tokens_out: None,
         };
+        Ok((sell_params, outcome_ctx))
+    }
 
-        let swap_result = executor.swap(sell_params).await;
-        let result = swap_result.map(|(success, sigs, err, timings)| {
-            let legacy_timings = timings
-                .into_iter()
-                .map(|timing| (timing.swqos_type, timing.submit_done_us))
-                .collect();
-            (success, sigs, err.map(TradeError::from), legacy_timings)
-        });
-        result
+    /// Reload protocol params for [`TradeSellParams::pool_override`] and check the pool pairs
+    /// `mint` with the requested output quote.
+    async fn load_sell_pool_override(
+        &self,
+        dex_type: DexType,
+        pool: &Pubkey,
+        mint: &Pubkey,
+        output_token_mint: Pubkey,
+    ) -> Result<DexParamEnum, anyhow::Error> {
+        if dex_type != DexType::PumpSwap {
+            return Err(anyhow::anyhow!("pool_override is only supported for PumpSwap"));
+        }
+        let pool_params = PumpSwapParams::from_pool_address_by_rpc(&self.infrastructure.rpc, pool)
+            .await
+            .map_err(TradeError::from)?;
+        // SOL output is received through the WSOL pool.
+        let expected_quote = if output_token_mint == SOL_TOKEN_ACCOUNT {
+            WSOL_TOKEN_ACCOUNT
+        } else {
+            output_token_mint
+        };
+        if pool_params.base_mint != *mint || pool_params.quote_mint != expected_quote {
+            return Err(anyhow::anyhow!(
+                "pool_override {} pairs {}/{}, expected {}/{}",
+                pool,
+                pool_params.base_mint,
+                pool_params.quote_mint,
+                mint,
+                expected_quote
+            ));
+        }
+        Ok(DexParamEnum::PumpSwap(pool_params))
+    }
+
+    /// Execute a high-level sell request.
+    #[inline]
+    pub async fn sell_simple(
+        &self,
+        params: SimpleSellParams,
+    ) -> Result<
+        (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
+        anyhow::Error,
+    > {
+        self.sell(params.into()).await
+    }
+
+    /// Execute a sell order for a percentage of the specified token amount
+    ///
+    /// This is a convenience function that calculates the exact amount to sell based on
+    /// a percentage of the total token amount and then calls the `sell` function.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Sell trade parameters (will be modified with calculated token amount)
+    /// * `amount_token` - Total amount of tokens available (in smallest token units)
+    /// * `percent` - Percentage of tokens to sell (1-100, where 100 = 100%)
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Signature)` with the transaction signature if the sell order is successfully executed,
+    /// or an error if the transaction fails.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - `percent` is 0 or greater than 100
+    /// - Invalid protocol parameters are provided for the specified DEX type
+    /// - The transaction fails to execute
+    /// - Network or RPC errors occur
+    /// - Insufficient token balance for the calculated sale amount
+    /// - Token account doesn't exist or is not properly initialized
+    /// - Required accounts cannot be created or accessed
+    pub async fn sell_by_percent(
+        &self,
+        mut params: TradeSellParams,
+        amount_token: u64,
+        percent: u64,
+    ) -> Result<
+        (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
+        anyhow::Error,
+    > {
+        if percent == 0 || percent > 100 {
+            return Err(anyhow::anyhow!("Percentage must be between 1 and 100"));
+        }
+        let amount = amount_token * percent / 100;
+        params.input_token_amount = amount;
+        self.sell(params).await
+    }
+
+    /// Sell `params.input_token_amount` in `num_tranches` transactions spaced `interval` apart,
+    /// for positions too large to sell into the pool in one swap. Before every tranche after the
+    /// first, reserves are re-read from chain (so the builders' slippage limits and any
+    /// `slippage_model` are recomputed against them) and a fresh blockhash or nonce is fetched;
+    /// with `multi_nonce`, every nonce account of the pool is re-read.
+    ///
+    /// The ladder stops without sending when a tranche's estimated price impact (constant product
+    /// against the token-side reserve) exceeds `max_price_impact_bps`, and after any tranche that
+    /// fails; fewer outcomes than tranches means it stopped early. A failed reserve, nonce or
+    /// blockhash refresh is recorded as that tranche's (failed) outcome and the tranches sold so
+    /// far are returned. Meteora DAMM v2 params carry no reserves, so its token-side reserve is
+    /// read from the pool's token vault before every tranche, including the first. Set
+    /// `wait_tx_confirmed` so each tranche lands before the next one reads reserves.
+    /// `fixed_output_token_amount` describes the whole position and is ignored.
+    /// `close_mint_token_ata` and PumpFun's `close_token_account_when_sell` apply to the final
+    /// tranche only.
+    /// 大仓位分批卖出，价格冲击过大时中止。
+    pub async fn sell_laddered(
+        &self,
+        params: TradeSellParams,
+        num_tranches: usize,
+        interval: std::time::Duration,
+        max_price_impact_bps: u64,
+    ) -> Result<Vec<crate::trading::TradeOutcome>, anyhow::Error> {
+        use crate::trading::core::reserve_check::{fetch_onchain_reserves, set_reserves};
+
+        if num_tranches == 0 {
+            return Err(anyhow::anyhow!("num_tranches must be at least 1"));
+        }
+        validate_trade_safety(
+            "sell_laddered",
+            params.input_token_amount,
+            None,
+            params.slippage_basis_points,
+        )?;
+        let rpc = &self.infrastructure.rpc;
+        let mut extension_params = params.extension_params.clone();
+        let tranches = ladder_tranches(params.input_token_amount, num_tranches);
+        let mut outcomes = Vec::with_capacity(tranches.len());
+        let last = tranches.len() - 1;
+        for (i, amount) in tranches.into_iter().enumerate() {
+            let mut tranche = params.clone();
+            let refreshed: Result<Option<u64>, anyhow::Error> = async {
+                if i > 0 {
+                    tokio::time::sleep(interval).await;
+                    if let Some(reserves) =
+                        fetch_onchain_reserves(rpc, &extension_params, &params.mint).await?
+                    {
+                        set_reserves(&mut extension_params, reserves);
+                    }
+                    refresh_tranche_nonce(rpc, &mut tranche).await?;
+                }
+                ladder_token_reserve(rpc, &extension_params, &params.mint).await
+            }
+            .await;
+            tranche.input_token_amount = amount;
+            tranche.fixed_output_token_amount = None;
+            let ctx = TradeOutcomeContext {
+                dex_type: tranche.dex_type,
+                is_buy: false,
+                mint: tranche.mint,
+                input_amount: amount,
+                fixed_output_amount: None,
+                slippage_basis_points: tranche.slippage_basis_points,
+                wait_tx_confirmed: tranche.wait_tx_confirmed,
+                simulate: tranche.simulate,
+                blockhash: used_blockhash(tranche.recent_blockhash, tranche.durable_nonce.as_ref()),
+            };
+            let reserve = match refreshed {
+                Ok(reserve) => reserve,
+                Err(e) => {
+                    outcomes.push(outcome_from_result(&ctx, &Err(e)));
+                    break;
+                }
+            };
+            if let Some(reserve) = reserve {
+                let impact_bps = sell_price_impact_bps(amount, reserve);
+                if impact_bps > max_price_impact_bps {
+                    if sdk_log::sdk_log_enabled() {
+                        println!(
+                            " [SDK] sell_laddered stopped at tranche {}: price impact {} bps > {} bps",
+                            i + 1,
+                            impact_bps,
+                            max_price_impact_bps
+                        );
+                    }
+                    break;
+                }
+            }
+            tranche.extension_params = extension_params.clone();
+            if i < last {
+                keep_mint_account_open(&mut tranche);
+            }
+            let result = self.sell(tranche).await;
+            let outcome = outcome_from_result(&ctx, &result);
+            let failed = !outcome.success;
+            outcomes.push(outcome);
+            if failed {
+                break;
+            }
+        }
+        Ok(outcomes)
+    }
+
+    /// Mirror a decoded trade: build the protocol params from `event` and buy (`amount` in
+    /// quote base units) or sell (`amount` in token base units) with a fresh blockhash and the
+    /// builder defaults (confirmation wait, inline ATA creation). For other settings build the
+    /// params yourself from [`CopyTradeEvent::protocol_params`]. 一行跟单。
+    pub async fn copy_trade(
+        &self,
+        event: &dyn CopyTradeEvent,
+        side: TradeType,
+        amount: u64,
+        slippage_basis_points: Option<u64>,
+        gas_fee_strategy: GasFeeStrategy,
+    ) -> Result<crate::trading::TradeOutcome, anyhow::Error> {
+        let recent_blockhash = self.infrastructure.rpc.get_latest_blockhash().await?;
+        let (dex_type, mint) = (event.dex_type(), event.mint());
+        let extension_params = event.protocol_params();
+        let ctx = TradeOutcomeContext {
+            dex_type,
+            is_buy: side == TradeType::Buy,
+            mint,
+            input_amount: amount,
+            fixed_output_amount: None,
+            slippage_basis_points,
+            wait_tx_confirmed: true,
+            simulate: false,
+            blockhash: Some(recent_blockhash),
+        };
+        let result = match side {
+            TradeType::Sell => {
+                let mut builder =
+                    TradeSellParams::builder(dex_type, mint, amount, extension_params)
+                        .output_token_type(event.quote_token_type())
+                        .recent_blockhash(recent_blockhash)
+                        .gas_fee_strategy(gas_fee_strategy);
+                if let Some(bps) = slippage_basis_points {
+                    builder = builder.slippage_basis_points(bps);
+                }
+                if let Some(program) = event.mint_token_program() {
+                    builder = builder.mint_token_program(program);
+                }
+                self.sell(builder.build()).await
+            }
+            TradeType::Buy => {
+                let mut builder = TradeBuyParams::builder(dex_type, mint, amount, extension_params)
+                    .input_token_type(event.quote_token_type())
+                    .recent_blockhash(recent_blockhash)
+                    .gas_fee_strategy(gas_fee_strategy);
+                if let Some(bps) = slippage_basis_points {
+                    builder = builder.slippage_basis_points(bps);
+                }
+                if let Some(program) = event.mint_token_program() {
+                    builder = builder.mint_token_program(program);
+                }
+                self.buy(builder.build()).await
+            }
+            other => {
+                return Err(anyhow::anyhow!("copy_trade supports Buy and Sell, got {:?}", other))
+            }
+        };
+        Ok(outcome_from_result(&ctx, &result))
+    }
+
+    /// Decimals of `mint`, read from chain on first use and cached process-wide
+    /// ([`DECIMALS_CACHE`](crate::common::decimals_cache::DECIMALS_CACHE), pre-seeded with
+    /// WSOL, USDC and USD1). 查询代币精度（带缓存）。
+    pub async fn decimals(&self, mint: Pubkey) -> Result<u8, anyhow::Error> {
+        crate::common::decimals_cache::DECIMALS_CACHE.resolve(&self.infrastructure.rpc, &mint).await
+    }
+
+    /// Tokens the payer holds: every nonzero SPL Token / Token-2022 account with raw and UI
+    /// amount (decimals via the same cache as [`Self::decimals`]). WSOL, USDC and USD1 accounts
+    /// are left out; see [`Self::positions_including_quote`]. 查询当前持仓。
+    pub async fn positions(&self) -> Result<Vec<Position>, anyhow::Error> {
+        fetch_positions(&self.infrastructure.rpc, &self.payer.pubkey(), false).await
+    }
+
+    /// [`Self::positions`] including WSOL / USDC / USD1 quote accounts.
+    pub async fn positions_including_quote(&self) -> Result<Vec<Position>, anyhow::Error> {
+        fetch_positions(&self.infrastructure.rpc, &self.payer.pubkey(), true).await
+    }
+
+    /// Sell every position worth at least `min_value_sol` back to SOL and close its token
+    /// account. Each mint is quoted against its SOL-quoted PumpFun curve, PumpSwap pool and Bonk
+    /// pool (direct PDA lookups only), and sold in full through the best one, one confirmed
+    /// transaction at a time. Raydium CPMM, Raydium AMM v4 and Meteora DAMM v2 pools cannot be
+    /// derived from the mint alone, so positions only tradable there are skipped along with
+    /// mints quoted below `min_value_sol`. Sells use `gas_fee_strategy` and go through the
+    /// Default (plain RPC) route without tips, so a Default SWQOS must be configured. A failed
+    /// blockhash fetch or sell is reported in that position's outcome and the sweep continues.
+    /// 清理粉尘：价值达到阈值的持仓全部卖成 SOL。
+    pub async fn sweep_dust(
+        &self,
+        min_value_sol: f64,
+        gas_fee_strategy: GasFeeStrategy,
+    ) -> Result<Vec<crate::trading::TradeOutcome>, anyhow::Error> {
+        use crate::trading::sweep::{best_sell_quote, sol_pool_candidates};
+
+        if !min_value_sol.is_finite() || min_value_sol < 0.0 {
+            return Err(anyhow::anyhow!("min_value_sol must be >= 0, got {}", min_value_sol));
+        }
+        let min_lamports = (min_value_sol * 1_000_000_000.0).round() as u64;
+        let rpc = &self.infrastructure.rpc;
+        let mut outcomes = Vec::new();
+        for position in self.positions().await? {
+            let candidates = sol_pool_candidates(rpc, &position.mint).await;
+            let Some((params, quote)) =
+                best_sell_quote(candidates, &position.mint, position.amount)
+            else {
+                if sdk_log::sdk_log_enabled() {
+                    println!(" [SDK] sweep_dust: no SOL pool for {}, skipped", position.mint);
+                }
+                continue;
+            };
+            if quote < min_lamports {
+                continue;
+            }
+            let dex_type = params.dex_type();
+            let mut ctx = TradeOutcomeContext {
+                dex_type,
+                is_buy: false,
+                mint: position.mint,
+                input_amount: position.amount,
+                fixed_output_amount: None,
+                slippage_basis_points: None,
+                wait_tx_confirmed: true,
+                simulate: false,
+                blockhash: None,
+            };
+            let recent_blockhash = match rpc.get_latest_blockhash().await {
+                Ok(blockhash) => blockhash,
+                Err(e) => {
+                    outcomes.push(outcome_from_result(&ctx, &Err(e.into())));
+                    continue;
+                }
+            };
+            ctx.blockhash = Some(recent_blockhash);
+            let sell = TradeSellParams::builder(dex_type, position.mint, position.amount, params)
+                .with_tip(false)
+                .close_mint_token_ata(true)
+                .mint_token_program(position.token_program)
+                .recent_blockhash(recent_blockhash)
+                .gas_fee_strategy(gas_fee_strategy.clone())
+                .build();
+            let result = self.sell(sell).await;
+            outcomes.push(outcome_from_result(&ctx, &result));
+        }
+        Ok(outcomes)
     }
 
-    /// Execute a high-level sell request.
-    #[inline]
-    pub async fn sell_simple(
-        &self,
-        params: SimpleSellParams,
-    ) -> Result<
-        (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
-        anyhow::Error,
-    > {
-        self.sell(params.into()).await
+    /// Which supported protocol `pool` belongs to, from the program that owns the account.
+    /// Errors when the account is missing or owned by an unsupported program.
+    /// 根据池子账户 owner 识别协议。
+    pub async fn detect_protocol(&self, pool: Pubkey) -> Result<DexType, anyhow::Error> {
+        let account = self.infrastructure.rpc.get_account(&pool).await?;
+        protocol_of_owner(&pool, &account.owner)
     }
 
-    /// Execute a sell order for a percentage of the specified token amount
-    ///
-    /// This is a convenience function that calculates the exact amount to sell based on
-    /// a percentage of the total token amount and then calls the `sell` function.
-    ///
-    /// # Arguments
-    ///
-    /// * `params` - Sell trade parameters (will be modified with calculated token amount)
-    /// * `amount_token` - Total amount of tokens available (in smallest token units)
-    /// * `percent` - Percentage of tokens to sell (1-100, where 100 = 100%)
-    ///
-    /// # Returns
-    ///
-    /// Returns `Ok(Signature)` with the transaction signature if the sell order is successfully executed,
-    /// or an error if the transaction fails.
-    ///
-    /// # Errors
-    ///
-    /// This function will return an error if:
-    /// - `percent` is 0 or greater than 100
-    /// - Invalid protocol parameters are provided for the specified DEX type
-    /// - The transaction fails to execute
-    /// - Network or RPC errors occur
-    /// - Insufficient token balance for the calculated sale amount
-    /// - Token account doesn't exist or is not properly initialized
-    /// - Required accounts cannot be created or accessed
-    pub async fn sell_by_percent(
+    /// Buy from any supported pool without naming its protocol: detect it, load the pool params
+    /// over RPC and buy the non-quote side with `amount` of the SOL/USDC/USD1 side (see
+    /// [`DecodedTrade::from_pool_params`]), otherwise as [`Self::copy_trade`]. PumpFun bonding
+    /// curves do not record their mint; use `PumpFunParams::from_mint_by_rpc` for those.
+    /// 只给池子地址即可买入。
+    pub async fn buy_from_pool(
         &self,
-        mut params: TradeSellParams,
-        amount_token: u64,
-        percent: u64,
-    ) -> Result<
-        (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
-        anyhow::Error,
-    > {
-        if percent == 0 || percent > 100 {
-            return Err(anyhow::anyhow!("Percentage must be between 1 and 100"));
-        }
-        let amount = amount_token * percent / 100;
-        params.input_token_amount = amount;
-        self.sell(params).await
+        pool: Pubkey,
+        amount: u64,
+        slippage_basis_points: Option<u64>,
+        gas_fee_strategy: GasFeeStrategy,
+    ) -> Result<crate::trading::TradeOutcome, anyhow::Error> {
+        let rpc = &self.infrastructure.rpc;
+        let account = rpc.get_account(&pool).await?;
+        let trade = match protocol_of_owner(&pool, &account.owner)? {
+            DexType::PumpSwap => DecodedTrade::from_pool_params(DexParamEnum::PumpSwap(
+                PumpSwapParams::from_pool_address_by_rpc(rpc, &pool).await?,
+            ))?,
+            DexType::RaydiumCpmm => DecodedTrade::from_pool_params(DexParamEnum::RaydiumCpmm(
+                RaydiumCpmmParams::from_pool_address_by_rpc(rpc, &pool).await?,
+            ))?,
+            DexType::RaydiumAmmV4 => DecodedTrade::from_pool_params(DexParamEnum::RaydiumAmmV4(
+                RaydiumAmmV4Params::from_amm_address_by_rpc(rpc, pool).await?,
+            ))?,
+            DexType::MeteoraDammV2 => DecodedTrade::from_pool_params(DexParamEnum::MeteoraDammV2(
+                MeteoraDammV2Params::from_pool_address_by_rpc(rpc, &pool).await?,
+            ))?,
+            DexType::Bonk => {
+                let state = account
+                    .data
+                    .get(8..)
+                    .and_then(crate::instruction::utils::bonk_types::pool_state_decode)
+                    .ok_or_else(|| anyhow::anyhow!("{} does not decode as a Bonk pool", pool))?;
+                let usd1_pool = state.quote_mint == USD1_TOKEN_ACCOUNT;
+                let params = BonkParams::from_mint_by_rpc(rpc, &state.base_mint, usd1_pool).await?;
+                let mint_token_program = params.mint_token_program;
+                let mut trade = DecodedTrade::new(state.base_mint, DexParamEnum::Bonk(params))
+                    .with_quote(if usd1_pool { TradeTokenType::USD1 } else { TradeTokenType::SOL });
+                trade.mint_token_program = Some(mint_token_program);
+                trade
+            }
+            DexType::PumpFun => {
+                return Err(anyhow::anyhow!(
+                    "{} is a PumpFun bonding curve, which does not record its mint; buy with PumpFunParams::from_mint_by_rpc",
+                    pool
+                ))
+            }
+        };
+        self.copy_trade(&trade, TradeType::Buy, amount, slippage_basis_points, gas_fee_strategy)
+            .await
     }
 
     /// Wraps native SOL into wSOL (Wrapped SOL) for use in SPL token operations
@@ -1832,6 +3591,143 @@ impl TradingClient {
     }
 }
 
+/// Protocol of a pool from its account owner; errors for unsupported owners.
+fn protocol_of_owner(pool: &Pubkey, owner: &Pubkey) -> Result<DexType, anyhow::Error> {
+    DexType::from_program_id(owner).ok_or_else(|| {
+        anyhow::anyhow!("{} is owned by {}, which is not a supported DEX program", pool, owner)
+    })
+}
+
+/// The hash a trade signs with: the recent blockhash, or the durable nonce value.
+fn used_blockhash(
+    recent_blockhash: Option<Hash>,
+    nonce: Option<&DurableNonceInfo>,
+) -> Option<Hash> {
+    recent_blockhash.or_else(|| nonce.and_then(|n| n.current_nonce))
+}
+
+fn outcome_from_result(
+    ctx: &TradeOutcomeContext,
+    result: &Result<
+        (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
+        anyhow::Error,
+    >,
+) -> crate::trading::TradeOutcome {
+    match result {
+        Ok((success, sigs, err, _)) => ctx.outcome(*success, sigs, err.as_ref()),
+        Err(e) => {
+            let err = e
+                .downcast_ref::<TradeError>()
+                .cloned()
+                .unwrap_or_else(|| TradeError::new(500, e.to_string()));
+            ctx.outcome(false, &[], Some(&err))
+        }
+    }
+}
+
+/// Read fee and compute units of the first signature that landed (even if it failed
+/// on-chain: the fee is paid either way). 读取实际手续费与 CU 消耗。
+async fn fill_landed_cost(rpc: &SolanaRpcClient, outcome: &mut crate::trading::TradeOutcome) {
+    for sig in &outcome.signatures {
+        let Ok(sig) = Signature::from_str(sig) else {
+            continue;
+        };
+        let config = crate::trading::outcome::landed_transaction_config(
+            solana_transaction_status::UiTransactionEncoding::Base64,
+        );
+        let Ok(tx) = rpc.get_transaction_with_config(&sig, config).await else {
+            continue;
+        };
+        if let Some(meta) = tx.transaction.meta {
+            let (fee, units) = crate::trading::outcome::cost_from_meta(&meta);
+            outcome.fee_lamports = Some(fee);
+            outcome.units_consumed = units;
+            return;
+        }
+    }
+}
+
+fn deliver_outcome(
+    sinks: &[Arc<dyn TradeOutcomeSink>],
+    broadcast: &TradeOutcomeBroadcast,
+    outcome: &crate::trading::TradeOutcome,
+) {
+    for sink in sinks {
+        sink.on_outcome(outcome);
+    }
+    broadcast.publish(outcome);
+}
+
+/// Token-side reserve for [`TradingClient::sell_laddered`]'s price-impact cap: from the params
+/// where they carry reserves, from the pool's token vault for Meteora DAMM v2.
+async fn ladder_token_reserve(
+    rpc: &SolanaRpcClient,
+    params: &DexParamEnum,
+    mint: &Pubkey,
+) -> Result<Option<u64>, anyhow::Error> {
+    use crate::trading::core::reserve_check::{event_reserves, token_reserve};
+
+    match params {
+        DexParamEnum::MeteoraDammV2(p) => {
+            let vault = if p.token_a_mint == *mint { &p.token_a_vault } else { &p.token_b_vault };
+            let balance = rpc.get_token_account_balance(vault).await?;
+            Ok(Some(balance.amount.parse::<u64>()?))
+        }
+        other => Ok(event_reserves(other).map(|r| token_reserve(other, mint, r))),
+    }
+}
+
+/// Fresh blockhash, durable nonce or `MultiNonceConfig` for a [`TradingClient::sell_laddered`]
+/// tranche: the previous tranche advanced whichever nonces it used, so the pool is re-read from
+/// the same nonce accounts rather than reused.
+async fn refresh_tranche_nonce(
+    rpc: &SolanaRpcClient,
+    tranche: &mut TradeSellParams,
+) -> Result<(), anyhow::Error> {
+    if let Some(pool) = &tranche.multi_nonce {
+        let accounts: Vec<Pubkey> = pool.nonces().iter().filter_map(|n| n.nonce_account).collect();
+        tranche.multi_nonce = Some(Arc::new(MultiNonceConfig::fetch(rpc, &accounts).await?));
+        return Ok(());
+    }
+    match tranche.durable_nonce.as_ref().and_then(|n| n.nonce_account) {
+        Some(nonce_account) => {
+            let nonce =
+                crate::common::nonce_cache::fetch_nonce_info(rpc, nonce_account).await.ok_or_else(
+                    || anyhow::anyhow!("failed to refresh durable nonce {}", nonce_account),
+                )?;
+            tranche.durable_nonce = Some(nonce);
+        }
+        None => tranche.recent_blockhash = Some(rpc.get_latest_blockhash().await?),
+    }
+    Ok(())
+}
+
+/// Drop the token-account close from a non-final [`TradingClient::sell_laddered`] tranche: the
+/// account still holds the later tranches, so closing it would revert the transaction.
+fn keep_mint_account_open(tranche: &mut TradeSellParams) {
+    tranche.close_mint_token_ata = false;
+    if let DexParamEnum::PumpFun(p) = &mut tranche.extension_params {
+        p.close_token_account_when_sell = Some(false);
+    }
+}
+
+/// `total` split into `n` near-equal tranches; the remainder goes to the first ones.
+fn ladder_tranches(total: u64, n: usize) -> Vec<u64> {
+    let n = (n as u64).clamp(1, total.max(1));
+    let (base, rem) = (total / n, total % n);
+    (0..n).map(|i| base + u64::from(i < rem)).collect()
+}
+
+/// Price impact of selling `amount` into a constant-product pool holding `token_reserve`:
+/// the average fill is `token_reserve / (token_reserve + amount)` of the spot price.
+fn sell_price_impact_bps(amount: u64, token_reserve: u64) -> u64 {
+    let total = token_reserve as u128 + amount as u128;
+    if total == 0 {
+        return 0;
+    }
+    (amount as u128 * 10_000 / total) as u64
+}
+
 fn validate_trade_safety(
     side: &str,
     input_amount: u64,
@@ -1839,10 +3735,10 @@ fn validate_trade_safety(
     slippage_basis_points: Option<u64>,
 ) -> Result<(), anyhow::Error> {
     if input_amount == 0 {
-        return Err(anyhow::anyhow!("{} input amount must be greater than zero", side));
+        return Err(TradeError::zero_amount().into());
     }
     if fixed_output_amount == Some(0) {
-        return Err(anyhow::anyhow!("{} fixed output amount must be greater than zero", side));
+        return Err(TradeError::zero_output_amount().into());
     }
     if let Some(bps) = slippage_basis_points {
         if bps >= 10_000 {
@@ -1856,6 +3752,63 @@ fn validate_trade_safety(
     Ok(())
 }
 
+/// Mint a quote token type maps to (`SOL` maps to the native placeholder, not WSOL).
+fn quote_token_mint(token_type: &TradeTokenType) -> Pubkey {
+    if *token_type == TradeTokenType::SOL {
+        SOL_TOKEN_ACCOUNT
+    } else if *token_type == TradeTokenType::WSOL {
+        WSOL_TOKEN_ACCOUNT
+    } else if *token_type == TradeTokenType::USDC {
+        USDC_TOKEN_ACCOUNT
+    } else {
+        USD1_TOKEN_ACCOUNT
+    }
+}
+
+/// [`check_min_quote_liquidity`], reading the quote vault balance from chain for Meteora DAMM v2
+/// (its params carry no reserves), so the floor applies to every protocol.
+async fn verify_min_quote_liquidity(
+    rpc: &SolanaRpcClient,
+    protocol_params: &DexParamEnum,
+    input_mint: &Pubkey,
+    min_quote_liquidity: u64,
+) -> Result<(), anyhow::Error> {
+    if let DexParamEnum::MeteoraDammV2(p) = protocol_params {
+        let quote_mint = liquidity_quote_mint(input_mint);
+        let vault = if p.token_a_mint == quote_mint { &p.token_a_vault } else { &p.token_b_vault };
+        let reserve = rpc.get_token_account_balance(vault).await?.amount.parse::<u64>()?;
+        if reserve < min_quote_liquidity {
+            return Err(TradeError::insufficient_liquidity(reserve, min_quote_liquidity).into());
+        }
+        return Ok(());
+    }
+    Ok(check_min_quote_liquidity(protocol_params, input_mint, min_quote_liquidity)?)
+}
+
+/// Native SOL is held as WSOL in pool vaults.
+fn liquidity_quote_mint(input_mint: &Pubkey) -> Pubkey {
+    if *input_mint == SOL_TOKEN_ACCOUNT {
+        WSOL_TOKEN_ACCOUNT
+    } else {
+        *input_mint
+    }
+}
+
+/// Fails closed: params without reserves (Meteora DAMM v2, see [`verify_min_quote_liquidity`])
+/// are rejected as a zero reserve rather than passed.
+fn check_min_quote_liquidity(
+    protocol_params: &DexParamEnum,
+    input_mint: &Pubkey,
+    min_quote_liquidity: u64,
+) -> Result<(), TradeError> {
+    let reserve =
+        protocol_params.quote_liquidity(&liquidity_quote_mint(input_mint)).unwrap_or_default();
+    if reserve < min_quote_liquidity {
+        return Err(TradeError::insufficient_liquidity(reserve, min_quote_liquidity));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1874,6 +3827,7 @@ mod tests {
             close_token_account_when_sell: None,
             fee_recipient: global_constants::FEE_RECIPIENT,
             quote_mint: Pubkey::default(),
+            layout: Default::default(),
         })
     }
 
@@ -1885,6 +3839,104 @@ mod tests {
         assert!(validate_trade_safety("sell", 1, None, Some(u64::MAX)).is_err());
     }
 
+    #[tokio::test]
+    async fn buy_and_sell_reject_zero_amounts_with_coded_errors() {
+        use crate::test_fixtures::{pumpswap_params, MINT};
+        let (client, _) = mock_trading_client(crate::common::MockSolanaRpcClient::new()).await;
+        let code = |result: Result<_, anyhow::Error>| {
+            result.unwrap_err().downcast_ref::<TradeError>().map(|e| e.code)
+        };
+        let buy = |amount| {
+            TradeBuyParams::builder(
+                DexType::PumpSwap,
+                MINT,
+                amount,
+                DexParamEnum::PumpSwap(pumpswap_params()),
+            )
+            .recent_blockhash(solana_hash::Hash::new_unique())
+        };
+        let sell = |amount| {
+            TradeSellParams::builder(
+                DexType::PumpSwap,
+                MINT,
+                amount,
+                DexParamEnum::PumpSwap(pumpswap_params()),
+            )
+            .recent_blockhash(solana_hash::Hash::new_unique())
+        };
+
+        assert_eq!(code(client.buy(buy(0).build()).await), Some(TradeError::ZERO_AMOUNT));
+        assert_eq!(
+            code(client.buy(buy(1_000).fixed_output_token_amount(0).build()).await),
+            Some(TradeError::ZERO_OUTPUT_AMOUNT)
+        );
+        assert_eq!(code(client.sell(sell(0).build()).await), Some(TradeError::ZERO_AMOUNT));
+        assert_eq!(
+            code(client.sell(sell(1_000).fixed_output_token_amount(0).build()).await),
+            Some(TradeError::ZERO_OUTPUT_AMOUNT)
+        );
+    }
+
+    #[test]
+    fn min_quote_liquidity_uses_real_sol_reserves_for_pumpfun() {
+        let params = dummy_pumpfun_params();
+        let err = check_min_quote_liquidity(&params, &SOL_TOKEN_ACCOUNT, 1).unwrap_err();
+        assert_eq!(err.code, TradeError::INSUFFICIENT_LIQUIDITY);
+        assert!(check_min_quote_liquidity(&params, &SOL_TOKEN_ACCOUNT, 0).is_ok());
+    }
+
+    #[tokio::test]
+    async fn min_quote_liquidity_reads_the_meteora_quote_vault() {
+        use crate::common::mock_rpc::MockSolanaRpcClient;
+        use crate::test_fixtures::meteora_damm_v2_params;
+
+        let params = DexParamEnum::MeteoraDammV2(meteora_damm_v2_params());
+        let rpc = MockSolanaRpcClient::new()
+            .with_token_account_balance(500, 9)
+            .with_token_account_balance(500, 9)
+            .build();
+        assert!(verify_min_quote_liquidity(&rpc, &params, &SOL_TOKEN_ACCOUNT, 500).await.is_ok());
+        let err = verify_min_quote_liquidity(&rpc, &params, &SOL_TOKEN_ACCOUNT, 501)
+            .await
+            .unwrap_err()
+            .downcast::<TradeError>()
+            .unwrap();
+        assert_eq!(err.code, TradeError::INSUFFICIENT_LIQUIDITY);
+
+        // without the vault read, reserve-less params fail closed
+        let err = check_min_quote_liquidity(&params, &SOL_TOKEN_ACCOUNT, 1).unwrap_err();
+        assert_eq!(err.code, TradeError::INSUFFICIENT_LIQUIDITY);
+    }
+
+    #[test]
+    fn usd1_quote_resolves_to_usd1_mint() {
+        assert_eq!(quote_token_mint(&TradeTokenType::USD1), USD1_TOKEN_ACCOUNT);
+        assert_eq!(quote_token_mint(&TradeTokenType::SOL), SOL_TOKEN_ACCOUNT);
+        assert!(TradeFactory::capabilities(DexType::Bonk).supports_usd1);
+    }
+
+    #[test]
+    fn program_ids_cover_every_protocol() {
+        let ids = SolanaTrade::program_ids();
+        assert_eq!(ids.len(), TradeFactory::supported_dex_types().len());
+        assert_eq!(
+            ids[&DexType::RaydiumCpmm],
+            crate::instruction::utils::raydium_cpmm::accounts::RAYDIUM_CPMM
+        );
+        assert_eq!(SolanaTrade::VERSION, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn ladder_splits_evenly_and_estimates_impact() {
+        assert_eq!(ladder_tranches(10, 3), vec![4, 3, 3]);
+        assert_eq!(ladder_tranches(2, 5), vec![1, 1]);
+        assert_eq!(ladder_tranches(10, 3).iter().sum::<u64>(), 10);
+        // 1_000 into 99_000: the average fill is 1% below spot
+        assert_eq!(sell_price_impact_bps(1_000, 99_000), 100);
+        assert_eq!(sell_price_impact_bps(100_000, 100_000), 5_000);
+        assert_eq!(sell_price_impact_bps(0, 0), 0);
+    }
+
     #[test]
     fn trade_safety_accepts_bounded_values() {
         assert!(validate_trade_safety("buy", 1, None, None).is_ok());
@@ -1901,6 +3953,27 @@ mod tests {
         assert!(normalized.iter().any(|c| matches!(c.swqos_type(), SwqosType::Default)));
     }
 
+    #[tokio::test]
+    async fn missing_swqos_routes_lists_configured_routes_without_a_client() {
+        let jito = SwqosConfig::Jito("uuid".to_string(), SwqosRegion::Frankfurt, None);
+        let jito_client = SwqosConfig::get_swqos_client(
+            "https://rpc.example".to_string(),
+            solana_commitment_config::CommitmentConfig::confirmed(),
+            jito.clone(),
+            false,
+        )
+        .await
+        .unwrap();
+
+        let missing =
+            missing_swqos_routes("https://rpc.example", &[jito.clone()], &[jito_client.clone()]);
+        assert_eq!(missing, vec![SwqosType::Default]);
+        assert_eq!(
+            missing_swqos_routes("https://rpc.example", &[jito], &[]),
+            vec![SwqosType::Jito, SwqosType::Default]
+        );
+    }
+
     #[test]
     fn normalize_swqos_configs_does_not_duplicate_default_rpc_route() {
         let configs = vec![SwqosConfig::Default("https://rpc.example".to_string())];
@@ -2079,6 +4152,54 @@ mod tests {
         assert!(!low.close_mint_token_ata);
     }
 
+    #[test]
+    fn ata_flags_map_quote_and_mint_sides_by_swap_direction() {
+        let mut buy: TradeBuyParams = SimpleBuyParams::new(
+            DexType::PumpSwap,
+            TradeTokenType::USDC,
+            Pubkey::new_unique(),
+            BuyAmount::ExactInput(10_000),
+            dummy_pumpfun_params(),
+            Hash::new_unique(),
+            GasFeeStrategy::new(),
+        )
+        .into();
+        buy.create_input_token_ata = true;
+        buy.close_input_token_ata = true;
+        buy.create_mint_ata = false;
+        // Quote side (USDC here, WSOL for SOL) is the swap input; the mint is the output.
+        let flags = buy.swap_ata_flags();
+        assert!(flags.create_input_mint_ata && flags.close_input_mint_ata);
+        assert!(!flags.create_output_mint_ata && !flags.close_output_mint_ata);
+
+        let mut sell: TradeSellParams = SimpleSellParams::new(
+            DexType::PumpSwap,
+            TradeTokenType::WSOL,
+            Pubkey::new_unique(),
+            SellAmount::ExactInput(50_000),
+            dummy_pumpfun_params(),
+            Hash::new_unique(),
+            GasFeeStrategy::new(),
+        )
+        .into();
+        sell.create_output_token_ata = false;
+        sell.close_output_token_ata = true;
+        sell.close_mint_token_ata = true;
+        // The sold mint is the swap input; the quote side is the output.
+        let flags = sell.swap_ata_flags();
+        assert_eq!(
+            flags,
+            SwapAtaFlags {
+                create_input_mint_ata: false,
+                close_input_mint_ata: true,
+                create_output_mint_ata: false,
+                close_output_mint_ata: true,
+            }
+        );
+        sell.receive_native_sol = true;
+        assert!(sell.swap_ata_flags().create_output_mint_ata);
+    }
+
     #[test]
     fn simple_sell_builder_can_use_durable_nonce() {
         let nonce_account = Pubkey::new_unique();
@@ -2105,4 +4226,465 @@ mod tests {
         assert_eq!(low.durable_nonce.as_ref().and_then(|n| n.nonce_account), Some(nonce_account));
         assert_eq!(low.durable_nonce.as_ref().and_then(|n| n.current_nonce), Some(nonce_hash));
     }
+
+    #[tokio::test]
+    async fn spawn_trade_converts_panics_into_trade_errors() {
+        let ok = TradingClient::spawn_trade(async { Ok(7u32) }).await.unwrap();
+        assert_eq!(ok.unwrap(), 7);
+        let failed = TradingClient::spawn_trade(async { Err::<u32, _>(anyhow::anyhow!("boom")) })
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(failed.code, 500);
+
+        // Under `panic = "abort"` the panic below would abort the test binary instead.
+        assert_eq!(TradingClient::catches_panics(), cfg!(panic = "unwind"));
+        if !TradingClient::catches_panics() {
+            return;
+        }
+        let panicked = TradingClient::spawn_trade(async {
+            let reserves: Option<u64> = None;
+            Ok::<u64, anyhow::Error>(reserves.expect("missing reserves"))
+        })
+        .await
+        .unwrap()
+        .unwrap_err();
+        assert_eq!(panicked.code, TradeError::PANICKED);
+        assert!(panicked.message.contains("missing reserves"));
+    }
+    /// A client over `mock`, with the rent lookups client startup makes queued in front.
+    async fn mock_trading_client(
+        mock: crate::common::MockSolanaRpcClient,
+    ) -> (TradingClient, Arc<SolanaRpcClient>) {
+        use solana_rpc_client_api::request::RpcRequest;
+        let mut startup = crate::common::MockSolanaRpcClient::new();
+        // rent for both token programs, at init and on the updater's first tick
+        for _ in 0..4 {
+            startup = startup.with_response(
+                RpcRequest::GetMinimumBalanceForRentExemption,
+                serde_json::json!(2_039_280),
+            );
+        }
+        let rpc = Arc::new(startup.merge(mock).build());
+        let config = TradeConfig::builder(
+            "http://127.0.0.1:8899".to_string(),
+            vec![],
+            solana_commitment_config::CommitmentConfig::confirmed(),
+        )
+        .create_wsol_ata_on_startup(false)
+        .log_enabled(false)
+        .verbose_startup(false)
+        .build();
+        let client =
+            TradingClient::with_rpc_client(Arc::new(Keypair::new()), rpc.clone(), config).await;
+        (client, rpc)
+    }
+
+    #[tokio::test]
+    async fn with_rpc_client_trades_through_the_injected_rpc() {
+        use solana_rpc_client_api::request::RpcRequest;
+
+        let transaction = crate::test_fixtures::signed_transaction();
+        // the config's rpc_url (127.0.0.1:8899) has no node: only the mock can accept the send
+        let mock = crate::common::MockSolanaRpcClient::new().with_response(
+            RpcRequest::SendTransaction,
+            serde_json::json!(transaction.signatures[0].to_string()),
+        );
+        let (client, rpc) = mock_trading_client(mock).await;
+        assert!(Arc::ptr_eq(&client.infrastructure.rpc, &rpc));
+        let routes = &client.infrastructure.swqos_clients;
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].get_swqos_type(), SwqosType::Default);
+        routes[0].send_transaction(TradeType::Buy, &transaction, false).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn fired_prepared_trades_and_create_and_buy_reach_subscribers() {
+        use crate::common::{nonce_cache::DurableNonceInfo, MockSolanaRpcClient};
+        use crate::test_fixtures::{pumpswap_buy_params, RecordingSwqos, MINT};
+        use crate::trading::core::params::PumpFunCreateArgs;
+        use crate::trading::PreparedTrade;
+        use solana_rpc_client_api::request::RpcRequest;
+
+        let mut params = pumpswap_buy_params(
+            MockSolanaRpcClient::new()
+                .with_latest_blockhash(solana_hash::Hash::new_unique(), 1_000),
+        )
+        .await;
+        let route = RecordingSwqos::new(SwqosType::Default);
+        params.swqos_clients = Arc::new(vec![route as Arc<crate::swqos::SwqosClient>]);
+        params.durable_nonce = Some(DurableNonceInfo {
+            nonce_account: Some(Pubkey::new_unique()),
+            current_nonce: Some(solana_hash::Hash::new_unique()),
+        });
+        let prepared = PreparedTrade::prepare(params).await.unwrap();
+        let signature = prepared.signatures()[0];
+        // the client's Default route sends through the injected rpc
+        let mock = MockSolanaRpcClient::new()
+            .with_response(RpcRequest::SendTransaction, serde_json::json!(signature.to_string()));
+        let (client, _) = mock_trading_client(mock).await;
+        let mut outcomes = client.subscribe_trades();
+
+        let (ok, signatures, _, _) = client.fire_prepared(&prepared).await.unwrap();
+        assert!(ok);
+        let outcome = outcomes.try_recv().unwrap();
+        assert_eq!((outcome.protocol.as_str(), outcome.side), ("PumpSwap", "buy"));
+        assert_eq!(outcome.mint, MINT.to_string());
+        assert_eq!(outcome.signatures, vec![signatures[0].to_string()]);
+        assert!(outcome.success && outcome.landed.is_none());
+
+        let mut mint_keypair = Keypair::new();
+        while mint_keypair.pubkey().to_string().ends_with("pump") {
+            mint_keypair = Keypair::new();
+        }
+        let mint = mint_keypair.pubkey();
+        let args = PumpFunCreateArgs::new("Coin", "COIN", "https://example.com/coin.json")
+            .with_mint(mint_keypair);
+        // reported whether or not the mock accepts the send
+        let _ = client.pumpfun_create_and_buy(args, 10_000_000, Some(500)).await;
+        let outcome = outcomes.try_recv().unwrap();
+        assert_eq!((outcome.protocol.as_str(), outcome.side), ("PumpFun", "buy"));
+        assert_eq!((outcome.mint, outcome.input_amount), (mint.to_string(), 10_000_000));
+    }
+
+    #[tokio::test]
+    async fn prepare_sell_ends_with_the_wsol_close_and_splits_off_ata_creation() {
+        use crate::test_fixtures::{pumpswap_params, MINT};
+
+        // the SeparateTx pre-transaction is sent and confirmed by the mock's default responses
+        let (client, _) = mock_trading_client(crate::common::MockSolanaRpcClient::new()).await;
+        let mut gas_fee_strategy = GasFeeStrategy::new();
+        gas_fee_strategy.set_global_fee_strategy(150_000, 150_000, 1_000, 1_000, 0.0, 0.0);
+        let sell = |ata_creation| {
+            TradeSellParams::builder(
+                DexType::PumpSwap,
+                MINT,
+                3_000,
+                DexParamEnum::PumpSwap(pumpswap_params()),
+            )
+            .gas_fee_strategy(gas_fee_strategy.clone())
+            .receive_native_sol(true)
+            .ata_creation(ata_creation)
+        };
+        let err = client
+            .prepare_sell(
+                sell(AtaCreationMode::Inline).recent_blockhash(Hash::new_unique()).build(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("requires a durable nonce"));
+
+        let mut lens = Vec::new();
+        for mode in [AtaCreationMode::Inline, AtaCreationMode::SeparateTx] {
+            let nonce = DurableNonceInfo {
+                nonce_account: Some(Pubkey::new_unique()),
+                current_nonce: Some(Hash::new_unique()),
+            };
+            let prepared =
+                client.prepare_sell(sell(mode).durable_nonce(nonce).build()).await.unwrap();
+            let message = &prepared.transactions()[0].1.message;
+            let last = message.instructions().last().unwrap();
+            let keys = message.static_account_keys();
+            assert_eq!(keys[last.program_id_index as usize], crate::constants::TOKEN_PROGRAM);
+            assert_eq!(last.data, vec![9]);
+            lens.push(message.instructions().len());
+        }
+        // the output account creation went out in its own transaction
+        assert!(lens[1] < lens[0]);
+    }
+
+    #[tokio::test]
+    async fn load_sell_pool_override_checks_the_pool_pairs_mint_with_the_output() {
+        use crate::test_fixtures::{self as fx, MINT, POOL};
+
+        // two uncached loads: the mismatched quote, then SOL output through the WSOL pool
+        let mock = fx::pumpswap_pool_load_rpc().merge(fx::pumpswap_pool_load_rpc());
+        let (client, _) = mock_trading_client(mock).await;
+
+        let err = client
+            .load_sell_pool_override(DexType::PumpFun, &POOL, &MINT, SOL_TOKEN_ACCOUNT)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("only supported for PumpSwap"));
+
+        let err = client
+            .load_sell_pool_override(DexType::PumpSwap, &POOL, &MINT, USDC_TOKEN_ACCOUNT)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(&format!("expected {}/{}", MINT, USDC_TOKEN_ACCOUNT)));
+
+        let params = client
+            .load_sell_pool_override(DexType::PumpSwap, &POOL, &MINT, SOL_TOKEN_ACCOUNT)
+            .await
+            .unwrap();
+        let DexParamEnum::PumpSwap(params) = params else { panic!("expected PumpSwap params") };
+        assert_eq!((params.base_mint, params.quote_mint), (MINT, WSOL_TOKEN_ACCOUNT));
+        assert_eq!(params.pool_base_token_reserves, fx::BASE_RESERVE);
+    }
+
+    #[tokio::test]
+    async fn sweep_dust_reports_a_failed_blockhash_and_moves_on() {
+        use crate::common::mock_rpc::mock_account;
+        use crate::constants::TOKEN_PROGRAM;
+        use crate::instruction::utils::bonk::accounts;
+        use solana_rpc_client_api::request::RpcRequest;
+
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let token_account = |mint: &Pubkey| {
+            let mut data = vec![0u8; 165];
+            data[..32].copy_from_slice(mint.as_ref());
+            data[64..72].copy_from_slice(&1_000_000u64.to_le_bytes());
+            mock_account(TOKEN_PROGRAM, data)
+        };
+        let (first_ata, second_ata) = (token_account(&first), token_account(&second));
+        let mut mint_data = vec![0u8; 82];
+        mint_data[44] = 6;
+        let mint = mock_account(TOKEN_PROGRAM, mint_data);
+        let bonk_pool =
+            mock_account(accounts::BONK, crate::test_fixtures::bonk_pool_account_data());
+
+        let mut mock = crate::common::MockSolanaRpcClient::new()
+            .with_token_accounts(&[
+                (Pubkey::new_unique(), &first_ata),
+                (Pubkey::new_unique(), &second_ata),
+            ])
+            .with_token_accounts(&[])
+            .with_multiple_accounts(&[Some(&mint), Some(&mint)]);
+        // per mint: no PumpFun curve, no PumpSwap v2 or canonical pool, then the Bonk pool
+        for _ in 0..2 {
+            mock = mock
+                .with_account(None)
+                .with_account(None)
+                .with_account(None)
+                .with_account(Some(&bonk_pool))
+                .with_account(Some(&mint));
+        }
+        let mock = mock
+            .with_response(RpcRequest::GetLatestBlockhash, serde_json::Value::Null)
+            .with_latest_blockhash(solana_hash::Hash::new_unique(), 1_000);
+        let (client, _) = mock_trading_client(mock).await;
+
+        let outcomes = client.sweep_dust(0.0, GasFeeStrategy::new()).await.unwrap();
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].mint, first.to_string());
+        assert!(!outcomes[0].success);
+        assert!(outcomes[0].error.is_some() && outcomes[0].signatures.is_empty());
+        // the sweep carried on to the second position with the next blockhash
+        assert_eq!(outcomes[1].mint, second.to_string());
+    }
+
+    #[tokio::test]
+    async fn pumpfun_create_and_buy_creates_extends_then_buys_signed_by_payer_and_mint() {
+        use crate::instruction::utils::pumpfun::{
+            accounts, get_bonding_curve_pda, BUY_DISCRIMINATOR, BUY_EXACT_SOL_IN_DISCRIMINATOR,
+            CREATE_DISCRIMINATOR, EXTEND_ACCOUNT_DISCRIMINATOR,
+        };
+        use crate::trading::core::params::PumpFunCreateArgs;
+
+        let (client, _) = mock_trading_client(crate::common::MockSolanaRpcClient::new()).await;
+        let mut mint_keypair = Keypair::new();
+        while mint_keypair.pubkey().to_string().ends_with("pump") {
+            mint_keypair = Keypair::new();
+        }
+        let args = PumpFunCreateArgs::new("Coin", "COIN", "https://example.com/coin.json")
+            .with_mint(mint_keypair.insecure_clone());
+        let (transaction, mint) = client
+            .pumpfun_create_and_buy_transaction(
+                args,
+                10_000_000,
+                Some(500),
+                &crate::trading::ProtocolOverrides::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(mint, mint_keypair.pubkey());
+
+        let compute_budget_program =
+            solana_compute_budget_interface::ComputeBudgetInstruction::set_compute_unit_limit(1)
+                .program_id;
+        let message = &transaction.message;
+        let keys = message.static_account_keys();
+        assert_eq!(message.header().num_required_signatures, 2);
+        assert_eq!((keys[0], keys[1]), (client.payer.pubkey(), mint));
+        assert!(transaction.verify_with_results().iter().all(|ok| *ok));
+
+        let instructions: Vec<_> = message
+            .instructions()
+            .iter()
+            .map(|ix| (keys[ix.program_id_index as usize], ix))
+            .filter(|(program, _)| *program != compute_budget_program)
+            .collect();
+        let (create, extend, buy) =
+            (instructions[0], instructions[1], instructions[instructions.len() - 1]);
+        assert_eq!((create.0, &create.1.data[..8]), (accounts::PUMPFUN, &CREATE_DISCRIMINATOR[..]));
+        assert_eq!(
+            (extend.0, &extend.1.data[..8]),
+            (accounts::PUMPFUN, &EXTEND_ACCOUNT_DISCRIMINATOR[..])
+        );
+        assert_eq!(keys[extend.1.accounts[0] as usize], get_bonding_curve_pda(&mint).unwrap());
+        assert_eq!(buy.0, accounts::PUMPFUN);
+        assert!([BUY_DISCRIMINATOR, BUY_EXACT_SOL_IN_DISCRIMINATOR]
+            .iter()
+            .any(|d| buy.1.data[..8] == d[..]));
+        // the payer's token account is created between the extend and the buy
+        let setup = &instructions[2..instructions.len() - 1];
+        assert!(!setup.is_empty());
+        assert!(setup.iter().all(|(program, _)| *program != accounts::PUMPFUN));
+    }
+
+    #[tokio::test]
+    async fn pumpfun_create_and_buy_applies_the_program_override_to_every_instruction() {
+        use crate::instruction::utils::pumpfun::accounts;
+        use crate::trading::core::params::PumpFunCreateArgs;
+        use crate::trading::{ProtocolOverride, ProtocolOverrides};
+
+        let (client, _) = mock_trading_client(crate::common::MockSolanaRpcClient::new()).await;
+        let mut mint_keypair = Keypair::new();
+        while mint_keypair.pubkey().to_string().ends_with("pump") {
+            mint_keypair = Keypair::new();
+        }
+        let redeployed = Pubkey::new_unique();
+        let overrides = ProtocolOverrides::new()
+            .with(DexType::PumpFun, ProtocolOverride::default().with_program_id(redeployed));
+        let args = PumpFunCreateArgs::new("Coin", "COIN", "https://example.com/coin.json")
+            .with_mint(mint_keypair);
+
+        let (transaction, _) = client
+            .pumpfun_create_and_buy_transaction(args, 10_000_000, Some(500), &overrides)
+            .await
+            .unwrap();
+
+        let message = &transaction.message;
+        let keys = message.static_account_keys();
+        let programs: Vec<Pubkey> =
+            message.instructions().iter().map(|ix| keys[ix.program_id_index as usize]).collect();
+        // create, extend and buy
+        assert_eq!(programs.iter().filter(|p| **p == redeployed).count(), 3);
+        assert!(!keys.contains(&accounts::PUMPFUN));
+    }
+
+    fn meteora_ladder_params() -> TradeSellParams {
+        use crate::test_fixtures::{meteora_damm_v2_params, MINT};
+        TradeSellParams::builder(
+            DexType::MeteoraDammV2,
+            MINT,
+            1_000_000,
+            DexParamEnum::MeteoraDammV2(meteora_damm_v2_params()),
+        )
+        .recent_blockhash(solana_hash::Hash::new_unique())
+        .build()
+    }
+
+    #[tokio::test]
+    async fn sell_laddered_records_a_failed_refresh_as_the_tranche_outcome() {
+        use solana_rpc_client_api::request::RpcRequest;
+        // the Meteora vault balance read fails (unparseable response)
+        let mock = crate::common::MockSolanaRpcClient::new()
+            .with_response(RpcRequest::GetTokenAccountBalance, serde_json::Value::Null);
+        let (client, _) = mock_trading_client(mock).await;
+
+        let outcomes = client
+            .sell_laddered(meteora_ladder_params(), 3, std::time::Duration::ZERO, 10_000)
+            .await
+            .unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert!(!outcomes[0].success);
+        assert!(outcomes[0].error.is_some());
+    }
+
+    #[tokio::test]
+    async fn sell_laddered_closes_the_token_account_only_in_the_final_tranche() {
+        use crate::instruction::pumpswap::PumpSwapInstructionBuilder;
+        use crate::test_fixtures::{pumpfun_params, pumpswap_params, swap_params, MINT};
+        use crate::trading::InstructionBuilder;
+
+        let mut curve = pumpfun_params();
+        curve.close_token_account_when_sell = Some(true);
+        let pumpfun =
+            TradeSellParams::builder(DexType::PumpFun, MINT, 3_000, DexParamEnum::PumpFun(curve))
+                .close_mint_token_ata(true)
+                .build();
+        let pumpswap = TradeSellParams::builder(
+            DexType::PumpSwap,
+            MINT,
+            3_000,
+            DexParamEnum::PumpSwap(pumpswap_params()),
+        )
+        .close_mint_token_ata(true)
+        .build();
+
+        let tranches = ladder_tranches(3_000, 3);
+        let last = tranches.len() - 1;
+        let mut closes = Vec::new();
+        for (i, amount) in tranches.into_iter().enumerate() {
+            let (mut pumpfun, mut pumpswap) = (pumpfun.clone(), pumpswap.clone());
+            if i < last {
+                keep_mint_account_open(&mut pumpfun);
+                keep_mint_account_open(&mut pumpswap);
+            }
+            let DexParamEnum::PumpFun(curve) = &pumpfun.extension_params else { unreachable!() };
+            assert_eq!(curve.close_token_account_when_sell, Some(i == last));
+            assert_eq!(pumpfun.close_mint_token_ata, i == last);
+
+            let mut swap = swap_params(pumpswap.extension_params.clone(), TradeType::Sell, amount);
+            swap.close_input_mint_ata = pumpswap.swap_ata_flags().close_input_mint_ata;
+            let instructions =
+                PumpSwapInstructionBuilder.build_sell_instructions(&swap).await.unwrap();
+            // SPL Token `CloseAccount`
+            closes.push(instructions.iter().filter(|ix| ix.data == [9]).count());
+        }
+        assert_eq!(closes, vec![0, 0, 1]);
+    }
+
+    #[tokio::test]
+    async fn sell_laddered_refetches_the_multi_nonce_pool_for_each_tranche() {
+        use crate::common::mock_rpc::mock_account;
+
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let nonce_account = |value: &solana_hash::Hash| {
+            let mut data = vec![0u8; 80];
+            data[40..72].copy_from_slice(value.as_ref());
+            mock_account(crate::constants::SYSTEM_PROGRAM, data)
+        };
+        let (fresh_first, fresh_second) =
+            (solana_hash::Hash::new_unique(), solana_hash::Hash::new_unique());
+        let rpc = crate::common::MockSolanaRpcClient::new()
+            .with_account(Some(&nonce_account(&fresh_first)))
+            .with_account(Some(&nonce_account(&fresh_second)))
+            .build();
+        let stale = |account| DurableNonceInfo {
+            nonce_account: Some(account),
+            current_nonce: Some(solana_hash::Hash::new_unique()),
+        };
+        let pool = Arc::new(MultiNonceConfig::new(vec![stale(first), stale(second)]));
+        pool.mark_consumed(first);
+        let mut tranche = meteora_ladder_params();
+        tranche.recent_blockhash = None;
+        tranche.multi_nonce = Some(pool);
+
+        refresh_tranche_nonce(&rpc, &mut tranche).await.unwrap();
+
+        let refreshed = tranche.multi_nonce.unwrap();
+        let nonces: Vec<_> =
+            refreshed.nonces().iter().map(|n| (n.nonce_account, n.current_nonce)).collect();
+        assert_eq!(
+            nonces,
+            vec![(Some(first), Some(fresh_first)), (Some(second), Some(fresh_second))]
+        );
+        assert!(refreshed.consumed().is_empty());
+        assert!(tranche.recent_blockhash.is_none() && tranche.durable_nonce.is_none());
+    }
+
+    #[tokio::test]
+    async fn sell_laddered_caps_meteora_price_impact_from_the_vault_balance() {
+        // 333_334 tokens into a 1_000 token vault: far above a 1% cap
+        let mock = crate::common::MockSolanaRpcClient::new().with_token_account_balance(1_000, 6);
+        let (client, _) = mock_trading_client(mock).await;
+
+        let outcomes = client
+            .sell_laddered(meteora_ladder_params(), 3, std::time::Duration::ZERO, 100)
+            .await
+            .unwrap();
+        assert!(outcomes.is_empty());
+    }
 }
@@ -0,0 +1,331 @@
+//! Fluent builders for [`TradeBuyParams`] / [`TradeSellParams`].
+//!
+//! Builders fill defaults for anything not set, so code that builds params through them keeps
+//! compiling when a field is added. The structs are not `#[non_exhaustive]`: struct literals
+//! still break on every new field, which is why the examples use the builders.
+//! 通过 builder 构造的调用方在字段新增时不受影响；结构体字面量仍会失效。
+
+use std::sync::Arc;
+
+use solana_sdk::{
+    hash::Hash, message::AddressLookupTableAccount, pubkey::Pubkey, signature::Keypair,
+};
+
+use super::{TradeBuyParams, TradeSellParams, TradeTokenType};
+use crate::common::{
+    nonce_cache::{DurableNonceInfo, MultiNonceConfig},
+    GasFeeStrategy,
+};
+use crate::trading::core::params::{AtaCreationMode, DexParamEnum};
+use crate::trading::factory::DexType;
+use crate::trading::slippage::SlippageModel;
+
+/// Setters that forward to the wrapped params. `some` marks `Option<T>` fields set from a `T`.
+macro_rules! setters {
+    (some $($(#[$doc:meta])* $name:ident: $ty:ty),* $(,)?) => {
+        $(
+            $(#[$doc])*
+            pub fn $name(mut self, value: $ty) -> Self {
+                self.0.$name = Some(value);
+                self
+            }
+        )*
+    };
+    ($($(#[$doc:meta])* $name:ident: $ty:ty),* $(,)?) => {
+        $(
+            $(#[$doc])*
+            pub fn $name(mut self, value: $ty) -> Self {
+                self.0.$name = value;
+                self
+            }
+        )*
+    };
+}
+
+/// Builder for [`TradeBuyParams`]; created with [`TradeBuyParams::builder`].
+#[derive(Clone)]
+pub struct TradeBuyParamsBuilder(TradeBuyParams);
+
+impl TradeBuyParams {
+    /// Start a buy of `input_token_amount` (quote base units) for `mint`.
+    ///
+    /// `extension_params` has no sensible default, so it is required here. Defaults:
+    /// - pay with `SOL`, default `GasFeeStrategy`
+    /// - `wait_tx_confirmed = true`, `create_mint_ata = true`
+    /// - no input ATA create/close, no slippage override, no ALT, inline ATA creation
+    /// - no blockhash or nonce: set one with `recent_blockhash` / `durable_nonce`
+    ///
+    /// ```rust,ignore
+    /// let params = TradeBuyParams::builder(DexType::PumpSwap, mint, 100_000_000, extension_params)
+    ///     .recent_blockhash(blockhash)
+    ///     .slippage_basis_points(300)
+    ///     .build();
+    /// ```
+    pub fn builder(
+        dex_type: DexType,
+        mint: Pubkey,
+        input_token_amount: u64,
+        extension_params: DexParamEnum,
+    ) -> TradeBuyParamsBuilder {
+        TradeBuyParamsBuilder(TradeBuyParams {
+            dex_type,
+            input_token_type: TradeTokenType::SOL,
+            mint,
+            input_token_amount,
+            slippage_basis_points: None,
+            recent_blockhash: None,
+            extension_params,
+            address_lookup_table_accounts: Vec::new(),
+            wait_tx_confirmed: true,
+            wait_for_all_submits: false,
+            create_input_token_ata: false,
+            close_input_token_ata: false,
+            create_mint_ata: true,
+            durable_nonce: None,
+            fixed_output_token_amount: None,
+            gas_fee_strategy: GasFeeStrategy::new(),
+            simulate: false,
+            use_exact_sol_amount: None,
+            grpc_recv_us: None,
+            open_seed_optimize_override: None,
+            ata_creation: AtaCreationMode::Inline,
+            memo: None,
+            min_quote_liquidity: None,
+            max_ata_creations: None,
+            slippage_model: None,
+            cleanup_on_failure: false,
+            skip_compute_budget: false,
+            priority_fee_percentile: None,
+            ata_rent_funder: None,
+            mint_token_program: None,
+            auto_alt: false,
+            multi_nonce: None,
+            min_tokens_out: None,
+        })
+    }
+}
+
+impl TradeBuyParamsBuilder {
+    setters! {
+        /// Quote token to pay with (`SOL`, `WSOL`, `USDC`, `USD1`).
+        input_token_type: TradeTokenType,
+        address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
+        wait_tx_confirmed: bool,
+        wait_for_all_submits: bool,
+        create_input_token_ata: bool,
+        close_input_token_ata: bool,
+        create_mint_ata: bool,
+        gas_fee_strategy: GasFeeStrategy,
+        simulate: bool,
+        ata_creation: AtaCreationMode,
+        /// Close empty token accounts created by a separate pre-transaction if the buy fails.
+        cleanup_on_failure: bool,
+        /// Omit the SDK's compute-budget instructions; set your own CU limit/price.
+        skip_compute_budget: bool,
+        /// Attach the lookup table registered for this pool/protocol in `ALT_REGISTRY`
+        /// (empty until the caller registers tables).
+        auto_alt: bool,
+    }
+
+    setters! { some
+        /// Slippage in basis points; `100` means 1%.
+        slippage_basis_points: u64,
+        recent_blockhash: Hash,
+        /// Exact-out buy: receive this many tokens; the max input is quoted from reserves and must
+        /// fit in `input_token_amount`.
+        fixed_output_token_amount: u64,
+        use_exact_sol_amount: bool,
+        grpc_recv_us: i64,
+        open_seed_optimize_override: bool,
+        memo: String,
+        min_quote_liquidity: u64,
+        /// Absolute tokens-out floor; see `TradeBuyParams::min_tokens_out`.
+        min_tokens_out: u64,
+        max_ata_creations: usize,
+        /// Percentile (0-100) of recent prioritization fees to price this trade's CUs at.
+        priority_fee_percentile: u8,
+        /// Signer funding the rent of created token accounts; see `ata_rent_funder`.
+        ata_rent_funder: Arc<Keypair>,
+        /// Per-trade slippage model; overrides `slippage_basis_points`.
+        slippage_model: Arc<dyn SlippageModel>,
+        /// Token program of the traded mint (e.g. Token-2022); overrides `extension_params`.
+        mint_token_program: Pubkey,
+    }
+
+    /// Use a durable nonce; clears `recent_blockhash` since the nonce is the blockhash.
+    pub fn durable_nonce(mut self, value: DurableNonceInfo) -> Self {
+        self.0.durable_nonce = Some(value);
+        self.0.multi_nonce = None;
+        self.0.recent_blockhash = None;
+        self
+    }
+
+    /// Give every SWQOS route its own durable nonce from `value`; clears `durable_nonce` and
+    /// `recent_blockhash`. 每条 SWQOS 路由使用独立的 durable nonce。
+    pub fn multi_nonce(mut self, value: Arc<MultiNonceConfig>) -> Self {
+        self.0.multi_nonce = Some(value);
+        self.0.durable_nonce = None;
+        self.0.recent_blockhash = None;
+        self
+    }
+
+    pub fn build(self) -> TradeBuyParams {
+        self.0
+    }
+}
+
+/// Builder for [`TradeSellParams`]; created with [`TradeSellParams::builder`].
+#[derive(Clone)]
+pub struct TradeSellParamsBuilder(TradeSellParams);
+
+impl TradeSellParams {
+    /// Start a sell of `input_token_amount` (token base units) of `mint`.
+    ///
+    /// Defaults: receive `SOL`, `with_tip = true`, `wait_tx_confirmed = true`, no ATA
+    /// create/close, default `GasFeeStrategy`, no blockhash or nonce (set one).
+    pub fn builder(
+        dex_type: DexType,
+        mint: Pubkey,
+        input_token_amount: u64,
+        extension_params: DexParamEnum,
+    ) -> TradeSellParamsBuilder {
+        TradeSellParamsBuilder(TradeSellParams {
+            dex_type,
+            output_token_type: TradeTokenType::SOL,
+            mint,
+            input_token_amount,
+            slippage_basis_points: None,
+            recent_blockhash: None,
+            with_tip: true,
+            extension_params,
+            address_lookup_table_accounts: Vec::new(),
+            wait_tx_confirmed: true,
+            wait_for_all_submits: false,
+            create_output_token_ata: false,
+            close_output_token_ata: false,
+            close_mint_token_ata: false,
+            durable_nonce: None,
+            fixed_output_token_amount: None,
+            gas_fee_strategy: GasFeeStrategy::new(),
+            simulate: false,
+            grpc_recv_us: None,
+            open_seed_optimize_override: None,
+            ata_creation: AtaCreationMode::Inline,
+            pool_override: None,
+            memo: None,
+            max_ata_creations: None,
+            slippage_model: None,
+            skip_compute_budget: false,
+            priority_fee_percentile: None,
+            ata_rent_funder: None,
+            mint_token_program: None,
+            auto_alt: false,
+            receive_native_sol: false,
+            multi_nonce: None,
+        })
+    }
+}
+
+impl TradeSellParamsBuilder {
+    setters! {
+        /// Quote token to receive (`SOL`, `WSOL`, `USDC`, `USD1`).
+        output_token_type: TradeTokenType,
+        with_tip: bool,
+        address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
+        wait_tx_confirmed: bool,
+        wait_for_all_submits: bool,
+        create_output_token_ata: bool,
+        close_output_token_ata: bool,
+        close_mint_token_ata: bool,
+        gas_fee_strategy: GasFeeStrategy,
+        simulate: bool,
+        ata_creation: AtaCreationMode,
+        /// Omit the SDK's compute-budget instructions; set your own CU limit/price.
+        skip_compute_budget: bool,
+        /// Attach the lookup table registered for this pool/protocol in `ALT_REGISTRY`
+        /// (empty until the caller registers tables).
+        auto_alt: bool,
+        /// Close the WSOL account last so proceeds arrive as native SOL.
+        receive_native_sol: bool,
+    }
+
+    setters! { some
+        /// Slippage in basis points; `100` means 1%.
+        slippage_basis_points: u64,
+        recent_blockhash: Hash,
+        /// Exact-out sell: receive this much quote; the max input is quoted from reserves and must
+        /// fit in `input_token_amount`.
+        fixed_output_token_amount: u64,
+        grpc_recv_us: i64,
+        open_seed_optimize_override: bool,
+        /// Sell through this PumpSwap pool instead of the one in `extension_params`.
+        pool_override: Pubkey,
+        memo: String,
+        max_ata_creations: usize,
+        /// Percentile (0-100) of recent prioritization fees to price this trade's CUs at.
+        priority_fee_percentile: u8,
+        /// Signer funding the rent of created token accounts; see `ata_rent_funder`.
+        ata_rent_funder: Arc<Keypair>,
+        /// Per-trade slippage model; overrides `slippage_basis_points`.
+        slippage_model: Arc<dyn SlippageModel>,
+        /// Token program of the traded mint (e.g. Token-2022); overrides `extension_params`.
+        mint_token_program: Pubkey,
+    }
+
+    /// Use a durable nonce; clears `recent_blockhash` since the nonce is the blockhash.
+    pub fn durable_nonce(mut self, value: DurableNonceInfo) -> Self {
+        self.0.durable_nonce = Some(value);
+        self.0.multi_nonce = None;
+        self.0.recent_blockhash = None;
+        self
+    }
+
+    /// Give every SWQOS route its own durable nonce from `value`; clears `durable_nonce` and
+    /// `recent_blockhash`. 每条 SWQOS 路由使用独立的 durable nonce。
+    pub fn multi_nonce(mut self, value: Arc<MultiNonceConfig>) -> Self {
+        self.0.multi_nonce = Some(value);
+        self.0.durable_nonce = None;
+        self.0.recent_blockhash = None;
+        self
+    }
+
+    pub fn build(self) -> TradeSellParams {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::core::params::MeteoraDammV2Params;
+
+    fn meteora_params() -> DexParamEnum {
+        let k = Pubkey::new_unique;
+        DexParamEnum::MeteoraDammV2(MeteoraDammV2Params::new(k(), k(), k(), k(), k(), k(), k()))
+    }
+
+    #[test]
+    fn builder_fills_defaults_and_nonce_clears_blockhash() {
+        let mint = Pubkey::new_unique();
+        let params = TradeBuyParams::builder(DexType::MeteoraDammV2, mint, 1_000, meteora_params())
+            .recent_blockhash(Hash::new_unique())
+            .slippage_basis_points(300)
+            .build();
+        assert!(params.wait_tx_confirmed && params.create_mint_ata);
+        assert!(params.input_token_type == TradeTokenType::SOL);
+        assert_eq!(params.slippage_basis_points, Some(300));
+        assert!(params.recent_blockhash.is_some());
+
+        let nonce = DurableNonceInfo {
+            nonce_account: Some(Pubkey::new_unique()),
+            current_nonce: Some(Hash::new_unique()),
+        };
+        let params =
+            TradeSellParams::builder(DexType::MeteoraDammV2, mint, 1_000, meteora_params())
+                .recent_blockhash(Hash::new_unique())
+                .durable_nonce(nonce)
+                .build();
+        assert!(params.recent_blockhash.is_none() && params.durable_nonce.is_some());
+        assert!(params.with_tip);
+    }
+}
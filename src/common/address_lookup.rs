@@ -1,5 +1,7 @@
 use crate::common::SolanaRpcClient;
 use anyhow::Result;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
 use solana_message::AddressLookupTableAccount;
 use solana_sdk::pubkey::Pubkey;
 
@@ -35,3 +37,147 @@ pub async fn fetch_address_lookup_table_account(
         AddressLookupTableAccount { key: *lookup_table_address, addresses };
     Ok(address_lookup_table_account)
 }
+
+/// Lookup tables to attach automatically when a trade sets `auto_alt`, keyed by pool address or
+/// by protocol program id. 按池子 / 协议程序自动附加的地址查找表。
+///
+/// The SDK ships no table addresses: register the ones your protocol or provider publishes (or
+/// your own) once at startup. Tables are fetched on first use and then cached for the life of the
+/// process, with no check for deactivation or later extension; call [`Self::invalidate`] after
+/// changing a table so the next trade refetches it. 查找表首次使用时加载并永久缓存，变更后需手动失效。
+#[derive(Default)]
+pub struct AltRegistry {
+    tables_by_key: DashMap<Pubkey, Pubkey>,
+    loaded: DashMap<Pubkey, AddressLookupTableAccount>,
+}
+
+impl AltRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `lookup_table` for trades on `key` (a pool address, or a program id for every pool
+    /// of that protocol). A pool entry wins over its program's.
+    /// Replacing a key's table drops the cached copy of the old one unless another key still
+    /// uses it.
+    pub fn register(&self, key: Pubkey, lookup_table: Pubkey) {
+        if let Some(replaced) = self.tables_by_key.insert(key, lookup_table) {
+            if replaced != lookup_table {
+                self.evict_if_unused(&replaced);
+            }
+        }
+    }
+
+    pub fn unregister(&self, key: &Pubkey) -> Option<Pubkey> {
+        let (_, table) = self.tables_by_key.remove(key)?;
+        self.evict_if_unused(&table);
+        Some(table)
+    }
+
+    fn evict_if_unused(&self, table: &Pubkey) {
+        if !self.tables_by_key.iter().any(|entry| entry.value() == table) {
+            self.loaded.remove(table);
+        }
+    }
+
+    /// Table address for `pool`, else for `program`.
+    pub fn lookup(&self, pool: Option<&Pubkey>, program: &Pubkey) -> Option<Pubkey> {
+        pool.and_then(|pool| self.tables_by_key.get(pool))
+            .or_else(|| self.tables_by_key.get(program))
+            .map(|entry| *entry)
+    }
+
+    /// Drop the cached copy of `lookup_table` so the next [`Self::resolve`] fetches it again
+    /// (e.g. after extending or deactivating it). Returns whether a copy was cached.
+    pub fn invalidate(&self, lookup_table: &Pubkey) -> bool {
+        self.loaded.remove(lookup_table).is_some()
+    }
+
+    /// The registered table for `pool` / `program`, fetched via RPC on first use and served from
+    /// the cache afterwards (see [`Self::invalidate`]). `Ok(None)` when nothing is registered.
+    pub async fn resolve(
+        &self,
+        rpc: &SolanaRpcClient,
+        pool: Option<&Pubkey>,
+        program: &Pubkey,
+    ) -> Result<Option<AddressLookupTableAccount>> {
+        let Some(table) = self.lookup(pool, program) else {
+            return Ok(None);
+        };
+        if let Some(loaded) = self.loaded.get(&table) {
+            return Ok(Some(loaded.clone()));
+        }
+        let account = fetch_address_lookup_table_account(rpc, &table).await?;
+        self.loaded.insert(table, account.clone());
+        Ok(Some(account))
+    }
+}
+
+/// Registry consulted by `TradingClient` for trades with `auto_alt` set. Starts empty, so
+/// `auto_alt` attaches nothing until you call `ALT_REGISTRY.register(pool_or_program, table)` at
+/// startup for each table to use. Program keys are the protocol's program id after
+/// `set_protocol_overrides`. Loaded tables stay cached until [`AltRegistry::invalidate`] or the
+/// key is re-registered to another table. 初始为空；已加载的查找表不会自动刷新。
+pub static ALT_REGISTRY: Lazy<AltRegistry> = Lazy::new(AltRegistry::new);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::MockSolanaRpcClient;
+    use solana_sdk::account::Account;
+
+    #[tokio::test]
+    async fn pool_entries_win_over_program_entries_and_tables_are_cached() {
+        let (pool, program) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (pool_table, program_table) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let registry = AltRegistry::new();
+        assert_eq!(registry.lookup(Some(&pool), &program), None);
+
+        registry.register(program, program_table);
+        assert_eq!(registry.lookup(Some(&pool), &program), Some(program_table));
+        registry.register(pool, pool_table);
+        assert_eq!(registry.lookup(Some(&pool), &program), Some(pool_table));
+        assert_eq!(registry.lookup(None, &program), Some(program_table));
+
+        let entry = Pubkey::new_unique();
+        let mut data = vec![0u8; 56];
+        data.extend_from_slice(entry.as_ref());
+        let account = Account { lamports: 1, data, ..Default::default() };
+        let rpc = MockSolanaRpcClient::new().with_account(Some(&account)).build();
+        let table = registry.resolve(&rpc, Some(&pool), &program).await.unwrap().unwrap();
+        assert_eq!((table.key, table.addresses), (pool_table, vec![entry]));
+        assert!(registry.loaded.contains_key(&pool_table));
+
+        // cached: no response is queued for a second fetch
+        let cached = registry.resolve(&rpc, Some(&pool), &program).await.unwrap().unwrap();
+        assert_eq!(cached.addresses, vec![entry]);
+
+        // the replaced table is evicted, the new one is fetched on next use
+        let new_table = Pubkey::new_unique();
+        registry.register(pool, new_table);
+        assert!(!registry.loaded.contains_key(&pool_table));
+        assert_eq!(registry.lookup(Some(&pool), &program), Some(new_table));
+        let new_entry = Pubkey::new_unique();
+        let mut data = vec![0u8; 56];
+        data.extend_from_slice(new_entry.as_ref());
+        let account = Account { lamports: 1, data, ..Default::default() };
+        let rpc = MockSolanaRpcClient::new().with_account(Some(&account)).build();
+        let table = registry.resolve(&rpc, Some(&pool), &program).await.unwrap().unwrap();
+        assert_eq!((table.key, table.addresses), (new_table, vec![new_entry]));
+        assert!(registry.loaded.contains_key(&new_table));
+
+        // invalidating drops the cached copy; registering again under the same key keeps it
+        assert!(registry.invalidate(&new_table));
+        assert!(!registry.invalidate(&new_table));
+        registry.loaded.insert(new_table, cached.clone());
+        registry.register(pool, new_table);
+        assert!(registry.loaded.contains_key(&new_table));
+
+        // a table still registered under another key stays cached
+        registry.register(program, new_table);
+        registry.unregister(&pool);
+        assert!(registry.loaded.contains_key(&new_table));
+        registry.unregister(&program);
+        assert!(!registry.loaded.contains_key(&new_table));
+    }
+}
@@ -0,0 +1,123 @@
+//! Mint decimals cache, read once per mint and reused (amount formatting, price display).
+//! 代币精度缓存：每个 mint 只读取一次链上数据。
+
+use anyhow::anyhow;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::SolanaRpcClient;
+use crate::constants::{
+    SOL_TOKEN_ACCOUNT, USD1_TOKEN_ACCOUNT, USDC_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT,
+};
+
+/// Offset of `decimals` in an SPL Token / Token-2022 mint account (after the mint authority
+/// option and supply).
+const MINT_DECIMALS_OFFSET: usize = 44;
+
+/// Decimals keyed by mint. Decimals never change after a mint is created, so entries don't
+/// expire.
+pub struct DecimalsCache {
+    entries: DashMap<Pubkey, u8>,
+}
+
+impl Default for DecimalsCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DecimalsCache {
+    /// Pre-seeded with SOL/WSOL (9), USDC (6) and USD1 (6).
+    pub fn new() -> Self {
+        let entries = DashMap::new();
+        entries.insert(SOL_TOKEN_ACCOUNT, 9);
+        entries.insert(WSOL_TOKEN_ACCOUNT, 9);
+        entries.insert(USDC_TOKEN_ACCOUNT, 6);
+        entries.insert(USD1_TOKEN_ACCOUNT, 6);
+        Self { entries }
+    }
+
+    /// Cached decimals for `mint`, without RPC.
+    pub fn get(&self, mint: &Pubkey) -> Option<u8> {
+        self.entries.get(mint).map(|entry| *entry)
+    }
+
+    /// Seed `mint` (e.g. from a create event) so it is never fetched.
+    pub fn insert(&self, mint: Pubkey, decimals: u8) {
+        self.entries.insert(mint, decimals);
+    }
+
+    /// Cached decimals, or read the mint account once and cache them.
+    pub async fn resolve(&self, rpc: &SolanaRpcClient, mint: &Pubkey) -> anyhow::Result<u8> {
+        if let Some(decimals) = self.get(mint) {
+            return Ok(decimals);
+        }
+        let account = rpc.get_account(mint).await?;
+        let decimals = *account
+            .data
+            .get(MINT_DECIMALS_OFFSET)
+            .ok_or_else(|| anyhow!("{} is not a token mint (data too short)", mint))?;
+        self.insert(*mint, decimals);
+        Ok(decimals)
+    }
+
+    /// Resolve every uncached mint in `mints` with batched `getMultipleAccounts` calls (100 per
+    /// call); afterwards [`Self::get`] answers for all of them. Missing mints are an error.
+    pub async fn prefetch(&self, rpc: &SolanaRpcClient, mints: &[Pubkey]) -> anyhow::Result<()> {
+        let missing: Vec<Pubkey> =
+            mints.iter().filter(|m| self.get(m).is_none()).copied().collect();
+        for chunk in missing.chunks(100) {
+            let accounts = rpc.get_multiple_accounts(chunk).await?;
+            for (mint, account) in chunk.iter().zip(accounts) {
+                let account = account.ok_or_else(|| anyhow!("mint {} not found", mint))?;
+                let decimals = *account
+                    .data
+                    .get(MINT_DECIMALS_OFFSET)
+                    .ok_or_else(|| anyhow!("{} is not a token mint (data too short)", mint))?;
+                self.insert(*mint, decimals);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Process-wide cache behind `TradingClient::decimals`.
+pub static DECIMALS_CACHE: Lazy<DecimalsCache> = Lazy::new(DecimalsCache::new);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::MockSolanaRpcClient;
+    use solana_sdk::account::Account;
+
+    #[tokio::test]
+    async fn known_mints_are_seeded_and_others_are_read_once() {
+        let cache = DecimalsCache::new();
+        assert_eq!(cache.get(&WSOL_TOKEN_ACCOUNT), Some(9));
+        assert_eq!(cache.get(&USDC_TOKEN_ACCOUNT), Some(6));
+
+        let mint = Pubkey::new_unique();
+        let mut data = vec![0u8; 82];
+        data[MINT_DECIMALS_OFFSET] = 5;
+        let account =
+            Account { data, owner: crate::constants::TOKEN_PROGRAM, ..Account::default() };
+        let rpc = MockSolanaRpcClient::new().with_account(Some(&account)).build();
+
+        assert_eq!(cache.get(&mint), None);
+        assert_eq!(cache.resolve(&rpc, &mint).await.unwrap(), 5);
+        assert_eq!(cache.get(&mint), Some(5));
+
+        let not_a_mint = Account { data: vec![0; 8], ..Account::default() };
+        let rpc = MockSolanaRpcClient::new().with_account(Some(&not_a_mint)).build();
+        assert!(cache.resolve(&rpc, &Pubkey::new_unique()).await.is_err());
+    }
+}
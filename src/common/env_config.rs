@@ -0,0 +1,228 @@
+//! Build a [`TradeConfig`] from environment variables (container / CI deployments).
+//!
+//! | Variable | Required | Format / default |
+//! |---|---|---|
+//! | `RPC_URL` | yes | RPC HTTP endpoint |
+//! | `COMMITMENT` | no | `processed` / `confirmed` / `finalized` (default `confirmed`) |
+//! | `SWQOS` | no | `;`-separated providers, each `provider\|api_token\|region\|custom_url` |
+//! | `SWQOS_MEV_PROTECTION` | no | bool (default `false`) |
+//! | `SWQOS_CORES_FROM_END` | no | bool (default `false`) |
+//! | `SWQOS_CHECK_MIN_TIP` | no | bool (default `false`) |
+//! | `USE_SEED_OPTIMIZE` | no | bool (default `true`) |
+//! | `LOG_ENABLED` | no | bool (default `true`) |
+//! | `VERBOSE_STARTUP` | no | bool (default `true`) |
+//! | `CREATE_WSOL_ATA_ON_STARTUP` | no | bool (default `true`) |
+//!
+//! Only `provider` is mandatory inside a `SWQOS` entry; `region` defaults to `default` and an
+//! empty `custom_url` means the built-in endpoint. Transport-selecting variants use their default
+//! transport. Example:
+//!
+//! ```text
+//! SWQOS="jito||frankfurt;helius|KEY|newyork;bloxroute|TOKEN|default|https://my.proxy/submit"
+//! ```
+
+use crate::common::TradeConfig;
+use crate::swqos::{SwqosConfig, SwqosRegion};
+use anyhow::{anyhow, Result};
+use solana_commitment_config::CommitmentConfig;
+
+pub const ENV_RPC_URL: &str = "RPC_URL";
+pub const ENV_COMMITMENT: &str = "COMMITMENT";
+pub const ENV_SWQOS: &str = "SWQOS";
+pub const ENV_SWQOS_MEV_PROTECTION: &str = "SWQOS_MEV_PROTECTION";
+pub const ENV_SWQOS_CORES_FROM_END: &str = "SWQOS_CORES_FROM_END";
+pub const ENV_SWQOS_CHECK_MIN_TIP: &str = "SWQOS_CHECK_MIN_TIP";
+pub const ENV_USE_SEED_OPTIMIZE: &str = "USE_SEED_OPTIMIZE";
+pub const ENV_LOG_ENABLED: &str = "LOG_ENABLED";
+pub const ENV_VERBOSE_STARTUP: &str = "VERBOSE_STARTUP";
+pub const ENV_CREATE_WSOL_ATA_ON_STARTUP: &str = "CREATE_WSOL_ATA_ON_STARTUP";
+
+impl TradeConfig {
+    /// Build from process environment variables; see [`crate::common::env_config`] for the list.
+    pub fn from_env() -> Result<TradeConfig> {
+        Self::from_env_with(|key| std::env::var(key).ok())
+    }
+
+    /// Same as [`Self::from_env`] with a custom lookup (e.g. a map in tests or a secrets store).
+    pub fn from_env_with<F>(lookup: F) -> Result<TradeConfig>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let var = |key: &str| lookup(key).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+        let rpc_url = var(ENV_RPC_URL).ok_or_else(|| anyhow!("{} is not set", ENV_RPC_URL))?;
+        if !rpc_url.starts_with("http://") && !rpc_url.starts_with("https://") {
+            return Err(anyhow!("{} must be an http(s) URL, got {}", ENV_RPC_URL, rpc_url));
+        }
+        let commitment = match var(ENV_COMMITMENT) {
+            Some(v) => parse_commitment(&v)?,
+            None => CommitmentConfig::confirmed(),
+        };
+        let swqos_configs = match var(ENV_SWQOS) {
+            Some(v) => parse_swqos_list(&v)?,
+            None => Vec::new(),
+        };
+        let flag = |key: &str, default: bool| -> Result<bool> {
+            match var(key) {
+                Some(v) => parse_bool(key, &v),
+                None => Ok(default),
+            }
+        };
+
+        Ok(TradeConfig::builder(rpc_url, swqos_configs, commitment)
+            .mev_protection(flag(ENV_SWQOS_MEV_PROTECTION, false)?)
+            .swqos_cores_from_end(flag(ENV_SWQOS_CORES_FROM_END, false)?)
+            .check_min_tip(flag(ENV_SWQOS_CHECK_MIN_TIP, false)?)
+            .use_seed_optimize(flag(ENV_USE_SEED_OPTIMIZE, true)?)
+            .log_enabled(flag(ENV_LOG_ENABLED, true)?)
+            .verbose_startup(flag(ENV_VERBOSE_STARTUP, true)?)
+            .create_wsol_ata_on_startup(flag(ENV_CREATE_WSOL_ATA_ON_STARTUP, true)?)
+            .build())
+    }
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(anyhow!("{} must be a boolean, got {}", key, value)),
+    }
+}
+
+fn parse_commitment(value: &str) -> Result<CommitmentConfig> {
+    match value.to_ascii_lowercase().as_str() {
+        "processed" => Ok(CommitmentConfig::processed()),
+        "confirmed" => Ok(CommitmentConfig::confirmed()),
+        "finalized" => Ok(CommitmentConfig::finalized()),
+        _ => Err(anyhow!(
+            "{} must be processed, confirmed or finalized, got {}",
+            ENV_COMMITMENT,
+            value
+        )),
+    }
+}
+
+fn parse_region(value: &str) -> Result<SwqosRegion> {
+    let region = match value.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+        "" | "default" => SwqosRegion::Default,
+        "newyork" | "ny" => SwqosRegion::NewYork,
+        "frankfurt" => SwqosRegion::Frankfurt,
+        "amsterdam" => SwqosRegion::Amsterdam,
+        "dublin" => SwqosRegion::Dublin,
+        "slc" => SwqosRegion::SLC,
+        "tokyo" => SwqosRegion::Tokyo,
+        "singapore" => SwqosRegion::Singapore,
+        "london" => SwqosRegion::London,
+        "losangeles" | "la" => SwqosRegion::LosAngeles,
+        _ => return Err(anyhow!("Unknown SWQOS region: {}", value)),
+    };
+    Ok(region)
+}
+
+/// Parse one `provider|api_token|region|custom_url` entry.
+fn parse_swqos_entry(entry: &str) -> Result<SwqosConfig> {
+    let mut fields = entry.splitn(4, '|').map(str::trim);
+    let provider = fields.next().unwrap_or_default().to_ascii_lowercase();
+    let token = fields.next().unwrap_or_default().to_string();
+    let region = parse_region(fields.next().unwrap_or_default())?;
+    let url = fields.next().filter(|u| !u.is_empty()).map(str::to_string);
+
+    let config = match provider.as_str() {
+        "default" | "rpc" => {
+            // Default carries its own URL in the token slot.
+            let url = url.unwrap_or(token);
+            if url.is_empty() {
+                return Err(anyhow!("SWQOS entry {:?}: default provider needs a URL", entry));
+            }
+            SwqosConfig::Default(url)
+        }
+        "jito" => SwqosConfig::Jito(token, region, url),
+        "nextblock" => SwqosConfig::NextBlock(token, region, url),
+        "bloxroute" => SwqosConfig::Bloxroute(token, region, url),
+        "temporal" => SwqosConfig::Temporal(token, region, url),
+        "zeroslot" => SwqosConfig::ZeroSlot(token, region, url),
+        "node1" => SwqosConfig::Node1(token, region, url, None),
+        "flashblock" => SwqosConfig::FlashBlock(token, region, url),
+        "blockrazor" => SwqosConfig::BlockRazor(token, region, url, None),
+        "astralane" => SwqosConfig::Astralane(token, region, url, None),
+        "stellium" => SwqosConfig::Stellium(token, region, url),
+        "lightspeed" => SwqosConfig::Lightspeed(token, region, url),
+        "soyas" => SwqosConfig::Soyas(token, region, url),
+        "speedlanding" => SwqosConfig::Speedlanding(token, region, url),
+        "helius" => SwqosConfig::Helius(token, region, url, None),
+        "solami" => SwqosConfig::Solami(token, region, url),
+        "lunarlander" => SwqosConfig::LunarLander(token, region, url, None),
+        "glaive" => SwqosConfig::Glaive(token, region, url, None),
+        "" => return Err(anyhow!("SWQOS entry {:?} has no provider", entry)),
+        other => return Err(anyhow!("Unknown SWQOS provider: {}", other)),
+    };
+    Ok(config)
+}
+
+/// Parse the `SWQOS` list; duplicate providers are rejected.
+fn parse_swqos_list(value: &str) -> Result<Vec<SwqosConfig>> {
+    let mut configs: Vec<SwqosConfig> = Vec::new();
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let config = parse_swqos_entry(entry)?;
+        if configs.iter().any(|c| c.swqos_type() == config.swqos_type()) {
+            return Err(anyhow!("SWQOS provider {:?} listed more than once", config.swqos_type()));
+        }
+        configs.push(config);
+    }
+    Ok(configs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config_from(vars: &[(&str, &str)]) -> Result<TradeConfig> {
+        let map: HashMap<String, String> =
+            vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        TradeConfig::from_env_with(|key| map.get(key).cloned())
+    }
+
+    #[test]
+    fn from_env_parses_swqos_list_and_flags() {
+        let config = config_from(&[
+            (ENV_RPC_URL, "https://rpc.example"),
+            (ENV_COMMITMENT, "processed"),
+            (ENV_SWQOS, "jito||frankfurt; helius|KEY|newyork ;bloxroute|T|default|https://x/y"),
+            (ENV_SWQOS_MEV_PROTECTION, "true"),
+            (ENV_USE_SEED_OPTIMIZE, "0"),
+            (ENV_VERBOSE_STARTUP, "false"),
+        ])
+        .unwrap();
+
+        assert_eq!(config.rpc_url, "https://rpc.example");
+        assert_eq!(config.commitment, CommitmentConfig::processed());
+        assert_eq!(
+            config.swqos_configs,
+            vec![
+                SwqosConfig::Jito(String::new(), SwqosRegion::Frankfurt, None),
+                SwqosConfig::Helius("KEY".into(), SwqosRegion::NewYork, None, None),
+                SwqosConfig::Bloxroute(
+                    "T".into(),
+                    SwqosRegion::Default,
+                    Some("https://x/y".into())
+                ),
+            ]
+        );
+        assert!(config.mev_protection);
+        assert!(!config.use_seed_optimize);
+        assert!(config.log_enabled);
+        assert!(!config.verbose_startup);
+    }
+
+    #[test]
+    fn from_env_rejects_missing_rpc_and_bad_values() {
+        assert!(config_from(&[]).is_err());
+        assert!(config_from(&[(ENV_RPC_URL, "rpc.example")]).is_err());
+        assert!(config_from(&[(ENV_RPC_URL, "https://r"), (ENV_COMMITMENT, "fast")]).is_err());
+        assert!(config_from(&[(ENV_RPC_URL, "https://r"), (ENV_SWQOS, "nope|k")]).is_err());
+        assert!(config_from(&[(ENV_RPC_URL, "https://r"), (ENV_SWQOS, "jito||mars")]).is_err());
+        assert!(config_from(&[(ENV_RPC_URL, "https://r"), (ENV_SWQOS, "jito;jito")]).is_err());
+        assert!(config_from(&[(ENV_RPC_URL, "https://r"), (ENV_LOG_ENABLED, "maybe")]).is_err());
+    }
+}
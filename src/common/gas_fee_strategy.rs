@@ -1,7 +1,12 @@
 use crate::swqos::{SwqosType, TradeType};
 use arc_swap::ArcSwap;
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use tracing::warn;
+
+/// `max_cu_price` sentinel for "no ceiling".
+const NO_CU_PRICE_CEILING: u64 = u64::MAX;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum GasFeeStrategyType {
@@ -36,11 +41,50 @@ pub struct GasFeeStrategyValue {
 pub struct GasFeeStrategy {
     strategies:
         Arc<ArcSwap<HashMap<(SwqosType, TradeType, GasFeeStrategyType), GasFeeStrategyValue>>>,
+    /// Ceiling applied to every `cu_price` written; `NO_CU_PRICE_CEILING` when unset.
+    max_cu_price: Arc<AtomicU64>,
 }
 
 impl GasFeeStrategy {
     pub fn new() -> Self {
-        Self { strategies: Arc::new(ArcSwap::from_pointee(HashMap::new())) }
+        Self {
+            strategies: Arc::new(ArcSwap::from_pointee(HashMap::new())),
+            max_cu_price: Arc::new(AtomicU64::new(NO_CU_PRICE_CEILING)),
+        }
+    }
+
+    /// 设置优先费上限：之后写入（`set*` / `update_*_cu_price`）的 cu_price 以及已有策略都被截断到上限。
+    /// Cap every configured or dynamically updated `cu_price` at `max_cu_price` (micro-lamports
+    /// per CU), including strategies already set; `None` removes the ceiling. Clamping is logged.
+    pub fn set_max_cu_price(&self, max_cu_price: Option<u64>) {
+        let ceiling = max_cu_price.unwrap_or(NO_CU_PRICE_CEILING);
+        self.max_cu_price.store(ceiling, Ordering::Relaxed);
+        if max_cu_price.is_none() {
+            return;
+        }
+        self.strategies.rcu(|current_map| {
+            let mut new_map = (**current_map).clone();
+            for value in new_map.values_mut() {
+                value.cu_price = self.clamp_cu_price(value.cu_price);
+            }
+            Arc::new(new_map)
+        });
+    }
+
+    /// Current `cu_price` ceiling, if any.
+    pub fn max_cu_price(&self) -> Option<u64> {
+        let ceiling = self.max_cu_price.load(Ordering::Relaxed);
+        (ceiling != NO_CU_PRICE_CEILING).then_some(ceiling)
+    }
+
+    #[inline]
+    fn clamp_cu_price(&self, cu_price: u64) -> u64 {
+        let ceiling = self.max_cu_price.load(Ordering::Relaxed);
+        if cu_price > ceiling {
+            warn!(target: "sol_trade_sdk", "cu_price {} clamped to max_cu_price {}", cu_price, ceiling);
+            return ceiling;
+        }
+        cu_price
     }
 
     /// 设置全局费率策略
@@ -262,6 +306,7 @@ impl GasFeeStrategy {
         } else {
             self.del(swqos_type, trade_type, GasFeeStrategyType::Normal);
         }
+        let cu_price = self.clamp_cu_price(cu_price);
         self.strategies.rcu(|current_map| {
             let mut new_map = (**current_map).clone();
             new_map.insert(
@@ -325,6 +370,43 @@ impl GasFeeStrategy {
         result
     }
 
+    /// 返回把 `trade_type` 中 cu_limit 为 0（未设置）的策略填为 `cu_limit` 的独立副本，原策略不变。
+    /// Detached copy whose unset (`cu_limit == 0`) `trade_type` strategies use `cu_limit`;
+    /// `None` when every strategy already has a limit.
+    pub fn fill_unset_cu_limit(&self, trade_type: TradeType, cu_limit: u32) -> Option<Self> {
+        let current = self.strategies.load();
+        if !current.iter().any(|((_, t, _), v)| *t == trade_type && v.cu_limit == 0) {
+            return None;
+        }
+        let mut filled = (**current).clone();
+        for ((_, t, _), v) in filled.iter_mut() {
+            if *t == trade_type && v.cu_limit == 0 {
+                v.cu_limit = cu_limit;
+            }
+        }
+        Some(Self {
+            strategies: Arc::new(ArcSwap::from_pointee(filled)),
+            max_cu_price: self.max_cu_price.clone(),
+        })
+    }
+
+    /// 返回 `trade_type` 全部策略 cu_price 设为 `cu_price`（受上限截断）的独立副本，原策略不变。
+    /// Detached copy whose `trade_type` strategies all use `cu_price` (clamped to
+    /// `max_cu_price`); the original is unchanged.
+    pub fn with_cu_price(&self, trade_type: TradeType, cu_price: u64) -> Self {
+        let cu_price = self.clamp_cu_price(cu_price);
+        let mut priced = (**self.strategies.load()).clone();
+        for ((_, t, _), v) in priced.iter_mut() {
+            if *t == trade_type {
+                v.cu_price = cu_price;
+            }
+        }
+        Self {
+            strategies: Arc::new(ArcSwap::from_pointee(priced)),
+            max_cu_price: self.max_cu_price.clone(),
+        }
+    }
+
     /// 清空所有策略。
     /// Clear all strategies
     pub fn clear(&self) {
@@ -383,6 +465,7 @@ impl GasFeeStrategy {
         strategy_type: GasFeeStrategyType,
         buy_cu_price: u64,
     ) {
+        let buy_cu_price = self.clamp_cu_price(buy_cu_price);
         self.strategies.rcu(|current_map| {
             let mut new_map = (**current_map).clone();
             for ((_swqos_type, trade_type, s_type), value) in new_map.iter_mut() {
@@ -406,6 +489,7 @@ impl GasFeeStrategy {
         strategy_type: GasFeeStrategyType,
         sell_cu_price: u64,
     ) {
+        let sell_cu_price = self.clamp_cu_price(sell_cu_price);
         self.strategies.rcu(|current_map| {
             let mut new_map = (**current_map).clone();
             for ((_swqos_type, trade_type, s_type), value) in new_map.iter_mut() {
@@ -509,6 +593,62 @@ mod tests {
         assert_eq!(high_tip_low_cu.tip, 0.005);
     }
 
+    #[test]
+    fn with_cu_price_reprices_a_detached_copy_of_one_side() {
+        let strategy = GasFeeStrategy::new();
+        strategy.set_global_fee_strategy(200_000, 150_000, 1_000, 2_000, 0.001, 0.001);
+        strategy.set_max_cu_price(Some(50_000));
+
+        let priced = strategy.with_cu_price(TradeType::Buy, 80_000);
+        assert!(priced.get_strategies(TradeType::Buy).iter().all(|(_, _, v)| v.cu_price == 50_000));
+        assert!(priced.get_strategies(TradeType::Sell).iter().all(|(_, _, v)| v.cu_price == 2_000));
+        assert!(strategy
+            .get_strategies(TradeType::Buy)
+            .iter()
+            .all(|(_, _, v)| v.cu_price == 1_000));
+    }
+
+    #[test]
+    fn max_cu_price_clamps_configured_and_dynamic_prices() {
+        let strategy = GasFeeStrategy::new();
+        let normal = GasFeeStrategyType::Normal;
+        strategy.set(SwqosType::Jito, TradeType::Buy, normal, 0, 300_000, 0.001);
+        strategy.set(SwqosType::Jito, TradeType::Sell, normal, 100_000, 50_000, 0.001);
+        assert_eq!(strategy.max_cu_price(), None);
+
+        // existing strategies above the ceiling are clamped, lower ones untouched
+        strategy.set_max_cu_price(Some(200_000));
+        let buy = |s: &GasFeeStrategy| {
+            find_strategy(
+                &s.get_strategies(TradeType::Buy),
+                SwqosType::Jito,
+                GasFeeStrategyType::Normal,
+            )
+        };
+        assert_eq!(buy(&strategy).cu_price, 200_000);
+        let sell = find_strategy(
+            &strategy.get_strategies(TradeType::Sell),
+            SwqosType::Jito,
+            GasFeeStrategyType::Normal,
+        );
+        assert_eq!(sell.cu_price, 50_000);
+
+        // a dynamic estimate during a fee spike
+        strategy.update_buy_cu_price(5_000_000);
+        assert_eq!(buy(&strategy).cu_price, 200_000);
+        strategy.update_buy_cu_price(150_000);
+        assert_eq!(buy(&strategy).cu_price, 150_000);
+
+        // detached copies keep the ceiling
+        let filled = strategy.fill_unset_cu_limit(TradeType::Buy, 100_000).unwrap();
+        filled.update_buy_cu_price(9_999_999);
+        assert_eq!(buy(&filled).cu_price, 200_000);
+
+        strategy.set_max_cu_price(None);
+        strategy.update_buy_cu_price(5_000_000);
+        assert_eq!(buy(&strategy).cu_price, 5_000_000);
+    }
+
     #[test]
     fn default_rpc_strategy_uses_priority_fee_without_tip() {
         let strategy = GasFeeStrategy::new();
@@ -0,0 +1,206 @@
+//! Programmable in-process RPC for tests (feature `test-util`). 测试用的可编程 RPC。
+//!
+//! [`SolanaRpcClient`] already dispatches every request through the `RpcSender` trait, so the
+//! mock plugs in there and yields a real `SolanaRpcClient`: executor, builders and pool loaders
+//! run unchanged, no network and no funds required.
+//!
+//! Responses are queued per RPC method and served in order; once a method's queue is empty the
+//! built-in defaults of `MockSender` are used (e.g. simulation succeeds, blockhash is a fixed
+//! value). Responses are matched by method only, not by argument, so queue `get_account_info`
+//! results in the order the code under test fetches them.
+//!
+//! ```ignore
+//! let rpc = MockSolanaRpcClient::new()
+//!     .with_latest_blockhash(Hash::new_unique(), 1_000)
+//!     .with_simulation(None, vec!["Program log: ok".into()], 42_000)
+//!     .build();
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_hash::Hash;
+use solana_rpc_client::mock_sender::MocksMap;
+use solana_rpc_client_api::request::RpcRequest;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::common::SolanaRpcClient;
+
+/// Slot reported in every mocked response context.
+pub const MOCK_SLOT: u64 = 1;
+
+/// Builder for a mock-backed [`SolanaRpcClient`].
+#[derive(Debug, Default, Clone)]
+pub struct MockSolanaRpcClient {
+    mocks: HashMap<RpcRequest, VecDeque<Value>>,
+}
+
+impl MockSolanaRpcClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a raw JSON-RPC `result` for `request` (escape hatch for methods without a helper).
+    pub fn with_response(mut self, request: RpcRequest, result: Value) -> Self {
+        self.mocks.entry(request).or_default().push_back(result);
+        self
+    }
+
+    /// Queue a `getLatestBlockhash` response.
+    pub fn with_latest_blockhash(self, blockhash: Hash, last_valid_block_height: u64) -> Self {
+        self.with_response(
+            RpcRequest::GetLatestBlockhash,
+            with_context(json!({
+                "blockhash": blockhash.to_string(),
+                "lastValidBlockHeight": last_valid_block_height,
+            })),
+        )
+    }
+
+    /// Queue a `getAccountInfo` response; `None` means the account does not exist.
+    pub fn with_account(self, account: Option<&Account>) -> Self {
+        self.with_response(RpcRequest::GetAccountInfo, with_context(account_json(account)))
+    }
+
+    /// Queue a `getMultipleAccounts` response, one entry per requested key in order.
+    pub fn with_multiple_accounts(self, accounts: &[Option<&Account>]) -> Self {
+        let values: Vec<Value> = accounts.iter().map(|a| account_json(*a)).collect();
+        self.with_response(RpcRequest::GetMultipleAccounts, with_context(json!(values)))
+    }
+
+    /// Queue a `simulateTransaction` response. `err` is the JSON form of a `TransactionError`
+    /// (e.g. `json!({"InstructionError": [2, {"Custom": 6004}]})`), `None` for success.
+    pub fn with_simulation(
+        self,
+        err: Option<Value>,
+        logs: Vec<String>,
+        units_consumed: u64,
+    ) -> Self {
+        self.with_response(
+            RpcRequest::SimulateTransaction,
+            with_context(json!({
+                "err": err,
+                "logs": logs,
+                "accounts": null,
+                "unitsConsumed": units_consumed,
+                "returnData": null,
+            })),
+        )
+    }
+
+    /// Queue a `getTokenAccountBalance` response.
+    pub fn with_token_account_balance(self, amount: u64, decimals: u8) -> Self {
+        let ui_amount = amount as f64 / 10f64.powi(decimals as i32);
+        self.with_response(
+            RpcRequest::GetTokenAccountBalance,
+            with_context(json!({
+                "amount": amount.to_string(),
+                "decimals": decimals,
+                "uiAmount": ui_amount,
+                "uiAmountString": ui_amount.to_string(),
+            })),
+        )
+    }
+
+    /// Queue a `getTokenAccountsByOwner` response listing `(token_account, account)` pairs.
+    pub fn with_token_accounts(self, accounts: &[(Pubkey, &Account)]) -> Self {
+        let value: Vec<Value> = accounts
+            .iter()
+            .map(|(pubkey, account)| {
+                json!({ "pubkey": pubkey.to_string(), "account": account_json(Some(account)) })
+            })
+            .collect();
+        self.with_response(RpcRequest::GetTokenAccountsByOwner, with_context(json!(value)))
+    }
+
+    /// Queue a `getFeeForMessage` response (lamports for the whole message).
+    pub fn with_fee_for_message(self, lamports: u64) -> Self {
+        self.with_response(RpcRequest::GetFeeForMessage, with_context(json!(lamports)))
+    }
+
+    /// Queue a `getSignatureStatuses` response, one entry per requested signature in order:
+    /// `true` is a successful tx confirmed at [`MOCK_SLOT`], `false` a signature the cluster has
+    /// not seen. Without a queued response every signature reports as finalized.
+    pub fn with_signature_statuses(self, landed: &[bool]) -> Self {
+        let statuses: Vec<Value> = landed
+            .iter()
+            .map(|landed| {
+                if *landed {
+                    json!({
+                        "slot": MOCK_SLOT,
+                        "confirmations": null,
+                        "err": null,
+                        "status": { "Ok": null },
+                        "confirmationStatus": "confirmed",
+                    })
+                } else {
+                    Value::Null
+                }
+            })
+            .collect();
+        self.with_response(RpcRequest::GetSignatureStatuses, with_context(json!(statuses)))
+    }
+
+    /// Append `other`'s queued responses after this builder's, method by method.
+    pub fn merge(mut self, other: MockSolanaRpcClient) -> Self {
+        for (request, responses) in other.mocks {
+            self.mocks.entry(request).or_default().extend(responses);
+        }
+        self
+    }
+
+    /// Build the client. The URL `"succeeds"` selects `MockSender`'s successful defaults.
+    pub fn build(self) -> SolanaRpcClient {
+        let mocks: MocksMap = self.mocks;
+        SolanaRpcClient::new_mock_with_mocks_map("succeeds".to_string(), mocks)
+    }
+}
+
+fn account_json(account: Option<&Account>) -> Value {
+    match account {
+        Some(account) => json!({
+            "lamports": account.lamports,
+            "data": [
+                base64::engine::general_purpose::STANDARD.encode(&account.data),
+                "base64"
+            ],
+            "owner": account.owner.to_string(),
+            "executable": account.executable,
+            "rentEpoch": account.rent_epoch,
+            "space": account.data.len(),
+        }),
+        None => Value::Null,
+    }
+}
+
+/// Convenience: a funded, rent-exempt-looking account owned by `owner`.
+pub fn mock_account(owner: Pubkey, data: Vec<u8>) -> Account {
+    Account { lamports: 1_000_000_000, data, owner, executable: false, rent_epoch: 0 }
+}
+
+fn with_context(value: Value) -> Value {
+    json!({ "context": { "slot": MOCK_SLOT }, "value": value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn serves_queued_responses_in_order() {
+        let blockhash = Hash::new_unique();
+        let owner = Pubkey::new_unique();
+        let rpc = MockSolanaRpcClient::new()
+            .with_latest_blockhash(blockhash, 7)
+            .with_account(Some(&mock_account(owner, vec![1, 2, 3])))
+            .with_token_account_balance(1_500_000, 6)
+            .build();
+
+        assert_eq!(rpc.get_latest_blockhash().await.unwrap(), blockhash);
+        let account = rpc.get_account(&Pubkey::new_unique()).await.unwrap();
+        assert_eq!((account.owner, account.data), (owner, vec![1, 2, 3]));
+        let balance = rpc.get_token_account_balance(&Pubkey::new_unique()).await.unwrap();
+        assert_eq!((balance.amount.as_str(), balance.decimals), ("1500000", 6));
+    }
+}
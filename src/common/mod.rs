@@ -1,12 +1,19 @@
 pub mod address_lookup;
 pub mod bonding_curve;
 pub mod clock;
+pub mod decimals_cache;
+pub mod env_config;
 pub mod fast_fn;
 pub mod fast_timing;
 pub mod gas_fee_strategy;
 pub mod global;
 pub mod keypair;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock_rpc;
 pub mod nonce_cache;
+pub mod positions;
+pub mod priority_fee;
+pub mod rpc_client;
 pub mod sdk_log;
 pub mod seed;
 pub mod spl_associated_token_account;
@@ -16,4 +23,7 @@ pub mod subscription_handle;
 pub mod types;
 
 pub use gas_fee_strategy::*;
+#[cfg(any(test, feature = "test-util"))]
+pub use mock_rpc::MockSolanaRpcClient;
+pub use rpc_client::RpcClientConfig;
 pub use types::*;
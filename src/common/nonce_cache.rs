@@ -1,4 +1,18 @@
+//! Durable nonce helpers.
+//!
+//! A durable nonce account must not be shared by concurrent traders: each nonce value can be
+//! consumed by exactly one transaction, so two builds using the same value race and one always
+//! fails. Within a process, [`claim_nonce`] detects this and rejects the second build with
+//! `TradeError::NONCE_CONTENTION`; separate processes cannot see each other's claims, so give
+//! every process (and every bot) its own nonce account.
+//! 持久 nonce 账户不可被并发共享；同进程内的冲突由 [`claim_nonce`] 检测。
+
+use std::collections::HashSet;
+
 use crate::common::SolanaRpcClient;
+use crate::swqos::common::TradeError;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use solana_hash::Hash;
 use solana_sdk::pubkey::Pubkey;
 use tracing::error;
@@ -12,6 +26,96 @@ pub struct DurableNonceInfo {
     pub current_nonce: Option<Hash>,
 }
 
+/// Nonce values currently used by an in-flight build/submit in this process.
+static IN_FLIGHT_NONCES: Lazy<Mutex<HashSet<(Pubkey, Hash)>>> =
+    Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Held while a trade builds and submits with a nonce value; releases the claim on drop.
+#[must_use]
+pub struct NonceClaim {
+    key: Option<(Pubkey, Hash)>,
+}
+
+impl Drop for NonceClaim {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            IN_FLIGHT_NONCES.lock().remove(&key);
+        }
+    }
+}
+
+/// Claim `info`'s nonce value for one in-flight trade. Fails with
+/// `TradeError::NONCE_CONTENTION` when another trade in this process (any client sharing the
+/// nonce account) is already using the same value. Incomplete info claims nothing.
+pub fn claim_nonce(info: &DurableNonceInfo) -> Result<NonceClaim, TradeError> {
+    let (Some(account), Some(nonce)) = (info.nonce_account, info.current_nonce) else {
+        return Ok(NonceClaim { key: None });
+    };
+    if !IN_FLIGHT_NONCES.lock().insert((account, nonce)) {
+        return Err(TradeError::nonce_contention(&account, &nonce));
+    }
+    Ok(NonceClaim { key: Some((account, nonce)) })
+}
+
+/// A pool of durable nonce accounts for submitting through several SWQOS routes with one
+/// nonce each: every route signs an independent transaction, so more than one can land
+/// (normally all routes share one nonce value and exactly one wins). Nonces are assigned to
+/// routes in order; a trade fails before submitting when there are fewer nonces than routes.
+/// 多 nonce 并行提交：每个通道独立 nonce，可多笔同时落地。
+pub struct MultiNonceConfig {
+    nonces: Vec<DurableNonceInfo>,
+    consumed: Mutex<Vec<Pubkey>>,
+}
+
+impl MultiNonceConfig {
+    pub fn new(nonces: Vec<DurableNonceInfo>) -> Self {
+        Self { nonces, consumed: Mutex::new(Vec::new()) }
+    }
+
+    /// Read the current value of each nonce account; fails if any cannot be read.
+    pub async fn fetch(
+        rpc: &SolanaRpcClient,
+        nonce_accounts: &[Pubkey],
+    ) -> Result<Self, anyhow::Error> {
+        let mut nonces = Vec::with_capacity(nonce_accounts.len());
+        for account in nonce_accounts {
+            nonces.push(
+                fetch_nonce_info(rpc, *account)
+                    .await
+                    .ok_or_else(|| anyhow::anyhow!("failed to read nonce account {}", account))?,
+            );
+        }
+        Ok(Self::new(nonces))
+    }
+
+    pub fn nonces(&self) -> &[DurableNonceInfo] {
+        &self.nonces
+    }
+
+    /// Nonce for the `index`-th route of a submit.
+    pub fn nonce_for_route(&self, index: usize) -> Option<&DurableNonceInfo> {
+        self.nonces.get(index)
+    }
+
+    /// Claim every nonce value in the pool (see [`claim_nonce`]).
+    pub(crate) fn claim_all(&self) -> Result<Vec<NonceClaim>, TradeError> {
+        self.nonces.iter().map(claim_nonce).collect()
+    }
+
+    pub(crate) fn mark_consumed(&self, nonce_account: Pubkey) {
+        let mut consumed = self.consumed.lock();
+        if !consumed.contains(&nonce_account) {
+            consumed.push(nonce_account);
+        }
+    }
+
+    /// Nonce accounts whose value went out in a transaction that a route accepted or that
+    /// landed: they are (or soon will be) advanced, so re-fetch them before reuse.
+    pub fn consumed(&self) -> Vec<Pubkey> {
+        self.consumed.lock().clone()
+    }
+}
+
 /// Fetch nonce information using RPC
 pub async fn fetch_nonce_info(
     rpc: &SolanaRpcClient,
@@ -39,3 +143,50 @@ pub async fn fetch_nonce_info(
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_nonce_value_cannot_be_claimed_twice_while_in_flight() {
+        let info = DurableNonceInfo {
+            nonce_account: Some(Pubkey::new_unique()),
+            current_nonce: Some(Hash::new_unique()),
+        };
+        let claim = claim_nonce(&info).unwrap();
+        let err = claim_nonce(&info).err().unwrap();
+        assert_eq!(err.code, TradeError::NONCE_CONTENTION);
+
+        // a fresh value (nonce advanced) is independent
+        let advanced = DurableNonceInfo { current_nonce: Some(Hash::new_unique()), ..info.clone() };
+        assert!(claim_nonce(&advanced).is_ok());
+
+        drop(claim);
+        assert!(claim_nonce(&info).is_ok());
+        let empty = DurableNonceInfo { nonce_account: None, current_nonce: None };
+        let _a = claim_nonce(&empty).unwrap();
+        let _b = claim_nonce(&empty).unwrap();
+    }
+
+    #[test]
+    fn multi_nonce_claims_the_whole_pool_and_tracks_consumed_accounts() {
+        let nonce = || DurableNonceInfo {
+            nonce_account: Some(Pubkey::new_unique()),
+            current_nonce: Some(Hash::new_unique()),
+        };
+        let pool = MultiNonceConfig::new(vec![nonce(), nonce()]);
+        let claims = pool.claim_all().unwrap();
+        assert_eq!(claims.len(), 2);
+        let err = claim_nonce(pool.nonce_for_route(1).unwrap()).err().unwrap();
+        assert_eq!(err.code, TradeError::NONCE_CONTENTION);
+        assert!(pool.claim_all().is_err());
+        drop(claims);
+        assert!(pool.nonce_for_route(2).is_none());
+
+        let second = pool.nonce_for_route(1).unwrap().nonce_account.unwrap();
+        pool.mark_consumed(second);
+        pool.mark_consumed(second);
+        assert_eq!(pool.consumed(), vec![second]);
+    }
+}
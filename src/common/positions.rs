@@ -0,0 +1,175 @@
+//! Open positions of a wallet, read from its SPL Token / Token-2022 accounts.
+//! 钱包持仓：枚举 token 账户并过滤非零余额。
+
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_rpc_client_api::{
+    config::RpcAccountInfoConfig,
+    request::RpcRequest,
+    response::{Response, RpcKeyedAccount},
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::common::decimals_cache::DECIMALS_CACHE;
+use crate::common::SolanaRpcClient;
+use crate::constants::{
+    TOKEN_PROGRAM, TOKEN_PROGRAM_2022, USD1_TOKEN_ACCOUNT, USDC_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT,
+};
+
+/// `mint` (0..32) and `amount` (64..72) share the same offsets in Token and Token-2022 accounts.
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+/// One nonzero token balance. 单个持仓。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub mint: Pubkey,
+    /// Token account holding the balance (ATA or seed-derived).
+    pub token_account: Pubkey,
+    /// Raw amount in base units.
+    pub amount: u64,
+    /// `amount / 10^decimals`.
+    pub ui_amount: f64,
+    pub decimals: u8,
+    pub token_program: Pubkey,
+}
+
+/// Quote-side mints (WSOL, USDC, USD1) left out unless `include_quote` is set.
+#[inline]
+pub(crate) fn is_quote_mint(mint: &Pubkey) -> bool {
+    *mint == WSOL_TOKEN_ACCOUNT || *mint == USDC_TOKEN_ACCOUNT || *mint == USD1_TOKEN_ACCOUNT
+}
+
+/// Nonzero token balances of `owner` across both token programs: one
+/// `getTokenAccountsByOwner` per program, plus one `getMultipleAccounts` for mints whose
+/// decimals are not cached yet.
+pub async fn fetch_positions(
+    rpc: &SolanaRpcClient,
+    owner: &Pubkey,
+    include_quote: bool,
+) -> Result<Vec<Position>, anyhow::Error> {
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(rpc.commitment()),
+        ..Default::default()
+    };
+    let mut positions = Vec::new();
+    for token_program in [TOKEN_PROGRAM, TOKEN_PROGRAM_2022] {
+        let response: Response<Vec<RpcKeyedAccount>> = rpc
+            .send(
+                RpcRequest::GetTokenAccountsByOwner,
+                serde_json::json!([
+                    owner.to_string(),
+                    { "programId": token_program.to_string() },
+                    config,
+                ]),
+            )
+            .await?;
+        for keyed in response.value {
+            let Some(account) = UiAccount::decode::<Account>(&keyed.account) else { continue };
+            let Some(amount) = account
+                .data
+                .get(TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)
+                .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+            else {
+                continue;
+            };
+            let mint = Pubkey::try_from(&account.data[..32])?;
+            if amount == 0 || (!include_quote && is_quote_mint(&mint)) {
+                continue;
+            }
+            positions.push(Position {
+                mint,
+                token_account: keyed.pubkey.parse()?,
+                amount,
+                ui_amount: 0.0,
+                decimals: 0,
+                token_program,
+            });
+        }
+    }
+
+    let mut mints: Vec<Pubkey> = Vec::with_capacity(positions.len());
+    for position in &positions {
+        if !mints.contains(&position.mint) {
+            mints.push(position.mint);
+        }
+    }
+    DECIMALS_CACHE.prefetch(rpc, &mints).await?;
+    for position in &mut positions {
+        let decimals = DECIMALS_CACHE
+            .get(&position.mint)
+            .ok_or_else(|| anyhow::anyhow!("mint {} not found", position.mint))?;
+        position.decimals = decimals;
+        position.ui_amount = position.amount as f64 / 10f64.powi(decimals as i32);
+    }
+    Ok(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::MockSolanaRpcClient;
+
+    fn token_account(mint: &Pubkey, amount: u64, program: Pubkey) -> Account {
+        let mut data = vec![0u8; 165];
+        data[..32].copy_from_slice(mint.as_ref());
+        data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]
+            .copy_from_slice(&amount.to_le_bytes());
+        Account { data, owner: program, lamports: 2_039_280, ..Account::default() }
+    }
+
+    #[tokio::test]
+    async fn positions_skip_empty_and_quote_accounts_by_default() {
+        let owner = Pubkey::new_unique();
+        let (held, emptied, token_2022) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (held_ata, token_2022_ata) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let legacy = [
+            (held_ata, token_account(&held, 1_500_000, TOKEN_PROGRAM)),
+            (Pubkey::new_unique(), token_account(&emptied, 0, TOKEN_PROGRAM)),
+            (Pubkey::new_unique(), token_account(&WSOL_TOKEN_ACCOUNT, 10, TOKEN_PROGRAM)),
+        ];
+        let t22 = [(token_2022_ata, token_account(&token_2022, 42, TOKEN_PROGRAM_2022))];
+        let mint_with_decimals = |decimals: u8| {
+            let mut data = vec![0u8; 82];
+            data[44] = decimals;
+            Account { data, owner: TOKEN_PROGRAM, ..Account::default() }
+        };
+        let (mint_6, mint_0) = (mint_with_decimals(6), mint_with_decimals(0));
+        let mock = || {
+            MockSolanaRpcClient::new()
+                .with_token_accounts(&legacy.iter().map(|(k, a)| (*k, a)).collect::<Vec<_>>())
+                .with_token_accounts(&t22.iter().map(|(k, a)| (*k, a)).collect::<Vec<_>>())
+        };
+
+        // uncached mints are fetched in first-seen order
+        let rpc = mock().with_multiple_accounts(&[Some(&mint_6), Some(&mint_0)]).build();
+        let mut positions = fetch_positions(&rpc, &owner, false).await.unwrap();
+        positions.sort_by_key(|p| p.amount);
+        assert_eq!(
+            positions,
+            vec![
+                Position {
+                    mint: token_2022,
+                    token_account: token_2022_ata,
+                    amount: 42,
+                    ui_amount: 42.0,
+                    decimals: 0,
+                    token_program: TOKEN_PROGRAM_2022,
+                },
+                Position {
+                    mint: held,
+                    token_account: held_ata,
+                    amount: 1_500_000,
+                    ui_amount: 1.5,
+                    decimals: 6,
+                    token_program: TOKEN_PROGRAM,
+                },
+            ]
+        );
+
+        // decimals are cached now; WSOL is seeded
+        let positions = fetch_positions(&mock().build(), &owner, true).await.unwrap();
+        assert_eq!(positions.len(), 3);
+        assert!(positions.iter().any(|p| p.mint == WSOL_TOKEN_ACCOUNT && p.ui_amount == 1e-8));
+    }
+}
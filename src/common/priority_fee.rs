@@ -0,0 +1,64 @@
+//! Priority fee from recent prioritization fees (`getRecentPrioritizationFees`) at a chosen
+//! percentile, e.g. p90 for a snipe and p50 for a patient trade. 按分位数估算近期优先费。
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::SolanaRpcClient;
+
+/// Rejects percentiles above 100.
+pub fn validate_percentile(percentile: u8) -> Result<()> {
+    if percentile > 100 {
+        return Err(anyhow!("priority_fee_percentile must be 0-100, got {}", percentile));
+    }
+    Ok(())
+}
+
+/// Nearest-rank `percentile` of `fees` (0 = lowest, 100 = highest); `0` when empty.
+pub fn fee_at_percentile(mut fees: Vec<u64>, percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+    fees.sort_unstable();
+    let rank = (percentile.min(100) as usize * fees.len()).div_ceil(100);
+    fees[rank.clamp(1, fees.len()) - 1]
+}
+
+/// `cu_price` (micro-lamports per CU) paid at `percentile` over the recent slots the node
+/// reports for transactions writing `accounts` (all transactions when empty).
+pub async fn estimate_priority_fee(
+    rpc: &SolanaRpcClient,
+    accounts: &[Pubkey],
+    percentile: u8,
+) -> Result<u64> {
+    validate_percentile(percentile)?;
+    let recent = rpc.get_recent_prioritization_fees(accounts).await?;
+    Ok(fee_at_percentile(recent.into_iter().map(|f| f.prioritization_fee).collect(), percentile))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::MockSolanaRpcClient;
+    use serde_json::json;
+    use solana_rpc_client_api::request::RpcRequest;
+
+    #[tokio::test]
+    async fn estimate_picks_the_requested_percentile_of_recent_fees() {
+        let fees: Vec<_> =
+            (1..=10u64).map(|i| json!({ "slot": i, "prioritizationFee": i * 1_000 })).collect();
+        let rpc = MockSolanaRpcClient::new()
+            .with_response(RpcRequest::GetRecentPrioritizationFees, json!(fees.clone()))
+            .with_response(RpcRequest::GetRecentPrioritizationFees, json!(fees))
+            .build();
+        let pool = [Pubkey::new_unique()];
+
+        assert_eq!(estimate_priority_fee(&rpc, &pool, 90).await.unwrap(), 9_000);
+        assert_eq!(estimate_priority_fee(&rpc, &pool, 50).await.unwrap(), 5_000);
+        assert!(estimate_priority_fee(&rpc, &pool, 101).await.is_err());
+
+        assert_eq!(fee_at_percentile(vec![7, 3], 0), 3);
+        assert_eq!(fee_at_percentile(vec![7, 3], 100), 7);
+        assert_eq!(fee_at_percentile(Vec::new(), 90), 0);
+    }
+}
@@ -0,0 +1,121 @@
+//! HTTP options for the main RPC client: extra headers (API keys, bearer tokens) and timeout.
+//! 主 RPC 客户端的 HTTP 配置：自定义请求头与超时。
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::http_sender::HttpSender;
+
+use crate::common::SolanaRpcClient;
+
+/// Request timeout used when [`RpcClientConfig::timeout`] is unset (same as the Solana client).
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Headers and timeout applied to every request of the SDK's RPC client, for providers that
+/// authenticate via headers instead of a key in the URL. SWQOS endpoints are not affected.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct RpcClientConfig {
+    /// Sent with every request, e.g. `("x-api-key", "...")` or `("Authorization", "Bearer ...")`.
+    pub headers: HashMap<String, String>,
+    /// Per-request timeout. `None` = [`DEFAULT_RPC_TIMEOUT`].
+    pub timeout: Option<Duration>,
+}
+
+impl RpcClientConfig {
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn with_bearer_token(self, token: impl AsRef<str>) -> Self {
+        self.with_header("Authorization", format!("Bearer {}", token.as_ref()))
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// True when nothing differs from the stock Solana client.
+    pub fn is_default(&self) -> bool {
+        self.headers.is_empty() && self.timeout.is_none()
+    }
+
+    fn header_map(&self) -> Result<HeaderMap> {
+        let mut map = HeaderMap::with_capacity(self.headers.len());
+        for (name, value) in &self.headers {
+            let header = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| anyhow!("invalid RPC header name {:?}: {}", name, e))?;
+            let mut value = HeaderValue::from_str(value)
+                .map_err(|e| anyhow!("invalid value for RPC header {:?}: {}", name, e))?;
+            value.set_sensitive(true);
+            map.insert(header, value);
+        }
+        Ok(map)
+    }
+
+    /// Build the RPC client for `url`. Fails on header names/values HTTP does not allow.
+    pub fn build_client(&self, url: &str, commitment: CommitmentConfig) -> Result<SolanaRpcClient> {
+        if self.is_default() {
+            return Ok(SolanaRpcClient::new_with_commitment(url.to_string(), commitment));
+        }
+        let client = reqwest::Client::builder()
+            .default_headers(self.header_map()?)
+            .timeout(self.timeout.unwrap_or(DEFAULT_RPC_TIMEOUT))
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build()?;
+        Ok(SolanaRpcClient::new_sender(
+            HttpSender::new_with_client(url.to_string(), client),
+            solana_rpc_client::rpc_client::RpcClientConfig::with_commitment(commitment),
+        ))
+    }
+}
+
+/// Header values are redacted: they usually carry credentials.
+impl std::fmt::Debug for RpcClientConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<&String> = self.headers.keys().collect();
+        names.sort();
+        f.debug_struct("RpcClientConfig")
+            .field("headers", &names)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl Hash for RpcClientConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut headers: Vec<(&String, &String)> = self.headers.iter().collect();
+        headers.sort();
+        headers.hash(state);
+        self.timeout.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headers_are_validated_and_redacted() {
+        let config = RpcClientConfig::default()
+            .with_header("x-api-key", "secret-key")
+            .with_bearer_token("token")
+            .with_timeout(Duration::from_secs(5));
+        let map = config.header_map().unwrap();
+        assert_eq!(map["x-api-key"], "secret-key");
+        assert_eq!(map["authorization"], "Bearer token");
+        assert!(map["x-api-key"].is_sensitive());
+        assert!(!format!("{:?}", config).contains("secret-key"));
+
+        let bad = RpcClientConfig::default().with_header("bad header", "v");
+        assert!(bad.build_client("http://localhost:8899", CommitmentConfig::confirmed()).is_err());
+        assert!(config
+            .build_client("http://localhost:8899", CommitmentConfig::confirmed())
+            .is_ok());
+    }
+}
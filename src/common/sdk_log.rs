@@ -64,6 +64,37 @@ pub fn set_sdk_log_enabled(enabled: bool) {
     SDK_LOG_ENABLED.store(enabled, Ordering::Relaxed);
 }
 
+static VERBOSE_STARTUP: AtomicBool = AtomicBool::new(true);
+
+/// Whether startup status lines are echoed to stderr (TradeConfig.verbose_startup, default true).
+#[inline]
+pub fn verbose_startup() -> bool {
+    VERBOSE_STARTUP.load(Ordering::Relaxed)
+}
+
+/// Set the startup echo switch (called from TradingClient::new; call before
+/// `TradingInfrastructure::new` when building infrastructure directly).
+pub fn set_verbose_startup(enabled: bool) {
+    VERBOSE_STARTUP.store(enabled, Ordering::Relaxed);
+}
+
+/// Startup/init status line (RPC, rent, SWQOS routes, WSOL ATA). Emitted through `tracing`
+/// (target `sol_trade_sdk`) when SDK logs are enabled, and echoed to stderr as ` [SDK] ...` when
+/// verbose startup is on, so init problems show up even without a tracing subscriber.
+/// Messages are plain English without emoji so log aggregators index them cleanly.
+pub fn startup_log(level: tracing::Level, args: fmt::Arguments<'_>) {
+    if sdk_log_enabled() {
+        match level {
+            tracing::Level::ERROR => tracing::error!(target: "sol_trade_sdk", "{}", args),
+            tracing::Level::WARN => tracing::warn!(target: "sol_trade_sdk", "{}", args),
+            _ => tracing::info!(target: "sol_trade_sdk", "{}", args),
+        }
+    }
+    if verbose_startup() {
+        eprintln!(" [SDK] {}", args);
+    }
+}
+
 /// Aligned log: ` [Soyas        ] Buy submitted: 13.936 µs`. Call only when sdk_log_enabled().
 #[inline]
 pub fn log_swqos_submitted(provider: &str, trade_type: impl fmt::Display, elapsed: Duration) {
@@ -69,6 +69,24 @@ fn derive_seed_from_mint(mint: &Pubkey) -> String {
     seed
 }
 
+/// Cached rent for a 165-byte token account of `token_program` (default before the first update).
+pub fn token_account_rent(token_program: &Pubkey) -> u64 {
+    let is_2022_token = token_program == &crate::constants::TOKEN_PROGRAM_2022;
+
+    // 🚀 优化：原子读取租金缓存
+    // Relaxed: 租金值不变，无需同步；Release/Acquire 在 update_rents 保证初始化可见性
+    let v = if is_2022_token {
+        SPL_TOKEN_2022_RENT.load(Ordering::Relaxed)
+    } else {
+        SPL_TOKEN_RENT.load(Ordering::Relaxed)
+    };
+    if v == u64::MAX {
+        DEFAULT_TOKEN_ACCOUNT_RENT
+    } else {
+        v
+    }
+}
+
 pub fn create_associated_token_account_use_seed(
     payer: &Pubkey,
     owner: &Pubkey,
@@ -76,37 +94,20 @@ pub fn create_associated_token_account_use_seed(
     token_program: &Pubkey,
 ) -> Result<Vec<Instruction>, anyhow::Error> {
     let is_2022_token = token_program == &crate::constants::TOKEN_PROGRAM_2022;
-
-    // 🚀 优化：原子读取租金缓存
-    // Relaxed: 租金值不变，无需同步；Release/Acquire 在 update_rents 保证初始化可见性
-    let rent = if is_2022_token {
-        let v = SPL_TOKEN_2022_RENT.load(Ordering::Relaxed);
-        if v == u64::MAX {
-            DEFAULT_TOKEN_ACCOUNT_RENT
-        } else {
-            v
-        }
-    } else {
-        let v = SPL_TOKEN_RENT.load(Ordering::Relaxed);
-        if v == u64::MAX {
-            DEFAULT_TOKEN_ACCOUNT_RENT
-        } else {
-            v
-        }
-    };
+    let rent = token_account_rent(token_program);
 
     let seed = derive_seed_from_mint(mint);
     // 🔧 修复：使用传入的 token_program 生成地址（支持 Token 和 Token-2022）
     // 买入和卖出只要都使用事件中的 token_program，地址自然一致
-    let ata_like = Pubkey::create_with_seed(payer, &seed, token_program)?;
+    let ata_like = Pubkey::create_with_seed(owner, &seed, token_program)?;
 
     let len = 165;
-    // 🔧 修复：create_account_with_seed 的第3个参数必须是 payer（与第92行生成地址时使用的 base 一致）
-    // 否则创建的账户地址与 ata_like 不匹配，导致 initializeAccount3 失败
+    // 🔧 修复：create_account_with_seed 的第3个参数必须是 owner（与生成地址时使用的 base 一致）
+    // 否则创建的账户地址与 ata_like 不匹配，导致 initializeAccount3 失败；租金由 payer 支付
     let create_acc = system_instruction::create_account_with_seed(
         payer,
         &ata_like,
-        payer,
+        owner,
         &seed,
         rent,
         len,
@@ -1,7 +1,9 @@
-use crate::common::GasFeeStrategyType;
+use crate::common::{GasFeeStrategyType, RpcClientConfig};
+use crate::swqos::submit_timeout::DEFAULT_SWQOS_SUBMIT_TIMEOUT;
 use crate::swqos::{SwqosConfig, SwqosType};
 use solana_commitment_config::CommitmentConfig;
 use std::hash::{Hash, Hasher};
+use std::time::Duration;
 
 /// Infrastructure-only configuration (wallet-independent)
 /// Can be shared across multiple wallets using the same RPC/SWQOS setup
@@ -16,6 +18,15 @@ pub struct InfrastructureConfig {
     /// (Astralane, BlockRazor, Glaive) use MEV-protected endpoints/modes. Glaive HTTP adds
     /// `mev-protect=true`; Glaive QUIC sets auth-frame flag bit 0. Default false.
     pub mev_protection: bool,
+    /// Per-endpoint submit timeout; a submit that has not resolved by then counts as failed for
+    /// that endpoint (`TradeError::SUBMIT_TIMEOUT`). Zero disables. Default 2s.
+    pub swqos_submit_timeout: Duration,
+    /// Per-provider overrides of `swqos_submit_timeout`.
+    pub swqos_submit_timeout_overrides: Vec<(SwqosType, Duration)>,
+    /// Per-provider submit limits in requests per second (`TradeError::RATE_LIMITED` beyond).
+    pub swqos_rate_limits: Vec<(SwqosType, u32)>,
+    /// Extra headers / timeout for the RPC client (header-based auth). Default: none.
+    pub rpc_client_config: RpcClientConfig,
 }
 
 impl InfrastructureConfig {
@@ -30,9 +41,19 @@ impl InfrastructureConfig {
             commitment,
             swqos_cores_from_end: false,
             mev_protection: false,
+            swqos_submit_timeout: DEFAULT_SWQOS_SUBMIT_TIMEOUT,
+            swqos_submit_timeout_overrides: Vec::new(),
+            swqos_rate_limits: Vec::new(),
+            rpc_client_config: RpcClientConfig::default(),
         }
     }
 
+    /// Send `config`'s headers / timeout with every RPC request.
+    pub fn with_rpc_client_config(mut self, config: RpcClientConfig) -> Self {
+        self.rpc_client_config = config;
+        self
+    }
+
     /// Create from TradeConfig (extract infrastructure-only settings)
     pub fn from_trade_config(config: &TradeConfig) -> Self {
         Self {
@@ -41,9 +62,27 @@ impl InfrastructureConfig {
             commitment: config.commitment.clone(),
             swqos_cores_from_end: config.swqos_cores_from_end,
             mev_protection: config.mev_protection,
+            swqos_submit_timeout: config.swqos_submit_timeout,
+            swqos_submit_timeout_overrides: config.swqos_submit_timeout_overrides.clone(),
+            swqos_rate_limits: config.swqos_rate_limits.clone(),
+            rpc_client_config: config.rpc_client_config.clone(),
         }
     }
 
+    /// Submit timeout for `swqos_type`: its override if set, else `swqos_submit_timeout`.
+    pub fn submit_timeout_for(&self, swqos_type: SwqosType) -> Duration {
+        self.swqos_submit_timeout_overrides
+            .iter()
+            .find(|(t, _)| *t == swqos_type)
+            .map(|(_, d)| *d)
+            .unwrap_or(self.swqos_submit_timeout)
+    }
+
+    /// Requests per second allowed for `swqos_type`; `None` = unlimited.
+    pub fn rate_limit_for(&self, swqos_type: SwqosType) -> Option<u32> {
+        self.swqos_rate_limits.iter().find(|(t, _)| *t == swqos_type).map(|(_, r)| *r)
+    }
+
     /// Generate a cache key for this infrastructure configuration
     pub fn cache_key(&self) -> String {
         use std::collections::hash_map::DefaultHasher;
@@ -61,6 +100,10 @@ impl Hash for InfrastructureConfig {
         format!("{:?}", self.commitment).hash(state);
         self.swqos_cores_from_end.hash(state);
         self.mev_protection.hash(state);
+        self.swqos_submit_timeout.hash(state);
+        self.swqos_submit_timeout_overrides.hash(state);
+        self.swqos_rate_limits.hash(state);
+        self.rpc_client_config.hash(state);
     }
 }
 
@@ -71,6 +114,10 @@ impl PartialEq for InfrastructureConfig {
             && self.commitment == other.commitment
             && self.swqos_cores_from_end == other.swqos_cores_from_end
             && self.mev_protection == other.mev_protection
+            && self.swqos_submit_timeout == other.swqos_submit_timeout
+            && self.swqos_submit_timeout_overrides == other.swqos_submit_timeout_overrides
+            && self.swqos_rate_limits == other.swqos_rate_limits
+            && self.rpc_client_config == other.rpc_client_config
     }
 }
 
@@ -81,6 +128,34 @@ pub struct SwqosSubmitTiming {
     pub swqos_type: SwqosType,
     pub strategy_type: GasFeeStrategyType,
     pub submit_done_us: i64,
+    /// Send wave that produced this result under [`ParallelStrategy`] (0 = first wave; always
+    /// 0 for `RaceAll`). Timings line up index-for-index with the returned signatures.
+    pub wave: usize,
+    /// `true` for the last-resort plain-RPC submit made after every SWQOS route failed
+    /// (`swqos_type` is then `Default`).
+    pub rpc_fallback: bool,
+}
+
+/// How a trade is fanned out across the configured SWQOS routes. 多通道发送顺序策略。
+///
+/// A route "wins" once it reports success: relay accepted the transaction, or confirmed on-chain
+/// when the executor waits for confirmation. Later waves are never sent after a win or after a
+/// landed-but-failed transaction, except under `Staggered` (see there).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParallelStrategy {
+    /// Send to every route at once (default).
+    #[default]
+    RaceAll,
+    /// Send to the first configured route only; fan out to the rest unless its transaction
+    /// lands within `delay`, polled with `getSignatureStatuses` on the trade's RPC (without an
+    /// RPC, the relay accepting the submit counts). Fans out at once if the first route
+    /// rejects. A first-wave transaction that lands after `delay` can still land next to the
+    /// fan-out, so pair this with a durable nonce when a double fill matters. Order routes
+    /// cheapest-first to save tips when the first usually lands.
+    Staggered { delay: Duration },
+    /// One route at a time in configured order; move on once the current route fails or its
+    /// submit window (5s) expires.
+    Sequential,
 }
 
 #[derive(Debug, Clone)]
@@ -95,6 +170,9 @@ pub struct TradeConfig {
     pub use_seed_optimize: bool,
     /// Whether to output all SDK logs (timing, SWQOS submit/confirm, WSOL, blacklist, etc.). Default true.
     pub log_enabled: bool,
+    /// Echo startup status (RPC, rent, SWQOS routes, WSOL ATA) to stderr in addition to
+    /// `tracing`. Default true; turn off when a tracing subscriber already collects them.
+    pub verbose_startup: bool,
     /// Whether to check minimum tip per SWQOS provider (filter out configs below min). Default false to save latency.
     pub check_min_tip: bool,
     /// When true, SWQOS uses the *last* N cores (instead of the first N). Use when main thread / tokio use low-numbered cores to reduce CPU contention. Default false.
@@ -103,6 +181,19 @@ pub struct TradeConfig {
     /// (Astralane, BlockRazor, Glaive) use their MEV-protected endpoints/modes. Glaive HTTP
     /// adds `mev-protect=true`; Glaive QUIC sets auth-frame flag bit 0. Default false.
     pub mev_protection: bool,
+    /// Per-endpoint submit timeout (zero disables). Default 2s.
+    pub swqos_submit_timeout: Duration,
+    /// Per-provider overrides of `swqos_submit_timeout`.
+    pub swqos_submit_timeout_overrides: Vec<(SwqosType, Duration)>,
+    /// Per-provider submit limits in requests per second. Default: none.
+    pub swqos_rate_limits: Vec<(SwqosType, u32)>,
+    /// Extra headers / timeout for the RPC client (header-based auth). Default: none.
+    pub rpc_client_config: RpcClientConfig,
+    /// How long `from_pool_address_by_rpc` reuses a decoded pool (zero disables). Default: zero.
+    pub pool_cache_ttl: Duration,
+    /// How often Jito tip accounts are refreshed via `getTipAccounts` (zero = built-in list).
+    /// Default: zero.
+    pub jito_tip_account_refresh: Duration,
 }
 
 impl TradeConfig {
@@ -112,9 +203,17 @@ impl TradeConfig {
     /// - `.create_wsol_ata_on_startup(bool)` — check & create WSOL ATA on init (default: true)
     /// - `.use_seed_optimize(bool)`           — seed optimization for ATA ops (default: true)
     /// - `.log_enabled(bool)`                 — SDK timing/SWQOS logs (default: true)
+    /// - `.verbose_startup(bool)`             — echo startup status to stderr (default: true)
     /// - `.check_min_tip(bool)`               — filter SWQOS below min tip (default: false)
     /// - `.swqos_cores_from_end(bool)`        — bind SWQOS to last N cores (default: false)
     /// - `.mev_protection(bool)`              — MEV protection for Astralane/BlockRazor/Glaive (default: false)
+    /// - `.swqos_submit_timeout(Duration)`    — per-endpoint submit timeout (default: 2s)
+    /// - `.swqos_submit_timeout_for(type, d)` — override the submit timeout for one provider
+    /// - `.swqos_rate_limit(type, rps)`       — cap submits per second to one provider
+    /// - `.rpc_header(name, value)`           — extra header on every RPC request (API key auth)
+    /// - `.rpc_client_config(config)`         — RPC headers and timeout at once
+    /// - `.pool_cache_ttl(Duration)`          — reuse pools loaded by RPC for this long (default: off)
+    /// - `.jito_tip_account_refresh(Duration)` — refresh Jito tip accounts this often (default: off)
     ///
     /// # Example
     /// ```rust,ignore
@@ -154,9 +253,16 @@ pub struct TradeConfigBuilder {
     create_wsol_ata_on_startup: bool,
     use_seed_optimize: bool,
     log_enabled: bool,
+    verbose_startup: bool,
     check_min_tip: bool,
     swqos_cores_from_end: bool,
     mev_protection: bool,
+    swqos_submit_timeout: Duration,
+    swqos_submit_timeout_overrides: Vec<(SwqosType, Duration)>,
+    swqos_rate_limits: Vec<(SwqosType, u32)>,
+    rpc_client_config: RpcClientConfig,
+    pool_cache_ttl: Duration,
+    jito_tip_account_refresh: Duration,
 }
 
 impl TradeConfigBuilder {
@@ -168,9 +274,16 @@ impl TradeConfigBuilder {
             create_wsol_ata_on_startup: true,
             use_seed_optimize: true,
             log_enabled: true,
+            verbose_startup: true,
             check_min_tip: false,
             swqos_cores_from_end: false,
             mev_protection: false,
+            swqos_submit_timeout: DEFAULT_SWQOS_SUBMIT_TIMEOUT,
+            swqos_submit_timeout_overrides: Vec::new(),
+            swqos_rate_limits: Vec::new(),
+            rpc_client_config: RpcClientConfig::default(),
+            pool_cache_ttl: Duration::ZERO,
+            jito_tip_account_refresh: Duration::ZERO,
         }
     }
 
@@ -192,6 +305,12 @@ impl TradeConfigBuilder {
         self
     }
 
+    /// Echo startup status lines to stderr as well as `tracing`. Default: `true`.
+    pub fn verbose_startup(mut self, v: bool) -> Self {
+        self.verbose_startup = v;
+        self
+    }
+
     /// Filter out SWQOS providers whose tip is below their minimum requirement.
     /// Adds a small check on the hot path; disable for lowest latency. Default: `false`.
     pub fn check_min_tip(mut self, v: bool) -> Self {
@@ -217,6 +336,60 @@ impl TradeConfigBuilder {
         self
     }
 
+    /// Fail an endpoint's submit that has not resolved within `timeout` instead of letting it
+    /// hang; the other endpoints keep racing. `Duration::ZERO` disables. Default: 2s.
+    pub fn swqos_submit_timeout(mut self, timeout: Duration) -> Self {
+        self.swqos_submit_timeout = timeout;
+        self
+    }
+
+    /// Override the submit timeout for one provider (e.g. a slower HTTP relay).
+    pub fn swqos_submit_timeout_for(mut self, swqos_type: SwqosType, timeout: Duration) -> Self {
+        self.swqos_submit_timeout_overrides.retain(|(t, _)| *t != swqos_type);
+        self.swqos_submit_timeout_overrides.push((swqos_type, timeout));
+        self
+    }
+
+    /// Allow at most `requests_per_second` submits to one provider (burst up to one second's
+    /// worth). Extra submits fail for that route with `TradeError::RATE_LIMITED` instead of
+    /// reaching the provider and risking a key ban; other routes are unaffected. Default: none.
+    pub fn swqos_rate_limit(mut self, swqos_type: SwqosType, requests_per_second: u32) -> Self {
+        self.swqos_rate_limits.retain(|(t, _)| *t != swqos_type);
+        self.swqos_rate_limits.push((swqos_type, requests_per_second));
+        self
+    }
+
+    /// Send `name: value` with every RPC request, for providers that take the API key in a
+    /// header (e.g. `x-api-key`) rather than the URL. A header HTTP does not allow is logged at
+    /// init and the client is built without the extra headers.
+    pub fn rpc_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.rpc_client_config = self.rpc_client_config.with_header(name, value);
+        self
+    }
+
+    /// Replace the RPC client's headers and timeout. Default: none / 30s.
+    pub fn rpc_client_config(mut self, config: RpcClientConfig) -> Self {
+        self.rpc_client_config = config;
+        self
+    }
+
+    /// Reuse params loaded by `from_pool_address_by_rpc` for `ttl`, e.g. across a buy and the
+    /// following sell. Reserves are as of the first load; see
+    /// [`pool_cache::invalidate`](crate::trading::core::params::pool_cache::invalidate).
+    /// `Duration::ZERO` disables. Default: off.
+    pub fn pool_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.pool_cache_ttl = ttl;
+        self
+    }
+
+    /// Fetch Jito tip accounts with `getTipAccounts` and re-fetch once older than `ttl`, so
+    /// tips follow account rotations. Until the first fetch succeeds, or after one fails, the
+    /// built-in list is used. `Duration::ZERO` disables. Default: off.
+    pub fn jito_tip_account_refresh(mut self, ttl: Duration) -> Self {
+        self.jito_tip_account_refresh = ttl;
+        self
+    }
+
     /// Consume the builder and produce a [`TradeConfig`].
     pub fn build(self) -> TradeConfig {
         TradeConfig {
@@ -226,9 +399,16 @@ impl TradeConfigBuilder {
             create_wsol_ata_on_startup: self.create_wsol_ata_on_startup,
             use_seed_optimize: self.use_seed_optimize,
             log_enabled: self.log_enabled,
+            verbose_startup: self.verbose_startup,
             check_min_tip: self.check_min_tip,
             swqos_cores_from_end: self.swqos_cores_from_end,
             mev_protection: self.mev_protection,
+            swqos_submit_timeout: self.swqos_submit_timeout,
+            swqos_submit_timeout_overrides: self.swqos_submit_timeout_overrides,
+            swqos_rate_limits: self.swqos_rate_limits,
+            rpc_client_config: self.rpc_client_config,
+            pool_cache_ttl: self.pool_cache_ttl,
+            jito_tip_account_refresh: self.jito_tip_account_refresh,
         }
     }
 }
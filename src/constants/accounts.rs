@@ -24,6 +24,9 @@ pub const TOKEN_PROGRAM_2022_META: solana_sdk::instruction::AccountMeta =
         is_writable: false,
     };
 
+/// SPL Memo program (v2).
+pub const MEMO_PROGRAM: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
 pub const SOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111111");
 
 pub const WSOL_TOKEN_ACCOUNT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
@@ -9,13 +9,15 @@ use crate::{
             accounts, get_pool_pda, get_vault_pda, BUY_EXECT_IN_DISCRIMINATOR,
             BUY_EXECT_OUT_DISCRIMINATOR, SELL_EXECT_IN_DISCRIMINATOR, SELL_EXECT_OUT_DISCRIMINATOR,
         },
+        utils::derived_pda,
     },
     trading::core::{
         params::{BonkParams, SwapParams},
         traits::InstructionBuilder,
     },
     utils::calc::bonk::{
-        get_buy_token_amount_from_sol_amount, get_sell_sol_amount_from_token_amount,
+        get_buy_sol_amount_from_token_amount, get_buy_token_amount_from_sol_amount,
+        get_sell_sol_amount_from_token_amount, get_sell_token_amount_from_sol_amount,
     },
 };
 use anyhow::{anyhow, Result};
@@ -28,28 +30,48 @@ use solana_sdk::{
 /// Instruction builder for Bonk protocol
 pub struct BonkInstructionBuilder;
 
+/// Exact-out trades sign for the reserve-quoted max input; refuse one that exceeds the caller's
+/// `input_amount` budget instead of building a tx the program would reject.
+#[inline]
+fn ensure_within_budget(max_amount_in: u64, budget: u64) -> Result<()> {
+    if max_amount_in > budget {
+        return Err(anyhow!(
+            "Bonk exact output needs up to {} input, above the input_amount budget {}",
+            max_amount_in,
+            budget
+        ));
+    }
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl InstructionBuilder for BonkInstructionBuilder {
     async fn build_buy_instructions(&self, params: &SwapParams) -> Result<Vec<Instruction>> {
         // ========================================
         // Parameter validation and basic data preparation
         // ========================================
-        if params.input_amount.unwrap_or(0) == 0 {
-            return Err(anyhow!("Amount cannot be zero"));
-        }
+        let amount_in = params.required_input_amount()?;
         let protocol_params = params
             .protocol_params
             .as_any()
             .downcast_ref::<BonkParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for Bonk"))?;
 
-        let usd1_pool = protocol_params.global_config == accounts::USD1_GLOBAL_CONFIG;
+        // 报价路由同时看 global_config 与请求的报价 mint，避免 USD1 买入落到 WSOL 池
+        let usd1_pool = protocol_params.global_config == accounts::USD1_GLOBAL_CONFIG
+            || params.input_mint == crate::constants::USD1_TOKEN_ACCOUNT;
 
         let pool_state = if protocol_params.pool_state == Pubkey::default() {
             if usd1_pool {
-                get_pool_pda(&params.output_mint, &crate::constants::USD1_TOKEN_ACCOUNT).unwrap()
+                derived_pda(
+                    get_pool_pda(&params.output_mint, &crate::constants::USD1_TOKEN_ACCOUNT),
+                    "Bonk pool",
+                )?
             } else {
-                get_pool_pda(&params.output_mint, &crate::constants::WSOL_TOKEN_ACCOUNT).unwrap()
+                derived_pda(
+                    get_pool_pda(&params.output_mint, &crate::constants::WSOL_TOKEN_ACCOUNT),
+                    "Bonk pool",
+                )?
             }
         } else {
             protocol_params.pool_state
@@ -75,17 +97,32 @@ impl InstructionBuilder for BonkInstructionBuilder {
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        let amount_in: u64 = params.input_amount.unwrap_or(0);
         let share_fee_rate: u64 = 0;
-        let minimum_amount_out: u64 = match params.fixed_output_amount {
-            Some(fixed_amount) => fixed_amount,
-            None => get_buy_token_amount_from_sol_amount(
+        // Exact-in: (amount_in, min tokens out). Exact-out: (fixed tokens out, max quote in).
+        let (first_amount, second_amount) = match params.fixed_output_amount {
+            Some(amount_out) => {
+                let max_amount_in = get_buy_sol_amount_from_token_amount(
+                    amount_out,
+                    protocol_params.virtual_base,
+                    protocol_params.virtual_quote,
+                    protocol_params.real_base,
+                    protocol_params.real_quote,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
+                )
+                .ok_or_else(|| anyhow!("Bonk curve cannot provide {} output tokens", amount_out))?;
+                ensure_within_budget(max_amount_in, amount_in)?;
+                (amount_out, max_amount_in)
+            }
+            None => (
                 amount_in,
-                protocol_params.virtual_base,
-                protocol_params.virtual_quote,
-                protocol_params.real_base,
-                protocol_params.real_quote,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
+                get_buy_token_amount_from_sol_amount(
+                    amount_in,
+                    protocol_params.virtual_base,
+                    protocol_params.virtual_quote,
+                    protocol_params.real_base,
+                    protocol_params.real_quote,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
+                ),
             ),
         };
 
@@ -105,12 +142,12 @@ impl InstructionBuilder for BonkInstructionBuilder {
             );
 
         let base_vault_account = if protocol_params.base_vault == Pubkey::default() {
-            get_vault_pda(&pool_state, &params.output_mint).unwrap()
+            derived_pda(get_vault_pda(&pool_state, &params.output_mint), "Bonk base vault")?
         } else {
             protocol_params.base_vault
         };
         let quote_vault_account = if protocol_params.quote_vault == Pubkey::default() {
-            get_vault_pda(&pool_state, &quote_mint).unwrap()
+            derived_pda(get_vault_pda(&pool_state, &quote_mint), "Bonk quote vault")?
         } else {
             protocol_params.quote_vault
         };
@@ -124,6 +161,7 @@ impl InstructionBuilder for BonkInstructionBuilder {
             push_create_or_wrap_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &quote_mint,
                 &crate::constants::TOKEN_PROGRAM,
                 amount_in,
@@ -134,7 +172,7 @@ impl InstructionBuilder for BonkInstructionBuilder {
         if params.create_output_mint_ata {
             instructions.extend(
                 crate::common::fast_fn::create_associated_token_account_idempotent_fast_use_seed(
-                    &params.payer.pubkey(),
+                    &params.ata_rent_payer(),
                     &params.payer.pubkey(),
                     &params.output_mint,
                     &protocol_params.mint_token_program,
@@ -144,15 +182,13 @@ impl InstructionBuilder for BonkInstructionBuilder {
         }
 
         let mut data = [0u8; 32];
-        if let Some(amount_out) = params.fixed_output_amount {
+        if params.fixed_output_amount.is_some() {
             data[..8].copy_from_slice(&BUY_EXECT_OUT_DISCRIMINATOR);
-            data[8..16].copy_from_slice(&amount_out.to_le_bytes());
-            data[16..24].copy_from_slice(&amount_in.to_le_bytes());
         } else {
             data[..8].copy_from_slice(&BUY_EXECT_IN_DISCRIMINATOR);
-            data[8..16].copy_from_slice(&amount_in.to_le_bytes());
-            data[16..24].copy_from_slice(&minimum_amount_out.to_le_bytes());
         }
+        data[8..16].copy_from_slice(&first_amount.to_le_bytes());
+        data[16..24].copy_from_slice(&second_amount.to_le_bytes());
         data[24..32].copy_from_slice(&share_fee_rate.to_le_bytes());
 
         let accounts: [AccountMeta; 15] = [
@@ -186,10 +222,8 @@ impl InstructionBuilder for BonkInstructionBuilder {
         // ========================================
         // Parameter validation and basic data preparation
         // ========================================
-        let amount = params
-            .input_amount
-            .filter(|&a| a > 0)
-            .ok_or_else(|| anyhow!("Bonk sell requires input_amount (token amount to sell); fetch balance via RPC before calling build_sell"))?;
+        // Token amount to sell; fetch the balance via RPC before calling build_sell.
+        let amount = params.required_input_amount()?;
 
         let protocol_params = params
             .protocol_params
@@ -197,13 +231,20 @@ impl InstructionBuilder for BonkInstructionBuilder {
             .downcast_ref::<BonkParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for Bonk"))?;
 
-        let usd1_pool = protocol_params.global_config == accounts::USD1_GLOBAL_CONFIG;
+        let usd1_pool = protocol_params.global_config == accounts::USD1_GLOBAL_CONFIG
+            || params.output_mint == crate::constants::USD1_TOKEN_ACCOUNT;
 
         let pool_state = if protocol_params.pool_state == Pubkey::default() {
             if usd1_pool {
-                get_pool_pda(&params.input_mint, &crate::constants::USD1_TOKEN_ACCOUNT).unwrap()
+                derived_pda(
+                    get_pool_pda(&params.input_mint, &crate::constants::USD1_TOKEN_ACCOUNT),
+                    "Bonk pool",
+                )?
             } else {
-                get_pool_pda(&params.input_mint, &crate::constants::WSOL_TOKEN_ACCOUNT).unwrap()
+                derived_pda(
+                    get_pool_pda(&params.input_mint, &crate::constants::WSOL_TOKEN_ACCOUNT),
+                    "Bonk pool",
+                )?
             }
         } else {
             protocol_params.pool_state
@@ -230,15 +271,31 @@ impl InstructionBuilder for BonkInstructionBuilder {
         // Trade calculation and account address preparation
         // ========================================
         let share_fee_rate: u64 = 0;
-        let minimum_amount_out: u64 = match params.fixed_output_amount {
-            Some(fixed_amount) => fixed_amount,
-            None => get_sell_sol_amount_from_token_amount(
+        // Exact-in: (tokens in, min quote out). Exact-out: (fixed quote out, max tokens in).
+        let (first_amount, second_amount) = match params.fixed_output_amount {
+            Some(amount_out) => {
+                let max_amount_in = get_sell_token_amount_from_sol_amount(
+                    amount_out,
+                    protocol_params.virtual_base,
+                    protocol_params.virtual_quote,
+                    protocol_params.real_base,
+                    protocol_params.real_quote,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
+                )
+                .ok_or_else(|| anyhow!("Bonk curve cannot provide {} output quote", amount_out))?;
+                ensure_within_budget(max_amount_in, amount)?;
+                (amount_out, max_amount_in)
+            }
+            None => (
                 amount,
-                protocol_params.virtual_base,
-                protocol_params.virtual_quote,
-                protocol_params.real_base,
-                protocol_params.real_quote,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
+                get_sell_sol_amount_from_token_amount(
+                    amount,
+                    protocol_params.virtual_base,
+                    protocol_params.virtual_quote,
+                    protocol_params.real_base,
+                    protocol_params.real_quote,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
+                ),
             ),
         };
 
@@ -258,12 +315,12 @@ impl InstructionBuilder for BonkInstructionBuilder {
             );
 
         let base_vault_account = if protocol_params.base_vault == Pubkey::default() {
-            get_vault_pda(&pool_state, &params.input_mint).unwrap()
+            derived_pda(get_vault_pda(&pool_state, &params.input_mint), "Bonk base vault")?
         } else {
             protocol_params.base_vault
         };
         let quote_vault_account = if protocol_params.quote_vault == Pubkey::default() {
-            get_vault_pda(&pool_state, &quote_mint).unwrap()
+            derived_pda(get_vault_pda(&pool_state, &quote_mint), "Bonk quote vault")?
         } else {
             protocol_params.quote_vault
         };
@@ -277,6 +334,7 @@ impl InstructionBuilder for BonkInstructionBuilder {
             push_create_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &quote_mint,
                 &crate::constants::TOKEN_PROGRAM,
                 params.open_seed_optimize,
@@ -284,15 +342,13 @@ impl InstructionBuilder for BonkInstructionBuilder {
         }
 
         let mut data = [0u8; 32];
-        if let Some(amount_out) = params.fixed_output_amount {
+        if params.fixed_output_amount.is_some() {
             data[..8].copy_from_slice(&SELL_EXECT_OUT_DISCRIMINATOR);
-            data[8..16].copy_from_slice(&amount_out.to_le_bytes());
-            data[16..24].copy_from_slice(&amount.to_le_bytes());
         } else {
             data[..8].copy_from_slice(&SELL_EXECT_IN_DISCRIMINATOR);
-            data[8..16].copy_from_slice(&amount.to_le_bytes());
-            data[16..24].copy_from_slice(&minimum_amount_out.to_le_bytes());
         }
+        data[8..16].copy_from_slice(&first_amount.to_le_bytes());
+        data[16..24].copy_from_slice(&second_amount.to_le_bytes());
         data[24..32].copy_from_slice(&share_fee_rate.to_le_bytes());
 
         let accounts: [AccountMeta; 15] = [
@@ -335,7 +391,11 @@ impl InstructionBuilder for BonkInstructionBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{common::GasFeeStrategy, swqos::TradeType, trading::core::params::DexParamEnum};
+    use crate::{
+        common::{GasFeeStrategy, ParallelStrategy},
+        swqos::TradeType,
+        trading::core::params::{AtaCreationMode, DexParamEnum},
+    };
     use solana_sdk::{pubkey::Pubkey, signature::Keypair};
     use std::sync::Arc;
 
@@ -350,6 +410,8 @@ mod tests {
             platform_associated_account: pk(9),
             creator_associated_account: pk(10),
             global_config: accounts::GLOBAL_CONFIG,
+            virtual_base: 1_073_025_605_596_382,
+            virtual_quote: 30_000_852_951,
             ..Default::default()
         }
     }
@@ -363,7 +425,7 @@ mod tests {
             input_token_program: None,
             output_mint: pk(3),
             output_token_program: None,
-            input_amount: Some(100_000),
+            input_amount: Some(10_000_000),
             slippage_basis_points: Some(100),
             address_lookup_table_accounts: Vec::new(),
             recent_blockhash: None,
@@ -390,6 +452,21 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: None,
+            swqos_watchdog: None,
+            ata_creation: AtaCreationMode::Inline,
+            memo: None,
+            parallel_strategy: ParallelStrategy::RaceAll,
+            max_ata_creations: None,
+            cu_learner: None,
+            rpc_fallback: false,
+            cleanup_on_failure: false,
+            skip_compute_budget: false,
+            simulation_capture: None,
+            ata_rent_funder: None,
+            receive_native_sol: false,
+            on_before_send: None,
+            multi_nonce: None,
+            min_tokens_out: None,
         }
     }
 
@@ -403,9 +480,20 @@ mod tests {
 
         assert_eq!(ix.accounts.len(), 15);
         assert_eq!(ix.accounts[14].pubkey, accounts::BONK);
+        let p = bonk_params();
+        let max_amount_in = get_buy_sol_amount_from_token_amount(
+            42,
+            p.virtual_base,
+            p.virtual_quote,
+            p.real_base,
+            p.real_quote,
+            100,
+        )
+        .unwrap();
         assert_eq!(&ix.data[..8], BUY_EXECT_OUT_DISCRIMINATOR);
         assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 42);
-        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 100_000);
+        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), max_amount_in);
+        assert!(max_amount_in < 10_000_000);
     }
 
     #[tokio::test]
@@ -418,9 +506,35 @@ mod tests {
 
         assert_eq!(ix.accounts.len(), 15);
         assert_eq!(ix.accounts[14].pubkey, accounts::BONK);
+        let p = bonk_params();
+        let max_amount_in = get_sell_token_amount_from_sol_amount(
+            42,
+            p.virtual_base,
+            p.virtual_quote,
+            p.real_base,
+            p.real_quote,
+            100,
+        )
+        .unwrap();
         assert_eq!(&ix.data[..8], SELL_EXECT_OUT_DISCRIMINATOR);
         assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 42);
-        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 100_000);
+        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), max_amount_in);
+        assert!(max_amount_in < 10_000_000);
+    }
+
+    #[tokio::test]
+    async fn bonk_exact_out_above_budget_or_reserves_is_rejected() {
+        let mut buy = swap_params(TradeType::Buy);
+        buy.input_amount = Some(1);
+        assert!(BonkInstructionBuilder.build_buy_instructions(&buy).await.is_err());
+
+        let mut sell = swap_params(TradeType::Sell);
+        sell.input_amount = Some(1);
+        assert!(BonkInstructionBuilder.build_sell_instructions(&sell).await.is_err());
+
+        let mut buy = swap_params(TradeType::Buy);
+        buy.fixed_output_amount = Some(bonk_params().virtual_base as u64);
+        assert!(BonkInstructionBuilder.build_buy_instructions(&buy).await.is_err());
     }
 
     #[tokio::test]
@@ -438,4 +552,92 @@ mod tests {
         assert_eq!(create_ix.program_id, crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID);
         assert_eq!(create_ix.accounts[3].pubkey, crate::constants::USD1_TOKEN_ACCOUNT);
     }
+
+    /// USD1 routing follows the requested quote mint even when `global_config` was left at the
+    /// WSOL default (e.g. `immediate_sell` built from a stale config).
+    #[tokio::test]
+    async fn bonk_usd1_routes_follow_quote_mint() {
+        use crate::constants::{USD1_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+
+        let mut buy = swap_params(TradeType::Buy);
+        buy.input_mint = USD1_TOKEN_ACCOUNT;
+        let mut sell = swap_params(TradeType::Sell);
+        sell.output_mint = USD1_TOKEN_ACCOUNT;
+
+        let pool = get_pool_pda(&pk(3), &USD1_TOKEN_ACCOUNT).unwrap();
+        let buy_ix = BonkInstructionBuilder.build_buy_instructions(&buy).await.unwrap();
+        let sell_ix = BonkInstructionBuilder.build_sell_instructions(&sell).await.unwrap();
+        for ix in [buy_ix.last().unwrap(), sell_ix.last().unwrap()] {
+            assert_eq!(ix.accounts[2].pubkey, accounts::USD1_GLOBAL_CONFIG);
+            assert_eq!(ix.accounts[4].pubkey, pool);
+            assert_eq!(ix.accounts[10].pubkey, USD1_TOKEN_ACCOUNT);
+            assert!(ix.accounts.iter().all(|a| a.pubkey != WSOL_TOKEN_ACCOUNT));
+        }
+    }
+
+    mod mock_rpc {
+        use super::*;
+        use crate::common::MockSolanaRpcClient;
+        use crate::constants::{USD1_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+        use crate::trading::factory::{DexType, TradeFactory};
+        use solana_hash::Hash;
+
+        async fn usd1_swap_params(trade_type: TradeType) -> SwapParams {
+            let rpc = Arc::new(
+                MockSolanaRpcClient::new()
+                    .with_latest_blockhash(Hash::new_unique(), 1_000)
+                    .with_simulation(None, vec!["Program log: Instruction: Trade".into()], 60_000)
+                    .build(),
+            );
+            let mut params = swap_params(trade_type);
+            params.recent_blockhash = Some(rpc.get_latest_blockhash().await.unwrap());
+            params.rpc = Some(rpc);
+            params.open_seed_optimize = false;
+            params
+                .gas_fee_strategy
+                .set_global_fee_strategy(150_000, 150_000, 1_000, 1_000, 0.0, 0.0);
+            if let DexParamEnum::Bonk(protocol_params) = &mut params.protocol_params {
+                protocol_params.global_config = accounts::USD1_GLOBAL_CONFIG;
+            }
+            match trade_type {
+                TradeType::Buy => {
+                    params.input_mint = USD1_TOKEN_ACCOUNT;
+                    params.create_input_mint_ata = true;
+                    params.close_input_mint_ata = true;
+                    params.create_output_mint_ata = true;
+                }
+                _ => {
+                    params.output_mint = USD1_TOKEN_ACCOUNT;
+                    params.create_output_mint_ata = true;
+                    params.close_output_mint_ata = true;
+                }
+            }
+            params
+        }
+
+        #[tokio::test]
+        async fn usd1_buy_and_sell_simulate_end_to_end() {
+            for trade_type in [TradeType::Buy, TradeType::Sell] {
+                let params = usd1_swap_params(trade_type).await;
+                let instructions = if trade_type == TradeType::Buy {
+                    BonkInstructionBuilder.build_buy_instructions(&params).await.unwrap()
+                } else {
+                    BonkInstructionBuilder.build_sell_instructions(&params).await.unwrap()
+                };
+                // USD1 quote ATA is created but never wrapped/closed like WSOL
+                assert!(instructions
+                    .iter()
+                    .flat_map(|ix| ix.accounts.iter())
+                    .all(|a| a.pubkey != WSOL_TOKEN_ACCOUNT));
+                let swap = instructions.last().unwrap();
+                assert_eq!(swap.accounts[2].pubkey, accounts::USD1_GLOBAL_CONFIG);
+                assert_eq!(swap.accounts[10].pubkey, USD1_TOKEN_ACCOUNT);
+
+                let (ok, signatures, err, _) =
+                    TradeFactory::create_executor(DexType::Bonk).swap(params).await.unwrap();
+                assert!(ok, "{:?} {:?}", trade_type, err);
+                assert_eq!(signatures.len(), 1);
+            }
+        }
+    }
 }
@@ -23,15 +23,40 @@ use solana_sdk::{
 /// Instruction builder for RaydiumCpmm protocol
 pub struct MeteoraDammV2InstructionBuilder;
 
+/// `swap2` amounts `(amount_in, minimum_amount_out)`. The params carry no pool price, so neither
+/// an exact-out max input nor a minimum output can be quoted from reserves: `fixed_output_amount`
+/// and exact-out are rejected and the caller sets `MeteoraDammV2Params::minimum_amount_out`.
+fn swap2_amounts(
+    params: &SwapParams,
+    protocol_params: &MeteoraDammV2Params,
+    amount_in: u64,
+) -> Result<(u64, u64)> {
+    if params.fixed_output_amount.is_some() {
+        return Err(anyhow!(
+            "MeteoraDammV2 cannot quote fixed_output_amount without the pool price; set MeteoraDammV2Params::minimum_amount_out instead"
+        ));
+    }
+    match protocol_params.swap_mode {
+        SWAP_MODE_EXACT_IN | SWAP_MODE_PARTIAL_FILL => {
+            let minimum_amount_out = protocol_params.minimum_amount_out.ok_or_else(|| {
+                anyhow!("MeteoraDammV2Params::minimum_amount_out must be set for swap2")
+            })?;
+            Ok((amount_in, minimum_amount_out))
+        }
+        SWAP_MODE_EXACT_OUT => Err(anyhow!(
+            "MeteoraDammV2 exact-out swap2 is unsupported: its max input cannot be quoted without the pool price"
+        )),
+        mode => Err(anyhow!("Unsupported MeteoraDammV2 swap_mode {}", mode)),
+    }
+}
+
 #[async_trait::async_trait]
 impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
     async fn build_buy_instructions(&self, params: &SwapParams) -> Result<Vec<Instruction>> {
         // ========================================
         // Parameter validation and basic data preparation
         // ========================================
-        if params.input_amount.unwrap_or(0) == 0 {
-            return Err(anyhow!("Amount cannot be zero"));
-        }
+        let amount_in = params.required_input_amount()?;
         let protocol_params = params
             .protocol_params
             .as_any()
@@ -59,22 +84,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
             if is_a_in { protocol_params.token_b_mint } else { protocol_params.token_a_mint };
         let output_token_program =
             if is_a_in { protocol_params.token_b_program } else { protocol_params.token_a_program };
-        let amount_in: u64 = params.input_amount.unwrap_or(0);
-        let (amount_0, amount_1) = match protocol_params.swap_mode {
-            SWAP_MODE_EXACT_OUT => {
-                let amount_out = params.fixed_output_amount.ok_or_else(|| {
-                    anyhow!("fixed_output_amount must be set for MeteoraDammV2 exact-out swap2")
-                })?;
-                (amount_out, amount_in)
-            }
-            SWAP_MODE_EXACT_IN | SWAP_MODE_PARTIAL_FILL => {
-                let minimum_amount_out = params.fixed_output_amount.ok_or_else(|| {
-                    anyhow!("fixed_output_amount must be set for MeteoraDammV2 swap2 min output")
-                })?;
-                (amount_in, minimum_amount_out)
-            }
-            mode => return Err(anyhow!("Unsupported MeteoraDammV2 swap_mode {}", mode)),
-        };
+        let (amount_0, amount_1) = swap2_amounts(params, protocol_params, amount_in)?;
 
         let input_token_account =
             crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed(
@@ -100,6 +110,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
             push_create_or_wrap_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &input_mint,
                 &input_token_program,
                 amount_in,
@@ -111,6 +122,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
             push_create_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &output_mint,
                 &output_token_program,
                 params.open_seed_optimize,
@@ -175,9 +187,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
             .downcast_ref::<MeteoraDammV2Params>()
             .ok_or_else(|| anyhow!("Invalid protocol params for MeteoraDammV2"))?;
 
-        if params.input_amount.is_none() || params.input_amount.unwrap_or(0) == 0 {
-            return Err(anyhow!("Token amount is not set"));
-        }
+        let amount_in = params.required_input_amount()?;
 
         let is_wsol = protocol_params.token_b_mint == crate::constants::WSOL_TOKEN_ACCOUNT
             || protocol_params.token_a_mint == crate::constants::WSOL_TOKEN_ACCOUNT;
@@ -200,22 +210,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
             if is_a_in { protocol_params.token_b_mint } else { protocol_params.token_a_mint };
         let output_token_program =
             if is_a_in { protocol_params.token_b_program } else { protocol_params.token_a_program };
-        let amount_in = params.input_amount.unwrap_or(0);
-        let (amount_0, amount_1) = match protocol_params.swap_mode {
-            SWAP_MODE_EXACT_OUT => {
-                let amount_out = params.fixed_output_amount.ok_or_else(|| {
-                    anyhow!("fixed_output_amount must be set for MeteoraDammV2 exact-out swap2")
-                })?;
-                (amount_out, amount_in)
-            }
-            SWAP_MODE_EXACT_IN | SWAP_MODE_PARTIAL_FILL => {
-                let minimum_amount_out = params.fixed_output_amount.ok_or_else(|| {
-                    anyhow!("fixed_output_amount must be set for MeteoraDammV2 swap2 min output")
-                })?;
-                (amount_in, minimum_amount_out)
-            }
-            mode => return Err(anyhow!("Unsupported MeteoraDammV2 swap_mode {}", mode)),
-        };
+        let (amount_0, amount_1) = swap2_amounts(params, protocol_params, amount_in)?;
 
         let input_token_account =
             crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed(
@@ -241,6 +236,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
             push_create_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &output_mint,
                 &output_token_program,
                 params.open_seed_optimize,
@@ -309,9 +305,9 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
 mod tests {
     use super::*;
     use crate::{
-        common::GasFeeStrategy,
+        common::{GasFeeStrategy, ParallelStrategy},
         swqos::TradeType,
-        trading::core::params::{DexParamEnum, SwapParams},
+        trading::core::params::{AtaCreationMode, DexParamEnum, SwapParams},
     };
     use solana_sdk::{pubkey::Pubkey, signature::Keypair};
     use std::sync::Arc;
@@ -329,7 +325,8 @@ mod tests {
             pk(4),
             crate::constants::TOKEN_PROGRAM,
             crate::constants::TOKEN_PROGRAM,
-        );
+        )
+        .with_minimum_amount_out(1);
         match referral {
             Some(account) => params.with_referral_token_account(account),
             None => params,
@@ -360,7 +357,7 @@ mod tests {
             close_input_mint_ata: false,
             create_output_mint_ata: false,
             close_output_mint_ata: false,
-            fixed_output_amount: Some(1),
+            fixed_output_amount: None,
             gas_fee_strategy: GasFeeStrategy::new(),
             simulate: true,
             log_enabled: false,
@@ -372,6 +369,21 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: None,
+            swqos_watchdog: None,
+            ata_creation: AtaCreationMode::Inline,
+            memo: None,
+            parallel_strategy: ParallelStrategy::RaceAll,
+            max_ata_creations: None,
+            cu_learner: None,
+            rpc_fallback: false,
+            cleanup_on_failure: false,
+            skip_compute_budget: false,
+            simulation_capture: None,
+            ata_rent_funder: None,
+            receive_native_sol: false,
+            on_before_send: None,
+            multi_nonce: None,
+            min_tokens_out: None,
         }
     }
 
@@ -422,18 +434,35 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn meteora_swap2_exact_out_uses_amount_out_then_max_input() {
-        let protocol_params = meteora_params(None).with_swap_mode(SWAP_MODE_EXACT_OUT);
+    async fn meteora_swap2_writes_amount_in_then_params_minimum_out() {
         let instructions = MeteoraDammV2InstructionBuilder
-            .build_buy_instructions(&swap_params(protocol_params))
+            .build_buy_instructions(&swap_params(meteora_params(None)))
             .await
             .unwrap();
         let ix = instructions.last().unwrap();
 
         assert_eq!(&ix.data[..8], SWAP2_DISCRIMINATOR);
-        assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 1);
-        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 100_000);
-        assert_eq!(ix.data[24], SWAP_MODE_EXACT_OUT);
+        assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 100_000);
+        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 1);
+        assert_eq!(ix.data[24], SWAP_MODE_PARTIAL_FILL);
+    }
+
+    #[tokio::test]
+    async fn meteora_rejects_limits_it_cannot_quote() {
+        let mut fixed = swap_params(meteora_params(None));
+        fixed.fixed_output_amount = Some(42);
+        assert!(MeteoraDammV2InstructionBuilder.build_buy_instructions(&fixed).await.is_err());
+        fixed.trade_type = TradeType::Sell;
+        assert!(MeteoraDammV2InstructionBuilder.build_sell_instructions(&fixed).await.is_err());
+
+        let exact_out = meteora_params(None).with_swap_mode(SWAP_MODE_EXACT_OUT);
+        let params = swap_params(exact_out);
+        assert!(MeteoraDammV2InstructionBuilder.build_buy_instructions(&params).await.is_err());
+
+        let mut no_minimum = meteora_params(None);
+        no_minimum.minimum_amount_out = None;
+        let params = swap_params(no_minimum);
+        assert!(MeteoraDammV2InstructionBuilder.build_buy_instructions(&params).await.is_err());
     }
 
     #[tokio::test]
@@ -462,4 +491,16 @@ mod tests {
         assert_eq!(ix.accounts[2].pubkey, expected_wsol_ata);
         assert_ne!(ix.accounts[2].pubkey, wrong_sol_ata);
     }
+
+    #[test]
+    fn non_zero_pool_status_rejects_params() {
+        let mut params = meteora_params(None);
+        params.pool_status = Some(0);
+        assert!(params.validate().is_ok());
+
+        params.pool_status = Some(1);
+        let err = params.validate().unwrap_err();
+        assert_eq!(err.code, crate::swqos::common::TradeError::POOL_PAUSED);
+        assert!(err.message.contains(&pk(1).to_string()));
+    }
 }
@@ -2,9 +2,13 @@ pub mod bonk;
 pub mod meteora_damm_v2;
 pub mod pumpfun;
 pub(crate) mod pumpfun_ix_data;
+pub mod pumpfun_layout;
 pub mod pumpswap;
 pub(crate) mod pumpswap_ix_data;
 pub mod raydium_amm_v4;
 pub mod raydium_cpmm;
 pub(crate) mod token_account_setup;
 pub mod utils;
+
+pub use pumpfun_layout::PumpFunLayout;
+pub use pumpswap_ix_data::{decode_pumpswap_ix_data, PumpSwapIxArgs, PumpSwapIxKind};
@@ -3,8 +3,10 @@
 //! The SDK selects the legacy or V2 on-chain layout from `PumpFunParams.quote_mint`.
 //! Native SOL-paired coins (`Pubkey::default()`, Solscan SOL sentinel, or WSOL sentinel)
 //! keep the smaller legacy SOL layout by default. Non-native quote mints such as USDC use
-//! the V2 27/26-account unified metas.
+//! the V2 27/26-account unified metas. `PumpFunParams::with_layout` pins either layout; the
+//! account order of each comes from the tables in [`pumpfun_layout`](super::pumpfun_layout).
 
+use crate::swqos::common::TradeError;
 use crate::swqos::TradeType;
 use crate::{
     common::bonding_curve::BondingCurveAccount,
@@ -17,19 +19,25 @@ use crate::{
 };
 use crate::{
     instruction::{
+        pumpfun_layout::{layout_metas, PumpFunAccount, PumpFunLayout},
         token_account_setup::{
             push_close_wsol_if_needed, push_create_or_wrap_user_token_account,
             push_create_user_token_account,
         },
         utils::pumpfun::{
-            accounts, get_bonding_curve_pda, get_user_volume_accumulator_pda,
+            accounts, get_bonding_curve_pda, get_metadata_pda, get_mint_authority_pda,
+            get_user_volume_accumulator_pda,
             global_constants::{self},
             pump_fun_fee_recipient_meta, resolve_creator_vault_for_ix_with_fee_sharing,
+            CREATE_DISCRIMINATOR,
         },
     },
     utils::calc::{
         common::{calculate_with_slippage_buy, calculate_with_slippage_sell},
-        pumpfun::{get_buy_token_amount_from_sol_amount, get_sell_sol_amount_from_token_amount},
+        pumpfun::{
+            get_buy_sol_amount_from_token_amount, get_buy_token_amount_from_sol_amount,
+            get_sell_sol_amount_from_token_amount,
+        },
     },
 };
 use anyhow::{anyhow, Result};
@@ -52,6 +60,39 @@ fn effective_pump_mint_token_program(protocol_params: &PumpFunParams, mint: &Pub
     }
 }
 
+/// Exact-out buy: the max quote is the curve cost of `token_amount` plus slippage. Errors when that
+/// exceeds the caller's `budget` (`input_amount`), since the program would reject the lower limit
+/// with ExceededSlippage. 精确输出买入：按储备计算最大花费，超出预算时报错。
+#[inline]
+fn exact_out_max_quote(
+    bonding_curve: &BondingCurveAccount,
+    creator: Pubkey,
+    token_amount: u64,
+    budget: u64,
+    slippage_bp: u64,
+) -> Result<u64> {
+    let cost = get_buy_sol_amount_from_token_amount(
+        bonding_curve.virtual_token_reserves as u128,
+        bonding_curve.virtual_sol_reserves as u128,
+        bonding_curve.real_token_reserves as u128,
+        creator,
+        token_amount,
+    )
+    .ok_or_else(|| {
+        anyhow!("Exact token output {} exceeds the curve's real reserves", token_amount)
+    })?;
+    let max_quote = calculate_with_slippage_buy(cost, slippage_bp);
+    if max_quote > budget {
+        return Err(anyhow!(
+            "Exact token output {} needs up to {} input, above the input_amount budget {}",
+            token_amount,
+            max_quote,
+            budget
+        ));
+    }
+    Ok(max_quote)
+}
+
 /// Resolve quote mint and its token program from PumpFunParams.
 /// `Pubkey::default()` / `SOL_TOKEN_ACCOUNT` / `WSOL_TOKEN_ACCOUNT` are native SOL-paired.
 /// V2 helpers still need a concrete SPL mint, so native SOL resolves to WSOL internally.
@@ -141,6 +182,14 @@ fn should_use_v2_layout(params: &SwapParams) -> Result<bool> {
         .downcast_ref::<PumpFunParams>()
         .ok_or_else(|| anyhow!("Invalid protocol params for PumpFun"))?;
     let (quote_mint, _) = effective_quote_mint_and_token_program(protocol_params);
+    match protocol_params.layout {
+        PumpFunLayout::Legacy if !is_sol_quote_mint(&quote_mint) => {
+            return Err(anyhow!("PumpFun legacy layout only supports SOL-quoted curves"));
+        }
+        PumpFunLayout::Legacy => return Ok(false),
+        PumpFunLayout::V2 => return Ok(true),
+        PumpFunLayout::Auto => {}
+    }
     if !is_sol_quote_mint(&quote_mint) {
         return Ok(true);
     }
@@ -157,6 +206,12 @@ fn should_use_v2_layout(params: &SwapParams) -> Result<bool> {
     })
 }
 
+/// A layout table listed a role its builder does not resolve: a table/builder mismatch.
+#[cold]
+fn unlisted_role(role: PumpFunAccount) -> Result<Option<AccountMeta>> {
+    Err(anyhow!("PumpFun layout role {:?} has no resolver in this builder", role))
+}
+
 #[inline]
 fn build_buy(params: &SwapParams) -> Result<Vec<Instruction>> {
     if should_use_v2_layout(params)? {
@@ -200,10 +255,7 @@ fn build_buy_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
         ));
     }
 
-    let lamports_in = params.input_amount.unwrap_or(0);
-    if lamports_in == 0 {
-        return Err(anyhow!("Amount cannot be zero"));
-    }
+    let lamports_in = params.required_input_amount()?;
 
     let slippage_bp = params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
 
@@ -215,10 +267,18 @@ fn build_buy_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
         &params.output_mint,
         protocol_params.fee_sharing_creator_vault_if_active,
     )
-    .ok_or_else(|| anyhow!("creator_vault PDA derivation failed (creator={})", creator))?;
+    .ok_or_else(|| {
+        TradeError::pda_derivation_failed(format!(
+            "creator_vault PDA derivation failed (creator={})",
+            creator
+        ))
+    })?;
 
     let bonding_curve_addr = get_bonding_curve_pda(&params.output_mint).ok_or_else(|| {
-        anyhow!("bonding_curve PDA derivation failed for mint {}", params.output_mint)
+        TradeError::pda_derivation_failed(format!(
+            "bonding_curve PDA derivation failed for mint {}",
+            params.output_mint
+        ))
     })?;
 
     let is_mayhem_mode = bonding_curve.is_mayhem_mode;
@@ -245,14 +305,16 @@ fn build_buy_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
         );
 
     let user_volume_accumulator = get_user_volume_accumulator_pda(&params.payer.pubkey())
-        .ok_or_else(|| anyhow!("user_volume_accumulator PDA derivation failed"))?;
+        .ok_or_else(|| {
+            TradeError::pda_derivation_failed("user_volume_accumulator PDA derivation failed")
+        })?;
 
     let mut instructions = Vec::with_capacity(2);
 
     if params.create_output_mint_ata {
         instructions.extend(
             crate::common::fast_fn::create_associated_token_account_idempotent_fast_use_seed(
-                &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &params.payer.pubkey(),
                 &params.output_mint,
                 &token_program,
@@ -265,7 +327,9 @@ fn build_buy_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
     let track_volume_val = if bonding_curve.is_cashback_coin { 1u8 } else { 0u8 };
     let ix_version = PumpFunIxVersion::Legacy { track_volume: track_volume_val };
     let buy_data = if let Some(token_amount) = params.fixed_output_amount {
-        encode_pumpfun_buy_ix_data(token_amount, lamports_in, ix_version)
+        let max_sol_cost =
+            exact_out_max_quote(bonding_curve, creator, token_amount, lamports_in, slippage_bp)?;
+        encode_pumpfun_buy_ix_data(token_amount, max_sol_cost, ix_version)
     } else {
         let buy_token_amount = get_buy_token_amount_from_sol_amount(
             bonding_curve.virtual_token_reserves as u128,
@@ -287,28 +351,40 @@ fn build_buy_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
         pump_fun_fee_recipient_meta(protocol_params.fee_recipient, is_mayhem_mode);
 
     let bonding_curve_v2 = get_bonding_curve_v2_pda(&params.output_mint).ok_or_else(|| {
-        anyhow!("bonding_curve_v2 PDA derivation failed for mint {}", params.output_mint)
+        TradeError::pda_derivation_failed(format!(
+            "bonding_curve_v2 PDA derivation failed for mint {}",
+            params.output_mint
+        ))
+    })?;
+    let metas = layout_metas(PumpFunLayout::Legacy, true, |role| {
+        Ok(Some(match role {
+            PumpFunAccount::Global => global_constants::GLOBAL_ACCOUNT_META,
+            PumpFunAccount::FeeRecipient => fee_recipient_meta.clone(),
+            PumpFunAccount::Mint => AccountMeta::new_readonly(params.output_mint, false),
+            PumpFunAccount::BondingCurve => AccountMeta::new(bonding_curve_addr, false),
+            PumpFunAccount::AssociatedBondingCurve => {
+                AccountMeta::new(associated_bonding_curve, false)
+            }
+            PumpFunAccount::UserTokenAccount => AccountMeta::new(user_token_account, false),
+            PumpFunAccount::User => AccountMeta::new(params.payer.pubkey(), true),
+            PumpFunAccount::SystemProgram => crate::constants::SYSTEM_PROGRAM_META,
+            PumpFunAccount::BaseTokenProgram => token_program_meta.clone(),
+            PumpFunAccount::CreatorVault => AccountMeta::new(creator_vault_account, false),
+            PumpFunAccount::EventAuthority => accounts::EVENT_AUTHORITY_META,
+            PumpFunAccount::Program => accounts::PUMPFUN_META,
+            PumpFunAccount::GlobalVolumeAccumulator => accounts::GLOBAL_VOLUME_ACCUMULATOR_META,
+            PumpFunAccount::UserVolumeAccumulator => {
+                AccountMeta::new(user_volume_accumulator, false)
+            }
+            PumpFunAccount::FeeConfig => accounts::FEE_CONFIG_META,
+            PumpFunAccount::FeeProgram => accounts::FEE_PROGRAM_META,
+            PumpFunAccount::BondingCurveV2 => AccountMeta::new_readonly(bonding_curve_v2, false),
+            PumpFunAccount::ProtocolExtraFeeRecipient => {
+                AccountMeta::new(get_protocol_extra_fee_recipient_random(), false)
+            }
+            other => return unlisted_role(other),
+        }))
     })?;
-    let mut metas: Vec<AccountMeta> = vec![
-        global_constants::GLOBAL_ACCOUNT_META,
-        fee_recipient_meta,
-        AccountMeta::new_readonly(params.output_mint, false),
-        AccountMeta::new(bonding_curve_addr, false),
-        AccountMeta::new(associated_bonding_curve, false),
-        AccountMeta::new(user_token_account, false),
-        AccountMeta::new(params.payer.pubkey(), true),
-        crate::constants::SYSTEM_PROGRAM_META,
-        token_program_meta,
-        AccountMeta::new(creator_vault_account, false),
-        accounts::EVENT_AUTHORITY_META,
-        accounts::PUMPFUN_META,
-        accounts::GLOBAL_VOLUME_ACCUMULATOR_META,
-        AccountMeta::new(user_volume_accumulator, false),
-        accounts::FEE_CONFIG_META,
-        accounts::FEE_PROGRAM_META,
-    ];
-    metas.push(AccountMeta::new_readonly(bonding_curve_v2, false));
-    metas.push(AccountMeta::new(get_protocol_extra_fee_recipient_random(), false));
 
     instructions.push(Instruction::new_with_bytes(accounts::PUMPFUN, buy_data.as_slice(), metas));
 
@@ -335,14 +411,7 @@ fn build_sell_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
         ));
     }
 
-    let token_amount = if let Some(amount) = params.input_amount {
-        if amount == 0 {
-            return Err(anyhow!("Amount cannot be zero"));
-        }
-        amount
-    } else {
-        return Err(anyhow!("Amount token is required"));
-    };
+    let token_amount = params.required_input_amount()?;
 
     let slippage_bp = params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
 
@@ -362,7 +431,10 @@ fn build_sell_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
             protocol_params.fee_sharing_creator_vault_if_active,
         )
         .ok_or_else(|| {
-            anyhow!("creator_vault PDA derivation failed (curve_creator={})", bonding_curve.creator)
+            TradeError::pda_derivation_failed(format!(
+                "creator_vault PDA derivation failed (curve_creator={})",
+                bonding_curve.creator
+            ))
         })?
     };
 
@@ -380,7 +452,10 @@ fn build_sell_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
     };
 
     let bonding_curve_addr = get_bonding_curve_pda(&params.input_mint).ok_or_else(|| {
-        anyhow!("bonding_curve PDA derivation failed for mint {}", params.input_mint)
+        TradeError::pda_derivation_failed(format!(
+            "bonding_curve PDA derivation failed for mint {}",
+            params.input_mint
+        ))
     })?;
 
     let is_mayhem_mode = bonding_curve.is_mayhem_mode;
@@ -407,7 +482,9 @@ fn build_sell_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
         );
 
     let user_volume_accumulator = get_user_volume_accumulator_pda(&params.payer.pubkey())
-        .ok_or_else(|| anyhow!("user_volume_accumulator PDA derivation failed"))?;
+        .ok_or_else(|| {
+            TradeError::pda_derivation_failed("user_volume_accumulator PDA derivation failed")
+        })?;
 
     let mut instructions = Vec::with_capacity(2);
     let sell_data = encode_pumpfun_sell_ix_data(
@@ -420,31 +497,41 @@ fn build_sell_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
         pump_fun_fee_recipient_meta(protocol_params.fee_recipient, is_mayhem_mode);
 
     let bonding_curve_v2 = get_bonding_curve_v2_pda(&params.input_mint).ok_or_else(|| {
-        anyhow!("bonding_curve_v2 PDA derivation failed for mint {}", params.input_mint)
+        TradeError::pda_derivation_failed(format!(
+            "bonding_curve_v2 PDA derivation failed for mint {}",
+            params.input_mint
+        ))
+    })?;
+    let metas = layout_metas(PumpFunLayout::Legacy, false, |role| {
+        Ok(Some(match role {
+            PumpFunAccount::Global => global_constants::GLOBAL_ACCOUNT_META,
+            PumpFunAccount::FeeRecipient => fee_recipient_meta.clone(),
+            PumpFunAccount::Mint => AccountMeta::new_readonly(params.input_mint, false),
+            PumpFunAccount::BondingCurve => AccountMeta::new(bonding_curve_addr, false),
+            PumpFunAccount::AssociatedBondingCurve => {
+                AccountMeta::new(associated_bonding_curve, false)
+            }
+            PumpFunAccount::UserTokenAccount => AccountMeta::new(user_token_account, false),
+            PumpFunAccount::User => AccountMeta::new(params.payer.pubkey(), true),
+            PumpFunAccount::SystemProgram => crate::constants::SYSTEM_PROGRAM_META,
+            PumpFunAccount::CreatorVault => AccountMeta::new(creator_vault_account, false),
+            PumpFunAccount::BaseTokenProgram => token_program_meta.clone(),
+            PumpFunAccount::EventAuthority => accounts::EVENT_AUTHORITY_META,
+            PumpFunAccount::Program => accounts::PUMPFUN_META,
+            PumpFunAccount::FeeConfig => accounts::FEE_CONFIG_META,
+            PumpFunAccount::FeeProgram => accounts::FEE_PROGRAM_META,
+            // legacy sell takes the volume accumulator only for cashback coins
+            PumpFunAccount::UserVolumeAccumulator if bonding_curve.is_cashback_coin => {
+                AccountMeta::new(user_volume_accumulator, false)
+            }
+            PumpFunAccount::UserVolumeAccumulator => return Ok(None),
+            PumpFunAccount::BondingCurveV2 => AccountMeta::new_readonly(bonding_curve_v2, false),
+            PumpFunAccount::ProtocolExtraFeeRecipient => {
+                AccountMeta::new(get_protocol_extra_fee_recipient_random(), false)
+            }
+            other => return unlisted_role(other),
+        }))
     })?;
-    let mut metas: Vec<AccountMeta> = vec![
-        global_constants::GLOBAL_ACCOUNT_META,
-        fee_recipient_meta,
-        AccountMeta::new_readonly(params.input_mint, false),
-        AccountMeta::new(bonding_curve_addr, false),
-        AccountMeta::new(associated_bonding_curve, false),
-        AccountMeta::new(user_token_account, false),
-        AccountMeta::new(params.payer.pubkey(), true),
-        crate::constants::SYSTEM_PROGRAM_META,
-        AccountMeta::new(creator_vault_account, false),
-        token_program_meta,
-        accounts::EVENT_AUTHORITY_META,
-        accounts::PUMPFUN_META,
-        accounts::FEE_CONFIG_META,
-        accounts::FEE_PROGRAM_META,
-    ];
-
-    if bonding_curve.is_cashback_coin {
-        metas.push(AccountMeta::new(user_volume_accumulator, false));
-    }
-
-    metas.push(AccountMeta::new_readonly(bonding_curve_v2, false));
-    metas.push(AccountMeta::new(get_protocol_extra_fee_recipient_random(), false));
 
     instructions.push(Instruction::new_with_bytes(accounts::PUMPFUN, sell_data.as_slice(), metas));
 
@@ -480,10 +567,7 @@ fn build_buy_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
         .downcast_ref::<PumpFunParams>()
         .ok_or_else(|| anyhow!("Invalid protocol params for PumpFun"))?;
 
-    let lamports_in = params.input_amount.unwrap_or(0);
-    if lamports_in == 0 {
-        return Err(anyhow!("Amount cannot be zero"));
-    }
+    let lamports_in = params.required_input_amount()?;
 
     let slippage_bp = params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
 
@@ -495,10 +579,18 @@ fn build_buy_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
         &params.output_mint,
         protocol_params.fee_sharing_creator_vault_if_active,
     )
-    .ok_or_else(|| anyhow!("creator_vault PDA derivation failed (creator={})", creator))?;
+    .ok_or_else(|| {
+        TradeError::pda_derivation_failed(format!(
+            "creator_vault PDA derivation failed (creator={})",
+            creator
+        ))
+    })?;
 
     let bonding_curve_addr = get_bonding_curve_pda(&params.output_mint).ok_or_else(|| {
-        anyhow!("bonding_curve PDA derivation failed for mint {}", params.output_mint)
+        TradeError::pda_derivation_failed(format!(
+            "bonding_curve PDA derivation failed for mint {}",
+            params.output_mint
+        ))
     })?;
 
     let is_mayhem_mode = bonding_curve.is_mayhem_mode;
@@ -567,11 +659,16 @@ fn build_buy_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
         );
 
     let sharing_config = get_fee_sharing_config_pda(&params.output_mint).ok_or_else(|| {
-        anyhow!("sharing_config PDA derivation failed for mint {}", params.output_mint)
+        TradeError::pda_derivation_failed(format!(
+            "sharing_config PDA derivation failed for mint {}",
+            params.output_mint
+        ))
     })?;
 
     let user_volume_accumulator = get_user_volume_accumulator_pda(&params.payer.pubkey())
-        .ok_or_else(|| anyhow!("user_volume_accumulator PDA derivation failed"))?;
+        .ok_or_else(|| {
+            TradeError::pda_derivation_failed("user_volume_accumulator PDA derivation failed")
+        })?;
     let associated_user_volume_accumulator =
         crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
             &user_volume_accumulator,
@@ -584,7 +681,7 @@ fn build_buy_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
     if params.create_output_mint_ata {
         instructions.extend(
             crate::common::fast_fn::create_associated_token_account_idempotent_fast_use_seed(
-                &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &params.payer.pubkey(),
                 &params.output_mint,
                 &base_token_program,
@@ -594,7 +691,12 @@ fn build_buy_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
     }
 
     let (buy_data, quote_amount_to_fund) = if let Some(token_amount) = params.fixed_output_amount {
-        (encode_pumpfun_buy_ix_data(token_amount, lamports_in, PumpFunIxVersion::V2), lamports_in)
+        let max_quote_cost =
+            exact_out_max_quote(bonding_curve, creator, token_amount, lamports_in, slippage_bp)?;
+        (
+            encode_pumpfun_buy_ix_data(token_amount, max_quote_cost, PumpFunIxVersion::V2),
+            max_quote_cost,
+        )
     } else {
         let buy_token_amount = get_buy_token_amount_from_sol_amount(
             bonding_curve.virtual_token_reserves as u128,
@@ -628,6 +730,7 @@ fn build_buy_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
         push_create_or_wrap_user_token_account(
             &mut instructions,
             &params.payer.pubkey(),
+            &params.ata_rent_payer(),
             &quote_mint,
             &quote_token_program,
             quote_amount_to_fund,
@@ -635,35 +738,54 @@ fn build_buy_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
         );
     }
 
-    let metas: Vec<AccountMeta> = vec![
-        global_constants::GLOBAL_ACCOUNT_META,
-        AccountMeta::new_readonly(params.output_mint, false),
-        AccountMeta::new_readonly(quote_mint, false),
-        base_token_program_meta,
-        quote_token_program_meta,
-        AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false),
-        fee_recipient_meta,
-        AccountMeta::new(associated_quote_fee_recipient, false),
-        AccountMeta::new(buyback_fee_recipient, false),
-        AccountMeta::new(associated_quote_buyback_fee_recipient, false),
-        AccountMeta::new(bonding_curve_addr, false),
-        AccountMeta::new(associated_base_bonding_curve, false),
-        AccountMeta::new(associated_quote_bonding_curve, false),
-        AccountMeta::new(params.payer.pubkey(), true),
-        AccountMeta::new(associated_base_user, false),
-        AccountMeta::new(associated_quote_user, false),
-        AccountMeta::new(creator_vault_account, false),
-        AccountMeta::new(associated_creator_vault, false),
-        AccountMeta::new_readonly(sharing_config, false),
-        accounts::GLOBAL_VOLUME_ACCUMULATOR_META,
-        AccountMeta::new(user_volume_accumulator, false),
-        AccountMeta::new(associated_user_volume_accumulator, false),
-        accounts::FEE_CONFIG_META,
-        accounts::FEE_PROGRAM_META,
-        crate::constants::SYSTEM_PROGRAM_META,
-        accounts::EVENT_AUTHORITY_META,
-        accounts::PUMPFUN_META,
-    ];
+    let metas = layout_metas(PumpFunLayout::V2, true, |role| {
+        Ok(Some(match role {
+            PumpFunAccount::Global => global_constants::GLOBAL_ACCOUNT_META,
+            PumpFunAccount::Mint => AccountMeta::new_readonly(params.output_mint, false),
+            PumpFunAccount::QuoteMint => AccountMeta::new_readonly(quote_mint, false),
+            PumpFunAccount::BaseTokenProgram => base_token_program_meta.clone(),
+            PumpFunAccount::QuoteTokenProgram => quote_token_program_meta.clone(),
+            PumpFunAccount::AssociatedTokenProgram => {
+                AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false)
+            }
+            PumpFunAccount::FeeRecipient => fee_recipient_meta.clone(),
+            PumpFunAccount::AssociatedQuoteFeeRecipient => {
+                AccountMeta::new(associated_quote_fee_recipient, false)
+            }
+            PumpFunAccount::BuybackFeeRecipient => AccountMeta::new(buyback_fee_recipient, false),
+            PumpFunAccount::AssociatedQuoteBuybackFeeRecipient => {
+                AccountMeta::new(associated_quote_buyback_fee_recipient, false)
+            }
+            PumpFunAccount::BondingCurve => AccountMeta::new(bonding_curve_addr, false),
+            PumpFunAccount::AssociatedBondingCurve => {
+                AccountMeta::new(associated_base_bonding_curve, false)
+            }
+            PumpFunAccount::AssociatedQuoteBondingCurve => {
+                AccountMeta::new(associated_quote_bonding_curve, false)
+            }
+            PumpFunAccount::User => AccountMeta::new(params.payer.pubkey(), true),
+            PumpFunAccount::UserTokenAccount => AccountMeta::new(associated_base_user, false),
+            PumpFunAccount::UserQuoteTokenAccount => AccountMeta::new(associated_quote_user, false),
+            PumpFunAccount::CreatorVault => AccountMeta::new(creator_vault_account, false),
+            PumpFunAccount::AssociatedCreatorVault => {
+                AccountMeta::new(associated_creator_vault, false)
+            }
+            PumpFunAccount::SharingConfig => AccountMeta::new_readonly(sharing_config, false),
+            PumpFunAccount::GlobalVolumeAccumulator => accounts::GLOBAL_VOLUME_ACCUMULATOR_META,
+            PumpFunAccount::UserVolumeAccumulator => {
+                AccountMeta::new(user_volume_accumulator, false)
+            }
+            PumpFunAccount::AssociatedUserVolumeAccumulator => {
+                AccountMeta::new(associated_user_volume_accumulator, false)
+            }
+            PumpFunAccount::FeeConfig => accounts::FEE_CONFIG_META,
+            PumpFunAccount::FeeProgram => accounts::FEE_PROGRAM_META,
+            PumpFunAccount::SystemProgram => crate::constants::SYSTEM_PROGRAM_META,
+            PumpFunAccount::EventAuthority => accounts::EVENT_AUTHORITY_META,
+            PumpFunAccount::Program => accounts::PUMPFUN_META,
+            other => return unlisted_role(other),
+        }))
+    })?;
 
     instructions.push(Instruction::new_with_bytes(accounts::PUMPFUN, buy_data.as_slice(), metas));
 
@@ -687,14 +809,7 @@ fn build_sell_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
         .downcast_ref::<PumpFunParams>()
         .ok_or_else(|| anyhow!("Invalid protocol params for PumpFun"))?;
 
-    let token_amount = if let Some(amount) = params.input_amount {
-        if amount == 0 {
-            return Err(anyhow!("Amount cannot be zero"));
-        }
-        amount
-    } else {
-        return Err(anyhow!("Amount token is required"));
-    };
+    let token_amount = params.required_input_amount()?;
 
     let slippage_bp = params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
 
@@ -714,7 +829,10 @@ fn build_sell_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
             protocol_params.fee_sharing_creator_vault_if_active,
         )
         .ok_or_else(|| {
-            anyhow!("creator_vault PDA derivation failed (curve_creator={})", bonding_curve.creator)
+            TradeError::pda_derivation_failed(format!(
+                "creator_vault PDA derivation failed (curve_creator={})",
+                bonding_curve.creator
+            ))
         })?
     };
 
@@ -732,7 +850,10 @@ fn build_sell_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
     };
 
     let bonding_curve_addr = get_bonding_curve_pda(&params.input_mint).ok_or_else(|| {
-        anyhow!("bonding_curve PDA derivation failed for mint {}", params.input_mint)
+        TradeError::pda_derivation_failed(format!(
+            "bonding_curve PDA derivation failed for mint {}",
+            params.input_mint
+        ))
     })?;
 
     let is_mayhem_mode = bonding_curve.is_mayhem_mode;
@@ -800,11 +921,16 @@ fn build_sell_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
         );
 
     let sharing_config = get_fee_sharing_config_pda(&params.input_mint).ok_or_else(|| {
-        anyhow!("sharing_config PDA derivation failed for mint {}", params.input_mint)
+        TradeError::pda_derivation_failed(format!(
+            "sharing_config PDA derivation failed for mint {}",
+            params.input_mint
+        ))
     })?;
 
     let user_volume_accumulator = get_user_volume_accumulator_pda(&params.payer.pubkey())
-        .ok_or_else(|| anyhow!("user_volume_accumulator PDA derivation failed"))?;
+        .ok_or_else(|| {
+            TradeError::pda_derivation_failed("user_volume_accumulator PDA derivation failed")
+        })?;
     let associated_user_volume_accumulator =
         crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
             &user_volume_accumulator,
@@ -818,6 +944,7 @@ fn build_sell_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
         push_create_user_token_account(
             &mut instructions,
             &params.payer.pubkey(),
+            &params.ata_rent_payer(),
             &quote_mint,
             &quote_token_program,
             params.open_seed_optimize,
@@ -826,34 +953,53 @@ fn build_sell_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
 
     let sell_data = encode_pumpfun_sell_ix_data(token_amount, min_sol_output, PumpFunIxVersion::V2);
 
-    let metas: Vec<AccountMeta> = vec![
-        global_constants::GLOBAL_ACCOUNT_META,
-        AccountMeta::new_readonly(params.input_mint, false),
-        AccountMeta::new_readonly(quote_mint, false),
-        base_token_program_meta,
-        quote_token_program_meta,
-        AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false),
-        fee_recipient_meta,
-        AccountMeta::new(associated_quote_fee_recipient, false),
-        AccountMeta::new(buyback_fee_recipient, false),
-        AccountMeta::new(associated_quote_buyback_fee_recipient, false),
-        AccountMeta::new(bonding_curve_addr, false),
-        AccountMeta::new(associated_base_bonding_curve, false),
-        AccountMeta::new(associated_quote_bonding_curve, false),
-        AccountMeta::new(params.payer.pubkey(), true),
-        AccountMeta::new(associated_base_user, false),
-        AccountMeta::new(associated_quote_user, false),
-        AccountMeta::new(creator_vault_account, false),
-        AccountMeta::new(associated_creator_vault, false),
-        AccountMeta::new_readonly(sharing_config, false),
-        AccountMeta::new(user_volume_accumulator, false),
-        AccountMeta::new(associated_user_volume_accumulator, false),
-        accounts::FEE_CONFIG_META,
-        accounts::FEE_PROGRAM_META,
-        crate::constants::SYSTEM_PROGRAM_META,
-        accounts::EVENT_AUTHORITY_META,
-        accounts::PUMPFUN_META,
-    ];
+    let metas = layout_metas(PumpFunLayout::V2, false, |role| {
+        Ok(Some(match role {
+            PumpFunAccount::Global => global_constants::GLOBAL_ACCOUNT_META,
+            PumpFunAccount::Mint => AccountMeta::new_readonly(params.input_mint, false),
+            PumpFunAccount::QuoteMint => AccountMeta::new_readonly(quote_mint, false),
+            PumpFunAccount::BaseTokenProgram => base_token_program_meta.clone(),
+            PumpFunAccount::QuoteTokenProgram => quote_token_program_meta.clone(),
+            PumpFunAccount::AssociatedTokenProgram => {
+                AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false)
+            }
+            PumpFunAccount::FeeRecipient => fee_recipient_meta.clone(),
+            PumpFunAccount::AssociatedQuoteFeeRecipient => {
+                AccountMeta::new(associated_quote_fee_recipient, false)
+            }
+            PumpFunAccount::BuybackFeeRecipient => AccountMeta::new(buyback_fee_recipient, false),
+            PumpFunAccount::AssociatedQuoteBuybackFeeRecipient => {
+                AccountMeta::new(associated_quote_buyback_fee_recipient, false)
+            }
+            PumpFunAccount::BondingCurve => AccountMeta::new(bonding_curve_addr, false),
+            PumpFunAccount::AssociatedBondingCurve => {
+                AccountMeta::new(associated_base_bonding_curve, false)
+            }
+            PumpFunAccount::AssociatedQuoteBondingCurve => {
+                AccountMeta::new(associated_quote_bonding_curve, false)
+            }
+            PumpFunAccount::User => AccountMeta::new(params.payer.pubkey(), true),
+            PumpFunAccount::UserTokenAccount => AccountMeta::new(associated_base_user, false),
+            PumpFunAccount::UserQuoteTokenAccount => AccountMeta::new(associated_quote_user, false),
+            PumpFunAccount::CreatorVault => AccountMeta::new(creator_vault_account, false),
+            PumpFunAccount::AssociatedCreatorVault => {
+                AccountMeta::new(associated_creator_vault, false)
+            }
+            PumpFunAccount::SharingConfig => AccountMeta::new_readonly(sharing_config, false),
+            PumpFunAccount::UserVolumeAccumulator => {
+                AccountMeta::new(user_volume_accumulator, false)
+            }
+            PumpFunAccount::AssociatedUserVolumeAccumulator => {
+                AccountMeta::new(associated_user_volume_accumulator, false)
+            }
+            PumpFunAccount::FeeConfig => accounts::FEE_CONFIG_META,
+            PumpFunAccount::FeeProgram => accounts::FEE_PROGRAM_META,
+            PumpFunAccount::SystemProgram => crate::constants::SYSTEM_PROGRAM_META,
+            PumpFunAccount::EventAuthority => accounts::EVENT_AUTHORITY_META,
+            PumpFunAccount::Program => accounts::PUMPFUN_META,
+            other => return unlisted_role(other),
+        }))
+    })?;
 
     instructions.push(Instruction::new_with_bytes(accounts::PUMPFUN, sell_data.as_slice(), metas));
 
@@ -875,6 +1021,61 @@ fn build_sell_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
     Ok(instructions)
 }
 
+// ---------------------------------------------------------------------------
+// Legacy create (SPL Token mint + Metaplex metadata)
+// ---------------------------------------------------------------------------
+
+/// Legacy `create(name, symbol, uri, creator)`; `mint` must sign alongside `payer`.
+/// The curve starts at the initial reserves, so a buy can follow in the same transaction.
+///
+/// The program initializes `associated_bonding_curve` itself (hence the ATA program and rent
+/// accounts), so it must not be pre-created: an earlier idempotent create would make `create`
+/// fail on the already-initialized account. Only the buyer's token account needs its own
+/// instruction, which the buy builder emits when `create_output_mint_ata` is set.
+pub fn create_pumpfun_instruction(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+    creator: &Pubkey,
+) -> Result<Instruction> {
+    let bonding_curve = get_bonding_curve_pda(mint)
+        .ok_or_else(|| TradeError::pda_derivation_failed("bonding curve PDA derivation failed"))?;
+    let associated_bonding_curve =
+        crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
+            &bonding_curve,
+            mint,
+            &crate::constants::TOKEN_PROGRAM,
+        );
+
+    let mut data = Vec::with_capacity(8 + 12 + name.len() + symbol.len() + uri.len() + 32);
+    data.extend_from_slice(&CREATE_DISCRIMINATOR);
+    for field in [name, symbol, uri] {
+        data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        data.extend_from_slice(field.as_bytes());
+    }
+    data.extend_from_slice(creator.as_ref());
+
+    let metas = vec![
+        AccountMeta::new(*mint, true),
+        AccountMeta::new_readonly(get_mint_authority_pda(), false),
+        AccountMeta::new(bonding_curve, false),
+        AccountMeta::new(associated_bonding_curve, false),
+        global_constants::GLOBAL_ACCOUNT_META,
+        AccountMeta::new_readonly(accounts::MPL_TOKEN_METADATA, false),
+        AccountMeta::new(get_metadata_pda(mint), false),
+        AccountMeta::new(*payer, true),
+        crate::constants::SYSTEM_PROGRAM_META,
+        crate::constants::TOKEN_PROGRAM_META,
+        crate::constants::ASSOCIATED_TOKEN_PROGRAM_META,
+        crate::constants::RENT_META,
+        accounts::EVENT_AUTHORITY_META,
+        accounts::PUMPFUN_META,
+    ];
+    Ok(Instruction { program_id: accounts::PUMPFUN, accounts: metas, data })
+}
+
 // ---------------------------------------------------------------------------
 // Shared: claim_cashback (independent of V1/V2)
 // ---------------------------------------------------------------------------
@@ -897,9 +1098,9 @@ pub fn claim_cashback_pumpfun_instruction(payer: &Pubkey) -> Option<Instruction>
 mod tests {
     use super::*;
     use crate::{
-        common::{bonding_curve::BondingCurveAccount, GasFeeStrategy},
+        common::{bonding_curve::BondingCurveAccount, GasFeeStrategy, ParallelStrategy},
         constants::TOKEN_PROGRAM,
-        trading::core::params::{DexParamEnum, PumpFunParams, SwapParams},
+        trading::core::params::{AtaCreationMode, DexParamEnum, PumpFunParams, SwapParams},
     };
     use solana_sdk::signature::Keypair;
     use std::sync::Arc;
@@ -932,6 +1133,7 @@ mod tests {
             close_token_account_when_sell: None,
             fee_recipient: global_constants::FEE_RECIPIENT,
             quote_mint: Pubkey::default(),
+            layout: PumpFunLayout::Auto,
         };
 
         SwapParams {
@@ -969,9 +1171,72 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: Some(true),
+            swqos_watchdog: None,
+            ata_creation: AtaCreationMode::Inline,
+            memo: None,
+            parallel_strategy: ParallelStrategy::RaceAll,
+            max_ata_creations: None,
+            cu_learner: None,
+            rpc_fallback: false,
+            cleanup_on_failure: false,
+            skip_compute_budget: false,
+            simulation_capture: None,
+            ata_rent_funder: None,
+            receive_native_sol: false,
+            on_before_send: None,
+            multi_nonce: None,
+            min_tokens_out: None,
         }
     }
 
+    fn expected_exact_out_max(params: &SwapParams, token_amount: u64) -> u64 {
+        let DexParamEnum::PumpFun(p) = &params.protocol_params else { unreachable!() };
+        let cost = get_buy_sol_amount_from_token_amount(
+            p.bonding_curve.virtual_token_reserves as u128,
+            p.bonding_curve.virtual_sol_reserves as u128,
+            p.bonding_curve.real_token_reserves as u128,
+            p.effective_creator_for_trade(),
+            token_amount,
+        )
+        .unwrap();
+        calculate_with_slippage_buy(cost, params.slippage_basis_points.unwrap())
+    }
+
+    #[test]
+    fn fixed_output_zero_over_budget_or_beyond_real_reserves_is_rejected() {
+        let mut params = swap_params_for_buy(pump_mint(), TOKEN_PROGRAM);
+        params.fixed_output_amount = Some(global_constants::INITIAL_REAL_TOKEN_RESERVES + 1);
+        assert!(build_buy(&params).is_err());
+
+        params.fixed_output_amount = Some(42);
+        params.input_amount = Some(expected_exact_out_max(&params, 42) - 1);
+        assert!(build_buy(&params).is_err());
+
+        params.fixed_output_amount = Some(0);
+        assert_eq!(params.validate().unwrap_err().code, TradeError::INVALID_PROTOCOL_PARAMS);
+    }
+
+    #[test]
+    fn unset_and_zero_input_amounts_fail_with_distinct_codes() {
+        let code = |params: &SwapParams, sell: bool| {
+            let result = if sell { build_sell(params) } else { build_buy(params) };
+            result.unwrap_err().downcast_ref::<TradeError>().unwrap().code
+        };
+        let mut params = swap_params_for_buy(pump_mint(), TOKEN_PROGRAM);
+        params.input_amount = None;
+        assert_eq!(code(&params, false), TradeError::AMOUNT_NOT_SET);
+        params.input_amount = Some(0);
+        assert_eq!(code(&params, false), TradeError::ZERO_AMOUNT);
+
+        params.trade_type = crate::swqos::TradeType::Sell;
+        params.input_mint = pump_mint();
+        params.output_mint = crate::constants::SOL_TOKEN_ACCOUNT;
+        params.input_amount = None;
+        assert_eq!(code(&params, true), TradeError::AMOUNT_NOT_SET);
+        params.input_amount = Some(0);
+        assert_eq!(code(&params, true), TradeError::ZERO_AMOUNT);
+    }
+
     #[test]
     fn test_claim_cashback_instruction() {
         let payer = Pubkey::new_unique();
@@ -980,6 +1245,73 @@ mod tests {
         assert_eq!(ix.accounts[0].pubkey, payer);
     }
 
+    #[test]
+    fn create_instruction_encodes_borsh_args_and_signers() {
+        let (payer, mint, creator) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = create_pumpfun_instruction(&payer, &mint, "Coin", "CN", "https://x/y", &creator)
+            .unwrap();
+
+        assert_eq!(&ix.data[..8], &CREATE_DISCRIMINATOR);
+        assert_eq!(&ix.data[8..12], &4u32.to_le_bytes());
+        assert_eq!(&ix.data[12..16], b"Coin");
+        assert_eq!(&ix.data[ix.data.len() - 32..], creator.as_ref());
+        assert_eq!(ix.data.len(), 8 + (4 + 4) + (4 + 2) + (4 + 11) + 32);
+
+        let signers: Vec<_> =
+            ix.accounts.iter().filter(|m| m.is_signer).map(|m| m.pubkey).collect();
+        assert_eq!(signers, vec![mint, payer]);
+        assert_eq!(ix.accounts[2].pubkey, get_bonding_curve_pda(&mint).unwrap());
+        assert_eq!(ix.accounts[6].pubkey, get_metadata_pda(&mint));
+    }
+
+    #[test]
+    fn create_and_buy_on_a_fresh_mint_creates_every_token_account_it_uses() {
+        crate::common::seed::set_default_rents();
+        let mint = Pubkey::new_unique();
+        let mut params = swap_params_for_buy(mint, TOKEN_PROGRAM);
+        params.trade_type = TradeType::CreateAndBuy;
+        let payer = params.payer.pubkey();
+        let create_ix =
+            create_pumpfun_instruction(&payer, &mint, "Coin", "CN", "https://x/y", &payer).unwrap();
+        let buy_ixs = build_buy(&params).unwrap();
+
+        let associated_bonding_curve =
+            crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
+                &get_bonding_curve_pda(&mint).unwrap(),
+                &mint,
+                &TOKEN_PROGRAM,
+            );
+        // `create` initializes the curve's token account, so nothing else may create it
+        assert!(create_ix
+            .accounts
+            .iter()
+            .any(|m| m.pubkey == associated_bonding_curve && m.is_writable));
+        assert!(create_ix
+            .accounts
+            .iter()
+            .any(|m| m.pubkey == crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID));
+        let (buy, creations) = buy_ixs.split_last().unwrap();
+        assert_eq!(buy.program_id, accounts::PUMPFUN);
+        assert!(buy.accounts.iter().any(|m| m.pubkey == associated_bonding_curve));
+        assert!(creations
+            .iter()
+            .all(|ix| ix.accounts.iter().all(|m| m.pubkey != associated_bonding_curve)));
+
+        // the buyer's token account is created ahead of the buy
+        let user_token_account =
+            crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed(
+                &payer,
+                &mint,
+                &TOKEN_PROGRAM,
+                params.open_seed_optimize,
+            );
+        assert!(buy.accounts.iter().any(|m| m.pubkey == user_token_account));
+        assert!(creations
+            .iter()
+            .any(|ix| ix.accounts.iter().any(|m| m.pubkey == user_token_account && m.is_writable)));
+    }
+
     #[test]
     fn pump_suffix_buy_forces_token_2022_even_with_explicit_legacy_token_program() {
         crate::common::seed::set_default_rents();
@@ -1018,7 +1350,7 @@ mod tests {
     }
 
     #[test]
-    fn pumpfun_v1_fixed_output_uses_buy_with_max_input_budget() {
+    fn pumpfun_v1_fixed_output_uses_buy_with_max_input_from_reserves() {
         let mut params = swap_params_for_buy(pump_mint(), TOKEN_PROGRAM);
         params.create_output_mint_ata = false;
         params.fixed_output_amount = Some(42);
@@ -1030,15 +1362,14 @@ mod tests {
         assert_eq!(&ix.data[..8], crate::instruction::utils::pumpfun::BUY_DISCRIMINATOR);
         assert_eq!(ix.data.len(), 25);
         assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 42);
-        assert_eq!(
-            u64::from_le_bytes(ix.data[16..24].try_into().unwrap()),
-            params.input_amount.unwrap()
-        );
+        let max_quote = u64::from_le_bytes(ix.data[16..24].try_into().unwrap());
+        assert_eq!(max_quote, expected_exact_out_max(&params, 42));
+        assert!(max_quote < params.input_amount.unwrap());
         assert_eq!(ix.data[24], 0);
     }
 
     #[test]
-    fn pumpfun_v2_usdc_fixed_output_uses_buy_with_max_input_budget() {
+    fn pumpfun_v2_usdc_fixed_output_uses_buy_with_max_input_from_reserves() {
         let mut params = swap_params_for_buy(pump_mint(), TOKEN_PROGRAM);
         params.create_output_mint_ata = false;
         params.input_mint = crate::constants::USDC_TOKEN_ACCOUNT;
@@ -1054,10 +1385,9 @@ mod tests {
 
         assert_eq!(&ix.data[..8], crate::instruction::utils::pumpfun::BUY_V2_DISCRIMINATOR);
         assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 42);
-        assert_eq!(
-            u64::from_le_bytes(ix.data[16..24].try_into().unwrap()),
-            params.input_amount.unwrap()
-        );
+        let max_quote = u64::from_le_bytes(ix.data[16..24].try_into().unwrap());
+        assert_eq!(max_quote, expected_exact_out_max(&params, 42));
+        assert!(max_quote < params.input_amount.unwrap());
     }
 
     #[test]
@@ -1122,14 +1452,17 @@ mod tests {
         let business_instructions = build_buy(&params).unwrap();
         let err = crate::trading::common::transaction_builder::build_transaction(
             &params.payer,
+            None,
             150_000,
             500_000,
+            false,
             &business_instructions,
             &[],
             Some(solana_hash::Hash::new_unique()),
             None,
             "PumpFun",
             true,
+            None,
             true,
             &Pubkey::new_unique(),
             0.001,
@@ -1157,14 +1490,17 @@ mod tests {
         let business_instructions = build_buy(&params).unwrap();
         let transaction = crate::trading::common::transaction_builder::build_transaction(
             &params.payer,
+            None,
             150_000,
             500_000,
+            false,
             &business_instructions,
             &[],
             Some(solana_hash::Hash::new_unique()),
             None,
             "PumpFun",
             true,
+            None,
             true,
             &Pubkey::new_unique(),
             0.001,
@@ -1181,6 +1517,49 @@ mod tests {
     }
 
     #[test]
+    fn ata_rent_funder_funds_the_created_ata_and_co_signs() {
+        let mut params = swap_params_for_buy(pump_mint(), TOKEN_PROGRAM);
+        params.create_output_mint_ata = true;
+        params.open_seed_optimize = false;
+        let funder = Arc::new(Keypair::new());
+        params.ata_rent_funder = Some(funder.clone());
+
+        let business_instructions = build_buy(&params).unwrap();
+        let create = business_instructions
+            .iter()
+            .find(|ix| ix.program_id == crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID)
+            .unwrap();
+        assert_eq!(create.accounts[0].pubkey, funder.pubkey());
+        assert_eq!(create.accounts[2].pubkey, params.payer.pubkey());
+
+        let build = |funder: Option<&Arc<Keypair>>| {
+            crate::trading::common::transaction_builder::build_transaction(
+                &params.payer,
+                funder,
+                150_000,
+                500_000,
+                false,
+                &business_instructions,
+                &[],
+                Some(solana_hash::Hash::new_unique()),
+                None,
+                "PumpFun",
+                true,
+                None,
+                false,
+                &Pubkey::default(),
+                0.0,
+                None,
+            )
+        };
+        let transaction = build(Some(&funder)).unwrap();
+        assert_eq!(transaction.signatures.len(), 2);
+        assert!(transaction.verify_with_results().into_iter().all(|ok| ok));
+        assert!(build(None).unwrap_err().to_string().contains("missing signer"));
+    }
+
+    #[test]
+    #[allow(deprecated)]
     fn pumpfun_from_trade_wsol_quote_regular_buy_selects_v1() {
         let mint = pump_mint();
         let mut params = swap_params_for_buy(mint, TOKEN_PROGRAM);
@@ -1273,6 +1652,51 @@ mod tests {
         assert_eq!(ix.accounts.len(), 26);
     }
 
+    #[test]
+    fn unresolved_layout_roles_are_errors_not_panics() {
+        let resolve = |role| match role {
+            PumpFunAccount::Global => Ok(Some(global_constants::GLOBAL_ACCOUNT_META)),
+            other => unlisted_role(other),
+        };
+        let err = layout_metas(PumpFunLayout::Legacy, true, resolve).unwrap_err();
+        assert!(err.to_string().contains("FeeRecipient"));
+        assert!(layout_metas(PumpFunLayout::Auto, true, |_| Ok(None)).is_err());
+    }
+
+    #[test]
+    fn pumpfun_explicit_layout_overrides_quote_mint_selection() {
+        assert_eq!(PumpFunLayout::Auto.accounts(true), None);
+        assert_eq!(PumpFunLayout::Legacy.accounts(true).unwrap().len(), 18);
+        assert_eq!(PumpFunLayout::Legacy.accounts(false).unwrap().len(), 17);
+        assert_eq!(PumpFunLayout::V2.accounts(true).unwrap().len(), 27);
+        assert_eq!(PumpFunLayout::V2.accounts(false).unwrap().len(), 26);
+
+        let mut params = swap_params_for_buy(pump_mint(), TOKEN_PROGRAM);
+        params.create_output_mint_ata = false;
+        let with_layout = |params: &mut SwapParams, layout| {
+            if let DexParamEnum::PumpFun(p) = &mut params.protocol_params {
+                *p = p.clone().with_layout(layout);
+            }
+        };
+
+        with_layout(&mut params, PumpFunLayout::V2);
+        let ix = build_buy(&params).unwrap().pop().unwrap();
+        assert_eq!(ix.accounts.len(), 27);
+        assert_eq!(ix.accounts[1].pubkey, params.output_mint);
+        assert_eq!(ix.accounts[2].pubkey, crate::constants::WSOL_TOKEN_ACCOUNT);
+
+        with_layout(&mut params, PumpFunLayout::Legacy);
+        let ix = build_buy(&params).unwrap().pop().unwrap();
+        assert_eq!(ix.accounts.len(), 18);
+        assert_eq!(ix.accounts[2].pubkey, params.output_mint);
+
+        params.input_mint = crate::constants::USDC_TOKEN_ACCOUNT;
+        if let DexParamEnum::PumpFun(p) = &mut params.protocol_params {
+            *p = p.clone().with_quote_mint(crate::constants::USDC_TOKEN_ACCOUNT);
+        }
+        assert!(build_buy(&params).is_err());
+    }
+
     #[test]
     fn pumpfun_usdc_dev_trade_uses_usdc_initial_quote_reserves() {
         let mint = pump_mint();
@@ -1328,6 +1752,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn pumpfun_usdc_trade_event_preserves_virtual_quote_reserves() {
         let mint = pump_mint();
         let virtual_quote_reserves = 4_527_693_121;
@@ -1356,6 +1781,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn pumpfun_solscan_sol_quote_mint_keeps_legacy_layout() {
         let mint = pump_mint();
         let params = PumpFunParams::from_trade(
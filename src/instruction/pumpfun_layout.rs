@@ -0,0 +1,195 @@
+//! Versioned Pump.fun bonding-curve `buy` / `sell` account layouts.
+//! Pump.fun 买卖指令的账户布局表（按版本）。
+//!
+//! Each layout is a table of account roles in instruction order; the builders resolve every role
+//! to an `AccountMeta`. When Pump.fun appends or reorders accounts, the change is a new table
+//! (and, if needed, a new [`PumpFunLayout`] variant) rather than an edit inside each builder.
+
+use solana_sdk::instruction::AccountMeta;
+
+/// Account layout of the Pump.fun `buy` / `sell` instructions, set via
+/// `PumpFunParams::with_layout`. 通过参数选择布局版本。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PumpFunLayout {
+    /// Smallest valid layout for the trade: `Legacy` for native SOL settlement, `V2` for
+    /// non-native quotes (USDC) or explicit WSOL settlement.
+    #[default]
+    Auto,
+    /// `buy` / `buy_exact_sol_in` / `sell`: 18 buy / 17 sell accounts (16 sell for
+    /// non-cashback coins), SOL quote only.
+    Legacy,
+    /// `*_v2` instructions: quote mint, buyback fee recipients, fee-sharing config and quote
+    /// volume accumulator ATA; 27 buy / 26 sell accounts.
+    V2,
+}
+
+/// One role in a Pump.fun account layout. 账户角色。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PumpFunAccount {
+    Global,
+    FeeRecipient,
+    Mint,
+    QuoteMint,
+    /// Token program of the traded mint (the only token program in `Legacy`).
+    BaseTokenProgram,
+    QuoteTokenProgram,
+    AssociatedTokenProgram,
+    AssociatedQuoteFeeRecipient,
+    BuybackFeeRecipient,
+    AssociatedQuoteBuybackFeeRecipient,
+    BondingCurve,
+    AssociatedBondingCurve,
+    AssociatedQuoteBondingCurve,
+    User,
+    UserTokenAccount,
+    UserQuoteTokenAccount,
+    CreatorVault,
+    AssociatedCreatorVault,
+    SharingConfig,
+    SystemProgram,
+    EventAuthority,
+    Program,
+    GlobalVolumeAccumulator,
+    /// Always present in `Legacy` buy and in `V2`; in `Legacy` sell only for cashback coins.
+    UserVolumeAccumulator,
+    AssociatedUserVolumeAccumulator,
+    FeeConfig,
+    FeeProgram,
+    BondingCurveV2,
+    ProtocolExtraFeeRecipient,
+}
+
+use PumpFunAccount::*;
+
+pub const LEGACY_BUY_ACCOUNTS: &[PumpFunAccount] = &[
+    Global,
+    FeeRecipient,
+    Mint,
+    BondingCurve,
+    AssociatedBondingCurve,
+    UserTokenAccount,
+    User,
+    SystemProgram,
+    BaseTokenProgram,
+    CreatorVault,
+    EventAuthority,
+    Program,
+    GlobalVolumeAccumulator,
+    UserVolumeAccumulator,
+    FeeConfig,
+    FeeProgram,
+    BondingCurveV2,
+    ProtocolExtraFeeRecipient,
+];
+
+pub const LEGACY_SELL_ACCOUNTS: &[PumpFunAccount] = &[
+    Global,
+    FeeRecipient,
+    Mint,
+    BondingCurve,
+    AssociatedBondingCurve,
+    UserTokenAccount,
+    User,
+    SystemProgram,
+    CreatorVault,
+    BaseTokenProgram,
+    EventAuthority,
+    Program,
+    FeeConfig,
+    FeeProgram,
+    UserVolumeAccumulator,
+    BondingCurveV2,
+    ProtocolExtraFeeRecipient,
+];
+
+pub const V2_BUY_ACCOUNTS: &[PumpFunAccount] = &[
+    Global,
+    Mint,
+    QuoteMint,
+    BaseTokenProgram,
+    QuoteTokenProgram,
+    AssociatedTokenProgram,
+    FeeRecipient,
+    AssociatedQuoteFeeRecipient,
+    BuybackFeeRecipient,
+    AssociatedQuoteBuybackFeeRecipient,
+    BondingCurve,
+    AssociatedBondingCurve,
+    AssociatedQuoteBondingCurve,
+    User,
+    UserTokenAccount,
+    UserQuoteTokenAccount,
+    CreatorVault,
+    AssociatedCreatorVault,
+    SharingConfig,
+    GlobalVolumeAccumulator,
+    UserVolumeAccumulator,
+    AssociatedUserVolumeAccumulator,
+    FeeConfig,
+    FeeProgram,
+    SystemProgram,
+    EventAuthority,
+    Program,
+];
+
+pub const V2_SELL_ACCOUNTS: &[PumpFunAccount] = &[
+    Global,
+    Mint,
+    QuoteMint,
+    BaseTokenProgram,
+    QuoteTokenProgram,
+    AssociatedTokenProgram,
+    FeeRecipient,
+    AssociatedQuoteFeeRecipient,
+    BuybackFeeRecipient,
+    AssociatedQuoteBuybackFeeRecipient,
+    BondingCurve,
+    AssociatedBondingCurve,
+    AssociatedQuoteBondingCurve,
+    User,
+    UserTokenAccount,
+    UserQuoteTokenAccount,
+    CreatorVault,
+    AssociatedCreatorVault,
+    SharingConfig,
+    UserVolumeAccumulator,
+    AssociatedUserVolumeAccumulator,
+    FeeConfig,
+    FeeProgram,
+    SystemProgram,
+    EventAuthority,
+    Program,
+];
+
+impl PumpFunLayout {
+    /// Account table for this layout; `None` for `Auto`, which the builder resolves first.
+    pub fn accounts(self, is_buy: bool) -> Option<&'static [PumpFunAccount]> {
+        match (self, is_buy) {
+            (PumpFunLayout::Auto, _) => None,
+            (PumpFunLayout::Legacy, true) => Some(LEGACY_BUY_ACCOUNTS),
+            (PumpFunLayout::Legacy, false) => Some(LEGACY_SELL_ACCOUNTS),
+            (PumpFunLayout::V2, true) => Some(V2_BUY_ACCOUNTS),
+            (PumpFunLayout::V2, false) => Some(V2_SELL_ACCOUNTS),
+        }
+    }
+}
+
+/// Resolve the `layout` table for a buy or sell in order; a role resolving to `Ok(None)` is
+/// omitted (optional accounts only). Errors for `Auto` and when `resolve` errors.
+#[inline]
+pub(crate) fn layout_metas(
+    layout: PumpFunLayout,
+    is_buy: bool,
+    mut resolve: impl FnMut(PumpFunAccount) -> anyhow::Result<Option<AccountMeta>>,
+) -> anyhow::Result<Vec<AccountMeta>> {
+    let table = layout.accounts(is_buy).ok_or_else(|| {
+        anyhow::anyhow!("PumpFun layout {:?} has no account table; resolve it first", layout)
+    })?;
+    let mut metas = Vec::with_capacity(table.len());
+    for role in table {
+        if let Some(meta) = resolve(*role)? {
+            metas.push(meta);
+        }
+    }
+    Ok(metas)
+}
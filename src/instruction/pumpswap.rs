@@ -1,3 +1,4 @@
+use crate::swqos::common::TradeError;
 use crate::{
     constants::trade::trade::DEFAULT_SLIPPAGE,
     instruction::pumpswap_ix_data::{
@@ -21,8 +22,10 @@ use crate::{
         traits::InstructionBuilder,
     },
     utils::calc::pumpswap::{
-        buy_quote_input_internal_with_fees, sell_base_input_internal_with_fees,
+        buy_base_input_internal_with_fees, buy_quote_input_internal_with_fees,
+        sell_base_input_internal_with_fees, PumpSwapFeeBasisPoints,
     },
+    TradeTokenType,
 };
 use anyhow::{anyhow, Result};
 use solana_sdk::{
@@ -41,6 +44,39 @@ fn request_mint_matches_pool(actual: Pubkey, expected: Pubkey) -> bool {
             && actual == crate::constants::SOL_TOKEN_ACCOUNT)
 }
 
+fn is_stable_mint(mint: Pubkey) -> bool {
+    mint == crate::constants::WSOL_TOKEN_ACCOUNT || mint == crate::constants::USDC_TOKEN_ACCOUNT
+}
+
+/// Whether the pool's quote side is the WSOL/USDC side the trade pays with (buy) or settles to
+/// (sell). When both sides are WSOL/USDC, `preferred` picks the side and defaults to the quote.
+fn quote_is_stable_side(
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+    preferred: Option<&TradeTokenType>,
+) -> Result<bool> {
+    match (is_stable_mint(base_mint), is_stable_mint(quote_mint)) {
+        (true, true) => {
+            let wanted = match preferred {
+                None => return Ok(true),
+                Some(TradeTokenType::SOL | TradeTokenType::WSOL) => {
+                    crate::constants::WSOL_TOKEN_ACCOUNT
+                }
+                Some(TradeTokenType::USDC) => crate::constants::USDC_TOKEN_ACCOUNT,
+                Some(TradeTokenType::USD1) => {
+                    return Err(anyhow!(
+                        "preferred_quote USD1 is not a side of this WSOL/USDC pool"
+                    ))
+                }
+            };
+            Ok(quote_mint == wanted)
+        }
+        (_, true) => Ok(true),
+        (true, false) => Ok(false),
+        (false, false) => Err(anyhow!("Pool must contain WSOL or USDC")),
+    }
+}
+
 fn push_cashback_remaining_accounts(
     accounts: &mut Vec<AccountMeta>,
     user: &Pubkey,
@@ -54,18 +90,62 @@ fn push_cashback_remaining_accounts(
     }
 
     let quote_ata = get_user_volume_accumulator_quote_ata(user, quote_mint, quote_token_program)
-        .ok_or_else(|| anyhow!("user volume accumulator quote ATA derivation failed"))?;
+        .ok_or_else(|| {
+            TradeError::pda_derivation_failed("user volume accumulator quote ATA derivation failed")
+        })?;
     accounts.push(AccountMeta::new(quote_ata, false));
 
     if !is_buy_instruction {
-        let accumulator = get_user_volume_accumulator_pda(user)
-            .ok_or_else(|| anyhow!("user volume accumulator PDA derivation failed"))?;
+        let accumulator = get_user_volume_accumulator_pda(user).ok_or_else(|| {
+            TradeError::pda_derivation_failed("user volume accumulator PDA derivation failed")
+        })?;
         accounts.push(AccountMeta::new(accumulator, false));
     }
 
     Ok(())
 }
 
+/// Max quote in for an exact `base_out` `buy`: the reserve quote plus slippage. Errors when that
+/// exceeds `budget` (`input_amount`) instead of writing a limit the program would reject with
+/// ExceededSlippage. 精确输出：按储备计算最大输入，超出预算时报错。
+fn exact_base_out_max_quote(
+    base_out: u64,
+    budget: u64,
+    slippage_basis_points: u64,
+    pool_base_token_reserves: u64,
+    pool_quote_token_reserves: u64,
+    virtual_quote_reserves: i128,
+    fee_basis_points: &PumpSwapFeeBasisPoints,
+) -> Result<u64> {
+    let result = buy_base_input_internal_with_fees(
+        base_out,
+        slippage_basis_points,
+        pool_base_token_reserves,
+        pool_quote_token_reserves,
+        virtual_quote_reserves,
+        fee_basis_points,
+    )
+    .map_err(anyhow::Error::msg)?;
+    if result.max_quote > budget {
+        return Err(anyhow!(
+            "PumpSwap exact output {} needs up to {} input, above the input_amount budget {}",
+            base_out,
+            result.max_quote,
+            budget
+        ));
+    }
+    Ok(result.max_quote)
+}
+
+/// Positions of `base_token_program` / `quote_token_program` in the buy/sell account list.
+///
+/// Both slots are mandatory even when they hold the same program: Anchor resolves accounts
+/// positionally, so dropping one shifts every later account and the program rejects the ix.
+/// The repeat is also nearly free — the message stores each key once and the second slot
+/// costs a single index byte — so there is nothing to gain from deduping it.
+pub const BASE_TOKEN_PROGRAM_ACCOUNT_INDEX: usize = 11;
+pub const QUOTE_TOKEN_PROGRAM_ACCOUNT_INDEX: usize = 12;
+
 #[async_trait::async_trait]
 impl InstructionBuilder for PumpSwapInstructionBuilder {
     async fn build_buy_instructions(&self, params: &SwapParams) -> Result<Vec<Instruction>> {
@@ -78,9 +158,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             .downcast_ref::<PumpSwapParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for PumpSwap"))?;
 
-        if params.input_amount.unwrap_or(0) == 0 {
-            return Err(anyhow!("Amount cannot be zero"));
-        }
+        let input_amount = params.required_input_amount()?;
         if params.fixed_output_amount == Some(0) {
             return Err(anyhow!("Fixed output amount cannot be zero"));
         }
@@ -101,23 +179,12 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         let pool_base_token_account = protocol_params.pool_base_token_account;
         let pool_quote_token_account = protocol_params.pool_quote_token_account;
 
-        let is_wsol = (base_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            && quote_mint != crate::constants::USDC_TOKEN_ACCOUNT)
-            || (quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-                && base_mint != crate::constants::USDC_TOKEN_ACCOUNT);
-        let is_usdc = (base_mint == crate::constants::USDC_TOKEN_ACCOUNT
-            && quote_mint != crate::constants::WSOL_TOKEN_ACCOUNT)
-            || (quote_mint == crate::constants::USDC_TOKEN_ACCOUNT
-                && base_mint != crate::constants::WSOL_TOKEN_ACCOUNT);
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
-        }
+        let quote_is_wsol_or_usdc =
+            quote_is_stable_side(base_mint, quote_mint, protocol_params.preferred_quote.as_ref())?;
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        let quote_is_wsol_or_usdc = quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || quote_mint == crate::constants::USDC_TOKEN_ACCOUNT;
         if params.fixed_output_amount.is_some() && !quote_is_wsol_or_usdc {
             return Err(anyhow!(
                 "PumpSwap exact-output buy is unsupported when the pool requires a sell instruction"
@@ -140,10 +207,21 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             if output_amount >= pool_base_token_reserves {
                 return Err(anyhow!("Exact base output must be below the pool base reserve"));
             }
-            (output_amount, params.input_amount.unwrap_or(0))
+            // Exact tokens out: the max quote is the reserve quote plus slippage.
+            let max_quote = exact_base_out_max_quote(
+                output_amount,
+                input_amount,
+                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                pool_base_token_reserves,
+                pool_quote_token_reserves,
+                virtual_quote_reserves,
+                &fee_basis_points,
+            )?;
+            params.check_min_tokens_out(output_amount)?;
+            (output_amount, max_quote)
         } else if quote_is_wsol_or_usdc {
             let result = buy_quote_input_internal_with_fees(
-                params.input_amount.unwrap_or(0),
+                input_amount,
                 params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
                 pool_base_token_reserves,
                 pool_quote_token_reserves,
@@ -151,11 +229,12 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
                 &fee_basis_points,
             )
             .map_err(anyhow::Error::msg)?;
+            params.check_min_tokens_out(result.base)?;
             // base_amount_out, max_quote_amount_in
             (result.base, result.max_quote)
         } else {
             let result = sell_base_input_internal_with_fees(
-                params.input_amount.unwrap_or(0),
+                input_amount,
                 params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
                 pool_base_token_reserves,
                 pool_quote_token_reserves,
@@ -163,8 +242,9 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
                 &fee_basis_points,
             )
             .map_err(anyhow::Error::msg)?;
+            params.check_min_tokens_out(result.ui_quote)?;
             // min_quote_amount_out, base_amount_in
-            (result.min_quote, params.input_amount.unwrap_or(0))
+            (params.min_out_with_floor(result.min_quote), input_amount)
         };
 
         let user_base_token_account =
@@ -203,13 +283,14 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             // - buy: program may spend up to max_quote, wrap max_quote
             let wrap_amount =
                 if quote_is_wsol_or_usdc && params.use_exact_sol_amount.unwrap_or(true) {
-                    params.input_amount.unwrap_or(0)
+                    input_amount
                 } else {
                     sol_amount
                 };
             push_create_or_wrap_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &input_stable_mint,
                 &input_stable_token_program,
                 wrap_amount,
@@ -221,6 +302,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             push_create_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &output_trade_mint,
                 &output_trade_token_program,
                 params.open_seed_optimize,
@@ -241,8 +323,8 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             AccountMeta::new(pool_quote_token_account, false),      // pool_quote_token_account
             fee_recipient_meta,                                     // fee_recipient (readonly)
             AccountMeta::new(fee_recipient_ata, false),             // fee_recipient_ata
-            AccountMeta::new_readonly(base_token_program, false),   // TOKEN_PROGRAM_ID (readonly)
-            AccountMeta::new_readonly(quote_token_program, false), // TOKEN_PROGRAM_ID (readonly, duplicated as in JS)
+            AccountMeta::new_readonly(base_token_program, false),   // base_token_program (readonly)
+            AccountMeta::new_readonly(quote_token_program, false), // quote_token_program (readonly, see QUOTE_TOKEN_PROGRAM_ACCOUNT_INDEX)
             crate::constants::SYSTEM_PROGRAM_META,                 // System Program (readonly)
             accounts::ASSOCIATED_TOKEN_PROGRAM_META, // ASSOCIATED_TOKEN_PROGRAM_ID (readonly)
             accounts::EVENT_AUTHORITY_META,          // event_authority (readonly)
@@ -250,10 +332,14 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             AccountMeta::new(params_coin_creator_vault_ata, false), // coin_creator_vault_ata
             AccountMeta::new_readonly(params_coin_creator_vault_authority, false), // coin_creator_vault_authority (readonly)
         ]);
+        // Volume accumulators belong to the on-chain `buy` / `buy_exact_quote_in` layout only;
+        // a reversed pool (stable side is base) executes this buy as `sell`, which omits them.
+        // 累加器账户只属于链上 buy 指令，反向池的买入实际发 sell。
         if quote_is_wsol_or_usdc {
             accounts.push(accounts::GLOBAL_VOLUME_ACCUMULATOR_META);
-            let uva = get_user_volume_accumulator_pda(&params.payer.pubkey())
-                .ok_or_else(|| anyhow!("user_volume_accumulator PDA derivation failed"))?;
+            let uva = get_user_volume_accumulator_pda(&params.payer.pubkey()).ok_or_else(|| {
+                TradeError::pda_derivation_failed("user_volume_accumulator PDA derivation failed")
+            })?;
             accounts.push(AccountMeta::new(uva, false));
         }
         accounts.push(accounts::FEE_CONFIG_META);
@@ -270,7 +356,10 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         // 否则多出的一格会把 buyback pubkey 错位，触发 BuybackFeeRecipientNotAuthorized（6053）。
         if protocol_params.coin_creator != Pubkey::default() {
             let pool_v2 = get_pool_v2_pda(&base_mint).ok_or_else(|| {
-                anyhow!("pool_v2 PDA derivation failed for base_mint {}", base_mint)
+                TradeError::pda_derivation_failed(format!(
+                    "pool_v2 PDA derivation failed for base_mint {}",
+                    base_mint
+                ))
             })?;
             accounts.push(AccountMeta::new_readonly(pool_v2, false));
         }
@@ -292,12 +381,14 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             let ix_data = if params.fixed_output_amount.is_some() {
                 encode_pumpswap_buy_ix_data(token_amount, sol_amount, track_volume)
             } else if params.use_exact_sol_amount.unwrap_or(true) {
-                let min_base_amount_out = crate::utils::calc::common::calculate_with_slippage_sell(
-                    token_amount,
-                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                let min_base_amount_out = params.min_out_with_floor(
+                    crate::utils::calc::common::calculate_with_slippage_sell(
+                        token_amount,
+                        params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                    ),
                 );
                 encode_pumpswap_buy_exact_quote_in_ix_data(
-                    params.input_amount.unwrap_or(0),
+                    input_amount,
                     min_base_amount_out,
                     track_volume,
                 )
@@ -353,21 +444,10 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         let base_token_program = protocol_params.base_token_program;
         let quote_token_program = protocol_params.quote_token_program;
 
-        let is_wsol = (base_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            && quote_mint != crate::constants::USDC_TOKEN_ACCOUNT)
-            || (quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-                && base_mint != crate::constants::USDC_TOKEN_ACCOUNT);
-        let is_usdc = (base_mint == crate::constants::USDC_TOKEN_ACCOUNT
-            && quote_mint != crate::constants::WSOL_TOKEN_ACCOUNT)
-            || (quote_mint == crate::constants::USDC_TOKEN_ACCOUNT
-                && base_mint != crate::constants::WSOL_TOKEN_ACCOUNT);
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
-        }
+        let quote_is_wsol_or_usdc =
+            quote_is_stable_side(base_mint, quote_mint, protocol_params.preferred_quote.as_ref())?;
 
-        if params.input_amount.unwrap_or_default() == 0 {
-            return Err(anyhow!("Token amount must be greater than zero"));
-        }
+        let input_amount = params.required_input_amount()?;
         if params.fixed_output_amount == Some(0) {
             return Err(anyhow!("Fixed output amount cannot be zero"));
         }
@@ -375,8 +455,6 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        let quote_is_wsol_or_usdc = quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || quote_mint == crate::constants::USDC_TOKEN_ACCOUNT;
         if params.fixed_output_amount.is_some() && quote_is_wsol_or_usdc {
             return Err(anyhow!(
                 "PumpSwap exact-output sell is unsupported when the pool requires a sell instruction"
@@ -394,13 +472,24 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         let fee_basis_points = protocol_params.fee_basis_points;
 
         let (token_amount, sol_amount) = if let Some(output_amount) = params.fixed_output_amount {
+            // Only reverse pools reach here: exact base (stable) out via `buy`, max tokens in
+            // quoted from reserves.
             if output_amount >= pool_base_token_reserves {
                 return Err(anyhow!("Exact base output must be below the pool base reserve"));
             }
-            (params.input_amount.unwrap(), output_amount)
+            let max_quote = exact_base_out_max_quote(
+                output_amount,
+                input_amount,
+                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                pool_base_token_reserves,
+                pool_quote_token_reserves,
+                virtual_quote_reserves,
+                &fee_basis_points,
+            )?;
+            (max_quote, output_amount)
         } else if quote_is_wsol_or_usdc {
             let result = sell_base_input_internal_with_fees(
-                params.input_amount.unwrap(),
+                input_amount,
                 params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
                 pool_base_token_reserves,
                 pool_quote_token_reserves,
@@ -409,10 +498,10 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             )
             .map_err(anyhow::Error::msg)?;
             // base_amount_in, min_quote_amount_out
-            (params.input_amount.unwrap(), result.min_quote)
+            (input_amount, result.min_quote)
         } else {
             let result = buy_quote_input_internal_with_fees(
-                params.input_amount.unwrap(),
+                input_amount,
                 params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
                 pool_base_token_reserves,
                 pool_quote_token_reserves,
@@ -458,6 +547,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             push_create_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &output_stable_mint,
                 &output_stable_token_program,
                 params.open_seed_optimize,
@@ -478,8 +568,8 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             AccountMeta::new(pool_quote_token_account, false),      // pool_quote_token_account
             fee_recipient_meta,                                     // fee_recipient (readonly)
             AccountMeta::new(fee_recipient_ata, false),             // fee_recipient_ata
-            AccountMeta::new_readonly(base_token_program, false),   // TOKEN_PROGRAM_ID (readonly)
-            AccountMeta::new_readonly(quote_token_program, false), // TOKEN_PROGRAM_ID (readonly, duplicated as in JS)
+            AccountMeta::new_readonly(base_token_program, false),   // base_token_program (readonly)
+            AccountMeta::new_readonly(quote_token_program, false), // quote_token_program (readonly, see QUOTE_TOKEN_PROGRAM_ACCOUNT_INDEX)
             crate::constants::SYSTEM_PROGRAM_META,                 // System Program (readonly)
             accounts::ASSOCIATED_TOKEN_PROGRAM_META, // ASSOCIATED_TOKEN_PROGRAM_ID (readonly)
             accounts::EVENT_AUTHORITY_META,          // event_authority (readonly)
@@ -487,10 +577,13 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             AccountMeta::new(params_coin_creator_vault_ata, false), // coin_creator_vault_ata
             AccountMeta::new_readonly(params_coin_creator_vault_authority, false), // coin_creator_vault_authority (readonly)
         ]);
+        // Mirror of the buy side: only a reversed pool executes this sell as an on-chain `buy`,
+        // and only then are the volume accumulators part of the layout.
         if !quote_is_wsol_or_usdc {
             accounts.push(accounts::GLOBAL_VOLUME_ACCUMULATOR_META);
-            let uva = get_user_volume_accumulator_pda(&params.payer.pubkey())
-                .ok_or_else(|| anyhow!("user_volume_accumulator PDA derivation failed"))?;
+            let uva = get_user_volume_accumulator_pda(&params.payer.pubkey()).ok_or_else(|| {
+                TradeError::pda_derivation_failed("user_volume_accumulator PDA derivation failed")
+            })?;
             accounts.push(AccountMeta::new(uva, false));
         }
         accounts.push(accounts::FEE_CONFIG_META);
@@ -505,7 +598,10 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         )?;
         if protocol_params.coin_creator != Pubkey::default() {
             let pool_v2 = get_pool_v2_pda(&base_mint).ok_or_else(|| {
-                anyhow!("pool_v2 PDA derivation failed for base_mint {}", base_mint)
+                TradeError::pda_derivation_failed(format!(
+                    "pool_v2 PDA derivation failed for base_mint {}",
+                    base_mint
+                ))
             })?;
             accounts.push(AccountMeta::new_readonly(pool_v2, false));
         }
@@ -542,7 +638,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
                 params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
             );
             let ix_data = encode_pumpswap_buy_exact_quote_in_ix_data(
-                params.input_amount.unwrap_or(0),
+                input_amount,
                 min_base_amount_out,
                 track_volume,
             );
@@ -616,9 +712,9 @@ pub fn claim_cashback_pumpswap_instruction(
 mod tests {
     use super::*;
     use crate::{
-        common::GasFeeStrategy,
+        common::{GasFeeStrategy, ParallelStrategy},
         swqos::TradeType,
-        trading::core::params::{DexParamEnum, PumpSwapParams, SwapParams},
+        trading::core::params::{AtaCreationMode, DexParamEnum, PumpSwapParams, SwapParams},
     };
     use solana_sdk::{pubkey::Pubkey, signature::Keypair};
     use std::sync::Arc;
@@ -710,11 +806,54 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: Some(true),
+            swqos_watchdog: None,
+            ata_creation: AtaCreationMode::Inline,
+            memo: None,
+            parallel_strategy: ParallelStrategy::RaceAll,
+            max_ata_creations: None,
+            cu_learner: None,
+            rpc_fallback: false,
+            cleanup_on_failure: false,
+            skip_compute_budget: false,
+            simulation_capture: None,
+            ata_rent_funder: None,
+            receive_native_sol: false,
+            on_before_send: None,
+            multi_nonce: None,
+            min_tokens_out: None,
         }
     }
 
     #[tokio::test]
-    async fn pumpswap_fixed_output_uses_buy_with_max_input_budget() {
+    async fn accounts_used_lists_pool_accounts_once_without_payer() {
+        use solana_sdk::signer::Signer;
+        let params = swap_params(TradeType::Buy, None);
+        let keys = PumpSwapInstructionBuilder.accounts_used(&params).await.unwrap();
+
+        assert!(keys.contains(&pk(1)));
+        assert!(keys.contains(&accounts::AMM_PROGRAM));
+        assert!(!keys.contains(&params.payer.pubkey()));
+        let mut deduped = keys.clone();
+        deduped.sort();
+        deduped.dedup();
+        assert_eq!(deduped.len(), keys.len());
+    }
+
+    fn expected_exact_out_max(protocol_params: &PumpSwapParams, base_out: u64) -> u64 {
+        buy_base_input_internal_with_fees(
+            base_out,
+            100,
+            protocol_params.pool_base_token_reserves,
+            protocol_params.pool_quote_token_reserves,
+            protocol_params.virtual_quote_reserves,
+            &protocol_params.fee_basis_points,
+        )
+        .unwrap()
+        .max_quote
+    }
+
+    #[tokio::test]
+    async fn pumpswap_fixed_output_uses_buy_with_max_input_from_reserves() {
         let instructions = PumpSwapInstructionBuilder
             .build_buy_instructions(&swap_params(TradeType::Buy, Some(42)))
             .await
@@ -723,7 +862,22 @@ mod tests {
 
         assert_eq!(&ix.data[..8], crate::instruction::utils::pumpswap::BUY_DISCRIMINATOR);
         assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 42);
-        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 100_000);
+        let max_quote = u64::from_le_bytes(ix.data[16..24].try_into().unwrap());
+        assert_eq!(max_quote, expected_exact_out_max(&pumpswap_params(), 42));
+        assert!(max_quote < 100_000);
+    }
+
+    #[tokio::test]
+    async fn pumpswap_fixed_output_above_budget_is_rejected() {
+        let mut params = swap_params(TradeType::Buy, Some(42));
+        params.input_amount = Some(expected_exact_out_max(&pumpswap_params(), 42) - 1);
+        assert!(PumpSwapInstructionBuilder.build_buy_instructions(&params).await.is_err());
+
+        let mut params = swap_params(TradeType::Sell, Some(42));
+        params.protocol_params = DexParamEnum::PumpSwap(reverse_pumpswap_params());
+        params.output_mint = crate::constants::USDC_TOKEN_ACCOUNT;
+        params.input_amount = Some(expected_exact_out_max(&reverse_pumpswap_params(), 42) - 1);
+        assert!(PumpSwapInstructionBuilder.build_sell_instructions(&params).await.is_err());
     }
 
     #[tokio::test]
@@ -752,10 +906,42 @@ mod tests {
         assert_eq!(&ix.data[..8], crate::instruction::utils::pumpswap::BUY_DISCRIMINATOR);
         assert_eq!(ix.data.len(), 25);
         assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 42);
-        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 100_000);
+        assert_eq!(
+            u64::from_le_bytes(ix.data[16..24].try_into().unwrap()),
+            expected_exact_out_max(&reverse_pumpswap_params(), 42)
+        );
         assert_eq!(ix.data[24], 1);
     }
 
+    #[tokio::test]
+    async fn sell_close_flags_close_wsol_and_token_ata_independently() {
+        use solana_sdk::signer::Signer;
+        let closed = |ixs: &[Instruction]| -> Vec<Pubkey> {
+            ixs.iter().filter(|ix| ix.data == [9]).map(|ix| ix.accounts[0].pubkey).collect()
+        };
+        let mut params = swap_params(TradeType::Sell, None);
+        let wsol_ata = crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
+            &params.payer.pubkey(),
+            &crate::constants::WSOL_TOKEN_ACCOUNT,
+            &crate::constants::TOKEN_PROGRAM,
+        );
+
+        params.close_output_mint_ata = true;
+        let ixs = PumpSwapInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+        assert_eq!(closed(&ixs), vec![wsol_ata]);
+
+        params.close_output_mint_ata = false;
+        params.close_input_mint_ata = true;
+        let ixs = PumpSwapInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+        let token_only = closed(&ixs);
+        assert_eq!(token_only.len(), 1);
+        assert_ne!(token_only[0], wsol_ata);
+
+        params.close_output_mint_ata = true;
+        let ixs = PumpSwapInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+        assert_eq!(closed(&ixs), vec![wsol_ata, token_only[0]]);
+    }
+
     #[tokio::test]
     async fn pumpswap_reverse_sell_exact_input_never_increases_token_spend() {
         let mut params = swap_params(TradeType::Sell, None);
@@ -837,6 +1023,40 @@ mod tests {
         assert_eq!(ix.accounts[22].pubkey, expected_accumulator);
     }
 
+    #[tokio::test]
+    async fn wsol_usdc_pool_trades_against_the_preferred_quote() {
+        let wsol_usdc = || {
+            let mut p = pumpswap_params();
+            p.base_mint = crate::constants::WSOL_TOKEN_ACCOUNT;
+            p.quote_mint = crate::constants::USDC_TOKEN_ACCOUNT;
+            p
+        };
+
+        // default: the pool's quote (USDC) pays, regular buy
+        let mut params = swap_params(TradeType::Buy, None);
+        params.protocol_params = DexParamEnum::PumpSwap(wsol_usdc());
+        params.input_mint = crate::constants::USDC_TOKEN_ACCOUNT;
+        params.output_mint = crate::constants::WSOL_TOKEN_ACCOUNT;
+        let ix = PumpSwapInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        assert_ne!(
+            &ix.last().unwrap().data[..8],
+            crate::instruction::utils::pumpswap::SELL_DISCRIMINATOR
+        );
+
+        // WSOL preferred: pay with the base side, which PumpSwap executes as a sell
+        params.protocol_params =
+            DexParamEnum::PumpSwap(wsol_usdc().with_preferred_quote(TradeTokenType::SOL));
+        params.input_mint = crate::constants::WSOL_TOKEN_ACCOUNT;
+        params.output_mint = crate::constants::USDC_TOKEN_ACCOUNT;
+        let ix = PumpSwapInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        assert_eq!(
+            &ix.last().unwrap().data[..8],
+            crate::instruction::utils::pumpswap::SELL_DISCRIMINATOR
+        );
+
+        assert!(quote_is_stable_side(pk(7), pk(8), None).is_err());
+    }
+
     #[tokio::test]
     async fn pumpswap_fee_atas_use_the_quote_token_program() {
         let quote_token_program = pk(99);
@@ -867,6 +1087,47 @@ mod tests {
         assert_eq!(ix.accounts[buyback_recipient_index + 1].pubkey, expected_buyback_ata);
     }
 
+    #[tokio::test]
+    async fn explicit_output_token_program_overrides_params_for_token_2022_mint() {
+        use solana_sdk::signer::Signer;
+        let token_2022 = crate::constants::TOKEN_PROGRAM_2022;
+        // params were built assuming the legacy program for the Token-2022 base mint
+        let mut params = swap_params(TradeType::Buy, None);
+        params.create_output_mint_ata = true;
+        params.output_token_program = Some(token_2022);
+        params.apply_token_program_overrides();
+        let DexParamEnum::PumpSwap(protocol_params) = &params.protocol_params else {
+            unreachable!()
+        };
+        assert_eq!(protocol_params.base_token_program, token_2022);
+        assert_eq!(protocol_params.quote_token_program, crate::constants::TOKEN_PROGRAM);
+
+        let instructions =
+            PumpSwapInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        let user_base_ata =
+            crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed(
+                &params.payer.pubkey(),
+                &pk(2),
+                &token_2022,
+                params.open_seed_optimize,
+            );
+        let swap = instructions.last().unwrap();
+        assert!(swap.accounts.iter().any(|m| m.pubkey == user_base_ata));
+        assert!(swap.accounts.iter().any(|m| m.pubkey == token_2022));
+        // the ATA create for the output mint uses the explicit program too
+        assert!(instructions[..instructions.len() - 1]
+            .iter()
+            .any(|ix| ix.accounts.iter().any(|m| m.pubkey == user_base_ata)));
+
+        // leaving it None keeps the program from the params
+        let mut params = swap_params(TradeType::Buy, None);
+        params.apply_token_program_overrides();
+        let DexParamEnum::PumpSwap(protocol_params) = &params.protocol_params else {
+            unreachable!()
+        };
+        assert_eq!(protocol_params.base_token_program, crate::constants::TOKEN_PROGRAM);
+    }
+
     #[tokio::test]
     async fn pumpswap_rejects_request_mints_from_another_pool() {
         let mut params = swap_params(TradeType::Buy, None);
@@ -884,7 +1145,8 @@ mod tests {
 
         let error = PumpSwapInstructionBuilder.build_sell_instructions(&params).await.unwrap_err();
 
-        assert_eq!(error.to_string(), "Token amount must be greater than zero");
+        let error = error.downcast_ref::<TradeError>().unwrap();
+        assert_eq!(error.code, TradeError::ZERO_AMOUNT);
     }
 
     #[tokio::test]
@@ -948,6 +1210,45 @@ mod tests {
         assert_eq!(base_amount_out, expected.base);
     }
 
+    #[tokio::test]
+    async fn pumpswap_buy_below_min_tokens_out_is_rejected() {
+        let mut params = swap_params(TradeType::Buy, None);
+        params.input_amount = Some(1_000_000);
+        // ~500k base out at a 1:2 pool; the floor applies to the quote, not the slippage minimum.
+        params.min_tokens_out = Some(1_000_000);
+        let err = PumpSwapInstructionBuilder.build_buy_instructions(&params).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<TradeError>().map(|e| e.code),
+            Some(TradeError::BELOW_MIN_TOKENS_OUT)
+        );
+
+        params.min_tokens_out = Some(400_000);
+        assert!(PumpSwapInstructionBuilder.build_buy_instructions(&params).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn pumpswap_buy_encodes_min_tokens_out_as_min_base_amount_out() {
+        let mut params = swap_params(TradeType::Buy, None);
+        params.input_amount = Some(1_000_000);
+        let encoded_min_base_out = |ixs: Vec<Instruction>| {
+            let ix = ixs.last().unwrap();
+            assert_eq!(
+                &ix.data[..8],
+                crate::instruction::utils::pumpswap::BUY_EXACT_QUOTE_IN_DISCRIMINATOR
+            );
+            u64::from_le_bytes(ix.data[16..24].try_into().unwrap())
+        };
+
+        // A floor under the slippage minimum leaves the slippage minimum in place.
+        params.min_tokens_out = Some(1);
+        let ixs = PumpSwapInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        let slippage_min = encoded_min_base_out(ixs);
+
+        params.min_tokens_out = Some(slippage_min + 1);
+        let ixs = PumpSwapInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        assert_eq!(encoded_min_base_out(ixs), slippage_min + 1);
+    }
+
     #[tokio::test]
     async fn pumpswap_sell_prices_with_effective_quote_reserves() {
         let mut params = swap_params(TradeType::Sell, None);
@@ -971,4 +1272,108 @@ mod tests {
         .unwrap();
         assert_eq!(min_quote_amount_out, expected.min_quote);
     }
+
+    #[test]
+    fn validate_rejects_unset_pool_identical_mints_and_empty_reserves() {
+        assert!(pumpswap_params().validate().is_ok());
+
+        let mut params = pumpswap_params();
+        params.pool = Pubkey::default();
+        assert!(params.validate().unwrap_err().message.contains("pool address is not set"));
+
+        let mut params = pumpswap_params();
+        params.quote_mint = params.base_mint;
+        assert!(params.validate().unwrap_err().message.contains("identical"));
+
+        let mut params = pumpswap_params();
+        params.pool_base_token_reserves = 0;
+        params.pool_quote_token_reserves = 0;
+        let err = DexParamEnum::PumpSwap(params).validate().unwrap_err();
+        assert_eq!(err.code, crate::swqos::common::TradeError::INVALID_PROTOCOL_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn pumpswap_keeps_both_token_program_slots_when_they_are_identical() {
+        for trade_type in [TradeType::Buy, TradeType::Sell] {
+            let params = swap_params(trade_type, None);
+            let instructions = if trade_type == TradeType::Buy {
+                PumpSwapInstructionBuilder.build_buy_instructions(&params).await.unwrap()
+            } else {
+                PumpSwapInstructionBuilder.build_sell_instructions(&params).await.unwrap()
+            };
+            let ix = instructions.last().unwrap();
+            let base = &ix.accounts[BASE_TOKEN_PROGRAM_ACCOUNT_INDEX];
+            let quote = &ix.accounts[QUOTE_TOKEN_PROGRAM_ACCOUNT_INDEX];
+
+            // Dedup would shift system_program into the quote_token_program slot.
+            assert_eq!(base.pubkey, crate::constants::TOKEN_PROGRAM);
+            assert_eq!(quote.pubkey, crate::constants::TOKEN_PROGRAM);
+            assert_eq!(
+                ix.accounts[QUOTE_TOKEN_PROGRAM_ACCOUNT_INDEX + 1].pubkey,
+                crate::constants::SYSTEM_PROGRAM
+            );
+
+            // The repeated key is stored once in the compiled message.
+            let message = solana_message::Message::new(&[ix.clone()], None);
+            let occurrences = message
+                .account_keys
+                .iter()
+                .filter(|key| **key == crate::constants::TOKEN_PROGRAM)
+                .count();
+            assert_eq!(occurrences, 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn volume_accumulators_follow_the_executed_instruction_for_wsol_and_usdc_quotes() {
+        let has_accumulators = |ix: &Instruction, payer: &Pubkey| {
+            let uva = get_user_volume_accumulator_pda(payer).unwrap();
+            if ix.accounts[19].pubkey == accounts::GLOBAL_VOLUME_ACCUMULATOR {
+                assert_eq!(ix.accounts[20].pubkey, uva);
+                assert_eq!(ix.accounts[21].pubkey, accounts::FEE_CONFIG);
+                true
+            } else {
+                assert_eq!(ix.accounts[19].pubkey, accounts::FEE_CONFIG);
+                false
+            }
+        };
+        let is_buy_ix = |ix: &Instruction| {
+            &ix.data[..8] != crate::instruction::utils::pumpswap::SELL_DISCRIMINATOR
+        };
+
+        for quote in [crate::constants::WSOL_TOKEN_ACCOUNT, crate::constants::USDC_TOKEN_ACCOUNT] {
+            let mut protocol_params = pumpswap_params();
+            protocol_params.quote_mint = quote;
+
+            // stable quote: buy → on-chain buy (with accumulators), sell → on-chain sell (without)
+            let mut params = swap_params(TradeType::Buy, None);
+            params.protocol_params = DexParamEnum::PumpSwap(protocol_params.clone());
+            params.input_mint = quote;
+            let ixs = PumpSwapInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+            let ix = ixs.last().unwrap();
+            assert!(is_buy_ix(ix) && has_accumulators(ix, &params.payer.pubkey()));
+
+            let mut params = swap_params(TradeType::Sell, None);
+            params.protocol_params = DexParamEnum::PumpSwap(protocol_params);
+            params.output_mint = quote;
+            let ixs = PumpSwapInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+            let ix = ixs.last().unwrap();
+            assert!(!is_buy_ix(ix) && !has_accumulators(ix, &params.payer.pubkey()));
+        }
+
+        // reversed pool (USDC is base): the directions swap, and the accumulators with them
+        let mut params = swap_params(TradeType::Buy, None);
+        params.protocol_params = DexParamEnum::PumpSwap(reverse_pumpswap_params());
+        params.input_mint = crate::constants::USDC_TOKEN_ACCOUNT;
+        let ixs = PumpSwapInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        let ix = ixs.last().unwrap();
+        assert!(!is_buy_ix(ix) && !has_accumulators(ix, &params.payer.pubkey()));
+
+        let mut params = swap_params(TradeType::Sell, None);
+        params.protocol_params = DexParamEnum::PumpSwap(reverse_pumpswap_params());
+        params.output_mint = crate::constants::USDC_TOKEN_ACCOUNT;
+        let ixs = PumpSwapInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+        let ix = ixs.last().unwrap();
+        assert!(is_buy_ix(ix) && has_accumulators(ix, &params.payer.pubkey()));
+    }
 }
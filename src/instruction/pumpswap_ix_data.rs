@@ -40,3 +40,114 @@ pub fn encode_pumpswap_sell_ix_data(base_amount_in: u64, min_quote_amount_out: u
     d[16..24].copy_from_slice(&min_quote_amount_out.to_le_bytes());
     d
 }
+
+/// Which PumpSwap instruction a data buffer encodes (selected by discriminator).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PumpSwapIxKind {
+    /// `buy(base_amount_out, max_quote_amount_in)`
+    Buy,
+    /// `buy_exact_quote_in(spendable_quote_in, min_base_amount_out)`
+    BuyExactQuoteIn,
+    /// `sell(base_amount_in, min_quote_amount_out)`
+    Sell,
+}
+
+/// Decoded PumpSwap instruction arguments, for debugging / logging only.
+/// `amount` / `limit` follow the on-chain argument order of [`Self::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PumpSwapIxArgs {
+    pub kind: PumpSwapIxKind,
+    /// First u64 argument: `base_amount_out` / `spendable_quote_in` / `base_amount_in`.
+    pub amount: u64,
+    /// Second u64 argument: `max_quote_amount_in` / `min_base_amount_out` / `min_quote_amount_out`.
+    pub limit: u64,
+    /// Trailing `track_volume` flag (buy variants only, when present).
+    pub track_volume: Option<bool>,
+}
+
+impl std::fmt::Display for PumpSwapIxArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            PumpSwapIxKind::Buy => write!(
+                f,
+                "buy(base_amount_out={}, max_quote_amount_in={})",
+                self.amount, self.limit
+            )?,
+            PumpSwapIxKind::BuyExactQuoteIn => write!(
+                f,
+                "buy_exact_quote_in(spendable_quote_in={}, min_base_amount_out={})",
+                self.amount, self.limit
+            )?,
+            PumpSwapIxKind::Sell => write!(
+                f,
+                "sell(base_amount_in={}, min_quote_amount_out={})",
+                self.amount, self.limit
+            )?,
+        }
+        if let Some(track_volume) = self.track_volume {
+            write!(f, " track_volume={}", track_volume)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decode PumpSwap `buy` / `buy_exact_quote_in` / `sell` instruction data (24 bytes, or 25 with `track_volume`).
+pub fn decode_pumpswap_ix_data(data: &[u8]) -> anyhow::Result<PumpSwapIxArgs> {
+    if data.len() < 24 {
+        return Err(anyhow::anyhow!(
+            "PumpSwap instruction data too short: {} bytes (need at least 24)",
+            data.len()
+        ));
+    }
+    let kind = match &data[..8] {
+        d if d == BUY_DISCRIMINATOR => PumpSwapIxKind::Buy,
+        d if d == BUY_EXACT_QUOTE_IN_DISCRIMINATOR => PumpSwapIxKind::BuyExactQuoteIn,
+        d if d == SELL_DISCRIMINATOR => PumpSwapIxKind::Sell,
+        d => return Err(anyhow::anyhow!("Unknown PumpSwap discriminator: {:?}", d)),
+    };
+    let amount = u64::from_le_bytes(data[8..16].try_into().expect("8-byte slice"));
+    let limit = u64::from_le_bytes(data[16..24].try_into().expect("8-byte slice"));
+    let track_volume = match kind {
+        PumpSwapIxKind::Sell => None,
+        _ => data.get(24).map(|b| *b != 0),
+    };
+    Ok(PumpSwapIxArgs { kind, amount, limit, track_volume })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_round_trips_all_encoders() {
+        let buy = decode_pumpswap_ix_data(&encode_pumpswap_buy_ix_data(10, 20, 1)).unwrap();
+        assert_eq!(
+            buy,
+            PumpSwapIxArgs {
+                kind: PumpSwapIxKind::Buy,
+                amount: 10,
+                limit: 20,
+                track_volume: Some(true)
+            }
+        );
+
+        let exact_in =
+            decode_pumpswap_ix_data(&encode_pumpswap_buy_exact_quote_in_ix_data(30, 40, 0))
+                .unwrap();
+        assert_eq!(exact_in.kind, PumpSwapIxKind::BuyExactQuoteIn);
+        assert_eq!((exact_in.amount, exact_in.limit), (30, 40));
+        assert_eq!(exact_in.track_volume, Some(false));
+
+        let sell = decode_pumpswap_ix_data(&encode_pumpswap_sell_ix_data(50, 60)).unwrap();
+        assert_eq!(sell.kind, PumpSwapIxKind::Sell);
+        assert_eq!((sell.amount, sell.limit), (50, 60));
+        assert_eq!(sell.track_volume, None);
+        assert_eq!(sell.to_string(), "sell(base_amount_in=50, min_quote_amount_out=60)");
+    }
+
+    #[test]
+    fn decode_rejects_short_data_and_unknown_discriminator() {
+        assert!(decode_pumpswap_ix_data(&[0u8; 23]).is_err());
+        assert!(decode_pumpswap_ix_data(&[0u8; 24]).is_err());
+    }
+}
@@ -13,7 +13,7 @@ use crate::{
         params::{RaydiumAmmV4Params, SwapParams},
         traits::InstructionBuilder,
     },
-    utils::calc::raydium_amm_v4::compute_swap_amount,
+    utils::calc::raydium_amm_v4::{compute_swap_amount, compute_swap_amount_base_out},
 };
 use anyhow::{anyhow, Result};
 use solana_sdk::{
@@ -50,15 +50,41 @@ fn ensure_market_accounts(params: &RaydiumAmmV4Params) -> Result<()> {
     Ok(())
 }
 
+/// Max input for a `swap_base_out` of `amount_out`, quoted from the pool reserves plus slippage.
+/// Errors when that exceeds the `input_amount` budget rather than signing a limit the program
+/// would reject.
+fn base_out_max_amount_in(
+    params: &SwapParams,
+    protocol_params: &RaydiumAmmV4Params,
+    is_base_in: bool,
+    amount_out: u64,
+    budget: u64,
+) -> Result<u64> {
+    let quote = compute_swap_amount_base_out(
+        protocol_params.coin_reserve,
+        protocol_params.pc_reserve,
+        is_base_in,
+        amount_out,
+        params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+    )
+    .ok_or_else(|| anyhow!("RaydiumAmmV4 pool cannot provide {} output tokens", amount_out))?;
+    if quote.max_amount_in > budget {
+        return Err(anyhow!(
+            "RaydiumAmmV4 exact output needs up to {} input, above the input_amount budget {}",
+            quote.max_amount_in,
+            budget
+        ));
+    }
+    Ok(quote.max_amount_in)
+}
+
 #[async_trait::async_trait]
 impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
     async fn build_buy_instructions(&self, params: &SwapParams) -> Result<Vec<Instruction>> {
         // ========================================
         // Parameter validation and basic data preparation
         // ========================================
-        if params.input_amount.unwrap_or(0) == 0 {
-            return Err(anyhow!("Amount cannot be zero"));
-        }
+        let amount_in = params.required_input_amount()?;
         let protocol_params = params
             .protocol_params
             .as_any()
@@ -81,7 +107,6 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
         // ========================================
         let is_base_in = protocol_params.coin_mint == crate::constants::WSOL_TOKEN_ACCOUNT
             || protocol_params.coin_mint == crate::constants::USDC_TOKEN_ACCOUNT;
-        let amount_in: u64 = params.input_amount.unwrap_or(0);
         let input_mint =
             if is_base_in { protocol_params.coin_mint } else { protocol_params.pc_mint };
         let output_mint =
@@ -111,6 +136,7 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
             push_create_or_wrap_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &input_mint,
                 &crate::constants::TOKEN_PROGRAM,
                 amount_in,
@@ -122,6 +148,7 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
             push_create_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &output_mint,
                 &crate::constants::TOKEN_PROGRAM,
                 params.open_seed_optimize,
@@ -152,18 +179,22 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
         // Create instruction data
         let mut data = [0u8; 17];
         if let Some(amount_out) = params.fixed_output_amount {
+            let max_amount_in =
+                base_out_max_amount_in(params, protocol_params, is_base_in, amount_out, amount_in)?;
+            params.check_min_tokens_out(amount_out)?;
             data[..1].copy_from_slice(&SWAP_BASE_OUT_DISCRIMINATOR);
-            data[1..9].copy_from_slice(&amount_in.to_le_bytes());
+            data[1..9].copy_from_slice(&max_amount_in.to_le_bytes());
             data[9..17].copy_from_slice(&amount_out.to_le_bytes());
         } else {
-            let minimum_amount_out = compute_swap_amount(
+            let quote = compute_swap_amount(
                 protocol_params.coin_reserve,
                 protocol_params.pc_reserve,
                 is_base_in,
                 amount_in,
                 params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-            )
-            .min_amount_out;
+            );
+            params.check_min_tokens_out(quote.amount_out)?;
+            let minimum_amount_out = params.min_out_with_floor(quote.min_amount_out);
             data[..1].copy_from_slice(&SWAP_BASE_IN_DISCRIMINATOR);
             data[1..9].copy_from_slice(&amount_in.to_le_bytes());
             data[9..17].copy_from_slice(&minimum_amount_out.to_le_bytes());
@@ -193,9 +224,7 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
             .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumAmmV4"))?;
         ensure_market_accounts(protocol_params)?;
 
-        if params.input_amount.is_none() || params.input_amount.unwrap_or(0) == 0 {
-            return Err(anyhow!("Token amount is not set"));
-        }
+        let amount_in = params.required_input_amount()?;
 
         let is_wsol = protocol_params.coin_mint == crate::constants::WSOL_TOKEN_ACCOUNT
             || protocol_params.pc_mint == crate::constants::WSOL_TOKEN_ACCOUNT;
@@ -241,6 +270,7 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
             push_create_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &output_mint,
                 &crate::constants::TOKEN_PROGRAM,
                 params.open_seed_optimize,
@@ -270,10 +300,11 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
         ];
         // Create instruction data
         let mut data = [0u8; 17];
-        let amount_in = params.input_amount.unwrap_or(0);
         if let Some(amount_out) = params.fixed_output_amount {
+            let max_amount_in =
+                base_out_max_amount_in(params, protocol_params, is_base_in, amount_out, amount_in)?;
             data[..1].copy_from_slice(&SWAP_BASE_OUT_DISCRIMINATOR);
-            data[1..9].copy_from_slice(&amount_in.to_le_bytes());
+            data[1..9].copy_from_slice(&max_amount_in.to_le_bytes());
             data[9..17].copy_from_slice(&amount_out.to_le_bytes());
         } else {
             let minimum_amount_out = compute_swap_amount(
@@ -316,9 +347,9 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
 mod tests {
     use super::*;
     use crate::{
-        common::GasFeeStrategy,
+        common::{GasFeeStrategy, ParallelStrategy},
         swqos::TradeType,
-        trading::core::params::{DexParamEnum, SwapParams},
+        trading::core::params::{AtaCreationMode, DexParamEnum, SwapParams},
     };
     use solana_sdk::{pubkey::Pubkey, signature::Keypair};
     use std::sync::Arc;
@@ -390,6 +421,21 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: None,
+            swqos_watchdog: None,
+            ata_creation: AtaCreationMode::Inline,
+            memo: None,
+            parallel_strategy: ParallelStrategy::RaceAll,
+            max_ata_creations: None,
+            cu_learner: None,
+            rpc_fallback: false,
+            cleanup_on_failure: false,
+            skip_compute_budget: false,
+            simulation_capture: None,
+            ata_rent_funder: None,
+            receive_native_sol: false,
+            on_before_send: None,
+            multi_nonce: None,
+            min_tokens_out: None,
         }
     }
 
@@ -425,11 +471,62 @@ mod tests {
             .unwrap();
         let ix = instructions.last().unwrap();
 
+        let quote =
+            compute_swap_amount_base_out(1_000_000_000, 2_000_000_000, true, 42, 100).unwrap();
         assert_eq!(&ix.data[..1], SWAP_BASE_OUT_DISCRIMINATOR);
-        assert_eq!(u64::from_le_bytes(ix.data[1..9].try_into().unwrap()), 100_000);
+        assert_eq!(u64::from_le_bytes(ix.data[1..9].try_into().unwrap()), quote.max_amount_in);
+        assert!(quote.max_amount_in < 100_000);
         assert_eq!(u64::from_le_bytes(ix.data[9..17].try_into().unwrap()), 42);
     }
 
+    #[tokio::test]
+    async fn raydium_amm_v4_fixed_output_above_budget_is_rejected() {
+        let quote =
+            compute_swap_amount_base_out(1_000_000_000, 2_000_000_000, true, 42, 100).unwrap();
+        let mut params = swap_params(market_params(), Some(42));
+        params.input_amount = Some(quote.max_amount_in - 1);
+        assert!(RaydiumAmmV4InstructionBuilder.build_buy_instructions(&params).await.is_err());
+
+        params.input_amount = Some(quote.max_amount_in);
+        assert!(RaydiumAmmV4InstructionBuilder.build_buy_instructions(&params).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn raydium_amm_v4_buy_below_min_tokens_out_is_rejected() {
+        let mut params = swap_params(market_params(), None);
+        let quote = compute_swap_amount(1_000_000_000, 2_000_000_000, true, 100_000, 100);
+        params.min_tokens_out = Some(quote.amount_out + 1);
+        let err = RaydiumAmmV4InstructionBuilder.build_buy_instructions(&params).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<crate::swqos::common::TradeError>().map(|e| e.code),
+            Some(crate::swqos::common::TradeError::BELOW_MIN_TOKENS_OUT)
+        );
+
+        params.min_tokens_out = Some(quote.amount_out);
+        assert!(RaydiumAmmV4InstructionBuilder.build_buy_instructions(&params).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn raydium_amm_v4_buy_encodes_min_tokens_out_as_minimum_amount_out() {
+        let mut params = swap_params(market_params(), None);
+        let quote = compute_swap_amount(1_000_000_000, 2_000_000_000, true, 100_000, 100);
+        assert!(quote.min_amount_out < quote.amount_out);
+        let encoded_min_out = |ixs: Vec<Instruction>| {
+            let ix = ixs.last().unwrap();
+            assert_eq!(&ix.data[..1], SWAP_BASE_IN_DISCRIMINATOR);
+            u64::from_le_bytes(ix.data[9..17].try_into().unwrap())
+        };
+
+        params.min_tokens_out = Some(quote.amount_out);
+        let ixs = RaydiumAmmV4InstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        assert_eq!(encoded_min_out(ixs), quote.amount_out);
+
+        // A floor under the slippage minimum leaves the slippage minimum in place.
+        params.min_tokens_out = Some(1);
+        let ixs = RaydiumAmmV4InstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        assert_eq!(encoded_min_out(ixs), quote.min_amount_out);
+    }
+
     #[tokio::test]
     async fn raydium_amm_v4_rejects_placeholder_market_accounts() {
         let err = RaydiumAmmV4InstructionBuilder
@@ -467,4 +564,18 @@ mod tests {
         assert_eq!(create_ix.program_id, crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID);
         assert_eq!(create_ix.accounts[3].pubkey, crate::constants::USDC_TOKEN_ACCOUNT);
     }
+
+    #[test]
+    fn disabled_status_rejects_params_but_swap_only_and_waiting_do_not() {
+        let mut params = market_params();
+        for status in [1, 6, 7] {
+            params.pool_status = Some(status);
+            assert!(params.validate().is_ok(), "status {}", status);
+        }
+        for status in [0, 2, 3, 4, 5] {
+            params.pool_status = Some(status);
+            let err = params.validate().unwrap_err();
+            assert_eq!(err.code, crate::swqos::common::TradeError::POOL_PAUSED);
+        }
+    }
 }
@@ -6,16 +6,17 @@ use crate::{
             push_close_wsol_if_needed, push_create_or_wrap_user_token_account,
             push_create_user_token_account,
         },
+        utils::derived_pda,
         utils::raydium_cpmm::{
             accounts, get_observation_state_pda, get_pool_pda, get_vault_account,
             SWAP_BASE_IN_DISCRIMINATOR, SWAP_BASE_OUT_DISCRIMINATOR,
         },
     },
     trading::core::{
-        params::{RaydiumCpmmParams, SwapParams},
+        params::{RaydiumCpmmParams, SwapMode, SwapParams},
         traits::InstructionBuilder,
     },
-    utils::calc::raydium_cpmm::compute_swap_amount,
+    utils::calc::raydium_cpmm::{compute_swap_amount, compute_swap_amount_base_out},
 };
 use anyhow::{anyhow, Result};
 use solana_sdk::{
@@ -33,9 +34,7 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
         // ========================================
         // Parameter validation and basic data preparation
         // ========================================
-        if params.input_amount.unwrap_or(0) == 0 {
-            return Err(anyhow!("Amount cannot be zero"));
-        }
+        params.required_input_amount()?;
 
         let protocol_params = params
             .protocol_params
@@ -44,12 +43,14 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumCpmm"))?;
 
         let pool_state = if protocol_params.pool_state == Pubkey::default() {
-            get_pool_pda(
-                &protocol_params.amm_config,
-                &protocol_params.base_mint,
-                &protocol_params.quote_mint,
-            )
-            .unwrap()
+            derived_pda(
+                get_pool_pda(
+                    &protocol_params.amm_config,
+                    &protocol_params.base_mint,
+                    &protocol_params.quote_mint,
+                ),
+                "Raydium CPMM pool",
+            )?
         } else {
             protocol_params.pool_state
         };
@@ -60,15 +61,19 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
         let is_usdc = protocol_params.base_mint == crate::constants::USDC_TOKEN_ACCOUNT
             || protocol_params.quote_mint == crate::constants::USDC_TOKEN_ACCOUNT;
 
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
+        let is_usd1 = protocol_params.base_mint == crate::constants::USD1_TOKEN_ACCOUNT
+            || protocol_params.quote_mint == crate::constants::USD1_TOKEN_ACCOUNT;
+
+        if !is_wsol && !is_usdc && !is_usd1 {
+            return Err(anyhow!("Pool must contain WSOL, USDC or USD1"));
         }
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
         let is_base_in = protocol_params.base_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.base_mint == crate::constants::USDC_TOKEN_ACCOUNT;
+            || protocol_params.base_mint == crate::constants::USDC_TOKEN_ACCOUNT
+            || protocol_params.base_mint == crate::constants::USD1_TOKEN_ACCOUNT;
         let input_mint =
             if is_base_in { protocol_params.base_mint } else { protocol_params.quote_mint };
         let input_token_program = if is_base_in {
@@ -84,7 +89,7 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             protocol_params.base_token_program
         };
 
-        let amount_in: u64 = params.input_amount.unwrap_or(0);
+        let (data, amount_in) = build_swap_data(params, protocol_params, is_base_in)?;
 
         let input_token_account = get_associated_token_address_with_program_id_fast_use_seed(
             &params.payer.pubkey(),
@@ -99,11 +104,11 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             params.open_seed_optimize,
         );
 
-        let input_vault_account = get_vault_account(&pool_state, &input_mint, protocol_params);
-        let output_vault_account = get_vault_account(&pool_state, &output_mint, protocol_params);
+        let input_vault_account = get_vault_account(&pool_state, &input_mint, protocol_params)?;
+        let output_vault_account = get_vault_account(&pool_state, &output_mint, protocol_params)?;
 
         let observation_state_account = if protocol_params.observation_state == Pubkey::default() {
-            get_observation_state_pda(&pool_state).unwrap()
+            derived_pda(get_observation_state_pda(&pool_state), "Raydium CPMM observation state")?
         } else {
             protocol_params.observation_state
         };
@@ -117,6 +122,7 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             push_create_or_wrap_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &input_mint,
                 &input_token_program,
                 amount_in,
@@ -128,6 +134,7 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             push_create_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &output_mint,
                 &output_token_program,
                 params.open_seed_optimize,
@@ -150,26 +157,6 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             AccountMeta::new_readonly(output_mint, false),          // Output token mint (readonly)
             AccountMeta::new(observation_state_account, false),     // Observation State Account
         ];
-        // Create instruction data
-        let mut data = [0u8; 24];
-        if let Some(amount_out) = params.fixed_output_amount {
-            data[..8].copy_from_slice(&SWAP_BASE_OUT_DISCRIMINATOR);
-            data[8..16].copy_from_slice(&amount_in.to_le_bytes());
-            data[16..24].copy_from_slice(&amount_out.to_le_bytes());
-        } else {
-            let minimum_amount_out = compute_swap_amount(
-                protocol_params.base_reserve,
-                protocol_params.quote_reserve,
-                is_base_in,
-                amount_in,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-            )
-            .min_amount_out;
-            data[..8].copy_from_slice(&SWAP_BASE_IN_DISCRIMINATOR);
-            data[8..16].copy_from_slice(&amount_in.to_le_bytes());
-            data[16..24].copy_from_slice(&minimum_amount_out.to_le_bytes());
-        }
-
         instructions.push(Instruction::new_with_bytes(
             accounts::RAYDIUM_CPMM,
             &data,
@@ -193,17 +180,17 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             .downcast_ref::<RaydiumCpmmParams>()
             .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumCpmm"))?;
 
-        if params.input_amount.is_none() || params.input_amount.unwrap_or(0) == 0 {
-            return Err(anyhow!("Token amount is not set"));
-        }
+        params.required_input_amount()?;
 
         let pool_state = if protocol_params.pool_state == Pubkey::default() {
-            get_pool_pda(
-                &protocol_params.amm_config,
-                &protocol_params.base_mint,
-                &protocol_params.quote_mint,
-            )
-            .unwrap()
+            derived_pda(
+                get_pool_pda(
+                    &protocol_params.amm_config,
+                    &protocol_params.base_mint,
+                    &protocol_params.quote_mint,
+                ),
+                "Raydium CPMM pool",
+            )?
         } else {
             protocol_params.pool_state
         };
@@ -214,15 +201,19 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
         let is_usdc = protocol_params.base_mint == crate::constants::USDC_TOKEN_ACCOUNT
             || protocol_params.quote_mint == crate::constants::USDC_TOKEN_ACCOUNT;
 
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
+        let is_usd1 = protocol_params.base_mint == crate::constants::USD1_TOKEN_ACCOUNT
+            || protocol_params.quote_mint == crate::constants::USD1_TOKEN_ACCOUNT;
+
+        if !is_wsol && !is_usdc && !is_usd1 {
+            return Err(anyhow!("Pool must contain WSOL, USDC or USD1"));
         }
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
         let is_quote_out = protocol_params.quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.quote_mint == crate::constants::USDC_TOKEN_ACCOUNT;
+            || protocol_params.quote_mint == crate::constants::USDC_TOKEN_ACCOUNT
+            || protocol_params.quote_mint == crate::constants::USD1_TOKEN_ACCOUNT;
         let input_mint =
             if is_quote_out { protocol_params.base_mint } else { protocol_params.quote_mint };
         let input_token_program = if is_quote_out {
@@ -251,11 +242,11 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             params.open_seed_optimize,
         );
 
-        let output_vault_account = get_vault_account(&pool_state, &output_mint, protocol_params);
-        let input_vault_account = get_vault_account(&pool_state, &input_mint, protocol_params);
+        let output_vault_account = get_vault_account(&pool_state, &output_mint, protocol_params)?;
+        let input_vault_account = get_vault_account(&pool_state, &input_mint, protocol_params)?;
 
         let observation_state_account = if protocol_params.observation_state == Pubkey::default() {
-            get_observation_state_pda(&pool_state).unwrap()
+            derived_pda(get_observation_state_pda(&pool_state), "Raydium CPMM observation state")?
         } else {
             protocol_params.observation_state
         };
@@ -269,6 +260,7 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             push_create_user_token_account(
                 &mut instructions,
                 &params.payer.pubkey(),
+                &params.ata_rent_payer(),
                 &output_mint,
                 &output_token_program,
                 params.open_seed_optimize,
@@ -292,25 +284,7 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             AccountMeta::new(observation_state_account, false),     // Observation State Account
         ];
         // Create instruction data
-        let mut data = [0u8; 24];
-        let amount_in = params.input_amount.unwrap_or(0);
-        if let Some(amount_out) = params.fixed_output_amount {
-            data[..8].copy_from_slice(&SWAP_BASE_OUT_DISCRIMINATOR);
-            data[8..16].copy_from_slice(&amount_in.to_le_bytes());
-            data[16..24].copy_from_slice(&amount_out.to_le_bytes());
-        } else {
-            let minimum_amount_out = compute_swap_amount(
-                protocol_params.base_reserve,
-                protocol_params.quote_reserve,
-                is_quote_out,
-                amount_in,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-            )
-            .min_amount_out;
-            data[..8].copy_from_slice(&SWAP_BASE_IN_DISCRIMINATOR);
-            data[8..16].copy_from_slice(&amount_in.to_le_bytes());
-            data[16..24].copy_from_slice(&minimum_amount_out.to_le_bytes());
-        }
+        let (data, _) = build_swap_data(params, protocol_params, is_quote_out)?;
 
         instructions.push(Instruction::new_with_bytes(
             accounts::RAYDIUM_CPMM,
@@ -335,13 +309,74 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
     }
 }
 
+/// Swap instruction data and the input amount signed for (the WSOL wrap amount on buys).
+///
+/// - `ExactIn`: `swap_base_input(input_amount, min_out)`, or `swap_base_output` as below when
+///   `fixed_output_amount` is set.
+/// - `ExactOut`: `swap_base_output(max_in, fixed_output_amount)` where `max_in` is quoted from
+///   reserves plus slippage; `input_amount` is the budget it must fit into.
+fn build_swap_data(
+    params: &SwapParams,
+    protocol_params: &RaydiumCpmmParams,
+    is_base_in: bool,
+) -> Result<([u8; 24], u64)> {
+    let amount_in = params.required_input_amount()?;
+    let slippage_basis_points = params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
+    let (discriminator, first, second) =
+        match (protocol_params.swap_mode, params.fixed_output_amount) {
+            (SwapMode::ExactOut, None) => {
+                return Err(anyhow!("RaydiumCpmm ExactOut requires fixed_output_amount"));
+            }
+            (_, Some(amount_out)) => {
+                let quote = compute_swap_amount_base_out(
+                    protocol_params.base_reserve,
+                    protocol_params.quote_reserve,
+                    is_base_in,
+                    amount_out,
+                    slippage_basis_points,
+                )
+                .ok_or_else(|| {
+                    anyhow!("RaydiumCpmm pool cannot provide {} output tokens", amount_out)
+                })?;
+                if quote.amount_in > amount_in {
+                    return Err(anyhow!(
+                        "RaydiumCpmm ExactOut needs {} input, above the input_amount budget {}",
+                        quote.amount_in,
+                        amount_in
+                    ));
+                }
+                (SWAP_BASE_OUT_DISCRIMINATOR, quote.max_amount_in.min(amount_in), amount_out)
+            }
+            (SwapMode::ExactIn, None) => {
+                let quote = compute_swap_amount(
+                    protocol_params.base_reserve,
+                    protocol_params.quote_reserve,
+                    is_base_in,
+                    amount_in,
+                    slippage_basis_points,
+                );
+                params.check_min_tokens_out(quote.amount_out)?;
+                (
+                    SWAP_BASE_IN_DISCRIMINATOR,
+                    amount_in,
+                    params.min_out_with_floor(quote.min_amount_out),
+                )
+            }
+        };
+    let mut data = [0u8; 24];
+    data[..8].copy_from_slice(&discriminator);
+    data[8..16].copy_from_slice(&first.to_le_bytes());
+    data[16..24].copy_from_slice(&second.to_le_bytes());
+    Ok((data, first))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        common::GasFeeStrategy,
+        common::{GasFeeStrategy, ParallelStrategy},
         swqos::TradeType,
-        trading::core::params::{DexParamEnum, SwapParams},
+        trading::core::params::{AtaCreationMode, DexParamEnum, SwapParams},
     };
     use solana_sdk::{pubkey::Pubkey, signature::Keypair};
     use std::sync::Arc;
@@ -363,6 +398,8 @@ mod tests {
             base_token_program: crate::constants::TOKEN_PROGRAM,
             quote_token_program: crate::constants::TOKEN_PROGRAM,
             observation_state: pk(6),
+            swap_mode: SwapMode::ExactIn,
+            pool_status: None,
         }
     }
 
@@ -402,6 +439,21 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: None,
+            swqos_watchdog: None,
+            ata_creation: AtaCreationMode::Inline,
+            memo: None,
+            parallel_strategy: ParallelStrategy::RaceAll,
+            max_ata_creations: None,
+            cu_learner: None,
+            rpc_fallback: false,
+            cleanup_on_failure: false,
+            skip_compute_budget: false,
+            simulation_capture: None,
+            ata_rent_funder: None,
+            receive_native_sol: false,
+            on_before_send: None,
+            multi_nonce: None,
+            min_tokens_out: None,
         }
     }
 
@@ -416,6 +468,31 @@ mod tests {
         assert!(!ix.accounts[2].is_writable);
     }
 
+    #[tokio::test]
+    async fn missing_pool_accounts_are_derived_without_panicking() {
+        use crate::instruction::utils::raydium_cpmm::{get_observation_state_pda, get_vault_pda};
+
+        let mut params = swap_params(None);
+        let DexParamEnum::RaydiumCpmm(cpmm) = &mut params.protocol_params else { unreachable!() };
+        cpmm.pool_state = Pubkey::default();
+        cpmm.base_vault = Pubkey::default();
+        cpmm.quote_vault = Pubkey::default();
+        cpmm.observation_state = Pubkey::default();
+
+        let instructions = RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await;
+        let ix = instructions.expect("fallback derivation returns a Result").pop().unwrap();
+
+        let pool_state =
+            get_pool_pda(&pk(2), &crate::constants::WSOL_TOKEN_ACCOUNT, &pk(3)).unwrap();
+        assert_eq!(ix.accounts[3].pubkey, pool_state);
+        assert_eq!(
+            ix.accounts[6].pubkey,
+            get_vault_pda(&pool_state, &crate::constants::WSOL_TOKEN_ACCOUNT).unwrap()
+        );
+        assert_eq!(ix.accounts[7].pubkey, get_vault_pda(&pool_state, &pk(3)).unwrap());
+        assert_eq!(ix.accounts[12].pubkey, get_observation_state_pda(&pool_state).unwrap());
+    }
+
     #[tokio::test]
     async fn raydium_cpmm_uses_base_output_when_fixed_output_is_set() {
         let instructions = RaydiumCpmmInstructionBuilder
@@ -424,9 +501,16 @@ mod tests {
             .unwrap();
         let ix = instructions.last().unwrap();
 
+        let quote =
+            compute_swap_amount_base_out(1_000_000_000, 2_000_000_000, true, 42, 100).unwrap();
         assert_eq!(&ix.data[..8], SWAP_BASE_OUT_DISCRIMINATOR);
-        assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 100_000);
+        assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), quote.max_amount_in);
+        assert!(quote.max_amount_in < 100_000);
         assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 42);
+
+        let mut params = swap_params(Some(42));
+        params.input_amount = Some(quote.amount_in - 1);
+        assert!(RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.is_err());
     }
 
     #[tokio::test]
@@ -450,4 +534,151 @@ mod tests {
         assert_eq!(create_ix.accounts[3].pubkey, crate::constants::USDC_TOKEN_ACCOUNT);
         assert_eq!(swap_ix.accounts[10].pubkey, crate::constants::USDC_TOKEN_ACCOUNT);
     }
+
+    #[tokio::test]
+    async fn raydium_cpmm_exact_out_quotes_max_input_from_reserves() {
+        let mut params = swap_params(Some(1_000));
+        params.protocol_params =
+            DexParamEnum::RaydiumCpmm(cpmm_params().with_swap_mode(SwapMode::ExactOut));
+
+        let instructions =
+            RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        let ix = instructions.last().unwrap();
+
+        assert_eq!(&ix.data[..8], SWAP_BASE_OUT_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 508);
+        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 1_000);
+
+        params.fixed_output_amount = None;
+        assert!(RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.is_err());
+        params.fixed_output_amount = Some(1_000_000);
+        assert!(RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn raydium_cpmm_buy_below_min_tokens_out_is_rejected() {
+        let mut params = swap_params(None);
+        let quote = compute_swap_amount(1_000_000_000, 2_000_000_000, true, 100_000, 100);
+        params.min_tokens_out = Some(quote.amount_out + 1);
+        let err = RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<crate::swqos::common::TradeError>().map(|e| e.code),
+            Some(crate::swqos::common::TradeError::BELOW_MIN_TOKENS_OUT)
+        );
+
+        params.min_tokens_out = Some(quote.amount_out);
+        assert!(RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn raydium_cpmm_buy_encodes_min_tokens_out_as_minimum_amount_out() {
+        let mut params = swap_params(None);
+        let quote = compute_swap_amount(1_000_000_000, 2_000_000_000, true, 100_000, 100);
+        assert!(quote.min_amount_out < quote.amount_out);
+        let encoded_min_out = |ixs: Vec<Instruction>| {
+            let ix = ixs.last().unwrap();
+            assert_eq!(&ix.data[..8], SWAP_BASE_IN_DISCRIMINATOR);
+            u64::from_le_bytes(ix.data[16..24].try_into().unwrap())
+        };
+
+        params.min_tokens_out = Some(quote.amount_out);
+        let ixs = RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        assert_eq!(encoded_min_out(ixs), quote.amount_out);
+
+        // A floor under the slippage minimum leaves the slippage minimum in place.
+        params.min_tokens_out = Some(1);
+        let ixs = RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        assert_eq!(encoded_min_out(ixs), quote.min_amount_out);
+    }
+
+    #[tokio::test]
+    async fn pool_with_quote_as_token0_maps_vaults_by_mint() {
+        use crate::common::{mock_rpc::mock_account, MockSolanaRpcClient};
+
+        let wsol = crate::constants::WSOL_TOKEN_ACCOUNT;
+        let token = pk(3);
+        let (token0_vault, token1_vault) = (pk(10), pk(11));
+        // PoolState: 10 pubkeys, 5 u8, 7 u64 + 31 u64 padding.
+        let mut data = vec![0u8; 8];
+        for key in [
+            pk(2),
+            pk(7),
+            token0_vault,
+            token1_vault,
+            pk(8),
+            wsol,
+            token,
+            crate::constants::TOKEN_PROGRAM,
+            crate::constants::TOKEN_PROGRAM,
+            pk(6),
+        ] {
+            data.extend_from_slice(key.as_ref());
+        }
+        data.extend_from_slice(&[0, 0, 6, 9, 6]);
+        data.resize(8 + crate::instruction::utils::raydium_cpmm_types::POOL_STATE_SIZE, 0);
+        let pool = mock_account(accounts::RAYDIUM_CPMM, data);
+        let rpc = MockSolanaRpcClient::new()
+            .with_account(Some(&pool))
+            .with_token_account_balance(5_000_000_000, 9)
+            .with_token_account_balance(7_000_000, 6)
+            .build();
+
+        let loaded = RaydiumCpmmParams::from_pool_address_by_rpc(&rpc, &pk(1)).await.unwrap();
+        // WSOL stored as token0 is loaded as the quote side
+        assert_eq!((loaded.base_mint, loaded.quote_mint), (token, wsol));
+        assert_eq!((loaded.base_vault, loaded.quote_vault), (token1_vault, token0_vault));
+        assert_eq!((loaded.base_reserve, loaded.quote_reserve), (7_000_000, 5_000_000_000));
+
+        let mut params = swap_params(None);
+        params.protocol_params = DexParamEnum::RaydiumCpmm(loaded);
+        let buy = RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        let ix = buy.last().unwrap();
+        // input vault, output vault, ..., input mint, output mint
+        assert_eq!((ix.accounts[6].pubkey, ix.accounts[7].pubkey), (token0_vault, token1_vault));
+        assert_eq!((ix.accounts[10].pubkey, ix.accounts[11].pubkey), (wsol, token));
+
+        params.trade_type = TradeType::Sell;
+        params.input_mint = token;
+        params.output_mint = wsol;
+        let sell = RaydiumCpmmInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+        let ix = sell.last().unwrap();
+        assert_eq!((ix.accounts[6].pubkey, ix.accounts[7].pubkey), (token1_vault, token0_vault));
+        assert_eq!((ix.accounts[10].pubkey, ix.accounts[11].pubkey), (token, wsol));
+    }
+
+    #[tokio::test]
+    async fn usd1_quoted_pool_pays_and_receives_usd1() {
+        let usd1 = crate::constants::USD1_TOKEN_ACCOUNT;
+        let token = pk(3);
+        let mut protocol_params = cpmm_params();
+        // `oriented` puts the USD1 side in quote_mint
+        protocol_params.base_mint = token;
+        protocol_params.quote_mint = usd1;
+
+        let mut params = swap_params(None);
+        params.protocol_params = DexParamEnum::RaydiumCpmm(protocol_params);
+        params.input_mint = usd1;
+        params.output_mint = token;
+        let buy = RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        let ix = buy.last().unwrap();
+        assert_eq!((ix.accounts[10].pubkey, ix.accounts[11].pubkey), (usd1, token));
+
+        params.trade_type = TradeType::Sell;
+        params.input_mint = token;
+        params.output_mint = usd1;
+        let sell = RaydiumCpmmInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+        let ix = sell.last().unwrap();
+        assert_eq!((ix.accounts[10].pubkey, ix.accounts[11].pubkey), (token, usd1));
+    }
+
+    #[test]
+    fn swap_disabled_status_bit_rejects_params_but_other_bits_do_not() {
+        let mut params = cpmm_params();
+        params.pool_status = Some(0b011);
+        assert!(params.validate().is_ok());
+
+        params.pool_status = Some(0b100);
+        let err = params.validate().unwrap_err();
+        assert_eq!(err.code, crate::swqos::common::TradeError::POOL_PAUSED);
+    }
 }
@@ -1,16 +1,18 @@
 use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
 
+/// `funder` pays the rent of the created account (`SwapParams::ata_rent_payer`); `payer` owns it.
 #[inline]
 pub(crate) fn push_create_user_token_account(
     instructions: &mut Vec<Instruction>,
     payer: &Pubkey,
+    funder: &Pubkey,
     mint: &Pubkey,
     token_program: &Pubkey,
     use_seed: bool,
 ) {
     instructions.extend(
         crate::common::fast_fn::create_associated_token_account_idempotent_fast_use_seed(
-            payer,
+            funder,
             payer,
             mint,
             token_program,
@@ -23,15 +25,16 @@ pub(crate) fn push_create_user_token_account(
 pub(crate) fn push_create_or_wrap_user_token_account(
     instructions: &mut Vec<Instruction>,
     payer: &Pubkey,
+    funder: &Pubkey,
     mint: &Pubkey,
     token_program: &Pubkey,
     amount: u64,
     use_seed: bool,
 ) {
     if *mint == crate::constants::WSOL_TOKEN_ACCOUNT {
-        instructions.extend(crate::trading::common::handle_wsol(payer, amount));
+        instructions.extend(crate::trading::common::handle_wsol_funded(payer, funder, amount));
     } else {
-        push_create_user_token_account(instructions, payer, mint, token_program, use_seed);
+        push_create_user_token_account(instructions, payer, funder, mint, token_program, use_seed);
     }
 }
 
@@ -21,9 +21,9 @@ pub mod accounts {
     pub const EVENT_AUTHORITY: Pubkey = pubkey!("2DPAtwB8L12vrMRExbLuyGnC7n2J5LNoZQSejeQGpwkr");
     pub const BONK: Pubkey = pubkey!("LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj");
 
-    pub const PLATFORM_FEE_RATE: u128 = 100; // 1%
-    pub const PROTOCOL_FEE_RATE: u128 = 25; // 0.25%
-    pub const SHARE_FEE_RATE: u128 = 0; // 0%
+    pub use crate::utils::calc::fees::bonk::{
+        PLATFORM_FEE_RATE, PROTOCOL_FEE_RATE, SHARE_FEE_RATE,
+    };
 
     // META
     pub const AUTHORITY_META: solana_sdk::instruction::AccountMeta =
@@ -66,10 +66,20 @@ pub async fn fetch_pool_state(
     pool_address: &Pubkey,
 ) -> Result<PoolState, anyhow::Error> {
     let account = rpc.get_account(pool_address).await?;
+    decode_pool_state_account(&account)
+}
+
+/// Check the owner and decode a pool state account.
+pub(crate) fn decode_pool_state_account(
+    account: &solana_sdk::account::Account,
+) -> Result<PoolState, anyhow::Error> {
     if account.owner != accounts::BONK {
         return Err(anyhow!("Account is not owned by Bonk program"));
     }
-    let pool_state = pool_state_decode(&account.data[8..])
+    let pool_state = account
+        .data
+        .get(8..)
+        .and_then(pool_state_decode)
         .ok_or_else(|| anyhow!("Failed to decode pool state"))?;
     Ok(pool_state)
 }
@@ -158,40 +168,36 @@ pub fn get_amount_out(
 }
 
 pub fn get_pool_pda(base_mint: &Pubkey, quote_mint: &Pubkey) -> Option<Pubkey> {
-    crate::common::fast_fn::get_cached_pda(
+    crate::trading::common::protocol_overrides::cached_pda(
+        crate::trading::factory::DexType::Bonk,
         crate::common::fast_fn::PdaCacheKey::BonkPool(*base_mint, *quote_mint),
-        || {
-            let seeds: &[&[u8]; 3] = &[seeds::POOL_SEED, base_mint.as_ref(), quote_mint.as_ref()];
-            let program_id: &Pubkey = &accounts::BONK;
-            let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
-            pda.map(|pubkey| pubkey.0)
-        },
+        &[seeds::POOL_SEED, base_mint.as_ref(), quote_mint.as_ref()],
     )
 }
 
 pub fn get_vault_pda(pool_state: &Pubkey, mint: &Pubkey) -> Option<Pubkey> {
-    crate::common::fast_fn::get_cached_pda(
+    crate::trading::common::protocol_overrides::cached_pda(
+        crate::trading::factory::DexType::Bonk,
         crate::common::fast_fn::PdaCacheKey::BonkVault(*pool_state, *mint),
-        || {
-            let seeds: &[&[u8]; 3] = &[seeds::POOL_VAULT_SEED, pool_state.as_ref(), mint.as_ref()];
-            let program_id: &Pubkey = &accounts::BONK;
-            let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
-            pda.map(|pubkey| pubkey.0)
-        },
+        &[seeds::POOL_VAULT_SEED, pool_state.as_ref(), mint.as_ref()],
     )
 }
 
 pub fn get_platform_associated_account(platform_config: &Pubkey) -> Option<Pubkey> {
     let seeds: &[&[u8]; 2] =
         &[platform_config.as_ref(), crate::constants::WSOL_TOKEN_ACCOUNT.as_ref()];
-    let program_id: &Pubkey = &accounts::BONK;
+    let program_id: &Pubkey = &crate::trading::common::protocol_overrides::program_id(
+        crate::trading::factory::DexType::Bonk,
+    );
     let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
     pda.map(|pubkey| pubkey.0)
 }
 
 pub fn get_creator_associated_account(creator: &Pubkey) -> Option<Pubkey> {
     let seeds: &[&[u8]; 2] = &[creator.as_ref(), crate::constants::WSOL_TOKEN_ACCOUNT.as_ref()];
-    let program_id: &Pubkey = &accounts::BONK;
+    let program_id: &Pubkey = &crate::trading::common::protocol_overrides::program_id(
+        crate::trading::factory::DexType::Bonk,
+    );
     let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
     pda.map(|pubkey| pubkey.0)
 }
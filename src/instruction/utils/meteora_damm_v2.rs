@@ -47,6 +47,13 @@ pub const SWAP2_DISCRIMINATOR: &[u8] = &[65, 75, 63, 76, 235, 91, 91, 136];
 pub const SWAP_MODE_EXACT_IN: u8 = 0;
 pub const SWAP_MODE_PARTIAL_FILL: u8 = 1;
 pub const SWAP_MODE_EXACT_OUT: u8 = 2;
+/// `Pool.pool_status`: 0 enabled, anything else disabled.
+pub const POOL_STATUS_ENABLED: u8 = 0;
+
+#[inline]
+pub fn is_swap_disabled(pool_status: u8) -> bool {
+    pool_status != POOL_STATUS_ENABLED
+}
 
 pub async fn fetch_pool(
     rpc: &SolanaRpcClient,
@@ -62,5 +69,8 @@ pub async fn fetch_pool(
 
 #[inline]
 pub fn get_event_authority_pda() -> Pubkey {
-    Pubkey::find_program_address(&[seeds::EVENT_AUTHORITY_SEED], &accounts::METEORA_DAMM_V2).0
+    let program_id = crate::trading::common::protocol_overrides::program_id(
+        crate::trading::factory::DexType::MeteoraDammV2,
+    );
+    Pubkey::find_program_address(&[seeds::EVENT_AUTHORITY_SEED], &program_id).0
 }
@@ -11,3 +11,25 @@ pub mod meteora_damm_v2_types;
 pub mod pumpswap_types;
 pub mod raydium_amm_v4_types;
 pub mod raydium_cpmm_types;
+
+use solana_sdk::pubkey::Pubkey;
+
+/// Turn a fallback PDA derivation into an error instead of a panic.
+#[inline]
+pub(crate) fn derived_pda(pda: Option<Pubkey>, what: &str) -> anyhow::Result<Pubkey> {
+    pda.ok_or_else(|| anyhow::anyhow!("Failed to derive {} PDA", what))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_derivation_is_an_error_not_a_panic() {
+        let err = derived_pda(None, "Bonk pool").unwrap_err();
+        assert_eq!(err.to_string(), "Failed to derive Bonk pool PDA");
+
+        let pda = Pubkey::new_unique();
+        assert_eq!(derived_pda(Some(pda), "Bonk pool").unwrap(), pda);
+    }
+}
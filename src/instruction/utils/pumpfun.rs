@@ -24,6 +24,8 @@ pub mod seeds {
     pub const CREATOR_VAULT_SEED: &[u8] = b"creator-vault";
     /// Metadata PDA seeds prefix.
     pub const METADATA_SEED: &[u8] = b"metadata";
+    /// Mint authority PDA (`["mint-authority"]`) set on mints created by Pump.fun.
+    pub const MINT_AUTHORITY_SEED: &[u8] = b"mint-authority";
     /// User volume accumulator for cashback / bonding-curve UX.
     pub const USER_VOLUME_ACCUMULATOR_SEED: &[u8] = b"user_volume_accumulator";
     /// Global volume accumulator.
@@ -37,15 +39,15 @@ pub mod global_constants {
 
     use solana_sdk::{pubkey, pubkey::Pubkey};
 
+    pub use crate::utils::calc::fees::pumpfun::{CREATOR_FEE, FEE_BASIS_POINTS};
+
     pub const INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000;
     pub const INITIAL_VIRTUAL_SOL_RESERVES: u64 = 30_000_000_000;
     pub const INITIAL_VIRTUAL_USDC_RESERVES: u64 = 4_292_000_000;
     pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
     pub const TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
-    pub const FEE_BASIS_POINTS: u64 = 95;
     pub const ENABLE_MIGRATE: bool = false;
     pub const POOL_MIGRATION_FEE: u64 = 15_000_001;
-    pub const CREATOR_FEE: u64 = 30;
     pub const SCALE: u64 = 1_000_000;
     pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
     pub const COMPLETION_LAMPORTS: u64 = 85 * LAMPORTS_PER_SOL;
@@ -187,6 +189,9 @@ pub const SELL_V2_DISCRIMINATOR: [u8; 8] = [93, 246, 130, 60, 231, 233, 64, 178]
 /// `buy_exact_quote_in_v2` (native SOL spend for SOL-paired coins when `quote_mint` is WSOL)
 pub const BUY_EXACT_QUOTE_IN_V2_DISCRIMINATOR: [u8; 8] = [194, 171, 28, 70, 104, 77, 91, 47];
 
+/// Legacy `create` (SPL Token mint + Metaplex metadata).
+pub const CREATE_DISCRIMINATOR: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
+
 pub const EXTEND_ACCOUNT_DISCRIMINATOR: [u8; 8] = [234, 102, 194, 203, 150, 72, 62, 229];
 
 pub const SHARING_CONFIG_ACCOUNT_DISCRIMINATOR: [u8; 8] = [216, 74, 9, 0, 56, 140, 93, 75];
@@ -323,25 +328,38 @@ pub fn extend_bonding_curve_account_instruction(
 
 #[inline]
 pub fn get_bonding_curve_pda(mint: &Pubkey) -> Option<Pubkey> {
-    crate::common::fast_fn::get_cached_pda(
+    crate::trading::common::protocol_overrides::cached_pda(
+        crate::trading::factory::DexType::PumpFun,
         crate::common::fast_fn::PdaCacheKey::PumpFunBondingCurve(*mint),
-        || {
-            let seeds: &[&[u8]; 2] = &[seeds::BONDING_CURVE_SEED, mint.as_ref()];
-            let program_id: &Pubkey = &accounts::PUMPFUN;
-            Pubkey::try_find_program_address(seeds, program_id).map(|pubkey| pubkey.0)
-        },
+        &[seeds::BONDING_CURVE_SEED, mint.as_ref()],
     )
 }
 
+/// `PDA(["mint-authority"])` under Pump.fun (or its program-id override).
+#[inline]
+pub fn get_mint_authority_pda() -> Pubkey {
+    let program_id = crate::trading::common::protocol_overrides::program_id(
+        crate::trading::factory::DexType::PumpFun,
+    );
+    Pubkey::find_program_address(&[seeds::MINT_AUTHORITY_SEED], &program_id).0
+}
+
+/// Metaplex metadata account `PDA(["metadata", mpl_program, mint])` under the Metaplex program.
+#[inline]
+pub fn get_metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[seeds::METADATA_SEED, accounts::MPL_TOKEN_METADATA.as_ref(), mint.as_ref()],
+        &accounts::MPL_TOKEN_METADATA,
+    )
+    .0
+}
+
 #[inline]
 pub fn get_bonding_curve_v2_pda(mint: &Pubkey) -> Option<Pubkey> {
-    crate::common::fast_fn::get_cached_pda(
+    crate::trading::common::protocol_overrides::cached_pda(
+        crate::trading::factory::DexType::PumpFun,
         crate::common::fast_fn::PdaCacheKey::PumpFunBondingCurveV2(*mint),
-        || {
-            let seeds: &[&[u8]; 2] = &[seeds::BONDING_CURVE_V2_SEED, mint.as_ref()];
-            let program_id: &Pubkey = &accounts::PUMPFUN;
-            Pubkey::try_find_program_address(seeds, program_id).map(|pubkey| pubkey.0)
-        },
+        &[seeds::BONDING_CURVE_V2_SEED, mint.as_ref()],
     )
 }
 
@@ -361,13 +379,10 @@ pub fn get_creator(creator_vault_pda: &Pubkey) -> Pubkey {
 
 #[inline]
 pub fn get_creator_vault_pda(creator: &Pubkey) -> Option<Pubkey> {
-    crate::common::fast_fn::get_cached_pda(
+    crate::trading::common::protocol_overrides::cached_pda(
+        crate::trading::factory::DexType::PumpFun,
         crate::common::fast_fn::PdaCacheKey::PumpFunCreatorVault(*creator),
-        || {
-            let seeds: &[&[u8]; 2] = &[seeds::CREATOR_VAULT_SEED, creator.as_ref()];
-            let program_id: &Pubkey = &accounts::PUMPFUN;
-            Pubkey::try_find_program_address(seeds, program_id).map(|pubkey| pubkey.0)
-        },
+        &[seeds::CREATOR_VAULT_SEED, creator.as_ref()],
     )
 }
 
@@ -440,13 +455,10 @@ pub fn resolve_creator_vault_for_ix_with_fee_sharing(
 
 #[inline]
 pub fn get_user_volume_accumulator_pda(user: &Pubkey) -> Option<Pubkey> {
-    crate::common::fast_fn::get_cached_pda(
+    crate::trading::common::protocol_overrides::cached_pda(
+        crate::trading::factory::DexType::PumpFun,
         crate::common::fast_fn::PdaCacheKey::PumpFunUserVolume(*user),
-        || {
-            let seed: &[&[u8]; 2] = &[seeds::USER_VOLUME_ACCUMULATOR_SEED, user.as_ref()];
-            let program_id: &Pubkey = &accounts::PUMPFUN;
-            Pubkey::try_find_program_address(seed, program_id).map(|pubkey| pubkey.0)
-        },
+        &[seeds::USER_VOLUME_ACCUMULATOR_SEED, user.as_ref()],
     )
 }
 
@@ -484,23 +496,30 @@ pub async fn fetch_fee_sharing_creator_vault_if_active(
         Ok(a) => a,
         Err(_) => return Ok(None),
     };
+    Ok(fee_sharing_creator_vault_from_account(&acc, &config_pda, mint))
+}
+
+/// Creator vault of an active `SharingConfig` account at `config_pda` for `mint`, else `None`.
+pub(crate) fn fee_sharing_creator_vault_from_account(
+    acc: &solana_sdk::account::Account,
+    config_pda: &Pubkey,
+    mint: &Pubkey,
+) -> Option<Pubkey> {
     if acc.owner != accounts::FEE_PROGRAM {
-        return Ok(None);
+        return None;
     }
     let d = acc.data.as_slice();
     if d.len() < 43 || d[..8] != SHARING_CONFIG_ACCOUNT_DISCRIMINATOR {
-        return Ok(None);
+        return None;
     }
     if d[10] != SHARING_CONFIG_STATUS_ACTIVE {
-        return Ok(None);
+        return None;
     }
-    let mint_on_chain = Pubkey::new_from_array(
-        d[11..43].try_into().map_err(|_| anyhow!("SharingConfig mint slice"))?,
-    );
+    let mint_on_chain = Pubkey::new_from_array(d[11..43].try_into().ok()?);
     if mint_on_chain != *mint {
-        return Ok(None);
+        return None;
     }
-    Ok(get_creator_vault_pda(&config_pda))
+    get_creator_vault_pda(config_pda)
 }
 
 #[inline]
@@ -512,7 +531,16 @@ pub async fn fetch_bonding_curve_account(
         get_bonding_curve_pda(mint).ok_or_else(|| anyhow!("Bonding curve not found"))?;
 
     let account = rpc.get_account(&bonding_curve_pda).await?;
-    if account.data.is_empty() {
+    let bonding_curve = decode_bonding_curve_account(&account.data, bonding_curve_pda)?;
+    Ok((Arc::new(bonding_curve), bonding_curve_pda))
+}
+
+/// Decode bonding curve account data read from `bonding_curve_pda`.
+pub(crate) fn decode_bonding_curve_account(
+    data: &[u8],
+    bonding_curve_pda: Pubkey,
+) -> Result<BondingCurveAccount, anyhow::Error> {
+    if data.is_empty() {
         return Err(anyhow!("Bonding curve not found"));
     }
 
@@ -520,11 +548,17 @@ pub async fn fetch_bonding_curve_account(
     // (from on-chain schema additions like new fields) are silently ignored.
     // `try_from_slice` requires the entire slice to be consumed, causing
     // "Not all bytes read" when the account has been extended.
-    let mut bonding_curve = BondingCurveAccount::deserialize(&mut &account.data[8..])
-        .map_err(|e| anyhow::anyhow!("Failed to decode bonding curve account: {}", e))?;
+    let mut bonding_curve =
+        BondingCurveAccount::deserialize(&mut data.get(8..).unwrap_or_default())
+            .map_err(|e| anyhow::anyhow!("Failed to decode bonding curve account: {}", e))?;
     bonding_curve.account = bonding_curve_pda;
+    Ok(bonding_curve)
+}
 
-    Ok((Arc::new(bonding_curve), bonding_curve_pda))
+/// Whether the mint's bonding curve is complete, i.e. the token graduated (migrated to
+/// PumpSwap) and can no longer be traded on the curve. 是否已毕业迁移到 PumpSwap。
+pub async fn is_graduated(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<bool, anyhow::Error> {
+    Ok(fetch_bonding_curve_account(rpc, mint).await?.0.complete)
 }
 
 #[cfg(test)]
@@ -741,4 +775,25 @@ mod tests {
         assert!(m.is_writable);
         assert!(!m.is_signer);
     }
+
+    #[tokio::test]
+    async fn is_graduated_reads_the_curve_complete_flag() {
+        use crate::common::MockSolanaRpcClient;
+        use solana_sdk::account::Account;
+
+        let curve = |complete: bool| {
+            let mut data = vec![0u8; 8 + 5 * 8];
+            data.push(complete as u8);
+            data.extend_from_slice(&[0u8; 32 + 2 + 32]);
+            Account { data, owner: accounts::PUMPFUN, ..Account::default() }
+        };
+        let mint = Pubkey::new_unique();
+        let rpc = MockSolanaRpcClient::new()
+            .with_account(Some(&curve(false)))
+            .with_account(Some(&curve(true)))
+            .build();
+
+        assert!(!is_graduated(&rpc, &mint).await.unwrap());
+        assert!(is_graduated(&rpc, &mint).await.unwrap());
+    }
 }
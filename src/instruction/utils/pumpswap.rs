@@ -71,9 +71,9 @@ pub mod accounts {
     /// Pump Bonding Curve program（canonical pool 的 creator 来自此程序的 pool-authority PDA）
     pub const PUMP_PROGRAM_ID: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
 
-    pub const LP_FEE_BASIS_POINTS: u64 = 25;
-    pub const PROTOCOL_FEE_BASIS_POINTS: u64 = 5;
-    pub const COIN_CREATOR_FEE_BASIS_POINTS: u64 = 5;
+    pub use crate::utils::calc::fees::pumpswap::{
+        COIN_CREATOR_FEE_BASIS_POINTS, LP_FEE_BASIS_POINTS, PROTOCOL_FEE_BASIS_POINTS,
+    };
 
     pub const FEE_PROGRAM: Pubkey = pubkey!("pfeeUxB6jkeY1Hxd7CsFCAjcbHA9rWtchMGdZ6VojVZ");
 
@@ -179,39 +179,7 @@ pub mod accounts {
         };
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct PumpSwapFeeBasisPoints {
-    pub lp_fee_basis_points: u64,
-    pub protocol_fee_basis_points: u64,
-    pub coin_creator_fee_basis_points: u64,
-}
-
-impl PumpSwapFeeBasisPoints {
-    #[inline]
-    pub const fn new(
-        lp_fee_basis_points: u64,
-        protocol_fee_basis_points: u64,
-        coin_creator_fee_basis_points: u64,
-    ) -> Self {
-        Self { lp_fee_basis_points, protocol_fee_basis_points, coin_creator_fee_basis_points }
-    }
-
-    #[inline]
-    pub const fn legacy_default() -> Self {
-        Self::new(
-            accounts::LP_FEE_BASIS_POINTS,
-            accounts::PROTOCOL_FEE_BASIS_POINTS,
-            accounts::COIN_CREATOR_FEE_BASIS_POINTS,
-        )
-    }
-}
-
-impl Default for PumpSwapFeeBasisPoints {
-    #[inline]
-    fn default() -> Self {
-        Self::legacy_default()
-    }
-}
+pub use crate::utils::calc::pumpswap::PumpSwapFeeBasisPoints;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PumpSwapFeeTier {
@@ -450,7 +418,11 @@ async fn refresh_fee_config_once(rpc: &SolanaRpcClient) -> Option<PumpSwapFeeCon
             return None;
         }
     };
+    store_fee_config(&account)
+}
 
+/// Validate and decode a FeeConfig account, refreshing the cache on success.
+fn store_fee_config(account: &solana_sdk::account::Account) -> Option<PumpSwapFeeConfig> {
     if account.owner != accounts::FEE_PROGRAM {
         warn!(
             target: "pumpswap_fee_config",
@@ -511,6 +483,31 @@ pub async fn fetch_fee_config(rpc: &SolanaRpcClient) -> Option<PumpSwapFeeConfig
     refresh_fee_config_once(rpc).await
 }
 
+/// [`fetch_fee_config`] read as of at least `min_context_slot`, bypassing the cache (the read
+/// refreshes it). `None` behaves like [`fetch_fee_config`].
+pub async fn fetch_fee_config_at_slot(
+    rpc: &SolanaRpcClient,
+    min_context_slot: Option<u64>,
+) -> Option<PumpSwapFeeConfig> {
+    if min_context_slot.is_none() {
+        return fetch_fee_config(rpc).await;
+    }
+    let accounts = match crate::trading::core::params::fetch_accounts_at_slot(
+        rpc,
+        &[accounts::FEE_CONFIG],
+        min_context_slot,
+    )
+    .await
+    {
+        Ok(accounts) => accounts,
+        Err(e) => {
+            warn!(target: "pumpswap_fee_config", "PumpSwap FeeConfig 读取失败: {}", e);
+            return None;
+        }
+    };
+    store_fee_config(accounts.first()?.as_ref()?)
+}
+
 #[inline]
 pub fn global_fee_basis_points() -> PumpSwapFeeBasisPoints {
     cached_global_config()
@@ -636,13 +633,19 @@ pub fn get_protocol_extra_fee_recipient_random() -> Pubkey {
         })
 }
 
+/// PumpSwap program PDAs are derived under: the installed program-id override, else `AMM_PROGRAM`.
+#[inline]
+fn amm_program_id() -> Pubkey {
+    crate::trading::common::protocol_overrides::program_id(
+        crate::trading::factory::DexType::PumpSwap,
+    )
+}
+
 /// Pool v2 PDA (seeds: ["pool-v2", base_mint]). Required at end of buy/sell/buy_exact_quote_in accounts.
 #[inline]
 pub fn get_pool_v2_pda(base_mint: &Pubkey) -> Option<Pubkey> {
-    let (pda, _) = Pubkey::find_program_address(
-        &[seeds::POOL_V2_SEED, base_mint.as_ref()],
-        &accounts::AMM_PROGRAM,
-    );
+    let (pda, _) =
+        Pubkey::find_program_address(&[seeds::POOL_V2_SEED, base_mint.as_ref()], &amm_program_id());
     Some(pda)
 }
 
@@ -651,7 +654,9 @@ pub fn get_pool_v2_pda(base_mint: &Pubkey) -> Option<Pubkey> {
 pub fn get_pump_pool_authority_pda(mint: &Pubkey) -> Pubkey {
     Pubkey::find_program_address(
         &[seeds::POOL_AUTHORITY_SEED, mint.as_ref()],
-        &accounts::PUMP_PROGRAM_ID,
+        &crate::trading::common::protocol_overrides::program_id(
+            crate::trading::factory::DexType::PumpFun,
+        ),
     )
     .0
 }
@@ -670,7 +675,7 @@ pub fn get_canonical_pool_pda(mint: &Pubkey) -> Pubkey {
             mint.as_ref(),
             WSOL_TOKEN_ACCOUNT.as_ref(),
         ],
-        &accounts::AMM_PROGRAM,
+        &amm_program_id(),
     );
     pda
 }
@@ -681,10 +686,24 @@ pub async fn find_pool(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<Pubkey, a
     Ok(pool_address)
 }
 
+/// `(coin_creator_vault_authority, coin_creator_vault_ata)` of a pool, derived from the pool
+/// account's `coin_creator` (not `creator`, and never taken from an event). The single
+/// derivation every constructor and check goes through. 由池子 `coin_creator` 推导创作者金库。
+pub fn coin_creator_vault_accounts(
+    coin_creator: Pubkey,
+    quote_mint: Pubkey,
+    quote_token_program: Pubkey,
+) -> (Pubkey, Pubkey) {
+    let authority = coin_creator_vault_authority(coin_creator);
+    let ata =
+        get_associated_token_address_with_program_id(&authority, &quote_mint, &quote_token_program);
+    (authority, ata)
+}
+
 pub(crate) fn coin_creator_vault_authority(coin_creator: Pubkey) -> Pubkey {
     let (pump_pool_authority, _) = Pubkey::find_program_address(
         &[b"creator_vault", &coin_creator.to_bytes()],
-        &accounts::AMM_PROGRAM,
+        &amm_program_id(),
     );
     pump_pool_authority
 }
@@ -694,13 +713,7 @@ pub(crate) fn coin_creator_vault_ata(
     quote_mint: Pubkey,
     quote_token_program: Pubkey,
 ) -> Pubkey {
-    let creator_vault_authority = coin_creator_vault_authority(coin_creator);
-    let associated_token_creator_vault_authority = get_associated_token_address_with_program_id(
-        &creator_vault_authority,
-        &quote_mint,
-        &quote_token_program,
-    );
-    associated_token_creator_vault_authority
+    coin_creator_vault_accounts(coin_creator, quote_mint, quote_token_program).1
 }
 
 pub(crate) fn fee_recipient_ata(
@@ -718,14 +731,10 @@ pub(crate) fn fee_recipient_ata(
 }
 
 pub fn get_user_volume_accumulator_pda(user: &Pubkey) -> Option<Pubkey> {
-    crate::common::fast_fn::get_cached_pda(
+    crate::trading::common::protocol_overrides::cached_pda(
+        crate::trading::factory::DexType::PumpSwap,
         crate::common::fast_fn::PdaCacheKey::PumpSwapUserVolume(*user),
-        || {
-            let seeds: &[&[u8]; 2] = &[&seeds::USER_VOLUME_ACCUMULATOR_SEED, user.as_ref()];
-            let program_id: &Pubkey = &accounts::AMM_PROGRAM;
-            let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
-            pda.map(|pubkey| pubkey.0)
-        },
+        &[seeds::USER_VOLUME_ACCUMULATOR_SEED, user.as_ref()],
     )
 }
 
@@ -755,7 +764,7 @@ pub fn get_user_volume_accumulator_quote_ata(
 
 pub fn get_global_volume_accumulator_pda() -> Option<Pubkey> {
     let seeds: &[&[u8]; 1] = &[&seeds::GLOBAL_VOLUME_ACCUMULATOR_SEED];
-    let program_id: &Pubkey = &accounts::AMM_PROGRAM;
+    let program_id: &Pubkey = &amm_program_id();
     let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
     pda.map(|pubkey| pubkey.0)
 }
@@ -768,8 +777,13 @@ pub async fn fetch_pool(
     decode_pool_account(&account).map_err(anyhow::Error::msg)
 }
 
-fn decode_pool_account(account: &solana_sdk::account::Account) -> Result<Pool, String> {
-    if account.owner != accounts::AMM_PROGRAM {
+pub(crate) fn decode_pool_account(account: &solana_sdk::account::Account) -> Result<Pool, String> {
+    if account.owner != accounts::AMM_PROGRAM
+        && Some(account.owner)
+            != crate::trading::common::protocol_overrides::overridden_program_id(
+                crate::trading::factory::DexType::PumpSwap,
+            )
+    {
         return Err("Account is not owned by PumpSwap program".to_string());
     }
     let discriminator = account
@@ -854,17 +868,11 @@ pub async fn find_by_base_mint(
     rpc: &SolanaRpcClient,
     base_mint: &Pubkey,
 ) -> Result<(Pubkey, Pool), anyhow::Error> {
-    // base_mint offset: 8(discriminator) + 1(bump) + 2(index) + 32(creator) = 43
-    let accounts = get_program_accounts_known_sizes(rpc, 43, base_mint).await?;
-    if accounts.is_empty() {
-        return Err(anyhow!("No pool found for mint {}", base_mint));
-    }
-    let mut pools = decode_pool_accounts(accounts);
-    if pools.is_empty() {
-        return Err(anyhow!("No valid pool decoded for mint {}", base_mint));
-    }
-    pools.sort_by(|a, b| b.1.lp_supply.cmp(&a.1.lp_supply));
-    Ok((pools[0].0, pools[0].1.clone()))
+    find_pools(rpc, base_mint)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("No valid pool found for base mint {}", base_mint))
 }
 
 pub async fn find_by_quote_mint(
@@ -884,6 +892,34 @@ pub async fn find_by_quote_mint(
     Ok((pools[0].0, pools[0].1.clone()))
 }
 
+/// All PumpSwap pools whose base mint is `mint`, deepest (largest `lp_supply`) first.
+/// A token migrated to PumpSwap normally has a WSOL pool; a second pool with another quote
+/// (e.g. USDC) only exists if someone created it, so callers should filter on `quote_mint`.
+pub async fn find_pools(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<Vec<(Pubkey, Pool)>, anyhow::Error> {
+    // base_mint offset: 8(discriminator) + 1(bump) + 2(index) + 32(creator) = 43
+    let accounts = get_program_accounts_known_sizes(rpc, 43, mint).await?;
+    let mut pools = decode_pool_accounts(accounts);
+    pools.sort_by(|a, b| b.1.lp_supply.cmp(&a.1.lp_supply));
+    Ok(pools)
+}
+
+/// Deepest pool from [`find_pools`] pairing `mint` with `quote_mint`.
+pub async fn find_pool_with_quote(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    quote_mint: &Pubkey,
+) -> Result<Pubkey, anyhow::Error> {
+    find_pools(rpc, mint)
+        .await?
+        .into_iter()
+        .find(|(_, pool)| pool.quote_mint == *quote_mint)
+        .map(|(address, _)| address)
+        .ok_or_else(|| anyhow!("No PumpSwap pool found for mint {} quoted in {}", mint, quote_mint))
+}
+
 /// 按 mint 查找 PumpSwap 池（本函数仅用于 PumpSwap，其他 DEX 勿用）。
 ///
 /// 查找顺序（与 @pump-fun/pump-swap-sdk 一致）：
@@ -1004,9 +1040,21 @@ fn decode_mint_supply(
 pub async fn get_pool_rpc_snapshot(
     pool: &Pool,
     rpc: &SolanaRpcClient,
+) -> Result<PoolRpcSnapshot, anyhow::Error> {
+    get_pool_rpc_snapshot_at_slot(pool, rpc, None).await
+}
+
+/// [`get_pool_rpc_snapshot`] read as of at least `min_context_slot`; a node still behind after
+/// `min_context_slot_timeout()` fails with `PoolLoadError::MinContextSlotNotReached`.
+pub async fn get_pool_rpc_snapshot_at_slot(
+    pool: &Pool,
+    rpc: &SolanaRpcClient,
+    min_context_slot: Option<u64>,
 ) -> Result<PoolRpcSnapshot, anyhow::Error> {
     let addresses = [pool.pool_base_token_account, pool.pool_quote_token_account, pool.base_mint];
-    let accounts = rpc.get_multiple_accounts(&addresses).await?;
+    let accounts =
+        crate::trading::core::params::fetch_accounts_at_slot(rpc, &addresses, min_context_slot)
+            .await?;
     let base_vault = accounts
         .first()
         .and_then(Option::as_ref)
@@ -1045,7 +1093,8 @@ pub async fn get_token_balances(
 
 #[inline]
 pub fn get_fee_config_pda() -> Option<Pubkey> {
-    let seeds: &[&[u8]; 2] = &[seeds::FEE_CONFIG_SEED, accounts::AMM_PROGRAM.as_ref()];
+    let amm_program = amm_program_id();
+    let seeds: &[&[u8]; 2] = &[seeds::FEE_CONFIG_SEED, amm_program.as_ref()];
     let program_id: &Pubkey = &accounts::FEE_PROGRAM;
     let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
     pda.map(|pubkey| pubkey.0)
@@ -1191,6 +1240,23 @@ mod tests {
         assert_eq!(fees, PumpSwapFeeBasisPoints::new(20, 5, 75));
     }
 
+    #[test]
+    fn pumpswap_compute_fees_falls_back_to_global_fees_without_config_or_supply() {
+        let base_mint = Pubkey::new_unique();
+        let canonical_creator = get_pump_pool_authority_pda(&base_mint);
+        let no_config = compute_fee_basis_points(None, canonical_creator, base_mint, None, 1, 1);
+        assert_eq!(no_config, global_fee_basis_points());
+        let no_supply = compute_fee_basis_points(
+            Some(&fee_config_fixture()),
+            canonical_creator,
+            base_mint,
+            None,
+            1_000_000_000_000_000,
+            4_500_000_000_000,
+        );
+        assert_eq!(no_supply, global_fee_basis_points());
+    }
+
     #[test]
     fn pumpswap_pool_queries_cover_current_serialized_and_padded_sizes() {
         assert_eq!(POOL_DATA_LEN_LEGACY, 252);
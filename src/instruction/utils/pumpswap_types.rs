@@ -82,19 +82,7 @@ pub fn pool_decode(data: &[u8]) -> Option<Pool> {
     None
 }
 
-/// Compute the quote reserves used by PumpSwap pricing.
-///
-/// Returns `None` when the signed sum is non-positive or cannot fit in a `u64`.
-#[inline]
-pub fn effective_quote_reserves(
-    quote_vault_balance: u64,
-    virtual_quote_reserves: i128,
-) -> Option<u64> {
-    i128::from(quote_vault_balance)
-        .checked_add(virtual_quote_reserves)
-        .and_then(|reserves| u64::try_from(reserves).ok())
-        .filter(|reserves| *reserves != 0)
-}
+pub use crate::utils::calc::pumpswap::effective_quote_reserves;
 
 #[cfg(test)]
 mod tests {
@@ -18,10 +18,9 @@ pub mod accounts {
     pub const AUTHORITY: Pubkey = pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
     pub const RAYDIUM_AMM_V4: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
 
-    pub const TRADE_FEE_NUMERATOR: u64 = 25;
-    pub const TRADE_FEE_DENOMINATOR: u64 = 10000;
-    pub const SWAP_FEE_NUMERATOR: u64 = 25;
-    pub const SWAP_FEE_DENOMINATOR: u64 = 10000;
+    pub use crate::utils::calc::fees::raydium_amm_v4::{
+        SWAP_FEE_DENOMINATOR, SWAP_FEE_NUMERATOR, TRADE_FEE_DENOMINATOR, TRADE_FEE_NUMERATOR,
+    };
 
     // META
 
@@ -36,6 +35,14 @@ pub mod accounts {
 pub const SWAP_BASE_IN_DISCRIMINATOR: &[u8] = &[9];
 pub const SWAP_BASE_OUT_DISCRIMINATOR: &[u8] = &[11];
 
+/// `AmmInfo.status` values that allow swaps: Initialized (1), SwapOnly (6), WaitingTrade (7,
+/// swaps open at `pool_open_time`). Disabled (2), WithdrawOnly (3), LiquidityOnly (4),
+/// OrderBookOnly (5) reject swaps.
+#[inline]
+pub fn is_swap_disabled(status: u64) -> bool {
+    !matches!(status, 1 | 6 | 7)
+}
+
 pub async fn fetch_amm_info(rpc: &SolanaRpcClient, amm: Pubkey) -> Result<AmmInfo, anyhow::Error> {
     let amm_info = rpc.get_account_data(&amm).await?;
     let amm_info =
@@ -18,11 +18,10 @@ pub mod accounts {
     use solana_sdk::{pubkey, pubkey::Pubkey};
     pub const AUTHORITY: Pubkey = pubkey!("GpMZbSM2GgvTKHJirzeGfMFoaZ8UR2X7F4v8vHTvxFbL");
     pub const RAYDIUM_CPMM: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
-    pub const FEE_RATE_DENOMINATOR_VALUE: u128 = 1_000_000;
-    pub const TRADE_FEE_RATE: u64 = 2500;
-    pub const CREATOR_FEE_RATE: u64 = 0;
-    pub const PROTOCOL_FEE_RATE: u64 = 120000;
-    pub const FUND_FEE_RATE: u64 = 40000;
+    pub use crate::utils::calc::fees::raydium_cpmm::{
+        CREATOR_FEE_RATE, FEE_RATE_DENOMINATOR_VALUE, FUND_FEE_RATE, PROTOCOL_FEE_RATE,
+        TRADE_FEE_RATE,
+    };
     // META
     pub const AUTHORITY_META: solana_sdk::instruction::AccountMeta =
         solana_sdk::instruction::AccountMeta {
@@ -35,6 +34,14 @@ pub mod accounts {
 pub const SWAP_BASE_IN_DISCRIMINATOR: &[u8] = &[143, 190, 90, 218, 196, 30, 51, 222];
 pub const SWAP_BASE_OUT_DISCRIMINATOR: &[u8] = &[55, 217, 98, 86, 163, 74, 180, 173];
 
+/// `PoolState.status` bit that disables swaps (bit0 deposit, bit1 withdraw, bit2 swap).
+pub const POOL_STATUS_SWAP_DISABLED_BIT: u8 = 1 << 2;
+
+#[inline]
+pub fn is_swap_disabled(status: u8) -> bool {
+    status & POOL_STATUS_SWAP_DISABLED_BIT != 0
+}
+
 pub async fn fetch_pool_state(
     rpc: &SolanaRpcClient,
     pool_address: &Pubkey,
@@ -51,21 +58,27 @@ pub async fn fetch_pool_state(
 pub fn get_pool_pda(amm_config: &Pubkey, mint1: &Pubkey, mint2: &Pubkey) -> Option<Pubkey> {
     let seeds: &[&[u8]; 4] =
         &[seeds::POOL_SEED, amm_config.as_ref(), mint1.as_ref(), mint2.as_ref()];
-    let program_id: &Pubkey = &accounts::RAYDIUM_CPMM;
+    let program_id: &Pubkey = &crate::trading::common::protocol_overrides::program_id(
+        crate::trading::factory::DexType::RaydiumCpmm,
+    );
     let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
     pda.map(|pubkey| pubkey.0)
 }
 
 pub fn get_vault_pda(pool_state: &Pubkey, mint: &Pubkey) -> Option<Pubkey> {
     let seeds: &[&[u8]; 3] = &[seeds::POOL_VAULT_SEED, pool_state.as_ref(), mint.as_ref()];
-    let program_id: &Pubkey = &accounts::RAYDIUM_CPMM;
+    let program_id: &Pubkey = &crate::trading::common::protocol_overrides::program_id(
+        crate::trading::factory::DexType::RaydiumCpmm,
+    );
     let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
     pda.map(|pubkey| pubkey.0)
 }
 
 pub fn get_observation_state_pda(pool_state: &Pubkey) -> Option<Pubkey> {
     let seeds: &[&[u8]; 2] = &[seeds::OBSERVATION_STATE_SEED, pool_state.as_ref()];
-    let program_id: &Pubkey = &accounts::RAYDIUM_CPMM;
+    let program_id: &Pubkey = &crate::trading::common::protocol_overrides::program_id(
+        crate::trading::factory::DexType::RaydiumCpmm,
+    );
     let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
     pda.map(|pubkey| pubkey.0)
 }
@@ -81,9 +94,18 @@ pub async fn get_pool_token_balances(
     token1_mint: &Pubkey,
 ) -> Result<(u64, u64), anyhow::Error> {
     let token0_vault = get_vault_pda(pool_state, token0_mint).unwrap();
-    let token0_balance = rpc.get_token_account_balance(&token0_vault).await?;
     let token1_vault = get_vault_pda(pool_state, token1_mint).unwrap();
-    let token1_balance = rpc.get_token_account_balance(&token1_vault).await?;
+    get_vault_token_balances(rpc, &token0_vault, &token1_vault).await
+}
+
+/// Balances of the pool's token0/token1 vaults as stored in `PoolState`.
+pub async fn get_vault_token_balances(
+    rpc: &SolanaRpcClient,
+    token0_vault: &Pubkey,
+    token1_vault: &Pubkey,
+) -> Result<(u64, u64), anyhow::Error> {
+    let token0_balance = rpc.get_token_account_balance(token0_vault).await?;
+    let token1_balance = rpc.get_token_account_balance(token1_vault).await?;
 
     // Parse balance string to u64
     let token0_amount = token0_balance
@@ -132,14 +154,14 @@ pub fn get_vault_account(
     pool_state: &Pubkey,
     token_mint: &Pubkey,
     protocol_params: &RaydiumCpmmParams,
-) -> Pubkey {
+) -> anyhow::Result<Pubkey> {
     if protocol_params.base_mint == *token_mint && protocol_params.base_vault != Pubkey::default() {
-        protocol_params.base_vault
+        Ok(protocol_params.base_vault)
     } else if protocol_params.quote_mint == *token_mint
         && protocol_params.quote_vault != Pubkey::default()
     {
-        protocol_params.quote_vault
+        Ok(protocol_params.quote_vault)
     } else {
-        get_vault_pda(pool_state, token_mint).unwrap()
+        super::derived_pda(get_vault_pda(pool_state, token_mint), "Raydium CPMM vault")
     }
 }
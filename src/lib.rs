@@ -4,14 +4,20 @@ pub mod constants;
 pub mod instruction;
 pub mod perf;
 pub mod swqos;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_fixtures;
 pub mod trading;
 pub mod utils;
 
 pub use crate::common::nonce_cache::{fetch_nonce_info, DurableNonceInfo};
+pub use crate::common::positions::Position;
 // Re-export transport selectors used by SWQoS configs (including Glaive).
 pub use crate::swqos::{AstralaneTransport, SwqosTransport};
+pub use crate::trading::core::params::AtaCreationMode;
+pub use crate::trading::core::params::PoolLoadError;
 pub use client::{
-    find_pool_by_mint, recommended_sender_thread_core_indices, AccountPolicy, BuyAmount,
-    SellAmount, SimpleBuyParams, SimpleSellParams, SolanaTrade, TradeBuyParams, TradeSellParams,
+    find_pool_by_mint, find_pool_by_mint_and_quote, recommended_sender_thread_core_indices,
+    AccountPolicy, BuyAmount, SellAmount, SimpleBuyParams, SimpleSellParams, SolanaTrade,
+    TradeBuyParams, TradeBuyParamsBuilder, TradeRequest, TradeSellParams, TradeSellParamsBuilder,
     TradeTokenType, TradingClient, TradingInfrastructure,
 };
@@ -182,18 +182,13 @@ impl AstralaneClient {
     ) -> Result<()> {
         let start_time = Instant::now();
         let signature = transaction.get_signature();
-        let body_bytes = bincode_serialize(transaction)
-            .map_err(|e| anyhow::anyhow!("Astralane binary serialize failed: {}", e))?;
+        let body_bytes = submit_body(transaction)?;
 
         match &self.backend {
             AstralaneBackend::Http { endpoint, auth_token, mev_http, http_client, .. } => {
-                let mut req = http_client
+                let response = http_client
                     .post(endpoint)
-                    .query(&[("api-key", auth_token.as_str()), ("method", "sendTransaction")]);
-                if *mev_http {
-                    req = req.query(&[("mev-protect", "true")]);
-                }
-                let response = req
+                    .query(&submit_query(auth_token, *mev_http))
                     .header("Content-Type", "application/octet-stream")
                     .body(body_bytes)
                     .send()
@@ -273,6 +268,21 @@ impl AstralaneClient {
     }
 }
 
+/// Binary `sendTransaction` body: the bincode-serialized transaction.
+fn submit_body(transaction: &VersionedTransaction) -> Result<Vec<u8>> {
+    bincode_serialize(transaction)
+        .map_err(|e| anyhow::anyhow!("Astralane binary serialize failed: {}", e))
+}
+
+/// Binary `sendTransaction` query; `mev-protect=true` only when MEV protection is on.
+fn submit_query(auth_token: &str, mev_protect: bool) -> Vec<(&'static str, &str)> {
+    let mut query = vec![("api-key", auth_token), ("method", "sendTransaction")];
+    if mev_protect {
+        query.push(("mev-protect", "true"));
+    }
+    query
+}
+
 impl Drop for AstralaneClient {
     fn drop(&mut self) {
         match &self.backend {
@@ -291,3 +301,21 @@ impl Drop for AstralaneClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_body_matches_binary_send_api_schema() {
+        let transaction = crate::test_fixtures::signed_transaction();
+        let body = submit_body(&transaction).unwrap();
+        assert_eq!(bincode::deserialize::<VersionedTransaction>(&body).unwrap(), transaction);
+
+        assert_eq!(submit_query("key", false), [("api-key", "key"), ("method", "sendTransaction")]);
+        assert_eq!(
+            submit_query("key", true),
+            [("api-key", "key"), ("method", "sendTransaction"), ("mev-protect", "true")]
+        );
+    }
+}
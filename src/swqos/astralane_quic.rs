@@ -209,13 +209,7 @@ impl AstralaneQuicClient {
     /// Send a single bincode-serialized `VersionedTransaction`.
     /// Fire-and-forget; automatically reconnects if the connection is dead.
     pub async fn send_transaction(&self, transaction_bytes: &[u8]) -> Result<()> {
-        if transaction_bytes.len() > MAX_TRANSACTION_SIZE {
-            anyhow::bail!(
-                "Transaction too large: {} bytes (max {})",
-                transaction_bytes.len(),
-                MAX_TRANSACTION_SIZE
-            );
-        }
+        validate_transaction_size(transaction_bytes)?;
 
         let conn = {
             let mut guard = self.connection.lock().await;
@@ -311,6 +305,17 @@ impl Drop for AstralaneQuicClient {
 }
 
 /// Skip server certificate verification (Astralane server may use self-signed cert).
+fn validate_transaction_size(transaction_bytes: &[u8]) -> Result<()> {
+    if transaction_bytes.len() > MAX_TRANSACTION_SIZE {
+        anyhow::bail!(
+            "Transaction too large: {} bytes (max {})",
+            transaction_bytes.len(),
+            MAX_TRANSACTION_SIZE
+        );
+    }
+    Ok(())
+}
+
 #[derive(Debug)]
 struct SkipServerVerification;
 
@@ -358,3 +363,21 @@ impl rustls::client::danger::ServerCertVerifier for SkipServerVerification {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transaction_frame_is_the_bincode_transaction_within_packet_limit() {
+        let transaction = crate::test_fixtures::signed_transaction();
+        let payload = bincode::serialize(&transaction).unwrap();
+        assert!(validate_transaction_size(&payload).is_ok());
+        assert_eq!(
+            bincode::deserialize::<solana_sdk::transaction::VersionedTransaction>(&payload)
+                .unwrap(),
+            transaction
+        );
+        assert!(validate_transaction_size(&vec![0; MAX_TRANSACTION_SIZE + 1]).is_err());
+    }
+}
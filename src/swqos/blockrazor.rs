@@ -403,13 +403,8 @@ impl BlockRazorClient {
                 let signature = client
                     .send_transaction(
                         content,
-                        // mev_protection=true: sandwichMitigation mode skips blacklisted Leader slots (MEV protection).
                         // revert_protection is unrelated to MEV; keep false.
-                        if *mev_protection {
-                            "sandwichMitigation".to_string()
-                        } else {
-                            "fast".to_string()
-                        },
+                        submit_mode(*mev_protection).to_string(),
                         None,
                         false,
                     )
@@ -460,16 +455,9 @@ impl BlockRazorClient {
                 let (content, _signature) =
                     serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64)?;
 
-                let query_params: Vec<(&str, &str)> = vec![
-                    ("auth", auth_token.as_str()),
-                    // mev_protection=true: sandwichMitigation mode skips blacklisted Leader slots (MEV protection).
-                    // revertProtection is unrelated to MEV; not set.
-                    ("mode", if *mev_protection { "sandwichMitigation" } else { "fast" }),
-                ];
-
                 let response = http_client
                     .post(endpoint)
-                    .query(&query_params)
+                    .query(&submit_query(auth_token, *mev_protection))
                     .header("Content-Type", "text/plain")
                     .body(content)
                     .send()
@@ -538,6 +526,21 @@ impl BlockRazorClient {
     }
 }
 
+/// Send mode: `sandwichMitigation` skips blacklisted leader slots (MEV protection).
+fn submit_mode(mev_protection: bool) -> &'static str {
+    if mev_protection {
+        "sandwichMitigation"
+    } else {
+        "fast"
+    }
+}
+
+/// HTTP `sendTransaction` query; `revertProtection` is unrelated to MEV and not set. The body
+/// is the base64 transaction as `text/plain`.
+fn submit_query(auth_token: &str, mev_protection: bool) -> [(&'static str, &str); 2] {
+    [("auth", auth_token), ("mode", submit_mode(mev_protection))]
+}
+
 impl Drop for BlockRazorClient {
     fn drop(&mut self) {
         match &self.backend {
@@ -557,3 +560,22 @@ impl Drop for BlockRazorClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    #[test]
+    fn request_body_matches_send_transaction_schema() {
+        let transaction = crate::test_fixtures::signed_transaction();
+        let (content, signature) =
+            serialize_transaction_and_encode(&transaction, UiTransactionEncoding::Base64).unwrap();
+        assert_eq!(signature, transaction.signatures[0]);
+        let bytes = base64::engine::general_purpose::STANDARD.decode(content).unwrap();
+        assert_eq!(bincode::deserialize::<VersionedTransaction>(&bytes).unwrap(), transaction);
+
+        assert_eq!(submit_query("key", false), [("auth", "key"), ("mode", "fast")]);
+        assert_eq!(submit_query("key", true), [("auth", "key"), ("mode", "sandwichMitigation")]);
+    }
+}
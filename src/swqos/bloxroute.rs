@@ -1,6 +1,7 @@
 use crate::swqos::common::default_http_client_builder;
 use crate::swqos::common::poll_transaction_confirmation;
 use crate::swqos::common::serialize_transaction_and_encode;
+use crate::swqos::common::{parse_submit_response, SubmitResponse};
 use crate::swqos::serialization;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -78,11 +79,7 @@ impl BloxrouteClient {
         let (content, signature) =
             serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64)?;
 
-        // Single format! for body to avoid json! + to_string() double allocation
-        let body = format!(
-            r#"{{"transaction":{{"content":"{}"}},"frontRunningProtection":false,"useStakedRPCs":true}}"#,
-            content
-        );
+        let body = submit_body(&content);
 
         let endpoint = format!("{}/api/v2/submit", self.endpoint);
         let response_text = self
@@ -97,15 +94,18 @@ impl BloxrouteClient {
             .await?;
 
         // Parse with from_str to avoid extra wait from .json().await
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if crate::common::sdk_log::sdk_log_enabled() {
-                if response_json.get("result").is_some() {
+        match parse_submit_response("bloxroute", &response_text, SUCCESS_KEYS) {
+            Ok(SubmitResponse::Accepted) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
                     crate::common::sdk_log::log_swqos_submitted(
                         "bloxroute",
                         trade_type,
                         start_time.elapsed(),
                     );
-                } else if let Some(_error) = response_json.get("error") {
+                }
+            }
+            Ok(SubmitResponse::Rejected(_error)) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
                     eprintln!(
                         " [bloxroute] {} submission failed after {:?}: {:?}",
                         trade_type,
@@ -114,13 +114,17 @@ impl BloxrouteClient {
                     );
                 }
             }
-        } else if crate::common::sdk_log::sdk_log_enabled() {
-            crate::common::sdk_log::log_swqos_submission_failed(
-                "bloxroute",
-                trade_type,
-                start_time.elapsed(),
-                response_text,
-            );
+            Err(e) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
+                    crate::common::sdk_log::log_swqos_submission_failed(
+                        "bloxroute",
+                        trade_type,
+                        start_time.elapsed(),
+                        &e,
+                    );
+                }
+                return Err(e.into());
+            }
         }
 
         let start_time: Instant = Instant::now();
@@ -166,12 +170,7 @@ impl BloxrouteClient {
             transactions.as_slice(),
             UiTransactionEncoding::Base64,
         )?;
-        let entries: String = contents
-            .iter()
-            .map(|c| format!(r#"{{"transaction":{{"content":"{}"}}}}"#, c))
-            .collect::<Vec<_>>()
-            .join(",");
-        let body = format!(r#"{{"entries":[{}]}}"#, entries);
+        let body = batch_body(&contents);
 
         let endpoint = format!("{}/api/v2/submit-batch", self.endpoint);
         let response_text = self
@@ -185,11 +184,13 @@ impl BloxrouteClient {
             .text()
             .await?;
 
+        let response = parse_submit_response("bloxroute", &response_text, SUCCESS_KEYS)?;
         if crate::common::sdk_log::sdk_log_enabled() {
-            if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-                if response_json.get("result").is_some() {
+            match response {
+                SubmitResponse::Accepted => {
                     println!(" bloxroute {} submitted: {:?}", trade_type, start_time.elapsed());
-                } else if let Some(_error) = response_json.get("error") {
+                }
+                SubmitResponse::Rejected(_error) => {
                     eprintln!(
                         " bloxroute {} submission failed after {:?}: {:?}",
                         trade_type,
@@ -203,3 +204,54 @@ impl BloxrouteClient {
         Ok(())
     }
 }
+
+/// Accepted submits answer with `result` (JSON-RPC style) or `signature` / `transactions`.
+const SUCCESS_KEYS: &[&str] = &["result", "signature", "transactions"];
+
+/// `/api/v2/submit` body. Single format! to avoid json! + to_string() double allocation.
+fn submit_body(content: &str) -> String {
+    format!(
+        r#"{{"transaction":{{"content":"{}"}},"frontRunningProtection":false,"useStakedRPCs":true}}"#,
+        content
+    )
+}
+
+/// `/api/v2/submit-batch` body.
+fn batch_body(contents: &[String]) -> String {
+    let entries: String = contents
+        .iter()
+        .map(|c| format!(r#"{{"transaction":{{"content":"{}"}}}}"#, c))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(r#"{{"entries":[{}]}}"#, entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn request_bodies_match_submit_api_schema() {
+        let body: Value = serde_json::from_str(&submit_body("dHg=")).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "transaction": { "content": "dHg=" },
+                "frontRunningProtection": false,
+                "useStakedRPCs": true
+            })
+        );
+        let batch: Value =
+            serde_json::from_str(&batch_body(&["YQ==".to_string(), "Yg==".to_string()])).unwrap();
+        assert_eq!(
+            batch,
+            json!({
+                "entries": [
+                    { "transaction": { "content": "YQ==" } },
+                    { "transaction": { "content": "Yg==" } }
+                ]
+            })
+        );
+    }
+}
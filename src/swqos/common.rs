@@ -1,5 +1,6 @@
 use crate::common::types::SolanaRpcClient;
 use crate::swqos::serialization;
+use crate::swqos::SwqosType;
 use anyhow::Result;
 use base64::engine::general_purpose::{self, STANDARD};
 use base64::Engine;
@@ -9,10 +10,12 @@ use serde_json;
 use serde_json::json;
 use solana_client::rpc_client::SerializableTransaction;
 use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
 use solana_sdk::transaction::VersionedTransaction;
 use solana_sdk::transaction::{Transaction, TransactionError};
 use solana_transaction_status::{TransactionConfirmationStatus, UiTransactionEncoding};
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
@@ -62,6 +65,207 @@ impl std::fmt::Display for TradeError {
 
 impl std::error::Error for TradeError {}
 
+impl TradeError {
+    /// SDK-side error codes start here; on-chain program codes (e.g. Anchor 6000+) stay below.
+    /// SDK 侧错误码起始值，链上程序错误码均小于该值。
+    pub const SDK_ERROR_CODE_BASE: u32 = 100_000;
+    /// Protocol params rejected before building instructions. 构建指令前协议参数校验失败。
+    pub const INVALID_PROTOCOL_PARAMS: u32 = 100_001;
+    /// A spawned trade task panicked (see `TradingClient::spawn_trade`). 交易任务 panic。
+    pub const PANICKED: u32 = 100_002;
+    /// Event reserves diverge from on-chain reserves (see `TradingClient::with_reserve_check`).
+    /// 事件储备量与链上不一致。
+    pub const RESERVE_MISMATCH: u32 = 100_003;
+    /// Pool status flags disable swaps; the trade would revert on-chain. 池子交易已暂停。
+    pub const POOL_PAUSED: u32 = 100_004;
+    /// Pool quote reserve below `TradeBuyParams.min_quote_liquidity`. 池子流动性低于下限。
+    pub const INSUFFICIENT_LIQUIDITY: u32 = 100_005;
+    /// A PDA/ATA needed by the instruction builder could not be derived. PDA 推导失败。
+    pub const PDA_DERIVATION_FAILED: u32 = 100_006;
+    /// A SWQOS endpoint did not answer the submit within its timeout. 通道提交超时。
+    pub const SUBMIT_TIMEOUT: u32 = 100_007;
+    /// The trade would create more token accounts than `max_ata_creations`. ATA 创建数超限。
+    pub const TOO_MANY_ATA_CREATIONS: u32 = 100_008;
+    /// A SWQOS provider answered in a format the SDK does not recognize (API schema changed).
+    /// Never counted as landed, so the other routes in the race keep going. 通道响应格式不符。
+    pub const SWQOS_PROTOCOL_ERROR: u32 = 100_009;
+    /// The address passed as a pool is not owned by the protocol's program (e.g. a token mint).
+    /// 传入的池子地址不属于该协议程序。
+    pub const NOT_A_POOL: u32 = 100_010;
+    /// Another in-flight trade in this process is already using the same durable nonce value.
+    /// 同一 nonce 值正被另一笔进行中的交易使用。
+    pub const NONCE_CONTENTION: u32 = 100_011;
+    /// A SWQOS provider's configured request rate was exceeded; the submit was not sent to it.
+    /// 超出通道限速，未发送。
+    pub const RATE_LIMITED: u32 = 100_012;
+    /// The `on_before_send` hook rejected the built transaction; it was not submitted.
+    /// 发送前钩子否决，未发送。
+    pub const SEND_VETOED: u32 = 100_013;
+    /// The token left the requested protocol (e.g. a PumpFun curve completed and migrated to
+    /// PumpSwap) before the trade was sent. 代币已迁移到其他协议。
+    pub const PROTOCOL_MIGRATED: u32 = 100_014;
+    /// The buy's quoted tokens out are below `TradeBuyParams.min_tokens_out`. 买入数量低于下限。
+    pub const BELOW_MIN_TOKENS_OUT: u32 = 100_015;
+    /// Seed optimization is on but the token sits in the standard ATA, not the seed-derived
+    /// account the sell would use. 代币在标准 ATA 而非 seed 账户中。
+    pub const SEED_ATA_MISMATCH: u32 = 100_016;
+    /// `input_amount` was left `None`: the caller forgot to set the amount. 未设置交易数量。
+    pub const AMOUNT_NOT_SET: u32 = 100_017;
+    /// `input_amount` was explicitly `Some(0)`. 交易数量为 0。
+    pub const ZERO_AMOUNT: u32 = 100_018;
+    /// `fixed_output_token_amount` was explicitly `Some(0)`. 固定输出数量为 0。
+    pub const ZERO_OUTPUT_AMOUNT: u32 = 100_019;
+
+    pub fn new(code: u32, message: impl Into<String>) -> Self {
+        TradeError { code, message: message.into(), instruction: None }
+    }
+
+    pub fn invalid_protocol_params(message: impl Into<String>) -> Self {
+        Self::new(Self::INVALID_PROTOCOL_PARAMS, message)
+    }
+
+    pub fn panicked(message: impl Into<String>) -> Self {
+        Self::new(Self::PANICKED, message)
+    }
+
+    pub fn reserve_mismatch(event: (u64, u64), onchain: (u64, u64), tolerance_bps: u64) -> Self {
+        Self::new(
+            Self::RESERVE_MISMATCH,
+            format!(
+                "Reserve mismatch: event {:?} vs on-chain {:?} (tolerance {} bps)",
+                event, onchain, tolerance_bps
+            ),
+        )
+    }
+
+    pub fn pool_paused(protocol: &str, pool: &Pubkey, status: u64) -> Self {
+        Self::new(
+            Self::POOL_PAUSED,
+            format!("{} pool {} has trading disabled (status {})", protocol, pool, status),
+        )
+    }
+
+    pub fn insufficient_liquidity(quote_reserve: u64, min_quote_liquidity: u64) -> Self {
+        Self::new(
+            Self::INSUFFICIENT_LIQUIDITY,
+            format!(
+                "Insufficient liquidity: quote reserve {} < min_quote_liquidity {}",
+                quote_reserve, min_quote_liquidity
+            ),
+        )
+    }
+
+    pub fn pda_derivation_failed(message: impl Into<String>) -> Self {
+        Self::new(Self::PDA_DERIVATION_FAILED, message)
+    }
+
+    pub fn submit_timeout(swqos_type: SwqosType, timeout: std::time::Duration) -> Self {
+        Self::new(
+            Self::SUBMIT_TIMEOUT,
+            format!("{} submit timed out after {} ms", swqos_type.as_str(), timeout.as_millis()),
+        )
+    }
+
+    pub fn too_many_ata_creations(planned: usize, max: usize) -> Self {
+        Self::new(
+            Self::TOO_MANY_ATA_CREATIONS,
+            format!("Trade would create {} token accounts, max_ata_creations is {}", planned, max),
+        )
+    }
+
+    pub fn swqos_protocol_error(provider: &str, detail: impl std::fmt::Display) -> Self {
+        Self::new(Self::SWQOS_PROTOCOL_ERROR, format!("{} protocol error: {}", provider, detail))
+    }
+
+    pub fn not_a_pool(
+        address: &solana_sdk::pubkey::Pubkey,
+        expected_program: &solana_sdk::pubkey::Pubkey,
+        actual_owner: &solana_sdk::pubkey::Pubkey,
+    ) -> Self {
+        Self::new(
+            Self::NOT_A_POOL,
+            format!(
+                "{} is not a pool: owned by {}, expected program {}",
+                address, actual_owner, expected_program
+            ),
+        )
+    }
+
+    pub fn nonce_contention(
+        nonce_account: &solana_sdk::pubkey::Pubkey,
+        nonce: &solana_hash::Hash,
+    ) -> Self {
+        Self::new(
+            Self::NONCE_CONTENTION,
+            format!(
+                "Durable nonce {} of account {} is already used by an in-flight trade; \
+                 nonce accounts must not be shared concurrently",
+                nonce, nonce_account
+            ),
+        )
+    }
+
+    pub fn rate_limited(swqos_type: SwqosType, rate: u32) -> Self {
+        Self::new(
+            Self::RATE_LIMITED,
+            format!("{} rate limit of {} req/s exceeded", swqos_type.as_str(), rate),
+        )
+    }
+
+    /// Wraps the hook's rejection; keeps its code in the message so it is never read as an
+    /// on-chain failure.
+    pub fn send_vetoed(reason: &TradeError) -> Self {
+        Self::new(
+            Self::SEND_VETOED,
+            format!("send vetoed by on_before_send: {} (code {})", reason.message, reason.code),
+        )
+    }
+
+    pub fn protocol_migrated(mint: &Pubkey, from: &str, to: &str) -> Self {
+        Self::new(
+            Self::PROTOCOL_MIGRATED,
+            format!("{} is no longer tradeable on {}; it migrated to {}", mint, from, to),
+        )
+    }
+
+    pub fn below_min_tokens_out(tokens_out: u64, min_tokens_out: u64) -> Self {
+        Self::new(
+            Self::BELOW_MIN_TOKENS_OUT,
+            format!("Quoted tokens out {} < min_tokens_out {}", tokens_out, min_tokens_out),
+        )
+    }
+
+    pub fn seed_ata_mismatch(mint: &Pubkey, seed_account: &Pubkey, standard_ata: &Pubkey) -> Self {
+        Self::new(
+            Self::SEED_ATA_MISMATCH,
+            format!(
+                "seed token account {} for {} does not exist but the standard ATA {} does; the \
+                 tokens were bought without seed optimization, sell with \
+                 open_seed_optimize_override = Some(false)",
+                seed_account, mint, standard_ata
+            ),
+        )
+    }
+
+    pub fn amount_not_set() -> Self {
+        Self::new(Self::AMOUNT_NOT_SET, "input_amount is not set")
+    }
+
+    pub fn zero_amount() -> Self {
+        Self::new(Self::ZERO_AMOUNT, "input_amount must be greater than zero")
+    }
+
+    pub fn zero_output_amount() -> Self {
+        Self::new(Self::ZERO_OUTPUT_AMOUNT, "fixed output amount must be greater than zero")
+    }
+
+    /// True for SDK-side codes (validation, panics) rather than on-chain program errors.
+    #[inline]
+    pub fn is_sdk_error(&self) -> bool {
+        self.code >= Self::SDK_ERROR_CODE_BASE
+    }
+}
+
 impl From<anyhow::Error> for TradeError {
     fn from(e: anyhow::Error) -> Self {
         if let Some(te) = e.downcast_ref::<TradeError>() {
@@ -71,6 +275,42 @@ impl From<anyhow::Error> for TradeError {
     }
 }
 
+/// A submit response that matched the provider's schema. 符合通道协议的提交响应。
+#[derive(Debug)]
+pub(crate) enum SubmitResponse {
+    /// One of the provider's success keys (usually `result`) is present.
+    Accepted,
+    /// The provider rejected the transaction with an `error` value.
+    Rejected(serde_json::Value),
+}
+
+/// Longest response excerpt kept in a protocol error message.
+const PROTOCOL_ERROR_BODY_LIMIT: usize = 256;
+
+/// Check a JSON submit response against the provider's schema. A body that is not JSON, or that
+/// carries neither one of `success_keys` nor `error`, becomes
+/// [`TradeError::SWQOS_PROTOCOL_ERROR`] instead of being silently ignored.
+pub(crate) fn parse_submit_response(
+    provider: &str,
+    body: &str,
+    success_keys: &[&str],
+) -> Result<SubmitResponse, TradeError> {
+    let excerpt = || body.chars().take(PROTOCOL_ERROR_BODY_LIMIT).collect::<String>();
+    let json = serde_json::from_str::<serde_json::Value>(body).map_err(|_| {
+        TradeError::swqos_protocol_error(provider, format!("response is not JSON: {}", excerpt()))
+    })?;
+    if success_keys.iter().any(|key| json.get(key).is_some()) {
+        return Ok(SubmitResponse::Accepted);
+    }
+    match json.get("error") {
+        Some(error) => Ok(SubmitResponse::Rejected(error.clone())),
+        None => Err(TradeError::swqos_protocol_error(
+            provider,
+            format!("expected one of {:?} or \"error\" in response: {}", success_keys, excerpt()),
+        )),
+    }
+}
+
 // High-performance serialization
 
 pub trait FormatBase64VersionedTransaction {
@@ -245,6 +485,78 @@ pub async fn poll_any_transaction_confirmation(
     }
 }
 
+/// Most signatures `getSignatureStatuses` accepts per call.
+pub const MAX_SIGNATURE_STATUSES_PER_CALL: usize = 256;
+/// Poll interval of [`confirm_signatures`].
+const CONFIRM_BATCH_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Where one signature ended up in [`confirm_signatures`]. 批量确认中单个签名的结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfirmStatus {
+    /// Executed successfully and reached `confirmed` (or `finalized`).
+    Confirmed { slot: u64 },
+    /// Reached `confirmed` but the transaction failed.
+    Failed { slot: u64, error: TransactionError },
+    /// Not confirmed before the timeout: dropped, expired, or still in flight.
+    TimedOut,
+}
+
+/// Poll many independent signatures until each is confirmed, failed or `timeout` elapses,
+/// with one `getSignatureStatuses` call per [`MAX_SIGNATURE_STATUSES_PER_CALL`] pending
+/// signatures per round. A failed RPC round is retried on the next tick. Every input signature
+/// gets an entry.
+pub async fn confirm_signatures(
+    rpc: &SolanaRpcClient,
+    signatures: &[Signature],
+    timeout: Duration,
+) -> HashMap<Signature, ConfirmStatus> {
+    let deadline = Instant::now() + timeout;
+    let mut results = HashMap::with_capacity(signatures.len());
+    let mut seen = std::collections::HashSet::with_capacity(signatures.len());
+    let mut pending: Vec<Signature> =
+        signatures.iter().copied().filter(|s| seen.insert(*s)).collect();
+
+    loop {
+        for chunk in pending.chunks(MAX_SIGNATURE_STATUSES_PER_CALL) {
+            let Ok(response) = rpc.get_signature_statuses(chunk).await else {
+                continue;
+            };
+            for (signature, status) in chunk.iter().zip(response.value) {
+                let Some(status) = status else { continue };
+                if !matches!(
+                    status.confirmation_status,
+                    Some(TransactionConfirmationStatus::Confirmed)
+                        | Some(TransactionConfirmationStatus::Finalized)
+                ) {
+                    continue;
+                }
+                let resolved = match status.err {
+                    None => ConfirmStatus::Confirmed { slot: status.slot },
+                    // Same JSON shape whichever error type the RPC crate exposes.
+                    Some(err) => match serde_json::to_value(&err)
+                        .and_then(serde_json::from_value::<TransactionError>)
+                    {
+                        Ok(error) => ConfirmStatus::Failed { slot: status.slot, error },
+                        Err(_) => continue,
+                    },
+                };
+                results.insert(*signature, resolved);
+            }
+        }
+        pending.retain(|signature| !results.contains_key(signature));
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if pending.is_empty() || remaining.is_zero() {
+            break;
+        }
+        sleep(CONFIRM_BATCH_INTERVAL.min(remaining)).await;
+    }
+
+    for signature in pending {
+        results.insert(signature, ConfirmStatus::TimedOut);
+    }
+    results
+}
+
 pub async fn send_nb_transaction(
     client: Client,
     endpoint: &str,
@@ -327,3 +639,77 @@ pub async fn serialize_smart_transaction_and_encode(
     };
     Ok((serialized, *signature))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::core::async_executor::is_landed_error;
+
+    #[tokio::test]
+    async fn confirm_signatures_resolves_each_signature_in_one_batched_poll() {
+        use solana_rpc_client_api::request::RpcRequest;
+        let status = |err: serde_json::Value| {
+            let result = if err.is_null() { json!({ "Ok": null }) } else { json!({ "Err": err }) };
+            json!({
+                "slot": 7, "confirmations": null, "status": result, "err": err,
+                "confirmationStatus": "confirmed"
+            })
+        };
+        let statuses =
+            |value: serde_json::Value| json!({ "context": { "slot": 7 }, "value": value });
+        let failed = json!({ "InstructionError": [2, { "Custom": 6004 }] });
+        let rpc = crate::common::MockSolanaRpcClient::new()
+            .with_response(
+                RpcRequest::GetSignatureStatuses,
+                statuses(json!([status(json!(null)), status(failed), null])),
+            )
+            // the last signature never lands
+            .with_response(RpcRequest::GetSignatureStatuses, statuses(json!([null])))
+            .with_response(RpcRequest::GetSignatureStatuses, statuses(json!([null])))
+            .build();
+        let sigs = [Signature::new_unique(), Signature::new_unique(), Signature::new_unique()];
+
+        let results = confirm_signatures(&rpc, &sigs, Duration::from_millis(50)).await;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[&sigs[0]], ConfirmStatus::Confirmed { slot: 7 });
+        assert_eq!(
+            results[&sigs[1]],
+            ConfirmStatus::Failed {
+                slot: 7,
+                error: TransactionError::InstructionError(
+                    2,
+                    solana_sdk::instruction::InstructionError::Custom(6004)
+                ),
+            }
+        );
+        assert_eq!(results[&sigs[2]], ConfirmStatus::TimedOut);
+    }
+
+    #[test]
+    fn submit_response_schema_drift_is_a_protocol_error() {
+        assert!(matches!(
+            parse_submit_response(
+                "jito",
+                r#"{"jsonrpc":"2.0","result":"sig","id":1}"#,
+                &["result"]
+            ),
+            Ok(SubmitResponse::Accepted)
+        ));
+        assert!(matches!(
+            parse_submit_response(
+                "jito",
+                r#"{"error":{"code":-32602,"message":"bad tip"}}"#,
+                &["result"]
+            ),
+            Ok(SubmitResponse::Rejected(_))
+        ));
+
+        for body in ["<html>502 Bad Gateway</html>", r#"{"status":"ok","signature":"sig"}"#] {
+            let err = parse_submit_response("jito", body, &["result"]).unwrap_err();
+            assert_eq!(err.code, TradeError::SWQOS_PROTOCOL_ERROR);
+            assert!(err.message.starts_with("jito protocol error"));
+            // A broken provider must not stop the race: it is never reported as landed.
+            assert!(!is_landed_error(&anyhow::Error::new(err)));
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
+    default_http_client_builder, parse_submit_response, poll_transaction_confirmation,
+    serialize_transaction_and_encode, SubmitResponse,
 };
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -73,10 +74,7 @@ impl FlashBlockClient {
         let (content, signature) =
             serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64)?;
 
-        // FlashBlock API format
-        let request_body = serde_json::to_string(&json!({
-            "transactions": [content]
-        }))?;
+        let request_body = submit_body(&content).to_string();
 
         let url = format!("{}/api/v2/submit-batch", self.endpoint);
 
@@ -95,14 +93,15 @@ impl FlashBlockClient {
             .await?;
 
         // Parse response
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if response_json.get("success").is_some() || response_json.get("result").is_some() {
+        match parse_submit_response("FlashBlock", &response_text, &["success", "result"]) {
+            Ok(SubmitResponse::Accepted) => {
                 crate::common::sdk_log::log_swqos_submitted(
                     "FlashBlock",
                     trade_type,
                     start_time.elapsed(),
                 );
-            } else if let Some(_error) = response_json.get("error") {
+            }
+            Ok(SubmitResponse::Rejected(_error)) => {
                 eprintln!(
                     " [FlashBlock] {} submission failed after {:?}: {:?}",
                     trade_type,
@@ -110,13 +109,15 @@ impl FlashBlockClient {
                     _error
                 );
             }
-        } else {
-            crate::common::sdk_log::log_swqos_submission_failed(
-                "FlashBlock",
-                trade_type,
-                start_time.elapsed(),
-                response_text,
-            );
+            Err(e) => {
+                crate::common::sdk_log::log_swqos_submission_failed(
+                    "FlashBlock",
+                    trade_type,
+                    start_time.elapsed(),
+                    &e,
+                );
+                return Err(e.into());
+            }
         }
 
         let start_time: Instant = Instant::now();
@@ -160,3 +161,25 @@ impl FlashBlockClient {
         Ok(())
     }
 }
+
+/// `/api/v2/submit-batch` body.
+fn submit_body(content: &str) -> serde_json::Value {
+    json!({
+        "transactions": [content]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_body_matches_submit_batch_schema() {
+        assert_eq!(
+            submit_body("dHg="),
+            json!({
+                "transactions": ["dHg="]
+            })
+        );
+    }
+}
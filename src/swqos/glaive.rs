@@ -382,6 +382,14 @@ mod tests {
         assert_eq!(health.as_str(), "https://custom.example/health");
     }
 
+    #[test]
+    fn binary_request_body_is_the_bincode_transaction() {
+        let transaction = crate::test_fixtures::signed_transaction();
+        let (body, signature) = serialize_transaction_bincode_sync(&transaction).unwrap();
+        assert_eq!(signature, transaction.signatures[0]);
+        assert_eq!(bincode::deserialize::<VersionedTransaction>(&body).unwrap(), transaction);
+    }
+
     #[test]
     fn binary_response_requires_matching_signature() {
         let signature = Signature::new_unique();
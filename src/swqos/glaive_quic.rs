@@ -238,4 +238,12 @@ mod tests {
         assert!(validate_transaction_size(&vec![0; MAX_TRANSACTION_SIZE]).is_ok());
         assert!(validate_transaction_size(&vec![0; MAX_TRANSACTION_SIZE + 1]).is_err());
     }
+
+    #[test]
+    fn signed_transaction_fits_one_transaction_stream() {
+        let transaction = crate::test_fixtures::signed_transaction();
+        let (payload, _) =
+            crate::swqos::serialization::serialize_transaction_bincode_sync(&transaction).unwrap();
+        assert!(validate_transaction_size(&payload).is_ok());
+    }
 }
@@ -8,7 +8,8 @@
 //! Optional query: api-key (custom TPS only), swqos_only (SWQOS-only routing, lower min tip).
 
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
+    default_http_client_builder, parse_submit_response, poll_transaction_confirmation,
+    serialize_transaction_and_encode, SubmitResponse,
 };
 use anyhow::Result;
 use rand::seq::IndexedRandom;
@@ -78,19 +79,7 @@ impl HeliusClient {
         let (content, signature) =
             serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64)?;
 
-        let request_body = serde_json::to_string(&json!({
-            "jsonrpc": "2.0",
-            "id": "1",
-            "method": "sendTransaction",
-            "params": [
-                content,
-                {
-                    "encoding": "base64",
-                    "skipPreflight": true,
-                    "maxRetries": 0
-                }
-            ]
-        }))?;
+        let request_body = submit_body(&content).to_string();
 
         let response = self
             .http_client
@@ -120,12 +109,18 @@ impl HeliusClient {
             ));
         }
 
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if response_json.get("error").is_some() {
-                let err_msg = response_json["error"]
-                    .get("message")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown");
+        match parse_submit_response("helius", &response_text, &["result"]) {
+            Ok(SubmitResponse::Accepted) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
+                    crate::common::sdk_log::log_swqos_submitted(
+                        "helius",
+                        trade_type,
+                        start_time.elapsed(),
+                    );
+                }
+            }
+            Ok(SubmitResponse::Rejected(error)) => {
+                let err_msg = error.get("message").and_then(|v| v.as_str()).unwrap_or("unknown");
                 if crate::common::sdk_log::sdk_log_enabled() {
                     crate::common::sdk_log::log_swqos_submission_failed(
                         "helius",
@@ -136,20 +131,17 @@ impl HeliusClient {
                 }
                 return Err(anyhow::anyhow!("Helius Sender error: {}", err_msg));
             }
-            if response_json.get("result").is_some() && crate::common::sdk_log::sdk_log_enabled() {
-                crate::common::sdk_log::log_swqos_submitted(
-                    "helius",
-                    trade_type,
-                    start_time.elapsed(),
-                );
+            Err(e) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
+                    crate::common::sdk_log::log_swqos_submission_failed(
+                        "helius",
+                        trade_type,
+                        start_time.elapsed(),
+                        &e,
+                    );
+                }
+                return Err(e.into());
             }
-        } else if crate::common::sdk_log::sdk_log_enabled() {
-            crate::common::sdk_log::log_swqos_submission_failed(
-                "helius",
-                trade_type,
-                start_time.elapsed(),
-                response_text,
-            );
         }
 
         match poll_transaction_confirmation(&self.rpc_client, signature, wait_confirmation).await {
@@ -225,3 +217,31 @@ impl SwqosClientTrait for HeliusClient {
         }
     }
 }
+
+/// Sender `sendTransaction` body: base64, preflight skipped, no RPC-side retries.
+fn submit_body(content: &str) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": "1",
+        "method": "sendTransaction",
+        "params": [content, { "encoding": "base64", "skipPreflight": true, "maxRetries": 0 }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_body_matches_sender_schema() {
+        assert_eq!(
+            submit_body("dHg="),
+            json!({
+                "jsonrpc": "2.0",
+                "id": "1",
+                "method": "sendTransaction",
+                "params": ["dHg=", { "encoding": "base64", "skipPreflight": true, "maxRetries": 0 }]
+            })
+        );
+    }
+}
@@ -1,11 +1,19 @@
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
-    FormatBase64VersionedTransaction,
+    default_http_client_builder, parse_submit_response, poll_transaction_confirmation,
+    serialize_transaction_and_encode, FormatBase64VersionedTransaction, SubmitResponse,
 };
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
-use std::{sync::Arc, time::Instant};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use solana_transaction_status::UiTransactionEncoding;
 
@@ -44,7 +52,13 @@ impl SwqosClientTrait for JitoClient {
     }
 
     fn get_tip_account(&self) -> Result<String> {
-        if let Some(acc) = JITO_TIP_ACCOUNTS.choose(&mut rand::rng()) {
+        let cache = &*JITO_TIP_ACCOUNT_CACHE;
+        if cache.begin_refresh() {
+            self.spawn_tip_account_refresh(cache);
+        }
+        let refreshed = cache.accounts();
+        let accounts = refreshed.as_deref().map(Vec::as_slice).unwrap_or(JITO_TIP_ACCOUNTS);
+        if let Some(acc) = accounts.choose(&mut rand::rng()) {
             Ok(acc.to_string())
         } else {
             Err(anyhow::anyhow!("no valid tip accounts found"))
@@ -63,6 +77,31 @@ impl JitoClient {
         Self { rpc_client: Arc::new(rpc_client), endpoint, auth_token, http_client }
     }
 
+    /// Fetch `getTipAccounts` in the background; needs a Tokio runtime, else the refresh is skipped.
+    fn spawn_tip_account_refresh(&self, cache: &'static TipAccountCache) {
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            cache.finish_refresh(Err(anyhow::anyhow!("no Tokio runtime")));
+            return;
+        };
+        let http_client = self.http_client.clone();
+        let url = format!("{}/api/v1/bundles", self.endpoint);
+        handle.spawn(async move {
+            let result = async {
+                let text = http_client
+                    .post(&url)
+                    .header("Content-Type", "application/json")
+                    .body(tip_accounts_body().to_string())
+                    .send()
+                    .await?
+                    .text()
+                    .await?;
+                parse_tip_accounts(&text)
+            }
+            .await;
+            cache.finish_refresh(result);
+        });
+    }
+
     pub async fn send_transaction_impl(
         &self,
         trade_type: TradeType,
@@ -73,17 +112,7 @@ impl JitoClient {
         let (content, signature) =
             serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64)?;
 
-        let request_body = serde_json::to_string(&json!({
-            "id": 1,
-            "jsonrpc": "2.0",
-            "method": "sendTransaction",
-            "params": [
-                content,
-                {
-                    "encoding": "base64"
-                }
-            ]
-        }))?;
+        let request_body = submit_body(&content).to_string();
 
         let endpoint = if self.auth_token.is_empty() {
             format!("{}/api/v1/transactions", self.endpoint)
@@ -103,14 +132,15 @@ impl JitoClient {
             .text()
             .await?;
 
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if response_json.get("result").is_some() {
+        match parse_submit_response("jito", &response_text, &["result"]) {
+            Ok(SubmitResponse::Accepted) => {
                 crate::common::sdk_log::log_swqos_submitted(
                     "jito",
                     trade_type,
                     start_time.elapsed(),
                 );
-            } else if let Some(_error) = response_json.get("error") {
+            }
+            Ok(SubmitResponse::Rejected(_error)) => {
                 eprintln!(
                     " [jito] {} submission failed after {:?}: {:?}",
                     trade_type,
@@ -118,13 +148,15 @@ impl JitoClient {
                     _error
                 );
             }
-        } else {
-            crate::common::sdk_log::log_swqos_submission_failed(
-                "jito",
-                trade_type,
-                start_time.elapsed(),
-                response_text,
-            );
+            Err(e) => {
+                crate::common::sdk_log::log_swqos_submission_failed(
+                    "jito",
+                    trade_type,
+                    start_time.elapsed(),
+                    &e,
+                );
+                return Err(e.into());
+            }
         }
 
         let start_time: Instant = Instant::now();
@@ -165,15 +197,7 @@ impl JitoClient {
         let start_time = Instant::now();
         let txs_base64 =
             transactions.iter().map(|tx| tx.to_base64_string()).collect::<Vec<String>>();
-        let body = serde_json::json!({
-            "jsonrpc": "2.0",
-            "method": "sendBundle",
-            "params": [
-                txs_base64,
-                { "encoding": "base64" }
-            ],
-            "id": 1,
-        });
+        let body = bundle_body(&txs_base64);
 
         let endpoint = if self.auth_token.is_empty() {
             format!("{}/api/v1/bundles", self.endpoint)
@@ -193,10 +217,11 @@ impl JitoClient {
             .text()
             .await?;
 
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if response_json.get("result").is_some() {
+        match parse_submit_response("jito", &response_text, &["result"])? {
+            SubmitResponse::Accepted => {
                 println!(" jito {} submitted: {:?}", trade_type, start_time.elapsed());
-            } else if let Some(_error) = response_json.get("error") {
+            }
+            SubmitResponse::Rejected(_error) => {
                 eprintln!(
                     " jito {} submission failed after {:?}: {:?}",
                     trade_type,
@@ -209,3 +234,172 @@ impl JitoClient {
         Ok(())
     }
 }
+
+/// Tip accounts from Jito's `getTipAccounts`, refreshed in the background once older than the
+/// TTL. While empty (disabled, not fetched yet, or last fetch failed) the built-in
+/// `JITO_TIP_ACCOUNTS` list is used.
+pub struct TipAccountCache {
+    ttl_ms: AtomicU64,
+    refreshing: AtomicBool,
+    /// (last refresh attempt, accounts from the last successful fetch)
+    state: RwLock<(Option<Instant>, Option<Arc<Vec<Pubkey>>>)>,
+}
+
+impl TipAccountCache {
+    /// `Duration::ZERO` disables refreshing.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl_ms: AtomicU64::new(ttl.as_millis() as u64),
+            refreshing: AtomicBool::new(false),
+            state: RwLock::new((None, None)),
+        }
+    }
+
+    #[inline]
+    pub fn ttl(&self) -> Duration {
+        Duration::from_millis(self.ttl_ms.load(Ordering::Relaxed))
+    }
+
+    /// Change the TTL; `Duration::ZERO` disables refreshing and drops fetched accounts.
+    pub fn set_ttl(&self, ttl: Duration) {
+        self.ttl_ms.store(ttl.as_millis() as u64, Ordering::Relaxed);
+        if ttl.is_zero() {
+            *self.state.write() = (None, None);
+        }
+    }
+
+    /// Fetched accounts, if any; `None` means use the built-in list.
+    pub fn accounts(&self) -> Option<Arc<Vec<Pubkey>>> {
+        self.state.read().1.clone()
+    }
+
+    /// Claim the next refresh: true at most once per TTL, and never while one is in flight.
+    fn begin_refresh(&self) -> bool {
+        let ttl = self.ttl();
+        if ttl.is_zero() || self.state.read().0.is_some_and(|at| at.elapsed() < ttl) {
+            return false;
+        }
+        if self.refreshing.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+        self.state.write().0 = Some(Instant::now());
+        true
+    }
+
+    /// Store a fetch result; a failed or empty fetch falls back to the built-in list.
+    fn finish_refresh(&self, result: Result<Vec<Pubkey>>) {
+        let accounts = match result {
+            Ok(accounts) if !accounts.is_empty() => Some(Arc::new(accounts)),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!(target: "sol_trade_sdk", "jito getTipAccounts failed: {}", e);
+                None
+            }
+        };
+        self.state.write().1 = accounts;
+        self.refreshing.store(false, Ordering::Release);
+    }
+}
+
+/// Process-wide Jito tip-account cache; its TTL is set from `TradeConfig::jito_tip_account_refresh`
+/// when a `TradingClient` is created.
+pub static JITO_TIP_ACCOUNT_CACHE: Lazy<TipAccountCache> =
+    Lazy::new(|| TipAccountCache::new(Duration::ZERO));
+
+/// JSON-RPC `getTipAccounts` body for `/api/v1/bundles`.
+fn tip_accounts_body() -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getTipAccounts",
+        "params": []
+    })
+}
+
+/// Parse `{"result": ["<pubkey>", ...]}`.
+fn parse_tip_accounts(response_text: &str) -> Result<Vec<Pubkey>> {
+    let value: serde_json::Value = serde_json::from_str(response_text)?;
+    let result = value
+        .get("result")
+        .and_then(|r| r.as_array())
+        .ok_or_else(|| anyhow::anyhow!("getTipAccounts: unexpected response {}", response_text))?;
+    result
+        .iter()
+        .map(|v| {
+            let s =
+                v.as_str().ok_or_else(|| anyhow::anyhow!("getTipAccounts: non-string entry"))?;
+            Pubkey::from_str(s).map_err(|e| anyhow::anyhow!("getTipAccounts: {}: {}", s, e))
+        })
+        .collect()
+}
+
+/// JSON-RPC `sendTransaction` body for `/api/v1/transactions`.
+fn submit_body(content: &str) -> serde_json::Value {
+    json!({
+        "id": 1,
+        "jsonrpc": "2.0",
+        "method": "sendTransaction",
+        "params": [content, { "encoding": "base64" }]
+    })
+}
+
+/// JSON-RPC `sendBundle` body for `/api/v1/bundles`.
+fn bundle_body(txs_base64: &[String]) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "sendBundle",
+        "params": [txs_base64, { "encoding": "base64" }],
+        "id": 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_bodies_match_block_engine_schema() {
+        assert_eq!(
+            submit_body("dHg="),
+            json!({
+                "id": 1,
+                "jsonrpc": "2.0",
+                "method": "sendTransaction",
+                "params": ["dHg=", { "encoding": "base64" }]
+            })
+        );
+        assert_eq!(
+            bundle_body(&["YQ==".to_string(), "Yg==".to_string()]),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "sendBundle",
+                "params": [["YQ==", "Yg=="], { "encoding": "base64" }]
+            })
+        );
+    }
+
+    #[test]
+    fn tip_accounts_refresh_once_per_ttl_and_fall_back_on_failure() {
+        let rotated = Pubkey::new_unique();
+        let response = format!(r#"{{"jsonrpc":"2.0","result":["{}"],"id":1}}"#, rotated);
+        assert_eq!(parse_tip_accounts(&response).unwrap(), vec![rotated]);
+        assert!(parse_tip_accounts(r#"{"error":{"code":-32601}}"#).is_err());
+
+        let cache = TipAccountCache::new(Duration::ZERO);
+        assert!(!cache.begin_refresh(), "disabled by default");
+
+        cache.set_ttl(Duration::from_secs(60));
+        assert!(cache.begin_refresh());
+        assert!(!cache.begin_refresh(), "one refresh in flight");
+        cache.finish_refresh(parse_tip_accounts(&response));
+        assert_eq!(cache.accounts().unwrap().as_slice(), &[rotated]);
+        assert!(!cache.begin_refresh(), "fresh for the TTL");
+
+        cache.set_ttl(Duration::from_millis(1));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.begin_refresh());
+        cache.finish_refresh(Err(anyhow::anyhow!("timeout")));
+        assert!(cache.accounts().is_none(), "failed refresh uses the built-in list");
+    }
+}
@@ -1,5 +1,6 @@
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
+    default_http_client_builder, parse_submit_response, poll_transaction_confirmation,
+    serialize_transaction_and_encode, SubmitResponse,
 };
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -75,21 +76,7 @@ impl LightspeedClient {
         let (content, signature) =
             serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64)?;
 
-        // Lightspeed uses standard Solana JSON-RPC format for sendTransaction
-        let request_body = serde_json::to_string(&json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "sendTransaction",
-            "params": [
-                content,
-                {
-                    "encoding": "base64",
-                    "skipPreflight": true,
-                    "preflightCommitment": "processed",
-                    "maxRetries": 0
-                }
-            ]
-        }))?;
+        let request_body = submit_body(&content).to_string();
 
         let response_text = self
             .http_client
@@ -101,14 +88,15 @@ impl LightspeedClient {
             .text()
             .await?;
 
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if response_json.get("result").is_some() {
+        match parse_submit_response("lightspeed", &response_text, &["result"]) {
+            Ok(SubmitResponse::Accepted) => {
                 crate::common::sdk_log::log_swqos_submitted(
                     "lightspeed",
                     trade_type,
                     start_time.elapsed(),
                 );
-            } else if let Some(_error) = response_json.get("error") {
+            }
+            Ok(SubmitResponse::Rejected(_error)) => {
                 crate::common::sdk_log::log_swqos_submission_failed(
                     "lightspeed",
                     trade_type,
@@ -116,13 +104,15 @@ impl LightspeedClient {
                     _error,
                 );
             }
-        } else {
-            crate::common::sdk_log::log_swqos_submission_failed(
-                "lightspeed",
-                trade_type,
-                start_time.elapsed(),
-                response_text,
-            );
+            Err(e) => {
+                crate::common::sdk_log::log_swqos_submission_failed(
+                    "lightspeed",
+                    trade_type,
+                    start_time.elapsed(),
+                    &e,
+                );
+                return Err(e.into());
+            }
         }
 
         let start_time: Instant = Instant::now();
@@ -166,3 +156,47 @@ impl LightspeedClient {
         Ok(())
     }
 }
+
+/// Standard Solana JSON-RPC `sendTransaction` body (skip preflight, no retries).
+fn submit_body(content: &str) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "sendTransaction",
+        "params": [
+            content,
+            {
+                "encoding": "base64",
+                "skipPreflight": true,
+                "preflightCommitment": "processed",
+                "maxRetries": 0
+            }
+        ]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_body_matches_json_rpc_schema() {
+        assert_eq!(
+            submit_body("dHg="),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "sendTransaction",
+                "params": [
+                    "dHg=",
+                    {
+                        "encoding": "base64",
+                        "skipPreflight": true,
+                        "preflightCommitment": "processed",
+                        "maxRetries": 0
+                    }
+                ]
+            })
+        );
+    }
+}
@@ -186,12 +186,11 @@ impl LunarLanderClient {
             .signatures
             .first()
             .ok_or_else(|| anyhow::anyhow!("LunarLander transaction has no signature"))?;
-        let body_bytes = bincode_serialize(transaction)
-            .map_err(|e| anyhow::anyhow!("LunarLander binary serialize failed: {}", e))?;
+        let body_bytes = submit_body(transaction)?;
 
         match &self.backend {
             LunarLanderBackend::Http { endpoint, auth_token, http_client, .. } => {
-                let url = format!("{}/send-bin", endpoint.trim_end_matches('/'));
+                let url = send_bin_url(endpoint);
                 let response = http_client
                     .post(&url)
                     .header("x-api-key", auth_token)
@@ -284,6 +283,17 @@ fn quic_client_options(mev_protection: bool) -> ClientOptions {
     ClientOptions { mev_protect: mev_protection, ..ClientOptions::default() }
 }
 
+/// `POST /send-bin` endpoint; tolerates a trailing slash on the configured endpoint.
+fn send_bin_url(endpoint: &str) -> String {
+    format!("{}/send-bin", endpoint.trim_end_matches('/'))
+}
+
+/// `/send-bin` body: the bincode-serialized transaction.
+fn submit_body(transaction: &VersionedTransaction) -> Result<Vec<u8>> {
+    bincode_serialize(transaction)
+        .map_err(|e| anyhow::anyhow!("LunarLander binary serialize failed: {}", e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -293,4 +303,20 @@ mod tests {
         assert!(!quic_client_options(false).mev_protect);
         assert!(quic_client_options(true).mev_protect);
     }
+
+    #[test]
+    fn request_body_matches_send_bin_schema() {
+        assert_eq!(
+            send_bin_url("http://fra.lunar-lander.example/"),
+            "http://fra.lunar-lander.example/send-bin"
+        );
+        assert_eq!(
+            send_bin_url("http://fra.lunar-lander.example"),
+            "http://fra.lunar-lander.example/send-bin"
+        );
+
+        let transaction = crate::test_fixtures::signed_transaction();
+        let body = submit_body(&transaction).unwrap();
+        assert_eq!(bincode::deserialize::<VersionedTransaction>(&body).unwrap(), transaction);
+    }
 }
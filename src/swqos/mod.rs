@@ -13,13 +13,16 @@ pub mod lunarlander;
 pub mod nextblock;
 pub mod node1;
 pub mod node1_quic;
+pub mod rate_limit;
 pub mod serialization;
 pub mod solami;
 pub mod solana_rpc;
 pub mod soyas;
 pub mod speedlanding;
 pub mod stellium;
+pub mod submit_timeout;
 pub mod temporal;
+pub mod watchdog;
 pub mod zeroslot;
 
 use std::sync::Arc;
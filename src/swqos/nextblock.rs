@@ -1,5 +1,6 @@
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
+    default_http_client_builder, parse_submit_response, poll_transaction_confirmation,
+    serialize_transaction_and_encode, SubmitResponse,
 };
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -79,12 +80,7 @@ impl NextBlockClient {
         let (content, signature) =
             serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64)?;
 
-        let request_body = serde_json::to_string(&json!({
-            "transaction": {
-                "content": content
-            },
-            "frontRunningProtection": false
-        }))?;
+        let request_body = submit_body(&content).to_string();
 
         let response_text = self
             .http_client
@@ -97,14 +93,15 @@ impl NextBlockClient {
             .text()
             .await?;
 
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if response_json.get("result").is_some() {
+        match parse_submit_response("nextblock", &response_text, &["result", "signature"]) {
+            Ok(SubmitResponse::Accepted) => {
                 crate::common::sdk_log::log_swqos_submitted(
                     "nextblock",
                     trade_type,
                     start_time.elapsed(),
                 );
-            } else if let Some(_error) = response_json.get("error") {
+            }
+            Ok(SubmitResponse::Rejected(_error)) => {
                 crate::common::sdk_log::log_swqos_submission_failed(
                     "nextblock",
                     trade_type,
@@ -112,13 +109,15 @@ impl NextBlockClient {
                     _error,
                 );
             }
-        } else {
-            crate::common::sdk_log::log_swqos_submission_failed(
-                "nextblock",
-                trade_type,
-                start_time.elapsed(),
-                response_text,
-            );
+            Err(e) => {
+                crate::common::sdk_log::log_swqos_submission_failed(
+                    "nextblock",
+                    trade_type,
+                    start_time.elapsed(),
+                    &e,
+                );
+                return Err(e.into());
+            }
         }
 
         let start_time: Instant = Instant::now();
@@ -162,3 +161,31 @@ impl NextBlockClient {
         Ok(())
     }
 }
+
+/// `/api/v2/submit` body.
+fn submit_body(content: &str) -> serde_json::Value {
+    json!({
+        "transaction": {
+            "content": content
+        },
+        "frontRunningProtection": false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_body_matches_submit_api_schema() {
+        assert_eq!(
+            submit_body("dHg="),
+            json!({
+                "transaction": {
+                    "content": "dHg="
+                },
+                "frontRunningProtection": false
+            })
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
+    default_http_client_builder, parse_submit_response, poll_transaction_confirmation,
+    serialize_transaction_and_encode, SubmitResponse,
 };
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -158,15 +159,7 @@ impl Node1Client {
         let (content, signature) =
             serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64)?;
 
-        let request_body = serde_json::to_string(&json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "sendTransaction",
-            "params": [
-                content,
-                { "encoding": "base64", "skipPreflight": true }
-            ]
-        }))?;
+        let request_body = submit_body(&content).to_string();
 
         // Node1 uses api-key header instead of URL parameter
         let response_text = self
@@ -181,15 +174,18 @@ impl Node1Client {
             .await?;
 
         // Parse JSON response
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if crate::common::sdk_log::sdk_log_enabled() {
-                if response_json.get("result").is_some() {
+        match parse_submit_response("node1", &response_text, &["result"]) {
+            Ok(SubmitResponse::Accepted) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
                     crate::common::sdk_log::log_swqos_submitted(
                         "node1",
                         trade_type,
                         start_time.elapsed(),
                     );
-                } else if let Some(_error) = response_json.get("error") {
+                }
+            }
+            Ok(SubmitResponse::Rejected(_error)) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
                     eprintln!(
                         " [node1] {} submission failed after {:?}: {:?}",
                         trade_type,
@@ -198,13 +194,17 @@ impl Node1Client {
                     );
                 }
             }
-        } else if crate::common::sdk_log::sdk_log_enabled() {
-            crate::common::sdk_log::log_swqos_submission_failed(
-                "node1",
-                trade_type,
-                start_time.elapsed(),
-                response_text,
-            );
+            Err(e) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
+                    crate::common::sdk_log::log_swqos_submission_failed(
+                        "node1",
+                        trade_type,
+                        start_time.elapsed(),
+                        &e,
+                    );
+                }
+                return Err(e.into());
+            }
         }
 
         let start_time: Instant = Instant::now();
@@ -268,3 +268,37 @@ impl Drop for Node1Client {
         });
     }
 }
+
+/// JSON-RPC `sendTransaction` body (skip preflight).
+fn submit_body(content: &str) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "sendTransaction",
+        "params": [
+            content,
+            { "encoding": "base64", "skipPreflight": true }
+        ]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_body_matches_json_rpc_schema() {
+        assert_eq!(
+            submit_body("dHg="),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "sendTransaction",
+                "params": [
+                    "dHg=",
+                    { "encoding": "base64", "skipPreflight": true }
+                ]
+            })
+        );
+    }
+}
@@ -48,9 +48,7 @@ impl Node1QuicClient {
             .next()
             .context("no socket address for Node1 QUIC")?;
 
-        let api_key_uuid =
-            Uuid::parse_str(api_key).context("Node1 API key must be a valid UUID")?;
-        let api_key_bytes: [u8; 16] = *api_key_uuid.as_bytes();
+        let api_key_bytes = auth_frame(api_key)?;
 
         let server_name = server_addr.split(':').next().unwrap_or(server_addr);
 
@@ -146,8 +144,7 @@ impl Node1QuicClient {
         recv.read_exact(&mut header)
             .await
             .map_err(|e| anyhow::anyhow!("read response header: {:?}", e))?;
-        let status = u16::from_be_bytes(header[0..2].try_into().unwrap());
-        let msg_len = u32::from_be_bytes(header[2..6].try_into().unwrap()) as usize;
+        let (status, msg_len) = parse_response_header(&header);
         let mut msg = vec![0u8; msg_len];
         if msg_len > 0 {
             recv.read_exact(&mut msg)
@@ -185,7 +182,7 @@ impl SwqosClientTrait for Node1QuicClient {
     ) -> Result<()> {
         let start = Instant::now();
         let signature = transaction.signatures.first().copied().unwrap_or_default();
-        let tx_bytes = bincode::serialize(transaction).context("Node1 QUIC: bincode serialize")?;
+        let tx_bytes = submit_payload(transaction)?;
 
         let (status, msg) = timeout(SEND_TIMEOUT, self.send_transaction_bytes(&tx_bytes))
             .await
@@ -251,6 +248,24 @@ impl SwqosClientTrait for Node1QuicClient {
     }
 }
 
+/// Auth stream payload: the API key UUID as 16 raw bytes.
+fn auth_frame(api_key: &str) -> Result<[u8; 16]> {
+    let api_key_uuid = Uuid::parse_str(api_key).context("Node1 API key must be a valid UUID")?;
+    Ok(*api_key_uuid.as_bytes())
+}
+
+/// Transaction stream payload: the bincode-serialized transaction.
+fn submit_payload(transaction: &VersionedTransaction) -> Result<Vec<u8>> {
+    bincode::serialize(transaction).context("Node1 QUIC: bincode serialize")
+}
+
+/// Response header: 2-byte big-endian status, then 4-byte big-endian message length.
+fn parse_response_header(header: &[u8; 6]) -> (u16, usize) {
+    let status = u16::from_be_bytes([header[0], header[1]]);
+    let msg_len = u32::from_be_bytes([header[2], header[3], header[4], header[5]]) as usize;
+    (status, msg_len)
+}
+
 impl Drop for Node1QuicClient {
     fn drop(&mut self) {
         self.connection.get_mut().close(0u32.into(), b"client closing");
@@ -304,3 +319,28 @@ impl rustls::client::danger::ServerCertVerifier for SkipServerVerification {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_match_the_node1_quic_protocol() {
+        let frame = auth_frame("00112233-4455-4677-8899-aabbccddeeff").unwrap();
+        assert_eq!(
+            frame,
+            [
+                0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x46, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+                0xee, 0xff
+            ]
+        );
+        assert!(auth_frame("not-a-uuid").is_err());
+
+        let transaction = crate::test_fixtures::signed_transaction();
+        let payload = submit_payload(&transaction).unwrap();
+        assert!(payload.len() <= MAX_TX_SIZE);
+        assert_eq!(bincode::deserialize::<VersionedTransaction>(&payload).unwrap(), transaction);
+
+        assert_eq!(parse_response_header(&[0x00, 0xc8, 0, 0, 0x01, 0x02]), (200, 0x0102));
+    }
+}
@@ -0,0 +1,164 @@
+//! Per-provider submit rate limit, so high-frequency strategies do not get an API key banned.
+//! 单通道提交限速（令牌桶），超出时本通道失败而不是继续发送。
+
+use std::{sync::Arc, time::Instant};
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::swqos::{common::TradeError, SwqosClient, SwqosClientTrait, SwqosType, TradeType};
+
+/// Token bucket holding up to `rate` tokens (one second of burst), refilled at `rate` per second.
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32) -> Self {
+        Self { rate: rate as f64, tokens: rate as f64, last_refill: Instant::now() }
+    }
+
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Wraps a SWQOS client so at most `rate` submits per second reach the provider; the rest fail
+/// at once with `TradeError::RATE_LIMITED` (never counted as landed, the other routes keep
+/// racing). A `send_transactions` call counts as one request.
+pub struct RateLimitedSwqosClient {
+    inner: Arc<SwqosClient>,
+    rate: u32,
+    bucket: Mutex<TokenBucket>,
+}
+
+impl RateLimitedSwqosClient {
+    /// Wrap `inner`; `None` or `Some(0)` returns `inner` unchanged.
+    pub fn wrap(inner: Arc<SwqosClient>, rate: Option<u32>) -> Arc<SwqosClient> {
+        match rate {
+            Some(rate) if rate > 0 => {
+                Arc::new(Self { inner, rate, bucket: Mutex::new(TokenBucket::new(rate)) })
+            }
+            _ => inner,
+        }
+    }
+
+    fn acquire(&self) -> Result<()> {
+        if self.bucket.lock().try_acquire(Instant::now()) {
+            Ok(())
+        } else {
+            Err(TradeError::rate_limited(self.inner.get_swqos_type(), self.rate).into())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for RateLimitedSwqosClient {
+    async fn send_transaction(
+        &self,
+        trade_type: TradeType,
+        transaction: &VersionedTransaction,
+        wait_confirmation: bool,
+    ) -> Result<()> {
+        self.acquire()?;
+        self.inner.send_transaction(trade_type, transaction, wait_confirmation).await
+    }
+
+    async fn send_transactions(
+        &self,
+        trade_type: TradeType,
+        transactions: &Vec<VersionedTransaction>,
+        wait_confirmation: bool,
+    ) -> Result<()> {
+        self.acquire()?;
+        self.inner.send_transactions(trade_type, transactions, wait_confirmation).await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        self.inner.get_tip_account()
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        self.inner.get_swqos_type()
+    }
+
+    fn min_tip_sol(&self) -> f64 {
+        self.inner.min_tip_sol()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::core::async_executor::is_landed_error;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    struct CountingClient(AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl SwqosClientTrait for CountingClient {
+        async fn send_transaction(
+            &self,
+            _trade_type: TradeType,
+            _transaction: &VersionedTransaction,
+            _wait_confirmation: bool,
+        ) -> Result<()> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        async fn send_transactions(
+            &self,
+            _trade_type: TradeType,
+            _transactions: &Vec<VersionedTransaction>,
+            _wait_confirmation: bool,
+        ) -> Result<()> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn get_tip_account(&self) -> Result<String> {
+            Ok(String::new())
+        }
+
+        fn get_swqos_type(&self) -> SwqosType {
+            SwqosType::NextBlock
+        }
+    }
+
+    #[tokio::test]
+    async fn submits_over_the_rate_fail_without_reaching_the_provider() {
+        let inner = Arc::new(CountingClient(AtomicUsize::new(0)));
+        let client = RateLimitedSwqosClient::wrap(inner.clone(), Some(2));
+        let tx = VersionedTransaction::default();
+
+        assert!(client.send_transaction(TradeType::Buy, &tx, false).await.is_ok());
+        assert!(client.send_transactions(TradeType::Buy, &vec![tx.clone()], false).await.is_ok());
+        let err = client.send_transaction(TradeType::Buy, &tx, false).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<TradeError>().unwrap().code, TradeError::RATE_LIMITED);
+        assert!(!is_landed_error(&err));
+        assert_eq!(inner.0.load(Ordering::Relaxed), 2);
+
+        // refills at `rate` per second
+        let mut bucket = TokenBucket::new(2);
+        let start = bucket.last_refill;
+        assert!(bucket.try_acquire(start) && bucket.try_acquire(start));
+        assert!(!bucket.try_acquire(start + Duration::from_millis(100)));
+        assert!(bucket.try_acquire(start + Duration::from_millis(600)));
+
+        let unlimited = RateLimitedSwqosClient::wrap(inner.clone(), None);
+        assert!(Arc::ptr_eq(&unlimited, &(inner as Arc<SwqosClient>)));
+    }
+}
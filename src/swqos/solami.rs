@@ -140,7 +140,7 @@ impl SwqosClientTrait for SolamiClient {
     ) -> Result<()> {
         let start_time = Instant::now();
         let signature = transaction.get_signature();
-        let serialized_tx = bincode::serialize(transaction)?;
+        let serialized_tx = submit_payload(transaction)?;
         let connection = self.ensure_connected().await?;
         let mut send_result =
             timeout(SEND_TIMEOUT, Self::try_send_bytes(&connection, &serialized_tx)).await;
@@ -240,3 +240,20 @@ impl SwqosClientTrait for SolamiClient {
         SwqosType::Solami
     }
 }
+
+/// QUIC uni-stream payload: the bincode-serialized transaction, nothing else.
+fn submit_payload(transaction: &VersionedTransaction) -> Result<Vec<u8>> {
+    bincode::serialize(transaction).context("Solami QUIC: bincode serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_is_the_bincode_transaction() {
+        let transaction = crate::test_fixtures::signed_transaction();
+        let payload = submit_payload(&transaction).unwrap();
+        assert_eq!(bincode::deserialize::<VersionedTransaction>(&payload).unwrap(), transaction);
+    }
+}
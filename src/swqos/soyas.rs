@@ -173,7 +173,7 @@ impl SwqosClientTrait for SoyasClient {
     ) -> Result<()> {
         let start_time = Instant::now();
         let signature = transaction.get_signature();
-        let serialized_tx = bincode::serialize(transaction)?;
+        let serialized_tx = submit_payload(transaction)?;
         let connection = self.connection.load_full();
         if Self::try_send_bytes(&connection, &serialized_tx).await.is_err() {
             if crate::common::sdk_log::sdk_log_enabled() {
@@ -255,3 +255,20 @@ impl SwqosClientTrait for SoyasClient {
         SwqosType::Soyas
     }
 }
+
+/// QUIC uni-stream payload: the bincode-serialized transaction, nothing else.
+fn submit_payload(transaction: &VersionedTransaction) -> Result<Vec<u8>> {
+    bincode::serialize(transaction).context("Soyas QUIC: bincode serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_is_the_bincode_transaction() {
+        let transaction = crate::test_fixtures::signed_transaction();
+        let payload = submit_payload(&transaction).unwrap();
+        assert_eq!(bincode::deserialize::<VersionedTransaction>(&payload).unwrap(), transaction);
+    }
+}
@@ -242,3 +242,16 @@ impl SwqosClientTrait for SpeedlandingClient {
         SwqosType::Speedlanding
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_is_the_bincode_transaction() {
+        let transaction = crate::test_fixtures::signed_transaction();
+        let (payload, signature) = serialize_transaction_bincode_sync(&transaction).unwrap();
+        assert_eq!(signature, transaction.signatures[0]);
+        assert_eq!(bincode::deserialize::<VersionedTransaction>(&payload).unwrap(), transaction);
+    }
+}
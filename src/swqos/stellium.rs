@@ -1,5 +1,6 @@
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
+    default_http_client_builder, parse_submit_response, poll_transaction_confirmation,
+    serialize_transaction_and_encode, SubmitResponse,
 };
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -137,16 +138,7 @@ impl StelliumClient {
         let (content, signature) =
             serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64)?;
 
-        // Stellium uses standard Solana sendTransaction format
-        let request_body = serde_json::to_string(&json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "sendTransaction",
-            "params": [
-                content,
-                { "encoding": "base64" }
-            ]
-        }))?;
+        let request_body = submit_body(&content).to_string();
 
         // Build the URL with the API key
         let url = format!("{}/{}", self.endpoint, self.auth_token);
@@ -165,15 +157,18 @@ impl StelliumClient {
             .await?;
 
         // Parse response
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if crate::common::sdk_log::sdk_log_enabled() {
-                if response_json.get("result").is_some() {
+        match parse_submit_response("Stellium", &response_text, &["result"]) {
+            Ok(SubmitResponse::Accepted) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
                     crate::common::sdk_log::log_swqos_submitted(
                         "Stellium",
                         trade_type,
                         start_time.elapsed(),
                     );
-                } else if let Some(_error) = response_json.get("error") {
+                }
+            }
+            Ok(SubmitResponse::Rejected(_error)) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
                     crate::common::sdk_log::log_swqos_submission_failed(
                         "Stellium",
                         trade_type,
@@ -182,13 +177,17 @@ impl StelliumClient {
                     );
                 }
             }
-        } else if crate::common::sdk_log::sdk_log_enabled() {
-            crate::common::sdk_log::log_swqos_submission_failed(
-                "Stellium",
-                trade_type,
-                start_time.elapsed(),
-                response_text,
-            );
+            Err(e) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
+                    crate::common::sdk_log::log_swqos_submission_failed(
+                        "Stellium",
+                        trade_type,
+                        start_time.elapsed(),
+                        &e,
+                    );
+                }
+                return Err(e.into());
+            }
         }
 
         let start_time: Instant = Instant::now();
@@ -246,3 +245,37 @@ impl Drop for StelliumClient {
         self.keep_alive_running.store(false, Ordering::Relaxed);
     }
 }
+
+/// Standard Solana JSON-RPC `sendTransaction` body.
+fn submit_body(content: &str) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "sendTransaction",
+        "params": [
+            content,
+            { "encoding": "base64" }
+        ]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_body_matches_json_rpc_schema() {
+        assert_eq!(
+            submit_body("dHg="),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "sendTransaction",
+                "params": [
+                    "dHg=",
+                    { "encoding": "base64" }
+                ]
+            })
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Per-client submit timeout so one hung endpoint cannot stall a parallel submit.
+//! 单通道提交超时：挂起的端点按失败处理，不阻塞其他通道。
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::swqos::{common::TradeError, SwqosClient, SwqosClientTrait, SwqosType, TradeType};
+
+/// Default per-endpoint submit timeout (see `TradeConfigBuilder::swqos_submit_timeout`).
+pub const DEFAULT_SWQOS_SUBMIT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Wraps a SWQOS client so submits that do not resolve within `timeout` fail with
+/// `TradeError::SUBMIT_TIMEOUT`. Sends that also wait for confirmation are not bounded here.
+pub struct TimeoutSwqosClient {
+    inner: Arc<SwqosClient>,
+    timeout: Duration,
+}
+
+impl TimeoutSwqosClient {
+    /// Wrap `inner`; a zero `timeout` returns `inner` unchanged.
+    pub fn wrap(inner: Arc<SwqosClient>, timeout: Duration) -> Arc<SwqosClient> {
+        if timeout.is_zero() {
+            return inner;
+        }
+        Arc::new(Self { inner, timeout })
+    }
+
+    async fn bounded<F>(&self, wait_confirmation: bool, send: F) -> Result<()>
+    where
+        F: std::future::Future<Output = Result<()>>,
+    {
+        if wait_confirmation {
+            return send.await;
+        }
+        match tokio::time::timeout(self.timeout, send).await {
+            Ok(result) => result,
+            Err(_) => {
+                Err(TradeError::submit_timeout(self.inner.get_swqos_type(), self.timeout).into())
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for TimeoutSwqosClient {
+    async fn send_transaction(
+        &self,
+        trade_type: TradeType,
+        transaction: &VersionedTransaction,
+        wait_confirmation: bool,
+    ) -> Result<()> {
+        self.bounded(
+            wait_confirmation,
+            self.inner.send_transaction(trade_type, transaction, wait_confirmation),
+        )
+        .await
+    }
+
+    async fn send_transactions(
+        &self,
+        trade_type: TradeType,
+        transactions: &Vec<VersionedTransaction>,
+        wait_confirmation: bool,
+    ) -> Result<()> {
+        self.bounded(
+            wait_confirmation,
+            self.inner.send_transactions(trade_type, transactions, wait_confirmation),
+        )
+        .await
+    }
+
+    fn get_tip_account(&self) -> Result<String> {
+        self.inner.get_tip_account()
+    }
+
+    fn get_swqos_type(&self) -> SwqosType {
+        self.inner.get_swqos_type()
+    }
+
+    fn min_tip_sol(&self) -> f64 {
+        self.inner.min_tip_sol()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::core::async_executor::is_landed_error;
+
+    struct HungClient;
+
+    #[async_trait::async_trait]
+    impl SwqosClientTrait for HungClient {
+        async fn send_transaction(
+            &self,
+            _trade_type: TradeType,
+            _transaction: &VersionedTransaction,
+            _wait_confirmation: bool,
+        ) -> Result<()> {
+            std::future::pending().await
+        }
+
+        async fn send_transactions(
+            &self,
+            _trade_type: TradeType,
+            _transactions: &Vec<VersionedTransaction>,
+            _wait_confirmation: bool,
+        ) -> Result<()> {
+            std::future::pending().await
+        }
+
+        fn get_tip_account(&self) -> Result<String> {
+            Ok(String::new())
+        }
+
+        fn get_swqos_type(&self) -> SwqosType {
+            SwqosType::Jito
+        }
+    }
+
+    #[tokio::test]
+    async fn hung_submit_fails_with_timeout_code_and_is_not_landed() {
+        let client = TimeoutSwqosClient::wrap(Arc::new(HungClient), Duration::from_millis(20));
+        let err = client
+            .send_transaction(TradeType::Buy, &VersionedTransaction::default(), false)
+            .await
+            .unwrap_err();
+
+        let trade_err = err.downcast_ref::<TradeError>().unwrap();
+        assert_eq!(trade_err.code, TradeError::SUBMIT_TIMEOUT);
+        assert!(!is_landed_error(&err));
+        assert_eq!(client.get_swqos_type(), SwqosType::Jito);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
+    default_http_client_builder, parse_submit_response, poll_transaction_confirmation,
+    serialize_transaction_and_encode, SubmitResponse,
 };
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -181,16 +182,7 @@ impl TemporalClient {
         let (content, signature) =
             serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64)?;
 
-        // Build request body according to Nozomi documentation requirements
-        let request_body = serde_json::to_string(&json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "sendTransaction",
-            "params": [
-                content,
-                { "encoding": "base64" }
-            ]
-        }))?;
+        let request_body = submit_body(&content).to_string();
 
         let mut url = String::with_capacity(self.endpoint.len() + self.auth_token.len() + 20);
         url.push_str(&self.endpoint);
@@ -207,14 +199,15 @@ impl TemporalClient {
             .text()
             .await?;
 
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if response_json.get("result").is_some() {
+        match parse_submit_response("nozomi", &response_text, &["result"]) {
+            Ok(SubmitResponse::Accepted) => {
                 crate::common::sdk_log::log_swqos_submitted(
                     "nozomi",
                     trade_type,
                     start_time.elapsed(),
                 );
-            } else if let Some(_error) = response_json.get("error") {
+            }
+            Ok(SubmitResponse::Rejected(_error)) => {
                 crate::common::sdk_log::log_swqos_submission_failed(
                     "nozomi",
                     trade_type,
@@ -222,13 +215,15 @@ impl TemporalClient {
                     _error,
                 );
             }
-        } else {
-            crate::common::sdk_log::log_swqos_submission_failed(
-                "nozomi",
-                trade_type,
-                start_time.elapsed(),
-                response_text,
-            );
+            Err(e) => {
+                crate::common::sdk_log::log_swqos_submission_failed(
+                    "nozomi",
+                    trade_type,
+                    start_time.elapsed(),
+                    &e,
+                );
+                return Err(e.into());
+            }
         }
 
         let start_time: Instant = Instant::now();
@@ -288,3 +283,37 @@ impl Drop for TemporalClient {
         });
     }
 }
+
+/// JSON-RPC `sendTransaction` body per the Nozomi docs.
+fn submit_body(content: &str) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "sendTransaction",
+        "params": [
+            content,
+            { "encoding": "base64" }
+        ]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_body_matches_nozomi_schema() {
+        assert_eq!(
+            submit_body("dHg="),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "sendTransaction",
+                "params": [
+                    "dHg=",
+                    { "encoding": "base64" }
+                ]
+            })
+        );
+    }
+}
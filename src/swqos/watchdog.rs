@@ -0,0 +1,228 @@
+//! SWQOS landing watchdog: tracks which endpoint's transaction actually landed and temporarily
+//! skips endpoints that keep accepting transactions without ever landing them.
+//! 记录各 SWQOS 通道落地率，落地率过低的通道暂时跳过，冷却后重新探测。
+//!
+//! Only trades that wait for confirmation feed the watchdog, because that is the only point
+//! where the SDK learns which submitted signature landed. In a multi-route submit at most one
+//! route "wins", so rates are relative: with N healthy routes each lands roughly 1/N of the time.
+//! Pick `min_landing_rate` well below that.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::swqos::{SwqosClient, SwqosType};
+
+/// Watchdog policy. 看门狗策略。
+#[derive(Debug, Clone, Copy)]
+pub struct SwqosWatchdogConfig {
+    /// Rolling window of recent attempts per endpoint.
+    pub window: usize,
+    /// Minimum attempts in the window before an endpoint may be disabled.
+    pub min_samples: usize,
+    /// Endpoints whose landed/attempts ratio over the window is below this are disabled.
+    pub min_landing_rate: f64,
+    /// How long a disabled endpoint is skipped before it is re-probed with a fresh window.
+    pub cooldown: Duration,
+}
+
+impl Default for SwqosWatchdogConfig {
+    fn default() -> Self {
+        Self {
+            window: 50,
+            min_samples: 20,
+            min_landing_rate: 0.02,
+            cooldown: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Per-endpoint counters exposed to callers. 单通道统计。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwqosEndpointStats {
+    pub swqos_type: SwqosType,
+    /// Lifetime attempts / landings.
+    pub attempts: u64,
+    pub landed: u64,
+    /// Attempts / landings inside the current rolling window.
+    pub window_attempts: usize,
+    pub window_landed: usize,
+    /// True while the endpoint is being skipped.
+    pub disabled: bool,
+}
+
+impl SwqosEndpointStats {
+    #[inline]
+    pub fn window_landing_rate(&self) -> f64 {
+        if self.window_attempts == 0 {
+            0.0
+        } else {
+            self.window_landed as f64 / self.window_attempts as f64
+        }
+    }
+}
+
+#[derive(Default)]
+struct EndpointState {
+    recent: VecDeque<bool>,
+    attempts: u64,
+    landed: u64,
+    disabled_until: Option<Instant>,
+}
+
+impl EndpointState {
+    #[inline]
+    fn window_landed(&self) -> usize {
+        self.recent.iter().filter(|l| **l).count()
+    }
+}
+
+/// Shared landing tracker; attach with `TradingClient::with_swqos_watchdog`.
+pub struct SwqosWatchdog {
+    config: SwqosWatchdogConfig,
+    state: Mutex<HashMap<SwqosType, EndpointState>>,
+}
+
+impl SwqosWatchdog {
+    pub fn new(config: SwqosWatchdogConfig) -> Self {
+        Self { config, state: Mutex::new(HashMap::new()) }
+    }
+
+    #[inline]
+    pub fn config(&self) -> &SwqosWatchdogConfig {
+        &self.config
+    }
+
+    /// Record one submitted attempt and whether its signature was the one that landed.
+    pub fn record(&self, swqos_type: SwqosType, landed: bool) {
+        self.record_at(swqos_type, landed, Instant::now());
+    }
+
+    fn record_at(&self, swqos_type: SwqosType, landed: bool, now: Instant) {
+        let mut state = self.state.lock();
+        let entry = state.entry(swqos_type).or_default();
+        entry.attempts += 1;
+        if landed {
+            entry.landed += 1;
+        }
+        entry.recent.push_back(landed);
+        while entry.recent.len() > self.config.window.max(1) {
+            entry.recent.pop_front();
+        }
+        let samples = entry.recent.len();
+        if swqos_type != SwqosType::Default && samples >= self.config.min_samples.max(1) {
+            let rate = entry.window_landed() as f64 / samples as f64;
+            if rate < self.config.min_landing_rate {
+                entry.disabled_until = Some(now + self.config.cooldown);
+                // Start the next probe period from a clean window.
+                entry.recent.clear();
+                if crate::common::sdk_log::sdk_log_enabled() {
+                    println!(
+                        " [SDK][{:?}] landing rate {:.3} below {:.3}; skipping for {:?}",
+                        swqos_type, rate, self.config.min_landing_rate, self.config.cooldown
+                    );
+                }
+            }
+        }
+    }
+
+    /// Whether `swqos_type` should receive submits now. The RPC `Default` route is never skipped.
+    pub fn is_enabled(&self, swqos_type: SwqosType) -> bool {
+        self.is_enabled_at(swqos_type, Instant::now())
+    }
+
+    fn is_enabled_at(&self, swqos_type: SwqosType, now: Instant) -> bool {
+        if swqos_type == SwqosType::Default {
+            return true;
+        }
+        let mut state = self.state.lock();
+        match state.get_mut(&swqos_type) {
+            Some(entry) => match entry.disabled_until {
+                Some(until) if now < until => false,
+                Some(_) => {
+                    // Cooldown elapsed: re-probe.
+                    entry.disabled_until = None;
+                    true
+                }
+                None => true,
+            },
+            None => true,
+        }
+    }
+
+    /// Drop currently-disabled endpoints. Returns `None` when nothing is filtered (no allocation),
+    /// and never filters down to an empty list.
+    pub fn filter_clients(&self, clients: &[Arc<SwqosClient>]) -> Option<Vec<Arc<SwqosClient>>> {
+        let enabled: Vec<bool> =
+            clients.iter().map(|c| self.is_enabled(c.get_swqos_type())).collect();
+        if enabled.iter().all(|e| *e) || !enabled.iter().any(|e| *e) {
+            return None;
+        }
+        Some(clients.iter().zip(enabled).filter(|(_, e)| *e).map(|(c, _)| c.clone()).collect())
+    }
+
+    /// Snapshot of every endpoint seen so far.
+    pub fn stats(&self) -> Vec<SwqosEndpointStats> {
+        let now = Instant::now();
+        let state = self.state.lock();
+        let mut out: Vec<SwqosEndpointStats> = state
+            .iter()
+            .map(|(swqos_type, entry)| SwqosEndpointStats {
+                swqos_type: *swqos_type,
+                attempts: entry.attempts,
+                landed: entry.landed,
+                window_attempts: entry.recent.len(),
+                window_landed: entry.window_landed(),
+                disabled: entry.disabled_until.is_some_and(|until| now < until),
+            })
+            .collect();
+        out.sort_by_key(|s| s.swqos_type.as_str());
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn watchdog() -> SwqosWatchdog {
+        SwqosWatchdog::new(SwqosWatchdogConfig {
+            window: 10,
+            min_samples: 5,
+            min_landing_rate: 0.2,
+            cooldown: Duration::from_secs(60),
+        })
+    }
+
+    #[test]
+    fn endpoint_that_never_lands_is_disabled_then_reprobed() {
+        let wd = watchdog();
+        let t0 = Instant::now();
+        for _ in 0..4 {
+            wd.record_at(SwqosType::Jito, false, t0);
+            wd.record_at(SwqosType::Helius, true, t0);
+        }
+        assert!(wd.is_enabled_at(SwqosType::Jito, t0));
+
+        wd.record_at(SwqosType::Jito, false, t0);
+        assert!(!wd.is_enabled_at(SwqosType::Jito, t0 + Duration::from_secs(1)));
+        assert!(wd.is_enabled_at(SwqosType::Helius, t0 + Duration::from_secs(1)));
+
+        assert!(wd.is_enabled_at(SwqosType::Jito, t0 + Duration::from_secs(61)));
+        let jito = wd.stats().into_iter().find(|s| s.swqos_type == SwqosType::Jito).unwrap();
+        assert_eq!((jito.attempts, jito.landed, jito.window_attempts), (5, 0, 0));
+        assert!(!jito.disabled);
+    }
+
+    #[test]
+    fn default_rpc_route_is_never_disabled() {
+        let wd = watchdog();
+        let t0 = Instant::now();
+        for _ in 0..10 {
+            wd.record_at(SwqosType::Default, false, t0);
+        }
+        assert!(wd.is_enabled_at(SwqosType::Default, t0));
+    }
+}
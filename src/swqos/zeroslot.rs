@@ -1,4 +1,7 @@
-use crate::swqos::common::{default_http_client_builder, poll_transaction_confirmation};
+use crate::swqos::common::{
+    default_http_client_builder, parse_submit_response, poll_transaction_confirmation,
+    SubmitResponse,
+};
 use bincode;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -148,11 +151,7 @@ impl ZeroSlotClient {
         // This is faster than JSON-RPC as it avoids unnecessary encoding/decoding
         let tx_bytes = bincode::serialize(transaction)?;
 
-        // Build URL for Binary-Tx endpoint: {endpoint}/txb?api-key={auth_token}
-        let mut url = String::with_capacity(self.endpoint.len() + self.auth_token.len() + 20);
-        url.push_str(&self.endpoint);
-        url.push_str("/txb?api-key=");
-        url.push_str(&self.auth_token);
+        let url = txb_url(&self.endpoint, &self.auth_token);
 
         // Send binary transaction directly
         let response = self
@@ -172,53 +171,40 @@ impl ZeroSlotClient {
         // 419: rate limit exceeded
         // 500: submission failed
         match status.as_u16() {
-            200 => {
-                if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(&response_text) {
-                    if json_value.get("result").is_some() {
-                        crate::common::sdk_log::log_swqos_submitted(
-                            "0slot",
-                            trade_type,
-                            start_time.elapsed(),
-                        );
-                    } else if let Some(error) = json_value.get("error") {
-                        let code = error
-                            .get("code")
-                            .and_then(|c| c.as_i64())
-                            .map(|c| c.to_string())
-                            .unwrap_or_else(|| "unknown".to_string());
-                        let message = error
-                            .get("message")
-                            .and_then(|m| m.as_str())
-                            .unwrap_or("unknown error");
-                        crate::common::sdk_log::log_swqos_submission_failed(
-                            "0slot",
-                            trade_type,
-                            start_time.elapsed(),
-                            format!("code {}: {}", code, message),
-                        );
-                        return Err(anyhow::anyhow!("0slot Binary-Tx error: {}", message));
-                    } else {
-                        crate::common::sdk_log::log_swqos_submission_failed(
-                            "0slot",
-                            trade_type,
-                            start_time.elapsed(),
-                            format!("unexpected JSON: {}", response_text),
-                        );
-                        return Err(anyhow::anyhow!(
-                            "0slot Binary-Tx unexpected JSON: {}",
-                            response_text
-                        ));
-                    }
-                } else {
+            200 => match parse_submit_response("0slot", &response_text, &["result"]) {
+                Ok(SubmitResponse::Accepted) => {
+                    crate::common::sdk_log::log_swqos_submitted(
+                        "0slot",
+                        trade_type,
+                        start_time.elapsed(),
+                    );
+                }
+                Ok(SubmitResponse::Rejected(error)) => {
+                    let code = error
+                        .get("code")
+                        .and_then(|c| c.as_i64())
+                        .map(|c| c.to_string())
+                        .unwrap_or_else(|| "unknown".to_string());
+                    let message =
+                        error.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error");
                     crate::common::sdk_log::log_swqos_submission_failed(
                         "0slot",
                         trade_type,
                         start_time.elapsed(),
-                        format!("invalid JSON: {}", response_text),
+                        format!("code {}: {}", code, message),
                     );
-                    return Err(anyhow::anyhow!("0slot Binary-Tx invalid JSON: {}", response_text));
+                    return Err(anyhow::anyhow!("0slot Binary-Tx error: {}", message));
                 }
-            }
+                Err(e) => {
+                    crate::common::sdk_log::log_swqos_submission_failed(
+                        "0slot",
+                        trade_type,
+                        start_time.elapsed(),
+                        &e,
+                    );
+                    return Err(e.into());
+                }
+            },
             403 => {
                 crate::common::sdk_log::log_swqos_submission_failed(
                     "0slot",
@@ -323,3 +309,35 @@ impl Drop for ZeroSlotClient {
         });
     }
 }
+
+/// Binary-Tx endpoint: `{endpoint}/txb?api-key={auth_token}`; the body is the raw bincode tx.
+fn txb_url(endpoint: &str, auth_token: &str) -> String {
+    let mut url = String::with_capacity(endpoint.len() + auth_token.len() + 20);
+    url.push_str(endpoint);
+    url.push_str("/txb?api-key=");
+    url.push_str(auth_token);
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swqos::common::TradeError;
+
+    #[test]
+    fn binary_tx_request_and_response_schema() {
+        assert_eq!(
+            txb_url("http://ny.0slot.trade", "key"),
+            "http://ny.0slot.trade/txb?api-key=key"
+        );
+
+        let ok = r#"{"jsonrpc":"2.0","result":"5h3c","id":1}"#;
+        assert!(matches!(
+            parse_submit_response("0slot", ok, &["result"]),
+            Ok(SubmitResponse::Accepted)
+        ));
+        let drifted = r#"{"jsonrpc":"2.0","signature":"5h3c"}"#;
+        let err = parse_submit_response("0slot", drifted, &["result"]).unwrap_err();
+        assert_eq!(err.code, TradeError::SWQOS_PROTOCOL_ERROR);
+    }
+}
@@ -0,0 +1,743 @@
+//! Deterministic keys, pool accounts and params for offline tests (feature `test-util`).
+//! 离线测试用的确定性密钥、池账户与参数。
+//!
+//! Pool fixtures are account bytes in each program's on-chain layout (`*_account_data`); the
+//! `*_pool` helpers decode them with the SDK's own decoders, so a layout change breaks the
+//! fixtures the same way it would break mainnet loading. `*_params` build valid protocol params
+//! from the decoded pools, and [`swap_params`] wraps any of them for the instruction builders.
+//! Serve the account bytes through [`MockSolanaRpcClient`](crate::common::MockSolanaRpcClient)
+//! (with [`mock_account`](crate::common::mock_rpc::mock_account)) to exercise the RPC loaders.
+//!
+//! Every fixture shares one token [`MINT`] traded against WSOL, reserves [`BASE_RESERVE`] /
+//! [`QUOTE_RESERVE`], and the payer [`payer`]. Executor tests submit [`pumpswap_submit_params`]
+//! to a [`RecordingSwqos`] and swap the real builder for a [`DirectionProbe`].
+
+use std::sync::Arc;
+
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, transaction::VersionedTransaction};
+
+use crate::common::bonding_curve::BondingCurveAccount;
+use crate::common::mock_rpc::{mock_account, MockSolanaRpcClient};
+use crate::common::{GasFeeStrategy, ParallelStrategy};
+use crate::constants::{TOKEN_PROGRAM, WSOL_TOKEN_ACCOUNT};
+use crate::instruction::utils::{
+    bonk_types, meteora_damm_v2_types, pumpswap, pumpswap_types, raydium_amm_v4_types,
+    raydium_cpmm_types,
+};
+use crate::swqos::{SwqosClient, SwqosClientTrait, SwqosType, TradeType};
+use crate::trading::common::memo::build_memo_instruction;
+use crate::trading::core::params::{
+    AtaCreationMode, BonkParams, DexParamEnum, MeteoraDammV2Params, PumpFunParams, PumpSwapParams,
+    RaydiumAmmV4Params, RaydiumCpmmParams, SwapParams,
+};
+use crate::trading::InstructionBuilder;
+
+/// Deterministic address: 32 copies of `seed`.
+pub const fn pubkey(seed: u8) -> Pubkey {
+    Pubkey::new_from_array([seed; 32])
+}
+
+/// Deterministic keypair from a 32-byte secret of `seed`; same seed, same address.
+pub fn keypair(seed: u8) -> Keypair {
+    Keypair::new_from_array([seed; 32])
+}
+
+/// Seed of [`payer`].
+pub const PAYER_SEED: u8 = 0xFA;
+
+/// The signer every fixture trade uses.
+pub fn payer() -> Arc<Keypair> {
+    Arc::new(keypair(PAYER_SEED))
+}
+
+/// Traded token (6 decimals, legacy SPL Token).
+pub const MINT: Pubkey = pubkey(0x11);
+/// Pool / bonding-curve account address.
+pub const POOL: Pubkey = pubkey(0x20);
+pub const BASE_VAULT: Pubkey = pubkey(0x21);
+pub const QUOTE_VAULT: Pubkey = pubkey(0x22);
+pub const LP_MINT: Pubkey = pubkey(0x23);
+/// Whoever created the pool (for PumpSwap: the migration authority, not the coin creator).
+pub const POOL_CREATOR: Pubkey = pubkey(0x30);
+/// Creator the protocol pays creator fees to; differs from [`POOL_CREATOR`] on purpose.
+pub const COIN_CREATOR: Pubkey = pubkey(0x31);
+/// Raydium CPMM amm config / Bonk global config.
+pub const CONFIG: Pubkey = pubkey(0x40);
+/// Raydium CPMM observation state / Bonk platform config.
+pub const AUX: Pubkey = pubkey(0x41);
+/// Tip account [`RecordingSwqos`] reports.
+pub const TIP_ACCOUNT: Pubkey = pubkey(0x60);
+
+/// Token base units in every pool (1M tokens at 6 decimals).
+pub const BASE_RESERVE: u64 = 1_000_000_000_000;
+/// Quote base units in every pool (30 SOL).
+pub const QUOTE_RESERVE: u64 = 30_000_000_000;
+
+/// Little-endian account writer in Borsh field order.
+struct Layout(Vec<u8>);
+
+impl Layout {
+    fn new(discriminator: [u8; 8]) -> Self {
+        Self(discriminator.to_vec())
+    }
+
+    fn raw() -> Self {
+        Self(Vec::new())
+    }
+
+    fn bytes(mut self, v: &[u8]) -> Self {
+        self.0.extend_from_slice(v);
+        self
+    }
+
+    fn zeros(self, n: usize) -> Self {
+        self.bytes(&vec![0; n])
+    }
+
+    fn u8(self, v: u8) -> Self {
+        self.bytes(&[v])
+    }
+
+    fn u64(self, v: u64) -> Self {
+        self.bytes(&v.to_le_bytes())
+    }
+
+    fn u128(self, v: u128) -> Self {
+        self.bytes(&v.to_le_bytes())
+    }
+
+    fn key(self, v: Pubkey) -> Self {
+        self.bytes(v.as_ref())
+    }
+
+    /// Zero-pad to `len` bytes (trailing padding fields).
+    fn finish(mut self, len: usize) -> Vec<u8> {
+        assert!(self.0.len() <= len, "fixture layout overflows {} bytes", len);
+        self.0.resize(len, 0);
+        self.0
+    }
+}
+
+// ---------------------------------------------------------------------------------------------
+// PumpFun
+// ---------------------------------------------------------------------------------------------
+
+/// Bonding-curve account (8-byte discriminator + `BondingCurveAccount`), not complete.
+pub fn pumpfun_curve_account_data() -> Vec<u8> {
+    Layout::new([0; 8])
+        .u64(BASE_RESERVE) // virtual_token_reserves
+        .u64(QUOTE_RESERVE) // virtual_sol_reserves
+        .u64(BASE_RESERVE - 200_000_000_000) // real_token_reserves
+        .u64(0) // real_sol_reserves
+        .u64(1_000_000_000_000_000) // token_total_supply
+        .u8(0) // complete
+        .key(COIN_CREATOR)
+        .u8(0) // is_mayhem_mode
+        .u8(0) // is_cashback_coin
+        .key(WSOL_TOKEN_ACCOUNT)
+        .finish(8 + 8 * 5 + 1 + 32 + 2 + 32)
+}
+
+pub fn pumpfun_curve() -> BondingCurveAccount {
+    use borsh::BorshDeserialize;
+    let mut curve = BondingCurveAccount::deserialize(&mut &pumpfun_curve_account_data()[8..])
+        .expect("pumpfun curve fixture decodes");
+    curve.account = crate::instruction::utils::pumpfun::get_bonding_curve_pda(&MINT)
+        .expect("bonding curve PDA");
+    curve
+}
+
+pub fn pumpfun_params() -> PumpFunParams {
+    let curve = pumpfun_curve();
+    let creator_vault = crate::instruction::utils::pumpfun::get_creator_vault_pda(&curve.creator)
+        .expect("creator vault PDA");
+    PumpFunParams {
+        associated_bonding_curve:
+            crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
+                &curve.account,
+                &MINT,
+                &TOKEN_PROGRAM,
+            ),
+        bonding_curve: Arc::new(curve),
+        observed_trade_creator: None,
+        creator_vault,
+        fee_sharing_creator_vault_if_active: None,
+        token_program: TOKEN_PROGRAM,
+        close_token_account_when_sell: None,
+        fee_recipient: Pubkey::default(),
+        quote_mint: WSOL_TOKEN_ACCOUNT,
+        layout: Default::default(),
+    }
+}
+
+// ---------------------------------------------------------------------------------------------
+// PumpSwap
+// ---------------------------------------------------------------------------------------------
+
+/// Canonical MINT/WSOL pool whose `coin_creator` ([`COIN_CREATOR`]) differs from its `creator`
+/// ([`POOL_CREATOR`]), as for every pool migrated from PumpFun.
+pub fn pumpswap_pool_account_data() -> Vec<u8> {
+    Layout::new(pumpswap_types::POOL_DISCRIMINATOR)
+        .u8(255) // pool_bump
+        .bytes(&0u16.to_le_bytes()) // index
+        .key(POOL_CREATOR)
+        .key(MINT)
+        .key(WSOL_TOKEN_ACCOUNT)
+        .key(LP_MINT)
+        .key(BASE_VAULT)
+        .key(QUOTE_VAULT)
+        .u64(1_000_000) // lp_supply
+        .key(COIN_CREATOR)
+        .u8(0) // is_mayhem_mode
+        .u8(0) // is_cashback_coin
+        .bytes(&0i128.to_le_bytes()) // virtual_quote_reserves
+        .finish(8 + pumpswap_types::POOL_SIZE)
+}
+
+pub fn pumpswap_pool() -> pumpswap_types::Pool {
+    pumpswap_types::pool_decode(&pumpswap_pool_account_data()[8..])
+        .expect("pumpswap pool fixture decodes")
+}
+
+/// Params for [`pumpswap_pool`] with the creator vault derived from `coin_creator`, the way
+/// `from_pool_data` does.
+pub fn pumpswap_params() -> PumpSwapParams {
+    let pool = pumpswap_pool();
+    pumpswap_params_with_creator_vault_of(&pool, pool.coin_creator)
+}
+
+/// Creator-vault bug: the vault derived from the pool's `creator` instead of its `coin_creator`.
+/// Builds fine, but the swap fails on-chain with a seeds constraint on
+/// `coin_creator_vault_authority`; [`PumpSwapParams::debug_pdas`] flags both vault entries.
+pub fn pumpswap_params_with_pool_creator_vault() -> PumpSwapParams {
+    let pool = pumpswap_pool();
+    pumpswap_params_with_creator_vault_of(&pool, pool.creator)
+}
+
+fn pumpswap_params_with_creator_vault_of(
+    pool: &pumpswap_types::Pool,
+    vault_owner: Pubkey,
+) -> PumpSwapParams {
+    let (vault_authority, vault_ata) =
+        pumpswap::coin_creator_vault_accounts(vault_owner, pool.quote_mint, TOKEN_PROGRAM);
+    PumpSwapParams::new(
+        POOL,
+        pool.base_mint,
+        pool.quote_mint,
+        pool.pool_base_token_account,
+        pool.pool_quote_token_account,
+        BASE_RESERVE,
+        QUOTE_RESERVE,
+        pool.virtual_quote_reserves,
+        vault_ata,
+        vault_authority,
+        TOKEN_PROGRAM,
+        TOKEN_PROGRAM,
+        pumpswap::accounts::PROTOCOL_FEE_RECIPIENT,
+        pool.coin_creator,
+        pool.is_cashback_coin,
+        0,
+    )
+    .with_pool_creator(pool.creator)
+}
+
+/// Responses for one uncached `PumpSwapParams::from_pool_address_by_rpc(&POOL)`: the pool,
+/// both vaults holding [`BASE_RESERVE`] / [`QUOTE_RESERVE`] plus the mint, and no fee config
+/// (default bps). Merge into a larger mock or `build()` it.
+pub fn pumpswap_pool_load_rpc() -> MockSolanaRpcClient {
+    let pool = pumpswap_pool();
+    let vault = |mint: &Pubkey, amount: u64| {
+        let mut data = vec![0u8; 165];
+        data[..32].copy_from_slice(mint.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data[108] = 1; // initialized
+        mock_account(TOKEN_PROGRAM, data)
+    };
+    let mut mint = vec![0u8; 82];
+    mint[36..44].copy_from_slice(&1_000_000_000_000_000u64.to_le_bytes());
+    mint[45] = 1; // initialized
+    MockSolanaRpcClient::new()
+        .with_account(Some(&mock_account(
+            pumpswap::accounts::AMM_PROGRAM,
+            pumpswap_pool_account_data(),
+        )))
+        .with_multiple_accounts(&[
+            Some(&vault(&pool.base_mint, BASE_RESERVE)),
+            Some(&vault(&pool.quote_mint, QUOTE_RESERVE)),
+            Some(&mock_account(TOKEN_PROGRAM, mint)),
+        ])
+        .with_account(None)
+}
+
+// ---------------------------------------------------------------------------------------------
+// Raydium CPMM
+// ---------------------------------------------------------------------------------------------
+
+/// WSOL/MINT pool with WSOL as token0.
+pub fn raydium_cpmm_pool_account_data() -> Vec<u8> {
+    Layout::new([0; 8])
+        .key(CONFIG) // amm_config
+        .key(POOL_CREATOR)
+        .key(QUOTE_VAULT) // token0_vault (WSOL)
+        .key(BASE_VAULT) // token1_vault (MINT)
+        .key(LP_MINT)
+        .key(WSOL_TOKEN_ACCOUNT) // token0_mint
+        .key(MINT) // token1_mint
+        .key(TOKEN_PROGRAM)
+        .key(TOKEN_PROGRAM)
+        .key(AUX) // observation_key
+        .bytes(&[255, 0, 9, 9, 6]) // auth_bump, status, lp/mint0/mint1 decimals
+        .finish(8 + raydium_cpmm_types::POOL_STATE_SIZE)
+}
+
+pub fn raydium_cpmm_pool() -> raydium_cpmm_types::PoolState {
+    raydium_cpmm_types::pool_state_decode(&raydium_cpmm_pool_account_data()[8..])
+        .expect("raydium cpmm pool fixture decodes")
+}
+
+pub fn raydium_cpmm_params() -> RaydiumCpmmParams {
+    let pool = raydium_cpmm_pool();
+    let mut params = RaydiumCpmmParams::from_trade(
+        POOL,
+        pool.amm_config,
+        pool.token0_mint,
+        pool.token1_mint,
+        pool.token0_vault,
+        pool.token1_vault,
+        pool.token0_program,
+        pool.token1_program,
+        pool.observation_key,
+        QUOTE_RESERVE,
+        BASE_RESERVE,
+    );
+    params.pool_status = Some(pool.status);
+    params
+}
+
+// ---------------------------------------------------------------------------------------------
+// Raydium AMM v4
+// ---------------------------------------------------------------------------------------------
+
+/// Serum/OpenBook accounts of the AMM v4 fixture, `pubkey(0x50..=0x59)` in
+/// `with_market_accounts` order.
+pub const fn raydium_amm_v4_market_account(index: u8) -> Pubkey {
+    pubkey(0x50 + index)
+}
+
+/// MINT (coin) / WSOL (pc) AMM; `AmmInfo` has no Anchor discriminator.
+pub fn raydium_amm_v4_account_data() -> Vec<u8> {
+    Layout::raw()
+        .u64(6) // status: swap enabled
+        .u64(255) // nonce
+        .zeros(8 * 2) // order_num, depth
+        .u64(6) // coin_decimals
+        .u64(9) // pc_decimals
+        .zeros(8 * 10) // state .. sys_decimal_value
+        .zeros(8 * 8) // fees
+        .zeros(8 * 8 + 16 * 2 + 8 + 16 * 2 + 8) // out_put
+        .key(BASE_VAULT) // token_coin
+        .key(QUOTE_VAULT) // token_pc
+        .key(MINT) // coin_mint
+        .key(WSOL_TOKEN_ACCOUNT) // pc_mint
+        .key(LP_MINT)
+        .key(raydium_amm_v4_market_account(0)) // open_orders
+        .key(raydium_amm_v4_market_account(3)) // market
+        .key(raydium_amm_v4_market_account(2)) // serum_dex
+        .key(raydium_amm_v4_market_account(1)) // target_orders
+        .zeros(32 * 2) // withdraw_queue, token_temp_lp
+        .key(POOL_CREATOR) // amm_owner
+        .finish(raydium_amm_v4_types::AMM_INFO_SIZE)
+}
+
+pub fn raydium_amm_v4_amm() -> raydium_amm_v4_types::AmmInfo {
+    raydium_amm_v4_types::amm_info_decode(&raydium_amm_v4_account_data())
+        .expect("raydium amm v4 fixture decodes")
+}
+
+pub fn raydium_amm_v4_params() -> RaydiumAmmV4Params {
+    let amm = raydium_amm_v4_amm();
+    RaydiumAmmV4Params::new(
+        POOL,
+        amm.coin_mint,
+        amm.pc_mint,
+        amm.token_coin,
+        amm.token_pc,
+        BASE_RESERVE,
+        QUOTE_RESERVE,
+    )
+    .with_market_accounts(
+        amm.open_orders,
+        amm.target_orders,
+        amm.serum_dex,
+        amm.market,
+        raydium_amm_v4_market_account(4),
+        raydium_amm_v4_market_account(5),
+        raydium_amm_v4_market_account(6),
+        raydium_amm_v4_market_account(7),
+        raydium_amm_v4_market_account(8),
+        raydium_amm_v4_market_account(9),
+    )
+}
+
+// ---------------------------------------------------------------------------------------------
+// Bonk
+// ---------------------------------------------------------------------------------------------
+
+/// MINT/WSOL launchpad pool still on its curve (status 0 = fund).
+pub fn bonk_pool_account_data() -> Vec<u8> {
+    Layout::new([0; 8])
+        .u64(0) // epoch
+        .bytes(&[255, 0, 6, 9, 0]) // auth_bump, status, base/quote decimals, migrate_type
+        .u64(1_000_000_000_000_000) // supply
+        .u64(793_100_000_000_000) // total_base_sell
+        .u64(1_073_025_605_596_382) // virtual_base
+        .u64(QUOTE_RESERVE) // virtual_quote
+        .u64(BASE_RESERVE) // real_base
+        .u64(1_000_000_000) // real_quote
+        .u64(85_000_000_000) // total_quote_fund_raising
+        .zeros(8 * 3) // quote_protocol_fee, platform_fee, migrate_fee
+        .zeros(8 * 5) // vesting_schedule
+        .key(CONFIG) // global_config
+        .key(AUX) // platform_config
+        .key(MINT)
+        .key(WSOL_TOKEN_ACCOUNT)
+        .key(BASE_VAULT)
+        .key(QUOTE_VAULT)
+        .key(COIN_CREATOR)
+        .finish(8 + bonk_types::POOL_STATE_SIZE)
+}
+
+pub fn bonk_pool() -> bonk_types::PoolState {
+    bonk_types::pool_state_decode(&bonk_pool_account_data()[8..])
+        .expect("bonk pool fixture decodes")
+}
+
+pub fn bonk_params() -> BonkParams {
+    use crate::instruction::utils::bonk::{
+        get_creator_associated_account, get_platform_associated_account,
+    };
+    let pool = bonk_pool();
+    BonkParams::from_trade(
+        pool.virtual_base,
+        pool.virtual_quote,
+        pool.real_base,
+        pool.real_quote,
+        POOL,
+        pool.base_vault,
+        pool.quote_vault,
+        TOKEN_PROGRAM,
+        pool.platform_config,
+        get_platform_associated_account(&pool.platform_config).expect("platform PDA"),
+        get_creator_associated_account(&pool.creator).expect("creator PDA"),
+        pool.global_config,
+    )
+}
+
+// ---------------------------------------------------------------------------------------------
+// Meteora DAMM v2
+// ---------------------------------------------------------------------------------------------
+
+/// MINT (token A) / WSOL (token B) pool, enabled; fees and rewards zeroed.
+pub fn meteora_damm_v2_pool_account_data() -> Vec<u8> {
+    Layout::new([0; 8])
+        .zeros(160) // pool_fees
+        .key(MINT) // token_a_mint
+        .key(WSOL_TOKEN_ACCOUNT) // token_b_mint
+        .key(BASE_VAULT) // token_a_vault
+        .key(QUOTE_VAULT) // token_b_vault
+        .zeros(32 * 2) // whitelisted_vault, partner
+        .u128(1 << 64) // liquidity
+        .zeros(16 + 8 * 4) // padding, protocol/partner fees
+        .u128(4_295_048_016) // sqrt_min_price
+        .u128(79_226_673_521_066_979_257_578_248_091) // sqrt_max_price
+        .u128(1 << 62) // sqrt_price
+        .u64(0) // activation_point
+        .u8(0) // activation_type
+        .u8(0) // pool_status: enabled
+        .finish(8 + meteora_damm_v2_types::POOL_SIZE)
+}
+
+pub fn meteora_damm_v2_pool() -> meteora_damm_v2_types::Pool {
+    meteora_damm_v2_types::pool_decode(&meteora_damm_v2_pool_account_data()[8..])
+        .expect("meteora damm v2 pool fixture decodes")
+}
+
+pub fn meteora_damm_v2_params() -> MeteoraDammV2Params {
+    let pool = meteora_damm_v2_pool();
+    let mut params = MeteoraDammV2Params::new(
+        POOL,
+        pool.token_a_vault,
+        pool.token_b_vault,
+        pool.token_a_mint,
+        pool.token_b_mint,
+        TOKEN_PROGRAM,
+        TOKEN_PROGRAM,
+    );
+    params.pool_status = Some(pool.pool_status);
+    params
+}
+
+// ---------------------------------------------------------------------------------------------
+// Swap params
+// ---------------------------------------------------------------------------------------------
+
+/// Offline [`SwapParams`] for `protocol_params`: [`payer`], no RPC, no SWQOS routes, simulate
+/// on, ATAs neither created nor closed. A buy spends `amount` WSOL on [`MINT`], a sell sells
+/// `amount` of [`MINT`] for WSOL; adjust fields as the test needs.
+pub fn swap_params(
+    protocol_params: DexParamEnum,
+    trade_type: TradeType,
+    amount: u64,
+) -> SwapParams {
+    let (input_mint, output_mint) = match trade_type {
+        TradeType::Sell => (MINT, WSOL_TOKEN_ACCOUNT),
+        _ => (WSOL_TOKEN_ACCOUNT, MINT),
+    };
+    SwapParams {
+        rpc: None,
+        payer: payer(),
+        trade_type,
+        input_mint,
+        input_token_program: None,
+        output_mint,
+        output_token_program: None,
+        input_amount: Some(amount),
+        slippage_basis_points: Some(100),
+        address_lookup_table_accounts: Vec::new(),
+        recent_blockhash: None,
+        wait_tx_confirmed: false,
+        protocol_params,
+        open_seed_optimize: false,
+        swqos_clients: Arc::new(Vec::new()),
+        middleware_manager: None,
+        durable_nonce: None,
+        with_tip: false,
+        create_input_mint_ata: false,
+        close_input_mint_ata: false,
+        create_output_mint_ata: false,
+        close_output_mint_ata: false,
+        fixed_output_amount: None,
+        gas_fee_strategy: GasFeeStrategy::new(),
+        simulate: true,
+        log_enabled: false,
+        wait_for_all_submits: false,
+        use_dedicated_sender_threads: false,
+        sender_thread_cores: None,
+        max_sender_concurrency: 0,
+        effective_core_ids: Arc::new(Vec::new()),
+        check_min_tip: false,
+        grpc_recv_us: None,
+        use_exact_sol_amount: None,
+        swqos_watchdog: None,
+        ata_creation: AtaCreationMode::Inline,
+        memo: None,
+        parallel_strategy: ParallelStrategy::RaceAll,
+        max_ata_creations: None,
+        cu_learner: None,
+        rpc_fallback: false,
+        cleanup_on_failure: false,
+        skip_compute_budget: false,
+        simulation_capture: None,
+        ata_rent_funder: None,
+        receive_native_sol: false,
+        on_before_send: None,
+        multi_nonce: None,
+        min_tokens_out: None,
+    }
+}
+
+/// [`pumpswap_params`] buy of 100_000 lamports through `rpc` (built here) for executor tests:
+/// the mock's latest blockhash, one fee config (150_000 CU at 1_000 micro-lamports, no tip),
+/// WSOL wrapped and closed, the output ATA created. Still simulates; use
+/// [`pumpswap_submit_params`] to submit through SWQOS routes.
+pub async fn pumpswap_buy_params(rpc: MockSolanaRpcClient) -> SwapParams {
+    let rpc = Arc::new(rpc.build());
+    let mut params =
+        swap_params(DexParamEnum::PumpSwap(pumpswap_params()), TradeType::Buy, 100_000);
+    params.recent_blockhash = Some(rpc.get_latest_blockhash().await.expect("mock blockhash"));
+    params.rpc = Some(rpc);
+    params.gas_fee_strategy.set_global_fee_strategy(150_000, 150_000, 1_000, 1_000, 0.0, 0.0);
+    params.create_input_mint_ata = true;
+    params.close_input_mint_ata = true;
+    params.create_output_mint_ata = true;
+    params.use_exact_sol_amount = Some(true);
+    params
+}
+
+/// [`pumpswap_buy_params`] against a mock that only serves a blockhash, submitted instead of
+/// simulated: a 0.001 SOL tip on both sides so every route in `routes` gets a tip transfer.
+pub async fn pumpswap_submit_params(routes: Vec<Arc<SwqosClient>>) -> SwapParams {
+    let rpc = MockSolanaRpcClient::new()
+        .with_latest_blockhash(solana_hash::Hash::new_from_array([3; 32]), 1_000);
+    let mut params = pumpswap_buy_params(rpc).await;
+    params.simulate = false;
+    params.gas_fee_strategy.set_global_fee_strategy(150_000, 150_000, 1_000, 1_000, 0.001, 0.001);
+    params.swqos_clients = Arc::new(routes);
+    params
+}
+
+// ---------------------------------------------------------------------------------------------
+// Test doubles
+// ---------------------------------------------------------------------------------------------
+
+/// SWQOS route that accepts and keeps every transaction.
+pub struct RecordingSwqos {
+    sent: parking_lot::Mutex<Vec<VersionedTransaction>>,
+    swqos_type: SwqosType,
+}
+
+impl RecordingSwqos {
+    pub fn new(swqos_type: SwqosType) -> Arc<Self> {
+        Arc::new(Self { sent: parking_lot::Mutex::new(Vec::new()), swqos_type })
+    }
+
+    /// Transactions submitted so far, in order.
+    pub fn sent(&self) -> Vec<VersionedTransaction> {
+        self.sent.lock().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl SwqosClientTrait for RecordingSwqos {
+    async fn send_transaction(
+        &self,
+        _trade_type: TradeType,
+        transaction: &VersionedTransaction,
+        _wait_confirmation: bool,
+    ) -> anyhow::Result<()> {
+        self.sent.lock().push(transaction.clone());
+        Ok(())
+    }
+    async fn send_transactions(
+        &self,
+        _trade_type: TradeType,
+        transactions: &Vec<VersionedTransaction>,
+        _wait_confirmation: bool,
+    ) -> anyhow::Result<()> {
+        self.sent.lock().extend(transactions.iter().cloned());
+        Ok(())
+    }
+    fn get_tip_account(&self) -> anyhow::Result<String> {
+        Ok(TIP_ACCOUNT.to_string())
+    }
+    fn get_swqos_type(&self) -> SwqosType {
+        self.swqos_type
+    }
+}
+
+/// Instruction builder that emits one memo and records which side the executor asked for
+/// (`"buy"` or `"sell"`).
+#[derive(Default)]
+pub struct DirectionProbe {
+    calls: parking_lot::Mutex<Vec<&'static str>>,
+}
+
+impl DirectionProbe {
+    pub fn new() -> Arc<Self> {
+        Arc::default()
+    }
+
+    pub fn calls(&self) -> Vec<&'static str> {
+        self.calls.lock().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl InstructionBuilder for DirectionProbe {
+    async fn build_buy_instructions(
+        &self,
+        _params: &SwapParams,
+    ) -> anyhow::Result<Vec<solana_sdk::instruction::Instruction>> {
+        self.calls.lock().push("buy");
+        Ok(vec![build_memo_instruction("buy")?])
+    }
+    async fn build_sell_instructions(
+        &self,
+        _params: &SwapParams,
+    ) -> anyhow::Result<Vec<solana_sdk::instruction::Instruction>> {
+        self.calls.lock().push("sell");
+        Ok(vec![build_memo_instruction("sell")?])
+    }
+}
+
+// ---------------------------------------------------------------------------------------------
+// Transactions
+// ---------------------------------------------------------------------------------------------
+
+/// A v0 transaction moving one lamport from [`payer`] to [`POOL`], signed by [`payer`]: the
+/// input for tests of what SWQOS clients put on the wire.
+pub fn signed_transaction() -> solana_sdk::transaction::VersionedTransaction {
+    use solana_sdk::signer::Signer;
+
+    let payer = payer();
+    let transfer = solana_system_interface::instruction::transfer(&payer.pubkey(), &POOL, 1);
+    let message = solana_message::v0::Message::try_compile(
+        &payer.pubkey(),
+        &[transfer],
+        &[],
+        solana_hash::Hash::new_from_array([7; 32]),
+    )
+    .expect("transfer message compiles");
+    solana_sdk::transaction::VersionedTransaction::try_new(
+        solana_message::VersionedMessage::V0(message),
+        &[payer.as_ref()],
+    )
+    .expect("payer signs the transfer")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::{
+        pumpswap::PumpSwapInstructionBuilder, raydium_amm_v4::RaydiumAmmV4InstructionBuilder,
+        raydium_cpmm::RaydiumCpmmInstructionBuilder,
+    };
+    use crate::trading::InstructionBuilder;
+    use solana_sdk::signer::Signer;
+
+    #[test]
+    fn fixtures_decode_and_validate() {
+        assert_eq!(keypair(PAYER_SEED).pubkey(), payer().pubkey());
+        assert_eq!(pumpfun_curve().creator, COIN_CREATOR);
+        assert_eq!(pumpswap_pool().coin_creator, COIN_CREATOR);
+        assert_eq!(raydium_cpmm_pool().token1_mint, MINT);
+        assert_eq!(raydium_amm_v4_amm().coin_mint, MINT);
+        assert_eq!(bonk_pool().base_mint, MINT);
+        assert_eq!(meteora_damm_v2_pool().token_a_mint, MINT);
+        for params in [
+            DexParamEnum::PumpFun(pumpfun_params()),
+            DexParamEnum::PumpSwap(pumpswap_params()),
+            DexParamEnum::RaydiumCpmm(raydium_cpmm_params()),
+            DexParamEnum::RaydiumAmmV4(raydium_amm_v4_params()),
+            DexParamEnum::Bonk(bonk_params()),
+            DexParamEnum::MeteoraDammV2(meteora_damm_v2_params()),
+        ] {
+            assert!(params.validate().is_ok(), "{:?} fixture invalid", params.dex_type());
+        }
+    }
+
+    #[test]
+    fn pumpswap_creator_vault_fixture_is_flagged() {
+        let payer = payer().pubkey();
+        assert!(pumpswap_params().debug_pdas(payer).mismatches().is_empty());
+        assert_eq!(
+            pumpswap_params_with_pool_creator_vault().debug_pdas(payer).mismatches(),
+            vec!["coin_creator_vault_authority", "coin_creator_vault_ata"]
+        );
+    }
+
+    #[tokio::test]
+    async fn pool_fixtures_build_buys_offline() {
+        for (builder, params) in [
+            (
+                &PumpSwapInstructionBuilder as &dyn InstructionBuilder,
+                DexParamEnum::PumpSwap(pumpswap_params()),
+            ),
+            (&RaydiumCpmmInstructionBuilder, DexParamEnum::RaydiumCpmm(raydium_cpmm_params())),
+            (&RaydiumAmmV4InstructionBuilder, DexParamEnum::RaydiumAmmV4(raydium_amm_v4_params())),
+        ] {
+            let swap = swap_params(params, TradeType::Buy, 100_000_000);
+            assert!(builder.build_buy_instructions(&swap).await.is_ok());
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Refresh caller-supplied `recent_blockhash` values that are too old or no longer valid, so a
+//! cached hash does not fail with `BlockhashNotFound`. 过期的 recent_blockhash 自动刷新。
+//!
+//! A hash's age is measured from when this guard first saw it (passed into a trade, fetched by
+//! the guard, or reported via [`BlockhashGuard::observe`]), which is a lower bound of its real
+//! age. Durable-nonce trades are never touched.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use solana_commitment_config::CommitmentConfig;
+use solana_hash::Hash;
+
+use crate::common::SolanaRpcClient;
+
+/// First-seen entries kept before old ones are pruned.
+const MAX_TRACKED_BLOCKHASHES: usize = 256;
+
+/// When to replace a caller's blockhash with a fresh one. 刷新策略。
+#[derive(Debug, Clone, Copy)]
+pub struct BlockhashRefreshConfig {
+    /// Refresh without asking RPC once the hash is at least this old (a blockhash expires
+    /// after ~150 slots, roughly 60s). Default 45s.
+    pub max_age: Duration,
+    /// Ask `isBlockhashValid` for younger hashes (one RPC round trip per trade). Default true.
+    pub check_validity: bool,
+}
+
+impl Default for BlockhashRefreshConfig {
+    fn default() -> Self {
+        Self { max_age: Duration::from_secs(45), check_validity: true }
+    }
+}
+
+impl BlockhashRefreshConfig {
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    pub fn with_check_validity(mut self, check_validity: bool) -> Self {
+        self.check_validity = check_validity;
+        self
+    }
+}
+
+/// Tracks blockhash ages and swaps stale ones for `getLatestBlockhash`.
+pub struct BlockhashGuard {
+    config: BlockhashRefreshConfig,
+    first_seen: Mutex<HashMap<Hash, Instant>>,
+}
+
+impl BlockhashGuard {
+    pub fn new(config: BlockhashRefreshConfig) -> Self {
+        Self { config, first_seen: Mutex::new(HashMap::new()) }
+    }
+
+    #[inline]
+    pub fn config(&self) -> &BlockhashRefreshConfig {
+        &self.config
+    }
+
+    /// Record that `hash` was fetched now, so its age is exact rather than a lower bound.
+    pub fn observe(&self, hash: Hash) {
+        self.first_seen_at(hash);
+    }
+
+    /// Time since `hash` was first seen (zero on first sight).
+    pub fn age(&self, hash: &Hash) -> Duration {
+        self.first_seen_at(*hash).elapsed()
+    }
+
+    fn first_seen_at(&self, hash: Hash) -> Instant {
+        let mut seen = self.first_seen.lock();
+        if seen.len() >= MAX_TRACKED_BLOCKHASHES && !seen.contains_key(&hash) {
+            let cutoff = self.config.max_age.max(Duration::from_secs(120));
+            seen.retain(|_, at| at.elapsed() < cutoff);
+            if seen.len() >= MAX_TRACKED_BLOCKHASHES {
+                seen.clear();
+            }
+        }
+        *seen.entry(hash).or_insert_with(Instant::now)
+    }
+
+    /// `hash` if it is younger than `max_age` (and, with `check_validity`, still valid on RPC);
+    /// otherwise the latest blockhash.
+    pub async fn ensure_fresh(&self, rpc: &SolanaRpcClient, hash: Hash) -> Result<Hash> {
+        if self.age(&hash) < self.config.max_age {
+            if !self.config.check_validity {
+                return Ok(hash);
+            }
+            if rpc.is_blockhash_valid(&hash, CommitmentConfig::processed()).await? {
+                return Ok(hash);
+            }
+        }
+        let fresh = rpc.get_latest_blockhash().await?;
+        self.observe(fresh);
+        Ok(fresh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::MockSolanaRpcClient;
+    use serde_json::json;
+    use solana_rpc_client_api::request::RpcRequest;
+
+    fn validity(valid: bool) -> serde_json::Value {
+        json!({ "context": { "slot": 1 }, "value": valid })
+    }
+
+    #[tokio::test]
+    async fn stale_or_invalid_hashes_are_replaced() {
+        let (cached, latest) = (Hash::new_unique(), Hash::new_unique());
+
+        // still valid: kept, one isBlockhashValid call
+        let rpc = MockSolanaRpcClient::new()
+            .with_response(RpcRequest::IsBlockhashValid, validity(true))
+            .build();
+        let guard = BlockhashGuard::new(BlockhashRefreshConfig::default());
+        assert_eq!(guard.ensure_fresh(&rpc, cached).await.unwrap(), cached);
+
+        // expired on chain: replaced
+        let rpc = MockSolanaRpcClient::new()
+            .with_response(RpcRequest::IsBlockhashValid, validity(false))
+            .with_latest_blockhash(latest, 1_000)
+            .build();
+        assert_eq!(guard.ensure_fresh(&rpc, cached).await.unwrap(), latest);
+
+        // older than max_age: replaced without asking RPC about validity
+        let guard = BlockhashGuard::new(
+            BlockhashRefreshConfig::default().with_max_age(Duration::from_millis(1)),
+        );
+        guard.observe(cached);
+        std::thread::sleep(Duration::from_millis(5));
+        let rpc = MockSolanaRpcClient::new().with_latest_blockhash(latest, 1_000).build();
+        assert_eq!(guard.ensure_fresh(&rpc, cached).await.unwrap(), latest);
+        assert!(guard.age(&latest) < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,41 @@
+//! Memo instruction for on-chain strategy attribution. 交易附带 memo 标签。
+
+use anyhow::anyhow;
+use solana_sdk::instruction::Instruction;
+
+use crate::constants::MEMO_PROGRAM;
+
+/// Max memo length in bytes. Keeps the tag short enough that a typical swap still fits the
+/// 1232-byte packet; `build_transaction` rejects anything that does not.
+pub const MAX_MEMO_LEN: usize = 256;
+
+/// Memo program instruction carrying `memo` as UTF-8, with no signer accounts.
+pub fn build_memo_instruction(memo: &str) -> Result<Instruction, anyhow::Error> {
+    if memo.is_empty() {
+        return Err(anyhow!("memo must not be empty"));
+    }
+    if memo.len() > MAX_MEMO_LEN {
+        return Err(anyhow!("memo is {} bytes, max {}", memo.len(), MAX_MEMO_LEN));
+    }
+    Ok(Instruction {
+        program_id: MEMO_PROGRAM,
+        accounts: Vec::new(),
+        data: memo.as_bytes().to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memo_carries_utf8_bytes_and_rejects_empty_or_oversized() {
+        let ix = build_memo_instruction("strategy:alpha-1").unwrap();
+        assert_eq!(ix.program_id, MEMO_PROGRAM);
+        assert!(ix.accounts.is_empty());
+        assert_eq!(ix.data, b"strategy:alpha-1");
+
+        assert!(build_memo_instruction("").is_err());
+        assert!(build_memo_instruction(&"x".repeat(MAX_MEMO_LEN + 1)).is_err());
+    }
+}
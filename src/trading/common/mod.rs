@@ -1,5 +1,9 @@
+pub mod blockhash_refresh;
 pub mod compute_budget_manager;
+pub mod memo;
 pub mod nonce_manager;
+pub mod pool_warm;
+pub mod protocol_overrides;
 pub mod transaction_builder;
 pub mod utils;
 pub mod wsol_manager;
@@ -0,0 +1,194 @@
+//! Pool account pre-fetch ("warming") ahead of an expected trade.
+//! 预期交易前预取池子相关账户，让 RPC 节点缓存热起来。
+
+use anyhow::anyhow;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::common::SolanaRpcClient;
+use crate::instruction::utils::{
+    bonk_types, meteora_damm_v2_types, pumpswap, raydium_amm_v4_types, raydium_cpmm_types,
+};
+use crate::trading::common::protocol_overrides;
+use crate::trading::core::params::pool_cache::{PoolCache, POOL_CACHE};
+use crate::trading::core::params::{MeteoraDammV2Params, PumpSwapParams, RaydiumCpmmParams};
+use crate::trading::factory::DexType;
+
+/// Protocols whose program can own a pool or bonding curve passed to [`warm_pool_accounts`].
+const POOL_PROTOCOLS: [DexType; 6] = [
+    DexType::PumpSwap,
+    DexType::RaydiumCpmm,
+    DexType::RaydiumAmmV4,
+    DexType::MeteoraDammV2,
+    DexType::Bonk,
+    DexType::PumpFun,
+];
+
+/// Protocol whose program (after [`protocol_overrides::program_id`]) owns the account.
+pub(crate) fn pool_dex_type(owner: &Pubkey) -> Option<DexType> {
+    POOL_PROTOCOLS.into_iter().find(|dex_type| protocol_overrides::program_id(*dex_type) == *owner)
+}
+
+/// Accounts a trade on this pool reads (vaults, mints, config), derived from the pool account.
+/// `None` when the owner is not a supported DEX program or the data does not decode.
+pub fn pool_related_accounts(account: &Account) -> Option<Vec<Pubkey>> {
+    let body = account.data.get(8..);
+    match pool_dex_type(&account.owner)? {
+        DexType::PumpSwap => {
+            let pool = pumpswap::decode_pool_account(account).ok()?;
+            Some(vec![
+                pool.base_mint,
+                pool.quote_mint,
+                pool.pool_base_token_account,
+                pool.pool_quote_token_account,
+            ])
+        }
+        DexType::RaydiumCpmm => {
+            let pool = raydium_cpmm_types::pool_state_decode(body?)?;
+            Some(vec![
+                pool.amm_config,
+                pool.token0_mint,
+                pool.token1_mint,
+                pool.token0_vault,
+                pool.token1_vault,
+                pool.observation_key,
+            ])
+        }
+        DexType::RaydiumAmmV4 => {
+            let amm = raydium_amm_v4_types::amm_info_decode(&account.data)?;
+            Some(vec![
+                amm.coin_mint,
+                amm.pc_mint,
+                amm.token_coin,
+                amm.token_pc,
+                amm.open_orders,
+                amm.target_orders,
+                amm.market,
+            ])
+        }
+        DexType::MeteoraDammV2 => {
+            let pool = meteora_damm_v2_types::pool_decode(body?)?;
+            Some(vec![pool.token_a_mint, pool.token_b_mint, pool.token_a_vault, pool.token_b_vault])
+        }
+        DexType::Bonk => {
+            let pool = bonk_types::pool_state_decode(body?)?;
+            Some(vec![
+                pool.global_config,
+                pool.platform_config,
+                pool.base_mint,
+                pool.quote_mint,
+                pool.base_vault,
+                pool.quote_vault,
+            ])
+        }
+        // Bonding curve: everything else is derived from the mint at build time.
+        DexType::PumpFun => Some(Vec::new()),
+    }
+}
+
+/// Fetch the pool and every related account in one `getMultipleAccounts`, and fill SDK-side
+/// caches the build path reads (PumpSwap fee config). With `TradeConfig::pool_cache_ttl` set,
+/// PumpSwap, Raydium CPMM and Meteora DAMM v2 pools are also decoded into the pool cache, so a
+/// following `from_pool_address_by_rpc` is served without RPC. Returns the number of accounts
+/// fetched.
+pub async fn warm_pool_accounts(rpc: &SolanaRpcClient, pool: &Pubkey) -> anyhow::Result<usize> {
+    warm_pool_accounts_into(rpc, pool, &POOL_CACHE).await
+}
+
+/// [`warm_pool_accounts`], decoding into `cache` instead of `POOL_CACHE`.
+pub(crate) async fn warm_pool_accounts_into(
+    rpc: &SolanaRpcClient,
+    pool: &Pubkey,
+    cache: &PoolCache,
+) -> anyhow::Result<usize> {
+    let account = rpc.get_account(pool).await?;
+    let related = pool_related_accounts(&account)
+        .ok_or_else(|| anyhow!("{} is not a supported pool (owner {})", pool, account.owner))?;
+    if account.owner == protocol_overrides::program_id(DexType::PumpSwap) {
+        let _ = pumpswap::fetch_fee_config(rpc).await;
+    }
+    if !cache.ttl().is_zero() {
+        // Replace whatever the cache holds with the pool as it is now.
+        cache.invalidate(pool);
+        match pool_dex_type(&account.owner) {
+            Some(DexType::PumpSwap) => {
+                PumpSwapParams::load_through_cache(rpc, pool, None, cache).await?;
+            }
+            Some(DexType::RaydiumCpmm) => {
+                RaydiumCpmmParams::load_through_cache(rpc, pool, None, cache).await?;
+            }
+            Some(DexType::MeteoraDammV2) => {
+                MeteoraDammV2Params::load_through_cache(rpc, pool, None, cache).await?;
+            }
+            _ => {}
+        }
+    }
+    if !related.is_empty() {
+        rpc.get_multiple_accounts(&related).await?;
+    }
+    Ok(related.len() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::utils::pumpswap_types::{POOL_DISCRIMINATOR, POOL_SIZE};
+
+    #[test]
+    fn pumpswap_pool_yields_mints_and_vaults_and_unknown_owner_is_rejected() {
+        let mut data = Vec::with_capacity(8 + POOL_SIZE);
+        data.extend_from_slice(&POOL_DISCRIMINATOR);
+        data.push(1);
+        data.extend_from_slice(&0u16.to_le_bytes());
+        for seed in 1..=6u8 {
+            data.extend_from_slice(&[seed; 32]);
+        }
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&[7; 32]);
+        data.extend_from_slice(&[0, 0]);
+        data.extend_from_slice(&0i128.to_le_bytes());
+        let mut account =
+            Account { data, owner: pumpswap::accounts::AMM_PROGRAM, ..Account::default() };
+
+        let related = pool_related_accounts(&account).unwrap();
+        let expected: Vec<Pubkey> =
+            [2u8, 3, 5, 6].iter().map(|s| Pubkey::new_from_array([*s; 32])).collect();
+        assert_eq!(related, expected);
+
+        account.owner = Pubkey::new_unique();
+        assert!(pool_related_accounts(&account).is_none());
+    }
+
+    #[tokio::test]
+    async fn warmed_pool_is_decoded_into_the_cache_and_loads_without_rpc() {
+        use crate::common::{mock_rpc::mock_account, MockSolanaRpcClient};
+        use crate::instruction::utils::raydium_cpmm;
+        use std::time::Duration;
+
+        let pk = |seed: u8| Pubkey::new_from_array([seed; 32]);
+        let mut data = vec![0u8; 8];
+        for key in [pk(2), pk(7), pk(10), pk(11), pk(8), pk(3), pk(4), pk(5), pk(5), pk(6)] {
+            data.extend_from_slice(key.as_ref());
+        }
+        data.resize(8 + raydium_cpmm_types::POOL_STATE_SIZE, 0);
+        let pool_account = mock_account(raydium_cpmm::accounts::RAYDIUM_CPMM, data);
+        let pool = pk(1);
+        // warm: pool, then the loader's pool and vault balances, then the related accounts
+        let rpc = MockSolanaRpcClient::new()
+            .with_account(Some(&pool_account))
+            .with_account(Some(&pool_account))
+            .with_token_account_balance(5_000_000, 6)
+            .with_token_account_balance(7_000_000, 6)
+            .with_multiple_accounts(&[None; 6])
+            .build();
+        let cache = PoolCache::new(Duration::from_secs(60));
+
+        assert_eq!(warm_pool_accounts_into(&rpc, &pool, &cache).await.unwrap(), 7);
+
+        // no responses queued: any fetch would get the mock's default account and fail
+        let no_rpc = MockSolanaRpcClient::new().build();
+        let loaded =
+            RaydiumCpmmParams::load_through_cache(&no_rpc, &pool, None, &cache).await.unwrap();
+        assert_eq!((loaded.base_vault, loaded.quote_vault), (pk(10), pk(11)));
+        assert_eq!((loaded.base_reserve, loaded.quote_reserve), (5_000_000, 7_000_000));
+    }
+}
@@ -0,0 +1,353 @@
+//! Runtime replacements for a protocol's program id and instruction discriminators, so a
+//! protocol redeploy does not have to wait for an SDK release. 协议程序 ID / 判别符的运行时覆盖。
+//!
+//! Process-wide: installed with [`set_protocol_overrides`] and shared by every client. PDA
+//! helpers read the overridden program id before falling back to the built-in one
+//! ([`program_id`], [`cached_pda`]), so bonding curves, pools, vaults and user volume
+//! accumulators are derived under the override. Every instruction a builder returns is then
+//! rewritten: instructions addressed to the built-in program are re-addressed, account metas
+//! naming the built-in program (e.g. Anchor's event-CPI `program` account) follow, the fixed PDAs
+//! builders emit as precomputed constants (global config, event authority, fee config, ...) are
+//! re-derived from their seeds, and a data prefix equal to a replaced discriminator is swapped.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::common::fast_fn::{get_cached_pda, PdaCacheKey};
+use crate::instruction::utils::{
+    bonk, meteora_damm_v2, pumpfun, pumpswap, raydium_amm_v4, raydium_cpmm,
+};
+use crate::trading::factory::DexType;
+
+/// Replacements for one protocol. 单个协议的覆盖项。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtocolOverride {
+    /// Program to address instead of [`DexType::program_id`].
+    pub program_id: Option<Pubkey>,
+    /// `(replaced, replacement)` discriminator pairs, matched as a prefix of the instruction
+    /// data; the first match wins. Lengths may differ.
+    pub discriminators: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl ProtocolOverride {
+    pub fn with_program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = Some(program_id);
+        self
+    }
+
+    /// Replace the built-in `replaced` (e.g. `pumpswap::BUY_DISCRIMINATOR`) with `replacement`.
+    pub fn with_discriminator(mut self, replaced: &[u8], replacement: &[u8]) -> Self {
+        self.discriminators.push((replaced.to_vec(), replacement.to_vec()));
+        self
+    }
+
+    fn apply(&self, dex_type: DexType, instructions: &mut [Instruction]) {
+        let builtin_program = dex_type.program_id();
+        let fixed = self.program_id.map(|program_id| remapped_fixed_pdas(dex_type, program_id));
+        for ix in instructions.iter_mut().filter(|ix| ix.program_id == builtin_program) {
+            if let Some((replaced, replacement)) =
+                self.discriminators.iter().find(|(replaced, _)| ix.data.starts_with(replaced))
+            {
+                let mut data = replacement.clone();
+                data.extend_from_slice(&ix.data[replaced.len()..]);
+                ix.data = data;
+            }
+            if let Some(program_id) = self.program_id {
+                ix.program_id = program_id;
+                for meta in ix.accounts.iter_mut() {
+                    if meta.pubkey == builtin_program {
+                        meta.pubkey = program_id;
+                    } else if let Some((_, pda)) = fixed
+                        .as_ref()
+                        .and_then(|fixed| fixed.iter().find(|(builtin, _)| *builtin == meta.pubkey))
+                    {
+                        meta.pubkey = *pda;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Overrides keyed by protocol ([`set_protocol_overrides`]). 按协议索引的覆盖表。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtocolOverrides(HashMap<DexType, ProtocolOverride>);
+
+impl ProtocolOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, dex_type: DexType, value: ProtocolOverride) -> Self {
+        self.0.insert(dex_type, value);
+        self
+    }
+
+    pub fn insert(&mut self, dex_type: DexType, value: ProtocolOverride) {
+        self.0.insert(dex_type, value);
+    }
+
+    #[inline]
+    pub fn get(&self, dex_type: DexType) -> Option<&ProtocolOverride> {
+        self.0.get(&dex_type)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Rewrite `instructions` built for `dex_type` in place.
+    pub fn apply(&self, dex_type: DexType, instructions: &mut [Instruction]) {
+        if let Some(o) = self.get(dex_type) {
+            o.apply(dex_type, instructions);
+        }
+    }
+
+    /// Program `dex_type` is addressed at under these overrides.
+    #[inline]
+    pub fn program_id(&self, dex_type: DexType) -> Pubkey {
+        self.get(dex_type).and_then(|o| o.program_id).unwrap_or_else(|| dex_type.program_id())
+    }
+
+    /// The override for the protocol whose built-in program is `builtin_program`, if any.
+    pub fn program_id_for(&self, builtin_program: &Pubkey) -> Option<Pubkey> {
+        let dex_type = DexType::from_program_id(builtin_program)?;
+        self.get(dex_type)?.program_id
+    }
+}
+
+/// How a fixed PDA that builders emit as a precomputed constant is derived.
+enum Derivation {
+    /// These seeds under the protocol's own program.
+    Own(Vec<Vec<u8>>),
+    /// `["fee_config", protocol program]` under the pump fee program.
+    PumpFeeConfig,
+}
+
+/// The fixed PDAs of `dex_type` as `(built-in constant, derivation)`.
+fn fixed_pdas(dex_type: DexType) -> Vec<(Pubkey, Derivation)> {
+    use Derivation::*;
+    let seeds = |s: &[&[u8]]| Own(s.iter().map(|s| s.to_vec()).collect());
+    let event_authority: &[u8] = b"__event_authority";
+    match dex_type {
+        DexType::PumpFun => vec![
+            (pumpfun::global_constants::GLOBAL_ACCOUNT, seeds(&[b"global"])),
+            (pumpfun::accounts::EVENT_AUTHORITY, seeds(&[event_authority])),
+            (pumpfun::accounts::GLOBAL_VOLUME_ACCUMULATOR, seeds(&[b"global_volume_accumulator"])),
+            (pumpfun::accounts::FEE_CONFIG, PumpFeeConfig),
+        ],
+        DexType::PumpSwap => vec![
+            (pumpswap::accounts::GLOBAL_ACCOUNT, seeds(&[b"global_config"])),
+            (pumpswap::accounts::EVENT_AUTHORITY, seeds(&[event_authority])),
+            (pumpswap::accounts::GLOBAL_VOLUME_ACCUMULATOR, seeds(&[b"global_volume_accumulator"])),
+            (
+                pumpswap::accounts::DEFAULT_COIN_CREATOR_VAULT_AUTHORITY,
+                seeds(&[b"creator_vault", Pubkey::default().as_ref()]),
+            ),
+            (pumpswap::accounts::FEE_CONFIG, PumpFeeConfig),
+        ],
+        DexType::Bonk => {
+            let global_config = |quote: Pubkey| {
+                seeds(&[b"global_config", quote.as_ref(), &[0], &0u16.to_le_bytes()])
+            };
+            vec![
+                (bonk::accounts::AUTHORITY, seeds(&[b"vault_auth_seed"])),
+                (bonk::accounts::EVENT_AUTHORITY, seeds(&[event_authority])),
+                (
+                    bonk::accounts::GLOBAL_CONFIG,
+                    global_config(crate::constants::WSOL_TOKEN_ACCOUNT),
+                ),
+                (
+                    bonk::accounts::USD1_GLOBAL_CONFIG,
+                    global_config(crate::constants::USD1_TOKEN_ACCOUNT),
+                ),
+            ]
+        }
+        DexType::RaydiumCpmm => {
+            vec![(raydium_cpmm::accounts::AUTHORITY, seeds(&[b"vault_and_lp_mint_auth_seed"]))]
+        }
+        DexType::RaydiumAmmV4 => {
+            vec![(raydium_amm_v4::accounts::AUTHORITY, seeds(&[b"amm authority"]))]
+        }
+        DexType::MeteoraDammV2 => {
+            vec![(meteora_damm_v2::accounts::AUTHORITY, seeds(&[b"pool_authority"]))]
+        }
+    }
+}
+
+/// `(built-in constant, same seeds under program_id)` for `dex_type`'s fixed PDAs, computed
+/// once per override.
+fn remapped_fixed_pdas(dex_type: DexType, program_id: Pubkey) -> Arc<Vec<(Pubkey, Pubkey)>> {
+    static REMAPPED: Lazy<DashMap<(DexType, Pubkey), Arc<Vec<(Pubkey, Pubkey)>>>> =
+        Lazy::new(DashMap::new);
+    REMAPPED
+        .entry((dex_type, program_id))
+        .or_insert_with(|| Arc::new(derive_fixed_pdas(dex_type, &program_id)))
+        .clone()
+}
+
+fn derive_fixed_pdas(dex_type: DexType, program_id: &Pubkey) -> Vec<(Pubkey, Pubkey)> {
+    fixed_pdas(dex_type)
+        .into_iter()
+        .map(|(builtin, derivation)| {
+            let pda = match derivation {
+                Derivation::Own(seeds) => {
+                    let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+                    Pubkey::find_program_address(&seeds, program_id).0
+                }
+                Derivation::PumpFeeConfig => {
+                    Pubkey::find_program_address(
+                        &[b"fee_config", program_id.as_ref()],
+                        &pumpfun::accounts::FEE_PROGRAM,
+                    )
+                    .0
+                }
+            };
+            (builtin, pda)
+        })
+        .collect()
+}
+
+static PROTOCOL_OVERRIDES: Lazy<RwLock<Arc<ProtocolOverrides>>> =
+    Lazy::new(|| RwLock::new(Arc::new(ProtocolOverrides::default())));
+
+/// Whether any installed override replaces a program id; keeps [`program_id`] to one atomic
+/// load on the hot path when none does.
+static ANY_PROGRAM_OVERRIDE: AtomicBool = AtomicBool::new(false);
+
+/// Install process-wide overrides, used by every client. Replaces the previously installed set.
+pub fn set_protocol_overrides(overrides: ProtocolOverrides) {
+    let any_program = overrides.0.values().any(|o| o.program_id.is_some());
+    *PROTOCOL_OVERRIDES.write() = Arc::new(overrides);
+    ANY_PROGRAM_OVERRIDE.store(any_program, Ordering::Release);
+}
+
+/// The installed program-id override for `dex_type`, if any.
+#[inline]
+pub fn overridden_program_id(dex_type: DexType) -> Option<Pubkey> {
+    if !ANY_PROGRAM_OVERRIDE.load(Ordering::Acquire) {
+        return None;
+    }
+    PROTOCOL_OVERRIDES.read().get(dex_type)?.program_id
+}
+
+/// Program `dex_type`'s PDAs are derived under: the installed override, else
+/// [`DexType::program_id`] (see [`ProtocolOverrides::program_id`]).
+#[inline]
+pub fn program_id(dex_type: DexType) -> Pubkey {
+    overridden_program_id(dex_type).unwrap_or_else(|| dex_type.program_id())
+}
+
+/// PDA of `seeds` under [`program_id`]`(dex_type)`. Served from the PDA cache for the built-in
+/// program only, since the cache keys do not carry the program.
+#[inline]
+pub(crate) fn cached_pda(dex_type: DexType, key: PdaCacheKey, seeds: &[&[u8]]) -> Option<Pubkey> {
+    match overridden_program_id(dex_type) {
+        Some(program_id) => Pubkey::try_find_program_address(seeds, &program_id).map(|p| p.0),
+        None => get_cached_pda(key, || {
+            Pubkey::try_find_program_address(seeds, &dex_type.program_id()).map(|p| p.0)
+        }),
+    }
+}
+
+/// The overrides currently in effect.
+pub fn protocol_overrides() -> Arc<ProtocolOverrides> {
+    PROTOCOL_OVERRIDES.read().clone()
+}
+
+/// Apply the installed overrides to a builder's output; a no-op when none are set.
+#[inline]
+pub(crate) fn apply_protocol_overrides(dex_type: DexType, instructions: &mut [Instruction]) {
+    let overrides = PROTOCOL_OVERRIDES.read();
+    if !overrides.is_empty() {
+        overrides.apply(dex_type, instructions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::utils::pumpswap::{accounts::AMM_PROGRAM, BUY_DISCRIMINATOR};
+    use solana_sdk::instruction::AccountMeta;
+
+    #[test]
+    fn override_readdresses_protocol_instructions_and_swaps_discriminator() {
+        let new_program = Pubkey::new_unique();
+        let new_disc = [9u8; 8];
+        let overrides = ProtocolOverrides::new().with(
+            DexType::PumpSwap,
+            ProtocolOverride::default()
+                .with_program_id(new_program)
+                .with_discriminator(&BUY_DISCRIMINATOR, &new_disc),
+        );
+        let mut data = BUY_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&42u64.to_le_bytes());
+        let other = Instruction::new_with_bytes(Pubkey::new_unique(), &BUY_DISCRIMINATOR, vec![]);
+        let mut ixs = vec![
+            Instruction::new_with_bytes(
+                AMM_PROGRAM,
+                &data,
+                vec![AccountMeta::new_readonly(AMM_PROGRAM, false)],
+            ),
+            other.clone(),
+        ];
+
+        overrides.apply(DexType::PumpSwap, &mut ixs);
+        assert_eq!(ixs[0].program_id, new_program);
+        assert_eq!(ixs[0].accounts[0].pubkey, new_program);
+        assert_eq!(&ixs[0].data[..8], &new_disc);
+        assert_eq!(&ixs[0].data[8..], &42u64.to_le_bytes());
+        assert_eq!(ixs[1], other);
+        assert_eq!(overrides.program_id_for(&AMM_PROGRAM), Some(new_program));
+
+        // other protocols are untouched
+        let mut ixs = vec![Instruction::new_with_bytes(AMM_PROGRAM, &data, vec![])];
+        overrides.apply(DexType::PumpFun, &mut ixs);
+        assert_eq!(ixs[0].program_id, AMM_PROGRAM);
+        assert_eq!(overrides.program_id(DexType::PumpSwap), new_program);
+        assert_eq!(overrides.program_id(DexType::PumpFun), DexType::PumpFun.program_id());
+    }
+
+    #[test]
+    fn fixed_pdas_are_rederived_under_the_overridden_program() {
+        use crate::instruction::utils::pumpswap::accounts::{FEE_CONFIG, GLOBAL_ACCOUNT};
+
+        // the seed table reproduces every built-in constant
+        for dex_type in crate::trading::factory::TradeFactory::supported_dex_types() {
+            for (builtin, derived) in derive_fixed_pdas(*dex_type, &dex_type.program_id()) {
+                assert_eq!(builtin, derived, "{dex_type}");
+            }
+        }
+
+        let new_program = Pubkey::new_unique();
+        let overrides = ProtocolOverrides::new()
+            .with(DexType::PumpSwap, ProtocolOverride::default().with_program_id(new_program));
+        let user = Pubkey::new_unique();
+        let mut ixs = vec![Instruction::new_with_bytes(
+            AMM_PROGRAM,
+            &BUY_DISCRIMINATOR,
+            vec![
+                AccountMeta::new_readonly(GLOBAL_ACCOUNT, false),
+                AccountMeta::new(user, true),
+                AccountMeta::new_readonly(FEE_CONFIG, false),
+            ],
+        )];
+        overrides.apply(DexType::PumpSwap, &mut ixs);
+
+        let global = Pubkey::find_program_address(&[b"global_config"], &new_program).0;
+        let fee_config = Pubkey::find_program_address(
+            &[b"fee_config", new_program.as_ref()],
+            &crate::instruction::utils::pumpfun::accounts::FEE_PROGRAM,
+        )
+        .0;
+        let metas: Vec<Pubkey> = ixs[0].accounts.iter().map(|m| m.pubkey).collect();
+        assert_eq!(metas, vec![global, user, fee_config]);
+    }
+}
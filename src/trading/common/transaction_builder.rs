@@ -13,11 +13,51 @@ use crate::{
     common::nonce_cache::DurableNonceInfo,
     trading::{
         core::transaction_pool::{acquire_builder, release_builder},
-        MiddlewareManager,
+        MiddlewareManager, TradeContext,
     },
 };
 
-const PACKET_DATA_SIZE: usize = 1232;
+pub(crate) const PACKET_DATA_SIZE: usize = 1232;
+
+/// Compiled instruction overhead: program index, account-count and data-length shortvecs.
+const IX_HEADER: usize = 3;
+/// Compute budget program key + SetComputeUnitLimit (u8 tag + u32) + SetComputeUnitPrice
+/// (u8 tag + u64), neither with accounts.
+const COMPUTE_BUDGET_ENVELOPE: usize = 32 + (IX_HEADER + 5) + (IX_HEADER + 9);
+/// Tip account + System program keys + transfer (from, to; u32 tag + u64 lamports).
+const TIP_ENVELOPE: usize = 32 + 32 + (IX_HEADER + 2 + 12);
+/// Nonce account + RecentBlockhashes sysvar keys + AdvanceNonceAccount (nonce, sysvar,
+/// authority = payer; u32 tag). The System program key is shared with the tip.
+const NONCE_ENVELOPE: usize = 32 + 32 + (IX_HEADER + 3 + 4);
+
+/// Bytes `build_transaction` may add on top of the business instructions: compute budget,
+/// relay tip transfer and durable nonce advance, assuming none of their keys is already in the
+/// message. 预留给 compute budget / tip / nonce 的字节数。
+pub(crate) const TX_ENVELOPE_RESERVE: usize =
+    COMPUTE_BUDGET_ENVELOPE + TIP_ENVELOPE + NONCE_ENVELOPE;
+
+/// Serialized size of a v0 transaction carrying `instructions`, with one signature per signer
+/// they require (the payer plus e.g. an `ata_rent_funder`). A legacy message, used when there
+/// is no table, is 2 bytes smaller.
+///
+/// Unlike a key count, this accounts for ALT compression: keys covered by a table cost one
+/// index byte, while signers, program ids and keys no table contains (freshly created ATAs,
+/// the payer) still cost a full 32 bytes. 按 ALT 压缩后的真实交易字节数估算。
+pub fn estimate_transaction_size(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+) -> Result<usize, anyhow::Error> {
+    let message = solana_sdk::message::v0::Message::try_compile(
+        payer,
+        instructions,
+        address_lookup_table_accounts,
+        Hash::default(),
+    )?;
+    let signatures = message.header.num_required_signatures as usize;
+    // shortvec signature count (1 byte) + signatures + version-prefixed message
+    Ok(1 + 64 * signatures + solana_sdk::message::VersionedMessage::V0(message).serialize().len())
+}
 
 /// Convert SOL amount (f64) to lamports without string allocation (hot path).
 #[inline(always)]
@@ -31,16 +71,22 @@ fn sol_f64_to_lamports(sol: f64) -> u64 {
 
 /// Build signed transaction (worker hot path, no RPC).
 /// Takes Arc/refs only; one Vec allocation (with_capacity), extend_from_slice for business_instructions, no extra clone of payer/middleware.
+/// With `skip_compute_budget`, `unit_limit`/`unit_price` are ignored and no ComputeBudget
+/// instructions are added; any the caller put in `business_instructions` (or middleware adds) stay.
+/// `ata_rent_funder` co-signs when the message requires its signature (it funds created ATAs).
 pub fn build_transaction(
     payer: &Arc<Keypair>,
+    ata_rent_funder: Option<&Arc<Keypair>>,
     unit_limit: u32,
     unit_price: u64,
+    skip_compute_budget: bool,
     business_instructions: &[Instruction],
     address_lookup_table_accounts: &[AddressLookupTableAccount],
     recent_blockhash: Option<Hash>,
     middleware_manager: Option<&Arc<MiddlewareManager>>,
     protocol_name: &str,
     is_buy: bool,
+    trade_context: Option<&TradeContext>,
     with_tip: bool,
     tip_account: &Pubkey,
     tip_amount: f64,
@@ -48,14 +94,17 @@ pub fn build_transaction(
 ) -> Result<VersionedTransaction, anyhow::Error> {
     let transaction = build_transaction_inner(
         payer,
+        ata_rent_funder,
         unit_limit,
         unit_price,
+        skip_compute_budget,
         business_instructions,
         address_lookup_table_accounts,
         recent_blockhash,
         middleware_manager,
         protocol_name,
         is_buy,
+        trade_context,
         with_tip,
         tip_account,
         tip_amount,
@@ -65,7 +114,7 @@ pub fn build_transaction(
     let serialized_len = bincode::serialized_size(&transaction)? as usize;
     if crate::common::sdk_log::sdk_log_enabled() {
         println!(
-            " [SDK][tx-size     ] {} {} serialized={} bytes, business_ix={}, nonce={}, tip={}, cu_limit={}, cu_price={}, alt={}",
+            " [SDK][tx-size     ] {} {} serialized={} bytes, business_ix={}, nonce={}, tip={}, cu_limit={}, cu_price={}, skip_cu={}, alt={}",
             protocol_name,
             if is_buy { "buy" } else { "sell" },
             serialized_len,
@@ -74,6 +123,7 @@ pub fn build_transaction(
             with_tip && tip_amount > 0.0,
             unit_limit,
             unit_price,
+            skip_compute_budget,
             address_lookup_table_accounts.len()
         );
     }
@@ -90,14 +140,17 @@ pub fn build_transaction(
 
 fn build_transaction_inner(
     payer: &Arc<Keypair>,
+    ata_rent_funder: Option<&Arc<Keypair>>,
     unit_limit: u32,
     unit_price: u64,
+    skip_compute_budget: bool,
     business_instructions: &[Instruction],
     address_lookup_table_accounts: &[AddressLookupTableAccount],
     recent_blockhash: Option<Hash>,
     middleware_manager: Option<&Arc<MiddlewareManager>>,
     protocol_name: &str,
     is_buy: bool,
+    trade_context: Option<&TradeContext>,
     with_tip: bool,
     tip_account: &Pubkey,
     tip_amount: f64,
@@ -114,11 +167,13 @@ fn build_transaction_inner(
         instructions.push(system_instruction::transfer(&payer.pubkey(), tip_account, tip_lamports));
     }
 
-    super::compute_budget_manager::extend_compute_budget_instructions(
-        &mut instructions,
-        unit_price,
-        unit_limit,
-    );
+    if !skip_compute_budget {
+        super::compute_budget_manager::extend_compute_budget_instructions(
+            &mut instructions,
+            unit_price,
+            unit_limit,
+        );
+    }
 
     instructions.extend_from_slice(business_instructions);
 
@@ -126,28 +181,34 @@ fn build_transaction_inner(
 
     build_versioned_transaction(
         payer,
+        ata_rent_funder,
         instructions,
         address_lookup_table_accounts,
         blockhash,
         middleware_manager,
         protocol_name,
         is_buy,
+        trade_context,
     )
 }
 
 fn build_versioned_transaction(
     payer: &Arc<Keypair>,
+    ata_rent_funder: Option<&Arc<Keypair>>,
     instructions: Vec<Instruction>,
     address_lookup_table_accounts: &[AddressLookupTableAccount],
     blockhash: Hash,
     middleware_manager: Option<&Arc<MiddlewareManager>>,
     protocol_name: &str,
     is_buy: bool,
+    trade_context: Option<&TradeContext>,
 ) -> Result<VersionedTransaction, anyhow::Error> {
-    let full_instructions = match middleware_manager {
-        Some(middleware_manager) => middleware_manager
+    let full_instructions = match (middleware_manager, trade_context) {
+        (Some(middleware_manager), Some(context)) => middleware_manager
+            .apply_middlewares_process_full_instructions_with_context(instructions, context)?,
+        (Some(middleware_manager), None) => middleware_manager
             .apply_middlewares_process_full_instructions(instructions, protocol_name, is_buy)?,
-        None => instructions,
+        (None, _) => instructions,
     };
 
     // 使用预分配的交易构建器以降低延迟
@@ -163,9 +224,17 @@ fn build_versioned_transaction(
     let versioned_msg = build_result?;
 
     let msg_bytes = versioned_msg.serialize();
-    let signature =
-        payer.as_ref().try_sign_message(&msg_bytes).map_err(|e| anyhow!("sign failed: {e}"))?;
-    let tx = VersionedTransaction { signatures: vec![signature], message: versioned_msg };
+    let required = versioned_msg.header().num_required_signatures as usize;
+    let mut signatures = Vec::with_capacity(required);
+    for key in versioned_msg.static_account_keys().iter().take(required) {
+        let signer = std::iter::once(payer)
+            .chain(ata_rent_funder)
+            .find(|k| k.pubkey() == *key)
+            .ok_or_else(|| anyhow!("missing signer {key}"))?;
+        signatures
+            .push(signer.try_sign_message(&msg_bytes).map_err(|e| anyhow!("sign failed: {e}"))?);
+    }
+    let tx = VersionedTransaction { signatures, message: versioned_msg };
 
     Ok(tx)
 }
@@ -175,6 +244,57 @@ mod tests {
     use super::*;
     use solana_sdk::instruction::AccountMeta;
 
+    #[test]
+    fn envelope_reserve_covers_compute_budget_tip_and_nonce() {
+        let payer = Arc::new(Keypair::new());
+        let business = vec![oversized_instruction(4, 32)];
+        let nonce = DurableNonceInfo {
+            nonce_account: Some(Pubkey::new_unique()),
+            current_nonce: Some(Hash::new_unique()),
+        };
+        let tx = build_transaction(
+            &payer,
+            None,
+            80_000,
+            100_000,
+            false,
+            &business,
+            &[],
+            None,
+            None,
+            "test",
+            true,
+            None,
+            true,
+            &Pubkey::new_unique(),
+            0.001,
+            Some(&nonce),
+        )
+        .unwrap();
+        let built = bincode::serialize(&tx).unwrap().len();
+        let business_only = estimate_transaction_size(&payer.pubkey(), &business, &[]).unwrap();
+        assert!(built - business_only <= TX_ENVELOPE_RESERVE, "{}", built - business_only);
+    }
+
+    #[test]
+    fn size_estimate_counts_every_required_signature() {
+        let payer = Pubkey::new_unique();
+        let funder = Pubkey::new_unique();
+        let single = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1],
+            vec![AccountMeta::new(payer, true), AccountMeta::new(funder, false)],
+        );
+        let co_signed = Instruction::new_with_bytes(
+            single.program_id,
+            &[1],
+            vec![AccountMeta::new(payer, true), AccountMeta::new(funder, true)],
+        );
+        let one = estimate_transaction_size(&payer, &[single], &[]).unwrap();
+        let two = estimate_transaction_size(&payer, &[co_signed], &[]).unwrap();
+        assert_eq!(two, one + 64);
+    }
+
     fn oversized_instruction(account_count: usize, data_len: usize) -> Instruction {
         let accounts =
             (0..account_count).map(|_| AccountMeta::new(Pubkey::new_unique(), false)).collect();
@@ -187,14 +307,17 @@ mod tests {
         let business_instructions = vec![oversized_instruction(36, 700)];
         let err = build_transaction(
             &payer,
+            None,
             80_000,
             100_000,
+            false,
             &business_instructions,
             &[],
             Some(Hash::new_unique()),
             None,
             "test",
             true,
+            None,
             true,
             &Pubkey::new_unique(),
             0.001,
@@ -206,4 +329,49 @@ mod tests {
         assert!(err.contains("transaction too large"), "{err}");
         assert!(err.contains("did not remove compute budget or relay tip"), "{err}");
     }
+
+    #[test]
+    fn skip_compute_budget_leaves_cu_settings_to_the_caller() {
+        use solana_compute_budget_interface::ComputeBudgetInstruction;
+
+        let payer = Arc::new(Keypair::new());
+        let business = vec![oversized_instruction(2, 8)];
+        let own = ComputeBudgetInstruction::set_compute_unit_limit(300_000);
+        let compute_budget_ixs = |skip: bool, business: &[Instruction]| {
+            let tx = build_transaction(
+                &payer,
+                None,
+                80_000,
+                100_000,
+                skip,
+                business,
+                &[],
+                Some(Hash::new_unique()),
+                None,
+                "test",
+                true,
+                None,
+                false,
+                &Pubkey::default(),
+                0.0,
+                None,
+            )
+            .unwrap();
+            let keys = tx.message.static_account_keys();
+            tx.message
+                .instructions()
+                .iter()
+                .filter(|ix| keys[ix.program_id_index as usize] == own.program_id)
+                .map(|ix| ix.data.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(compute_budget_ixs(false, &business).len(), 2);
+        assert!(compute_budget_ixs(true, &business).is_empty());
+
+        // the caller's own budget is kept as-is
+        let mut with_own = vec![own.clone()];
+        with_own.extend(business);
+        assert_eq!(compute_budget_ixs(true, &with_own), vec![own.data]);
+    }
 }
@@ -1,4 +1,9 @@
-use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
 use solana_system_interface::instruction as system_instruction;
 
 use crate::common::{
@@ -34,13 +39,37 @@ pub async fn get_multi_token_balances(
     Ok((token0_amount, token1_amount))
 }
 
+/// Map a mint account owner to its token program; errors for anything that is not SPL Token / Token-2022.
+#[inline]
+pub fn token_program_from_mint_owner(
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Pubkey, anyhow::Error> {
+    if *owner == crate::constants::TOKEN_PROGRAM || *owner == crate::constants::TOKEN_PROGRAM_2022 {
+        Ok(*owner)
+    } else {
+        Err(anyhow!("Mint {} is owned by {}, not a token program", mint, owner))
+    }
+}
+
+/// 通过 mint 账户 owner 推导 token program（SPL Token 或 Token-2022），避免调用方传错 program 导致查错 ATA。
+pub async fn resolve_token_program(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<Pubkey, anyhow::Error> {
+    let account = rpc.get_account(mint).await?;
+    token_program_from_mint_owner(mint, &account.owner)
+}
+
+/// Token program is inferred from the mint owner (one extra RPC read).
 #[inline]
 pub async fn get_token_balance(
     rpc: &SolanaRpcClient,
     payer: &Pubkey,
     mint: &Pubkey,
 ) -> Result<u64, anyhow::Error> {
-    get_token_balance_with_options(rpc, payer, mint, &crate::constants::TOKEN_PROGRAM, false).await
+    let token_program = resolve_token_program(rpc, mint).await?;
+    get_token_balance_with_options(rpc, payer, mint, &token_program, false).await
 }
 
 /// 使用与交易指令一致的 ATA 推导（可选 seed）查询余额；卖出/余额查询应与买入使用同一 ATA 地址。
@@ -105,6 +134,44 @@ pub async fn transfer_sol(
     Ok(())
 }
 
+/// Genesis hash of mainnet-beta, where `requestAirdrop` is unavailable.
+pub const MAINNET_BETA_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+
+/// Request `lamports` from the cluster faucet for `recipient` (devnet / testnet / local
+/// validator) and wait up to `timeout` for the airdrop to confirm. Refuses mainnet-beta, detected
+/// from the RPC URL or, failing that, the genesis hash. 测试网空投并等待确认，主网直接报错。
+pub async fn request_airdrop(
+    rpc: &SolanaRpcClient,
+    recipient: &Pubkey,
+    lamports: u64,
+    timeout: std::time::Duration,
+) -> Result<Signature, anyhow::Error> {
+    use crate::swqos::common::{confirm_signatures, ConfirmStatus};
+
+    if lamports == 0 {
+        return Err(anyhow!("request_airdrop: Amount cannot be zero"));
+    }
+    if rpc.url().contains("mainnet")
+        || rpc.get_genesis_hash().await?.to_string() == MAINNET_BETA_GENESIS_HASH
+    {
+        return Err(anyhow!(
+            "request_airdrop: airdrops are unavailable on mainnet-beta; point the RPC at devnet, testnet or a local validator"
+        ));
+    }
+    let signature = rpc.request_airdrop(recipient, lamports).await?;
+    match confirm_signatures(rpc, &[signature], timeout).await.remove(&signature) {
+        Some(ConfirmStatus::Confirmed { .. }) => Ok(signature),
+        Some(ConfirmStatus::Failed { error, .. }) => {
+            Err(anyhow!("request_airdrop: airdrop {} failed: {}", signature, error))
+        }
+        _ => Err(anyhow!(
+            "request_airdrop: airdrop {} not confirmed within {:?}",
+            signature,
+            timeout
+        )),
+    }
+}
+
 /// Close token account
 ///
 /// This function is used to close the associated token account for a specified token,
@@ -160,3 +227,55 @@ pub async fn close_token_account(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_program_is_taken_from_mint_owner() {
+        let mint = Pubkey::new_unique();
+        assert_eq!(
+            token_program_from_mint_owner(&mint, &crate::constants::TOKEN_PROGRAM_2022).unwrap(),
+            crate::constants::TOKEN_PROGRAM_2022
+        );
+        assert_eq!(
+            token_program_from_mint_owner(&mint, &crate::constants::TOKEN_PROGRAM).unwrap(),
+            crate::constants::TOKEN_PROGRAM
+        );
+        assert!(token_program_from_mint_owner(&mint, &crate::constants::SYSTEM_PROGRAM).is_err());
+    }
+
+    #[tokio::test]
+    async fn airdrop_confirms_on_devnet_and_is_refused_on_mainnet() {
+        use serde_json::json;
+        use solana_rpc_client_api::request::RpcRequest;
+        const DEVNET_GENESIS_HASH: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+
+        let signature = Signature::new_unique();
+        let confirmed = json!({
+            "context": { "slot": 7 },
+            "value": [{
+                "slot": 7, "confirmations": null, "status": { "Ok": null }, "err": null,
+                "confirmationStatus": "confirmed"
+            }]
+        });
+        let devnet = crate::common::MockSolanaRpcClient::new()
+            .with_response(RpcRequest::GetGenesisHash, json!(DEVNET_GENESIS_HASH))
+            .with_response(RpcRequest::RequestAirdrop, json!(signature.to_string()))
+            .with_response(RpcRequest::GetSignatureStatuses, confirmed)
+            .build();
+        let recipient = Pubkey::new_unique();
+        let timeout = std::time::Duration::from_millis(50);
+        assert_eq!(
+            request_airdrop(&devnet, &recipient, 1_000_000_000, timeout).await.unwrap(),
+            signature
+        );
+
+        let mainnet = crate::common::MockSolanaRpcClient::new()
+            .with_response(RpcRequest::GetGenesisHash, json!(MAINNET_BETA_GENESIS_HASH))
+            .build();
+        let err = request_airdrop(&mainnet, &recipient, 1_000_000_000, timeout).await.unwrap_err();
+        assert!(err.to_string().contains("mainnet-beta"));
+    }
+}
@@ -12,6 +12,17 @@ use solana_system_interface::instruction as system_instruction;
 
 #[inline]
 pub fn handle_wsol(payer: &Pubkey, amount_in: u64) -> SmallVec<[Instruction; 3]> {
+    handle_wsol_funded(payer, payer, amount_in)
+}
+
+/// [`handle_wsol`] with the WSOL ATA rent paid by `funder`; the wrapped SOL still comes from
+/// `payer`. 租金由 funder 支付。
+#[inline]
+pub fn handle_wsol_funded(
+    payer: &Pubkey,
+    funder: &Pubkey,
+    amount_in: u64,
+) -> SmallVec<[Instruction; 3]> {
     let wsol_token_account =
         crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
             &payer,
@@ -21,7 +32,7 @@ pub fn handle_wsol(payer: &Pubkey, amount_in: u64) -> SmallVec<[Instruction; 3]>
 
     let mut insts = SmallVec::<[Instruction; 3]>::new();
     insts.extend(create_associated_token_account_idempotent_fast(
-        &payer,
+        funder,
         &payer,
         &crate::constants::WSOL_TOKEN_ACCOUNT,
         &crate::constants::TOKEN_PROGRAM,
@@ -39,6 +50,15 @@ pub fn handle_wsol(payer: &Pubkey, amount_in: u64) -> SmallVec<[Instruction; 3]>
     insts
 }
 
+/// Make `close_wsol(payer)` the tail of `instructions`: drop any earlier copy (e.g. from a
+/// builder's close flag) and append it. Returns the unwrapped WSOL to the payer in the same
+/// transaction.
+pub fn push_trailing_wsol_close(instructions: &mut Vec<Instruction>, payer: &Pubkey) {
+    let close = close_wsol(payer);
+    instructions.retain(|ix| !close.contains(ix));
+    instructions.extend(close);
+}
+
 pub fn close_wsol(payer: &Pubkey) -> Vec<Instruction> {
     use std::sync::Arc;
 
@@ -198,3 +218,37 @@ pub fn wrap_wsol_to_sol_without_create(
 
     Ok(instructions)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_wsol_close_is_present_once_and_last() {
+        let payer = Pubkey::new_unique();
+        let swap = Instruction::new_with_bytes(Pubkey::new_unique(), &[1], vec![]);
+        let token_close = close_account(
+            &crate::constants::TOKEN_PROGRAM,
+            &Pubkey::new_unique(),
+            &payer,
+            &payer,
+            &[],
+        )
+        .unwrap();
+        // builder already closed WSOL before closing the token account
+        let mut instructions = vec![swap.clone()];
+        instructions.extend(close_wsol(&payer));
+        instructions.push(token_close.clone());
+
+        push_trailing_wsol_close(&mut instructions, &payer);
+
+        let close = close_wsol(&payer);
+        assert_eq!(instructions.len(), 2 + close.len());
+        assert_eq!(&instructions[..2], &[swap.clone(), token_close]);
+        assert_eq!(&instructions[2..], &close[..]);
+
+        let mut bare = vec![swap];
+        push_trailing_wsol_close(&mut bare, &payer);
+        assert_eq!(bare.last(), close.last());
+    }
+}
@@ -0,0 +1,219 @@
+//! Copy trading from decoded events: one call instead of per-protocol param assembly.
+//! 跟单：由解析后的交易事件直接构造参数并下单。
+//!
+//! The SDK does not depend on a particular parser. [`PumpFunTradeEvent`] implements
+//! [`CopyTradeEvent`] directly; the workspace crate `sol-trade-sdk-streamer` turns
+//! solana-streamer's PumpSwap and Bonk events into a [`DecodedTrade`]. Raydium CPMM / AMM v4 and
+//! Meteora DAMM v2 swap events carry no reserves: load the pool they name and wrap it with
+//! [`DecodedTrade::from_pool_params`]; for other parsers map the event into params once and wrap
+//! them in [`DecodedTrade`]. See `TradingClient::copy_trade`. [`DecodedTrade::from_pool_params`]
+//! also serves params loaded from a pool address (`TradingClient::buy_from_pool`).
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::constants::{USD1_TOKEN_ACCOUNT, USDC_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+use crate::trading::core::params::{DexParamEnum, PumpFunParams, PumpFunTradeEvent};
+use crate::trading::factory::DexType;
+use crate::TradeTokenType;
+
+/// A decoded trade that can be replayed on our own wallet. 可跟单的事件。
+pub trait CopyTradeEvent: Send + Sync {
+    fn dex_type(&self) -> DexType;
+
+    /// The traded token (bought on buys, sold on sells).
+    fn mint(&self) -> Pubkey;
+
+    /// Quote the pool trades against. Default `SOL`.
+    fn quote_token_type(&self) -> TradeTokenType {
+        TradeTokenType::SOL
+    }
+
+    /// Token program of `mint` when the event knows it.
+    fn mint_token_program(&self) -> Option<Pubkey> {
+        None
+    }
+
+    /// Protocol params for trading `mint`; the same params serve buys and sells.
+    fn protocol_params(&self) -> DexParamEnum;
+}
+
+impl CopyTradeEvent for PumpFunTradeEvent {
+    fn dex_type(&self) -> DexType {
+        DexType::PumpFun
+    }
+
+    fn mint(&self) -> Pubkey {
+        self.mint
+    }
+
+    fn quote_token_type(&self) -> TradeTokenType {
+        if self.quote_mint == USDC_TOKEN_ACCOUNT {
+            TradeTokenType::USDC
+        } else {
+            TradeTokenType::SOL
+        }
+    }
+
+    fn mint_token_program(&self) -> Option<Pubkey> {
+        (self.token_program != Pubkey::default()).then_some(self.token_program)
+    }
+
+    fn protocol_params(&self) -> DexParamEnum {
+        DexParamEnum::PumpFun(PumpFunParams::from_event(self, None))
+    }
+}
+
+/// Params already built from an event (e.g. with `PumpSwapParams::from_trade`), for protocols
+/// without a dedicated [`CopyTradeEvent`] impl.
+#[derive(Clone)]
+pub struct DecodedTrade {
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    pub quote: TradeTokenType,
+    pub params: DexParamEnum,
+    /// Token program of `mint`, when known.
+    pub mint_token_program: Option<Pubkey>,
+}
+
+impl DecodedTrade {
+    /// SOL-quoted trade of `mint`; the protocol is taken from `params`.
+    pub fn new(mint: Pubkey, params: DexParamEnum) -> Self {
+        let dex_type = params.dex_type();
+        Self { dex_type, mint, quote: TradeTokenType::SOL, params, mint_token_program: None }
+    }
+
+    pub fn with_quote(mut self, quote: TradeTokenType) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Trade of the non-quote side of a pool loaded by address (`from_pool_address_by_rpc`).
+    /// The quote is the side that is WSOL (traded as SOL), USDC or USD1, preferring the pool's
+    /// second mint. PumpFun and Bonk params carry no mints and are rejected.
+    /// 由池子参数确定交易代币与计价币。
+    pub fn from_pool_params(params: DexParamEnum) -> anyhow::Result<Self> {
+        let (first, second) = match &params {
+            DexParamEnum::PumpSwap(p) => (
+                (p.base_mint, Some(p.base_token_program)),
+                (p.quote_mint, Some(p.quote_token_program)),
+            ),
+            DexParamEnum::RaydiumCpmm(p) => (
+                (p.base_mint, Some(p.base_token_program)),
+                (p.quote_mint, Some(p.quote_token_program)),
+            ),
+            DexParamEnum::RaydiumAmmV4(p) => ((p.coin_mint, None), (p.pc_mint, None)),
+            DexParamEnum::MeteoraDammV2(p) => ((p.token_a_mint, None), (p.token_b_mint, None)),
+            DexParamEnum::PumpFun(_) | DexParamEnum::Bonk(_) => {
+                return Err(anyhow::anyhow!("PumpFun/Bonk params do not name the traded mint"))
+            }
+        };
+        let ((mint, mint_token_program), quote) = pool_buy_side(first, second)?;
+        let mut trade = Self::new(mint, params).with_quote(quote);
+        trade.mint_token_program = mint_token_program;
+        Ok(trade)
+    }
+}
+
+/// Quote token type for a quote-capable mint.
+fn quote_token_type_of(mint: &Pubkey) -> Option<TradeTokenType> {
+    if *mint == WSOL_TOKEN_ACCOUNT {
+        Some(TradeTokenType::SOL)
+    } else if *mint == USDC_TOKEN_ACCOUNT {
+        Some(TradeTokenType::USDC)
+    } else if *mint == USD1_TOKEN_ACCOUNT {
+        Some(TradeTokenType::USD1)
+    } else {
+        None
+    }
+}
+
+/// `(mint, token program)` of each pool side -> (side to buy, quote to pay with).
+fn pool_buy_side(
+    first: (Pubkey, Option<Pubkey>),
+    second: (Pubkey, Option<Pubkey>),
+) -> anyhow::Result<((Pubkey, Option<Pubkey>), TradeTokenType)> {
+    if let Some(quote) = quote_token_type_of(&second.0) {
+        Ok((first, quote))
+    } else if let Some(quote) = quote_token_type_of(&first.0) {
+        Ok((second, quote))
+    } else {
+        Err(anyhow::anyhow!(
+            "pool {} / {} has no SOL, USDC or USD1 side to trade against",
+            first.0,
+            second.0
+        ))
+    }
+}
+
+impl CopyTradeEvent for DecodedTrade {
+    fn dex_type(&self) -> DexType {
+        self.dex_type
+    }
+
+    fn mint(&self) -> Pubkey {
+        self.mint
+    }
+
+    fn quote_token_type(&self) -> TradeTokenType {
+        self.quote.clone()
+    }
+
+    fn mint_token_program(&self) -> Option<Pubkey> {
+        self.mint_token_program
+    }
+
+    fn protocol_params(&self) -> DexParamEnum {
+        self.params.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::TOKEN_PROGRAM_2022;
+
+    #[test]
+    fn pumpfun_events_and_decoded_trades_describe_the_trade() {
+        let event = PumpFunTradeEvent {
+            mint: Pubkey::new_unique(),
+            quote_mint: USDC_TOKEN_ACCOUNT,
+            token_program: TOKEN_PROGRAM_2022,
+            virtual_token_reserves: 1_000,
+            virtual_quote_reserves: 2_000,
+            ..Default::default()
+        };
+        let copy: &dyn CopyTradeEvent = &event;
+        assert_eq!(copy.dex_type(), DexType::PumpFun);
+        assert!(copy.quote_token_type() == TradeTokenType::USDC);
+        assert_eq!(copy.mint_token_program(), Some(TOKEN_PROGRAM_2022));
+        let DexParamEnum::PumpFun(params) = copy.protocol_params() else {
+            panic!("expected PumpFun params");
+        };
+        assert_eq!(params.bonding_curve.virtual_quote_reserves(), 2_000);
+
+        let decoded = DecodedTrade::new(event.mint, copy.protocol_params());
+        assert_eq!(decoded.dex_type(), DexType::PumpFun);
+        assert!(decoded.quote_token_type() == TradeTokenType::SOL);
+        assert_eq!(decoded.mint_token_program(), None);
+    }
+
+    #[test]
+    fn pool_buy_side_trades_the_non_quote_mint() {
+        let token = (Pubkey::new_unique(), Some(TOKEN_PROGRAM_2022));
+        let ((mint, program), quote) = pool_buy_side(token, (WSOL_TOKEN_ACCOUNT, None)).unwrap();
+        assert_eq!((mint, program), token);
+        assert!(quote == TradeTokenType::SOL);
+
+        let (side, quote) = pool_buy_side((USDC_TOKEN_ACCOUNT, None), token).unwrap();
+        assert_eq!(side, token);
+        assert!(quote == TradeTokenType::USDC);
+
+        // WSOL/USDC: the second mint is the quote
+        let (side, quote) =
+            pool_buy_side((USDC_TOKEN_ACCOUNT, None), (WSOL_TOKEN_ACCOUNT, None)).unwrap();
+        assert_eq!(side.0, USDC_TOKEN_ACCOUNT);
+        assert!(quote == TradeTokenType::SOL);
+
+        assert!(pool_buy_side(token, (Pubkey::new_unique(), None)).is_err());
+    }
+}
@@ -19,6 +19,7 @@ use solana_hash::Hash;
 use solana_message::AddressLookupTableAccount;
 use solana_sdk::{
     instruction::Instruction, pubkey::Pubkey, signature::Keypair, signature::Signature,
+    transaction::VersionedTransaction,
 };
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
@@ -36,10 +37,13 @@ type FnvHashMap<K, V> = HashMap<K, V, BuildHasherDefault<FnvHasher>>;
 
 use crate::{
     common::gas_fee_strategy::{GasFeeStrategyType, GasFeeStrategyValue},
-    common::{nonce_cache::DurableNonceInfo, GasFeeStrategy, SwqosSubmitTiming},
+    common::{
+        nonce_cache::{DurableNonceInfo, MultiNonceConfig},
+        GasFeeStrategy, ParallelStrategy, SolanaRpcClient, SwqosSubmitTiming,
+    },
     swqos::{SwqosClient, SwqosType, TradeType},
     trading::core::params::SenderConcurrencyConfig,
-    trading::{common::build_transaction, MiddlewareManager},
+    trading::{common::build_transaction, MiddlewareManager, TradeContext},
 };
 
 /// 与 transaction_pool::PARALLEL_SENDER_COUNT 一致，保证多路 build 不串行
@@ -48,10 +52,21 @@ const SWQOS_QUEUE_CAP: usize = 128;
 const SWQOS_DEDICATED_DEFAULT_THREADS: usize = 18;
 const FAST_SUBMIT_RESULT_TIMEOUT: Duration = Duration::from_secs(5);
 const FAST_SUBMIT_DRAIN_GRACE: Duration = Duration::from_millis(20);
+/// `getSignatureStatuses` interval while a staggered wave waits to land.
+const STAGGERED_LANDING_POLL: Duration = Duration::from_millis(20);
+
+/// Last-chance veto on each fully built and signed transaction (compute budget, tip, nonce
+/// included) right before it is submitted; `Err` drops that route's submit with
+/// `TradeError::SEND_VETOED`. Set via `TradingClient::with_on_before_send`.
+pub type BeforeSendHook = Arc<
+    dyn Fn(&VersionedTransaction) -> Result<(), crate::swqos::common::TradeError> + Send + Sync,
+>;
 
 /// Shared across all jobs in one batch; built once, cloned as single Arc per job (minimal hot-path clone).
 struct SwqosSharedContext {
     payer: Arc<Keypair>,
+    /// Co-signer funding created token accounts (`SwapParams::ata_rent_funder`).
+    ata_rent_funder: Option<Arc<Keypair>>,
     instructions: Arc<Vec<Instruction>>,
     address_lookup_table_accounts: Arc<Vec<AddressLookupTableAccount>>,
     recent_blockhash: Option<Hash>,
@@ -61,7 +76,16 @@ struct SwqosSharedContext {
     is_buy: bool,
     wait_transaction_confirmed: bool,
     with_tip: bool,
+    skip_compute_budget: bool,
+    on_before_send: Option<BeforeSendHook>,
+    /// Per-route nonces; when set each job carries its own `durable_nonce`.
+    multi_nonce: Option<Arc<MultiNonceConfig>>,
+    /// Passed to middleware building the full instruction list.
+    trade_context: Option<TradeContext>,
     collector: Arc<ResultCollector>,
+    /// Kept only when an RPC fallback is configured: the Default route's transaction if any,
+    /// else the first one built.
+    fallback_tx: Option<Mutex<Option<VersionedTransaction>>>,
 }
 
 /// One SWQOS submit task; only per-task data + one Arc to shared (reduces hot-path clones).
@@ -76,6 +100,9 @@ struct SwqosJob {
     strategy_type: GasFeeStrategyType,
     core_id: Option<core_affinity::CoreId>,
     use_affinity: bool,
+    wave: usize,
+    /// This route's nonce under `MultiNonceConfig`; otherwise the shared one is used.
+    durable_nonce: Option<DurableNonceInfo>,
 }
 
 async fn run_one_swqos_job(job: SwqosJob) {
@@ -87,21 +114,25 @@ async fn run_one_swqos_job(job: SwqosJob) {
     }
 
     let tip_amount = if s.with_tip { job.tip } else { 0.0 };
+    let durable_nonce = job.durable_nonce.as_ref().or(s.durable_nonce.as_ref());
 
     let transaction = match build_transaction(
         &s.payer,
+        s.ata_rent_funder.as_ref(),
         job.unit_limit,
         job.unit_price,
+        s.skip_compute_budget,
         s.instructions.as_ref(),
         s.address_lookup_table_accounts.as_slice(),
         s.recent_blockhash,
         s.middleware_manager.as_ref(),
         s.protocol_name,
         s.is_buy,
+        s.trade_context.as_ref(),
         job.swqos_type != SwqosType::Default,
         &job.tip_account,
         tip_amount,
-        s.durable_nonce.as_ref(),
+        durable_nonce,
     ) {
         Ok(tx) => tx,
         Err(e) => {
@@ -113,11 +144,35 @@ async fn run_one_swqos_job(job: SwqosJob) {
                 strategy_type: job.strategy_type,
                 landed_on_chain: false,
                 submit_done_us: crate::common::clock::now_micros(),
+                wave: job.wave,
             });
             return;
         }
     };
 
+    if let Some(hook) = &s.on_before_send {
+        if let Err(reason) = hook(&transaction) {
+            s.collector.submit(TaskResult {
+                success: false,
+                signature: Signature::default(),
+                error: Some(crate::swqos::common::TradeError::send_vetoed(&reason).into()),
+                swqos_type: job.swqos_type,
+                strategy_type: job.strategy_type,
+                landed_on_chain: false,
+                submit_done_us: crate::common::clock::now_micros(),
+                wave: job.wave,
+            });
+            return;
+        }
+    }
+
+    if let Some(slot) = &s.fallback_tx {
+        let mut slot = slot.lock();
+        if slot.is_none() || job.swqos_type == SwqosType::Default {
+            *slot = Some(transaction.clone());
+        }
+    }
+
     let (success, err, landed_on_chain) = match job
         .swqos_client
         .send_transaction(
@@ -134,6 +189,14 @@ async fn run_one_swqos_job(job: SwqosJob) {
         }
     };
 
+    if let (Some(pool), Some(account)) =
+        (&s.multi_nonce, job.durable_nonce.as_ref().and_then(|n| n.nonce_account))
+    {
+        if success || landed_on_chain {
+            pool.mark_consumed(account);
+        }
+    }
+
     let sig = transaction.signatures.first().copied().unwrap_or_default();
     s.collector.submit(TaskResult {
         success,
@@ -143,6 +206,7 @@ async fn run_one_swqos_job(job: SwqosJob) {
         strategy_type: job.strategy_type,
         landed_on_chain,
         submit_done_us: crate::common::clock::now_micros(),
+        wave: job.wave,
     });
 }
 
@@ -327,10 +391,11 @@ struct TaskResult {
     landed_on_chain: bool,
     /// Microsecond timestamp when this task finished (SWQOS returned); for per-SWQOS event→submit timing.
     submit_done_us: i64,
+    wave: usize,
 }
 
 /// Check if an error indicates the transaction landed on-chain (vs network/timeout error)
-fn is_landed_error(error: &anyhow::Error) -> bool {
+pub(crate) fn is_landed_error(error: &anyhow::Error) -> bool {
     use crate::swqos::common::TradeError;
 
     // If it's a TradeError with a non-zero code, the tx landed but failed on-chain
@@ -339,6 +404,10 @@ fn is_landed_error(error: &anyhow::Error) -> bool {
         if trade_error.code == 500 && trade_error.message.contains("timed out") {
             return false;
         }
+        // SDK-side errors (validation, SWQOS protocol errors) never mean the tx landed
+        if trade_error.is_sdk_error() {
+            return false;
+        }
         // Any other TradeError means the tx landed (e.g., ExceededSlippage = 6004)
         return trade_error.code > 0;
     }
@@ -358,7 +427,10 @@ struct ResultCollector {
     success_flag: Arc<AtomicBool>,
     landed_failed_flag: Arc<AtomicBool>, // 🔧 Tx landed on-chain but failed (nonce consumed)
     completed_count: Arc<AtomicUsize>,
-    total_tasks: usize,
+    /// Tasks enqueued so far; grows per wave under staggered/sequential strategies.
+    total_tasks: AtomicUsize,
+    /// Signatures of accepted submits not yet polled by [`Self::wait_for_wave_to_land`].
+    accepted: ArrayQueue<Signature>,
 }
 
 impl ResultCollector {
@@ -368,7 +440,74 @@ impl ResultCollector {
             success_flag: Arc::new(AtomicBool::new(false)),
             landed_failed_flag: Arc::new(AtomicBool::new(false)),
             completed_count: Arc::new(AtomicUsize::new(0)),
-            total_tasks: capacity,
+            total_tasks: AtomicUsize::new(capacity),
+            accepted: ArrayQueue::new(capacity),
+        }
+    }
+
+    /// Collector for a batch sent in waves: `capacity` is the whole batch, tasks are counted as
+    /// each wave is enqueued.
+    fn with_waves(capacity: usize) -> Self {
+        let collector = Self::new(capacity);
+        collector.total_tasks.store(0, Ordering::Release);
+        collector
+    }
+
+    fn add_tasks(&self, n: usize) {
+        self.total_tasks.fetch_add(n, Ordering::AcqRel);
+    }
+
+    #[inline]
+    fn total(&self) -> usize {
+        self.total_tasks.load(Ordering::Acquire)
+    }
+
+    /// Wait for the current wave: `true` if a route won or a tx landed-but-failed (stop sending),
+    /// `false` once every enqueued task failed or `window` elapsed (send the next wave).
+    async fn wait_for_wave(&self, window: Duration) -> bool {
+        let start = Instant::now();
+        loop {
+            if self.success_flag.load(Ordering::Acquire)
+                || self.landed_failed_flag.load(Ordering::Acquire)
+            {
+                return true;
+            }
+            if self.completed_count.load(Ordering::Acquire) >= self.total()
+                || start.elapsed() >= window
+            {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+    /// Staggered wave wait: a relay accepting the submit is not enough, stop (`true`) only once
+    /// an accepted signature shows up in `getSignatureStatuses` or a tx landed-but-failed.
+    /// `false` once every enqueued task failed or nothing landed within `window`.
+    async fn wait_for_wave_to_land(&self, rpc: &SolanaRpcClient, window: Duration) -> bool {
+        let start = Instant::now();
+        let mut accepted = Vec::new();
+        loop {
+            if self.landed_failed_flag.load(Ordering::Acquire) {
+                return true;
+            }
+            while let Some(signature) = self.accepted.pop() {
+                accepted.push(signature);
+            }
+            if !accepted.is_empty() {
+                if let Ok(statuses) = rpc.get_signature_statuses(&accepted).await {
+                    if statuses.value.iter().any(Option::is_some) {
+                        return true;
+                    }
+                }
+            } else if self.completed_count.load(Ordering::Acquire) >= self.total() {
+                return false;
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= window {
+                return false;
+            }
+            tokio::time::sleep(STAGGERED_LANDING_POLL.min(window - elapsed)).await;
         }
     }
 
@@ -377,6 +516,9 @@ impl ResultCollector {
         let is_success = result.success;
         let is_landed_failed = result.landed_on_chain && !result.success;
 
+        if is_success {
+            let _ = self.accepted.push(result.signature);
+        }
         let _ = self.results.push(result);
 
         if is_success {
@@ -433,7 +575,7 @@ impl ResultCollector {
             }
 
             let completed = self.completed_count.load(Ordering::Acquire);
-            if completed >= self.total_tasks {
+            if completed >= self.total() {
                 let mut signatures = Vec::new();
                 let mut last_error = None;
                 let mut any_success = false;
@@ -500,7 +642,7 @@ impl ResultCollector {
         loop {
             if self.success_flag.load(Ordering::Acquire)
                 || self.landed_failed_flag.load(Ordering::Acquire)
-                || self.completed_count.load(Ordering::Acquire) >= self.total_tasks
+                || self.completed_count.load(Ordering::Acquire) >= self.total()
                 || start.elapsed() >= timeout
             {
                 return self.get_first();
@@ -521,7 +663,7 @@ impl ResultCollector {
         let start = Instant::now();
         let primary = Duration::from_secs(timeout_secs);
         let poll_interval = Duration::from_millis(2);
-        while self.completed_count.load(Ordering::Acquire) < self.total_tasks {
+        while self.completed_count.load(Ordering::Acquire) < self.total() {
             if start.elapsed() > primary {
                 break;
             }
@@ -538,10 +680,10 @@ impl ResultCollector {
 type GasFeeConfig = (SwqosType, GasFeeStrategyType, GasFeeStrategyValue);
 
 #[derive(Debug, Clone, Copy)]
-struct SwqosTaskConfig {
-    task_ordinal: usize,
-    swqos_index: usize,
-    gas_fee_config: GasFeeConfig,
+pub(crate) struct SwqosTaskConfig {
+    pub(crate) task_ordinal: usize,
+    pub(crate) swqos_index: usize,
+    pub(crate) gas_fee_config: GasFeeConfig,
 }
 
 impl TaskResult {
@@ -551,11 +693,13 @@ impl TaskResult {
             swqos_type: self.swqos_type,
             strategy_type: self.strategy_type,
             submit_done_us: self.submit_done_us,
+            wave: self.wave,
+            rpc_fallback: false,
         }
     }
 }
 
-fn select_swqos_task_configs(
+pub(crate) fn select_swqos_task_configs(
     swqos_types: &[SwqosType],
     gas_fee_configs: &[GasFeeConfig],
     with_tip: bool,
@@ -592,12 +736,58 @@ fn select_swqos_task_configs(
     task_configs
 }
 
+/// Position of each selected route (`swqos_index`) in selection order. Every fee lane of a route
+/// shares its slot, and so its `MultiNonceConfig` nonce.
+fn route_slots(tasks: &[SwqosTaskConfig]) -> FnvHashMap<usize, usize> {
+    let mut slots = FnvHashMap::default();
+    for task in tasks {
+        let next = slots.len();
+        slots.entry(task.swqos_index).or_insert(next);
+    }
+    slots
+}
+
+/// Split selected tasks into send waves; every fee lane of one route stays in the same wave.
+fn plan_waves(
+    strategy: ParallelStrategy,
+    tasks: Vec<SwqosTaskConfig>,
+) -> Vec<Vec<SwqosTaskConfig>> {
+    match strategy {
+        ParallelStrategy::RaceAll => vec![tasks],
+        ParallelStrategy::Staggered { .. } => {
+            let first = tasks.first().map(|t| t.swqos_index);
+            let (head, rest): (Vec<_>, Vec<_>) =
+                tasks.into_iter().partition(|t| Some(t.swqos_index) == first);
+            if rest.is_empty() {
+                vec![head]
+            } else {
+                vec![head, rest]
+            }
+        }
+        ParallelStrategy::Sequential => {
+            let mut waves: Vec<Vec<SwqosTaskConfig>> = Vec::new();
+            for task in tasks {
+                match waves.last_mut() {
+                    Some(wave) if wave[0].swqos_index == task.swqos_index => wave.push(task),
+                    _ => waves.push(vec![task]),
+                }
+            }
+            waves
+        }
+    }
+}
+
 /// Execute trade on multiple SWQOS clients in parallel; returns success flag, all signatures, and last error.
 ///
 /// `sender_config` merges sender_thread_cores, effective_core_ids, max_sender_concurrency (precomputed at SDK init; no get_core_ids on hot path).
+/// `parallel_strategy` decides whether routes are raced, staggered or tried one by one; the wave
+/// each result came from is reported in `SwqosSubmitTiming::wave`. With `landing_rpc`, a
+/// staggered first wave only counts once its signature lands, see [`ParallelStrategy`]. A lone Default (plain RPC)
+/// route with one fee lane and no RPC fallback is sent inline, see [`send_single_rpc`].
 pub async fn execute_parallel(
     swqos_clients: &[Arc<SwqosClient>],
     payer: Arc<Keypair>,
+    ata_rent_funder: Option<Arc<Keypair>>,
     instructions: Vec<Instruction>,
     address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
     recent_blockhash: Option<Hash>,
@@ -612,6 +802,13 @@ pub async fn execute_parallel(
     use_dedicated_sender_threads: bool,
     sender_config: SenderConcurrencyConfig,
     check_min_tip: bool,
+    parallel_strategy: ParallelStrategy,
+    rpc_fallback: Option<Arc<SolanaRpcClient>>,
+    landing_rpc: Option<Arc<SolanaRpcClient>>,
+    skip_compute_budget: bool,
+    on_before_send: Option<BeforeSendHook>,
+    multi_nonce: Option<Arc<MultiNonceConfig>>,
+    trade_context: Option<TradeContext>,
 ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>)> {
     if swqos_clients.is_empty() {
         return Err(anyhow!("swqos_clients is empty"));
@@ -650,12 +847,49 @@ pub async fn execute_parallel(
     if selected_task_configs.is_empty() {
         return Err(anyhow!("No available gas fee strategy configs"));
     }
+    let route_slots = route_slots(&selected_task_configs);
+    if let Some(pool) = &multi_nonce {
+        if pool.nonces().len() < route_slots.len() {
+            return Err(anyhow!(
+                "MultiNonceConfig has {} nonces for {} SWQOS routes",
+                pool.nonces().len(),
+                route_slots.len()
+            ));
+        }
+    }
+
+    if rpc_fallback.is_none() && is_single_rpc_route(&swqos_types, &selected_task_configs) {
+        let gas_fee_config = selected_task_configs[0].gas_fee_config;
+        return Ok(send_single_rpc(
+            &swqos_clients[selected_task_configs[0].swqos_index],
+            &payer,
+            ata_rent_funder.as_ref(),
+            &instructions,
+            &address_lookup_table_accounts,
+            recent_blockhash,
+            multi_nonce
+                .as_ref()
+                .and_then(|pool| pool.nonce_for_route(0))
+                .or(durable_nonce.as_ref()),
+            middleware_manager.as_ref(),
+            protocol_name,
+            is_buy,
+            wait_transaction_confirmed,
+            gas_fee_config,
+            skip_compute_budget,
+            on_before_send.as_ref(),
+            multi_nonce.as_deref(),
+            trade_context.as_ref(),
+        )
+        .await);
+    }
 
     // Task preparation completed: one shared context (clone once per batch), then minimal per-task data.
     let channel_count = selected_task_configs.len().max(1);
-    let collector = Arc::new(ResultCollector::new(channel_count));
+    let collector = Arc::new(ResultCollector::with_waves(channel_count));
     let shared = Arc::new(SwqosSharedContext {
         payer,
+        ata_rent_funder,
         instructions,
         address_lookup_table_accounts: Arc::new(address_lookup_table_accounts),
         recent_blockhash,
@@ -665,7 +899,12 @@ pub async fn execute_parallel(
         is_buy,
         wait_transaction_confirmed,
         with_tip,
+        skip_compute_budget,
+        on_before_send,
+        multi_nonce: multi_nonce.clone(),
+        trade_context,
         collector: collector.clone(),
+        fallback_tx: rpc_fallback.is_some().then(|| Mutex::new(None)),
     });
 
     let (queue, notify) = if use_dedicated_sender_threads {
@@ -679,20 +918,21 @@ pub async fn execute_parallel(
         (q.clone(), SWQOS_NOTIFY.get_or_init(|| Arc::new(Notify::new())).clone())
     };
 
-    {
-        let effective_core_ids = sender_config.effective_core_ids.as_slice();
-        let core_len = effective_core_ids.len().max(1);
-        let mut tip_cache: FnvHashMap<*const (), Arc<Pubkey>> =
-            FnvHashMap::with_capacity_and_hasher(
-                selected_task_configs.len(),
-                BuildHasherDefault::default(),
-            );
-        for task_config in selected_task_configs {
+    let effective_core_ids = sender_config.effective_core_ids.as_slice();
+    let core_len = effective_core_ids.len().max(1);
+    let mut tip_cache: FnvHashMap<usize, Arc<Pubkey>> =
+        FnvHashMap::with_capacity_and_hasher(channel_count, BuildHasherDefault::default());
+    let waves = plan_waves(parallel_strategy, selected_task_configs);
+    let wave_count = waves.len();
+    for (wave, tasks) in waves.into_iter().enumerate() {
+        collector.add_tasks(tasks.len());
+        for task_config in tasks {
             let swqos_client = swqos_clients[task_config.swqos_index].clone();
             let core_id = effective_core_ids.get(task_config.task_ordinal % core_len).copied();
             let swqos_type = swqos_client.get_swqos_type();
             let gas_fee_strategy_config = task_config.gas_fee_config;
-            let key = Arc::as_ptr(&swqos_client) as *const ();
+            // Pointer as usize keeps the future Send across the wave wait below.
+            let key = Arc::as_ptr(&swqos_client) as *const () as usize;
             let tip_account = match tip_cache.get(&key) {
                 Some(t) => t.clone(),
                 None => {
@@ -718,6 +958,10 @@ pub async fn execute_parallel(
                 strategy_type: gas_fee_strategy_config.1,
                 core_id,
                 use_affinity: !effective_core_ids.is_empty(),
+                wave,
+                durable_nonce: multi_nonce.as_ref().and_then(|pool| {
+                    pool.nonce_for_route(route_slots[&task_config.swqos_index]).cloned()
+                }),
             };
             if let Err(job) = queue.push(job) {
                 shared.collector.submit(TaskResult {
@@ -728,12 +972,27 @@ pub async fn execute_parallel(
                     strategy_type: job.strategy_type,
                     landed_on_chain: false,
                     submit_done_us: crate::common::clock::now_micros(),
+                    wave,
                 });
             }
         }
-    }
+        notify.notify_waiters();
 
-    notify.notify_waiters();
+        if wave + 1 < wave_count {
+            let stop = match (parallel_strategy, landing_rpc.as_deref()) {
+                (ParallelStrategy::Staggered { delay }, Some(rpc)) => {
+                    collector.wait_for_wave_to_land(rpc, delay).await
+                }
+                (ParallelStrategy::Staggered { delay }, None) => {
+                    collector.wait_for_wave(delay).await
+                }
+                _ => collector.wait_for_wave(FAST_SUBMIT_RESULT_TIMEOUT).await,
+            };
+            if stop {
+                break;
+            }
+        }
+    }
 
     // All jobs enqueued (no spawn on hot path)
 
@@ -755,15 +1014,164 @@ pub async fn execute_parallel(
                 vec![],
             ))
         };
-        let (success, signatures, last_error, submit_timings) = ret;
-        return Ok((success, signatures, last_error, submit_timings));
+        return Ok(send_rpc_fallback(&shared, rpc_fallback.as_deref(), ret, wave_count).await);
     }
 
-    if let Some(result) = collector.wait_for_success().await {
-        let (success, signatures, last_error, submit_timings) = result;
-        Ok((success, signatures, last_error, submit_timings))
-    } else {
-        Err(anyhow!("All transactions failed"))
+    match collector.wait_for_success().await {
+        Some(result) => {
+            Ok(send_rpc_fallback(&shared, rpc_fallback.as_deref(), result, wave_count).await)
+        }
+        None if rpc_fallback.is_some() => Ok(send_rpc_fallback(
+            &shared,
+            rpc_fallback.as_deref(),
+            (false, vec![], Some(anyhow!("All transactions failed")), vec![]),
+            wave_count,
+        )
+        .await),
+        None => Err(anyhow!("All transactions failed")),
+    }
+}
+
+/// Only one plain-RPC (`SwqosType::Default`) route with one fee lane: nothing to race, and a
+/// Default route never carries a tip.
+#[inline]
+fn is_single_rpc_route(swqos_types: &[SwqosType], task_configs: &[SwqosTaskConfig]) -> bool {
+    matches!(swqos_types, [SwqosType::Default]) && task_configs.len() == 1
+}
+
+/// Fast path for [`is_single_rpc_route`]: build and `sendTransaction` on the caller's task —
+/// no tip-account lookup, sender queue, waves or result collector. 单 RPC 无小费直发。
+async fn send_single_rpc(
+    swqos_client: &SwqosClient,
+    payer: &Arc<Keypair>,
+    ata_rent_funder: Option<&Arc<Keypair>>,
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Option<Hash>,
+    durable_nonce: Option<&DurableNonceInfo>,
+    middleware_manager: Option<&Arc<MiddlewareManager>>,
+    protocol_name: &'static str,
+    is_buy: bool,
+    wait_transaction_confirmed: bool,
+    gas_fee_config: GasFeeConfig,
+    skip_compute_budget: bool,
+    on_before_send: Option<&BeforeSendHook>,
+    multi_nonce: Option<&MultiNonceConfig>,
+    trade_context: Option<&TradeContext>,
+) -> (bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>) {
+    let timing = |strategy_type| SwqosSubmitTiming {
+        swqos_type: SwqosType::Default,
+        strategy_type,
+        submit_done_us: crate::common::clock::now_micros(),
+        wave: 0,
+        rpc_fallback: false,
+    };
+    let transaction = match build_transaction(
+        payer,
+        ata_rent_funder,
+        gas_fee_config.2.cu_limit,
+        gas_fee_config.2.cu_price,
+        skip_compute_budget,
+        instructions,
+        address_lookup_table_accounts,
+        recent_blockhash,
+        middleware_manager,
+        protocol_name,
+        is_buy,
+        trade_context,
+        false,
+        &Pubkey::default(),
+        0.0,
+        durable_nonce,
+    ) {
+        Ok(tx) => tx,
+        Err(e) => {
+            return (false, vec![Signature::default()], Some(e), vec![timing(gas_fee_config.1)])
+        }
+    };
+    if let Some(hook) = on_before_send {
+        if let Err(reason) = hook(&transaction) {
+            let error = crate::swqos::common::TradeError::send_vetoed(&reason).into();
+            return (
+                false,
+                vec![Signature::default()],
+                Some(error),
+                vec![timing(gas_fee_config.1)],
+            );
+        }
+    }
+
+    let signature = transaction.signatures.first().copied().unwrap_or_default();
+    let trade_type = if is_buy { TradeType::Buy } else { TradeType::Sell };
+    let (success, error, landed_on_chain) = match swqos_client
+        .send_transaction(trade_type, &transaction, wait_transaction_confirmed)
+        .await
+    {
+        Ok(()) => (true, None, true),
+        Err(e) => {
+            let landed = is_landed_error(&e);
+            (false, Some(e), landed)
+        }
+    };
+    if let (Some(pool), Some(account)) = (multi_nonce, durable_nonce.and_then(|n| n.nonce_account))
+    {
+        if success || landed_on_chain {
+            pool.mark_consumed(account);
+        }
+    }
+    (success, vec![signature], error, vec![timing(gas_fee_config.1)])
+}
+
+/// Last resort after every SWQOS route failed: resend one already-built transaction through
+/// plain RPC. Skipped when a route succeeded or a transaction landed but failed on-chain (the
+/// blockhash/nonce outcome is already decided). 全部 SWQOS 失败后经 RPC 重发一次。
+async fn send_rpc_fallback(
+    shared: &SwqosSharedContext,
+    rpc: Option<&SolanaRpcClient>,
+    result: (bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>),
+    wave: usize,
+) -> (bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>) {
+    let (success, mut signatures, last_error, mut submit_timings) = result;
+    let Some(rpc) = rpc else {
+        return (success, signatures, last_error, submit_timings);
+    };
+    if success || shared.collector.landed_failed_flag.load(Ordering::Acquire) {
+        return (success, signatures, last_error, submit_timings);
+    }
+    let Some(transaction) = shared.fallback_tx.as_ref().and_then(|slot| slot.lock().take()) else {
+        return (success, signatures, last_error, submit_timings);
+    };
+    let config = solana_client::rpc_config::RpcSendTransactionConfig {
+        skip_preflight: true,
+        preflight_commitment: Some(solana_commitment_config::CommitmentLevel::Processed),
+        encoding: Some(solana_transaction_status::UiTransactionEncoding::Base64),
+        max_retries: Some(3),
+        min_context_slot: None,
+    };
+    match rpc.send_transaction_with_config(&transaction, config).await {
+        Ok(signature) => {
+            if crate::common::sdk_log::sdk_log_enabled() {
+                println!("⚠️ All SWQOS routes failed; submitted via RPC fallback: {}", signature);
+            }
+            if !signatures.contains(&signature) {
+                signatures.push(signature);
+            }
+            submit_timings.push(SwqosSubmitTiming {
+                swqos_type: SwqosType::Default,
+                strategy_type: GasFeeStrategyType::Normal,
+                submit_done_us: crate::common::clock::now_micros(),
+                wave,
+                rpc_fallback: true,
+            });
+            (true, signatures, None, submit_timings)
+        }
+        Err(e) => {
+            let error = match last_error {
+                Some(last) => anyhow!("{}; RPC fallback failed: {}", last, e),
+                None => anyhow!("RPC fallback failed: {}", e),
+            };
+            (false, signatures, Some(error), submit_timings)
+        }
     }
 }
 
@@ -775,6 +1183,100 @@ mod tests {
         GasFeeStrategyValue { cu_limit: 100_000, cu_price, tip }
     }
 
+    #[tokio::test]
+    async fn send_single_rpc_returns_the_signature_vetoes_and_consumes_the_nonce() {
+        use crate::common::MockSolanaRpcClient;
+        use crate::swqos::{common::TradeError, solana_rpc::SolRpcClient};
+        use crate::test_fixtures::{payer, POOL};
+        use solana_rpc_client_api::request::RpcRequest;
+        use solana_sdk::signer::Signer;
+
+        let payer = payer();
+        let instructions =
+            [solana_system_interface::instruction::transfer(&payer.pubkey(), &POOL, 1)];
+        let nonce = DurableNonceInfo {
+            nonce_account: Some(Pubkey::new_unique()),
+            current_nonce: Some(Hash::new_unique()),
+        };
+        let gas = (SwqosType::Default, GasFeeStrategyType::Normal, value(200_000, 0.0));
+        let send = |rpc: MockSolanaRpcClient,
+                    hook: Option<BeforeSendHook>,
+                    pool: Arc<MultiNonceConfig>| {
+            let (payer, nonce, instructions) = (payer.clone(), nonce.clone(), instructions.clone());
+            async move {
+                let client = SolRpcClient::new(Arc::new(rpc.build()));
+                send_single_rpc(
+                    &client,
+                    &payer,
+                    None,
+                    &instructions,
+                    &[],
+                    None,
+                    Some(&nonce),
+                    None,
+                    "PumpFun",
+                    true,
+                    false,
+                    gas,
+                    false,
+                    hook.as_ref(),
+                    Some(pool.as_ref()),
+                    None,
+                )
+                .await
+            }
+        };
+        // signing is deterministic: the signature the node must echo back is known up front
+        let expected = build_transaction(
+            &payer,
+            None,
+            gas.2.cu_limit,
+            gas.2.cu_price,
+            false,
+            &instructions,
+            &[],
+            None,
+            None,
+            "PumpFun",
+            true,
+            None,
+            false,
+            &Pubkey::default(),
+            0.0,
+            Some(&nonce),
+        )
+        .unwrap()
+        .signatures[0];
+
+        let pool = Arc::new(MultiNonceConfig::new(vec![nonce.clone()]));
+        let rpc = MockSolanaRpcClient::new()
+            .with_response(RpcRequest::SendTransaction, serde_json::json!(expected.to_string()));
+        let (ok, signatures, error, timings) = send(rpc, None, pool.clone()).await;
+        assert!(ok && error.is_none(), "{:?}", error);
+        assert_eq!(signatures, vec![expected]);
+        assert_eq!(timings[0].swqos_type, SwqosType::Default);
+        assert_eq!(pool.consumed(), vec![nonce.nonce_account.unwrap()]);
+
+        // a veto returns before anything is sent: nothing queued, nonce untouched
+        let pool = Arc::new(MultiNonceConfig::new(vec![nonce.clone()]));
+        let hook: BeforeSendHook = Arc::new(|_| Err(TradeError::new(1, "blocked")));
+        let (ok, signatures, error, _) =
+            send(MockSolanaRpcClient::new(), Some(hook), pool.clone()).await;
+        assert!(!ok);
+        assert_eq!(signatures, vec![Signature::default()]);
+        let error = error.unwrap().downcast::<TradeError>().unwrap();
+        assert_eq!(error.code, TradeError::SEND_VETOED);
+        assert!(pool.consumed().is_empty());
+
+        // a rejected send never landed: the nonce stays reusable
+        let rpc = MockSolanaRpcClient::new()
+            .with_response(RpcRequest::SendTransaction, serde_json::Value::Null);
+        let (ok, signatures, error, _) = send(rpc, None, pool.clone()).await;
+        assert!(!ok && error.is_some());
+        assert_eq!(signatures, vec![expected]);
+        assert!(pool.consumed().is_empty());
+    }
+
     #[test]
     fn select_task_configs_keeps_two_fee_lanes_per_swqos() {
         let swqos_types = [SwqosType::Jito, SwqosType::Helius];
@@ -836,6 +1338,83 @@ mod tests {
         assert_eq!(selected[0].gas_fee_config.2.tip, 0.0);
     }
 
+    #[test]
+    fn single_rpc_route_only_for_one_default_client_with_one_lane() {
+        let default_only = [SwqosType::Default];
+        let normal = (SwqosType::Default, GasFeeStrategyType::Normal, value(700_000, 0.0));
+        let one_lane = select_swqos_task_configs(&default_only, &[normal], true, true, |_| 0.0);
+        assert!(is_single_rpc_route(&default_only, &one_lane));
+
+        // two fee lanes still race each other through the worker pool
+        let mut fast = normal;
+        fast.1 = GasFeeStrategyType::LowTipHighCuPrice;
+        let two_lanes =
+            select_swqos_task_configs(&default_only, &[normal, fast], true, true, |_| 0.0);
+        assert!(!is_single_rpc_route(&default_only, &two_lanes));
+
+        let with_jito = [SwqosType::Jito, SwqosType::Default];
+        let selected = select_swqos_task_configs(&with_jito, &[normal], true, false, |_| 0.0);
+        assert_eq!(selected.len(), 1);
+        assert!(!is_single_rpc_route(&with_jito, &selected));
+    }
+
+    #[test]
+    fn plan_waves_groups_fee_lanes_by_route() {
+        let swqos_types = [SwqosType::Jito, SwqosType::Helius, SwqosType::Default];
+        let configs = [
+            (SwqosType::Jito, GasFeeStrategyType::LowTipHighCuPrice, value(400_000, 0.002)),
+            (SwqosType::Jito, GasFeeStrategyType::HighTipLowCuPrice, value(180_000, 0.005)),
+            (SwqosType::Helius, GasFeeStrategyType::LowTipHighCuPrice, value(400_000, 0.002)),
+            (SwqosType::Default, GasFeeStrategyType::Normal, value(700_000, 0.0)),
+        ];
+        let selected = select_swqos_task_configs(&swqos_types, &configs, true, false, |_| 0.0);
+        let routes = |waves: Vec<Vec<SwqosTaskConfig>>| {
+            waves
+                .iter()
+                .map(|w| w.iter().map(|t| t.swqos_index).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            routes(plan_waves(ParallelStrategy::RaceAll, selected.clone())),
+            vec![vec![0, 0, 1, 2]]
+        );
+        assert_eq!(
+            routes(plan_waves(
+                ParallelStrategy::Staggered { delay: Duration::from_millis(50) },
+                selected.clone()
+            )),
+            vec![vec![0, 0], vec![1, 2]]
+        );
+        assert_eq!(
+            routes(plan_waves(ParallelStrategy::Sequential, selected)),
+            vec![vec![0, 0], vec![1], vec![2]]
+        );
+    }
+
+    #[tokio::test]
+    async fn wait_for_wave_stops_on_win_and_continues_after_failures() {
+        let result = |success: bool| TaskResult {
+            success,
+            signature: Signature::default(),
+            error: (!success).then(|| anyhow!("rejected")),
+            swqos_type: SwqosType::Jito,
+            strategy_type: GasFeeStrategyType::Normal,
+            landed_on_chain: false,
+            submit_done_us: 0,
+            wave: 0,
+        };
+
+        let collector = ResultCollector::with_waves(2);
+        collector.add_tasks(1);
+        collector.submit(result(false));
+        assert!(!collector.wait_for_wave(Duration::from_secs(5)).await);
+
+        collector.add_tasks(1);
+        collector.submit(result(true));
+        assert!(collector.wait_for_wave(Duration::from_secs(5)).await);
+    }
+
     #[tokio::test]
     async fn wait_for_all_submitted_timeout_is_bounded() {
         let collector = ResultCollector::new(1);
@@ -11,18 +11,32 @@ use std::{
 #[allow(unused_imports)]
 use tracing::{info, trace, warn};
 
-use super::{params::SwapParams, traits::InstructionBuilder};
+use super::{
+    params::{AtaCreationMode, SwapParams},
+    traits::{check_ata_creation_cap, is_ata_creation, InstructionBuilder},
+};
 use crate::swqos::TradeType;
 use crate::{
-    common::{nonce_cache::DurableNonceInfo, GasFeeStrategy, SolanaRpcClient, SwqosSubmitTiming},
+    common::{
+        nonce_cache::{claim_nonce, DurableNonceInfo},
+        GasFeeStrategy, SolanaRpcClient, SwqosSubmitTiming,
+    },
     perf::syscall_bypass::SystemCallBypassManager,
-    swqos::common::poll_any_transaction_confirmation,
+    swqos::{common::poll_any_transaction_confirmation, watchdog::SwqosWatchdog},
     trading::core::{
-        async_executor::execute_parallel,
+        async_executor::{execute_parallel, is_landed_error},
         execution::{InstructionProcessor, Prefetch},
         traits::TradeExecutor,
     },
-    trading::MiddlewareManager,
+    trading::{
+        common::{
+            memo::build_memo_instruction, protocol_overrides::apply_protocol_overrides,
+            push_trailing_wsol_close,
+        },
+        cu_learner::CuLearner,
+        simulation::SimulationCapture,
+        MiddlewareManager, TradeContext,
+    },
 };
 use once_cell::sync::Lazy;
 
@@ -35,6 +49,85 @@ static SYSCALL_BYPASS: Lazy<SystemCallBypassManager> = Lazy::new(|| {
         .expect("Failed to create SystemCallBypassManager")
 });
 
+/// Protocol instructions for `params`' direction, before compute budget, tip and signing:
+/// built by `builder`, then protocol overrides, the `max_ata_creations` cap, preprocessing,
+/// middleware and the memo. Shared by [`GenericTradeExecutor`] and
+/// [`PreparedTrade`](super::prepared::PreparedTrade); also returns the middleware context
+/// (set only with a middleware manager) for the transaction-level middleware pass.
+pub(crate) async fn build_protocol_instructions(
+    builder: &dyn InstructionBuilder,
+    protocol_name: &'static str,
+    params: &SwapParams,
+) -> Result<(Vec<Instruction>, Option<TradeContext>)> {
+    let is_buy = params.is_buy();
+    let mut instructions = if is_buy {
+        builder.build_buy_instructions(params).await?
+    } else {
+        builder.build_sell_instructions(params).await?
+    };
+    apply_protocol_overrides(params.protocol_params.dex_type(), &mut instructions);
+    check_ata_creation_cap(&instructions, params.max_ata_creations)?;
+
+    InstructionProcessor::preprocess(&instructions)?;
+
+    let trade_context = params
+        .middleware_manager
+        .is_some()
+        .then(|| TradeContext::from_swap_params(params, protocol_name, is_buy));
+    let mut instructions = match (&params.middleware_manager, &trade_context) {
+        (Some(middleware_manager), Some(context)) => middleware_manager
+            .apply_middlewares_process_protocol_instructions_with_context(instructions, context)?,
+        _ => instructions,
+    };
+    if let Some(memo) = params.memo.as_deref() {
+        instructions.push(build_memo_instruction(memo)?);
+    }
+    Ok((instructions, trade_context))
+}
+
+/// Post-build steps of [`build_protocol_instructions`]' output, shared by
+/// [`GenericTradeExecutor`] and [`PreparedTrade`](super::prepared::PreparedTrade): the trailing
+/// WSOL close for `receive_native_sol` sells, then the `ata_creation` split, sending and
+/// confirming any split-off account creation first. Returns the swap instructions and, for buys
+/// with `cleanup_on_failure`, the token accounts the pre-transaction created. Simulation keeps
+/// everything inline.
+pub(crate) async fn finish_swap_instructions(
+    params: &SwapParams,
+    mut instructions: Vec<Instruction>,
+) -> Result<(Vec<Instruction>, Vec<(Pubkey, Pubkey)>)> {
+    use solana_sdk::signer::Signer;
+
+    let is_buy = params.is_buy();
+    let payer = params.payer.pubkey();
+    if params.receive_native_sol && !is_buy {
+        push_trailing_wsol_close(&mut instructions, &payer);
+    }
+    if params.simulate {
+        return Ok((instructions, Vec::new()));
+    }
+
+    // SeparateTx / Auto: create token accounts in a confirmed pre-transaction, then send a lean swap.
+    let mut created_accounts = Vec::new();
+    let instructions = match split_ata_creation(
+        params.ata_creation,
+        instructions,
+        &payer,
+        &params.address_lookup_table_accounts,
+        params.log_enabled,
+    ) {
+        (pre, swap) if !pre.is_empty() => {
+            if is_buy && params.cleanup_on_failure {
+                created_accounts =
+                    not_yet_created(params.rpc.as_deref(), created_token_accounts(&pre)).await;
+            }
+            send_ata_creation_tx(params, pre, is_buy).await?;
+            swap
+        }
+        (_, swap) => swap,
+    };
+    Ok((instructions, created_accounts))
+}
+
 /// Generic trade executor implementation
 pub struct GenericTradeExecutor {
     instruction_builder: Arc<dyn InstructionBuilder>,
@@ -54,7 +147,7 @@ impl GenericTradeExecutor {
 impl TradeExecutor for GenericTradeExecutor {
     async fn swap(
         &self,
-        params: SwapParams,
+        mut params: SwapParams,
     ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>)> {
         // Sample total start only when logging or simulate. 仅在有日志或 simulate 时取起点。
         let total_start = (params.log_enabled || params.simulate).then(Instant::now);
@@ -64,31 +157,43 @@ impl TradeExecutor for GenericTradeExecutor {
             None
         };
 
-        let is_buy =
-            params.trade_type == TradeType::Buy || params.trade_type == TradeType::CreateAndBuy;
+        let is_buy = params.is_buy();
 
         Prefetch::keypair(&params.payer);
 
-        // Time build only when log_enabled to avoid cold-path syscalls. 仅 log_enabled 时计时，减少冷路径 syscall。
-        let build_start = params.log_enabled.then(Instant::now);
-        let instructions = if is_buy {
-            self.instruction_builder.build_buy_instructions(&params).await?
-        } else {
-            self.instruction_builder.build_sell_instructions(&params).await?
+        // Reject logically-invalid params (protocol, zero fixed output) before the builder runs.
+        params.validate()?;
+        params.apply_token_program_overrides();
+        // 同进程内同一 nonce 值只允许一笔交易在途（模拟不消耗 nonce）
+        let _nonce_claim = match params.durable_nonce.as_ref() {
+            Some(info) if !params.simulate => Some(claim_nonce(info)?),
+            _ => None,
+        };
+        let _multi_nonce_claims = match params.multi_nonce.as_ref() {
+            Some(pool) if !params.simulate => Some(pool.claim_all()?),
+            _ => None,
         };
-        let _build_elapsed = build_start.map(|s| s.elapsed()).unwrap_or(Duration::ZERO);
 
-        InstructionProcessor::preprocess(&instructions)?;
+        // 未设置 cu_limit 的通道使用学习到的建议值（需开启 auto_apply）
+        if let Some(learner) = params.cu_learner.as_ref() {
+            if let Some(filled) =
+                learner.apply(&params.gas_fee_strategy, self.protocol_name, is_buy)
+            {
+                params.gas_fee_strategy = filled;
+            }
+        }
 
-        let final_instructions = match &params.middleware_manager {
-            Some(middleware_manager) => middleware_manager
-                .apply_middlewares_process_protocol_instructions(
-                    instructions,
-                    self.protocol_name,
-                    is_buy,
-                )?,
-            None => instructions,
-        };
+        // Time build only when log_enabled to avoid cold-path syscalls. 仅 log_enabled 时计时，减少冷路径 syscall。
+        let build_start = params.log_enabled.then(Instant::now);
+        let (final_instructions, trade_context) = build_protocol_instructions(
+            self.instruction_builder.as_ref(),
+            self.protocol_name,
+            &params,
+        )
+        .await?;
+        let _build_elapsed = build_start.map(|s| s.elapsed()).unwrap_or(Duration::ZERO);
+        let (final_instructions, created_accounts) =
+            finish_swap_instructions(&params, final_instructions).await?;
 
         let build_end_us = (params.log_enabled && crate::common::sdk_log::sdk_log_enabled())
             .then(crate::common::clock::now_micros);
@@ -103,15 +208,23 @@ impl TradeExecutor for GenericTradeExecutor {
             let result = simulate_transaction(
                 params.rpc,
                 params.payer,
+                params.ata_rent_funder,
                 final_instructions,
                 address_lookup_table_accounts,
                 params.recent_blockhash,
-                params.durable_nonce,
+                // multi-nonce: simulate against the first route's nonce
+                params.durable_nonce.or_else(|| {
+                    params.multi_nonce.as_ref().and_then(|pool| pool.nonce_for_route(0).cloned())
+                }),
                 params.middleware_manager,
                 self.protocol_name,
                 is_buy,
+                trade_context.as_ref(),
                 if is_buy { true } else { params.with_tip },
                 params.gas_fee_strategy,
+                params.cu_learner.as_deref(),
+                params.skip_compute_budget,
+                params.simulation_capture.as_deref(),
             )
             .await;
             let send_elapsed = send_start.map(|s| s.elapsed()).unwrap_or(Duration::ZERO);
@@ -161,11 +274,19 @@ impl TradeExecutor for GenericTradeExecutor {
         // Each SWQOS lane may submit a distinct transaction because relay tips
         // can use different accounts, so confirmation must be able to poll every
         // returned signature when the caller opts in.
-        let wait_for_all_submits = params.wait_for_all_submits;
+        // Multi-nonce: every route may land, so collect every submit.
+        let wait_for_all_submits = params.wait_for_all_submits || params.multi_nonce.is_some();
         let sender_config = params.sender_concurrency_config();
+        // 看门狗跳过长期不落地的通道
+        let watched_clients =
+            params.swqos_watchdog.as_ref().and_then(|w| w.filter_clients(&params.swqos_clients));
+        // rent goes back to whoever funded the accounts
+        let cleanup_payer =
+            (!created_accounts.is_empty()).then(|| (params.payer.clone(), params.ata_rent_payer()));
         let result = execute_parallel(
-            params.swqos_clients.as_slice(),
+            watched_clients.as_deref().unwrap_or(params.swqos_clients.as_slice()),
             params.payer,
+            params.ata_rent_funder,
             final_instructions,
             address_lookup_table_accounts,
             params.recent_blockhash,
@@ -180,6 +301,13 @@ impl TradeExecutor for GenericTradeExecutor {
             params.use_dedicated_sender_threads,
             sender_config,
             params.check_min_tip,
+            params.parallel_strategy,
+            if params.rpc_fallback { params.rpc.clone() } else { None },
+            params.rpc.clone(),
+            params.skip_compute_budget,
+            params.on_before_send.clone(),
+            params.multi_nonce.clone(),
+            trade_context,
         )
         .await;
 
@@ -198,6 +326,25 @@ impl TradeExecutor for GenericTradeExecutor {
             let confirm_result = if let Some(rpc) = params.rpc.as_ref() {
                 if signatures.is_empty() {
                     (ok, signatures, err)
+                } else if params.multi_nonce.is_some() {
+                    // Independent transactions: confirm each, keep every one that landed.
+                    let polls =
+                        signatures.iter().filter(|sig| **sig != Signature::default()).map(|sig| {
+                            poll_any_transaction_confirmation(rpc, std::slice::from_ref(sig), true)
+                        });
+                    let mut landed = Vec::new();
+                    let mut last_err = err;
+                    for poll in futures::future::join_all(polls).await {
+                        match poll {
+                            Ok(sig) => landed.push(sig),
+                            Err(e) => last_err = Some(e),
+                        }
+                    }
+                    if landed.is_empty() {
+                        (false, signatures, last_err)
+                    } else {
+                        (true, landed, None)
+                    }
                 } else {
                     let poll_res = poll_any_transaction_confirmation(rpc, &signatures, true).await;
                     let confirm_done_us = log_enabled.then(crate::common::clock::now_micros);
@@ -212,6 +359,12 @@ impl TradeExecutor for GenericTradeExecutor {
                             confirm_done_us,
                         );
                     }
+                    if let Some(watchdog) = params.swqos_watchdog.as_ref() {
+                        record_landing(watchdog, &signatures, submit_timings_ref, &poll_res);
+                    }
+                    if let (Some(learner), Ok(sig)) = (params.cu_learner.as_ref(), &poll_res) {
+                        learner.record_landed(rpc.clone(), *sig, self.protocol_name, is_buy);
+                    }
                     match poll_res {
                         Ok(_) => (true, signatures, None),
                         Err(e) => (false, signatures, Some(e)),
@@ -240,12 +393,281 @@ impl TradeExecutor for GenericTradeExecutor {
             Ok((ok, signatures, err, submit_timings))
         };
 
+        // 买入失败时在后台关闭预交易创建的空账户，回收租金，不阻塞返回
+        if let (Some((payer, rent_destination)), Some(rpc)) = (cleanup_payer, params.rpc.clone()) {
+            if !matches!(result, Ok((true, ..))) {
+                tokio::spawn(async move {
+                    match close_empty_token_accounts(
+                        &rpc,
+                        &payer,
+                        &rent_destination,
+                        &created_accounts,
+                    )
+                    .await
+                    {
+                        Ok(Some(signature)) if log_enabled => {
+                            println!(
+                                " [SDK] closed empty token accounts after failed buy: {}",
+                                signature
+                            )
+                        }
+                        Err(e) => {
+                            warn!(target: "sol_trade_sdk", "cleanup_on_failure failed: {}", e)
+                        }
+                        _ => {}
+                    }
+                });
+            }
+        }
+
         result
     }
 
     fn protocol_name(&self) -> &'static str {
         self.protocol_name
     }
+
+    async fn accounts_used(&self, params: &SwapParams) -> Result<Vec<Pubkey>> {
+        self.instruction_builder.accounts_used(params).await
+    }
+
+    async fn planned_ata_creations(&self, params: &SwapParams) -> Result<usize> {
+        self.instruction_builder.planned_ata_creations(params).await
+    }
+}
+
+/// Whether `instructions` plus the compute budget / tip / nonce envelope would overflow a packet
+/// once compiled against `alts`. Keys no table covers (new ATAs, mints, the payer) count in full.
+fn exceeds_size_budget(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    alts: &[AddressLookupTableAccount],
+) -> bool {
+    use crate::trading::common::transaction_builder::{
+        estimate_transaction_size, PACKET_DATA_SIZE, TX_ENVELOPE_RESERVE,
+    };
+    estimate_transaction_size(payer, instructions, alts)
+        .is_ok_and(|size| size + TX_ENVELOPE_RESERVE > PACKET_DATA_SIZE)
+}
+
+/// Split token-account creation out of `instructions` per `mode`. Returns `(pre, swap)`;
+/// `pre` is empty when everything stays inline. Inline mode only compiles the message for its
+/// oversize warning when `log_enabled`, keeping it off the default hot path.
+fn split_ata_creation(
+    mode: AtaCreationMode,
+    instructions: Vec<Instruction>,
+    payer: &Pubkey,
+    alts: &[AddressLookupTableAccount],
+    log_enabled: bool,
+) -> (Vec<Instruction>, Vec<Instruction>) {
+    match mode {
+        AtaCreationMode::Inline => {
+            if log_enabled
+                && instructions.iter().any(is_ata_creation)
+                && exceeds_size_budget(payer, &instructions, alts)
+            {
+                warn!(
+                    target: "sol_trade_sdk",
+                    "swap tx with inline ATA creation likely exceeds {} bytes even with {} ALT(s); use AtaCreationMode::Auto or SeparateTx",
+                    crate::trading::common::transaction_builder::PACKET_DATA_SIZE,
+                    alts.len()
+                );
+            }
+            return (Vec::new(), instructions);
+        }
+        AtaCreationMode::SeparateTx => {}
+        AtaCreationMode::Auto { max_accounts } => {
+            let mut keys: Vec<&Pubkey> = Vec::with_capacity(32);
+            for ix in &instructions {
+                for key in
+                    std::iter::once(&ix.program_id).chain(ix.accounts.iter().map(|a| &a.pubkey))
+                {
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+            }
+            if keys.len() <= max_accounts && !exceeds_size_budget(payer, &instructions, alts) {
+                return (Vec::new(), instructions);
+            }
+        }
+    }
+    let mut pre = Vec::new();
+    let mut swap = Vec::with_capacity(instructions.len());
+    let mut init_pending = false;
+    for ix in instructions {
+        let is_seed_init = init_pending
+            && (ix.program_id == crate::constants::TOKEN_PROGRAM
+                || ix.program_id == crate::constants::TOKEN_PROGRAM_2022)
+            && ix.data.first() == Some(&18); // InitializeAccount3
+        init_pending = false;
+        if is_seed_init {
+            pre.push(ix);
+        } else if is_ata_creation(&ix) {
+            init_pending = ix.program_id == crate::constants::SYSTEM_PROGRAM;
+            pre.push(ix);
+        } else {
+            swap.push(ix);
+        }
+    }
+    (pre, swap)
+}
+
+/// Send the account-creation pre-transaction over RPC and wait for confirmation. Uses a fresh
+/// blockhash so a durable nonce is left for the swap transaction.
+async fn send_ata_creation_tx(
+    params: &SwapParams,
+    instructions: Vec<Instruction>,
+    is_buy: bool,
+) -> Result<()> {
+    use solana_sdk::{signer::Signer, transaction::Transaction};
+
+    let rpc = params
+        .rpc
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("RPC client is required for separate ATA creation"))?;
+    let trade_type = if is_buy { TradeType::Buy } else { TradeType::Sell };
+    let unit_price = params
+        .gas_fee_strategy
+        .get_strategies(trade_type)
+        .iter()
+        .find(|config| config.0 == crate::swqos::SwqosType::Default)
+        .map(|config| config.2.cu_price)
+        .unwrap_or(0);
+    let unit_limit = ATA_CREATION_CU_PER_IX.saturating_mul(instructions.len() as u32);
+
+    let mut all = Vec::with_capacity(instructions.len() + 2);
+    all.extend(crate::trading::common::compute_budget_manager::compute_budget_instructions(
+        unit_price, unit_limit,
+    ));
+    all.extend(instructions);
+    let blockhash = rpc.get_latest_blockhash().await?;
+    let mut signers = vec![params.payer.as_ref()];
+    signers.extend(params.ata_rent_funder.as_deref());
+    let tx =
+        Transaction::new_signed_with_payer(&all, Some(&params.payer.pubkey()), &signers, blockhash);
+    let signature = rpc.send_and_confirm_transaction(&tx).await?;
+    if params.log_enabled && crate::common::sdk_log::sdk_log_enabled() {
+        println!(" [SDK] token accounts created in separate tx: {}", signature);
+    }
+    Ok(())
+}
+
+/// Compute units budgeted per account-creation instruction in the pre-transaction.
+const ATA_CREATION_CU_PER_IX: u32 = 40_000;
+
+/// `(account, token_program)` for each token account a pre-transaction creates: ATA creates and
+/// the `InitializeAccount3` that completes a seed account.
+fn created_token_accounts(instructions: &[Instruction]) -> Vec<(Pubkey, Pubkey)> {
+    instructions
+        .iter()
+        .filter_map(|ix| {
+            if ix.program_id == crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID {
+                Some((ix.accounts.get(1)?.pubkey, ix.accounts.get(5)?.pubkey))
+            } else if (ix.program_id == crate::constants::TOKEN_PROGRAM
+                || ix.program_id == crate::constants::TOKEN_PROGRAM_2022)
+                && ix.data.first() == Some(&18)
+            {
+                Some((ix.accounts.first()?.pubkey, ix.program_id))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Those of `accounts` that do not exist yet, i.e. the ones the pre-transaction really creates.
+/// Idempotent creates of existing accounts (the WSOL ATA pre-created at init, an empty mint ATA
+/// left from an earlier trade) are dropped so cleanup never closes them; when the snapshot
+/// cannot be taken nothing is cleaned up. 仅清理本次交易新建的账户。
+async fn not_yet_created(
+    rpc: Option<&SolanaRpcClient>,
+    accounts: Vec<(Pubkey, Pubkey)>,
+) -> Vec<(Pubkey, Pubkey)> {
+    let Some(rpc) = rpc else { return Vec::new() };
+    let keys: Vec<Pubkey> = accounts.iter().map(|(account, _)| *account).collect();
+    match rpc.get_multiple_accounts(&keys).await {
+        Ok(fetched) => accounts
+            .into_iter()
+            .zip(fetched)
+            .filter_map(|(account, fetched)| fetched.is_none().then_some(account))
+            .collect(),
+        Err(e) => {
+            warn!(target: "sol_trade_sdk", "cleanup_on_failure disabled, account snapshot failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// `close_account` instructions for those of `accounts` that exist with a zero token balance,
+/// sending the rent to `destination`.
+async fn empty_token_account_closes(
+    rpc: &SolanaRpcClient,
+    owner: &Pubkey,
+    destination: &Pubkey,
+    accounts: &[(Pubkey, Pubkey)],
+) -> Result<Vec<Instruction>> {
+    if accounts.is_empty() {
+        return Ok(Vec::new());
+    }
+    let keys: Vec<Pubkey> = accounts.iter().map(|(account, _)| *account).collect();
+    let fetched = rpc.get_multiple_accounts(&keys).await?;
+    let mut instructions = Vec::with_capacity(accounts.len());
+    for ((account, token_program), fetched) in accounts.iter().zip(fetched) {
+        let Some(fetched) = fetched else { continue };
+        // SPL token account: amount is the u64 at offset 64
+        let empty = fetched.owner == *token_program
+            && fetched.data.get(64..72) == Some(&0u64.to_le_bytes()[..]);
+        if empty {
+            instructions.push(crate::common::spl_token::close_account(
+                token_program,
+                account,
+                destination,
+                owner,
+                &[],
+            )?);
+        }
+    }
+    Ok(instructions)
+}
+
+/// Close those of `accounts` that still exist with a zero token balance, returning their rent to
+/// `rent_destination` (the `ata_rent_funder` that paid it, else the payer). `Ok(None)` when
+/// nothing needed closing.
+async fn close_empty_token_accounts(
+    rpc: &SolanaRpcClient,
+    payer: &Keypair,
+    rent_destination: &Pubkey,
+    accounts: &[(Pubkey, Pubkey)],
+) -> Result<Option<Signature>> {
+    use solana_sdk::{signer::Signer, transaction::Transaction};
+
+    let owner = payer.pubkey();
+    let instructions = empty_token_account_closes(rpc, &owner, rent_destination, accounts).await?;
+    if instructions.is_empty() {
+        return Ok(None);
+    }
+    let blockhash = rpc.get_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(&instructions, Some(&owner), &[payer], blockhash);
+    Ok(Some(rpc.send_and_confirm_transaction(&tx).await?))
+}
+
+/// Feed the watchdog: the landed signature's route wins, every other route misses.
+/// On-chain failures also land, but the failing signature is unknown, so they are not recorded.
+fn record_landing(
+    watchdog: &SwqosWatchdog,
+    signatures: &[Signature],
+    submit_timings: &[SwqosSubmitTiming],
+    poll_res: &Result<Signature>,
+) {
+    let landed = match poll_res {
+        Ok(sig) => Some(*sig),
+        Err(e) if is_landed_error(e) => return,
+        Err(_) => None,
+    };
+    for (sig, timing) in signatures.iter().zip(submit_timings) {
+        watchdog.record(timing.swqos_type, landed == Some(*sig));
+    }
 }
 
 /// Simulate mode: single RPC simulation, returns Vec<Signature> for API consistency.
@@ -253,6 +675,7 @@ impl TradeExecutor for GenericTradeExecutor {
 async fn simulate_transaction(
     rpc: Option<Arc<SolanaRpcClient>>,
     payer: Arc<Keypair>,
+    ata_rent_funder: Option<Arc<Keypair>>,
     instructions: Vec<Instruction>,
     address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
     recent_blockhash: Option<Hash>,
@@ -260,8 +683,12 @@ async fn simulate_transaction(
     middleware_manager: Option<Arc<MiddlewareManager>>,
     protocol_name: &'static str,
     is_buy: bool,
+    trade_context: Option<&TradeContext>,
     with_tip: bool,
     gas_fee_strategy: GasFeeStrategy,
+    cu_learner: Option<&CuLearner>,
+    skip_compute_budget: bool,
+    capture: Option<&SimulationCapture>,
 ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>)> {
     use crate::trading::common::build_transaction;
     use solana_client::rpc_config::RpcSimulateTransactionConfig;
@@ -286,14 +713,17 @@ async fn simulate_transaction(
 
     let transaction = build_transaction(
         &payer,
+        ata_rent_funder.as_ref(),
         unit_limit,
         unit_price,
+        skip_compute_budget,
         &instructions,
         address_lookup_table_accounts.as_slice(),
         recent_blockhash,
         middleware_manager.as_ref(),
         protocol_name,
         is_buy,
+        trade_context,
         false,
         &Pubkey::default(),
         tip,
@@ -312,12 +742,15 @@ async fn simulate_transaction(
                     commitment: CommitmentLevel::Processed, // Use Processed level to get latest state
                 }),
                 encoding: Some(UiTransactionEncoding::Base64), // Base64 encoding
-                accounts: None, // Don't return specific account states (can be specified if needed)
-                min_context_slot: None, // Don't specify minimum context slot
+                accounts: capture.map(SimulationCapture::accounts_config), // Post-states for simulate_with_accounts
+                min_context_slot: None,   // Don't specify minimum context slot
                 inner_instructions: true, // Enable inner instructions for debugging and detailed execution flow
             },
         )
         .await?;
+    if let Some(capture) = capture {
+        capture.record(&simulate_result.value);
+    }
 
     let signature = transaction
         .signatures
@@ -340,6 +773,9 @@ async fn simulate_transaction(
     }
 
     // Simulation succeeded
+    if let (Some(learner), Some(units)) = (cu_learner, simulate_result.value.units_consumed) {
+        learner.record_protocol(protocol_name, is_buy, units);
+    }
     #[cfg(feature = "perf-trace")]
     {
         info!(target: "sol_trade_sdk", "[Simulation Succeeded] signature={:?}", signature);
@@ -439,4 +875,678 @@ mod tests {
         println!(" [SDK][{:width$}] {} total: {:.4} ms", "-", dir, 36.51, width = w);
         println!();
     }
+
+    #[test]
+    fn separate_tx_moves_only_account_creation_out_of_the_swap() {
+        use super::{split_ata_creation, AtaCreationMode};
+        use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+        let payer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut instructions: Vec<Instruction> =
+            crate::trading::common::handle_wsol(&payer, 1_000).into_iter().collect();
+        instructions.extend(
+            crate::common::seed::create_associated_token_account_use_seed(
+                &payer,
+                &payer,
+                &mint,
+                &crate::constants::TOKEN_PROGRAM,
+            )
+            .unwrap(),
+        );
+        let swap_ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[9], vec![]);
+        instructions.push(swap_ix.clone());
+
+        let (pre, swap) =
+            split_ata_creation(AtaCreationMode::Inline, instructions.clone(), &payer, &[], false);
+        assert!(pre.is_empty());
+        assert_eq!(swap.len(), 6);
+
+        let (pre, swap) = split_ata_creation(
+            AtaCreationMode::SeparateTx,
+            instructions.clone(),
+            &payer,
+            &[],
+            false,
+        );
+        // WSOL ATA create + seed create/init go first; transfer + sync_native + swap stay.
+        assert_eq!(pre.len(), 3);
+        assert_eq!(swap.len(), 3);
+        assert_eq!(swap.last(), Some(&swap_ix));
+
+        let (pre, _) = split_ata_creation(
+            AtaCreationMode::Auto { max_accounts: 64 },
+            instructions.clone(),
+            &payer,
+            &[],
+            false,
+        );
+        assert!(pre.is_empty());
+        let (pre, _) = split_ata_creation(
+            AtaCreationMode::Auto { max_accounts: 3 },
+            instructions.clone(),
+            &payer,
+            &[],
+            false,
+        );
+        assert_eq!(pre.len(), 3);
+
+        // WSOL ATA + seed account; the seed init is not a second creation.
+        use crate::trading::core::traits::{check_ata_creation_cap, count_ata_creations};
+        assert_eq!(count_ata_creations(&instructions), 2);
+        assert!(check_ata_creation_cap(&instructions, Some(2)).is_ok());
+        let err = check_ata_creation_cap(&instructions, Some(1)).unwrap_err();
+        assert_eq!(err.code, crate::swqos::common::TradeError::TOO_MANY_ATA_CREATIONS);
+    }
+
+    #[test]
+    fn auto_mode_counts_keys_outside_the_alt_and_splits_when_too_large() {
+        use super::{exceeds_size_budget, split_ata_creation, AtaCreationMode};
+        use crate::trading::common::transaction_builder::estimate_transaction_size;
+        use solana_message::AddressLookupTableAccount;
+        use solana_sdk::{
+            instruction::{AccountMeta, Instruction},
+            pubkey::Pubkey,
+        };
+
+        let payer = Pubkey::new_unique();
+        // swap touching 40 pool accounts, all of them in the ALT
+        let pool_accounts: Vec<Pubkey> = (0..40).map(|_| Pubkey::new_unique()).collect();
+        let swap_ix = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: pool_accounts.iter().map(|k| AccountMeta::new(*k, false)).collect(),
+            data: vec![7; 600],
+        };
+        let alt = AddressLookupTableAccount { key: Pubkey::new_unique(), addresses: pool_accounts };
+        // six fresh ATAs: neither the ATA nor its mint is in the table
+        let mut instructions: Vec<Instruction> = (0..6)
+            .map(|_| {
+                crate::common::spl_associated_token_account::create_associated_token_account_idempotent(
+                    &payer,
+                    &payer,
+                    &Pubkey::new_unique(),
+                    &crate::constants::TOKEN_PROGRAM,
+                )
+            })
+            .collect();
+        instructions.push(swap_ix.clone());
+        let alts = [alt];
+
+        // the swap alone only fits thanks to the ALT
+        assert!(!exceeds_size_budget(&payer, std::slice::from_ref(&swap_ix), &alts));
+        assert!(
+            estimate_transaction_size(&payer, std::slice::from_ref(&swap_ix), &[]).unwrap()
+                > crate::trading::common::transaction_builder::PACKET_DATA_SIZE
+        );
+        // ... but the uncovered ATA keys push the combined tx over the packet
+        assert!(exceeds_size_budget(&payer, &instructions, &alts));
+
+        // 57 unique keys stay under the account cap, so only the size check triggers the split
+        let (pre, swap) = split_ata_creation(
+            AtaCreationMode::Auto { max_accounts: 64 },
+            instructions.clone(),
+            &payer,
+            &alts,
+            false,
+        );
+        assert_eq!(pre.len(), 6);
+        assert_eq!(swap, vec![swap_ix]);
+        assert!(!exceeds_size_budget(&payer, &swap, &alts));
+
+        // Inline keeps everything (and only warns, when logging)
+        let (pre, swap) =
+            split_ata_creation(AtaCreationMode::Inline, instructions.clone(), &payer, &alts, true);
+        assert!(pre.is_empty());
+        assert_eq!(swap, instructions);
+    }
+
+    #[test]
+    fn cleanup_targets_every_account_the_pre_transaction_creates() {
+        use super::{created_token_accounts, split_ata_creation, AtaCreationMode};
+        use solana_sdk::pubkey::Pubkey;
+
+        let payer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut instructions: Vec<_> =
+            crate::trading::common::handle_wsol(&payer, 1_000).into_iter().collect();
+        let seed_ixs = crate::common::seed::create_associated_token_account_use_seed(
+            &payer,
+            &payer,
+            &mint,
+            &crate::constants::TOKEN_PROGRAM,
+        )
+        .unwrap();
+        let seed_account = seed_ixs[0].accounts[1].pubkey;
+        instructions.extend(seed_ixs);
+
+        let (pre, swap) =
+            split_ata_creation(AtaCreationMode::SeparateTx, instructions, &payer, &[], false);
+        let wsol_ata = pre[0].accounts[1].pubkey;
+        assert_eq!(
+            created_token_accounts(&pre),
+            vec![
+                (wsol_ata, crate::constants::TOKEN_PROGRAM),
+                (seed_account, crate::constants::TOKEN_PROGRAM)
+            ]
+        );
+        // transfer + sync_native only fund the account; nothing to clean up there
+        assert!(created_token_accounts(&swap).is_empty());
+    }
+
+    #[tokio::test]
+    async fn cleanup_leaves_pre_existing_empty_accounts_alone() {
+        use super::{empty_token_account_closes, not_yet_created};
+        use crate::common::{mock_rpc::mock_account, MockSolanaRpcClient};
+        use crate::constants::TOKEN_PROGRAM;
+        use solana_sdk::pubkey::Pubkey;
+
+        let owner = Pubkey::new_unique();
+        let existing = (Pubkey::new_unique(), TOKEN_PROGRAM);
+        let fresh = (Pubkey::new_unique(), TOKEN_PROGRAM);
+        let empty = mock_account(TOKEN_PROGRAM, vec![0; 165]);
+        let rpc = MockSolanaRpcClient::new()
+            // before the pre-transaction: only `existing` is on chain
+            .with_multiple_accounts(&[Some(&empty), None])
+            // after the failed buy: `fresh` was created and is still empty
+            .with_multiple_accounts(&[Some(&empty)])
+            .build();
+
+        let targets = not_yet_created(Some(&rpc), vec![existing, fresh]).await;
+        assert_eq!(targets, vec![fresh]);
+        let closes = empty_token_account_closes(&rpc, &owner, &owner, &targets).await.unwrap();
+        assert_eq!(closes.len(), 1);
+        assert_eq!(closes[0].accounts[0].pubkey, fresh.0);
+        assert_eq!(closes[0].accounts[1].pubkey, owner);
+        assert!(not_yet_created(None, vec![fresh]).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cleanup_returns_rent_to_the_ata_rent_funder() {
+        use super::empty_token_account_closes;
+        use crate::common::{mock_rpc::mock_account, MockSolanaRpcClient};
+        use crate::constants::TOKEN_PROGRAM;
+        use crate::swqos::TradeType;
+        use crate::test_fixtures::{keypair, pumpswap_params, swap_params};
+        use crate::trading::core::params::DexParamEnum;
+        use solana_sdk::{pubkey::Pubkey, signer::Signer};
+        use std::sync::Arc;
+
+        let mut params =
+            swap_params(DexParamEnum::PumpSwap(pumpswap_params()), TradeType::Buy, 100_000);
+        let funder = Arc::new(keypair(0x70));
+        params.ata_rent_funder = Some(funder.clone());
+        let owner = params.payer.pubkey();
+        let fresh = (Pubkey::new_unique(), TOKEN_PROGRAM);
+        let rpc = MockSolanaRpcClient::new()
+            .with_multiple_accounts(&[Some(&mock_account(TOKEN_PROGRAM, vec![0; 165]))])
+            .build();
+
+        let closes = empty_token_account_closes(&rpc, &owner, &params.ata_rent_payer(), &[fresh])
+            .await
+            .unwrap();
+
+        // close_account(account, destination, authority): the sponsor gets the rent back
+        assert_eq!(closes[0].accounts[1].pubkey, funder.pubkey());
+        assert_eq!(closes[0].accounts[2].pubkey, owner);
+    }
+
+    mod mock_rpc {
+        use crate::common::MockSolanaRpcClient;
+        use crate::swqos::{SwqosClient, TradeType};
+        use crate::test_fixtures::{
+            pumpswap_buy_params, pumpswap_submit_params, DirectionProbe, RecordingSwqos, MINT,
+        };
+        use crate::trading::factory::{DexType, TradeFactory};
+        use serde_json::json;
+        use solana_hash::Hash;
+        use solana_sdk::pubkey::Pubkey;
+        use std::sync::Arc;
+
+        fn pk(seed: u8) -> Pubkey {
+            Pubkey::new_from_array([seed; 32])
+        }
+
+        #[tokio::test]
+        async fn pumpswap_buy_builds_and_simulates_against_mock_rpc() {
+            let rpc = MockSolanaRpcClient::new()
+                .with_latest_blockhash(Hash::new_unique(), 1_000)
+                .with_simulation(None, vec!["Program log: Instruction: Buy".into()], 42_000);
+            let params = pumpswap_buy_params(rpc).await;
+
+            let (ok, signatures, err, timings) =
+                TradeFactory::create_executor(DexType::PumpSwap).swap(params).await.unwrap();
+
+            assert!(ok, "{:?}", err);
+            assert_eq!(signatures.len(), 1);
+            assert!(timings.is_empty());
+        }
+
+        #[tokio::test]
+        async fn simulation_error_is_reported_without_submitting() {
+            let rpc = MockSolanaRpcClient::new()
+                .with_latest_blockhash(Hash::new_unique(), 1_000)
+                .with_simulation(
+                    Some(json!({"InstructionError": [4, {"Custom": 6004}]})),
+                    vec![],
+                    0,
+                );
+            let params = pumpswap_buy_params(rpc).await;
+
+            let (ok, signatures, err, _) =
+                TradeFactory::create_executor(DexType::PumpSwap).swap(params).await.unwrap();
+
+            assert!(!ok);
+            assert_eq!(signatures.len(), 1);
+            assert!(err.unwrap().to_string().contains("6004"));
+        }
+
+        #[tokio::test]
+        async fn simulation_capture_requests_and_records_account_post_states() {
+            use crate::trading::simulation::SimulationCapture;
+            use base64::Engine;
+            use solana_rpc_client_api::request::RpcRequest;
+
+            let mut token_data = vec![0u8; 165];
+            token_data[64..72].copy_from_slice(&7_000u64.to_le_bytes());
+            let rpc = MockSolanaRpcClient::new()
+                .with_latest_blockhash(Hash::new_unique(), 1_000)
+                .with_response(
+                    RpcRequest::SimulateTransaction,
+                    json!({
+                        "context": {"slot": 1},
+                        "value": {
+                            "err": null,
+                            "logs": ["Program log: Instruction: Buy"],
+                            "accounts": [null, {
+                                "lamports": 2_039_280u64,
+                                "owner": crate::constants::TOKEN_PROGRAM.to_string(),
+                                "data": [base64::engine::general_purpose::STANDARD.encode(&token_data), "base64"],
+                                "executable": false,
+                                "rentEpoch": 0,
+                                "space": 165,
+                            }],
+                            "unitsConsumed": 42_000,
+                            "returnData": null,
+                        },
+                    }),
+                );
+            let mut params = pumpswap_buy_params(rpc).await;
+            let capture = Arc::new(SimulationCapture::new(vec![pk(40), pk(41)]));
+            params.simulation_capture = Some(capture.clone());
+
+            let (ok, _, err, _) =
+                TradeFactory::create_executor(DexType::PumpSwap).swap(params).await.unwrap();
+
+            assert!(ok, "{:?}", err);
+            let report = capture.report(&[None, None], ok, None);
+            assert_eq!(report.units_consumed, Some(42_000));
+            assert!(report.accounts[0].post.is_none());
+            assert_eq!(report.accounts[1].token_delta(), Some(7_000));
+        }
+
+        /// SWQOS route that rejects every submit.
+        struct RejectingSwqos(Pubkey);
+
+        #[async_trait::async_trait]
+        impl crate::swqos::SwqosClientTrait for RejectingSwqos {
+            async fn send_transaction(
+                &self,
+                _trade_type: TradeType,
+                _transaction: &solana_sdk::transaction::VersionedTransaction,
+                _wait_confirmation: bool,
+            ) -> anyhow::Result<()> {
+                Err(anyhow::anyhow!("relay unavailable"))
+            }
+            async fn send_transactions(
+                &self,
+                _trade_type: TradeType,
+                _transactions: &Vec<solana_sdk::transaction::VersionedTransaction>,
+                _wait_confirmation: bool,
+            ) -> anyhow::Result<()> {
+                Err(anyhow::anyhow!("relay unavailable"))
+            }
+            fn get_tip_account(&self) -> anyhow::Result<String> {
+                Ok(self.0.to_string())
+            }
+            fn get_swqos_type(&self) -> crate::swqos::SwqosType {
+                crate::swqos::SwqosType::Jito
+            }
+        }
+
+        #[tokio::test]
+        async fn rpc_fallback_resends_once_every_swqos_route_failed() {
+            for rpc_fallback in [false, true] {
+                let route: Arc<SwqosClient> = Arc::new(RejectingSwqos(pk(9)));
+                let mut params = pumpswap_submit_params(vec![route]).await;
+                params.rpc_fallback = rpc_fallback;
+
+                let (ok, signatures, err, timings) =
+                    TradeFactory::create_executor(DexType::PumpSwap).swap(params).await.unwrap();
+
+                if rpc_fallback {
+                    assert!(ok, "{:?}", err);
+                    assert_eq!(signatures.len(), 1, "fallback reuses the built transaction");
+                    assert!(timings.last().unwrap().rpc_fallback);
+                } else {
+                    assert!(!ok);
+                    assert!(err.unwrap().to_string().contains("relay unavailable"));
+                    assert!(timings.iter().all(|t| !t.rpc_fallback));
+                }
+            }
+        }
+
+        #[tokio::test]
+        async fn receive_native_sol_ends_the_sell_with_the_wsol_close() {
+            use crate::trading::core::executor::GenericTradeExecutor;
+            use crate::trading::core::traits::TradeExecutor;
+
+            let route = RecordingSwqos::new(crate::swqos::SwqosType::Jito);
+            let mut params = pumpswap_submit_params(vec![route.clone() as Arc<SwqosClient>]).await;
+            params.trade_type = TradeType::Sell;
+            params.input_mint = MINT;
+            params.output_mint = crate::constants::WSOL_TOKEN_ACCOUNT;
+            params.receive_native_sol = true;
+
+            let (ok, _, err, _) = GenericTradeExecutor::new(DirectionProbe::new(), "probe")
+                .swap(params)
+                .await
+                .unwrap();
+
+            assert!(ok, "{:?}", err);
+            let sent = route.sent();
+            let message = &sent[0].message;
+            let last = message.instructions().last().unwrap();
+            let keys = message.static_account_keys();
+            assert_eq!(keys[last.program_id_index as usize], crate::constants::TOKEN_PROGRAM);
+            assert_eq!(last.data, vec![9]);
+        }
+
+        #[tokio::test]
+        async fn on_before_send_sees_the_signed_transaction_and_can_veto_it() {
+            use crate::swqos::common::TradeError;
+            use crate::trading::core::async_executor::is_landed_error;
+            use crate::trading::core::executor::GenericTradeExecutor;
+            use crate::trading::core::traits::TradeExecutor;
+
+            for veto in [false, true] {
+                let route = RecordingSwqos::new(crate::swqos::SwqosType::Jito);
+                let mut params =
+                    pumpswap_submit_params(vec![route.clone() as Arc<SwqosClient>]).await;
+                let seen = Arc::new(parking_lot::Mutex::new(0usize));
+                let seen_in_hook = seen.clone();
+                params.on_before_send =
+                    Some(Arc::new(move |tx: &solana_sdk::transaction::VersionedTransaction| {
+                        // compute budget + tip + business instruction, already signed
+                        *seen_in_hook.lock() = tx.message.instructions().len();
+                        assert_ne!(tx.signatures[0], Default::default());
+                        if veto {
+                            Err(TradeError::new(1, "too many accounts"))
+                        } else {
+                            Ok(())
+                        }
+                    }));
+
+                let (ok, _, err, _) = GenericTradeExecutor::new(DirectionProbe::new(), "probe")
+                    .swap(params)
+                    .await
+                    .unwrap();
+
+                assert!(*seen.lock() >= 3);
+                if veto {
+                    assert!(!ok);
+                    assert!(route.sent().is_empty());
+                    let err = err.unwrap();
+                    assert!(err.to_string().contains("too many accounts"), "{err}");
+                    assert!(!is_landed_error(&err));
+                } else {
+                    assert!(ok, "{:?}", err);
+                    assert_eq!(route.sent().len(), 1);
+                }
+            }
+        }
+
+        #[tokio::test]
+        async fn staggered_fans_out_when_the_accepted_first_wave_does_not_land() {
+            use crate::common::ParallelStrategy;
+            use crate::swqos::SwqosType;
+            use crate::trading::core::executor::GenericTradeExecutor;
+            use crate::trading::core::traits::TradeExecutor;
+
+            for first_lands in [false, true] {
+                let routes = [
+                    RecordingSwqos::new(SwqosType::Jito),
+                    RecordingSwqos::new(SwqosType::NextBlock),
+                ];
+                let mut params = pumpswap_submit_params(
+                    routes.iter().map(|r| r.clone() as Arc<SwqosClient>).collect(),
+                )
+                .await;
+                // every poll within the delay sees the same status
+                let rpc = (0..16).fold(MockSolanaRpcClient::new(), |rpc, _| {
+                    rpc.with_signature_statuses(&[first_lands])
+                });
+                params.rpc = Some(Arc::new(rpc.build()));
+                params.parallel_strategy =
+                    ParallelStrategy::Staggered { delay: std::time::Duration::from_millis(50) };
+                params.wait_for_all_submits = true;
+
+                let (ok, _, err, _) = GenericTradeExecutor::new(DirectionProbe::new(), "probe")
+                    .swap(params)
+                    .await
+                    .unwrap();
+
+                assert!(ok, "{:?}", err);
+                assert_eq!(routes[0].sent().len(), 1);
+                assert_eq!(routes[1].sent().len(), usize::from(!first_lands));
+            }
+        }
+
+        #[tokio::test]
+        async fn multi_nonce_gives_each_route_its_own_nonce() {
+            use crate::common::nonce_cache::{DurableNonceInfo, MultiNonceConfig};
+            use crate::swqos::SwqosType;
+            use crate::trading::core::executor::GenericTradeExecutor;
+            use crate::trading::core::traits::TradeExecutor;
+
+            let routes =
+                [RecordingSwqos::new(SwqosType::Jito), RecordingSwqos::new(SwqosType::NextBlock)];
+            let mut params = pumpswap_submit_params(
+                routes.iter().map(|r| r.clone() as Arc<SwqosClient>).collect(),
+            )
+            .await;
+            let nonce = || DurableNonceInfo {
+                nonce_account: Some(Pubkey::new_unique()),
+                current_nonce: Some(Hash::new_unique()),
+            };
+            let pool = Arc::new(MultiNonceConfig::new(vec![nonce(), nonce()]));
+            params.recent_blockhash = None;
+            params.multi_nonce = Some(pool.clone());
+
+            let (ok, signatures, err, _) =
+                GenericTradeExecutor::new(DirectionProbe::new(), "probe")
+                    .swap(params)
+                    .await
+                    .unwrap();
+
+            assert!(ok, "{:?}", err);
+            assert_eq!(signatures.len(), 2);
+            let mut used: Vec<Hash> =
+                routes.iter().map(|r| *r.sent()[0].message.recent_blockhash()).collect();
+            used.sort();
+            let mut expected: Vec<Hash> =
+                pool.nonces().iter().map(|n| n.current_nonce.unwrap()).collect();
+            expected.sort();
+            assert_eq!(used, expected);
+            let mut consumed = pool.consumed();
+            consumed.sort();
+            let mut accounts: Vec<Pubkey> =
+                pool.nonces().iter().map(|n| n.nonce_account.unwrap()).collect();
+            accounts.sort();
+            assert_eq!(consumed, accounts);
+        }
+
+        #[tokio::test]
+        async fn multi_nonce_shares_one_nonce_across_the_fee_lanes_of_a_route() {
+            use crate::common::nonce_cache::{DurableNonceInfo, MultiNonceConfig};
+            use crate::swqos::SwqosType;
+            use crate::trading::core::executor::GenericTradeExecutor;
+            use crate::trading::core::traits::TradeExecutor;
+
+            let routes =
+                [RecordingSwqos::new(SwqosType::Jito), RecordingSwqos::new(SwqosType::NextBlock)];
+            let mut params = pumpswap_submit_params(
+                routes.iter().map(|r| r.clone() as Arc<SwqosClient>).collect(),
+            )
+            .await;
+            params.gas_fee_strategy.set_high_low_fee_strategies(
+                &[SwqosType::Jito, SwqosType::NextBlock],
+                TradeType::Buy,
+                150_000,
+                1_000,
+                2_000,
+                0.001,
+                0.002,
+            );
+            let nonce = || DurableNonceInfo {
+                nonce_account: Some(Pubkey::new_unique()),
+                current_nonce: Some(Hash::new_unique()),
+            };
+            // two routes with two fee lanes each still need only two nonces
+            let pool = Arc::new(MultiNonceConfig::new(vec![nonce(), nonce()]));
+            params.recent_blockhash = None;
+            params.multi_nonce = Some(pool.clone());
+            params.wait_for_all_submits = true;
+
+            let (ok, _, err, _) = GenericTradeExecutor::new(DirectionProbe::new(), "probe")
+                .swap(params)
+                .await
+                .unwrap();
+
+            assert!(ok, "{:?}", err);
+            let per_route: Vec<Vec<Hash>> = routes
+                .iter()
+                .map(|r| r.sent().iter().map(|tx| *tx.message.recent_blockhash()).collect())
+                .collect();
+            for used in &per_route {
+                assert_eq!(used.len(), 2, "both fee lanes are sent");
+                assert_eq!(used[0], used[1], "fee lanes of one route share its nonce");
+            }
+            let mut used = vec![per_route[0][0], per_route[1][0]];
+            used.sort();
+            let mut expected: Vec<Hash> =
+                pool.nonces().iter().map(|n| n.current_nonce.unwrap()).collect();
+            expected.sort();
+            assert_eq!(used, expected);
+        }
+
+        #[tokio::test]
+        async fn middleware_receives_the_trade_context_at_both_stages() {
+            use crate::trading::core::executor::GenericTradeExecutor;
+            use crate::trading::core::traits::TradeExecutor;
+            use crate::trading::{InstructionMiddleware, MiddlewareManager, TradeContext};
+            use solana_sdk::instruction::Instruction;
+
+            type Seen = Arc<parking_lot::Mutex<Vec<(&'static str, TradeContext)>>>;
+            #[derive(Clone)]
+            struct ContextAware(Seen);
+            impl InstructionMiddleware for ContextAware {
+                fn name(&self) -> &'static str {
+                    "context_aware"
+                }
+                fn process_protocol_instructions(
+                    &self,
+                    _: Vec<Instruction>,
+                    _: &str,
+                    _: bool,
+                ) -> anyhow::Result<Vec<Instruction>> {
+                    unreachable!("the context overload is called")
+                }
+                fn process_full_instructions(
+                    &self,
+                    _: Vec<Instruction>,
+                    _: &str,
+                    _: bool,
+                ) -> anyhow::Result<Vec<Instruction>> {
+                    unreachable!("the context overload is called")
+                }
+                fn process_protocol_instructions_with_context(
+                    &self,
+                    ixs: Vec<Instruction>,
+                    context: &TradeContext,
+                ) -> anyhow::Result<Vec<Instruction>> {
+                    self.0.lock().push(("protocol", context.clone()));
+                    Ok(ixs)
+                }
+                fn process_full_instructions_with_context(
+                    &self,
+                    ixs: Vec<Instruction>,
+                    context: &TradeContext,
+                ) -> anyhow::Result<Vec<Instruction>> {
+                    self.0.lock().push(("full", context.clone()));
+                    Ok(ixs)
+                }
+                fn clone_box(&self) -> Box<dyn InstructionMiddleware> {
+                    Box::new(self.clone())
+                }
+            }
+            /// Implements only the context-free methods.
+            #[derive(Clone)]
+            struct Legacy(Arc<parking_lot::Mutex<usize>>);
+            impl InstructionMiddleware for Legacy {
+                fn name(&self) -> &'static str {
+                    "legacy"
+                }
+                fn process_protocol_instructions(
+                    &self,
+                    ixs: Vec<Instruction>,
+                    _: &str,
+                    _: bool,
+                ) -> anyhow::Result<Vec<Instruction>> {
+                    *self.0.lock() += 1;
+                    Ok(ixs)
+                }
+                fn process_full_instructions(
+                    &self,
+                    ixs: Vec<Instruction>,
+                    _: &str,
+                    _: bool,
+                ) -> anyhow::Result<Vec<Instruction>> {
+                    *self.0.lock() += 1;
+                    Ok(ixs)
+                }
+                fn clone_box(&self) -> Box<dyn InstructionMiddleware> {
+                    Box::new(self.clone())
+                }
+            }
+
+            let route = RecordingSwqos::new(crate::swqos::SwqosType::Jito);
+            let mut params = pumpswap_submit_params(vec![route as Arc<SwqosClient>]).await;
+            let seen: Seen = Arc::default();
+            let legacy_calls = Arc::new(parking_lot::Mutex::new(0));
+            params.middleware_manager = Some(Arc::new(
+                MiddlewareManager::new()
+                    .add_middleware(Box::new(ContextAware(seen.clone())))
+                    .add_middleware(Box::new(Legacy(legacy_calls.clone()))),
+            ));
+
+            let (ok, _, err, _) = GenericTradeExecutor::new(DirectionProbe::new(), "probe")
+                .swap(params)
+                .await
+                .unwrap();
+
+            assert!(ok, "{:?}", err);
+            let seen = seen.lock();
+            assert_eq!(
+                seen.iter().map(|(stage, _)| *stage).collect::<Vec<_>>(),
+                ["protocol", "full"]
+            );
+            let context = &seen[0].1;
+            assert_eq!(context.dex_type, DexType::PumpSwap);
+            assert!(context.is_buy);
+            assert_eq!(context.mint, MINT);
+            assert_eq!(context.input_amount, Some(100_000));
+            assert_eq!(seen[1].1, *context);
+            assert_eq!(*legacy_calls.lock(), 2);
+        }
+    }
 }
@@ -2,5 +2,8 @@ pub mod async_executor;
 pub mod execution;
 pub mod executor;
 pub mod params;
+pub mod prepared;
+pub mod reserve_check;
+pub mod seed_ata_check;
 pub mod traits;
 pub mod transaction_pool;
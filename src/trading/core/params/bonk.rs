@@ -1,4 +1,5 @@
 use crate::common::SolanaRpcClient;
+use crate::swqos::common::TradeError;
 use solana_sdk::pubkey::Pubkey;
 
 /// Bonk protocol specific parameters
@@ -139,6 +140,19 @@ impl BonkParams {
         rpc: &SolanaRpcClient,
         mint: &Pubkey,
         usd1_pool: bool,
+    ) -> Result<Self, anyhow::Error> {
+        Self::from_mint_by_rpc_at_slot(rpc, mint, usd1_pool, None).await
+    }
+
+    /// [`Self::from_mint_by_rpc`] with the pool state and mint read in one `getMultipleAccounts`
+    /// as of at least `min_context_slot` (e.g. the slot of a copied trade); a node still behind
+    /// after [`min_context_slot_timeout`](super::min_context_slot_timeout) fails with
+    /// [`PoolLoadError::MinContextSlotNotReached`](super::PoolLoadError).
+    pub async fn from_mint_by_rpc_at_slot(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+        usd1_pool: bool,
+        min_context_slot: Option<u64>,
     ) -> Result<Self, anyhow::Error> {
         let pool_address = crate::instruction::utils::bonk::get_pool_pda(
             mint,
@@ -149,9 +163,31 @@ impl BonkParams {
             },
         )
         .unwrap();
-        let pool_data =
-            crate::instruction::utils::bonk::fetch_pool_state(rpc, &pool_address).await?;
-        let token_account = rpc.get_account(&pool_data.base_mint).await?;
+        let (pool_data, mint_token_program) = match min_context_slot {
+            None => {
+                let pool_data =
+                    crate::instruction::utils::bonk::fetch_pool_state(rpc, &pool_address).await?;
+                let token_account = rpc.get_account(&pool_data.base_mint).await?;
+                (pool_data, token_account.owner)
+            }
+            Some(_) => {
+                // the pool's base mint is `mint` (the pool PDA is derived from it)
+                let accounts =
+                    super::fetch_accounts_at_slot(rpc, &[pool_address, *mint], min_context_slot)
+                        .await?;
+                let pool_account = accounts
+                    .first()
+                    .and_then(Option::as_ref)
+                    .ok_or(super::PoolLoadError::NotFound(pool_address))?;
+                let pool_data =
+                    crate::instruction::utils::bonk::decode_pool_state_account(pool_account)?;
+                let mint_account = accounts
+                    .get(1)
+                    .and_then(Option::as_ref)
+                    .ok_or_else(|| anyhow::anyhow!("mint account not found: {}", mint))?;
+                (pool_data, mint_account.owner)
+            }
+        };
         let platform_associated_account =
             crate::instruction::utils::bonk::get_platform_associated_account(
                 &pool_data.platform_config,
@@ -168,11 +204,26 @@ impl BonkParams {
             pool_state: pool_address,
             base_vault: pool_data.base_vault,
             quote_vault: pool_data.quote_vault,
-            mint_token_program: token_account.owner,
+            mint_token_program,
             platform_config: pool_data.platform_config,
             platform_associated_account,
             creator_associated_account,
             global_config: pool_data.global_config,
         })
     }
+
+    /// Cheap sanity checks run before building instructions. A default `pool_state` is derived
+    /// by the builder, and fully zeroed reserves are allowed (see [`Self::immediate_sell`]).
+    pub fn validate(&self) -> Result<(), TradeError> {
+        if self.platform_config == Pubkey::default() {
+            return Err(TradeError::invalid_protocol_params("Bonk platform_config is not set"));
+        }
+        if (self.virtual_base == 0) != (self.virtual_quote == 0) {
+            return Err(TradeError::invalid_protocol_params(format!(
+                "Bonk pool reserves are inconsistent: virtual_base={} virtual_quote={}",
+                self.virtual_base, self.virtual_quote
+            )));
+        }
+        Ok(())
+    }
 }
@@ -1,7 +1,17 @@
-use crate::common::nonce_cache::DurableNonceInfo;
-use crate::common::{GasFeeStrategy, SolanaRpcClient};
+use crate::common::nonce_cache::{DurableNonceInfo, MultiNonceConfig};
+use crate::common::{GasFeeStrategy, ParallelStrategy, SolanaRpcClient};
+use crate::swqos::common::TradeError;
+use crate::swqos::watchdog::SwqosWatchdog;
 use crate::swqos::{SwqosClient, TradeType};
+use crate::trading::core::async_executor::BeforeSendHook;
+use crate::trading::cu_learner::CuLearner;
+use crate::trading::factory::DexType;
+use crate::trading::simulation::SimulationCapture;
 use crate::trading::MiddlewareManager;
+use crate::utils::calc::{
+    bonk as bonk_calc, pumpfun as pumpfun_calc, pumpswap as pumpswap_calc,
+    raydium_amm_v4 as raydium_amm_v4_calc, raydium_cpmm as raydium_cpmm_calc,
+};
 use core_affinity::CoreId;
 use solana_hash::Hash;
 use solana_message::AddressLookupTableAccount;
@@ -23,6 +33,23 @@ pub struct SenderConcurrencyConfig {
     pub max_sender_concurrency: usize,
 }
 
+/// Where token-account creation instructions go. ATA 创建指令放置方式。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AtaCreationMode {
+    /// Create accounts inside the swap transaction (default, single tx). With `log_enabled`,
+    /// logs a warning when the result likely overflows the packet size.
+    #[default]
+    Inline,
+    /// Send account creation as its own transaction, wait for confirmation, then send a lean
+    /// swap transaction. Costs one extra round trip; ignored when simulating.
+    SeparateTx,
+    /// Inline unless the swap transaction would reference more than `max_accounts` unique
+    /// accounts or would not fit the 1232-byte packet, then behave like `SeparateTx`. The size
+    /// check understands ALTs: keys a table covers cost one byte, while new ATAs, their mints and
+    /// the payer still count in full.
+    Auto { max_accounts: usize },
+}
+
 /// DEX 参数枚举 - 零开销抽象替代 Box<dyn ProtocolParams>
 #[derive(Clone)]
 pub enum DexParamEnum {
@@ -47,6 +74,156 @@ impl DexParamEnum {
             DexParamEnum::MeteoraDammV2(p) => p,
         }
     }
+
+    /// Protocol these params belong to.
+    pub fn dex_type(&self) -> DexType {
+        match self {
+            DexParamEnum::PumpFun(_) => DexType::PumpFun,
+            DexParamEnum::PumpSwap(_) => DexType::PumpSwap,
+            DexParamEnum::Bonk(_) => DexType::Bonk,
+            DexParamEnum::RaydiumCpmm(_) => DexType::RaydiumCpmm,
+            DexParamEnum::RaydiumAmmV4(_) => DexType::RaydiumAmmV4,
+            DexParamEnum::MeteoraDammV2(_) => DexType::MeteoraDammV2,
+        }
+    }
+
+    /// Pool (or bonding curve) account the params trade against; `None` when not set.
+    pub fn pool_address(&self) -> Option<Pubkey> {
+        let pool = match self {
+            DexParamEnum::PumpFun(p) => p.bonding_curve.account,
+            DexParamEnum::PumpSwap(p) => p.pool,
+            DexParamEnum::Bonk(p) => p.pool_state,
+            DexParamEnum::RaydiumCpmm(p) => p.pool_state,
+            DexParamEnum::RaydiumAmmV4(p) => p.amm,
+            DexParamEnum::MeteoraDammV2(p) => p.pool,
+        };
+        (pool != Pubkey::default()).then_some(pool)
+    }
+
+    /// Pool reserve on the `quote_mint` side (what a buy pays with), in quote base units.
+    /// PumpFun/Bonk use real (not virtual) reserves. `None` when params carry no reserves
+    /// (Meteora DAMM v2). 买入所付币种一侧的池子储备量。
+    pub fn quote_liquidity(&self, quote_mint: &Pubkey) -> Option<u64> {
+        match self {
+            DexParamEnum::PumpFun(p) => Some(p.bonding_curve.real_sol_reserves),
+            DexParamEnum::PumpSwap(p) => Some(if p.base_mint == *quote_mint {
+                p.pool_base_token_reserves
+            } else {
+                p.pool_quote_token_reserves
+            }),
+            DexParamEnum::Bonk(p) => u64::try_from(p.real_quote).ok(),
+            DexParamEnum::RaydiumCpmm(p) => {
+                Some(if p.base_mint == *quote_mint { p.base_reserve } else { p.quote_reserve })
+            }
+            DexParamEnum::RaydiumAmmV4(p) => {
+                Some(if p.coin_mint == *quote_mint { p.coin_reserve } else { p.pc_reserve })
+            }
+            DexParamEnum::MeteoraDammV2(_) => None,
+        }
+    }
+
+    /// Expected output of buying with `amount_in` of `quote_mint` at the current reserves, before
+    /// slippage (the same curve math the instruction builders use). `None` when params carry no
+    /// reserves (Meteora DAMM v2) or the curve rejects the input. 按当前储备估算买入所得（未计滑点）。
+    pub fn expected_buy_output(&self, quote_mint: &Pubkey, amount_in: u64) -> Option<u64> {
+        match self {
+            DexParamEnum::PumpFun(p) => Some(pumpfun_calc::get_buy_token_amount_from_sol_amount(
+                p.bonding_curve.virtual_token_reserves as u128,
+                p.bonding_curve.virtual_sol_reserves as u128,
+                p.bonding_curve.real_token_reserves as u128,
+                p.bonding_curve.creator,
+                amount_in,
+            )),
+            DexParamEnum::PumpSwap(p) if p.base_mint == *quote_mint => {
+                pumpswap_calc::sell_base_input_internal_with_fees(
+                    amount_in,
+                    0,
+                    p.pool_base_token_reserves,
+                    p.pool_quote_token_reserves,
+                    p.virtual_quote_reserves,
+                    &p.fee_basis_points,
+                )
+                .ok()
+                .map(|r| r.ui_quote)
+            }
+            DexParamEnum::PumpSwap(p) => pumpswap_calc::buy_quote_input_internal_with_fees(
+                amount_in,
+                0,
+                p.pool_base_token_reserves,
+                p.pool_quote_token_reserves,
+                p.virtual_quote_reserves,
+                &p.fee_basis_points,
+            )
+            .ok()
+            .map(|r| r.base),
+            DexParamEnum::Bonk(p) => Some(bonk_calc::get_buy_token_amount_from_sol_amount(
+                amount_in,
+                p.virtual_base,
+                p.virtual_quote,
+                p.real_base,
+                p.real_quote,
+                0,
+            )),
+            DexParamEnum::RaydiumCpmm(p) => Some(
+                raydium_cpmm_calc::compute_swap_amount(
+                    p.base_reserve,
+                    p.quote_reserve,
+                    p.base_mint == *quote_mint,
+                    amount_in,
+                    0,
+                )
+                .amount_out,
+            ),
+            DexParamEnum::RaydiumAmmV4(p) => Some(
+                raydium_amm_v4_calc::compute_swap_amount(
+                    p.coin_reserve,
+                    p.pc_reserve,
+                    p.coin_mint == *quote_mint,
+                    amount_in,
+                    0,
+                )
+                .amount_out,
+            ),
+            DexParamEnum::MeteoraDammV2(_) => None,
+        }
+    }
+
+    /// Expected quote-side output of selling `amount_in` of `mint` at the current reserves, before
+    /// slippage. Pool protocols reuse [`Self::expected_buy_output`] paying with `mint`; PumpFun and
+    /// Bonk use their sell curves. 按当前储备估算卖出所得（未计滑点）。
+    pub fn expected_sell_output(&self, mint: &Pubkey, amount_in: u64) -> Option<u64> {
+        match self {
+            DexParamEnum::PumpFun(p) => Some(pumpfun_calc::get_sell_sol_amount_from_token_amount(
+                p.bonding_curve.virtual_token_reserves as u128,
+                p.bonding_curve.virtual_sol_reserves as u128,
+                p.bonding_curve.creator,
+                amount_in,
+            )),
+            DexParamEnum::Bonk(p) => Some(bonk_calc::get_sell_sol_amount_from_token_amount(
+                amount_in,
+                p.virtual_base,
+                p.virtual_quote,
+                p.real_base,
+                p.real_quote,
+                0,
+            )),
+            _ => self.expected_buy_output(mint, amount_in),
+        }
+    }
+
+    /// Per-protocol sanity checks (pool set, reserves, mints), run by the executor before building instructions.
+    /// 按协议校验参数，执行器在构建指令前调用。
+    #[inline]
+    pub fn validate(&self) -> Result<(), TradeError> {
+        match self {
+            DexParamEnum::PumpFun(p) => p.validate(),
+            DexParamEnum::PumpSwap(p) => p.validate(),
+            DexParamEnum::Bonk(p) => p.validate(),
+            DexParamEnum::RaydiumCpmm(p) => p.validate(),
+            DexParamEnum::RaydiumAmmV4(p) => p.validate(),
+            DexParamEnum::MeteoraDammV2(p) => p.validate(),
+        }
+    }
 }
 
 /// Swap parameters
@@ -56,8 +233,12 @@ pub struct SwapParams {
     pub payer: Arc<Keypair>,
     pub trade_type: TradeType,
     pub input_mint: Pubkey,
+    /// Token program of `input_mint`; when set it overrides the program carried in
+    /// `protocol_params` (see [`Self::apply_token_program_overrides`]). `None` keeps the params'
+    /// program, which the RPC loaders take from the mint account's on-chain owner.
     pub input_token_program: Option<Pubkey>,
     pub output_mint: Pubkey,
+    /// Token program of `output_mint`; same rules as `input_token_program`.
     pub output_token_program: Option<Pubkey>,
     pub input_amount: Option<u64>,
     pub slippage_basis_points: Option<u64>,
@@ -69,14 +250,29 @@ pub struct SwapParams {
     /// Arc<Vec<..>> so cloning from infrastructure is a single Arc clone.
     pub swqos_clients: Arc<Vec<Arc<SwqosClient>>>,
     pub middleware_manager: Option<Arc<MiddlewareManager>>,
+    /// Inline (default) or separate-transaction ATA creation.
+    pub ata_creation: AtaCreationMode,
+    /// Cap on token-account creations (ATA or seed accounts, WSOL included). `None` = no cap.
+    pub max_ata_creations: Option<usize>,
+    /// Optional landing watchdog: skips degraded SWQOS routes and records confirmed landings.
+    pub swqos_watchdog: Option<Arc<SwqosWatchdog>>,
+    /// Optional compute-unit learner: records units consumed and may fill unset `cu_limit`s.
+    pub cu_learner: Option<Arc<CuLearner>>,
+    /// Memo tag appended after the swap instructions (see `trading::common::memo`).
+    pub memo: Option<String>,
     pub durable_nonce: Option<DurableNonceInfo>,
     pub with_tip: bool,
     pub create_input_mint_ata: bool,
+    /// Buy: close the WSOL input ATA (no-op for other quotes). Sell: close the sold token's ATA.
     pub close_input_mint_ata: bool,
     pub create_output_mint_ata: bool,
+    /// Sell: close the WSOL output ATA (no-op for other quotes). Unused on buys.
     pub close_output_mint_ata: bool,
-    /// Fixed output amount. For protocols with exact-out instructions this selects exact-out
-    /// semantics and treats `input_amount` as the maximum input budget.
+    /// Fixed output amount. Buy: exact tokens out; sell: exact (or, for PumpFun/PumpSwap
+    /// exact-in, minimum) quote out. The opposing max input is quoted from the params' reserves
+    /// plus slippage and must fit in `input_amount`; builders error rather than sign a lower
+    /// limit. Meteora DAMM v2 params carry no reserves and reject it. Never `Some(0)` (see
+    /// [`Self::validate`]).
     pub fixed_output_amount: Option<u64>,
     pub gas_fee_strategy: GasFeeStrategy,
     pub simulate: bool,
@@ -102,6 +298,35 @@ pub struct SwapParams {
     pub effective_core_ids: Arc<Vec<CoreId>>,
     /// Whether to check minimum tip per SWQOS (from TradeConfig.check_min_tip). When false, skip filter for lower latency.
     pub check_min_tip: bool,
+    /// Race all routes (default), stagger, or try them one by one (from `TradingClient::with_parallel_strategy`).
+    pub parallel_strategy: ParallelStrategy,
+    /// Resend the built transaction via `rpc` once every SWQOS route failed (from `TradingClient::with_rpc_fallback`).
+    pub rpc_fallback: bool,
+    /// Buys only: close still-empty accounts created by the separate ATA pre-transaction when the
+    /// swap fails (from `TradeBuyParams::cleanup_on_failure`).
+    pub cleanup_on_failure: bool,
+    /// Sells only: make a close of the payer's WSOL account the last instruction (from
+    /// `TradeSellParams::receive_native_sol`).
+    pub receive_native_sol: bool,
+    /// Veto hook run on each signed transaction before submit (from
+    /// `TradingClient::with_on_before_send`).
+    pub on_before_send: Option<BeforeSendHook>,
+    /// One durable nonce per SWQOS route instead of `durable_nonce` (from
+    /// `Trade*Params::multi_nonce`).
+    pub multi_nonce: Option<Arc<MultiNonceConfig>>,
+    /// Buys only: fail when the quoted tokens out fall below this, and never encode a lower
+    /// on-chain minimum out (from `TradeBuyParams::min_tokens_out`). See
+    /// [`Self::check_min_tokens_out`] and [`Self::min_out_with_floor`].
+    pub min_tokens_out: Option<u64>,
+    /// Omit the SDK's compute-budget instructions; the caller supplies them (from
+    /// `Trade*Params::skip_compute_budget`).
+    pub skip_compute_budget: bool,
+    /// Simulation only: request these accounts' post-states and keep what the RPC returned
+    /// (from `TradingClient::simulate_with_accounts`).
+    pub simulation_capture: Option<Arc<SimulationCapture>>,
+    /// Pays the rent of token accounts the trade creates and co-signs the transaction; `None`
+    /// charges the payer (from `Trade*Params::ata_rent_funder`). 代付 ATA 租金的账户。
+    pub ata_rent_funder: Option<Arc<Keypair>>,
     /// Optional event receive time in microseconds (same scale as sol-parser-sdk clock::now_micros). Used as timing start when log_enabled.
     pub grpc_recv_us: Option<i64>,
     /// Use exact quote-input buy instructions (legacy PumpFun uses SOL quote; V2/PumpSwap use generic quote).
@@ -121,6 +346,92 @@ impl SwapParams {
             max_sender_concurrency: self.max_sender_concurrency,
         }
     }
+
+    /// Funding account of created token accounts: `ata_rent_funder` if set, else the payer.
+    #[inline]
+    pub fn ata_rent_payer(&self) -> Pubkey {
+        use solana_sdk::signer::Signer;
+        self.ata_rent_funder.as_ref().unwrap_or(&self.payer).pubkey()
+    }
+
+    /// Whether the buy builder runs for `trade_type`.
+    #[inline]
+    pub fn is_buy(&self) -> bool {
+        matches!(self.trade_type, TradeType::Buy | TradeType::CreateAndBuy)
+    }
+
+    /// Write explicit `input_token_program` / `output_token_program` into the protocol params
+    /// so builders derive ATAs and account metas with them, e.g. for a Token-2022 mint whose
+    /// params were built with the legacy program. PumpFun/Bonk carry one program for the traded
+    /// token; pool protocols are matched by mint. RaydiumAmmV4 is SPL Token only and is left as is.
+    /// 显式指定的 token program 优先于协议参数中的推导值。
+    pub fn apply_token_program_overrides(&mut self) {
+        let is_buy = self.is_buy();
+        for (mint, program, is_traded_token) in [
+            (self.input_mint, self.input_token_program, !is_buy),
+            (self.output_mint, self.output_token_program, is_buy),
+        ] {
+            let Some(program) = program else { continue };
+            match &mut self.protocol_params {
+                DexParamEnum::PumpFun(p) if is_traded_token => p.token_program = program,
+                DexParamEnum::Bonk(p) if is_traded_token => p.mint_token_program = program,
+                DexParamEnum::PumpSwap(p) if p.base_mint == mint => p.base_token_program = program,
+                DexParamEnum::PumpSwap(p) if p.quote_mint == mint => {
+                    p.quote_token_program = program
+                }
+                DexParamEnum::RaydiumCpmm(p) if p.base_mint == mint => {
+                    p.base_token_program = program
+                }
+                DexParamEnum::RaydiumCpmm(p) if p.quote_mint == mint => {
+                    p.quote_token_program = program
+                }
+                DexParamEnum::MeteoraDammV2(p) if p.token_a_mint == mint => {
+                    p.token_a_program = program
+                }
+                DexParamEnum::MeteoraDammV2(p) if p.token_b_mint == mint => {
+                    p.token_b_program = program
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Protocol checks plus amount checks shared by every builder; a zero
+    /// `fixed_output_amount` is always a mistake. 执行器与预签名路径在构建前调用。
+    #[inline]
+    pub fn validate(&self) -> Result<(), TradeError> {
+        if self.fixed_output_amount == Some(0) {
+            return Err(TradeError::invalid_protocol_params("fixed_output_amount must be > 0"));
+        }
+        self.protocol_params.validate()
+    }
+
+    /// `input_amount` for the instruction builders, distinguishing unset
+    /// ([`TradeError::AMOUNT_NOT_SET`]) from explicitly zero ([`TradeError::ZERO_AMOUNT`]).
+    #[inline]
+    pub fn required_input_amount(&self) -> Result<u64, TradeError> {
+        match self.input_amount {
+            None => Err(TradeError::amount_not_set()),
+            Some(0) => Err(TradeError::zero_amount()),
+            Some(amount) => Ok(amount),
+        }
+    }
+
+    /// Builders call this with the tokens out they computed from reserves, before slippage.
+    #[inline]
+    pub fn check_min_tokens_out(&self, tokens_out: u64) -> Result<(), TradeError> {
+        match self.min_tokens_out {
+            Some(min) if tokens_out < min => Err(TradeError::below_min_tokens_out(tokens_out, min)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Builders encode this as the instruction's minimum out: the slippage-adjusted `min_out`,
+    /// raised to `min_tokens_out` so the program itself rejects fills below the floor.
+    #[inline]
+    pub fn min_out_with_floor(&self, min_out: u64) -> u64 {
+        self.min_tokens_out.map_or(min_out, |floor| min_out.max(floor))
+    }
 }
 
 impl std::fmt::Debug for SwapParams {
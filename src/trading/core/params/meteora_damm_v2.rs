@@ -1,4 +1,10 @@
+use super::{
+    fetch_accounts_at_slot, fetch_pool_account, pool_account_data,
+    pool_cache::{PoolCache, POOL_CACHE},
+    DexParamEnum, PoolLoadError,
+};
 use crate::common::SolanaRpcClient;
+use crate::swqos::common::TradeError;
 use solana_sdk::pubkey::Pubkey;
 
 /// MeteoraDammV2 protocol specific parameters
@@ -13,10 +19,16 @@ pub struct MeteoraDammV2Params {
     pub token_a_program: Pubkey,
     pub token_b_program: Pubkey,
     pub referral_token_account: Option<Pubkey>,
-    /// `swap2` mode: 0 exact-in, 1 partial-fill (recommended default), 2 exact-out.
+    /// `swap2` mode: 0 exact-in or 1 partial-fill (recommended default). Exact-out (2) is
+    /// rejected by the builder: these params carry no pool price to quote its max input from.
     pub swap_mode: u8,
+    /// `swap2` minimum output for this transaction. Required: the params carry no pool price, so
+    /// the builder cannot derive it from reserves and `fixed_output_amount` is rejected.
+    pub minimum_amount_out: Option<u64>,
     /// Include the instructions sysvar remaining account when the pool's rate limiter applies.
     pub include_rate_limiter_sysvar: bool,
+    /// On-chain pool status, set by `from_pool_address_by_rpc`; `None` skips the paused check.
+    pub pool_status: Option<u8>,
 }
 
 impl MeteoraDammV2Params {
@@ -39,7 +51,9 @@ impl MeteoraDammV2Params {
             token_b_program,
             referral_token_account: None,
             swap_mode: crate::instruction::utils::meteora_damm_v2::SWAP_MODE_PARTIAL_FILL,
+            minimum_amount_out: None,
             include_rate_limiter_sysvar: false,
+            pool_status: None,
         }
     }
 
@@ -53,6 +67,11 @@ impl MeteoraDammV2Params {
         self
     }
 
+    pub fn with_minimum_amount_out(mut self, minimum_amount_out: u64) -> Self {
+        self.minimum_amount_out = Some(minimum_amount_out);
+        self
+    }
+
     pub fn with_rate_limiter_sysvar(mut self, include: bool) -> Self {
         self.include_rate_limiter_sysvar = include;
         self
@@ -61,22 +80,55 @@ impl MeteoraDammV2Params {
     pub async fn from_pool_address_by_rpc(
         rpc: &SolanaRpcClient,
         pool_address: &Pubkey,
-    ) -> Result<Self, anyhow::Error> {
-        let pool_data =
-            crate::instruction::utils::meteora_damm_v2::fetch_pool(rpc, pool_address).await?;
-        let mint_accounts =
-            rpc.get_multiple_accounts(&[pool_data.token_a_mint, pool_data.token_b_mint]).await?;
-        let token_a_program = mint_accounts
-            .get(0)
-            .and_then(|a| a.as_ref())
-            .map(|a| a.owner)
-            .ok_or_else(|| anyhow::anyhow!("Token A mint account not found"))?;
-        let token_b_program = mint_accounts
-            .get(1)
-            .and_then(|a| a.as_ref())
-            .map(|a| a.owner)
-            .ok_or_else(|| anyhow::anyhow!("Token B mint account not found"))?;
-        Ok(Self {
+    ) -> Result<Self, PoolLoadError> {
+        Self::from_pool_address_by_rpc_at_slot(rpc, pool_address, None).await
+    }
+
+    /// [`Self::from_pool_address_by_rpc`] with the pool and mints read as of at least
+    /// `min_context_slot`, bypassing `POOL_CACHE` for the read.
+    pub async fn from_pool_address_by_rpc_at_slot(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+        min_context_slot: Option<u64>,
+    ) -> Result<Self, PoolLoadError> {
+        Self::load_through_cache(rpc, pool_address, min_context_slot, &POOL_CACHE).await
+    }
+
+    /// The `from_pool_address_by_rpc*` body, reading and filling `cache` instead of `POOL_CACHE`.
+    pub(crate) async fn load_through_cache(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+        min_context_slot: Option<u64>,
+        cache: &PoolCache,
+    ) -> Result<Self, PoolLoadError> {
+        if min_context_slot.is_none() {
+            if let Some(DexParamEnum::MeteoraDammV2(cached)) = cache.get(pool_address) {
+                return Ok(cached);
+            }
+        }
+        let account = fetch_pool_account(rpc, pool_address, min_context_slot).await?;
+        let data = pool_account_data(
+            &account,
+            pool_address,
+            &crate::instruction::utils::meteora_damm_v2::accounts::METEORA_DAMM_V2,
+        )?;
+        let pool_data = crate::instruction::utils::meteora_damm_v2_types::pool_decode(data)
+            .ok_or_else(|| PoolLoadError::decode(pool_address, "invalid Pool layout"))?;
+        let mint_accounts = fetch_accounts_at_slot(
+            rpc,
+            &[pool_data.token_a_mint, pool_data.token_b_mint],
+            min_context_slot,
+        )
+        .await?;
+        let token_a_program =
+            mint_accounts.get(0).and_then(|a| a.as_ref()).map(|a| a.owner).ok_or(
+                PoolLoadError::MintNotFound { pool: *pool_address, mint: pool_data.token_a_mint },
+            )?;
+        let token_b_program =
+            mint_accounts.get(1).and_then(|a| a.as_ref()).map(|a| a.owner).ok_or(
+                PoolLoadError::MintNotFound { pool: *pool_address, mint: pool_data.token_b_mint },
+            )?;
+        let params = Self {
             pool: *pool_address,
             token_a_vault: pool_data.token_a_vault,
             token_b_vault: pool_data.token_b_vault,
@@ -86,7 +138,37 @@ impl MeteoraDammV2Params {
             token_b_program,
             referral_token_account: None,
             swap_mode: crate::instruction::utils::meteora_damm_v2::SWAP_MODE_PARTIAL_FILL,
+            minimum_amount_out: None,
             include_rate_limiter_sysvar: false,
-        })
+            pool_status: Some(pool_data.pool_status),
+        };
+        cache.insert(*pool_address, DexParamEnum::MeteoraDammV2(params.clone()));
+        Ok(params)
+    }
+
+    /// Cheap sanity checks run before building instructions.
+    pub fn validate(&self) -> Result<(), TradeError> {
+        if self.pool == Pubkey::default() {
+            return Err(TradeError::invalid_protocol_params(
+                "MeteoraDammV2 pool address is not set",
+            ));
+        }
+        if self.token_a_mint == self.token_b_mint {
+            return Err(TradeError::invalid_protocol_params(format!(
+                "MeteoraDammV2 token_a_mint and token_b_mint are identical: {}",
+                self.token_a_mint
+            )));
+        }
+        if self.minimum_amount_out == Some(0) {
+            return Err(TradeError::invalid_protocol_params(
+                "MeteoraDammV2 minimum_amount_out must be > 0",
+            ));
+        }
+        if let Some(status) = self.pool_status {
+            if crate::instruction::utils::meteora_damm_v2::is_swap_disabled(status) {
+                return Err(TradeError::pool_paused("MeteoraDammV2", &self.pool, status as u64));
+            }
+        }
+        Ok(())
     }
 }
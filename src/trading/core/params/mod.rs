@@ -3,15 +3,25 @@
 mod bonk;
 mod dex_swap;
 mod meteora_damm_v2;
+pub mod pool_cache;
+mod pool_load;
 mod pumpfun;
 mod pumpswap;
 mod raydium_amm_v4;
 mod raydium_cpmm;
 
 pub use bonk::BonkParams;
-pub use dex_swap::{DexParamEnum, SenderConcurrencyConfig, SwapParams};
+pub use dex_swap::{AtaCreationMode, DexParamEnum, SenderConcurrencyConfig, SwapParams};
 pub use meteora_damm_v2::MeteoraDammV2Params;
-pub use pumpfun::PumpFunParams;
-pub use pumpswap::PumpSwapParams;
+pub use pool_cache::PoolCache;
+pub(crate) use pool_load::{
+    check_pool_owner, fetch_accounts_at_slot, fetch_pool_account, pool_account_data,
+    token_balances_at_slot,
+};
+pub use pool_load::{
+    min_context_slot_timeout, set_min_context_slot_timeout, PoolLoadError, MIN_CONTEXT_SLOT_TIMEOUT,
+};
+pub use pumpfun::{PumpFunCreateArgs, PumpFunParams, PumpFunTradeEvent};
+pub use pumpswap::{DerivedPda, PumpSwapParams, PumpSwapPdaReport};
 pub use raydium_amm_v4::RaydiumAmmV4Params;
-pub use raydium_cpmm::RaydiumCpmmParams;
+pub use raydium_cpmm::{RaydiumCpmmParams, SwapMode};
@@ -0,0 +1,128 @@
+//! Short-lived cache of params loaded by `from_pool_address_by_rpc`, so a buy followed by a sell
+//! on the same pool reuses the first load instead of fetching it again. 池子参数短时缓存。
+//!
+//! Off by default; enable with `TradeConfig::pool_cache_ttl`. Cached params carry the reserves
+//! seen at load time, so keep the TTL short and call [`invalidate`] when a pool is known to have
+//! changed (e.g. after your own trade landed).
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+
+use super::DexParamEnum;
+
+/// Entries kept before expired ones are swept on insert.
+const MAX_POOL_CACHE_ENTRIES: usize = 1_024;
+
+/// Decoded pool params keyed by pool address, each valid for `ttl` after it was loaded.
+pub struct PoolCache {
+    ttl_ms: AtomicU64,
+    entries: DashMap<Pubkey, (Instant, DexParamEnum)>,
+}
+
+impl PoolCache {
+    /// `Duration::ZERO` disables the cache.
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl_ms: AtomicU64::new(ttl.as_millis() as u64), entries: DashMap::new() }
+    }
+
+    #[inline]
+    pub fn ttl(&self) -> Duration {
+        Duration::from_millis(self.ttl_ms.load(Ordering::Relaxed))
+    }
+
+    /// Change the TTL; `Duration::ZERO` disables the cache and drops every entry.
+    pub fn set_ttl(&self, ttl: Duration) {
+        self.ttl_ms.store(ttl.as_millis() as u64, Ordering::Relaxed);
+        if ttl.is_zero() {
+            self.entries.clear();
+        }
+    }
+
+    /// Params for `pool` if loaded within the TTL; expired entries are dropped.
+    pub fn get(&self, pool: &Pubkey) -> Option<DexParamEnum> {
+        let ttl = self.ttl();
+        if ttl.is_zero() {
+            return None;
+        }
+        let entry = self.entries.get(pool)?;
+        if entry.0.elapsed() <= ttl {
+            return Some(entry.1.clone());
+        }
+        drop(entry);
+        self.entries.remove_if(pool, |_, (loaded_at, _)| loaded_at.elapsed() > ttl);
+        None
+    }
+
+    /// Store freshly loaded params; no-op while the cache is disabled.
+    pub fn insert(&self, pool: Pubkey, params: DexParamEnum) {
+        let ttl = self.ttl();
+        if ttl.is_zero() {
+            return;
+        }
+        self.entries.insert(pool, (Instant::now(), params));
+        if self.entries.len() > MAX_POOL_CACHE_ENTRIES {
+            self.entries.retain(|_, (loaded_at, _)| loaded_at.elapsed() <= ttl);
+        }
+    }
+
+    /// Drop `pool` so the next load hits RPC. Returns whether an entry was removed.
+    pub fn invalidate(&self, pool: &Pubkey) -> bool {
+        self.entries.remove(pool).is_some()
+    }
+
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Process-wide cache used by the `from_pool_address_by_rpc` constructors; its TTL is set from
+/// `TradeConfig::pool_cache_ttl` when a `TradingClient` is created.
+pub static POOL_CACHE: Lazy<PoolCache> = Lazy::new(|| PoolCache::new(Duration::ZERO));
+
+/// Drop `pool` from the global cache. 池子状态已变化时手动失效。
+pub fn invalidate(pool: &Pubkey) -> bool {
+    POOL_CACHE.invalidate(pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::core::params::MeteoraDammV2Params;
+
+    fn params(pool: Pubkey) -> DexParamEnum {
+        let d = Pubkey::default();
+        DexParamEnum::MeteoraDammV2(MeteoraDammV2Params::new(pool, d, d, d, d, d, d))
+    }
+
+    #[test]
+    fn entries_live_for_the_ttl_and_can_be_invalidated() {
+        let pool = Pubkey::new_unique();
+        let cache = PoolCache::new(Duration::ZERO);
+        cache.insert(pool, params(pool));
+        assert!(cache.get(&pool).is_none(), "disabled by default");
+
+        cache.set_ttl(Duration::from_secs(60));
+        cache.insert(pool, params(pool));
+        assert!(matches!(cache.get(&pool), Some(DexParamEnum::MeteoraDammV2(p)) if p.pool == pool));
+        assert!(cache.invalidate(&pool));
+        assert!(cache.get(&pool).is_none());
+
+        cache.set_ttl(Duration::from_millis(1));
+        cache.insert(pool, params(pool));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get(&pool).is_none());
+        assert!(cache.is_empty(), "expired entry is dropped on read");
+    }
+}
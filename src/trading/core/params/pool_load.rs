@@ -0,0 +1,384 @@
+//! Typed error and account reads for the `from_pool_address_by_rpc` family of constructors.
+//! 按 RPC 加载池子参数时的类型化错误。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_rpc_client_api::{
+    client_error::ErrorKind, config::RpcAccountInfoConfig,
+    custom_error::JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED, request::RpcError,
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::common::SolanaRpcClient;
+use crate::swqos::common::TradeError;
+
+/// Why a pool could not be loaded: the address is not a (decodable) pool, or RPC failed.
+/// Converts into `anyhow::Error` via `?` for callers that don't care.
+#[derive(Debug)]
+pub enum PoolLoadError {
+    /// No account exists at the address.
+    NotFound(Pubkey),
+    /// The pool decoded, but one of the mints it references has no account.
+    MintNotFound { pool: Pubkey, mint: Pubkey },
+    /// The account is owned by another program, typically a token mint passed as a pool address.
+    NotAPool { address: Pubkey, expected_program: Pubkey, actual_owner: Pubkey },
+    /// The account is owned by the protocol but does not decode as its pool.
+    DecodeFailed { address: Pubkey, reason: String },
+    /// RPC request failed (network, rate limit, node error), including follow-up vault/mint reads.
+    RpcError(anyhow::Error),
+    /// The node stayed behind `min_context_slot` for [`min_context_slot_timeout`].
+    MinContextSlotNotReached { min_context_slot: u64, node_slot: Option<u64> },
+}
+
+impl PoolLoadError {
+    pub(crate) fn decode(address: &Pubkey, reason: impl Into<String>) -> Self {
+        Self::DecodeFailed { address: *address, reason: reason.into() }
+    }
+
+    /// True when the address is definitely not a usable pool (retrying will not help).
+    pub fn is_not_a_pool(&self) -> bool {
+        !matches!(self, Self::RpcError(_) | Self::MinContextSlotNotReached { .. })
+    }
+}
+
+impl std::fmt::Display for PoolLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(address) => write!(f, "pool account not found: {}", address),
+            Self::MintNotFound { pool, mint } => {
+                write!(f, "mint {} referenced by pool {} not found", mint, pool)
+            }
+            Self::NotAPool { address, expected_program, actual_owner } => write!(
+                f,
+                "{} is not a pool: owned by {}, expected program {} (token mint passed as pool address?)",
+                address, actual_owner, expected_program
+            ),
+            Self::DecodeFailed { address, reason } => {
+                write!(f, "failed to decode pool {}: {}", address, reason)
+            }
+            Self::RpcError(e) => write!(f, "rpc error while loading pool: {}", e),
+            Self::MinContextSlotNotReached { min_context_slot, node_slot: Some(slot) } => write!(
+                f,
+                "rpc node at slot {} did not reach min_context_slot {} within {:?}",
+                slot,
+                min_context_slot,
+                min_context_slot_timeout()
+            ),
+            Self::MinContextSlotNotReached { min_context_slot, node_slot: None } => write!(
+                f,
+                "rpc node did not reach min_context_slot {} within {:?}",
+                min_context_slot,
+                min_context_slot_timeout()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PoolLoadError {}
+
+impl From<PoolLoadError> for TradeError {
+    fn from(e: PoolLoadError) -> Self {
+        match e {
+            PoolLoadError::NotAPool { address, expected_program, actual_owner } => {
+                TradeError::not_a_pool(&address, &expected_program, &actual_owner)
+            }
+            other => TradeError::new(500, other.to_string()),
+        }
+    }
+}
+
+impl From<solana_rpc_client_api::client_error::Error> for PoolLoadError {
+    fn from(e: solana_rpc_client_api::client_error::Error) -> Self {
+        Self::RpcError(e.into())
+    }
+}
+
+/// Default for [`min_context_slot_timeout`].
+pub const MIN_CONTEXT_SLOT_TIMEOUT: Duration = Duration::from_secs(2);
+const MIN_CONTEXT_SLOT_RETRY: Duration = Duration::from_millis(50);
+
+static MIN_CONTEXT_SLOT_TIMEOUT_MS: AtomicU64 =
+    AtomicU64::new(MIN_CONTEXT_SLOT_TIMEOUT.as_millis() as u64);
+
+/// How long a read with `min_context_slot` keeps retrying a lagging node before giving up
+/// (process-wide, default [`MIN_CONTEXT_SLOT_TIMEOUT`]).
+#[inline]
+pub fn min_context_slot_timeout() -> Duration {
+    Duration::from_millis(MIN_CONTEXT_SLOT_TIMEOUT_MS.load(Ordering::Relaxed))
+}
+
+/// Set [`min_context_slot_timeout`]. `Duration::ZERO` tries each read once.
+/// 设置按 slot 读取时等待滞后节点的最长时间。
+pub fn set_min_context_slot_timeout(timeout: Duration) {
+    MIN_CONTEXT_SLOT_TIMEOUT_MS
+        .store(u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX), Ordering::Relaxed);
+}
+
+/// Fetch the pool account, mapping a missing account to `NotFound` instead of an RPC error.
+pub(crate) async fn fetch_pool_account(
+    rpc: &SolanaRpcClient,
+    address: &Pubkey,
+    min_context_slot: Option<u64>,
+) -> Result<Account, PoolLoadError> {
+    let account = match min_context_slot {
+        None => rpc.get_account_with_commitment(address, rpc.commitment()).await?.value,
+        Some(_) => {
+            fetch_accounts_at_slot(rpc, &[*address], min_context_slot).await?.pop().flatten()
+        }
+    };
+    account.ok_or(PoolLoadError::NotFound(*address))
+}
+
+/// `getMultipleAccounts` as of at least `min_context_slot`. A node that is behind (RPC error
+/// -32016, or a response context older than the slot) is retried every 50ms until
+/// [`min_context_slot_timeout`]. 读取不早于指定 slot 的账户，避免滞后节点返回旧储备。
+pub(crate) async fn fetch_accounts_at_slot(
+    rpc: &SolanaRpcClient,
+    keys: &[Pubkey],
+    min_context_slot: Option<u64>,
+) -> Result<Vec<Option<Account>>, PoolLoadError> {
+    fetch_accounts_within(rpc, keys, min_context_slot, min_context_slot_timeout()).await
+}
+
+async fn fetch_accounts_within(
+    rpc: &SolanaRpcClient,
+    keys: &[Pubkey],
+    min_context_slot: Option<u64>,
+    timeout: Duration,
+) -> Result<Vec<Option<Account>>, PoolLoadError> {
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(rpc.commitment()),
+        min_context_slot,
+        ..Default::default()
+    };
+    let Some(min_slot) = min_context_slot else {
+        return Ok(rpc.get_multiple_accounts_with_config(keys, config).await?.value);
+    };
+    let start = Instant::now();
+    loop {
+        let node_slot = match rpc.get_multiple_accounts_with_config(keys, config.clone()).await {
+            Ok(response) if response.context.slot >= min_slot => return Ok(response.value),
+            Ok(response) => Some(response.context.slot),
+            Err(e) if is_min_context_slot_not_reached(e.kind()) => None,
+            Err(e) => return Err(e.into()),
+        };
+        if start.elapsed() + MIN_CONTEXT_SLOT_RETRY > timeout {
+            return Err(PoolLoadError::MinContextSlotNotReached {
+                min_context_slot: min_slot,
+                node_slot,
+            });
+        }
+        tokio::time::sleep(MIN_CONTEXT_SLOT_RETRY).await;
+    }
+}
+
+#[inline]
+fn is_min_context_slot_not_reached(kind: &ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if *code == JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+    )
+}
+
+/// Amounts of two token accounts read via [`fetch_accounts_at_slot`] (SPL Token and Token-2022
+/// share the `amount` offset).
+pub(crate) async fn token_balances_at_slot(
+    rpc: &SolanaRpcClient,
+    vault0: &Pubkey,
+    vault1: &Pubkey,
+    min_context_slot: Option<u64>,
+) -> Result<(u64, u64), PoolLoadError> {
+    let accounts = fetch_accounts_at_slot(rpc, &[*vault0, *vault1], min_context_slot).await?;
+    let amount = |i: usize, vault: &Pubkey| {
+        let account =
+            accounts.get(i).and_then(Option::as_ref).ok_or(PoolLoadError::NotFound(*vault))?;
+        account
+            .data
+            .get(64..72)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+            .ok_or_else(|| PoolLoadError::decode(vault, "token account data too short"))
+    };
+    Ok((amount(0, vault0)?, amount(1, vault1)?))
+}
+
+/// Reject accounts not owned by the protocol `program` (or its configured override, see
+/// [`protocol_overrides`](crate::trading::common::protocol_overrides)) before decoding them.
+pub(crate) fn check_pool_owner(
+    account: &Account,
+    address: &Pubkey,
+    program: &Pubkey,
+) -> Result<(), PoolLoadError> {
+    if account.owner != *program
+        && Some(account.owner)
+            != crate::trading::common::protocol_overrides::protocol_overrides()
+                .program_id_for(program)
+    {
+        return Err(PoolLoadError::NotAPool {
+            address: *address,
+            expected_program: *program,
+            actual_owner: account.owner,
+        });
+    }
+    Ok(())
+}
+
+/// Check the owner and strip the 8-byte Anchor discriminator.
+pub(crate) fn pool_account_data<'a>(
+    account: &'a Account,
+    address: &Pubkey,
+    program: &Pubkey,
+) -> Result<&'a [u8], PoolLoadError> {
+    check_pool_owner(account, address, program)?;
+    account.data.get(8..).ok_or_else(|| PoolLoadError::decode(address, "account data too short"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{mock_rpc::mock_account, MockSolanaRpcClient};
+
+    #[tokio::test]
+    async fn missing_account_is_not_found_and_rpc_failure_is_rpc_error() {
+        let address = Pubkey::new_unique();
+        let rpc = MockSolanaRpcClient::new().with_account(None).build();
+        let err = fetch_pool_account(&rpc, &address, None).await.unwrap_err();
+        assert!(matches!(err, PoolLoadError::NotFound(a) if a == address));
+        assert!(err.is_not_a_pool());
+
+        let program = Pubkey::new_unique();
+        let err =
+            pool_account_data(&mock_account(program, vec![0; 4]), &address, &program).unwrap_err();
+        assert!(matches!(err, PoolLoadError::DecodeFailed { .. }));
+
+        let err = PoolLoadError::RpcError(anyhow::anyhow!("connection refused"));
+        assert!(!err.is_not_a_pool());
+        let any: anyhow::Error = err.into();
+        assert!(any.to_string().contains("connection refused"));
+    }
+
+    #[tokio::test]
+    async fn min_context_slot_reads_fail_while_the_node_lags() {
+        use crate::common::mock_rpc::MOCK_SLOT;
+
+        let vault = mock_account(crate::constants::TOKEN_PROGRAM, vec![7; 165]);
+        let keys = [Pubkey::new_unique()];
+        let rpc = MockSolanaRpcClient::new().with_multiple_accounts(&[Some(&vault)]).build();
+        let accounts =
+            fetch_accounts_within(&rpc, &keys, Some(MOCK_SLOT), Duration::ZERO).await.unwrap();
+        assert_eq!(accounts, vec![Some(vault.clone())]);
+
+        let rpc = MockSolanaRpcClient::new().with_multiple_accounts(&[Some(&vault)]).build();
+        let err = fetch_accounts_within(&rpc, &keys, Some(MOCK_SLOT + 10), Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PoolLoadError::MinContextSlotNotReached { min_context_slot, node_slot: Some(MOCK_SLOT) }
+                if min_context_slot == MOCK_SLOT + 10
+        ));
+        assert!(!err.is_not_a_pool());
+    }
+
+    #[tokio::test]
+    async fn slot_bound_loaders_read_pool_and_mints_at_the_slot() {
+        use crate::common::mock_rpc::MOCK_SLOT;
+        use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+        use crate::instruction::utils::{bonk, meteora_damm_v2};
+        use crate::test_fixtures::{
+            bonk_pool_account_data, meteora_damm_v2_pool_account_data, MINT, QUOTE_RESERVE,
+        };
+        use crate::trading::core::params::{BonkParams, MeteoraDammV2Params};
+
+        let mint = mock_account(TOKEN_PROGRAM_2022, vec![0; 82]);
+        let pool = mock_account(bonk::accounts::BONK, bonk_pool_account_data());
+        let rpc =
+            MockSolanaRpcClient::new().with_multiple_accounts(&[Some(&pool), Some(&mint)]).build();
+        let params = BonkParams::from_mint_by_rpc_at_slot(&rpc, &MINT, false, Some(MOCK_SLOT))
+            .await
+            .unwrap();
+        assert_eq!(params.virtual_quote, QUOTE_RESERVE as u128);
+        assert_eq!(params.mint_token_program, TOKEN_PROGRAM_2022);
+
+        let address = Pubkey::new_unique();
+        let pool = mock_account(
+            meteora_damm_v2::accounts::METEORA_DAMM_V2,
+            meteora_damm_v2_pool_account_data(),
+        );
+        let wsol = mock_account(TOKEN_PROGRAM, vec![0; 82]);
+        let rpc = MockSolanaRpcClient::new()
+            .with_multiple_accounts(&[Some(&pool)])
+            .with_multiple_accounts(&[Some(&mint), Some(&wsol)])
+            .build();
+        let params =
+            MeteoraDammV2Params::from_pool_address_by_rpc_at_slot(&rpc, &address, Some(MOCK_SLOT))
+                .await
+                .unwrap();
+        assert_eq!((params.token_a_mint, params.token_a_program), (MINT, TOKEN_PROGRAM_2022));
+        assert_eq!(params.token_b_program, TOKEN_PROGRAM);
+    }
+
+    #[tokio::test]
+    async fn missing_mint_is_reported_as_mint_not_found() {
+        use crate::instruction::utils::meteora_damm_v2;
+        use crate::test_fixtures::{meteora_damm_v2_pool_account_data, MINT};
+        use crate::trading::core::params::MeteoraDammV2Params;
+
+        let address = Pubkey::new_unique();
+        let pool = mock_account(
+            meteora_damm_v2::accounts::METEORA_DAMM_V2,
+            meteora_damm_v2_pool_account_data(),
+        );
+        let wsol = mock_account(crate::constants::TOKEN_PROGRAM, vec![0; 82]);
+        let rpc = MockSolanaRpcClient::new()
+            .with_multiple_accounts(&[Some(&pool)])
+            .with_multiple_accounts(&[None, Some(&wsol)])
+            .build();
+        let err = MeteoraDammV2Params::from_pool_address_by_rpc_at_slot(
+            &rpc,
+            &address,
+            Some(crate::common::mock_rpc::MOCK_SLOT),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            PoolLoadError::MintNotFound { pool, mint } if pool == address && mint == MINT
+        ));
+        assert!(err.to_string().contains("mint"));
+    }
+
+    #[test]
+    fn min_context_slot_timeout_is_configurable() {
+        assert_eq!(min_context_slot_timeout(), MIN_CONTEXT_SLOT_TIMEOUT);
+        set_min_context_slot_timeout(Duration::from_millis(750));
+        assert_eq!(min_context_slot_timeout(), Duration::from_millis(750));
+        set_min_context_slot_timeout(MIN_CONTEXT_SLOT_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn mint_passed_as_pool_is_not_a_pool() {
+        use crate::trading::core::params::{PumpSwapParams, RaydiumCpmmParams};
+
+        let mint = Pubkey::new_unique();
+        let mint_account = mock_account(crate::constants::TOKEN_PROGRAM, vec![0; 82]);
+        let rpc = MockSolanaRpcClient::new().with_account(Some(&mint_account)).build();
+        let err = PumpSwapParams::from_pool_address_by_rpc(&rpc, &mint).await.unwrap_err();
+        assert!(matches!(
+            err,
+            PoolLoadError::NotAPool { address, expected_program, actual_owner }
+                if address == mint
+                    && expected_program == crate::instruction::utils::pumpswap::accounts::AMM_PROGRAM
+                    && actual_owner == crate::constants::TOKEN_PROGRAM
+        ));
+        assert!(err.is_not_a_pool());
+        assert_eq!(TradeError::from(err).code, TradeError::NOT_A_POOL);
+
+        let rpc = MockSolanaRpcClient::new().with_account(Some(&mint_account)).build();
+        let err = RaydiumCpmmParams::from_pool_address_by_rpc(&rpc, &mint).await.unwrap_err();
+        assert!(matches!(err, PoolLoadError::NotAPool { .. }), "{}", err);
+    }
+}
@@ -1,10 +1,109 @@
 use crate::common::bonding_curve::BondingCurveAccount;
 use crate::common::spl_associated_token_account::get_associated_token_address_with_program_id;
 use crate::common::SolanaRpcClient;
+use crate::instruction::pumpfun_layout::PumpFunLayout;
 use crate::instruction::utils::pumpfun::reconcile_mayhem_mode_for_trade;
-use solana_sdk::pubkey::Pubkey;
+use crate::swqos::common::TradeError;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair};
 use std::sync::Arc;
 
+/// PumpFun trade event fields used to build [`PumpFunParams`], named as in sol-parser-sdk /
+/// solana-streamer `PumpFunTradeEvent`. 交易事件字段（按名称映射，避免位置参数写错）。
+///
+/// Fill it field by field from your parser's event and pass it to [`PumpFunParams::from_event`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PumpFunTradeEvent {
+    pub mint: Pubkey,
+    /// `Pubkey::default()` / SOL / WSOL for SOL-paired coins, the quote mint (e.g. USDC) for V2.
+    pub quote_mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub associated_bonding_curve: Pubkey,
+    pub creator: Pubkey,
+    pub creator_vault: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub token_program: Pubkey,
+    pub virtual_token_reserves: u64,
+    pub real_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_sol_reserves: u64,
+    /// Quote reserves of V2 events; when `virtual_quote_reserves` is 0 the SOL reserves are used.
+    pub virtual_quote_reserves: u64,
+    pub real_quote_reserves: u64,
+    pub is_cashback_coin: bool,
+    pub mayhem_mode: bool,
+}
+
+impl PumpFunTradeEvent {
+    /// `(virtual, real)` quote reserves: the quote fields for V2 events, else the SOL fields.
+    pub fn quote_reserves(&self) -> (u64, u64) {
+        if self.virtual_quote_reserves != 0 {
+            (self.virtual_quote_reserves, self.real_quote_reserves)
+        } else {
+            (self.virtual_sol_reserves, self.real_sol_reserves)
+        }
+    }
+}
+
+impl TryFrom<&PumpFunTradeEvent> for PumpFunParams {
+    type Error = TradeError;
+
+    /// [`PumpFunParams::from_event`] (token account kept after sells), then
+    /// [`PumpFunParams::validate`].
+    fn try_from(event: &PumpFunTradeEvent) -> Result<Self, TradeError> {
+        let params = Self::from_event(event, None);
+        params.validate()?;
+        Ok(params)
+    }
+}
+
+/// Metadata and fees for [`TradingClient::pumpfun_create_and_buy`](crate::TradingClient::pumpfun_create_and_buy).
+/// 发币参数：名称、符号、元数据 URI 与 CU 设置。
+pub struct PumpFunCreateArgs {
+    pub name: String,
+    pub symbol: String,
+    /// Metadata JSON URI (image, description, socials).
+    pub uri: String,
+    /// Coin creator recorded on the curve; defaults to the payer.
+    pub creator: Option<Pubkey>,
+    /// Mint keypair; a fresh one is generated when `None`. Addresses ending in `pump` are
+    /// rejected: the buy path treats those as Token-2022 mints, while `create` mints SPL Token.
+    pub mint: Option<Keypair>,
+    pub cu_limit: u32,
+    /// Priority fee in micro-lamports per CU.
+    pub cu_price: u64,
+}
+
+impl PumpFunCreateArgs {
+    /// Defaults: payer as creator, generated mint, 300k CU, no priority fee.
+    pub fn new(name: impl Into<String>, symbol: impl Into<String>, uri: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            symbol: symbol.into(),
+            uri: uri.into(),
+            creator: None,
+            mint: None,
+            cu_limit: 300_000,
+            cu_price: 0,
+        }
+    }
+
+    pub fn with_creator(mut self, creator: Pubkey) -> Self {
+        self.creator = Some(creator);
+        self
+    }
+
+    pub fn with_mint(mut self, mint: Keypair) -> Self {
+        self.mint = Some(mint);
+        self
+    }
+
+    pub fn with_compute_budget(mut self, cu_limit: u32, cu_price: u64) -> Self {
+        self.cu_limit = cu_limit;
+        self.cu_price = cu_price;
+        self
+    }
+}
+
 /// PumpFun protocol specific parameters
 /// Configuration parameters specific to PumpFun trading protocol.
 ///
@@ -47,6 +146,8 @@ pub struct PumpFunParams {
     /// USDC and other non-native quote mints select V2.
     /// For USDC-paired coins, set to `EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v`.
     pub quote_mint: Pubkey,
+    /// Buy/sell account layout; `Auto` (default) picks it from `quote_mint` as above.
+    pub layout: PumpFunLayout,
 }
 
 impl PumpFunParams {
@@ -89,6 +190,7 @@ impl PumpFunParams {
             close_token_account_when_sell: Some(close_token_account_when_sell),
             fee_recipient: Pubkey::default(),
             quote_mint: Pubkey::default(),
+            layout: PumpFunLayout::Auto,
         }
     }
 
@@ -180,6 +282,7 @@ impl PumpFunParams {
             token_program: token_program,
             fee_recipient,
             quote_mint: Self::quote_mint_for_layout(quote_mint),
+            layout: PumpFunLayout::Auto,
         }
     }
 
@@ -191,6 +294,7 @@ impl PumpFunParams {
     /// `mayhem_mode`:
     /// - **`Some(v)`**：优先采用 gRPC / `tradeEvent`，但与 **`fee_recipient` 所属池**（Mayhem vs 普通，见 pump-public-docs）不一致时，以 fee 地址为准纠偏，避免链上 `NotAuthorized`。
     /// - **`None`**：用 `fee_recipient` 是否落在 Mayhem 静态列表推断。
+    #[deprecated(note = "use PumpFunParams::from_event(&PumpFunTradeEvent, ..)")]
     pub fn from_trade(
         bonding_curve: Pubkey,
         associated_bonding_curve: Pubkey,
@@ -243,11 +347,39 @@ impl PumpFunParams {
             token_program: token_program,
             fee_recipient,
             quote_mint: Self::quote_mint_for_layout(quote_mint),
+            layout: PumpFunLayout::Auto,
         }
     }
 
-    /// Deprecated compatibility alias. Prefer [`Self::from_trade`] and pass `quote_mint` there.
-    #[deprecated(note = "use PumpFunParams::from_trade(..., quote_mint)")]
+    /// Build PumpFun params from a trade event; fields are mapped by name, and the quote
+    /// reserves fall back to the SOL reserves for SOL-paired events.
+    /// `is_sell`: forwarded as `close_token_account_when_sell` (`Some(true)` closes the token
+    /// ATA after selling everything).
+    #[allow(deprecated)]
+    pub fn from_event(event: &PumpFunTradeEvent, is_sell: Option<bool>) -> Self {
+        let (virtual_quote_reserves, real_quote_reserves) = event.quote_reserves();
+        Self::from_trade(
+            event.bonding_curve,
+            event.associated_bonding_curve,
+            event.mint,
+            event.quote_mint,
+            event.creator,
+            event.creator_vault,
+            event.virtual_token_reserves,
+            virtual_quote_reserves,
+            event.real_token_reserves,
+            real_quote_reserves,
+            is_sell,
+            event.fee_recipient,
+            event.token_program,
+            event.is_cashback_coin,
+            Some(event.mayhem_mode),
+        )
+    }
+
+    /// Deprecated compatibility alias. Prefer [`Self::from_event`].
+    #[deprecated(note = "use PumpFunParams::from_event(&PumpFunTradeEvent, ..)")]
+    #[allow(deprecated)]
     pub fn from_trade_with_quote_mint(
         bonding_curve: Pubkey,
         associated_bonding_curve: Pubkey,
@@ -289,9 +421,35 @@ impl PumpFunParams {
         rpc: &SolanaRpcClient,
         mint: &Pubkey,
     ) -> Result<Self, anyhow::Error> {
-        let account =
-            crate::instruction::utils::pumpfun::fetch_bonding_curve_account(rpc, mint).await?;
-        let mint_account = rpc.get_account(&mint).await?;
+        Self::from_mint_by_rpc_at_slot(rpc, mint, None).await
+    }
+
+    /// [`Self::from_mint_by_rpc`] with the curve, mint and fee-sharing config read in one
+    /// `getMultipleAccounts` as of at least `min_context_slot` (e.g. the slot of a copied trade);
+    /// a node still behind after [`min_context_slot_timeout`](super::min_context_slot_timeout)
+    /// fails with [`PoolLoadError::MinContextSlotNotReached`](super::PoolLoadError).
+    /// 跟单时按事件 slot 读取曲线，避免滞后节点返回旧储备。
+    pub async fn from_mint_by_rpc_at_slot(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+        min_context_slot: Option<u64>,
+    ) -> Result<Self, anyhow::Error> {
+        let (account, mint_token_program, fee_sharing_creator_vault_if_active) =
+            match min_context_slot {
+                None => {
+                    let account =
+                        crate::instruction::utils::pumpfun::fetch_bonding_curve_account(rpc, mint)
+                            .await?;
+                    let mint_account = rpc.get_account(&mint).await?;
+                    let fee_sharing =
+                        crate::instruction::utils::pumpfun::fetch_fee_sharing_creator_vault_if_active(
+                            rpc, mint,
+                        )
+                        .await?;
+                    (account, mint_account.owner, fee_sharing)
+                }
+                Some(_) => Self::curve_accounts_at_slot(rpc, mint, min_context_slot).await?,
+            };
         let bonding_curve = BondingCurveAccount {
             discriminator: 0,
             account: account.1,
@@ -309,13 +467,8 @@ impl PumpFunParams {
         let associated_bonding_curve = get_associated_token_address_with_program_id(
             &bonding_curve.account,
             mint,
-            &mint_account.owner,
+            &mint_token_program,
         );
-        let fee_sharing_creator_vault_if_active =
-            crate::instruction::utils::pumpfun::fetch_fee_sharing_creator_vault_if_active(
-                rpc, mint,
-            )
-            .await?;
         let creator_vault =
             crate::instruction::utils::pumpfun::resolve_creator_vault_for_ix_with_fee_sharing(
                 &bonding_curve.creator,
@@ -335,12 +488,47 @@ impl PumpFunParams {
             creator_vault,
             fee_sharing_creator_vault_if_active,
             close_token_account_when_sell: None,
-            token_program: mint_account.owner,
+            token_program: mint_token_program,
             fee_recipient: Pubkey::default(),
             quote_mint: Self::quote_mint_for_rpc_return(quote_mint),
+            layout: PumpFunLayout::Auto,
         })
     }
 
+    /// (curve and its PDA, mint owner, active fee-sharing creator vault) in one slot-bound read.
+    async fn curve_accounts_at_slot(
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+        min_context_slot: Option<u64>,
+    ) -> Result<((Arc<BondingCurveAccount>, Pubkey), Pubkey, Option<Pubkey>), anyhow::Error> {
+        use crate::instruction::utils::pumpfun::{
+            decode_bonding_curve_account, fee_sharing_creator_vault_from_account,
+            get_bonding_curve_pda, get_fee_sharing_config_pda,
+        };
+
+        let curve_pda = get_bonding_curve_pda(mint)
+            .ok_or_else(|| anyhow::anyhow!("Bonding curve not found"))?;
+        let config_pda = get_fee_sharing_config_pda(mint);
+        let mut keys = vec![curve_pda, *mint];
+        keys.extend(config_pda);
+        let accounts = super::fetch_accounts_at_slot(rpc, &keys, min_context_slot).await?;
+        let curve = accounts
+            .first()
+            .and_then(Option::as_ref)
+            .ok_or_else(|| anyhow::anyhow!("Bonding curve not found"))?;
+        let curve = decode_bonding_curve_account(&curve.data, curve_pda)?;
+        let mint_token_program = accounts
+            .get(1)
+            .and_then(Option::as_ref)
+            .map(|a| a.owner)
+            .ok_or_else(|| anyhow::anyhow!("mint account not found: {}", mint))?;
+        let fee_sharing = config_pda.and_then(|config_pda| {
+            let account = accounts.get(2)?.as_ref()?;
+            fee_sharing_creator_vault_from_account(account, &config_pda, mint)
+        });
+        Ok(((Arc::new(curve), curve_pda), mint_token_program, fee_sharing))
+    }
+
     /// 链下公式与 **`creator_vault` 推导回退**：**日志/事件 `creator`**（若已写入 `observed_trade_creator`）
     /// 优先，否则使用 `bonding_curve.creator`。
     #[inline]
@@ -389,6 +577,14 @@ impl PumpFunParams {
         self
     }
 
+    /// Pin the buy/sell account layout instead of deriving it from `quote_mint`.
+    /// `Legacy` only supports native SOL settlement; the builder rejects it for other quotes.
+    #[inline]
+    pub fn with_layout(mut self, layout: PumpFunLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
     /// Updates the cached `creator_vault` field only. Buy/sell ix use [`Self::effective_creator_for_trade`] + resolve.
     #[inline]
     pub fn with_creator_vault(mut self, creator_vault: Pubkey) -> Self {
@@ -412,12 +608,45 @@ impl PumpFunParams {
         self.fee_sharing_creator_vault_if_active = fee_sharing_creator_vault_if_active;
         self
     }
+
+    /// Cheap sanity checks run before building instructions. Fully zeroed reserves are allowed
+    /// (see [`Self::immediate_sell`]); only a half-populated curve snapshot is rejected.
+    pub fn validate(&self) -> Result<(), TradeError> {
+        let curve = &self.bonding_curve;
+        if (curve.virtual_token_reserves == 0) != (curve.virtual_sol_reserves == 0) {
+            return Err(TradeError::invalid_protocol_params(format!(
+                "PumpFun bonding curve reserves are inconsistent: virtual_token={} virtual_sol={}",
+                curve.virtual_token_reserves, curve.virtual_sol_reserves
+            )));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn from_mint_at_slot_reads_curve_mint_and_sharing_config_in_one_request() {
+        use crate::common::mock_rpc::{mock_account, MOCK_SLOT};
+        use crate::common::MockSolanaRpcClient;
+        use crate::constants::TOKEN_PROGRAM_2022;
+        use crate::instruction::utils::pumpfun::accounts::PUMPFUN;
+        use crate::test_fixtures::{pumpfun_curve_account_data, BASE_RESERVE, MINT};
+
+        let curve = mock_account(PUMPFUN, pumpfun_curve_account_data());
+        let mint = mock_account(TOKEN_PROGRAM_2022, vec![0; 82]);
+        let rpc = MockSolanaRpcClient::new()
+            .with_multiple_accounts(&[Some(&curve), Some(&mint), None])
+            .build();
+        let params =
+            PumpFunParams::from_mint_by_rpc_at_slot(&rpc, &MINT, Some(MOCK_SLOT)).await.unwrap();
+        assert_eq!(params.bonding_curve.virtual_token_reserves, BASE_RESERVE);
+        assert_eq!(params.token_program, TOKEN_PROGRAM_2022);
+        assert_eq!(params.fee_sharing_creator_vault_if_active, None);
+    }
+
     #[test]
     fn rpc_return_quote_mint_normalizes_legacy_sol_to_sol_sentinel() {
         assert_eq!(
@@ -458,4 +687,98 @@ mod tests {
             crate::constants::USDC_TOKEN_ACCOUNT
         );
     }
+
+    #[test]
+    fn validate_accepts_immediate_sell_and_rejects_half_populated_curve() {
+        let params = PumpFunParams::immediate_sell(Pubkey::new_unique(), Pubkey::default(), false);
+        assert!(params.validate().is_ok());
+
+        let mut params = params;
+        params.bonding_curve = Arc::new(BondingCurveAccount {
+            virtual_token_reserves: 1_000_000,
+            ..Default::default()
+        });
+        let err = params.validate().unwrap_err();
+        assert_eq!(err.code, TradeError::INVALID_PROTOCOL_PARAMS);
+        assert!(err.is_sdk_error());
+    }
+
+    #[test]
+    fn try_from_event_maps_every_field() {
+        let creator = Pubkey::new_unique();
+        let event = PumpFunTradeEvent {
+            mint: Pubkey::new_unique(),
+            quote_mint: crate::constants::WSOL_TOKEN_ACCOUNT,
+            bonding_curve: Pubkey::new_unique(),
+            associated_bonding_curve: Pubkey::new_unique(),
+            creator,
+            creator_vault: crate::instruction::utils::pumpfun::get_creator_vault_pda(&creator)
+                .unwrap(),
+            fee_recipient: Pubkey::new_unique(),
+            token_program: crate::constants::TOKEN_PROGRAM_2022,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_sol_reserves: 1_000_000_000,
+            virtual_quote_reserves: 0,
+            real_quote_reserves: 0,
+            is_cashback_coin: true,
+            mayhem_mode: false,
+        };
+        let params = PumpFunParams::try_from(&event).unwrap();
+        let curve = &params.bonding_curve;
+        assert_eq!(curve.account, event.bonding_curve);
+        assert_eq!(curve.creator, event.creator);
+        assert_eq!(curve.virtual_token_reserves, event.virtual_token_reserves);
+        assert_eq!(curve.real_token_reserves, event.real_token_reserves);
+        assert_eq!(curve.virtual_quote_reserves(), event.virtual_sol_reserves);
+        assert_eq!(curve.real_quote_reserves(), event.real_sol_reserves);
+        assert!(curve.is_cashback_coin && !curve.is_mayhem_mode);
+        assert_eq!(params.associated_bonding_curve, event.associated_bonding_curve);
+        assert_eq!(params.observed_trade_creator, Some(creator));
+        assert_eq!(params.creator_vault, event.creator_vault);
+        assert_eq!(params.fee_recipient, event.fee_recipient);
+        assert_eq!(params.token_program, event.token_program);
+        assert_eq!(params.close_token_account_when_sell, None);
+        // WSOL is the native-SOL sentinel: legacy layout
+        assert_eq!(params.quote_mint, Pubkey::default());
+
+        let half = PumpFunTradeEvent { virtual_sol_reserves: 0, ..event };
+        let err = PumpFunParams::try_from(&half).err().unwrap();
+        assert_eq!(err.code, TradeError::INVALID_PROTOCOL_PARAMS);
+    }
+
+    #[test]
+    fn from_event_maps_reserves_by_name() {
+        let event = PumpFunTradeEvent {
+            mint: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            token_program: crate::constants::TOKEN_PROGRAM_2022,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_sol_reserves: 1_000_000_000,
+            ..Default::default()
+        };
+        let params = PumpFunParams::from_event(&event, Some(true));
+        let curve = &params.bonding_curve;
+        assert_eq!(curve.virtual_token_reserves, event.virtual_token_reserves);
+        assert_eq!(curve.real_token_reserves, event.real_token_reserves);
+        assert_eq!(curve.virtual_quote_reserves(), event.virtual_sol_reserves);
+        assert_eq!(curve.real_quote_reserves(), event.real_sol_reserves);
+        assert_eq!(params.close_token_account_when_sell, Some(true));
+        assert_eq!(params.token_program, event.token_program);
+
+        // V2 events carry the quote reserves in their own fields
+        let v2 = PumpFunTradeEvent {
+            quote_mint: crate::constants::USDC_TOKEN_ACCOUNT,
+            virtual_quote_reserves: 4_527_693_121,
+            real_quote_reserves: 235_693_121,
+            ..event
+        };
+        let params = PumpFunParams::from_event(&v2, None);
+        assert_eq!(params.bonding_curve.virtual_quote_reserves(), 4_527_693_121);
+        assert_eq!(params.bonding_curve.real_quote_reserves(), 235_693_121);
+        assert_eq!(params.quote_mint, crate::constants::USDC_TOKEN_ACCOUNT);
+    }
 }
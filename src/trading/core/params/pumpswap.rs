@@ -1,7 +1,14 @@
+use super::{
+    check_pool_owner, fetch_accounts_at_slot, fetch_pool_account,
+    pool_cache::{PoolCache, POOL_CACHE},
+    DexParamEnum, PoolLoadError,
+};
 use crate::common::SolanaRpcClient;
 use crate::instruction::utils::pumpswap::{
     accounts::MAYHEM_FEE_RECIPIENT as MAYHEM_FEE_RECIPIENT_SWAP, PumpSwapFeeBasisPoints,
 };
+use crate::swqos::common::TradeError;
+use crate::TradeTokenType;
 use solana_sdk::pubkey::Pubkey;
 
 /// PumpSwap Protocol Specific Parameters
@@ -62,6 +69,9 @@ pub struct PumpSwapParams {
     /// Effective PumpSwap fee bps for this pool snapshot. Instruction building reads this
     /// only from params, so hot-path trading never adds an RPC call for fee discovery.
     pub fee_basis_points: PumpSwapFeeBasisPoints,
+    /// Which side pays/settles in a pool where both sides are WSOL/USDC (e.g. a WSOL/USDC
+    /// pool). `None` = the pool's quote mint. Ignored for pools with a single WSOL/USDC side.
+    pub preferred_quote: Option<TradeTokenType>,
 }
 
 impl PumpSwapParams {
@@ -114,6 +124,7 @@ impl PumpSwapParams {
                 crate::instruction::utils::pumpswap::accounts::PROTOCOL_FEE_BASIS_POINTS,
                 creator_fee_basis_points,
             ),
+            preferred_quote: None,
         }
     }
 
@@ -127,6 +138,12 @@ impl PumpSwapParams {
         self
     }
 
+    /// Trade against `quote` in a WSOL/USDC pool (`SOL` and `WSOL` both mean the WSOL side).
+    pub fn with_preferred_quote(mut self, quote: TradeTokenType) -> Self {
+        self.preferred_quote = Some(quote);
+        self
+    }
+
     /// Quote reserves used by PumpSwap pricing and fee-tier selection.
     pub fn effective_quote_reserves(&self) -> Result<u64, anyhow::Error> {
         crate::instruction::utils::pumpswap_types::effective_quote_reserves(
@@ -268,12 +285,165 @@ impl PumpSwapParams {
         Self::from_pool_data(rpc, &pool_address, &pool).await
     }
 
+    /// Load params from the pool account. `coin_creator` is decoded from the pool itself and the
+    /// creator vault authority/ATA derived from it via
+    /// [`coin_creator_vault_accounts`](crate::instruction::utils::pumpswap::coin_creator_vault_accounts),
+    /// so these are the authoritative values [`Self::from_event_verified`] reconciles events to.
     pub async fn from_pool_address_by_rpc(
         rpc: &SolanaRpcClient,
         pool_address: &Pubkey,
-    ) -> Result<Self, anyhow::Error> {
-        let pool_data = crate::instruction::utils::pumpswap::fetch_pool(rpc, pool_address).await?;
-        Self::from_pool_data(rpc, pool_address, &pool_data).await
+    ) -> Result<Self, PoolLoadError> {
+        Self::from_pool_address_by_rpc_at_slot(rpc, pool_address, None).await
+    }
+
+    /// [`Self::from_pool_address_by_rpc`] with pool and reserves read as of at least
+    /// `min_context_slot` (e.g. the slot of a copied trade), bypassing `POOL_CACHE` for the
+    /// read. A node still behind after
+    /// [`min_context_slot_timeout`](super::min_context_slot_timeout) yields
+    /// [`PoolLoadError::MinContextSlotNotReached`]. 跟单时按事件 slot 读取，避免旧储备。
+    pub async fn from_pool_address_by_rpc_at_slot(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+        min_context_slot: Option<u64>,
+    ) -> Result<Self, PoolLoadError> {
+        Self::load_through_cache(rpc, pool_address, min_context_slot, &POOL_CACHE).await
+    }
+
+    /// The `from_pool_address_by_rpc*` body, reading and filling `cache` instead of `POOL_CACHE`.
+    pub(crate) async fn load_through_cache(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+        min_context_slot: Option<u64>,
+        cache: &PoolCache,
+    ) -> Result<Self, PoolLoadError> {
+        if min_context_slot.is_none() {
+            if let Some(DexParamEnum::PumpSwap(cached)) = cache.get(pool_address) {
+                return Ok(cached);
+            }
+        }
+        let account = fetch_pool_account(rpc, pool_address, min_context_slot).await?;
+        check_pool_owner(
+            &account,
+            pool_address,
+            &crate::instruction::utils::pumpswap::accounts::AMM_PROGRAM,
+        )?;
+        let pool_data = crate::instruction::utils::pumpswap::decode_pool_account(&account)
+            .map_err(|reason| PoolLoadError::decode(pool_address, reason))?;
+        let params = Self::load_from_pool_data(rpc, pool_address, &pool_data, min_context_slot)
+            .await
+            .map_err(|e| e.downcast::<PoolLoadError>().unwrap_or_else(PoolLoadError::RpcError))?;
+        cache.insert(*pool_address, DexParamEnum::PumpSwap(params.clone()));
+        Ok(params)
+    }
+
+    /// Re-check event-built params (e.g. from [`Self::from_trade`]) against the on-chain pool.
+    ///
+    /// One `getMultipleAccounts` (pool + both mints) supplies the mints, vaults, creators, flags
+    /// and token programs; the creator vault authority/ATA are re-derived from them. Only the pool
+    /// address, reserves and fee bps are taken from the event. Each corrected field is logged.
+    /// 以链上池子为准重新推导 PDA/ATA，只信任事件中的池子地址与储备量。
+    pub async fn from_event_verified(
+        event_params: PumpSwapParams,
+        rpc: &SolanaRpcClient,
+    ) -> Result<Self, PoolLoadError> {
+        Self::from_event_verified_at_slot(event_params, rpc, None).await
+    }
+
+    /// [`Self::from_event_verified`] with the pool and mints read as of at least
+    /// `min_context_slot` (the event's slot), so a lagging node cannot "correct" the event back
+    /// to older pool state.
+    pub async fn from_event_verified_at_slot(
+        event_params: PumpSwapParams,
+        rpc: &SolanaRpcClient,
+        min_context_slot: Option<u64>,
+    ) -> Result<Self, PoolLoadError> {
+        let pool_address = event_params.pool;
+        let account = fetch_pool_account(rpc, &pool_address, min_context_slot).await?;
+        check_pool_owner(
+            &account,
+            &pool_address,
+            &crate::instruction::utils::pumpswap::accounts::AMM_PROGRAM,
+        )?;
+        let pool = crate::instruction::utils::pumpswap::decode_pool_account(&account)
+            .map_err(|reason| PoolLoadError::decode(&pool_address, reason))?;
+        let mints =
+            fetch_accounts_at_slot(rpc, &[pool.base_mint, pool.quote_mint], min_context_slot)
+                .await?;
+        let base_token_program = mints
+            .first()
+            .and_then(|a| a.as_ref())
+            .map(|a| a.owner)
+            .ok_or(PoolLoadError::MintNotFound { pool: pool_address, mint: pool.base_mint })?;
+        let quote_token_program = mints
+            .get(1)
+            .and_then(|a| a.as_ref())
+            .map(|a| a.owner)
+            .ok_or(PoolLoadError::MintNotFound { pool: pool_address, mint: pool.quote_mint })?;
+        let (verified, mismatches) =
+            event_params.reconcile_with_pool(&pool, base_token_program, quote_token_program);
+        if !mismatches.is_empty() && crate::common::sdk_log::sdk_log_enabled() {
+            println!(
+                "⚠️ PumpSwap event params for pool {} disagree with chain, corrected: {}",
+                pool_address,
+                mismatches.join(", ")
+            );
+        }
+        Ok(verified)
+    }
+
+    /// Overwrite every pool-derived account with values from `pool`; returns corrected field names.
+    /// When the pool gains or loses a `coin_creator` the creator fee bucket is recomputed too
+    /// (global creator bps, or none, plus the event's cashback bps).
+    pub(crate) fn reconcile_with_pool(
+        mut self,
+        pool: &crate::instruction::utils::pumpswap_types::Pool,
+        base_token_program: Pubkey,
+        quote_token_program: Pubkey,
+    ) -> (Self, Vec<&'static str>) {
+        let mut mismatches = Vec::new();
+        macro_rules! reconcile {
+            ($field:ident, $value:expr) => {
+                let value = $value;
+                if self.$field != value {
+                    mismatches.push(stringify!($field));
+                    self.$field = value;
+                }
+            };
+        }
+        reconcile!(base_mint, pool.base_mint);
+        reconcile!(quote_mint, pool.quote_mint);
+        reconcile!(pool_base_token_account, pool.pool_base_token_account);
+        reconcile!(pool_quote_token_account, pool.pool_quote_token_account);
+        reconcile!(base_token_program, base_token_program);
+        reconcile!(quote_token_program, quote_token_program);
+        reconcile!(pool_creator, pool.creator);
+        let had_coin_creator = self.coin_creator != Pubkey::default();
+        reconcile!(coin_creator, pool.coin_creator);
+        let has_coin_creator = self.coin_creator != Pubkey::default();
+        if had_coin_creator != has_coin_creator {
+            let creator_fee_basis_points = if has_coin_creator {
+                crate::instruction::utils::pumpswap::global_fee_basis_points()
+                    .coin_creator_fee_basis_points
+            } else {
+                0
+            };
+            let fee = creator_fee_basis_points.saturating_add(self.cashback_fee_basis_points);
+            if self.fee_basis_points.coin_creator_fee_basis_points != fee {
+                mismatches.push("coin_creator_fee_basis_points");
+                self.fee_basis_points.coin_creator_fee_basis_points = fee;
+            }
+        }
+        reconcile!(is_mayhem_mode, pool.is_mayhem_mode);
+        reconcile!(is_cashback_coin, pool.is_cashback_coin);
+        let (authority, vault_ata) =
+            crate::instruction::utils::pumpswap::coin_creator_vault_accounts(
+                pool.coin_creator,
+                pool.quote_mint,
+                quote_token_program,
+            );
+        reconcile!(coin_creator_vault_authority, authority);
+        reconcile!(coin_creator_vault_ata, vault_ata);
+        (self, mismatches)
     }
 
     /// Build params from an already-decoded Pool, only fetching token balances.
@@ -286,8 +456,21 @@ impl PumpSwapParams {
         pool_address: &Pubkey,
         pool_data: &crate::instruction::utils::pumpswap_types::Pool,
     ) -> Result<Self, anyhow::Error> {
-        let snapshot =
-            crate::instruction::utils::pumpswap::get_pool_rpc_snapshot(pool_data, rpc).await?;
+        Self::load_from_pool_data(rpc, pool_address, pool_data, None).await
+    }
+
+    async fn load_from_pool_data(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+        pool_data: &crate::instruction::utils::pumpswap_types::Pool,
+        min_context_slot: Option<u64>,
+    ) -> Result<Self, anyhow::Error> {
+        let snapshot = crate::instruction::utils::pumpswap::get_pool_rpc_snapshot_at_slot(
+            pool_data,
+            rpc,
+            min_context_slot,
+        )
+        .await?;
         let pool_base_token_reserves = snapshot.base_reserve;
         let pool_quote_token_reserves = snapshot.quote_reserve;
         let effective_quote_token_reserves =
@@ -303,7 +486,9 @@ impl PumpSwapParams {
                 )
             })?;
         let base_mint_supply = Some(snapshot.base_mint_supply);
-        let fee_config = crate::instruction::utils::pumpswap::fetch_fee_config(rpc).await;
+        let fee_config =
+            crate::instruction::utils::pumpswap::fetch_fee_config_at_slot(rpc, min_context_slot)
+                .await;
         let raw_fee_basis_points = crate::instruction::utils::pumpswap::compute_fee_basis_points(
             fee_config.as_ref(),
             pool_data.creator,
@@ -317,14 +502,13 @@ impl PumpSwapParams {
         } else {
             raw_fee_basis_points.coin_creator_fee_basis_points
         };
-        let creator = pool_data.coin_creator;
-        let coin_creator_vault_ata = crate::instruction::utils::pumpswap::coin_creator_vault_ata(
-            creator,
-            pool_data.quote_mint,
-            snapshot.quote_token_program,
-        );
-        let coin_creator_vault_authority =
-            crate::instruction::utils::pumpswap::coin_creator_vault_authority(creator);
+        // Creator vault comes from the pool account's own `coin_creator`, never from an event.
+        let (coin_creator_vault_authority, coin_creator_vault_ata) =
+            crate::instruction::utils::pumpswap::coin_creator_vault_accounts(
+                pool_data.coin_creator,
+                pool_data.quote_mint,
+                snapshot.quote_token_program,
+            );
 
         Ok(Self {
             pool: *pool_address,
@@ -350,6 +534,321 @@ impl PumpSwapParams {
                 raw_fee_basis_points.protocol_fee_basis_points,
                 creator_fee_basis_points,
             ),
+            preferred_quote: None,
         })
     }
+
+    /// Cheap sanity checks run before building instructions. 构建指令前的参数校验。
+    pub fn validate(&self) -> Result<(), TradeError> {
+        if self.pool == Pubkey::default() {
+            return Err(TradeError::invalid_protocol_params("PumpSwap pool address is not set"));
+        }
+        if self.base_mint == self.quote_mint {
+            return Err(TradeError::invalid_protocol_params(format!(
+                "PumpSwap base_mint and quote_mint are identical: {}",
+                self.base_mint
+            )));
+        }
+        if self.pool_base_token_reserves == 0 && self.pool_quote_token_reserves == 0 {
+            return Err(TradeError::invalid_protocol_params(format!(
+                "PumpSwap pool {} has zero base and quote reserves",
+                self.pool
+            )));
+        }
+        Ok(())
+    }
+
+    /// Derive every PDA a trade on this pool touches, for checking seed-constraint failures
+    /// (e.g. ConstraintSeeds) before sending. 发送前核对 PDA 推导。
+    pub fn debug_pdas(&self, payer: Pubkey) -> PumpSwapPdaReport {
+        use crate::instruction::utils::pumpswap::{
+            accounts::{AMM_PROGRAM, FEE_PROGRAM},
+            coin_creator_vault_accounts, get_fee_config_pda, get_global_volume_accumulator_pda,
+            get_pool_v2_pda, get_user_volume_accumulator_pda,
+        };
+        let has_creator = self.coin_creator != Pubkey::default();
+        let (authority, vault_ata) = coin_creator_vault_accounts(
+            self.coin_creator,
+            self.quote_mint,
+            self.quote_token_program,
+        );
+        PumpSwapPdaReport {
+            coin_creator_vault_authority: DerivedPda {
+                address: authority,
+                seeds: format!(
+                    "[\"creator_vault\", coin_creator {}] @ {}",
+                    self.coin_creator, AMM_PROGRAM
+                ),
+                matches_params: has_creator.then(|| authority == self.coin_creator_vault_authority),
+            },
+            coin_creator_vault_ata: DerivedPda {
+                address: vault_ata,
+                seeds: format!(
+                    "ATA(creator_vault {}, quote_mint {}, token_program {})",
+                    authority, self.quote_mint, self.quote_token_program
+                ),
+                matches_params: has_creator.then(|| vault_ata == self.coin_creator_vault_ata),
+            },
+            user_volume_accumulator: DerivedPda {
+                address: get_user_volume_accumulator_pda(&payer).unwrap_or_default(),
+                seeds: format!("[\"user_volume_accumulator\", payer {}] @ {}", payer, AMM_PROGRAM),
+                matches_params: None,
+            },
+            global_volume_accumulator: DerivedPda {
+                address: get_global_volume_accumulator_pda().unwrap_or_default(),
+                seeds: format!("[\"global_volume_accumulator\"] @ {}", AMM_PROGRAM),
+                matches_params: None,
+            },
+            fee_config: DerivedPda {
+                address: get_fee_config_pda().unwrap_or_default(),
+                seeds: format!("[\"fee_config\", {}] @ {}", AMM_PROGRAM, FEE_PROGRAM),
+                matches_params: None,
+            },
+            pool_v2: DerivedPda {
+                address: get_pool_v2_pda(&self.base_mint).unwrap_or_default(),
+                seeds: format!("[\"pool-v2\", base_mint {}] @ {}", self.base_mint, AMM_PROGRAM),
+                matches_params: None,
+            },
+        }
+    }
+}
+
+/// One derived address and the seeds it came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivedPda {
+    pub address: Pubkey,
+    /// Human-readable seeds and owning program.
+    pub seeds: String,
+    /// Whether the value stored in [`PumpSwapParams`] equals the derived one;
+    /// `None` when params carry no such field (or the pool has no coin creator).
+    pub matches_params: Option<bool>,
+}
+
+/// Every PDA a PumpSwap trade derives, from [`PumpSwapParams::debug_pdas`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PumpSwapPdaReport {
+    pub coin_creator_vault_authority: DerivedPda,
+    pub coin_creator_vault_ata: DerivedPda,
+    pub user_volume_accumulator: DerivedPda,
+    pub global_volume_accumulator: DerivedPda,
+    pub fee_config: DerivedPda,
+    pub pool_v2: DerivedPda,
+}
+
+impl PumpSwapPdaReport {
+    pub fn entries(&self) -> [(&'static str, &DerivedPda); 6] {
+        [
+            ("coin_creator_vault_authority", &self.coin_creator_vault_authority),
+            ("coin_creator_vault_ata", &self.coin_creator_vault_ata),
+            ("user_volume_accumulator", &self.user_volume_accumulator),
+            ("global_volume_accumulator", &self.global_volume_accumulator),
+            ("fee_config", &self.fee_config),
+            ("pool_v2", &self.pool_v2),
+        ]
+    }
+
+    /// Names of entries whose params value differs from the derived address.
+    pub fn mismatches(&self) -> Vec<&'static str> {
+        self.entries()
+            .into_iter()
+            .filter(|(_, pda)| pda.matches_params == Some(false))
+            .map(|(name, _)| name)
+            .collect()
+    }
+}
+
+impl std::fmt::Display for PumpSwapPdaReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, pda) in self.entries() {
+            let status = match pda.matches_params {
+                Some(true) => " [ok]",
+                Some(false) => " [MISMATCH]",
+                None => "",
+            };
+            writeln!(f, "{name}: {}{status}\n    seeds: {}", pda.address, pda.seeds)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::utils::pumpswap::{
+        accounts, coin_creator_vault_ata, coin_creator_vault_authority,
+    };
+    use crate::instruction::utils::pumpswap_types::Pool;
+
+    fn pk(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    #[test]
+    fn reconcile_rederives_creator_vault_accounts_and_keeps_event_reserves() {
+        let creator = pk(9);
+        let quote = crate::constants::WSOL_TOKEN_ACCOUNT;
+        let token_program = crate::constants::TOKEN_PROGRAM;
+        let pool = Pool {
+            creator: pk(8),
+            base_mint: pk(2),
+            quote_mint: quote,
+            pool_base_token_account: pk(3),
+            pool_quote_token_account: pk(4),
+            coin_creator: creator,
+            ..Pool::default()
+        };
+        // Event swapped the creator ATA and authority (the classic ConstraintSeeds mistake).
+        let event = PumpSwapParams::new(
+            pk(1),
+            pk(2),
+            quote,
+            pk(3),
+            pk(4),
+            1_000,
+            2_000,
+            0,
+            coin_creator_vault_authority(creator),
+            coin_creator_vault_ata(creator, quote, token_program),
+            token_program,
+            token_program,
+            accounts::PROTOCOL_FEE_RECIPIENT,
+            creator,
+            false,
+            0,
+        )
+        .with_pool_creator(pk(8));
+
+        let (verified, mismatches) = event.reconcile_with_pool(&pool, token_program, token_program);
+        assert_eq!(mismatches, vec!["coin_creator_vault_authority", "coin_creator_vault_ata"]);
+        assert_eq!(verified.coin_creator_vault_authority, coin_creator_vault_authority(creator));
+        assert_eq!(verified.pool_base_token_reserves, 1_000);
+        assert_eq!(verified.pool_quote_token_reserves, 2_000);
+    }
+
+    #[test]
+    fn reconcile_recomputes_the_creator_fee_when_the_pool_creator_flips() {
+        use crate::instruction::utils::pumpswap::global_fee_basis_points;
+        use crate::instruction::utils::pumpswap_types::Pool;
+
+        let token_program = crate::constants::TOKEN_PROGRAM;
+        let pool = Pool {
+            base_mint: pk(2),
+            quote_mint: crate::constants::WSOL_TOKEN_ACCOUNT,
+            pool_base_token_account: pk(3),
+            pool_quote_token_account: pk(4),
+            coin_creator: pk(9),
+            ..Pool::default()
+        };
+        // the event reported no creator, so its creator bucket holds only the cashback bps
+        let event = PumpSwapParams::from_trade(
+            pk(1),
+            pool.base_mint,
+            pool.quote_mint,
+            pool.pool_base_token_account,
+            pool.pool_quote_token_account,
+            1_000,
+            2_000,
+            0,
+            Pubkey::default(),
+            Pubkey::default(),
+            token_program,
+            token_program,
+            accounts::PROTOCOL_FEE_RECIPIENT,
+            Pubkey::default(),
+            true,
+            5,
+        )
+        .with_fee_basis_points(20, 5, 30);
+        assert_eq!(event.fee_basis_points.coin_creator_fee_basis_points, 5);
+
+        let (verified, mismatches) = event.reconcile_with_pool(&pool, token_program, token_program);
+        assert!(mismatches.contains(&"coin_creator_fee_basis_points"));
+        assert_eq!(
+            verified.fee_basis_points.coin_creator_fee_basis_points,
+            global_fee_basis_points().coin_creator_fee_basis_points + 5
+        );
+
+        // and back: a pool without a coin creator charges no creator fee
+        let pool = Pool { coin_creator: Pubkey::default(), ..pool };
+        let (verified, mismatches) =
+            verified.reconcile_with_pool(&pool, token_program, token_program);
+        assert!(mismatches.contains(&"coin_creator_fee_basis_points"));
+        assert_eq!(verified.fee_basis_points.coin_creator_fee_basis_points, 5);
+    }
+
+    #[tokio::test]
+    async fn pool_load_derives_creator_vault_from_the_pools_coin_creator() {
+        use crate::instruction::utils::pumpswap::coin_creator_vault_accounts;
+        use crate::test_fixtures as fx;
+
+        let pool = fx::pumpswap_pool();
+        assert_ne!(pool.coin_creator, pool.creator);
+        let token_program = crate::constants::TOKEN_PROGRAM;
+        let rpc = fx::pumpswap_pool_load_rpc().build();
+
+        let params = PumpSwapParams::from_pool_address_by_rpc(&rpc, &fx::POOL).await.unwrap();
+        let (authority, vault_ata) =
+            coin_creator_vault_accounts(pool.coin_creator, pool.quote_mint, token_program);
+        assert_eq!(params.coin_creator, pool.coin_creator);
+        assert_eq!(params.coin_creator_vault_authority, authority);
+        assert_eq!(params.coin_creator_vault_ata, vault_ata);
+        assert!(params.debug_pdas(pk(7)).mismatches().is_empty());
+
+        // event-built params using the pool `creator` disagree with the authoritative load
+        let event = fx::pumpswap_params_with_pool_creator_vault();
+        assert_ne!(event.coin_creator_vault_authority, params.coin_creator_vault_authority);
+        let (verified, _) = event.reconcile_with_pool(&pool, token_program, token_program);
+        assert_eq!(verified.coin_creator_vault_authority, params.coin_creator_vault_authority);
+        assert_eq!(verified.coin_creator_vault_ata, params.coin_creator_vault_ata);
+    }
+
+    #[test]
+    fn debug_pdas_flags_a_swapped_creator_vault() {
+        use crate::instruction::utils::pumpswap::{
+            get_fee_config_pda, get_user_volume_accumulator_pda,
+        };
+        let creator = pk(9);
+        let quote = crate::constants::WSOL_TOKEN_ACCOUNT;
+        let token_program = crate::constants::TOKEN_PROGRAM;
+        let params = |ata, authority| {
+            PumpSwapParams::new(
+                pk(1),
+                pk(2),
+                quote,
+                pk(3),
+                pk(4),
+                1_000,
+                2_000,
+                0,
+                ata,
+                authority,
+                token_program,
+                token_program,
+                accounts::PROTOCOL_FEE_RECIPIENT,
+                creator,
+                false,
+                0,
+            )
+        };
+        let authority = coin_creator_vault_authority(creator);
+        let ata = coin_creator_vault_ata(creator, quote, token_program);
+
+        let good = params(ata, authority).debug_pdas(pk(7));
+        assert!(good.mismatches().is_empty(), "{good}");
+        assert_eq!(good.coin_creator_vault_authority.matches_params, Some(true));
+        assert_eq!(
+            good.user_volume_accumulator.address,
+            get_user_volume_accumulator_pda(&pk(7)).unwrap()
+        );
+        assert_eq!(good.fee_config.address, get_fee_config_pda().unwrap());
+        assert!(good.to_string().contains("creator_vault"));
+
+        // Event swapped the creator ATA and authority.
+        let swapped = params(authority, ata).debug_pdas(pk(7));
+        assert_eq!(
+            swapped.mismatches(),
+            vec!["coin_creator_vault_authority", "coin_creator_vault_ata"]
+        );
+        assert!(swapped.to_string().contains("[MISMATCH]"));
+    }
 }
@@ -1,4 +1,6 @@
+use super::{check_pool_owner, fetch_pool_account, token_balances_at_slot, PoolLoadError};
 use crate::common::SolanaRpcClient;
+use crate::swqos::common::TradeError;
 use crate::trading::common::get_multi_token_balances;
 use solana_sdk::pubkey::Pubkey;
 
@@ -40,6 +42,8 @@ pub struct RaydiumAmmV4Params {
     pub coin_reserve: u64,
     /// Current pc reserve amount in the pool
     pub pc_reserve: u64,
+    /// On-chain pool status, set by `from_pool_address_by_rpc`; `None` skips the paused check.
+    pub pool_status: Option<u64>,
 }
 
 impl RaydiumAmmV4Params {
@@ -70,6 +74,7 @@ impl RaydiumAmmV4Params {
             serum_vault_signer: Pubkey::default(),
             coin_reserve,
             pc_reserve,
+            pool_status: None,
         }
     }
 
@@ -103,19 +108,54 @@ impl RaydiumAmmV4Params {
     pub async fn from_amm_address_by_rpc(
         rpc: &SolanaRpcClient,
         amm: Pubkey,
-    ) -> Result<Self, anyhow::Error> {
-        let amm_info = crate::instruction::utils::raydium_amm_v4::fetch_amm_info(rpc, amm).await?;
-        let market_state =
-            crate::instruction::utils::raydium_amm_v4::fetch_market_state(rpc, amm_info.market)
-                .await?;
+    ) -> Result<Self, PoolLoadError> {
+        Self::from_amm_address_by_rpc_at_slot(rpc, amm, None).await
+    }
+
+    /// [`Self::from_amm_address_by_rpc`] with AMM, market and vault balances read as of at
+    /// least `min_context_slot`.
+    pub async fn from_amm_address_by_rpc_at_slot(
+        rpc: &SolanaRpcClient,
+        amm: Pubkey,
+        min_context_slot: Option<u64>,
+    ) -> Result<Self, PoolLoadError> {
+        use crate::instruction::utils::raydium_amm_v4_types::{
+            amm_info_decode, market_state_decode,
+        };
+
+        let account = fetch_pool_account(rpc, &amm, min_context_slot).await?;
+        check_pool_owner(
+            &account,
+            &amm,
+            &crate::instruction::utils::raydium_amm_v4::accounts::RAYDIUM_AMM_V4,
+        )?;
+        let amm_info = amm_info_decode(&account.data)
+            .ok_or_else(|| PoolLoadError::decode(&amm, "invalid AmmInfo layout"))?;
+        let market = fetch_pool_account(rpc, &amm_info.market, min_context_slot).await?;
+        let market_state = market_state_decode(&market.data).ok_or_else(|| {
+            PoolLoadError::decode(&amm_info.market, "invalid market state layout")
+        })?;
         let serum_vault_signer =
             crate::instruction::utils::raydium_amm_v4::derive_serum_vault_signer(
                 &amm_info.serum_dex,
                 &amm_info.market,
                 market_state.vault_signer_nonce,
-            )?;
-        let (coin_reserve, pc_reserve) =
-            get_multi_token_balances(rpc, &amm_info.token_coin, &amm_info.token_pc).await?;
+            )
+            .map_err(|e| PoolLoadError::decode(&amm_info.market, e.to_string()))?;
+        let (coin_reserve, pc_reserve) = match min_context_slot {
+            None => get_multi_token_balances(rpc, &amm_info.token_coin, &amm_info.token_pc)
+                .await
+                .map_err(PoolLoadError::RpcError)?,
+            Some(_) => {
+                token_balances_at_slot(
+                    rpc,
+                    &amm_info.token_coin,
+                    &amm_info.token_pc,
+                    min_context_slot,
+                )
+                .await?
+            }
+        };
         Ok(Self {
             amm,
             coin_mint: amm_info.coin_mint,
@@ -134,6 +174,32 @@ impl RaydiumAmmV4Params {
             serum_vault_signer,
             coin_reserve,
             pc_reserve,
+            pool_status: Some(amm_info.status),
         })
     }
+
+    /// Cheap sanity checks run before building instructions.
+    pub fn validate(&self) -> Result<(), TradeError> {
+        if self.amm == Pubkey::default() {
+            return Err(TradeError::invalid_protocol_params("RaydiumAmmV4 amm address is not set"));
+        }
+        if self.coin_mint == self.pc_mint {
+            return Err(TradeError::invalid_protocol_params(format!(
+                "RaydiumAmmV4 coin_mint and pc_mint are identical: {}",
+                self.coin_mint
+            )));
+        }
+        if self.coin_reserve == 0 && self.pc_reserve == 0 {
+            return Err(TradeError::invalid_protocol_params(format!(
+                "RaydiumAmmV4 pool {} has zero coin and pc reserves",
+                self.amm
+            )));
+        }
+        if let Some(status) = self.pool_status {
+            if crate::instruction::utils::raydium_amm_v4::is_swap_disabled(status) {
+                return Err(TradeError::pool_paused("RaydiumAmmV4", &self.amm, status));
+            }
+        }
+        Ok(())
+    }
 }
@@ -1,6 +1,23 @@
+use super::{
+    fetch_pool_account, pool_account_data,
+    pool_cache::{PoolCache, POOL_CACHE},
+    token_balances_at_slot, DexParamEnum, PoolLoadError,
+};
 use crate::common::SolanaRpcClient;
+use crate::swqos::common::TradeError;
 use solana_sdk::pubkey::Pubkey;
 
+/// Swap direction for Raydium CPMM. CPMM 交换方向。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SwapMode {
+    /// `swap_base_input`: spend exactly `input_amount`, slippage bounds the output.
+    #[default]
+    ExactIn,
+    /// `swap_base_output`: receive exactly `fixed_output_amount`; the max input is quoted from
+    /// reserves plus slippage and capped by `input_amount`.
+    ExactOut,
+}
+
 /// RaydiumCpmm protocol specific parameters
 /// Configuration parameters specific to Raydium CPMM trading protocol
 #[derive(Clone)]
@@ -27,6 +44,10 @@ pub struct RaydiumCpmmParams {
     pub quote_token_program: Pubkey,
     /// Observation state account
     pub observation_state: Pubkey,
+    /// Exact-in (default) or exact-out swap
+    pub swap_mode: SwapMode,
+    /// On-chain pool status, set by `from_pool_address_by_rpc`; `None` skips the paused check.
+    pub pool_status: Option<u8>,
 }
 
 impl RaydiumCpmmParams {
@@ -55,24 +76,69 @@ impl RaydiumCpmmParams {
             base_token_program: input_token_program,
             quote_token_program: output_token_program,
             observation_state: observation_state,
+            swap_mode: SwapMode::ExactIn,
+            pool_status: None,
         }
     }
 
     pub async fn from_pool_address_by_rpc(
         rpc: &SolanaRpcClient,
         pool_address: &Pubkey,
-    ) -> Result<Self, anyhow::Error> {
-        let pool =
-            crate::instruction::utils::raydium_cpmm::fetch_pool_state(rpc, pool_address).await?;
-        let (token0_balance, token1_balance) =
-            crate::instruction::utils::raydium_cpmm::get_pool_token_balances(
+    ) -> Result<Self, PoolLoadError> {
+        Self::from_pool_address_by_rpc_at_slot(rpc, pool_address, None).await
+    }
+
+    /// [`Self::from_pool_address_by_rpc`] with pool and vault balances read as of at least
+    /// `min_context_slot`, bypassing `POOL_CACHE` for the read.
+    pub async fn from_pool_address_by_rpc_at_slot(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+        min_context_slot: Option<u64>,
+    ) -> Result<Self, PoolLoadError> {
+        Self::load_through_cache(rpc, pool_address, min_context_slot, &POOL_CACHE).await
+    }
+
+    /// The `from_pool_address_by_rpc*` body, reading and filling `cache` instead of `POOL_CACHE`.
+    pub(crate) async fn load_through_cache(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+        min_context_slot: Option<u64>,
+        cache: &PoolCache,
+    ) -> Result<Self, PoolLoadError> {
+        if min_context_slot.is_none() {
+            if let Some(DexParamEnum::RaydiumCpmm(cached)) = cache.get(pool_address) {
+                return Ok(cached);
+            }
+        }
+        let account = fetch_pool_account(rpc, pool_address, min_context_slot).await?;
+        let data = pool_account_data(
+            &account,
+            pool_address,
+            &crate::instruction::utils::raydium_cpmm::accounts::RAYDIUM_CPMM,
+        )?;
+        let pool = crate::instruction::utils::raydium_cpmm_types::pool_state_decode(data)
+            .ok_or_else(|| PoolLoadError::decode(pool_address, "invalid PoolState layout"))?;
+        // Reserves come from the vaults the pool actually stores rather than ones re-derived
+        // from the mints; `oriented` then puts the WSOL/USDC/USD1 side on quote.
+        let (token0_balance, token1_balance) = match min_context_slot {
+            None => crate::instruction::utils::raydium_cpmm::get_vault_token_balances(
                 rpc,
-                pool_address,
-                &pool.token0_mint,
-                &pool.token1_mint,
+                &pool.token0_vault,
+                &pool.token1_vault,
             )
-            .await?;
-        Ok(Self {
+            .await
+            .map_err(PoolLoadError::RpcError)?,
+            Some(_) => {
+                token_balances_at_slot(
+                    rpc,
+                    &pool.token0_vault,
+                    &pool.token1_vault,
+                    min_context_slot,
+                )
+                .await?
+            }
+        };
+        let params = Self {
             pool_state: *pool_address,
             amm_config: pool.amm_config,
             base_mint: pool.token0_mint,
@@ -84,6 +150,112 @@ impl RaydiumCpmmParams {
             base_token_program: pool.token0_program,
             quote_token_program: pool.token1_program,
             observation_state: pool.observation_key,
-        })
+            swap_mode: SwapMode::ExactIn,
+            pool_status: Some(pool.status),
+        }
+        .oriented();
+        cache.insert(*pool_address, DexParamEnum::RaydiumCpmm(params.clone()));
+        Ok(params)
+    }
+
+    /// Swap base and quote when only `base_mint` is WSOL/USDC/USD1 (the pool stores it as
+    /// token0), so `quote_reserve / base_reserve` is the token's price. Pairs with such a mint on
+    /// neither or both sides keep token0/token1 order; the builders pick direction by mint.
+    /// 报价币在 token0 时交换 base/quote，避免价格倒置。
+    pub fn oriented(self) -> Self {
+        use crate::common::positions::is_quote_mint;
+        if !is_quote_mint(&self.base_mint) || is_quote_mint(&self.quote_mint) {
+            return self;
+        }
+        Self {
+            base_mint: self.quote_mint,
+            quote_mint: self.base_mint,
+            base_reserve: self.quote_reserve,
+            quote_reserve: self.base_reserve,
+            base_vault: self.quote_vault,
+            quote_vault: self.base_vault,
+            base_token_program: self.quote_token_program,
+            quote_token_program: self.base_token_program,
+            ..self
+        }
+    }
+
+    /// Select exact-in or exact-out (`swap_base_output`).
+    pub fn with_swap_mode(mut self, swap_mode: SwapMode) -> Self {
+        self.swap_mode = swap_mode;
+        self
+    }
+
+    /// Cheap sanity checks run before building instructions. A default `pool_state` is derived
+    /// from `amm_config` by the builder, so only one of the two needs to be set.
+    pub fn validate(&self) -> Result<(), TradeError> {
+        if self.pool_state == Pubkey::default() && self.amm_config == Pubkey::default() {
+            return Err(TradeError::invalid_protocol_params(
+                "RaydiumCpmm pool_state and amm_config are both unset",
+            ));
+        }
+        if self.base_mint == self.quote_mint {
+            return Err(TradeError::invalid_protocol_params(format!(
+                "RaydiumCpmm base_mint and quote_mint are identical: {}",
+                self.base_mint
+            )));
+        }
+        if self.base_reserve == 0 && self.quote_reserve == 0 {
+            return Err(TradeError::invalid_protocol_params(
+                "RaydiumCpmm pool has zero base and quote reserves",
+            ));
+        }
+        if let Some(status) = self.pool_status {
+            if crate::instruction::utils::raydium_cpmm::is_swap_disabled(status) {
+                return Err(TradeError::pool_paused(
+                    "RaydiumCpmm",
+                    &self.pool_state,
+                    status as u64,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oriented_moves_the_quote_mint_to_the_quote_side() {
+        let token = Pubkey::new_unique();
+        let inverted = RaydiumCpmmParams::from_trade(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            crate::constants::WSOL_TOKEN_ACCOUNT,
+            token,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            crate::constants::TOKEN_PROGRAM,
+            crate::constants::TOKEN_PROGRAM_2022,
+            Pubkey::new_unique(),
+            5_000_000_000,
+            7_000_000,
+        );
+        let params = inverted.clone().oriented();
+        assert_eq!((params.base_mint, params.quote_mint), (token, inverted.base_mint));
+        assert_eq!((params.base_reserve, params.quote_reserve), (7_000_000, 5_000_000_000));
+        assert_eq!(
+            (params.base_vault, params.quote_vault),
+            (inverted.quote_vault, inverted.base_vault)
+        );
+        assert_eq!(
+            (params.base_token_program, params.quote_token_program),
+            (crate::constants::TOKEN_PROGRAM_2022, crate::constants::TOKEN_PROGRAM)
+        );
+
+        let stable_pair = RaydiumCpmmParams {
+            quote_mint: crate::constants::USDC_TOKEN_ACCOUNT,
+            ..inverted.clone()
+        };
+        assert_eq!(stable_pair.clone().oriented().base_mint, stable_pair.base_mint);
+        let token_pair = RaydiumCpmmParams { base_mint: Pubkey::new_unique(), ..inverted };
+        assert_eq!(token_pair.clone().oriented().base_mint, token_pair.base_mint);
     }
 }
@@ -0,0 +1,206 @@
+//! Pre-built, pre-signed trades that can be broadcast later without any build/sign work.
+//! 预构建并签名的交易，触发时只做广播。
+//!
+//! A prepared trade is signed against a durable nonce, so it does not expire with the
+//! blockhash. The nonce must not be advanced by any other transaction between `prepare` and
+//! `fire`: once it is consumed every prepared transaction becomes permanently invalid.
+//! 准备与触发之间，该 nonce 账户不能被其他交易消耗，否则所有预签名交易失效。
+
+use anyhow::{anyhow, Result};
+use futures::future::join_all;
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction};
+use std::{str::FromStr, sync::Arc};
+
+use crate::{
+    swqos::{SwqosClient, SwqosType, TradeType},
+    trading::{
+        common::build_transaction,
+        core::{
+            async_executor::select_swqos_task_configs,
+            executor::{build_protocol_instructions, finish_swap_instructions},
+            params::SwapParams,
+        },
+        outcome::TradeOutcomeContext,
+        TradeFactory,
+    },
+};
+
+/// Signed transactions for one trade, one per SWQOS route / fee config.
+#[derive(Clone)]
+pub struct PreparedTrade {
+    trade_type: TradeType,
+    transactions: Vec<(SwqosType, VersionedTransaction)>,
+    /// Request fields for the outcome `TradingClient::fire_prepared` reports.
+    outcome_ctx: TradeOutcomeContext,
+}
+
+impl PreparedTrade {
+    /// Build and sign one transaction per SWQOS client in `params.swqos_clients`, using the same
+    /// instructions, tip accounts and gas fee configs as a live `buy`/`sell`, including the
+    /// `receive_native_sol` WSOL close and the `ata_creation` split. Account creation split off
+    /// by `ata_creation` is sent and confirmed here, so only the swap is left for `fire`.
+    /// `params.durable_nonce` is required; `params.simulate` is ignored.
+    pub async fn prepare(mut params: SwapParams) -> Result<Self> {
+        params.simulate = false;
+        params.apply_token_program_overrides();
+        let nonce = params
+            .durable_nonce
+            .as_ref()
+            .filter(|n| n.nonce_account.is_some() && n.current_nonce.is_some());
+        let Some(nonce) = nonce else {
+            return Err(anyhow!("PreparedTrade requires a durable nonce (blockhash would expire)"));
+        };
+        let is_buy = params.is_buy();
+
+        params.validate()?;
+        let dex_type = params.protocol_params.dex_type();
+        let outcome_ctx = TradeOutcomeContext {
+            dex_type,
+            is_buy,
+            mint: if is_buy { params.output_mint } else { params.input_mint },
+            input_amount: params.input_amount.unwrap_or_default(),
+            fixed_output_amount: params.fixed_output_amount,
+            slippage_basis_points: params.slippage_basis_points,
+            wait_tx_confirmed: false,
+            simulate: false,
+            blockhash: nonce.current_nonce,
+        };
+        let protocol_name = dex_type.protocol_name();
+        let builder = TradeFactory::instruction_builder(dex_type);
+        let (instructions, trade_context) =
+            build_protocol_instructions(builder.as_ref(), protocol_name, &params).await?;
+        let (instructions, _) = finish_swap_instructions(&params, instructions).await?;
+
+        let with_tip = if is_buy { true } else { params.with_tip };
+        let trade_type = if is_buy { TradeType::Buy } else { TradeType::Sell };
+        let swqos_types: Vec<SwqosType> =
+            params.swqos_clients.iter().map(|c| c.get_swqos_type()).collect();
+        let gas_fee_configs = params.gas_fee_strategy.get_strategies(trade_type);
+        let task_configs = select_swqos_task_configs(
+            &swqos_types,
+            &gas_fee_configs,
+            with_tip,
+            params.check_min_tip,
+            |swqos_type| {
+                params
+                    .swqos_clients
+                    .iter()
+                    .find(|c| c.get_swqos_type() == swqos_type)
+                    .map(|c| c.min_tip_sol())
+                    .unwrap_or(0.0)
+            },
+        );
+
+        let mut transactions = Vec::with_capacity(task_configs.len());
+        for task in task_configs {
+            let client = &params.swqos_clients[task.swqos_index];
+            let swqos_type = client.get_swqos_type();
+            let tip_account = Pubkey::from_str(&client.get_tip_account()?).unwrap_or_default();
+            let fee = task.gas_fee_config.2;
+            let tx = build_transaction(
+                &params.payer,
+                params.ata_rent_funder.as_ref(),
+                fee.cu_limit,
+                fee.cu_price,
+                params.skip_compute_budget,
+                &instructions,
+                &params.address_lookup_table_accounts,
+                None,
+                params.middleware_manager.as_ref(),
+                protocol_name,
+                is_buy,
+                trade_context.as_ref(),
+                swqos_type != SwqosType::Default,
+                &tip_account,
+                if with_tip { fee.tip } else { 0.0 },
+                Some(nonce),
+            )?;
+            transactions.push((swqos_type, tx));
+        }
+        if transactions.is_empty() {
+            return Err(anyhow!("No SWQOS route produced a prepared transaction"));
+        }
+        Ok(Self { trade_type, transactions, outcome_ctx })
+    }
+
+    /// Signatures of all prepared transactions; at most one of them can land.
+    pub fn signatures(&self) -> Vec<Signature> {
+        self.transactions
+            .iter()
+            .map(|(_, tx)| tx.signatures.first().copied().unwrap_or_default())
+            .collect()
+    }
+
+    pub fn transactions(&self) -> &[(SwqosType, VersionedTransaction)] {
+        &self.transactions
+    }
+
+    pub(crate) fn outcome_context(&self) -> &TradeOutcomeContext {
+        &self.outcome_ctx
+    }
+
+    /// Broadcast each prepared transaction through the client of the same SWQOS type; no build,
+    /// sign or confirmation wait. Returns `(any_accepted, signatures, last_error)`. Reports no
+    /// outcome; `TradingClient::fire_prepared` fires and reports to the client's outcome sinks.
+    pub async fn fire(
+        &self,
+        swqos_clients: &[Arc<SwqosClient>],
+    ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>)> {
+        let sends = self.transactions.iter().filter_map(|(swqos_type, tx)| {
+            let client = swqos_clients.iter().find(|c| c.get_swqos_type() == *swqos_type)?;
+            Some(async move {
+                let sig = tx.signatures.first().copied().unwrap_or_default();
+                (sig, client.send_transaction(self.trade_type, tx, false).await)
+            })
+        });
+        let results = join_all(sends).await;
+        if results.is_empty() {
+            return Err(anyhow!("No SWQOS client matches the prepared routes"));
+        }
+        let mut ok = false;
+        let mut signatures = Vec::with_capacity(results.len());
+        let mut last_error = None;
+        for (sig, res) in results {
+            signatures.push(sig);
+            match res {
+                Ok(()) => ok = true,
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Ok((ok, signatures, last_error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{nonce_cache::DurableNonceInfo, MockSolanaRpcClient};
+    use crate::test_fixtures::{pumpswap_buy_params, RecordingSwqos};
+    use solana_hash::Hash;
+
+    #[tokio::test]
+    async fn prepare_requires_nonce_and_fire_only_broadcasts() {
+        let rpc = MockSolanaRpcClient::new().with_latest_blockhash(Hash::new_unique(), 1_000);
+        let params = pumpswap_buy_params(rpc).await;
+        assert!(PreparedTrade::prepare(params.clone()).await.is_err());
+
+        let nonce_hash = Hash::new_unique();
+        let client = RecordingSwqos::new(SwqosType::Default);
+        let mut params = params;
+        params.swqos_clients = Arc::new(vec![client.clone() as Arc<SwqosClient>]);
+        params.durable_nonce = Some(DurableNonceInfo {
+            nonce_account: Some(Pubkey::new_unique()),
+            current_nonce: Some(nonce_hash),
+        });
+        let prepared = PreparedTrade::prepare(params).await.unwrap();
+        assert_eq!(prepared.transactions().len(), 1);
+        assert_eq!(*prepared.transactions()[0].1.message.recent_blockhash(), nonce_hash);
+
+        let (ok, signatures, err) =
+            prepared.fire(&[client.clone() as Arc<SwqosClient>]).await.unwrap();
+        assert!(ok && err.is_none());
+        assert_eq!(signatures, prepared.signatures());
+        let sent: Vec<Signature> = client.sent().iter().map(|tx| tx.signatures[0]).collect();
+        assert_eq!(sent, signatures);
+    }
+}
@@ -0,0 +1,223 @@
+//! Optional cross-check of event-sourced reserves against on-chain state before building a trade.
+//! 构建交易前，将事件中的储备量与链上储备量比对（可选）。
+//!
+//! gRPC/event flows can hand the SDK stale or zero reserves; pricing and slippage limits are
+//! derived from them, so a large divergence means the trade would be priced against a pool state
+//! that no longer exists. Enable with `TradingClient::with_reserve_check`.
+//!
+//! Meteora DAMM v2 params carry no reserves, so they always pass without an RPC read. PumpFun
+//! and Bonk params built with `immediate_sell` carry all-zero reserves, which mean "unknown" and
+//! also pass; zero reserves from an event for any other protocol still fail.
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::SolanaRpcClient;
+use crate::swqos::common::TradeError;
+use crate::trading::core::params::DexParamEnum;
+
+/// Reserve pair the params were priced with; `None` for protocols without reserves in params.
+/// PumpFun: (virtual token, virtual sol). PumpSwap/CPMM: (base, quote). AmmV4: (coin, pc).
+/// Bonk: (real base, real quote).
+pub fn event_reserves(params: &DexParamEnum) -> Option<(u64, u64)> {
+    match params {
+        DexParamEnum::PumpFun(p) => {
+            Some((p.bonding_curve.virtual_token_reserves, p.bonding_curve.virtual_sol_reserves))
+        }
+        DexParamEnum::PumpSwap(p) => {
+            Some((p.pool_base_token_reserves, p.pool_quote_token_reserves))
+        }
+        DexParamEnum::RaydiumCpmm(p) => Some((p.base_reserve, p.quote_reserve)),
+        DexParamEnum::RaydiumAmmV4(p) => Some((p.coin_reserve, p.pc_reserve)),
+        DexParamEnum::Bonk(p) => {
+            Some((u64::try_from(p.real_base).ok()?, u64::try_from(p.real_quote).ok()?))
+        }
+        DexParamEnum::MeteoraDammV2(_) => None,
+    }
+}
+
+/// Write a reserve pair (same orientation as [`event_reserves`]) back into `params`, e.g. after
+/// [`fetch_onchain_reserves`]. No-op for protocols without reserves in params.
+pub fn set_reserves(params: &mut DexParamEnum, reserves: (u64, u64)) {
+    match params {
+        DexParamEnum::PumpFun(p) => {
+            let curve = std::sync::Arc::make_mut(&mut p.bonding_curve);
+            curve.virtual_token_reserves = reserves.0;
+            curve.virtual_sol_reserves = reserves.1;
+        }
+        DexParamEnum::PumpSwap(p) => {
+            p.pool_base_token_reserves = reserves.0;
+            p.pool_quote_token_reserves = reserves.1;
+        }
+        DexParamEnum::RaydiumCpmm(p) => (p.base_reserve, p.quote_reserve) = reserves,
+        DexParamEnum::RaydiumAmmV4(p) => (p.coin_reserve, p.pc_reserve) = reserves,
+        DexParamEnum::Bonk(p) => {
+            p.real_base = reserves.0 as u128;
+            p.real_quote = reserves.1 as u128;
+        }
+        DexParamEnum::MeteoraDammV2(_) => {}
+    }
+}
+
+/// The side of `reserves` holding `mint` (pairs may list the token second, e.g. a WSOL/token
+/// CPMM pool).
+pub fn token_reserve(params: &DexParamEnum, mint: &Pubkey, reserves: (u64, u64)) -> u64 {
+    let token_first = match params {
+        DexParamEnum::PumpSwap(p) => p.base_mint == *mint,
+        DexParamEnum::RaydiumCpmm(p) => p.base_mint == *mint,
+        DexParamEnum::RaydiumAmmV4(p) => p.coin_mint == *mint,
+        _ => true,
+    };
+    if token_first {
+        reserves.0
+    } else {
+        reserves.1
+    }
+}
+
+/// Fetch the same reserve pair from chain. `mint` is the traded token (used to derive default
+/// pool addresses the same way the builders do).
+pub async fn fetch_onchain_reserves(
+    rpc: &SolanaRpcClient,
+    params: &DexParamEnum,
+    mint: &Pubkey,
+) -> Result<Option<(u64, u64)>, anyhow::Error> {
+    use crate::trading::common::get_multi_token_balances;
+
+    let reserves = match params {
+        DexParamEnum::PumpFun(_) => {
+            let (curve, _) =
+                crate::instruction::utils::pumpfun::fetch_bonding_curve_account(rpc, mint).await?;
+            (curve.virtual_token_reserves, curve.virtual_sol_reserves)
+        }
+        DexParamEnum::PumpSwap(p) => {
+            get_multi_token_balances(rpc, &p.pool_base_token_account, &p.pool_quote_token_account)
+                .await?
+        }
+        DexParamEnum::RaydiumCpmm(p) => {
+            use crate::instruction::utils::raydium_cpmm::{get_pool_pda, get_vault_account};
+            let pool_state = if p.pool_state == Pubkey::default() {
+                get_pool_pda(&p.amm_config, &p.base_mint, &p.quote_mint)
+                    .ok_or_else(|| anyhow::anyhow!("RaydiumCpmm pool PDA derivation failed"))?
+            } else {
+                p.pool_state
+            };
+            get_multi_token_balances(
+                rpc,
+                &get_vault_account(&pool_state, &p.base_mint, p)?,
+                &get_vault_account(&pool_state, &p.quote_mint, p)?,
+            )
+            .await?
+        }
+        DexParamEnum::RaydiumAmmV4(p) => {
+            get_multi_token_balances(rpc, &p.token_coin, &p.token_pc).await?
+        }
+        DexParamEnum::Bonk(p) => {
+            use crate::instruction::utils::bonk::{accounts, fetch_pool_state, get_pool_pda};
+            let pool_state = if p.pool_state == Pubkey::default() {
+                let quote = if p.global_config == accounts::USD1_GLOBAL_CONFIG {
+                    crate::constants::USD1_TOKEN_ACCOUNT
+                } else {
+                    crate::constants::WSOL_TOKEN_ACCOUNT
+                };
+                get_pool_pda(mint, &quote)
+                    .ok_or_else(|| anyhow::anyhow!("Bonk pool PDA derivation failed"))?
+            } else {
+                p.pool_state
+            };
+            let pool = fetch_pool_state(rpc, &pool_state).await?;
+            (pool.real_base, pool.real_quote)
+        }
+        DexParamEnum::MeteoraDammV2(_) => return Ok(None),
+    };
+    Ok(Some(reserves))
+}
+
+/// True when `a` and `b` differ by more than `tolerance_bps` of the larger value.
+#[inline]
+pub fn diverges(a: u64, b: u64, tolerance_bps: u64) -> bool {
+    let (hi, lo) = if a >= b { (a, b) } else { (b, a) };
+    ((hi - lo) as u128) * 10_000 > (hi as u128) * (tolerance_bps as u128)
+}
+
+/// Compare event reserves with on-chain reserves; `Err(TradeError::RESERVE_MISMATCH)` when either
+/// side of the pair diverges beyond `tolerance_bps`. Protocols without reserves (Meteora DAMM v2)
+/// and `immediate_sell` params (see [`reserves_unknown`]) pass without fetching.
+pub async fn verify_reserves(
+    rpc: &SolanaRpcClient,
+    params: &DexParamEnum,
+    mint: &Pubkey,
+    tolerance_bps: u64,
+) -> Result<(), anyhow::Error> {
+    let Some(event) = event_reserves(params) else {
+        return Ok(());
+    };
+    if reserves_unknown(params, event) {
+        return Ok(());
+    }
+    let Some(onchain) = fetch_onchain_reserves(rpc, params, mint).await? else {
+        return Ok(());
+    };
+    check_reserves(event, onchain, tolerance_bps)?;
+    Ok(())
+}
+
+/// True for the all-zero reserves of PumpFun/Bonk `immediate_sell` params: the caller never had
+/// the curve, so there is nothing to compare.
+#[inline]
+pub fn reserves_unknown(params: &DexParamEnum, event: (u64, u64)) -> bool {
+    event == (0, 0) && matches!(params, DexParamEnum::PumpFun(_) | DexParamEnum::Bonk(_))
+}
+
+#[inline]
+pub fn check_reserves(
+    event: (u64, u64),
+    onchain: (u64, u64),
+    tolerance_bps: u64,
+) -> Result<(), TradeError> {
+    if diverges(event.0, onchain.0, tolerance_bps) || diverges(event.1, onchain.1, tolerance_bps) {
+        return Err(TradeError::reserve_mismatch(event, onchain, tolerance_bps));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserves_within_tolerance_pass_and_stale_or_zero_reserves_fail() {
+        assert!(check_reserves((1_000_000, 2_000_000), (1_000_000, 2_000_000), 0).is_ok());
+        assert!(check_reserves((1_000_000, 2_000_000), (1_010_000, 2_000_000), 100).is_ok());
+
+        let err = check_reserves((1_000_000, 2_000_000), (1_020_000, 2_000_000), 100).unwrap_err();
+        assert_eq!(err.code, TradeError::RESERVE_MISMATCH);
+        assert!(err.message.contains("1020000"));
+        assert!(check_reserves((0, 0), (1_000_000, 2_000_000), 500).is_err());
+    }
+
+    #[tokio::test]
+    async fn immediate_sell_reserves_are_unknown_and_skip_the_check() {
+        use crate::common::mock_rpc::MockSolanaRpcClient;
+        use crate::trading::core::params::{BonkParams, PumpFunParams};
+
+        // no curve or pool queued: the default mock has no such account, so a fetch would fail
+        let rpc = MockSolanaRpcClient::new().build();
+        let mint = Pubkey::new_unique();
+        let pumpfun = DexParamEnum::PumpFun(PumpFunParams::immediate_sell(
+            Pubkey::new_unique(),
+            crate::constants::TOKEN_PROGRAM,
+            false,
+        ));
+        assert!(verify_reserves(&rpc, &pumpfun, &mint, 0).await.is_ok());
+        let bonk = DexParamEnum::Bonk(BonkParams::immediate_sell(
+            crate::constants::TOKEN_PROGRAM,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ));
+        assert!(verify_reserves(&rpc, &bonk, &mint, 0).await.is_ok());
+
+        assert!(!reserves_unknown(&pumpfun, (1, 0)));
+    }
+}
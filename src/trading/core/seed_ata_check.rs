@@ -0,0 +1,110 @@
+//! Optional pre-sell check that the seed-derived token account exists (enable with
+//! `TradingClient::with_seed_ata_check`). 卖出前检查 seed 派生的代币账户。
+//!
+//! With `use_seed_optimize` the SDK sells from the account derived by `create_with_seed` rather
+//! than the standard ATA. Tokens bought without seed optimization (or with another tool) sit in
+//! the standard ATA, so such a sell fails with a generic "account not found". When the seed
+//! account is missing but the standard ATA exists, this check fails the sell up front with
+//! [`TradeError::SEED_ATA_MISMATCH`] instead.
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::fast_fn::{
+    get_associated_token_address_with_program_id_fast,
+    get_associated_token_address_with_program_id_fast_use_seed,
+};
+use crate::common::SolanaRpcClient;
+use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+use crate::swqos::common::TradeError;
+
+/// `(seed account, standard ATA)` for `owner`/`mint`; one pair per candidate token program
+/// (both programs when `token_program` is unknown).
+fn candidates(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: Option<Pubkey>,
+) -> Vec<(Pubkey, Pubkey)> {
+    let programs = match token_program {
+        Some(program) => vec![program],
+        None => vec![TOKEN_PROGRAM, TOKEN_PROGRAM_2022],
+    };
+    programs
+        .iter()
+        .map(|program| {
+            (
+                get_associated_token_address_with_program_id_fast_use_seed(
+                    owner, mint, program, true,
+                ),
+                get_associated_token_address_with_program_id_fast(owner, mint, program),
+            )
+        })
+        .filter(|(seed, standard)| seed != standard)
+        .collect()
+}
+
+/// Fail with [`TradeError::SEED_ATA_MISMATCH`] when `owner`'s seed-derived account for `mint` is
+/// missing but the standard ATA exists. One `getMultipleAccounts` call; a no-op for mints that
+/// never use a seed account (SOL/WSOL, unknown token programs).
+pub async fn check_seed_ata(
+    rpc: &SolanaRpcClient,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: Option<Pubkey>,
+) -> Result<(), anyhow::Error> {
+    let pairs = candidates(owner, mint, token_program);
+    if pairs.is_empty() {
+        return Ok(());
+    }
+    let keys: Vec<Pubkey> = pairs.iter().flat_map(|(seed, standard)| [*seed, *standard]).collect();
+    let accounts = rpc.get_multiple_accounts(&keys).await?;
+    let exists = |key: &Pubkey| {
+        keys.iter()
+            .position(|k| k == key)
+            .and_then(|i| accounts.get(i))
+            .is_some_and(Option::is_some)
+    };
+    if pairs.iter().any(|(seed, _)| exists(seed)) {
+        return Ok(());
+    }
+    match pairs.iter().find(|(_, standard)| exists(standard)) {
+        Some((seed, standard)) => Err(TradeError::seed_ata_mismatch(mint, seed, standard).into()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::mock_rpc::{mock_account, MockSolanaRpcClient};
+
+    #[tokio::test]
+    async fn standard_ata_without_seed_account_is_a_seed_ata_mismatch() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_account = mock_account(TOKEN_PROGRAM, vec![0; 165]);
+
+        // seed account missing, standard ATA present
+        let rpc = MockSolanaRpcClient::new()
+            .with_multiple_accounts(&[None, Some(&token_account)])
+            .build();
+        let err = check_seed_ata(&rpc, &owner, &mint, Some(TOKEN_PROGRAM)).await.unwrap_err();
+        let err = err.downcast_ref::<TradeError>().unwrap();
+        assert_eq!(err.code, TradeError::SEED_ATA_MISMATCH);
+        let (seed, standard) = candidates(&owner, &mint, Some(TOKEN_PROGRAM))[0];
+        assert!(err.message.contains(&seed.to_string()));
+        assert!(err.message.contains(&standard.to_string()));
+
+        // seed account present, or neither: leave it to the trade
+        let rpc = MockSolanaRpcClient::new()
+            .with_multiple_accounts(&[Some(&token_account), Some(&token_account)])
+            .with_multiple_accounts(&[None, None])
+            .build();
+        assert!(check_seed_ata(&rpc, &owner, &mint, Some(TOKEN_PROGRAM)).await.is_ok());
+        assert!(check_seed_ata(&rpc, &owner, &mint, Some(TOKEN_PROGRAM)).await.is_ok());
+
+        // WSOL never uses a seed account: no RPC call at all
+        let rpc = MockSolanaRpcClient::new().build();
+        let wsol = crate::constants::WSOL_TOKEN_ACCOUNT;
+        assert!(check_seed_ata(&rpc, &owner, &wsol, None).await.is_ok());
+    }
+}
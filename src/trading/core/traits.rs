@@ -1,7 +1,8 @@
 use crate::common::SwqosSubmitTiming;
+use crate::swqos::common::TradeError;
 use crate::trading::SwapParams;
 use anyhow::Result;
-use solana_sdk::{instruction::Instruction, signature::Signature};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signature, signer::Signer};
 /// 交易执行器trait - 定义了所有交易协议都需要实现的核心方法
 #[async_trait::async_trait]
 pub trait TradeExecutor: Send + Sync {
@@ -15,6 +16,21 @@ pub trait TradeExecutor: Send + Sync {
     ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>)>;
     /// 获取协议名称
     fn protocol_name(&self) -> &'static str;
+
+    /// Accounts this trade would touch, for building an address lookup table.
+    /// 列出交易涉及的账户（用于创建 ALT）。
+    async fn accounts_used(&self, _params: &SwapParams) -> Result<Vec<Pubkey>> {
+        Err(anyhow::anyhow!("{} executor does not expose accounts_used", self.protocol_name()))
+    }
+
+    /// Token-account creations this trade would emit (ATA, seed accounts, WSOL wrap), for fee
+    /// budgeting and `max_ata_creations`. 预估 ATA 创建数量。
+    async fn planned_ata_creations(&self, _params: &SwapParams) -> Result<usize> {
+        Err(anyhow::anyhow!(
+            "{} executor does not expose planned_ata_creations",
+            self.protocol_name()
+        ))
+    }
 }
 
 /// 指令构建器trait - 负责构建协议特定的交易指令
@@ -25,4 +41,81 @@ pub trait InstructionBuilder: Send + Sync {
 
     /// 构建卖出指令
     async fn build_sell_instructions(&self, params: &SwapParams) -> Result<Vec<Instruction>>;
+
+    /// Dry-run the build for the trade's direction ([`SwapParams::is_buy`]) and list every
+    /// account key (programs included, payer excluded since signers cannot come from a lookup
+    /// table), deduplicated in first-seen order. Call once with buy params and once with sell
+    /// params to cover both directions.
+    /// 构建一次指令并收集全部账户，供 ALT 自动填充。
+    async fn accounts_used(&self, params: &SwapParams) -> Result<Vec<Pubkey>> {
+        let instructions = if params.is_buy() {
+            self.build_buy_instructions(params).await?
+        } else {
+            self.build_sell_instructions(params).await?
+        };
+        Ok(lookup_table_accounts(&instructions, &params.payer.pubkey()))
+    }
+
+    /// Dry-run the build for the trade's direction ([`SwapParams::is_buy`]) and count the
+    /// token-account creations it emits (see [`count_ata_creations`]). 预估交易会创建的代币账户数量。
+    async fn planned_ata_creations(&self, params: &SwapParams) -> Result<usize> {
+        let instructions = if params.is_buy() {
+            self.build_buy_instructions(params).await?
+        } else {
+            self.build_sell_instructions(params).await?
+        };
+        Ok(count_ata_creations(&instructions))
+    }
+}
+
+/// Whether `ix` creates a user token account: an associated-token-account create, or the
+/// `create_account_with_seed` (seed optimization) that precedes `initialize_account3`.
+pub(crate) fn is_ata_creation(ix: &Instruction) -> bool {
+    if ix.program_id == crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID {
+        return true;
+    }
+    if ix.program_id == crate::constants::SYSTEM_PROGRAM {
+        // SystemInstruction::CreateAccountWithSeed
+        return ix.data.get(..4) == Some(&3u32.to_le_bytes()[..]);
+    }
+    false
+}
+
+/// Token accounts (ATA or seed-derived, WSOL included) `instructions` would create.
+pub fn count_ata_creations(instructions: &[Instruction]) -> usize {
+    instructions.iter().filter(|ix| is_ata_creation(ix)).count()
+}
+
+/// `Err(TOO_MANY_ATA_CREATIONS)` when `instructions` create more than `max` token accounts.
+pub(crate) fn check_ata_creation_cap(
+    instructions: &[Instruction],
+    max: Option<usize>,
+) -> Result<(), TradeError> {
+    match max {
+        Some(max) => {
+            let planned = count_ata_creations(instructions);
+            if planned > max {
+                return Err(TradeError::too_many_ata_creations(planned, max));
+            }
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
+
+/// Unique account keys and program ids of `instructions`, excluding `payer`.
+pub fn lookup_table_accounts(instructions: &[Instruction], payer: &Pubkey) -> Vec<Pubkey> {
+    let mut keys: Vec<Pubkey> = Vec::new();
+    let mut push = |key: &Pubkey| {
+        if key != payer && !keys.contains(key) {
+            keys.push(*key);
+        }
+    };
+    for ix in instructions {
+        push(&ix.program_id);
+        for meta in &ix.accounts {
+            push(&meta.pubkey);
+        }
+    }
+    keys
 }
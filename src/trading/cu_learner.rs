@@ -0,0 +1,249 @@
+//! Compute-unit learner: records the units each protocol/direction actually consumed (from
+//! simulations and confirmed trades) and suggests a `cu_limit` at a configurable percentile.
+//! 按协议与买卖方向统计实际 CU 消耗，按分位数给出建议的 cu_limit。
+//!
+//! Confirmed trades cost one extra `getTransaction` per landing (fetched in the background, off
+//! the trade path); simulations report units directly.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+
+use crate::common::{GasFeeStrategy, SolanaRpcClient};
+use crate::swqos::TradeType;
+use crate::trading::factory::DexType;
+
+/// Upper bound of a transaction's compute budget.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Learner policy. 学习策略。
+#[derive(Debug, Clone, Copy)]
+pub struct CuLearnerConfig {
+    /// Recent samples kept per (protocol, direction).
+    pub window: usize,
+    /// Samples required before a limit is suggested.
+    pub min_samples: usize,
+    /// Percentile of the window used as the base, in `0.0..=1.0` (nearest-rank).
+    pub percentile: f64,
+    /// Headroom added on top of the percentile, in basis points (`1_000` = +10%).
+    pub headroom_bps: u32,
+    /// Use the suggestion for routes whose `cu_limit` is 0 (unset). Off by default.
+    pub auto_apply: bool,
+}
+
+impl Default for CuLearnerConfig {
+    fn default() -> Self {
+        Self {
+            window: 100,
+            min_samples: 5,
+            percentile: 0.95,
+            headroom_bps: 1_000,
+            auto_apply: false,
+        }
+    }
+}
+
+impl CuLearnerConfig {
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples.max(1);
+        self
+    }
+
+    pub fn with_percentile(mut self, percentile: f64) -> Self {
+        self.percentile = percentile.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_headroom_bps(mut self, headroom_bps: u32) -> Self {
+        self.headroom_bps = headroom_bps;
+        self
+    }
+
+    pub fn with_auto_apply(mut self, auto_apply: bool) -> Self {
+        self.auto_apply = auto_apply;
+        self
+    }
+}
+
+/// Rolling per-protocol compute-unit samples. Keys are executor protocol names
+/// ([`DexType::protocol_name`]) so the executor can record without a `DexType` at hand.
+pub struct CuLearner {
+    config: CuLearnerConfig,
+    samples: Mutex<HashMap<(&'static str, bool), VecDeque<u32>>>,
+}
+
+impl CuLearner {
+    pub fn new(config: CuLearnerConfig) -> Self {
+        Self { config, samples: Mutex::new(HashMap::new()) }
+    }
+
+    #[inline]
+    pub fn config(&self) -> &CuLearnerConfig {
+        &self.config
+    }
+
+    /// Record units consumed by one `dex` trade. 记录一次实际消耗。
+    pub fn record(&self, dex: DexType, is_buy: bool, units_consumed: u64) {
+        self.record_protocol(dex.protocol_name(), is_buy, units_consumed);
+    }
+
+    pub(crate) fn record_protocol(
+        &self,
+        protocol: &'static str,
+        is_buy: bool,
+        units_consumed: u64,
+    ) {
+        if units_consumed == 0 {
+            return;
+        }
+        let units = units_consumed.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32;
+        let mut samples = self.samples.lock();
+        let window = samples.entry((protocol, is_buy)).or_default();
+        if window.len() >= self.config.window.max(1) {
+            window.pop_front();
+        }
+        window.push_back(units);
+    }
+
+    /// Suggested `cu_limit` for `dex` buys/sells: the configured percentile of recent samples
+    /// plus headroom, capped at [`MAX_COMPUTE_UNIT_LIMIT`]. `None` until `min_samples` are seen.
+    pub fn suggested_limit(&self, dex: DexType, is_buy: bool) -> Option<u32> {
+        self.suggested_limit_for(dex.protocol_name(), is_buy)
+    }
+
+    pub(crate) fn suggested_limit_for(&self, protocol: &'static str, is_buy: bool) -> Option<u32> {
+        let mut sorted: Vec<u32> = {
+            let samples = self.samples.lock();
+            let window = samples.get(&(protocol, is_buy))?;
+            if window.len() < self.config.min_samples.max(1) {
+                return None;
+            }
+            window.iter().copied().collect()
+        };
+        sorted.sort_unstable();
+        let rank = (self.config.percentile * sorted.len() as f64).ceil() as usize;
+        let base = sorted[rank.clamp(1, sorted.len()) - 1] as u64;
+        let limit = base * (10_000 + self.config.headroom_bps as u64) / 10_000;
+        Some(limit.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32)
+    }
+
+    /// With `auto_apply`, a copy of `strategy` whose unset `cu_limit`s use the suggestion.
+    pub(crate) fn apply(
+        &self,
+        strategy: &GasFeeStrategy,
+        protocol: &'static str,
+        is_buy: bool,
+    ) -> Option<GasFeeStrategy> {
+        if !self.config.auto_apply {
+            return None;
+        }
+        let limit = self.suggested_limit_for(protocol, is_buy)?;
+        let trade_type = if is_buy { TradeType::Buy } else { TradeType::Sell };
+        strategy.fill_unset_cu_limit(trade_type, limit)
+    }
+
+    /// Samples currently held for `dex` buys/sells.
+    pub fn sample_count(&self, dex: DexType, is_buy: bool) -> usize {
+        self.samples.lock().get(&(dex.protocol_name(), is_buy)).map_or(0, VecDeque::len)
+    }
+
+    /// Fetch the landed transaction in the background and record its `computeUnitsConsumed`.
+    pub(crate) fn record_landed(
+        self: &Arc<Self>,
+        rpc: Arc<SolanaRpcClient>,
+        signature: Signature,
+        protocol: &'static str,
+        is_buy: bool,
+    ) {
+        let learner = self.clone();
+        tokio::spawn(async move {
+            let config = crate::trading::outcome::landed_transaction_config(
+                solana_transaction_status::UiTransactionEncoding::Base64,
+            );
+            let Ok(tx) = rpc.get_transaction_with_config(&signature, config).await else {
+                return;
+            };
+            if let Some(meta) = tx.transaction.meta.filter(|m| m.err.is_none()) {
+                if let OptionSerializer::Some(units) = meta.compute_units_consumed {
+                    learner.record_protocol(protocol, is_buy, units);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_percentile_plus_headroom_per_direction() {
+        let learner = CuLearner::new(
+            CuLearnerConfig::default().with_window(10).with_min_samples(3).with_percentile(0.9),
+        );
+        learner.record(DexType::PumpSwap, true, 80_000);
+        learner.record(DexType::PumpSwap, true, 90_000);
+        assert_eq!(learner.suggested_limit(DexType::PumpSwap, true), None);
+
+        for units in [100_000, 60_000, 70_000, 75_000, 85_000, 95_000, 65_000, 72_000] {
+            learner.record(DexType::PumpSwap, true, units);
+        }
+        // 90th percentile of 10 samples is the 9th smallest (95_000), +10% headroom
+        assert_eq!(learner.suggested_limit(DexType::PumpSwap, true), Some(104_500));
+        assert_eq!(learner.suggested_limit(DexType::PumpSwap, false), None);
+
+        // window evicts the oldest samples
+        learner.record(DexType::PumpSwap, true, 50_000);
+        assert_eq!(learner.sample_count(DexType::PumpSwap, true), 10);
+
+        learner.record(DexType::PumpFun, false, 2_000_000);
+        learner.record(DexType::PumpFun, false, 2_000_000);
+        learner.record(DexType::PumpFun, false, 2_000_000);
+        assert_eq!(learner.suggested_limit(DexType::PumpFun, false), Some(MAX_COMPUTE_UNIT_LIMIT));
+    }
+
+    #[test]
+    fn auto_apply_fills_only_unset_limits() {
+        use crate::common::GasFeeStrategyType;
+        use crate::swqos::SwqosType;
+
+        let strategy = GasFeeStrategy::new();
+        strategy.set(SwqosType::Jito, TradeType::Buy, GasFeeStrategyType::Normal, 0, 1_000, 0.001);
+        strategy.set(
+            SwqosType::Default,
+            TradeType::Buy,
+            GasFeeStrategyType::Normal,
+            150_000,
+            1_000,
+            0.0,
+        );
+        let learner = CuLearner::new(CuLearnerConfig::default().with_min_samples(1));
+        learner.record(DexType::Bonk, true, 100_000);
+        let protocol = DexType::Bonk.protocol_name();
+        assert!(learner.apply(&strategy, protocol, true).is_none(), "auto_apply is opt-in");
+
+        let learner =
+            CuLearner::new(CuLearnerConfig::default().with_min_samples(1).with_auto_apply(true));
+        learner.record(DexType::Bonk, true, 100_000);
+        let filled = learner.apply(&strategy, protocol, true).expect("jito limit unset");
+        let limit = |s: &GasFeeStrategy, swqos| {
+            s.get_strategies(TradeType::Buy)
+                .iter()
+                .find(|(t, _, _)| *t == swqos)
+                .unwrap()
+                .2
+                .cu_limit
+        };
+        assert_eq!(limit(&filled, SwqosType::Jito), 110_000);
+        assert_eq!(limit(&filled, SwqosType::Default), 150_000);
+        assert_eq!(limit(&strategy, SwqosType::Jito), 0, "shared strategy untouched");
+    }
+}
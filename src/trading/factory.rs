@@ -1,15 +1,21 @@
 use std::sync::Arc;
 
+use solana_sdk::pubkey::Pubkey;
+
+use crate::instruction::utils;
 use crate::instruction::{
     bonk::BonkInstructionBuilder, meteora_damm_v2::MeteoraDammV2InstructionBuilder,
     pumpfun::PumpFunInstructionBuilder, pumpswap::PumpSwapInstructionBuilder,
     raydium_amm_v4::RaydiumAmmV4InstructionBuilder, raydium_cpmm::RaydiumCpmmInstructionBuilder,
 };
 
-use super::core::{executor::GenericTradeExecutor, traits::TradeExecutor};
+use super::core::{
+    executor::GenericTradeExecutor,
+    traits::{InstructionBuilder, TradeExecutor},
+};
 
 /// 支持的交易协议
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DexType {
     PumpFun,
     PumpSwap,
@@ -19,10 +25,145 @@ pub enum DexType {
     MeteoraDammV2,
 }
 
+impl DexType {
+    /// Canonical lowercase name, also what `Display` prints and `FromStr` accepts.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            DexType::PumpFun => "pumpfun",
+            DexType::PumpSwap => "pumpswap",
+            DexType::Bonk => "bonk",
+            DexType::RaydiumCpmm => "raydium_cpmm",
+            DexType::RaydiumAmmV4 => "raydium_amm_v4",
+            DexType::MeteoraDammV2 => "meteora_damm_v2",
+        }
+    }
+
+    /// Name the executor reports to middleware and logs (`"PumpFun"`, `"RaydiumCpmm"`, ...).
+    pub const fn protocol_name(&self) -> &'static str {
+        match self {
+            DexType::PumpFun => "PumpFun",
+            DexType::PumpSwap => "PumpSwap",
+            DexType::Bonk => "Bonk",
+            DexType::RaydiumCpmm => "RaydiumCpmm",
+            DexType::RaydiumAmmV4 => "RaydiumAmmV4",
+            DexType::MeteoraDammV2 => "MeteoraDammV2",
+        }
+    }
+
+    /// On-chain program the SDK builds this protocol's instructions against.
+    /// 当前编译所用的协议程序 ID。
+    pub const fn program_id(&self) -> Pubkey {
+        match self {
+            DexType::PumpFun => utils::pumpfun::accounts::PUMPFUN,
+            DexType::PumpSwap => utils::pumpswap::accounts::AMM_PROGRAM,
+            DexType::Bonk => utils::bonk::accounts::BONK,
+            DexType::RaydiumCpmm => utils::raydium_cpmm::accounts::RAYDIUM_CPMM,
+            DexType::RaydiumAmmV4 => utils::raydium_amm_v4::accounts::RAYDIUM_AMM_V4,
+            DexType::MeteoraDammV2 => utils::meteora_damm_v2::accounts::METEORA_DAMM_V2,
+        }
+    }
+
+    /// Protocol whose program owns an account (reverse of [`DexType::program_id`]).
+    /// 按账户 owner 识别协议。
+    pub fn from_program_id(program_id: &Pubkey) -> Option<DexType> {
+        TradeFactory::supported_dex_types()
+            .iter()
+            .copied()
+            .find(|dex| dex.program_id() == *program_id)
+    }
+}
+
+impl std::fmt::Display for DexType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Case-insensitive; `-`, `_`, spaces and dots are ignored, so `Raydium-CPMM`, `raydium_cpmm` and
+/// `RaydiumCpmm` all parse. Common aliases (`cpmm`, `raydium_v4`, `letsbonk`, `damm_v2`, ...) are
+/// accepted too. 解析协议名（忽略大小写与分隔符，支持常见别名）。
+impl std::str::FromStr for DexType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' ' | '.'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        let dex_type = match normalized.as_str() {
+            "pumpfun" | "pump" => DexType::PumpFun,
+            "pumpswap" | "pumpamm" => DexType::PumpSwap,
+            "bonk" | "letsbonk" | "raydiumlaunchpad" | "launchpad" => DexType::Bonk,
+            "raydiumcpmm" | "cpmm" => DexType::RaydiumCpmm,
+            "raydiumammv4" | "raydiumamm" | "raydiumv4" | "ammv4" => DexType::RaydiumAmmV4,
+            "meteoradammv2" | "dammv2" | "meteoradamm" => DexType::MeteoraDammV2,
+            _ => {
+                let valid: Vec<&str> = SUPPORTED_DEX_TYPES.iter().map(|d| d.as_str()).collect();
+                return Err(anyhow::anyhow!(
+                    "Unknown DEX type {:?}; expected one of: {}",
+                    s,
+                    valid.join(", ")
+                ));
+            }
+        };
+        Ok(dex_type)
+    }
+}
+
+/// 协议能力描述，用于 UI/集成方只展示有效选项。
+/// Quote flags mirror what `TradingClient::buy`/`sell` accept for this protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DexCapabilities {
+    pub dex_type: DexType,
+    /// Native SOL as input/output (wrapped/unwrapped by the SDK).
+    pub supports_sol: bool,
+    pub supports_wsol: bool,
+    pub supports_usdc: bool,
+    pub supports_usd1: bool,
+    /// Honors `use_seed_optimize` when deriving user token accounts.
+    pub supports_seed_optimize: bool,
+    /// Accepts `fixed_output_token_amount` and quotes the opposing limit from reserves. Meteora
+    /// DAMM v2 params carry no pool price, so it takes `MeteoraDammV2Params::minimum_amount_out`.
+    pub supports_fixed_output: bool,
+}
+
+const SUPPORTED_DEX_TYPES: [DexType; 6] = [
+    DexType::PumpFun,
+    DexType::PumpSwap,
+    DexType::Bonk,
+    DexType::RaydiumCpmm,
+    DexType::RaydiumAmmV4,
+    DexType::MeteoraDammV2,
+];
+
 /// 交易工厂 - 用于创建不同协议的交易执行器
 pub struct TradeFactory;
 
 impl TradeFactory {
+    /// 所有已支持的协议
+    pub fn supported_dex_types() -> &'static [DexType] {
+        &SUPPORTED_DEX_TYPES
+    }
+
+    /// 查询协议支持的 quote 资产与特性
+    pub fn capabilities(dex_type: DexType) -> DexCapabilities {
+        let (supports_usdc, supports_usd1) = match dex_type {
+            // Bonk pools are quoted in SOL or USD1 only
+            DexType::Bonk => (false, true),
+            _ => (true, false),
+        };
+        DexCapabilities {
+            dex_type,
+            supports_sol: true,
+            supports_wsol: true,
+            supports_usdc,
+            supports_usd1,
+            supports_seed_optimize: true,
+            supports_fixed_output: dex_type != DexType::MeteoraDammV2,
+        }
+    }
+
     /// 创建指定协议的交易执行器（零开销单例）
     pub fn create_executor(dex_type: DexType) -> Arc<dyn TradeExecutor> {
         match dex_type {
@@ -35,13 +176,29 @@ impl TradeFactory {
         }
     }
 
+    /// The instruction builder behind `dex_type`'s executor, for paths that build without
+    /// executing (e.g. `PreparedTrade`).
+    pub(crate) fn instruction_builder(dex_type: DexType) -> Arc<dyn InstructionBuilder> {
+        match dex_type {
+            DexType::PumpFun => Arc::new(PumpFunInstructionBuilder),
+            DexType::PumpSwap => Arc::new(PumpSwapInstructionBuilder),
+            DexType::Bonk => Arc::new(BonkInstructionBuilder),
+            DexType::RaydiumCpmm => Arc::new(RaydiumCpmmInstructionBuilder),
+            DexType::RaydiumAmmV4 => Arc::new(RaydiumAmmV4InstructionBuilder),
+            DexType::MeteoraDammV2 => Arc::new(MeteoraDammV2InstructionBuilder),
+        }
+    }
+
     // Static instances created at compile time - zero runtime overhead
     #[inline]
     fn pumpfun_executor() -> Arc<dyn TradeExecutor> {
         static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
             std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(PumpFunInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "PumpFun"))
+                let instruction_builder = Self::instruction_builder(DexType::PumpFun);
+                Arc::new(GenericTradeExecutor::new(
+                    instruction_builder,
+                    DexType::PumpFun.protocol_name(),
+                ))
             });
         INSTANCE.clone()
     }
@@ -50,8 +207,11 @@ impl TradeFactory {
     fn pumpswap_executor() -> Arc<dyn TradeExecutor> {
         static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
             std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(PumpSwapInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "PumpSwap"))
+                let instruction_builder = Self::instruction_builder(DexType::PumpSwap);
+                Arc::new(GenericTradeExecutor::new(
+                    instruction_builder,
+                    DexType::PumpSwap.protocol_name(),
+                ))
             });
         INSTANCE.clone()
     }
@@ -60,8 +220,11 @@ impl TradeFactory {
     fn bonk_executor() -> Arc<dyn TradeExecutor> {
         static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
             std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(BonkInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "Bonk"))
+                let instruction_builder = Self::instruction_builder(DexType::Bonk);
+                Arc::new(GenericTradeExecutor::new(
+                    instruction_builder,
+                    DexType::Bonk.protocol_name(),
+                ))
             });
         INSTANCE.clone()
     }
@@ -70,8 +233,11 @@ impl TradeFactory {
     fn raydium_cpmm_executor() -> Arc<dyn TradeExecutor> {
         static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
             std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(RaydiumCpmmInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "RaydiumCpmm"))
+                let instruction_builder = Self::instruction_builder(DexType::RaydiumCpmm);
+                Arc::new(GenericTradeExecutor::new(
+                    instruction_builder,
+                    DexType::RaydiumCpmm.protocol_name(),
+                ))
             });
         INSTANCE.clone()
     }
@@ -80,8 +246,11 @@ impl TradeFactory {
     fn raydium_amm_v4_executor() -> Arc<dyn TradeExecutor> {
         static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
             std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(RaydiumAmmV4InstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "RaydiumAmmV4"))
+                let instruction_builder = Self::instruction_builder(DexType::RaydiumAmmV4);
+                Arc::new(GenericTradeExecutor::new(
+                    instruction_builder,
+                    DexType::RaydiumAmmV4.protocol_name(),
+                ))
             });
         INSTANCE.clone()
     }
@@ -90,9 +259,57 @@ impl TradeFactory {
     fn meteora_damm_v2_executor() -> Arc<dyn TradeExecutor> {
         static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
             std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(MeteoraDammV2InstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "MeteoraDammV2"))
+                let instruction_builder = Self::instruction_builder(DexType::MeteoraDammV2);
+                Arc::new(GenericTradeExecutor::new(
+                    instruction_builder,
+                    DexType::MeteoraDammV2.protocol_name(),
+                ))
             });
         INSTANCE.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usd1_is_only_advertised_for_bonk() {
+        for dex_type in TradeFactory::supported_dex_types() {
+            let caps = TradeFactory::capabilities(*dex_type);
+            assert_eq!(caps.dex_type, *dex_type);
+            assert_eq!(caps.supports_usd1, *dex_type == DexType::Bonk);
+            assert_eq!(caps.supports_usdc, *dex_type != DexType::Bonk);
+        }
+        assert_eq!(TradeFactory::supported_dex_types().len(), 6);
+    }
+
+    #[test]
+    fn dex_type_round_trips_through_display_and_accepts_aliases() {
+        for dex_type in TradeFactory::supported_dex_types() {
+            assert_eq!(dex_type.to_string().parse::<DexType>().unwrap(), *dex_type);
+        }
+        assert_eq!("Raydium-CPMM".parse::<DexType>().unwrap(), DexType::RaydiumCpmm);
+        assert_eq!("raydium_v4".parse::<DexType>().unwrap(), DexType::RaydiumAmmV4);
+        assert_eq!("PumpSwap".parse::<DexType>().unwrap(), DexType::PumpSwap);
+        assert_eq!("letsbonk".parse::<DexType>().unwrap(), DexType::Bonk);
+
+        let err = "orca".parse::<DexType>().unwrap_err().to_string();
+        assert!(err.contains("orca") && err.contains("meteora_damm_v2"));
+    }
+
+    #[test]
+    fn program_ids_are_distinct_per_protocol() {
+        let ids: std::collections::HashSet<Pubkey> =
+            TradeFactory::supported_dex_types().iter().map(DexType::program_id).collect();
+        assert_eq!(ids.len(), TradeFactory::supported_dex_types().len());
+        assert_eq!(
+            DexType::PumpFun.program_id().to_string(),
+            "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P"
+        );
+        for dex_type in TradeFactory::supported_dex_types() {
+            assert_eq!(DexType::from_program_id(&dex_type.program_id()), Some(*dex_type));
+        }
+        assert_eq!(DexType::from_program_id(&Pubkey::new_unique()), None);
+    }
+}
@@ -1,7 +1,20 @@
+use crate::instruction::decode_pumpswap_ix_data;
+use crate::instruction::utils::pumpswap::accounts::AMM_PROGRAM;
 use crate::trading::middleware::traits::InstructionMiddleware;
 use anyhow::Result;
 use solana_sdk::instruction::Instruction;
 
+/// Print one instruction; PumpSwap swap data is additionally decoded into readable amounts.
+fn log_instruction(index: usize, instruction: &Instruction) {
+    println!("Instruction {}:", index + 1);
+    if instruction.program_id == AMM_PROGRAM {
+        if let Ok(args) = decode_pumpswap_ix_data(&instruction.data) {
+            println!("PumpSwap {}", args);
+        }
+    }
+    println!("{:?}\n", instruction);
+}
+
 /// Logging middleware - Records instruction information
 #[derive(Clone)]
 pub struct LoggingMiddleware;
@@ -23,8 +36,7 @@ impl InstructionMiddleware for LoggingMiddleware {
         println!("[{}] Protocol name: {}\n", self.name(), protocol_name);
         println!("[{}] Is buy: {}", self.name(), is_buy);
         for (i, instruction) in protocol_instructions.iter().enumerate() {
-            println!("Instruction {}:", i + 1);
-            println!("{:?}\n", instruction);
+            log_instruction(i, instruction);
         }
         Ok(protocol_instructions)
     }
@@ -41,8 +53,7 @@ impl InstructionMiddleware for LoggingMiddleware {
         println!("[{}] Protocol name: {}\n", self.name(), protocol_name);
         println!("[{}] Is buy: {}", self.name(), is_buy);
         for (i, instruction) in full_instructions.iter().enumerate() {
-            println!("Instruction {}:", i + 1);
-            println!("{:?}\n", instruction);
+            log_instruction(i, instruction);
         }
         Ok(full_instructions)
     }
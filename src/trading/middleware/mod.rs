@@ -1,4 +1,4 @@
 pub mod builtin;
 pub mod traits;
 
-pub use traits::{InstructionMiddleware, MiddlewareManager};
+pub use traits::{InstructionMiddleware, MiddlewareManager, TradeContext};
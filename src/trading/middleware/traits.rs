@@ -1,5 +1,47 @@
 use anyhow::Result;
-use solana_sdk::instruction::Instruction;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signer::Signer};
+
+use crate::trading::core::params::SwapParams;
+use crate::trading::factory::DexType;
+
+/// What a middleware sees about the trade being built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradeContext {
+    pub protocol_name: &'static str,
+    pub dex_type: DexType,
+    pub is_buy: bool,
+    pub payer: Pubkey,
+    /// The traded token: bought on buys, sold on sells.
+    pub mint: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    /// Buy: quote spent (max quote for exact-out). Sell: tokens sold.
+    pub input_amount: Option<u64>,
+    /// Exact (or minimum) output when the trade fixes one.
+    pub output_amount: Option<u64>,
+    pub pool: Option<Pubkey>,
+}
+
+impl TradeContext {
+    pub fn from_swap_params(
+        params: &SwapParams,
+        protocol_name: &'static str,
+        is_buy: bool,
+    ) -> Self {
+        Self {
+            protocol_name,
+            dex_type: params.protocol_params.dex_type(),
+            is_buy,
+            payer: params.payer.pubkey(),
+            mint: if is_buy { params.output_mint } else { params.input_mint },
+            input_mint: params.input_mint,
+            output_mint: params.output_mint,
+            input_amount: params.input_amount,
+            output_amount: params.fixed_output_amount,
+            pool: params.protocol_params.pool_address(),
+        }
+    }
+}
 
 /// Instruction middleware trait
 ///
@@ -40,6 +82,30 @@ pub trait InstructionMiddleware: Send + Sync {
         is_buy: bool,
     ) -> Result<Vec<Instruction>>;
 
+    /// Like [`Self::process_protocol_instructions`], with the trade's mint, amounts and pool.
+    /// Defaults to the context-free method, so existing middleware keeps working.
+    fn process_protocol_instructions_with_context(
+        &self,
+        protocol_instructions: Vec<Instruction>,
+        context: &TradeContext,
+    ) -> Result<Vec<Instruction>> {
+        self.process_protocol_instructions(
+            protocol_instructions,
+            context.protocol_name,
+            context.is_buy,
+        )
+    }
+
+    /// Like [`Self::process_full_instructions`], with the trade's mint, amounts and pool.
+    /// Defaults to the context-free method.
+    fn process_full_instructions_with_context(
+        &self,
+        full_instructions: Vec<Instruction>,
+        context: &TradeContext,
+    ) -> Result<Vec<Instruction>> {
+        self.process_full_instructions(full_instructions, context.protocol_name, context.is_buy)
+    }
+
     /// Clone middleware
     fn clone_box(&self) -> Box<dyn InstructionMiddleware>;
 }
@@ -105,6 +171,38 @@ impl MiddlewareManager {
         Ok(protocol_instructions)
     }
 
+    /// Apply all middlewares to full_instructions with the trade context.
+    pub fn apply_middlewares_process_full_instructions_with_context(
+        &self,
+        mut full_instructions: Vec<Instruction>,
+        context: &TradeContext,
+    ) -> Result<Vec<Instruction>> {
+        for middleware in &self.middlewares {
+            full_instructions =
+                middleware.process_full_instructions_with_context(full_instructions, context)?;
+            if full_instructions.is_empty() {
+                break;
+            }
+        }
+        Ok(full_instructions)
+    }
+
+    /// Apply all middlewares to protocol_instructions with the trade context.
+    pub fn apply_middlewares_process_protocol_instructions_with_context(
+        &self,
+        mut protocol_instructions: Vec<Instruction>,
+        context: &TradeContext,
+    ) -> Result<Vec<Instruction>> {
+        for middleware in &self.middlewares {
+            protocol_instructions = middleware
+                .process_protocol_instructions_with_context(protocol_instructions, context)?;
+            if protocol_instructions.is_empty() {
+                break;
+            }
+        }
+        Ok(protocol_instructions)
+    }
+
     /// Create manager with common middlewares
     pub fn with_common_middlewares() -> Self {
         Self::new().add_middleware(Box::new(crate::trading::middleware::builtin::LoggingMiddleware))
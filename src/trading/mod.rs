@@ -1,9 +1,26 @@
 pub mod common;
+pub mod copy_trade;
 pub mod core;
+pub mod cu_learner;
 pub mod factory;
 pub mod middleware;
+pub mod outcome;
+pub mod preview;
+pub mod simulation;
+pub mod slippage;
+pub(crate) mod sweep;
+pub mod webhook;
 
+pub use common::protocol_overrides::{set_protocol_overrides, ProtocolOverride, ProtocolOverrides};
+pub use copy_trade::{CopyTradeEvent, DecodedTrade};
 pub use core::params::SwapParams;
+pub use core::prepared::PreparedTrade;
 pub use core::traits::InstructionBuilder;
-pub use factory::TradeFactory;
-pub use middleware::{InstructionMiddleware, MiddlewareManager};
+pub use cu_learner::{CuLearner, CuLearnerConfig};
+pub use factory::{DexCapabilities, TradeFactory};
+pub use middleware::{InstructionMiddleware, MiddlewareManager, TradeContext};
+pub use outcome::{TradeFill, TradeOutcome, TradeOutcomeBroadcast, TradeOutcomeSink};
+pub use preview::{BalanceImpact, DepthEstimate};
+pub use simulation::{AccountDelta, AccountState, SimulationReport};
+pub use slippage::{FixedSlippage, SlippageContext, SlippageModel, TwapDeviationSlippage};
+pub use webhook::WebhookSink;
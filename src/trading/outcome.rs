@@ -0,0 +1,461 @@
+//! Per-trade outcome record and sinks that receive it after every `buy`/`sell`.
+//! 每笔交易的结果记录，以及在 buy/sell 结束后接收结果的 sink。
+
+use serde::Serialize;
+use solana_hash::Hash;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionStatusMeta};
+
+use crate::constants::{USD1_TOKEN_ACCOUNT, USDC_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+use crate::swqos::common::TradeError;
+use crate::trading::factory::DexType;
+
+/// Serializable summary of one `buy`/`sell` call. 单笔交易结果（可序列化）。
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeOutcome {
+    /// DEX protocol, e.g. `"PumpSwap"`.
+    pub protocol: String,
+    /// `"buy"` or `"sell"`.
+    pub side: &'static str,
+    pub mint: String,
+    /// Input amount requested (quote for buys, tokens for sells).
+    pub input_amount: u64,
+    pub fixed_output_amount: Option<u64>,
+    pub slippage_basis_points: Option<u64>,
+    /// All submitted signatures (base58).
+    pub signatures: Vec<String>,
+    /// `Some(true)` when a signature was confirmed, `Some(false)` when confirmation failed,
+    /// `None` when the caller did not wait for confirmation or the trade was simulated.
+    pub landed: Option<bool>,
+    pub success: bool,
+    pub simulated: bool,
+    pub error_code: Option<u32>,
+    pub error: Option<String>,
+    /// Unix time in milliseconds when the outcome was recorded.
+    pub timestamp_ms: i64,
+    /// Amounts actually moved, filled by `TradingClient::enrich_outcome` after landing.
+    pub fill: Option<TradeFill>,
+    /// Network fee actually charged (base + priority, lamports; tips are transfers, not fees),
+    /// read from the landed transaction's meta when the caller waited for confirmation.
+    pub fee_lamports: Option<u64>,
+    /// `computeUnitsConsumed` of the landed transaction (same source as `fee_lamports`).
+    pub units_consumed: Option<u64>,
+    /// `true` when every SWQOS route failed and the transaction went out through the plain-RPC
+    /// fallback (`TradingClient::with_rpc_fallback`); `false` for the normal SWQOS path.
+    pub rpc_fallback: bool,
+    /// Blockhash the transaction was signed with (the nonce value for durable-nonce trades),
+    /// after any refresh by `TradingClient::with_blockhash_refresh`.
+    pub blockhash: Option<String>,
+}
+
+/// What the landed transaction actually moved for the payer, read from its balance changes.
+/// 链上实际成交数量（来自交易前后余额变化）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TradeFill {
+    pub token_amount: u64,
+    pub token_decimals: u8,
+    /// Quote spent (buy) or received (sell). For native SOL this is the payer's lamport change
+    /// net of the network fee, so tips and ATA rent in the same transaction are included.
+    pub quote_amount: u64,
+    pub quote_decimals: u8,
+    /// `"SOL"`, `"USDC"`, `"USD1"`, or the abbreviated quote mint.
+    pub quote_symbol: String,
+}
+
+impl TradeOutcome {
+    /// One-line human summary, e.g. `Bought 1,234.56 Ab12…xY9z for 0.1 SOL @ 0.000081 SOL`.
+    /// Without a [`TradeFill`] only the requested raw input and the status are shown.
+    pub fn summary(&self) -> String {
+        let token = abbreviate(&self.mint);
+        let Some(fill) = &self.fill else {
+            let status = match (&self.error, self.landed) {
+                (Some(err), _) => format!("failed: {}", err),
+                (None, Some(true)) => "landed".to_string(),
+                _ if self.simulated => "simulated".to_string(),
+                _ => "submitted".to_string(),
+            };
+            return format!(
+                "{} {} {} input {} (raw) {}",
+                self.protocol, self.side, token, self.input_amount, status
+            );
+        };
+        let verb = if self.side == "buy" { "Bought" } else { "Sold" };
+        let tokens = fill.token_amount as f64 / 10f64.powi(fill.token_decimals as i32);
+        let quote = fill.quote_amount as f64 / 10f64.powi(fill.quote_decimals as i32);
+        let price = if tokens > 0.0 { quote / tokens } else { 0.0 };
+        format!(
+            "{} {} {} for {} {} @ {} {}",
+            verb,
+            format_amount(fill.token_amount, fill.token_decimals),
+            token,
+            format_amount(fill.quote_amount, fill.quote_decimals),
+            fill.quote_symbol,
+            format_price(price),
+            fill.quote_symbol
+        )
+    }
+}
+
+fn abbreviate(mint: &str) -> String {
+    if mint.len() <= 10 {
+        return mint.to_string();
+    }
+    format!("{}…{}", &mint[..4], &mint[mint.len() - 4..])
+}
+
+/// Base units → decimal string with thousands separators and trailing zeros trimmed. Decimals
+/// beyond what `u128` can scale by (> 38, never a real mint) print the raw amount.
+fn format_amount(raw: u64, decimals: u8) -> String {
+    let Some(scale) = 10u128.checked_pow(decimals as u32) else {
+        return raw.to_string();
+    };
+    let (whole, frac) = (raw as u128 / scale, raw as u128 % scale);
+    let digits = whole.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    if frac == 0 {
+        return grouped;
+    }
+    let frac = format!("{:0width$}", frac, width = decimals as usize);
+    format!("{}.{}", grouped, frac.trim_end_matches('0'))
+}
+
+/// Price with four significant digits, trailing zeros trimmed.
+fn format_price(price: f64) -> String {
+    if price <= 0.0 || !price.is_finite() {
+        return "0".to_string();
+    }
+    let decimals = (3 - price.log10().floor() as i32).max(0) as usize;
+    let s = format!("{:.*}", decimals, price);
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        s
+    }
+}
+
+fn quote_symbol(mint: &str) -> String {
+    if mint == WSOL_TOKEN_ACCOUNT.to_string() {
+        "SOL".to_string()
+    } else if mint == USDC_TOKEN_ACCOUNT.to_string() {
+        "USDC".to_string()
+    } else if mint == USD1_TOKEN_ACCOUNT.to_string() {
+        "USD1".to_string()
+    } else {
+        abbreviate(mint)
+    }
+}
+
+/// Build a fill from the payer's per-mint token balance changes `(mint, pre, post, decimals)` and
+/// its lamport change net of the network fee. The quote side is the largest non-`mint` token
+/// change, falling back to native SOL when no quote token account moved (e.g. wrapped and closed).
+fn fill_from_changes(
+    changes: &[(String, u64, u64, u8)],
+    sol_change: i128,
+    mint: &str,
+) -> Option<TradeFill> {
+    let (_, pre, post, token_decimals) = changes.iter().find(|c| c.0 == mint)?;
+    let token_amount = pre.abs_diff(*post);
+    if token_amount == 0 {
+        return None;
+    }
+    let quote =
+        changes.iter().filter(|c| c.0 != mint && c.1 != c.2).max_by_key(|c| c.1.abs_diff(c.2));
+    let (quote_amount, quote_decimals, quote_symbol) = match quote {
+        Some((quote_mint, pre, post, decimals)) => {
+            (pre.abs_diff(*post), *decimals, self::quote_symbol(quote_mint))
+        }
+        None => (u64::try_from(sol_change.unsigned_abs()).ok()?, 9, "SOL".to_string()),
+    };
+    Some(TradeFill {
+        token_amount,
+        token_decimals: *token_decimals,
+        quote_amount,
+        quote_decimals,
+        quote_symbol,
+    })
+}
+
+/// Read the payer's fill for `mint` out of a landed transaction's status meta. The payer is the
+/// fee payer, i.e. account index 0.
+pub(crate) fn fill_from_meta(
+    meta: &UiTransactionStatusMeta,
+    payer: &Pubkey,
+    mint: &Pubkey,
+) -> Option<TradeFill> {
+    let payer = payer.to_string();
+    let mut changes: Vec<(String, u64, u64, u8)> = Vec::new();
+    let balances = |b: &OptionSerializer<Vec<_>>| match b {
+        OptionSerializer::Some(v) => v.clone(),
+        _ => Vec::new(),
+    };
+    for (is_post, list) in
+        [(false, balances(&meta.pre_token_balances)), (true, balances(&meta.post_token_balances))]
+    {
+        for b in list {
+            if !matches!(&b.owner, OptionSerializer::Some(o) if *o == payer) {
+                continue;
+            }
+            let amount = b.ui_token_amount.amount.parse::<u64>().unwrap_or(0);
+            let idx = match changes.iter().position(|c| c.0 == b.mint) {
+                Some(i) => i,
+                None => {
+                    changes.push((b.mint.clone(), 0, 0, b.ui_token_amount.decimals));
+                    changes.len() - 1
+                }
+            };
+            if is_post {
+                changes[idx].2 += amount;
+            } else {
+                changes[idx].1 += amount;
+            }
+        }
+    }
+    let sol_change = match (meta.pre_balances.first(), meta.post_balances.first()) {
+        (Some(pre), Some(post)) => *post as i128 - *pre as i128 + meta.fee as i128,
+        _ => 0,
+    };
+    fill_from_changes(&changes, sol_change, &mint.to_string())
+}
+
+/// `getTransaction` config for reading a landed transaction's meta (v0 included, `confirmed`).
+pub(crate) fn landed_transaction_config(
+    encoding: solana_transaction_status::UiTransactionEncoding,
+) -> solana_client::rpc_config::RpcTransactionConfig {
+    solana_client::rpc_config::RpcTransactionConfig {
+        encoding: Some(encoding),
+        max_supported_transaction_version: Some(0),
+        commitment: Some(solana_commitment_config::CommitmentConfig::confirmed()),
+    }
+}
+
+/// Actual `(fee_lamports, units_consumed)` from a landed transaction's status meta.
+pub(crate) fn cost_from_meta(meta: &UiTransactionStatusMeta) -> (u64, Option<u64>) {
+    let units = match meta.compute_units_consumed {
+        OptionSerializer::Some(units) => Some(units),
+        _ => None,
+    };
+    (meta.fee, units)
+}
+
+/// Request-side fields captured before the params are moved into the executor.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TradeOutcomeContext {
+    pub dex_type: DexType,
+    pub is_buy: bool,
+    pub mint: Pubkey,
+    pub input_amount: u64,
+    pub fixed_output_amount: Option<u64>,
+    pub slippage_basis_points: Option<u64>,
+    pub wait_tx_confirmed: bool,
+    pub simulate: bool,
+    pub blockhash: Option<Hash>,
+}
+
+impl TradeOutcomeContext {
+    pub(crate) fn outcome(
+        &self,
+        success: bool,
+        signatures: &[Signature],
+        error: Option<&TradeError>,
+    ) -> TradeOutcome {
+        TradeOutcome {
+            protocol: format!("{:?}", self.dex_type),
+            side: if self.is_buy { "buy" } else { "sell" },
+            mint: self.mint.to_string(),
+            input_amount: self.input_amount,
+            fixed_output_amount: self.fixed_output_amount,
+            slippage_basis_points: self.slippage_basis_points,
+            signatures: signatures.iter().map(|s| s.to_string()).collect(),
+            landed: (self.wait_tx_confirmed && !self.simulate).then_some(success),
+            success,
+            simulated: self.simulate,
+            error_code: error.map(|e| e.code),
+            error: error.map(|e| e.message.clone()),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            fill: None,
+            fee_lamports: None,
+            units_consumed: None,
+            rpc_fallback: false,
+            blockhash: self.blockhash.map(|h| h.to_string()),
+        }
+    }
+}
+
+/// Receives every [`TradeOutcome`]; called on the trade path, so implementations must not block.
+/// Attach with `TradingClient::with_outcome_sink`.
+pub trait TradeOutcomeSink: Send + Sync {
+    fn on_outcome(&self, outcome: &TradeOutcome);
+}
+
+/// Outcomes kept for slow subscribers before the oldest are dropped.
+pub const TRADE_OUTCOME_CHANNEL_CAPACITY: usize = 1_024;
+
+/// Bounded broadcast of every [`TradeOutcome`] (`TradingClient::subscribe_trades`). Clones share
+/// one channel. A receiver more than [`TRADE_OUTCOME_CHANNEL_CAPACITY`] outcomes behind gets
+/// `RecvError::Lagged(n)` once, then resumes from the oldest outcome still buffered; sending
+/// never waits for receivers. 交易结果广播（有界，慢订阅者会丢最旧的）。
+#[derive(Clone)]
+pub struct TradeOutcomeBroadcast {
+    sender: tokio::sync::broadcast::Sender<TradeOutcome>,
+}
+
+impl TradeOutcomeBroadcast {
+    pub fn new(capacity: usize) -> Self {
+        Self { sender: tokio::sync::broadcast::channel(capacity.max(1)).0 }
+    }
+
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<TradeOutcome> {
+        self.sender.subscribe()
+    }
+
+    /// Whether anyone is listening (publishing is skipped otherwise).
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    pub fn publish(&self, outcome: &TradeOutcome) {
+        if self.has_subscribers() {
+            let _ = self.sender.send(outcome.clone());
+        }
+    }
+}
+
+impl Default for TradeOutcomeBroadcast {
+    fn default() -> Self {
+        Self::new(TRADE_OUTCOME_CHANNEL_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outcome_serializes_landed_status_only_when_confirmed() {
+        let mut ctx = TradeOutcomeContext {
+            dex_type: DexType::PumpSwap,
+            is_buy: true,
+            mint: Pubkey::new_unique(),
+            input_amount: 100_000,
+            fixed_output_amount: None,
+            slippage_basis_points: Some(100),
+            wait_tx_confirmed: true,
+            simulate: false,
+            blockhash: None,
+        };
+        let sig = Signature::new_unique();
+        let json = serde_json::to_value(ctx.outcome(true, &[sig], None)).unwrap();
+        assert_eq!(json["protocol"], "PumpSwap");
+        assert_eq!(json["side"], "buy");
+        assert_eq!(json["landed"], true);
+        assert_eq!(json["signatures"][0], sig.to_string());
+
+        ctx.wait_tx_confirmed = false;
+        let err = TradeError::new(6004, "slippage");
+        let json = serde_json::to_value(ctx.outcome(false, &[], Some(&err))).unwrap();
+        assert!(json["landed"].is_null());
+        assert_eq!(json["error_code"], 6004);
+    }
+
+    #[test]
+    fn broadcast_is_bounded_and_reports_lag() {
+        use tokio::sync::broadcast::error::TryRecvError;
+
+        let ctx = TradeOutcomeContext {
+            dex_type: DexType::PumpSwap,
+            is_buy: false,
+            mint: Pubkey::new_unique(),
+            input_amount: 0,
+            fixed_output_amount: None,
+            slippage_basis_points: None,
+            wait_tx_confirmed: false,
+            simulate: false,
+            blockhash: None,
+        };
+        let bus = TradeOutcomeBroadcast::new(2);
+        assert!(!bus.has_subscribers());
+        bus.publish(&ctx.outcome(true, &[], None)); // nobody listening: dropped
+
+        let mut rx = bus.clone().subscribe();
+        for input_amount in 1..=3 {
+            let mut outcome = ctx.outcome(true, &[], None);
+            outcome.input_amount = input_amount;
+            bus.publish(&outcome);
+        }
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Lagged(1))));
+        assert_eq!(rx.try_recv().unwrap().input_amount, 2);
+        assert_eq!(rx.try_recv().unwrap().input_amount, 3);
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn summary_formats_fill_from_balance_changes() {
+        let mint = Pubkey::new_unique();
+        let ctx = TradeOutcomeContext {
+            dex_type: DexType::PumpSwap,
+            is_buy: true,
+            mint,
+            input_amount: 100_000_000,
+            fixed_output_amount: None,
+            slippage_basis_points: Some(100),
+            wait_tx_confirmed: true,
+            simulate: false,
+            blockhash: None,
+        };
+        let mut outcome = ctx.outcome(true, &[Signature::new_unique()], None);
+        assert!(outcome.summary().ends_with("input 100000000 (raw) landed"));
+
+        // WSOL wrapped and closed in the same tx: quote falls back to the lamport change.
+        let changes = vec![(mint.to_string(), 0, 1_234_560_000, 6)];
+        outcome.fill = fill_from_changes(&changes, -100_000_000, &mint.to_string());
+        let token = abbreviate(&mint.to_string());
+        assert_eq!(
+            outcome.summary(),
+            format!("Bought 1,234.56 {} for 0.1 SOL @ 0.000081 SOL", token)
+        );
+
+        let usdc = USDC_TOKEN_ACCOUNT.to_string();
+        let changes = vec![(mint.to_string(), 5_000_000, 0, 6), (usdc, 0, 2_500_000, 6)];
+        let fill = fill_from_changes(&changes, -5_000, &mint.to_string()).unwrap();
+        assert_eq!((fill.quote_amount, fill.quote_symbol.as_str()), (2_500_000, "USDC"));
+        assert!(fill_from_changes(&[], 0, &mint.to_string()).is_none());
+    }
+
+    #[test]
+    fn format_amount_falls_back_to_raw_for_unscalable_decimals() {
+        assert_eq!(format_amount(1_234_560_000, 6), "1,234.56");
+        assert_eq!(format_amount(5, 38), format!("0.{}5", "0".repeat(37)));
+        assert_eq!(format_amount(1_234_560_000, 39), "1234560000");
+        assert_eq!(format_amount(u64::MAX, u8::MAX), u64::MAX.to_string());
+    }
+
+    #[test]
+    fn cost_reads_fee_and_units_from_meta() {
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": {"Ok": null},
+            "fee": 105_000,
+            "preBalances": [],
+            "postBalances": [],
+            "computeUnitsConsumed": 71_234,
+        }))
+        .unwrap();
+        assert_eq!(cost_from_meta(&meta), (105_000, Some(71_234)));
+
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": {"Ok": null},
+            "fee": 5_000,
+            "preBalances": [],
+            "postBalances": [],
+        }))
+        .unwrap();
+        assert_eq!(cost_from_meta(&meta), (5_000, None));
+    }
+}
@@ -0,0 +1,302 @@
+//! Dry-run balance impact of a buy; nothing is built or sent. 买入预演：估算余额变化，不发送交易。
+
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use solana_sdk::{message::Message, pubkey::Pubkey};
+
+use crate::common::{GasFeeStrategyType, GasFeeStrategyValue, SolanaRpcClient};
+use crate::swqos::SwqosType;
+
+/// Base fee per signature, in lamports; used when `getFeeForMessage` is unavailable.
+pub const BASE_FEE_LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// How long a fetched base-fee rate is reused; the fee schedule changes rarely.
+pub const BASE_FEE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Exact base fee of a compiled `message` for the current fee schedule (`getFeeForMessage`),
+/// counting every required signature. Includes the priority fee when the message carries
+/// compute-budget instructions.
+pub async fn fee_for_message(rpc: &SolanaRpcClient, message: &Message) -> anyhow::Result<u64> {
+    Ok(rpc.get_fee_for_message(message).await?)
+}
+
+/// Lamports per signature from `getFeeForMessage`, reused for [`BASE_FEE_CACHE_TTL`].
+/// 基础签名费（短时缓存）。
+pub struct BaseFeeCache {
+    cached: Mutex<Option<(Instant, u64)>>,
+}
+
+impl BaseFeeCache {
+    pub fn new() -> Self {
+        Self { cached: Mutex::new(None) }
+    }
+
+    /// Cached rate, or price a message without instructions (so no priority fee) whose only
+    /// required signature is `payer`'s: its fee is the per-signature rate. Falls back to
+    /// [`BASE_FEE_LAMPORTS_PER_SIGNATURE`] on RPC errors without caching the fallback. Multiply
+    /// by the transaction's signature count with [`Self::base_fee`].
+    pub async fn lamports_per_signature(&self, rpc: &SolanaRpcClient, payer: &Pubkey) -> u64 {
+        if let Some((fetched_at, lamports)) = *self.cached.lock() {
+            if fetched_at.elapsed() < BASE_FEE_CACHE_TTL {
+                return lamports;
+            }
+        }
+        match fee_for_message(rpc, &Message::new(&[], Some(payer))).await {
+            Ok(lamports) => {
+                *self.cached.lock() = Some((Instant::now(), lamports));
+                lamports
+            }
+            Err(_) => BASE_FEE_LAMPORTS_PER_SIGNATURE,
+        }
+    }
+
+    /// Base fee of a transaction with `num_required_signatures` signers (the payer, plus e.g.
+    /// an `ata_rent_funder` co-signing), at the cached rate.
+    pub async fn base_fee(
+        &self,
+        rpc: &SolanaRpcClient,
+        payer: &Pubkey,
+        num_required_signatures: u8,
+    ) -> u64 {
+        self.lamports_per_signature(rpc, payer).await.saturating_mul(num_required_signatures as u64)
+    }
+}
+
+impl Default for BaseFeeCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Process-wide rate used by `TradingClient::preview`.
+pub static BASE_FEE_CACHE: Lazy<BaseFeeCache> = Lazy::new(BaseFeeCache::new);
+
+/// Estimated effect of a buy on the payer's balances, from
+/// [`TradingClient::preview`](crate::TradingClient::preview).
+///
+/// Estimates only: output uses the reserves in `extension_params`, fees assume the most
+/// expensive configured route lands (one route is expected to land) and count a separate
+/// `ata_rent_funder` as a second signature, and WSOL wrap rent is not counted since the wrap
+/// account is closed again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceImpact {
+    /// Quote spent: `input_token_amount` (the max input on exact-out buys).
+    pub quote_spend: u64,
+    /// Expected token output before slippage (`fixed_output_token_amount` on exact-out buys);
+    /// `None` when the params carry no reserves (Meteora DAMM v2).
+    pub expected_token_out: Option<u64>,
+    /// Signature fee plus priority fee (`cu_limit * cu_price`) of the most expensive route.
+    pub network_fee_lamports: u64,
+    /// Largest tip among the configured buy routes.
+    pub tip_lamports: u64,
+    /// Mint ATA rent when the account does not exist yet and `create_mint_ata` is set.
+    pub ata_rent_lamports: u64,
+    /// Current SOL balance (lamports).
+    pub sol_balance: u64,
+    /// Current quote-token balance when paying with WSOL/USDC/USD1; `None` for native SOL.
+    pub quote_balance: Option<u64>,
+    /// Current balance of the bought token (0 when its ATA does not exist).
+    pub token_balance: u64,
+}
+
+impl BalanceImpact {
+    /// Lamports the buy costs on top of the quote spend (fees, tip, rent).
+    pub fn sol_overhead(&self) -> u64 {
+        self.network_fee_lamports
+            .saturating_add(self.tip_lamports)
+            .saturating_add(self.ata_rent_lamports)
+    }
+
+    /// SOL balance after the buy; the spend is taken from SOL only when paying with native SOL.
+    pub fn sol_balance_after(&self) -> u64 {
+        let spend = if self.quote_balance.is_none() { self.quote_spend } else { 0 };
+        self.sol_balance.saturating_sub(self.sol_overhead().saturating_add(spend))
+    }
+
+    /// Quote-token balance after the buy (`None` when paying with native SOL).
+    pub fn quote_balance_after(&self) -> Option<u64> {
+        self.quote_balance.map(|b| b.saturating_sub(self.quote_spend))
+    }
+
+    /// Token balance after the buy (current + expected output).
+    pub fn token_balance_after(&self) -> Option<u64> {
+        self.expected_token_out.map(|out| self.token_balance.saturating_add(out))
+    }
+
+    /// Whether current balances cover the spend plus fees. 余额是否足够。
+    pub fn is_affordable(&self) -> bool {
+        let (sol_needed, quote_needed) = match self.quote_balance {
+            None => (self.sol_overhead().saturating_add(self.quote_spend), 0),
+            Some(_) => (self.sol_overhead(), self.quote_spend),
+        };
+        self.sol_balance >= sol_needed && self.quote_balance.unwrap_or(0) >= quote_needed
+    }
+}
+
+/// Executable depth measured by simulating a slice of a buy, from
+/// [`TradingClient::probe_depth`](crate::TradingClient::probe_depth). 小额模拟探测深度。
+///
+/// Prices are quote base units per token base unit. The full-size output is the curve quote
+/// from the params' reserves, scaled by how far the simulated probe landed from its own quote
+/// (fees, stale reserves, transfer fees), so it is an estimate, not a simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthEstimate {
+    /// Quote the probe spent.
+    pub probe_input: u64,
+    /// Tokens the simulated probe received.
+    pub probe_output: u64,
+    /// Effective price of the probe (`probe_input / probe_output`).
+    pub probe_price: f64,
+    /// Quote of the intended full-size buy.
+    pub full_input: u64,
+    /// Estimated tokens for the full size; `None` when the params carry no reserves
+    /// (Meteora DAMM v2).
+    pub estimated_full_output: Option<u64>,
+    /// Estimated effective price of the full size.
+    pub estimated_full_price: Option<f64>,
+    /// How much worse the full-size price is than the probe price, in bps.
+    pub estimated_slippage_bps: Option<f64>,
+}
+
+impl DepthEstimate {
+    /// Combine a simulated probe with curve quotes for the probe and the full size. `None`
+    /// when the probe received nothing.
+    pub fn from_probe(
+        probe_input: u64,
+        probe_output: u64,
+        full_input: u64,
+        quoted_probe_output: Option<u64>,
+        quoted_full_output: Option<u64>,
+    ) -> Option<Self> {
+        if probe_output == 0 {
+            return None;
+        }
+        let probe_price = probe_input as f64 / probe_output as f64;
+        let estimated_full_output = match (quoted_probe_output, quoted_full_output) {
+            (Some(quoted_probe), Some(quoted_full)) if quoted_probe > 0 => {
+                Some((quoted_full as u128 * probe_output as u128 / quoted_probe as u128) as u64)
+            }
+            _ => None,
+        };
+        let estimated_full_price =
+            estimated_full_output.filter(|out| *out > 0).map(|out| full_input as f64 / out as f64);
+        Some(Self {
+            probe_input,
+            probe_output,
+            probe_price,
+            full_input,
+            estimated_full_output,
+            estimated_full_price,
+            estimated_slippage_bps: estimated_full_price
+                .map(|price| (price / probe_price - 1.0) * 10_000.0),
+        })
+    }
+}
+
+/// `(network_fee_lamports, tip_lamports)` of the most expensive routes in `strategies`, with
+/// `base_fee_lamports` for the transaction's signatures.
+pub(crate) fn route_costs(
+    strategies: &[(SwqosType, GasFeeStrategyType, GasFeeStrategyValue)],
+    base_fee_lamports: u64,
+) -> (u64, u64) {
+    strategies.iter().fold((base_fee_lamports, 0), |(fee, tip), (_, _, v)| {
+        let priority = (v.cu_limit as u128 * v.cu_price as u128 / 1_000_000) as u64;
+        let route_fee = base_fee_lamports.saturating_add(priority);
+        let route_tip = (v.tip * 1_000_000_000.0).round() as u64;
+        (fee.max(route_fee), tip.max(route_tip))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_estimate_scales_the_full_quote_by_the_probe_fill() {
+        // constant-product pool: 100 SOL / 1_000_000 tokens, no fees
+        let quote = |amount_in: u64| {
+            let (quote_reserve, token_reserve) = (100_000_000_000u128, 1_000_000_000_000u128);
+            Some((token_reserve * amount_in as u128 / (quote_reserve + amount_in as u128)) as u64)
+        };
+        let (full, probe) = (10_000_000_000u64, 100_000_000u64);
+        // the simulated probe filled 1% below its quote (e.g. a fee)
+        let probe_out = quote(probe).unwrap() * 99 / 100;
+
+        let estimate =
+            DepthEstimate::from_probe(probe, probe_out, full, quote(probe), quote(full)).unwrap();
+        assert_eq!(estimate.probe_output, probe_out);
+        assert_eq!(estimate.estimated_full_output, Some(quote(full).unwrap() * 99 / 100));
+        // 10 SOL into 100 SOL of depth costs ~9.9% more per token than a 0.1 SOL probe
+        let slippage = estimate.estimated_slippage_bps.unwrap();
+        assert!((slippage - 989.0).abs() < 1.0, "{slippage}");
+
+        // no reserves in the params: only the probe is reported
+        let probe_only = DepthEstimate::from_probe(probe, probe_out, full, None, None).unwrap();
+        assert_eq!(probe_only.estimated_full_output, None);
+        assert_eq!(probe_only.estimated_slippage_bps, None);
+        assert!(DepthEstimate::from_probe(probe, 0, full, None, None).is_none());
+    }
+
+    #[test]
+    fn native_sol_buy_projects_spend_fees_and_output() {
+        let (network_fee_lamports, tip_lamports) = route_costs(
+            &[
+                (
+                    SwqosType::Default,
+                    GasFeeStrategyType::Normal,
+                    GasFeeStrategyValue { cu_limit: 200_000, cu_price: 1_000_000, tip: 0.0 },
+                ),
+                (
+                    SwqosType::Jito,
+                    GasFeeStrategyType::Normal,
+                    GasFeeStrategyValue { cu_limit: 200_000, cu_price: 500_000, tip: 0.001 },
+                ),
+            ],
+            BASE_FEE_LAMPORTS_PER_SIGNATURE,
+        );
+        assert_eq!((network_fee_lamports, tip_lamports), (205_000, 1_000_000));
+
+        let impact = BalanceImpact {
+            quote_spend: 100_000_000,
+            expected_token_out: Some(3_000),
+            network_fee_lamports,
+            tip_lamports,
+            ata_rent_lamports: 2_039_280,
+            sol_balance: 1_000_000_000,
+            quote_balance: None,
+            token_balance: 500,
+        };
+        assert_eq!(impact.sol_balance_after(), 1_000_000_000 - 100_000_000 - 3_244_280);
+        assert_eq!(impact.token_balance_after(), Some(3_500));
+        assert_eq!(impact.quote_balance_after(), None);
+        assert!(impact.is_affordable());
+
+        let usdc = BalanceImpact { quote_balance: Some(50_000_000), ..impact };
+        assert_eq!(usdc.sol_balance_after(), 1_000_000_000 - 3_244_280);
+        assert_eq!(usdc.quote_balance_after(), Some(0));
+        assert!(!usdc.is_affordable());
+    }
+
+    #[tokio::test]
+    async fn base_fee_comes_from_get_fee_for_message_and_is_cached() {
+        use crate::common::MockSolanaRpcClient;
+
+        let cache = BaseFeeCache::new();
+        let payer = Pubkey::new_unique();
+        let rpc = MockSolanaRpcClient::new()
+            .with_fee_for_message(10_000)
+            .with_fee_for_message(20_000)
+            .build();
+        assert_eq!(cache.lamports_per_signature(&rpc, &payer).await, 10_000);
+        // served from cache; the second queued rate is never read
+        assert_eq!(cache.lamports_per_signature(&rpc, &payer).await, 10_000);
+        assert_eq!(fee_for_message(&rpc, &Message::new(&[], Some(&payer))).await.unwrap(), 20_000);
+        // a co-signing rent funder is a second signature
+        assert_eq!(cache.base_fee(&rpc, &payer, 2).await, 20_000);
+
+        let (network_fee, _) = route_costs(&[], 10_000);
+        assert_eq!(network_fee, 10_000);
+    }
+}
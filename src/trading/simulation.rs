@@ -0,0 +1,227 @@
+//! Simulation with account snapshots: which balances a trade would change, before sending it.
+//! 模拟交易并返回指定账户的前后余额变化。
+//!
+//! See `TradingClient::simulate_with_accounts`. Pre-states come from one `getMultipleAccounts`
+//! right before the simulation, post-states from the `accounts` field of `simulateTransaction`.
+
+use parking_lot::Mutex;
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::{
+    rpc_config::RpcSimulateTransactionAccountsConfig, rpc_response::RpcSimulateTransactionResult,
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+
+/// Balance-relevant view of one account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountState {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    /// Token amount (base units) when the account is an SPL Token / Token-2022 token account.
+    pub token_amount: Option<u64>,
+}
+
+impl AccountState {
+    pub fn from_account(account: &Account) -> Self {
+        Self {
+            lamports: account.lamports,
+            owner: account.owner,
+            token_amount: token_account_amount(account),
+        }
+    }
+}
+
+/// Token accounts keep the amount at bytes 64..72 (after mint and owner); mints are 82 bytes.
+fn token_account_amount(account: &Account) -> Option<u64> {
+    if account.owner != TOKEN_PROGRAM && account.owner != TOKEN_PROGRAM_2022 {
+        return None;
+    }
+    if account.data.len() < 165 {
+        return None;
+    }
+    account.data.get(64..72).map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+}
+
+/// One requested account before and after the simulated trade. `None` = account does not exist
+/// (e.g. an ATA the trade would create, or an account the trade closes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountDelta {
+    pub pubkey: Pubkey,
+    pub pre: Option<AccountState>,
+    pub post: Option<AccountState>,
+    /// Whether the simulation returned this account's post-state. A failed simulation returns
+    /// none, so `post` says nothing and the deltas are `None`.
+    pub post_recorded: bool,
+}
+
+impl AccountDelta {
+    /// Post minus pre lamports (missing account = 0); `None` without a recorded post-state.
+    pub fn lamports_delta(&self) -> Option<i128> {
+        if !self.post_recorded {
+            return None;
+        }
+        let lamports = |s: &Option<AccountState>| s.map_or(0, |s| s.lamports) as i128;
+        Some(lamports(&self.post) - lamports(&self.pre))
+    }
+
+    /// Post minus pre token amount; `None` when neither side is a token account or without a
+    /// recorded post-state.
+    pub fn token_delta(&self) -> Option<i128> {
+        if !self.post_recorded {
+            return None;
+        }
+        let pre = self.pre.and_then(|s| s.token_amount);
+        let post = self.post.and_then(|s| s.token_amount);
+        if pre.is_none() && post.is_none() {
+            return None;
+        }
+        Some(post.unwrap_or(0) as i128 - pre.unwrap_or(0) as i128)
+    }
+}
+
+/// Result of `TradingClient::simulate_with_accounts`.
+#[derive(Debug, Clone, Default)]
+pub struct SimulationReport {
+    pub success: bool,
+    /// Simulation error (e.g. `InstructionError`), `None` on success.
+    pub error: Option<String>,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+    /// In the order the accounts were requested.
+    pub accounts: Vec<AccountDelta>,
+}
+
+#[derive(Default)]
+struct Captured {
+    post: Vec<Option<AccountState>>,
+    logs: Vec<String>,
+    units_consumed: Option<u64>,
+}
+
+/// Accounts to request from `simulateTransaction` and what it returned; passed to the executor
+/// through `SwapParams::simulation_capture`.
+pub struct SimulationCapture {
+    addresses: Vec<Pubkey>,
+    captured: Mutex<Option<Captured>>,
+}
+
+impl SimulationCapture {
+    pub fn new(addresses: Vec<Pubkey>) -> Self {
+        Self { addresses, captured: Mutex::new(None) }
+    }
+
+    pub fn addresses(&self) -> &[Pubkey] {
+        &self.addresses
+    }
+
+    pub(crate) fn accounts_config(&self) -> RpcSimulateTransactionAccountsConfig {
+        RpcSimulateTransactionAccountsConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            addresses: self.addresses.iter().map(Pubkey::to_string).collect(),
+        }
+    }
+
+    pub(crate) fn record(&self, result: &RpcSimulateTransactionResult) {
+        let post = result
+            .accounts
+            .iter()
+            .flatten()
+            .map(|a| a.as_ref().and_then(UiAccount::decode::<Account>))
+            .map(|a| a.as_ref().map(AccountState::from_account))
+            .collect();
+        *self.captured.lock() = Some(Captured {
+            post,
+            logs: result.logs.clone().unwrap_or_default(),
+            units_consumed: result.units_consumed,
+        });
+    }
+
+    /// Whether a simulation has reported back.
+    pub fn is_recorded(&self) -> bool {
+        self.captured.lock().is_some()
+    }
+
+    /// Pair `pre` (same order as the addresses) with the recorded post-states.
+    pub fn report(
+        &self,
+        pre: &[Option<Account>],
+        success: bool,
+        error: Option<String>,
+    ) -> SimulationReport {
+        let captured = self.captured.lock().take().unwrap_or_default();
+        let accounts = self
+            .addresses
+            .iter()
+            .enumerate()
+            .map(|(i, pubkey)| AccountDelta {
+                pubkey: *pubkey,
+                pre: pre.get(i).and_then(|a| a.as_ref()).map(AccountState::from_account),
+                post: captured.post.get(i).copied().flatten(),
+                post_recorded: i < captured.post.len(),
+            })
+            .collect();
+        SimulationReport {
+            success,
+            error,
+            units_consumed: captured.units_consumed,
+            logs: captured.logs,
+            accounts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_account(amount: u64, lamports: u64) -> Account {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        Account { lamports, data, owner: TOKEN_PROGRAM, executable: false, rent_epoch: 0 }
+    }
+
+    #[test]
+    fn deltas_decode_token_amounts_and_missing_accounts() {
+        let (wallet, ata) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let capture = SimulationCapture::new(vec![wallet, ata]);
+        capture.captured.lock().replace(Captured {
+            post: vec![
+                Some(AccountState { lamports: 900, owner: Pubkey::default(), token_amount: None }),
+                Some(AccountState::from_account(&token_account(5_000, 2_039_280))),
+            ],
+            ..Default::default()
+        });
+        let wallet_account = Account { lamports: 1_000, ..Default::default() };
+
+        let deltas = capture.report(&[Some(wallet_account), None], true, None).accounts;
+
+        assert_eq!(deltas[0].lamports_delta(), Some(-100));
+        assert_eq!(deltas[0].token_delta(), None);
+        assert_eq!(deltas[1].pubkey, ata);
+        assert!(deltas[1].pre.is_none(), "ATA created by the trade");
+        assert_eq!(deltas[1].token_delta(), Some(5_000));
+        assert_eq!(deltas[1].lamports_delta(), Some(2_039_280));
+        assert_eq!(
+            token_account_amount(&Account { owner: TOKEN_PROGRAM, ..Default::default() }),
+            None
+        );
+    }
+
+    #[test]
+    fn deltas_are_unknown_without_post_states() {
+        let wallet = Pubkey::new_unique();
+        let capture = SimulationCapture::new(vec![wallet]);
+        let wallet_account = Account { lamports: 1_000, ..Default::default() };
+
+        // failed simulation: nothing recorded
+        let delta = &capture.report(&[Some(wallet_account.clone())], false, None).accounts[0];
+        assert!(!delta.post_recorded);
+        assert_eq!((delta.lamports_delta(), delta.token_delta()), (None, None));
+
+        // recorded as missing: the trade closed the account
+        capture.captured.lock().replace(Captured { post: vec![None], ..Default::default() });
+        let delta = &capture.report(&[Some(wallet_account)], true, None).accounts[0];
+        assert_eq!(delta.lamports_delta(), Some(-1_000));
+    }
+}
@@ -0,0 +1,178 @@
+//! Pluggable slippage: compute `slippage_basis_points` per trade instead of passing a fixed value.
+//! 可插拔滑点模型：按每笔交易计算滑点，而非固定值。
+//!
+//! Set `slippage_model` on `TradeBuyParams` / `TradeSellParams`; the client asks the model right
+//! before building and the result replaces `slippage_basis_points`.
+
+use std::collections::{HashMap, VecDeque};
+
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::core::reserve_check::event_reserves;
+use crate::trading::factory::DexType;
+
+/// What a [`SlippageModel`] sees for one trade. 单笔交易的滑点上下文。
+#[derive(Debug, Clone, Copy)]
+pub struct SlippageContext {
+    pub dex_type: DexType,
+    pub is_buy: bool,
+    pub mint: Pubkey,
+    /// Quote base units for buys, token base units for sells.
+    pub input_amount: u64,
+    /// Reserve pair the params were priced with (see
+    /// [`event_reserves`](crate::trading::core::reserve_check::event_reserves) for the
+    /// orientation per protocol). `None` for protocols without reserves in params.
+    pub reserves: Option<(u64, u64)>,
+    /// `slippage_basis_points` from the params, if the caller also set one.
+    pub requested_bps: Option<u64>,
+}
+
+impl SlippageContext {
+    pub(crate) fn new(
+        dex_type: DexType,
+        is_buy: bool,
+        mint: Pubkey,
+        input_amount: u64,
+        params: &DexParamEnum,
+        requested_bps: Option<u64>,
+    ) -> Self {
+        Self {
+            dex_type,
+            is_buy,
+            mint,
+            input_amount,
+            reserves: event_reserves(params),
+            requested_bps,
+        }
+    }
+
+    /// Second reserve over first (quote per token for most protocols); `None` without reserves.
+    pub fn spot_price(&self) -> Option<f64> {
+        match self.reserves {
+            Some((a, b)) if a > 0 && b > 0 => Some(b as f64 / a as f64),
+            _ => None,
+        }
+    }
+}
+
+/// Decides the slippage (basis points) for one trade; called on the trade path, keep it cheap.
+pub trait SlippageModel: Send + Sync {
+    fn slippage_bps(&self, ctx: &SlippageContext) -> u64;
+}
+
+/// Current behaviour: the same slippage for every trade.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedSlippage(pub u64);
+
+impl SlippageModel for FixedSlippage {
+    fn slippage_bps(&self, _ctx: &SlippageContext) -> u64 {
+        self.0
+    }
+}
+
+/// Widens slippage with volatility: keeps the last `window` spot prices per mint (from trades and
+/// from [`Self::record`], e.g. fed by an event stream) and adds the current price's deviation
+/// from their average (TWAP over samples), times `multiplier`, on top of `base_bps`.
+/// 价格偏离近期均价越大，滑点越宽。
+pub struct TwapDeviationSlippage {
+    pub base_bps: u64,
+    pub max_bps: u64,
+    /// Extra slippage per bps of deviation, e.g. `1.5`.
+    pub multiplier: f64,
+    pub window: usize,
+    history: Mutex<HashMap<Pubkey, VecDeque<f64>>>,
+}
+
+impl TwapDeviationSlippage {
+    pub fn new(base_bps: u64, max_bps: u64, multiplier: f64, window: usize) -> Self {
+        Self {
+            base_bps,
+            max_bps: max_bps.max(base_bps),
+            multiplier: multiplier.max(0.0),
+            window: window.max(1),
+            history: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a reserve observation for `mint` outside of trades (same orientation as
+    /// [`SlippageContext::reserves`]).
+    pub fn record(&self, mint: Pubkey, reserves: (u64, u64)) {
+        if reserves.0 > 0 && reserves.1 > 0 {
+            self.push(mint, reserves.1 as f64 / reserves.0 as f64);
+        }
+    }
+
+    fn push(&self, mint: Pubkey, price: f64) {
+        let mut history = self.history.lock();
+        let samples = history.entry(mint).or_default();
+        if samples.len() >= self.window {
+            samples.pop_front();
+        }
+        samples.push_back(price);
+    }
+
+    /// Deviation of `price` from the recorded average, in bps. 0 without history.
+    fn deviation_bps(&self, mint: &Pubkey, price: f64) -> f64 {
+        let history = self.history.lock();
+        let Some(samples) = history.get(mint).filter(|s| !s.is_empty()) else {
+            return 0.0;
+        };
+        let twap = samples.iter().sum::<f64>() / samples.len() as f64;
+        if twap <= 0.0 {
+            return 0.0;
+        }
+        ((price - twap).abs() / twap) * 10_000.0
+    }
+}
+
+impl SlippageModel for TwapDeviationSlippage {
+    fn slippage_bps(&self, ctx: &SlippageContext) -> u64 {
+        let Some(price) = ctx.spot_price() else {
+            return self.base_bps;
+        };
+        let deviation = self.deviation_bps(&ctx.mint, price);
+        self.push(ctx.mint, price);
+        let extra = (deviation * self.multiplier).min(u64::MAX as f64) as u64;
+        self.base_bps.saturating_add(extra).min(self.max_bps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(mint: Pubkey, reserves: Option<(u64, u64)>) -> SlippageContext {
+        SlippageContext {
+            dex_type: DexType::PumpSwap,
+            is_buy: true,
+            mint,
+            input_amount: 1_000,
+            reserves,
+            requested_bps: None,
+        }
+    }
+
+    #[test]
+    fn fixed_slippage_ignores_context() {
+        assert_eq!(FixedSlippage(300).slippage_bps(&ctx(Pubkey::new_unique(), None)), 300);
+    }
+
+    #[test]
+    fn twap_deviation_widens_with_volatility_and_is_capped() {
+        let model = TwapDeviationSlippage::new(100, 1_000, 1.0, 4);
+        let mint = Pubkey::new_unique();
+        // no history yet, and no reserves at all
+        assert_eq!(model.slippage_bps(&ctx(mint, Some((1_000, 1_000)))), 100);
+        assert_eq!(model.slippage_bps(&ctx(mint, None)), 100);
+
+        model.record(mint, (1_000, 1_000));
+        // price 1.05 vs twap 1.0 → 500 bps deviation
+        assert_eq!(model.slippage_bps(&ctx(mint, Some((1_000, 1_050)))), 600);
+        // a 3x move is capped
+        assert_eq!(model.slippage_bps(&ctx(mint, Some((1_000, 3_000)))), 1_000);
+        // other mints keep their own history
+        assert_eq!(model.slippage_bps(&ctx(Pubkey::new_unique(), Some((1, 2)))), 100);
+    }
+}
@@ -0,0 +1,89 @@
+//! Dust sweep: find a SOL-quoted pool for each small position and sell it.
+//! 清理粉尘持仓：为每个代币寻找 SOL 池并卖出。
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::SolanaRpcClient;
+use crate::constants::{SOL_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+use crate::instruction::utils::pumpswap::{get_canonical_pool_pda, get_pool_v2_pda};
+use crate::trading::core::params::{BonkParams, DexParamEnum, PumpFunParams, PumpSwapParams};
+
+/// SOL-quoted pools of `mint` found with direct lookups only (no `getProgramAccounts`): the
+/// PumpFun bonding curve while it is trading, the PumpSwap v2 and canonical pool PDAs, and the
+/// Bonk WSOL pool. Lookups that fail are skipped. Raydium CPMM, Raydium AMM v4 and Meteora
+/// DAMM v2 pool addresses also depend on a config or market account, not just the mint, so
+/// they are never candidates.
+pub(crate) async fn sol_pool_candidates(rpc: &SolanaRpcClient, mint: &Pubkey) -> Vec<DexParamEnum> {
+    let mut candidates = Vec::new();
+    if let Ok(params) = PumpFunParams::from_mint_by_rpc(rpc, mint).await {
+        let quote = params.bonding_curve.quote_mint;
+        let sol_quoted =
+            quote == Pubkey::default() || quote == SOL_TOKEN_ACCOUNT || quote == WSOL_TOKEN_ACCOUNT;
+        if !params.bonding_curve.complete && sol_quoted {
+            candidates.push(DexParamEnum::PumpFun(params));
+        }
+    }
+    let pumpswap_pools = get_pool_v2_pda(mint).into_iter().chain([get_canonical_pool_pda(mint)]);
+    for pool in pumpswap_pools {
+        if let Ok(params) = PumpSwapParams::from_pool_address_by_rpc(rpc, &pool).await {
+            if params.base_mint == *mint && params.quote_mint == WSOL_TOKEN_ACCOUNT {
+                candidates.push(DexParamEnum::PumpSwap(params));
+            }
+        }
+    }
+    if let Ok(params) = BonkParams::from_mint_by_rpc(rpc, mint, false).await {
+        candidates.push(DexParamEnum::Bonk(params));
+    }
+    candidates
+}
+
+/// Candidate paying the most SOL for `amount` of `mint`, with that quote in lamports.
+/// Candidates that cannot be quoted or fail [`DexParamEnum::validate`] are dropped.
+pub(crate) fn best_sell_quote(
+    candidates: Vec<DexParamEnum>,
+    mint: &Pubkey,
+    amount: u64,
+) -> Option<(DexParamEnum, u64)> {
+    candidates
+        .into_iter()
+        .filter(|params| params.validate().is_ok())
+        .filter_map(|params| {
+            let out = params.expected_sell_output(mint, amount)?;
+            Some((params, out))
+        })
+        .max_by_key(|(_, out)| *out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures as fx;
+
+    #[test]
+    fn best_sell_quote_picks_the_highest_sol_output() {
+        let shallow = fx::pumpswap_params();
+        let mut deep = fx::pumpswap_params();
+        deep.pool_quote_token_reserves *= 4;
+        let amount = fx::BASE_RESERVE / 1_000;
+        let curve = DexParamEnum::PumpFun(fx::pumpfun_params());
+
+        let (best, out) = best_sell_quote(
+            vec![
+                DexParamEnum::PumpSwap(shallow),
+                curve.clone(),
+                DexParamEnum::PumpSwap(deep.clone()),
+            ],
+            &fx::MINT,
+            amount,
+        )
+        .unwrap();
+        let deep_out = DexParamEnum::PumpSwap(deep).expected_sell_output(&fx::MINT, amount);
+        assert!(deep_out > curve.expected_sell_output(&fx::MINT, amount));
+        assert_eq!(Some(out), deep_out);
+        assert!(
+            matches!(best, DexParamEnum::PumpSwap(p) if p.pool_quote_token_reserves == 4 * fx::QUOTE_RESERVE)
+        );
+
+        assert!(best_sell_quote(Vec::new(), &fx::MINT, amount).is_none());
+    }
+}
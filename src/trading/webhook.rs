@@ -0,0 +1,67 @@
+//! Fire-and-forget JSON POST of every [`TradeOutcome`] to a webhook URL.
+//! 将每笔交易结果以 JSON POST 到 webhook（异步、不阻塞交易路径）。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use crate::trading::outcome::{TradeOutcome, TradeOutcomeSink};
+
+/// Default bounded queue size; outcomes beyond it are dropped, never awaited.
+pub const DEFAULT_WEBHOOK_QUEUE_CAPACITY: usize = 1024;
+const WEBHOOK_TIMEOUT_MS: u64 = 5000;
+
+/// Posts outcomes from a background task. Must be created inside a tokio runtime.
+pub struct WebhookSink {
+    tx: mpsc::Sender<TradeOutcome>,
+    dropped: AtomicU64,
+}
+
+impl WebhookSink {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_capacity(url, DEFAULT_WEBHOOK_QUEUE_CAPACITY)
+    }
+
+    pub fn with_capacity(url: impl Into<String>, capacity: usize) -> Self {
+        let url = url.into();
+        let (tx, mut rx) = mpsc::channel::<TradeOutcome>(capacity.max(1));
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(WEBHOOK_TIMEOUT_MS))
+            .build()
+            .unwrap_or_default();
+        tokio::spawn(async move {
+            while let Some(outcome) = rx.recv().await {
+                let body = match serde_json::to_vec(&outcome) {
+                    Ok(body) => body,
+                    Err(_) => continue,
+                };
+                let res = client
+                    .post(&url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(body)
+                    .send()
+                    .await;
+                if let Err(e) = res.and_then(|r| r.error_for_status()) {
+                    if crate::common::sdk_log::sdk_log_enabled() {
+                        eprintln!(" [SDK] webhook post failed: {}", e);
+                    }
+                }
+            }
+        });
+        Self { tx, dropped: AtomicU64::new(0) }
+    }
+
+    /// Outcomes dropped because the queue was full or the worker stopped.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl TradeOutcomeSink for WebhookSink {
+    fn on_outcome(&self, outcome: &TradeOutcome) {
+        if self.tx.try_send(outcome.clone()).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
@@ -1,6 +0,0 @@
-pub mod bonk;
-pub mod common;
-pub mod pumpfun;
-pub mod pumpswap;
-pub mod raydium_amm_v4;
-pub mod raydium_cpmm;
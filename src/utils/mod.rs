@@ -1,4 +1,4 @@
-pub mod calc;
+pub use sol_trade_sdk_calc as calc;
 pub mod price;
 use crate::trading;
 use crate::TradingClient;
@@ -54,6 +54,15 @@ impl TradingClient {
         .await
     }
 
+    /// Same ATA derivation as trades (seed optimize included), with the token program inferred
+    /// from the mint owner, so Token-2022 mints are never looked up under the legacy program.
+    #[inline]
+    pub async fn get_payer_trade_token_balance(&self, mint: &Pubkey) -> Result<u64, anyhow::Error> {
+        let token_program =
+            trading::common::utils::resolve_token_program(&self.infrastructure.rpc, mint).await?;
+        self.get_payer_token_balance_with_program(mint, &token_program).await
+    }
+
     #[inline]
     pub fn get_payer_pubkey(&self) -> Pubkey {
         self.payer.pubkey()
@@ -90,3 +99,22 @@ impl TradingClient {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::calc::fees;
+
+    #[test]
+    fn instruction_modules_reexport_the_calc_fee_rates() {
+        use crate::instruction::utils;
+        assert_eq!(
+            utils::pumpfun::global_constants::FEE_BASIS_POINTS,
+            fees::pumpfun::FEE_BASIS_POINTS
+        );
+        assert_eq!(utils::pumpfun::global_constants::CREATOR_FEE, fees::pumpfun::CREATOR_FEE);
+        assert_eq!(
+            utils::raydium_cpmm::accounts::TRADE_FEE_RATE,
+            fees::raydium_cpmm::TRADE_FEE_RATE
+        );
+    }
+}
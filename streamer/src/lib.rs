@@ -0,0 +1,394 @@
+//! Protocol params straight from solana-streamer trade events.
+//! 由 solana-streamer 交易事件直接构造协议参数。
+//!
+//! Lives outside the published SDK: solana-streamer-sdk is pinned to the git revision the
+//! examples use, which a crates.io release of `sol-trade-sdk` could not carry.
+//!
+//! PumpSwap and Bonk events carry the whole pool state: convert with [`FromEvent`], no RPC.
+//! Raydium CPMM, Raydium AMM v4 and Meteora DAMM v2 swap events name the pool but carry no
+//! reserves, so [`FromEventByRpc`] loads the pool they name (one read, cached in `POOL_CACHE`).
+//! [`EventTrade`] turns an event into a [`DecodedTrade`] for `TradingClient::copy_trade`.
+
+use std::future::Future;
+
+use sol_trade_sdk::common::SolanaRpcClient;
+use sol_trade_sdk::constants::{USD1_TOKEN_ACCOUNT, USDC_TOKEN_ACCOUNT};
+use sol_trade_sdk::swqos::common::TradeError;
+use sol_trade_sdk::trading::core::params::{
+    BonkParams, DexParamEnum, MeteoraDammV2Params, PumpSwapParams, RaydiumAmmV4Params,
+    RaydiumCpmmParams,
+};
+use sol_trade_sdk::trading::DecodedTrade;
+use sol_trade_sdk::{PoolLoadError, TradeTokenType};
+use solana_sdk::pubkey::Pubkey;
+use solana_streamer_sdk::streaming::event_parser::protocols::{
+    bonk::BonkTradeEvent,
+    meteora_damm_v2::MeteoraDammV2SwapEvent,
+    pumpswap::{PumpSwapBuyEvent, PumpSwapSellEvent},
+    raydium_amm_v4::RaydiumAmmV4SwapEvent,
+    raydium_cpmm::RaydiumCpmmSwapEvent,
+};
+
+/// Validated params from an event that carries the whole pool state.
+pub trait FromEvent<E>: Sized {
+    fn try_from_event(event: &E) -> Result<Self, TradeError>;
+}
+
+/// Params loaded from the pool an event names.
+pub trait FromEventByRpc<E>: Sized {
+    fn from_event_by_rpc(
+        rpc: &SolanaRpcClient,
+        event: &E,
+    ) -> impl Future<Output = Result<Self, PoolLoadError>> + Send;
+}
+
+/// The trade an event describes, ready for `TradingClient::copy_trade`.
+pub trait EventTrade {
+    fn decoded_trade(&self) -> anyhow::Result<DecodedTrade>;
+}
+
+/// Buy and sell events carry the same pool fields. Fee bps all `0` means the parser did not
+/// report them: the SDK defaults are kept.
+macro_rules! pumpswap_params_from_event {
+    ($event:expr) => {{
+        let event = $event;
+        let params = PumpSwapParams::from_trade(
+            event.pool,
+            event.base_mint,
+            event.quote_mint,
+            event.pool_base_token_account,
+            event.pool_quote_token_account,
+            event.pool_base_token_reserves,
+            event.pool_quote_token_reserves,
+            event.virtual_quote_reserves,
+            event.coin_creator_vault_ata,
+            event.coin_creator_vault_authority,
+            event.base_token_program,
+            event.quote_token_program,
+            event.protocol_fee_recipient,
+            event.coin_creator,
+            event.cashback_fee_basis_points != 0 || event.cashback != 0,
+            event.cashback_fee_basis_points,
+        );
+        let fees = (
+            event.lp_fee_basis_points,
+            event.protocol_fee_basis_points,
+            event.coin_creator_fee_basis_points,
+        );
+        if fees == (0, 0, 0) {
+            params
+        } else {
+            params.with_fee_basis_points(fees.0, fees.1, fees.2)
+        }
+    }};
+}
+
+/// PumpSwap buy and sell events name both pool sides; the traded mint is the non-quote one.
+/// `decoded_trade` leaves the params unvalidated; the send validates them.
+macro_rules! impl_pumpswap_event {
+    ($event:ty) => {
+        impl FromEvent<$event> for PumpSwapParams {
+            fn try_from_event(event: &$event) -> Result<Self, TradeError> {
+                let params = pumpswap_params_from_event!(event);
+                params.validate()?;
+                Ok(params)
+            }
+        }
+
+        impl EventTrade for $event {
+            fn decoded_trade(&self) -> anyhow::Result<DecodedTrade> {
+                let params = pumpswap_params_from_event!(self);
+                DecodedTrade::from_pool_params(DexParamEnum::PumpSwap(params))
+            }
+        }
+    };
+}
+
+impl_pumpswap_event!(PumpSwapBuyEvent);
+impl_pumpswap_event!(PumpSwapSellEvent);
+
+/// Unvalidated params from a trade event.
+fn bonk_params_from_trade(event: &BonkTradeEvent) -> BonkParams {
+    BonkParams::from_trade(
+        event.virtual_base,
+        event.virtual_quote,
+        event.real_base_after,
+        event.real_quote_after,
+        event.pool_state,
+        event.base_vault,
+        event.quote_vault,
+        event.base_token_program,
+        event.platform_config,
+        event.platform_associated_account,
+        event.creator_associated_account,
+        event.global_config,
+    )
+}
+
+impl FromEvent<BonkTradeEvent> for BonkParams {
+    fn try_from_event(event: &BonkTradeEvent) -> Result<Self, TradeError> {
+        let params = bonk_params_from_trade(event);
+        params.validate()?;
+        Ok(params)
+    }
+}
+
+impl EventTrade for BonkTradeEvent {
+    /// Base mint against the event's quote mint (SOL when it is not a quote mint).
+    fn decoded_trade(&self) -> anyhow::Result<DecodedTrade> {
+        let quote = if self.quote_token_mint == USDC_TOKEN_ACCOUNT {
+            TradeTokenType::USDC
+        } else if self.quote_token_mint == USD1_TOKEN_ACCOUNT {
+            TradeTokenType::USD1
+        } else {
+            TradeTokenType::SOL
+        };
+        let mut trade = DecodedTrade::new(
+            self.base_token_mint,
+            DexParamEnum::Bonk(bonk_params_from_trade(self)),
+        )
+        .with_quote(quote);
+        trade.mint_token_program =
+            (self.base_token_program != Pubkey::default()).then_some(self.base_token_program);
+        Ok(trade)
+    }
+}
+
+impl FromEventByRpc<RaydiumCpmmSwapEvent> for RaydiumCpmmParams {
+    /// Load the event's `pool_state`. The event's input/output sides flip with the swap
+    /// direction, so sides come from the pool account (`oriented`: the WSOL/USDC/USD1 mint is
+    /// `quote_mint`), not from the event.
+    fn from_event_by_rpc(
+        rpc: &SolanaRpcClient,
+        event: &RaydiumCpmmSwapEvent,
+    ) -> impl Future<Output = Result<Self, PoolLoadError>> + Send {
+        Self::from_pool_address_by_rpc(rpc, &event.pool_state)
+    }
+}
+
+impl FromEventByRpc<RaydiumAmmV4SwapEvent> for RaydiumAmmV4Params {
+    /// Load the event's `amm` (mints and reserves are not in the swap event).
+    fn from_event_by_rpc(
+        rpc: &SolanaRpcClient,
+        event: &RaydiumAmmV4SwapEvent,
+    ) -> impl Future<Output = Result<Self, PoolLoadError>> + Send {
+        Self::from_amm_address_by_rpc(rpc, event.amm)
+    }
+}
+
+impl FromEventByRpc<MeteoraDammV2SwapEvent> for MeteoraDammV2Params {
+    /// Load the event's `pool` (reserves are not in the swap event).
+    fn from_event_by_rpc(
+        rpc: &SolanaRpcClient,
+        event: &MeteoraDammV2SwapEvent,
+    ) -> impl Future<Output = Result<Self, PoolLoadError>> + Send {
+        Self::from_pool_address_by_rpc(rpc, &event.pool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sol_trade_sdk::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022, WSOL_TOKEN_ACCOUNT};
+    use sol_trade_sdk::instruction::utils::pumpswap::{accounts, PumpSwapFeeBasisPoints};
+    use sol_trade_sdk::trading::factory::DexType;
+    use sol_trade_sdk::trading::CopyTradeEvent;
+
+    fn pk(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    #[test]
+    fn pumpswap_buy_and_sell_events_map_every_field() {
+        let buy = PumpSwapBuyEvent {
+            pool: pk(1),
+            base_mint: pk(2),
+            quote_mint: pk(3),
+            pool_base_token_account: pk(4),
+            pool_quote_token_account: pk(5),
+            pool_base_token_reserves: 1_000,
+            pool_quote_token_reserves: 2_000,
+            virtual_quote_reserves: -300,
+            coin_creator_vault_ata: pk(6),
+            coin_creator_vault_authority: pk(7),
+            base_token_program: pk(8),
+            quote_token_program: pk(9),
+            protocol_fee_recipient: pk(10),
+            coin_creator: pk(11),
+            cashback_fee_basis_points: 7,
+            lp_fee_basis_points: 20,
+            protocol_fee_basis_points: 5,
+            coin_creator_fee_basis_points: 30,
+            ..Default::default()
+        };
+        let params = PumpSwapParams::try_from_event(&buy).unwrap();
+        assert_eq!((params.pool, params.base_mint, params.quote_mint), (pk(1), pk(2), pk(3)));
+        assert_eq!(params.pool_base_token_account, pk(4));
+        assert_eq!(params.pool_quote_token_account, pk(5));
+        assert_eq!(params.pool_base_token_reserves, 1_000);
+        assert_eq!(params.pool_quote_token_reserves, 2_000);
+        assert_eq!(params.virtual_quote_reserves, -300);
+        assert_eq!(params.coin_creator_vault_ata, pk(6));
+        assert_eq!(params.coin_creator_vault_authority, pk(7));
+        assert_eq!((params.base_token_program, params.quote_token_program), (pk(8), pk(9)));
+        // the fee recipient only selects mayhem mode
+        assert!(!params.is_mayhem_mode);
+        assert_eq!(params.coin_creator, pk(11));
+        assert!(params.is_cashback_coin);
+        // creator bucket = event creator bps + cashback bps
+        assert_eq!(params.fee_basis_points, PumpSwapFeeBasisPoints::new(20, 5, 37));
+
+        let mayhem = PumpSwapBuyEvent {
+            protocol_fee_recipient: accounts::MAYHEM_FEE_RECIPIENT,
+            ..buy.clone()
+        };
+        assert!(PumpSwapParams::try_from_event(&mayhem).unwrap().is_mayhem_mode);
+
+        let sell = PumpSwapSellEvent {
+            pool: buy.pool,
+            base_mint: buy.base_mint,
+            quote_mint: buy.quote_mint,
+            pool_base_token_account: buy.pool_base_token_account,
+            pool_quote_token_account: buy.pool_quote_token_account,
+            pool_base_token_reserves: buy.pool_base_token_reserves,
+            pool_quote_token_reserves: buy.pool_quote_token_reserves,
+            coin_creator_vault_ata: buy.coin_creator_vault_ata,
+            coin_creator_vault_authority: buy.coin_creator_vault_authority,
+            base_token_program: buy.base_token_program,
+            quote_token_program: buy.quote_token_program,
+            protocol_fee_recipient: accounts::MAYHEM_FEE_RECIPIENT,
+            coin_creator: buy.coin_creator,
+            ..Default::default()
+        };
+        let params = PumpSwapParams::try_from_event(&sell).unwrap();
+        assert_eq!(params.pool_base_token_reserves, 1_000);
+        assert!(params.is_mayhem_mode);
+        assert!(!params.is_cashback_coin);
+        // no fee bps in the event: SDK defaults
+        assert_eq!(params.fee_basis_points.lp_fee_basis_points, accounts::LP_FEE_BASIS_POINTS);
+
+        let empty = PumpSwapSellEvent { pool: Pubkey::default(), ..sell };
+        let err = PumpSwapParams::try_from_event(&empty).err().unwrap();
+        assert_eq!(err.code, TradeError::INVALID_PROTOCOL_PARAMS);
+    }
+
+    #[test]
+    fn bonk_trade_event_maps_every_field() {
+        let event = BonkTradeEvent {
+            pool_state: pk(1),
+            virtual_base: 1_073_025_605_596_382,
+            virtual_quote: 30_000_852_951,
+            real_base_after: 206_900_000_000_000,
+            real_quote_after: 85_000_000_000,
+            base_vault: pk(2),
+            quote_vault: pk(3),
+            base_token_program: pk(4),
+            platform_config: pk(5),
+            platform_associated_account: pk(6),
+            creator_associated_account: pk(7),
+            global_config: pk(8),
+            ..Default::default()
+        };
+        let params = BonkParams::try_from_event(&event).unwrap();
+        assert_eq!(params.pool_state, pk(1));
+        assert_eq!(params.virtual_base, event.virtual_base as u128);
+        assert_eq!(params.virtual_quote, event.virtual_quote as u128);
+        assert_eq!(params.real_base, event.real_base_after as u128);
+        assert_eq!(params.real_quote, event.real_quote_after as u128);
+        assert_eq!((params.base_vault, params.quote_vault), (pk(2), pk(3)));
+        assert_eq!(params.mint_token_program, pk(4));
+        assert_eq!(params.platform_config, pk(5));
+        assert_eq!(params.platform_associated_account, pk(6));
+        assert_eq!(params.creator_associated_account, pk(7));
+        assert_eq!(params.global_config, pk(8));
+
+        let unset = BonkTradeEvent { platform_config: Pubkey::default(), ..event };
+        let err = BonkParams::try_from_event(&unset).err().unwrap();
+        assert_eq!(err.code, TradeError::INVALID_PROTOCOL_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn cpmm_events_load_the_pool_in_token0_token1_order_whatever_the_direction() {
+        use sol_trade_sdk::common::{mock_rpc::mock_account, MockSolanaRpcClient};
+        use sol_trade_sdk::instruction::utils::raydium_cpmm::accounts::RAYDIUM_CPMM;
+        use sol_trade_sdk::test_fixtures::{raydium_cpmm_pool_account_data, MINT};
+
+        // fresh address: loads go through the process-wide pool cache
+        let pool = Pubkey::new_unique();
+        let account = mock_account(RAYDIUM_CPMM, raydium_cpmm_pool_account_data());
+        for (input, output) in [(MINT, WSOL_TOKEN_ACCOUNT), (WSOL_TOKEN_ACCOUNT, MINT)] {
+            let event = RaydiumCpmmSwapEvent {
+                pool_state: pool,
+                input_token_mint: input,
+                output_token_mint: output,
+                ..Default::default()
+            };
+            let rpc = MockSolanaRpcClient::new()
+                .with_account(Some(&account))
+                .with_token_account_balance(30_000_000_000, 9)
+                .with_token_account_balance(1_000_000_000_000, 6)
+                .build();
+            let params = RaydiumCpmmParams::from_event_by_rpc(&rpc, &event).await.unwrap();
+            assert_eq!(params.pool_state, pool);
+            assert_eq!((params.base_mint, params.quote_mint), (MINT, WSOL_TOKEN_ACCOUNT));
+            assert_eq!(
+                (params.base_reserve, params.quote_reserve),
+                (1_000_000_000_000, 30_000_000_000)
+            );
+        }
+    }
+
+    #[test]
+    fn pumpswap_events_trade_the_non_quote_side() {
+        let token = Pubkey::new_unique();
+        // WSOL stored as base: the token is the quote side of the pool
+        let event = PumpSwapSellEvent {
+            pool: Pubkey::new_unique(),
+            base_mint: WSOL_TOKEN_ACCOUNT,
+            quote_mint: token,
+            base_token_program: TOKEN_PROGRAM,
+            quote_token_program: TOKEN_PROGRAM_2022,
+            pool_base_token_reserves: 1_000,
+            pool_quote_token_reserves: 2_000,
+            ..Default::default()
+        };
+        let trade = event.decoded_trade().unwrap();
+        assert_eq!(trade.dex_type(), DexType::PumpSwap);
+        assert_eq!(trade.mint(), token);
+        assert!(trade.quote_token_type() == TradeTokenType::SOL);
+        assert_eq!(trade.mint_token_program(), Some(TOKEN_PROGRAM_2022));
+        let DexParamEnum::PumpSwap(params) = trade.protocol_params() else {
+            panic!("expected PumpSwap params");
+        };
+        assert_eq!((params.base_mint, params.quote_mint), (WSOL_TOKEN_ACCOUNT, token));
+
+        let buy = PumpSwapBuyEvent {
+            base_mint: token,
+            quote_mint: WSOL_TOKEN_ACCOUNT,
+            base_token_program: TOKEN_PROGRAM_2022,
+            ..Default::default()
+        };
+        let trade = buy.decoded_trade().unwrap();
+        assert_eq!((trade.mint(), trade.mint_token_program()), (token, Some(TOKEN_PROGRAM_2022)));
+
+        // neither side is a quote mint
+        let unquoted = PumpSwapBuyEvent { quote_mint: Pubkey::new_unique(), ..buy };
+        assert!(unquoted.decoded_trade().is_err());
+    }
+
+    #[test]
+    fn bonk_events_trade_the_base_mint_against_the_quote_mint() {
+        let event = BonkTradeEvent {
+            base_token_mint: Pubkey::new_unique(),
+            quote_token_mint: USD1_TOKEN_ACCOUNT,
+            base_token_program: TOKEN_PROGRAM_2022,
+            ..Default::default()
+        };
+        let trade = event.decoded_trade().unwrap();
+        assert_eq!(trade.dex_type(), DexType::Bonk);
+        assert_eq!(trade.mint(), event.base_token_mint);
+        assert!(trade.quote_token_type() == TradeTokenType::USD1);
+        assert_eq!(trade.mint_token_program(), Some(TOKEN_PROGRAM_2022));
+        assert!(matches!(trade.protocol_params(), DexParamEnum::Bonk(_)));
+    }
+}